@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use lumina_network::{compression, MAX_DECOMPRESSED_BYTES};
+use lumina_types::block::Block;
+use lumina_types::transaction::Transaction;
+
+fuzz_target!(|data: &[u8]| {
+    // Blocks arrive raw or zstd-framed; transactions always raw. The node
+    // decodes both with bincode.
+    if let Ok(block) = compression::decode(data.to_vec(), MAX_DECOMPRESSED_BYTES) {
+        let _ = bincode::deserialize::<Block>(&block);
+    }
+    let _ = bincode::deserialize::<Transaction>(data);
+});
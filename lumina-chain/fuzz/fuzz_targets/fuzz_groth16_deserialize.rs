@@ -0,0 +1,24 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use ark_bls12_381::Bls12_381;
+use ark_groth16::{Proof, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+fuzz_target!(|data: &[u8]| {
+    // Whatever parses must survive a round trip through its canonical
+    // encoding. (Points at infinity parse whatever their other bytes are,
+    // so the input itself need not be canonical.)
+    if let Ok(proof) = Proof::<Bls12_381>::deserialize_compressed(data) {
+        let mut bytes = Vec::new();
+        proof.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(
+            Proof::<Bls12_381>::deserialize_compressed(&bytes[..]).unwrap(),
+            proof
+        );
+    }
+    let _ = Proof::<Bls12_381>::deserialize_uncompressed(data);
+    let _ = VerifyingKey::<Bls12_381>::deserialize_compressed(data);
+    let _ = VerifyingKey::<Bls12_381>::deserialize_uncompressed(data);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use lumina_network::{decode_sync_request, decode_sync_response};
+
+fuzz_target!(|data: &[u8]| {
+    // The same bytes read as either side of the `/lumina/sync/1` protocol,
+    // including the payload decompression applied to responses.
+    let _ = decode_sync_request(data);
+    let _ = decode_sync_response(data);
+});
@@ -0,0 +1,31 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+
+use lumina_crypto::zk::{
+    verify_compliance_proof, verify_confidential_transfer, verify_credit_score_proof,
+    verify_green_energy_proof, verify_insurance_loss_proof, verify_min_valuation,
+    verify_rwa_attestation, verify_sealed_rwa_attestation,
+};
+
+#[derive(Arbitrary, Debug)]
+struct AttestationInput {
+    proof: Vec<u8>,
+    value: u64,
+    commitment: [u8; 32],
+}
+
+fuzz_target!(|data: AttestationInput| {
+    // Context-bound Groth16 attestations
+    let _ = verify_rwa_attestation(&data.proof, data.value);
+    let _ = verify_credit_score_proof(&data.proof);
+    let _ = verify_compliance_proof(&data.commitment, &data.proof);
+    let _ = verify_sealed_rwa_attestation(&data.proof, &data.commitment);
+    let _ = verify_green_energy_proof(&data.proof);
+
+    // Bulletproof range proofs over Pedersen commitments
+    let _ = verify_confidential_transfer(&data.commitment, &data.proof);
+    let _ = verify_insurance_loss_proof(&data.proof, data.value);
+    let _ = verify_min_valuation(&data.commitment, data.value, &data.proof);
+});
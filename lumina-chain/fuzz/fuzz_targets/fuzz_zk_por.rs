@@ -0,0 +1,23 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use std::sync::OnceLock;
+
+use lumina_crypto::zk::ZkManager;
+
+#[derive(Arbitrary, Debug)]
+struct ProofInput {
+    proof: Vec<u8>,
+    total_reserve: u64,
+    max_value: u64,
+}
+
+fuzz_target!(|data: ProofInput| {
+    // Setup clones the proving keys; do it once, not per input.
+    static ZK: OnceLock<ZkManager> = OnceLock::new();
+    let zk = ZK.get_or_init(ZkManager::setup);
+
+    let _ = zk.verify_zk_por(&data.proof, data.total_reserve);
+    let _ = zk.verify_range_proof(&data.proof, data.max_value);
+});
@@ -1,79 +1,181 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
+use lumina_consensus::ConsensusStatus;
+use lumina_network::PeerInfo;
 use lumina_storage::db::Storage;
+use lumina_storage::snapshot::StateSnapshot;
+use lumina_types::archive::ArchiveTree;
 use lumina_types::block::Block;
-use lumina_types::state::GlobalState;
+use lumina_types::checkpoint::Checkpoint;
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{AssetType, StablecoinInstruction, INSTRUCTION_KINDS};
+use lumina_types::proof::BlockProof;
+use lumina_types::screening::ScreeningTree;
+use lumina_types::state::{
+    AccountState, ConcentrationBreach, CustodianState, GlobalState, InstructionBitmap,
+    RandomnessBeacon,
+};
+use lumina_types::taxlots::LotMethod;
 use lumina_types::transaction::Transaction;
 use prometheus_client::encoding::text::encode;
+use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
 use prometheus_client::registry::Registry;
+use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, RwLock};
 use tower_http::cors::{Any, CorsLayer};
-use tracing::info;
+use tracing::{info, info_span, Instrument};
 
 #[derive(Clone)]
 pub struct AppState {
+    /// Live consensus state; only the devnet faucet takes this lock
     pub global_state: Arc<RwLock<GlobalState>>,
+    /// Latest committed state, published by consensus after each block
+    pub snapshot: watch::Sender<Arc<GlobalState>>,
     pub storage: Arc<Storage>,
-    pub tx_sender: mpsc::Sender<Transaction>,
+    /// Consensus mempool; `None` on read-only nodes (followers, indexers),
+    /// which refuse submissions and the faucet
+    pub tx_sender: Option<mpsc::Sender<Transaction>>,
+    /// `<chain_id>-<genesis prefix>` this node gossips on
+    pub network: String,
+    /// Connected peers, refreshed by the P2P task
+    pub peers: watch::Receiver<Vec<PeerInfo>>,
+    /// Chain tip and mempool size, published by consensus
+    pub consensus: watch::Receiver<ConsensusStatus>,
+    /// Whether each configured alert rule is firing, by rule name
+    pub rules: watch::Receiver<RuleStates>,
+    /// Unanswered faucet challenges and when each expires
+    pub faucet_challenges: Arc<Mutex<HashMap<[u8; 32], Instant>>>,
+    pub started_at: Instant,
+}
+
+/// Alert rule names and whether each is firing.
+pub type RuleStates = std::collections::BTreeMap<String, bool>;
+
+impl AppState {
+    /// Immutable view of the latest committed state for read-only queries.
+    /// Never blocks on the consensus write lock.
+    fn latest(&self) -> Arc<GlobalState> {
+        self.snapshot.borrow().clone()
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn start_server(
     global_state: Arc<RwLock<GlobalState>>,
+    snapshot: watch::Sender<Arc<GlobalState>>,
     storage: Arc<Storage>,
-    tx_sender: mpsc::Sender<Transaction>,
+    tx_sender: Option<mpsc::Sender<Transaction>>,
+    network: String,
+    peers: watch::Receiver<Vec<PeerInfo>>,
+    consensus: watch::Receiver<ConsensusStatus>,
+    rules: watch::Receiver<RuleStates>,
+    addr: SocketAddr,
 ) {
     let state = AppState {
         global_state,
+        snapshot,
         storage,
         tx_sender,
+        network,
+        peers,
+        consensus,
+        rules,
+        faucet_challenges: Arc::default(),
+        started_at: Instant::now(),
     };
+    let app = router(state);
+
+    info!("API listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+    axum::serve(listener, app).await.unwrap();
+}
 
+/// Every route, served from `state`.
+fn router(state: AppState) -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_headers(Any)
         .allow_methods(Any);
 
-    let app = Router::new()
+    Router::new()
         .route("/", get(root))
         .route("/state", get(get_state))
         .route("/health", get(get_health))
+        .route("/health/components", get(get_health_components))
+        .route("/por/history", get(get_por_history))
         .route("/metrics", get(get_metrics))
         .route("/tx/signing_bytes", post(tx_signing_bytes))
         .route("/tx", post(submit_tx))
-        .route("/block/{height}", get(get_block))
-        .route("/account/{address}", get(get_account))
+        .route("/tx/:id", get(get_tx_status))
+        .route("/transfers/:recipient/:memo", get(get_memo_transfers))
+        .route("/archive/:address", get(get_archive_proof))
+        .route("/randomness", get(get_randomness))
+        .route("/randomness/:epoch", get(get_randomness_beacon))
+        .route("/block/:height", get(get_block))
+        .route("/block/:height/proofs", get(get_block_proofs))
+        .route("/checkpoint/:height", get(get_checkpoint))
+        .route("/snapshot/:height", get(get_snapshot))
+        .route("/account/:address", get(get_account))
+        .route("/account/:address/statement", get(get_account_statement))
+        .route("/account/:address/txs", get(get_account_txs))
+        .route("/account/:address/taxlots", get(get_tax_lots))
+        .route("/accounts", get(list_accounts))
+        .route("/rwa_listings", get(list_rwa_listings))
+        .route("/blocks", get(list_blocks))
+        .route("/events", get(get_events))
+        .route("/audit", get(get_audit))
         .route("/faucet", post(faucet))
+        .route("/faucet/challenge", get(faucet_challenge))
         .route("/validators", get(get_validators))
+        .route("/custodians", get(get_custodians))
+        .route("/custodians/:pubkey", get(get_custodian))
+        .route("/compliance/screening", get(get_screening_roots))
+        .route("/compliance/screening/prove", post(prove_not_screened))
+        .route("/node/status", get(get_node_status))
+        .route("/node/peers", get(get_peers))
         .route("/insurance", get(get_insurance))
+        .route("/treasury", get(get_treasury))
+        .route("/auctions", get(get_auctions))
+        .route("/credit_lines", get(get_credit_lines))
+        .route("/hash_locks/:id", get(get_hash_lock))
+        .route("/redemptions/delayed", get(get_delayed_redemptions))
+        .route("/council", get(get_council))
+        .route("/instructions/paused", get(get_paused_instructions))
+        .route("/bridge/spv", get(get_spv_bridges))
+        .route("/crosschain/chains", get(get_remote_chains))
+        .route("/crosschain/batches", get(get_message_batches))
+        .route("/stats/instructions", get(get_instruction_stats))
+        .route("/stats/supply", get(get_supply_stats))
         .layer(cors)
-        .with_state(state);
-
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
-    info!("API listening on {}", addr);
-
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+        .with_state(state)
 }
 
-async fn root() -> &'static str {
-    "LuminaChain API v1.0 — Production L1 Stablecoin Network"
+async fn root(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(serde_json::json!({
+        "name": "LuminaChain API v1.0 — Production L1 Stablecoin Network",
+        "network": state.network,
+    }))
 }
 
 async fn get_state(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let guard = state.global_state.read().await;
+    let guard = state.latest();
     let summary = serde_json::json!({
         "total_lusd_supply": guard.total_lusd_supply,
         "total_ljun_supply": guard.total_ljun_supply,
+        "ljun_exchange_rate": guard.effective_ljun_rate(),
         "reserve_ratio": guard.reserve_ratio,
         "stabilization_pool_balance": guard.stabilization_pool_balance,
+        "pool_composition": pool_composition(&guard),
         "circuit_breaker_active": guard.circuit_breaker_active,
         "insurance_fund_balance": guard.insurance_fund_balance,
         "health_index": guard.health_index,
@@ -84,12 +186,19 @@ async fn get_state(State(state): State<AppState>) -> Json<serde_json::Value> {
         "current_epoch": guard.current_epoch,
         "velocity_reward_pool": guard.velocity_reward_pool,
         "account_count": guard.accounts.len(),
+        "dust_policy": guard.dust_policy,
+        "tracked_dust_accounts": guard.dust_accounts.len(),
+        "mint_fee_bps": guard.params.mint_fee_bps,
+        "redeem_fee_bps": guard.params.redeem_fee_bps,
+        "savings_rate_bps": guard.stabilizer.savings_rate_bps,
+        "redeem_cap_bps": guard.stabilizer.redeem_cap_bps,
+        "stabilizer_bounds": guard.stabilizer.bounds,
     });
     Json(summary)
 }
 
 async fn get_health(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let guard = state.global_state.read().await;
+    let guard = state.latest();
     let health = serde_json::json!({
         "health_index": guard.health_index,
         "health_pct": format!("{:.2}%", guard.health_index as f64 / 100.0),
@@ -98,12 +207,127 @@ async fn get_health(State(state): State<AppState>) -> Json<serde_json::Value> {
         "insurance_fund_balance": guard.insurance_fund_balance,
         "green_validator_count": guard.validators.iter().filter(|v| v.is_green).count(),
         "total_validator_count": guard.validators.len(),
+        "pool_composition": pool_composition(&guard),
+        "concentration_limits": guard.concentration_limits,
+        "concentration_breaches": concentration_breaches(&guard),
     });
     Json(health)
 }
 
+/// Custodians (hex pubkey) and pool assets above their concentration limit
+/// at the last health index computation.
+fn concentration_breaches(state: &GlobalState) -> serde_json::Value {
+    let breaches: Vec<serde_json::Value> = state
+        .concentration_breaches
+        .iter()
+        .map(|breach| match breach {
+            ConcentrationBreach::Custodian(pubkey) => {
+                serde_json::json!({ "custodian": hex::encode(pubkey) })
+            }
+            ConcentrationBreach::Asset(asset) => serde_json::json!({ "asset": asset.label() }),
+        })
+        .collect();
+    serde_json::Value::Array(breaches)
+}
+
+/// Assets backing the stabilization pool: units held, LUSD value at the
+/// last ratio recalculation, and share of the valued total.
+fn pool_composition(state: &GlobalState) -> serde_json::Value {
+    let total: u128 = state.pool_valuation.values().map(|v| *v as u128).sum();
+    let assets: Vec<serde_json::Value> = state
+        .pool_composition
+        .iter()
+        .map(|(asset, amount)| {
+            let value = state.pool_valuation.get(asset).copied().unwrap_or(0);
+            let share_bps = (value as u128 * 10_000).checked_div(total).unwrap_or(0);
+            serde_json::json!({
+                "asset": asset.label(),
+                "amount": amount,
+                "value": value,
+                "share_pct": format!("{:.2}%", share_bps as f64 / 100.0),
+            })
+        })
+        .collect();
+    serde_json::Value::Array(assets)
+}
+
+/// Health index weights and thresholds, the latest sub-scores, and each
+/// epoch's last sub-scores (the most recent `MAX_PAGE_LIMIT` epochs of
+/// `from..=to`).
+async fn get_health_components(
+    State(state): State<AppState>,
+    Query(range): Query<EpochRangeQuery>,
+) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let to = range.to.unwrap_or(guard.current_epoch);
+    let from = range.from.unwrap_or(0);
+    let history: Vec<serde_json::Value> = guard
+        .health_history
+        .range(from..=to.max(from))
+        .rev()
+        .take(MAX_PAGE_LIMIT)
+        .map(|(epoch, c)| {
+            let mut entry = serde_json::json!(c);
+            entry["epoch"] = serde_json::json!(epoch);
+            entry["health_index"] = serde_json::json!(c.total().min(10_000));
+            entry
+        })
+        .collect();
+    Json(serde_json::json!({
+        "health_index": guard.health_index,
+        "params": guard.health_params,
+        "components": guard.health_components,
+        "current_epoch": guard.current_epoch,
+        "history": history,
+    }))
+}
+
+#[derive(serde::Deserialize)]
+struct TimestampRangeQuery {
+    from: Option<u64>,
+    to: Option<u64>,
+}
+
+/// Accepted proof-of-reserves attestations with the coverage each implied
+/// (the most recent `MAX_PAGE_LIMIT` of timestamps `from..=to`).
+async fn get_por_history(
+    State(state): State<AppState>,
+    Query(range): Query<TimestampRangeQuery>,
+) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let from = range.from.unwrap_or(0);
+    let to = range.to.unwrap_or(u64::MAX);
+    let attestations: Vec<serde_json::Value> = guard
+        .por_history
+        .range(from..=to.max(from))
+        .rev()
+        .take(MAX_PAGE_LIMIT)
+        .map(|(timestamp, a)| {
+            // As `reserve_ratio` is recalculated: a chain with no supply is covered.
+            let coverage_ratio = if a.lusd_supply == 0 {
+                1.0
+            } else {
+                a.total_reserves as f64 / a.lusd_supply as f64
+            };
+            serde_json::json!({
+                "timestamp": timestamp,
+                "height": a.height,
+                "total_reserves": a.total_reserves,
+                "proof_hash": hex::encode(a.proof_hash),
+                "verifying_key_hash": hex::encode(a.verifying_key_hash),
+                "lusd_supply": a.lusd_supply,
+                "coverage_ratio": coverage_ratio,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "count": guard.por_history.len(),
+        "attestations": attestations,
+    }))
+}
+
 async fn get_metrics(State(state): State<AppState>) -> Response {
-    let guard = state.global_state.read().await;
+    let guard = state.latest();
 
     let mut registry = Registry::default();
 
@@ -115,7 +339,7 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         i64::try_from(v).unwrap_or(i64::MAX)
     }
 
-    let mut health_index = Gauge::<i64>::default();
+    let health_index = Gauge::<i64>::default();
     health_index.set(as_i64_u64(guard.health_index));
     registry.register(
         "lumina_health_index",
@@ -123,7 +347,7 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         health_index,
     );
 
-    let mut reserve_ratio_bps = Gauge::<i64>::default();
+    let reserve_ratio_bps = Gauge::<i64>::default();
     let rr_bps = (guard.reserve_ratio.max(0.0) * 10_000.0) as u64;
     reserve_ratio_bps.set(as_i64_u64(rr_bps));
     registry.register(
@@ -132,7 +356,7 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         reserve_ratio_bps,
     );
 
-    let mut total_lusd_supply = Gauge::<i64>::default();
+    let total_lusd_supply = Gauge::<i64>::default();
     total_lusd_supply.set(as_i64_u64(guard.total_lusd_supply));
     registry.register(
         "lumina_total_lusd_supply",
@@ -140,7 +364,7 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         total_lusd_supply,
     );
 
-    let mut total_ljun_supply = Gauge::<i64>::default();
+    let total_ljun_supply = Gauge::<i64>::default();
     total_ljun_supply.set(as_i64_u64(guard.total_ljun_supply));
     registry.register(
         "lumina_total_ljun_supply",
@@ -148,7 +372,7 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         total_ljun_supply,
     );
 
-    let mut stabilization_pool_balance = Gauge::<i64>::default();
+    let stabilization_pool_balance = Gauge::<i64>::default();
     stabilization_pool_balance.set(as_i64_u64(guard.stabilization_pool_balance));
     registry.register(
         "lumina_stabilization_pool_balance",
@@ -156,7 +380,7 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         stabilization_pool_balance,
     );
 
-    let mut insurance_fund_balance = Gauge::<i64>::default();
+    let insurance_fund_balance = Gauge::<i64>::default();
     insurance_fund_balance.set(as_i64_u64(guard.insurance_fund_balance));
     registry.register(
         "lumina_insurance_fund_balance",
@@ -164,7 +388,31 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         insurance_fund_balance,
     );
 
-    let mut circuit_breaker_active = Gauge::<i64>::default();
+    let flash_loan_volume = Gauge::<i64>::default();
+    flash_loan_volume.set(as_i64_u64(guard.flash_loan_volume));
+    registry.register(
+        "lumina_flash_loan_volume",
+        "Cumulative LUSD borrowed via flash loans",
+        flash_loan_volume,
+    );
+
+    let flash_loan_fees = Gauge::<i64>::default();
+    flash_loan_fees.set(as_i64_u64(guard.flash_loan_fees));
+    registry.register(
+        "lumina_flash_loan_fees",
+        "Cumulative flash loan fees paid to the insurance fund",
+        flash_loan_fees,
+    );
+
+    let flash_loan_utilization_bps = Gauge::<i64>::default();
+    flash_loan_utilization_bps.set(as_i64_u64(guard.flash_loan_utilization_bps));
+    registry.register(
+        "lumina_flash_loan_utilization_bps",
+        "Peak stabilization pool utilization by flash loans in the last block (bps)",
+        flash_loan_utilization_bps,
+    );
+
+    let circuit_breaker_active = Gauge::<i64>::default();
     circuit_breaker_active.set(if guard.circuit_breaker_active { 1 } else { 0 });
     registry.register(
         "lumina_circuit_breaker_active",
@@ -172,11 +420,11 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         circuit_breaker_active,
     );
 
-    let mut validator_count = Gauge::<i64>::default();
+    let validator_count = Gauge::<i64>::default();
     validator_count.set(as_i64_usize(guard.validators.len()));
     registry.register("lumina_validator_count", "Validator count", validator_count);
 
-    let mut green_validator_count = Gauge::<i64>::default();
+    let green_validator_count = Gauge::<i64>::default();
     green_validator_count.set(as_i64_usize(
         guard.validators.iter().filter(|v| v.is_green).count(),
     ));
@@ -186,11 +434,11 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         green_validator_count,
     );
 
-    let mut account_count = Gauge::<i64>::default();
+    let account_count = Gauge::<i64>::default();
     account_count.set(as_i64_usize(guard.accounts.len()));
     registry.register("lumina_account_count", "Account count", account_count);
 
-    let mut rwa_listing_count = Gauge::<i64>::default();
+    let rwa_listing_count = Gauge::<i64>::default();
     rwa_listing_count.set(as_i64_usize(guard.rwa_listings.len()));
     registry.register(
         "lumina_rwa_listing_count",
@@ -198,6 +446,18 @@ async fn get_metrics(State(state): State<AppState>) -> Response {
         rwa_listing_count,
     );
 
+    let alert_firing = Family::<Vec<(String, String)>, Gauge<i64>>::default();
+    for (rule, firing) in state.rules.borrow().iter() {
+        alert_firing
+            .get_or_create(&vec![("rule".to_string(), rule.clone())])
+            .set(i64::from(*firing));
+    }
+    registry.register(
+        "lumina_alert_firing",
+        "Alert rule firing (1/0), by rule",
+        alert_firing,
+    );
+
     let mut out = String::new();
     if encode(&mut out, &registry).is_err() {
         return StatusCode::INTERNAL_SERVER_ERROR.into_response();
@@ -218,6 +478,377 @@ async fn get_block(State(state): State<AppState>, Path(height): Path<u64>) -> Js
     }
 }
 
+/// The checkpoint validators sign for the canonical block at `height`.
+async fn get_checkpoint(
+    State(state): State<AppState>,
+    Path(height): Path<u64>,
+) -> Json<Option<Checkpoint>> {
+    match state.storage.load_block_by_height(height) {
+        Ok(block) => Json(block.as_ref().map(Checkpoint::of)),
+        Err(_) => Json(None),
+    }
+}
+
+/// Bincode `StateSnapshot` of the canonical block at `height` and its
+/// state, for `lumina-node bootstrap`.
+async fn get_snapshot(State(state): State<AppState>, Path(height): Path<u64>) -> Response {
+    let block = match state.storage.load_block_by_height(height) {
+        Ok(Some(block)) => block,
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let snapshot = match state.storage.load_state_by_height(height) {
+        Ok(Some(committed)) => StateSnapshot::new(block, &committed).and_then(|s| s.encode()),
+        Ok(None) => return StatusCode::NOT_FOUND.into_response(),
+        Err(_) => return StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    };
+    let Ok(bytes) = snapshot else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    (headers, bytes).into_response()
+}
+
+/// PoR and compliance proofs accepted in the canonical block at `height`,
+/// with the public inputs needed to re-verify them. `None` if no such block.
+async fn get_block_proofs(
+    State(state): State<AppState>,
+    Path(height): Path<u64>,
+) -> Json<Option<Vec<BlockProof>>> {
+    let block = match state.storage.load_block_by_height(height) {
+        Ok(Some(block)) => block,
+        _ => return Json(None),
+    };
+    match state.storage.load_block_proofs(&block.hash()) {
+        Ok(proofs) => Json(Some(proofs.unwrap_or_default())),
+        Err(_) => Json(None),
+    }
+}
+
+/// Upper bound on entries returned by a single paginated request
+const MAX_PAGE_LIMIT: usize = 100;
+
+#[derive(serde::Deserialize)]
+struct PageQuery {
+    start: Option<String>,
+    limit: Option<usize>,
+}
+
+impl PageQuery {
+    fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(MAX_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct BlockRangeQuery {
+    from: u64,
+    to: Option<u64>,
+}
+
+async fn list_accounts(
+    State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
+) -> Json<serde_json::Value> {
+    let start = match page.start.as_deref() {
+        None => None,
+        Some(s) => match hex::decode(s.trim_start_matches("0x")) {
+            Ok(bytes) if bytes.len() == 32 => {
+                let mut key = [0u8; 32];
+                key.copy_from_slice(&bytes);
+                Some(key)
+            }
+            _ => return Json(serde_json::json!({"error": "start must be a 32-byte hex address"})),
+        },
+    };
+    let limit = page.limit();
+    let mut entries = match state.storage.iterate_accounts(start, limit + 1) {
+        Ok(entries) => entries,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let next = (entries.len() > limit).then(|| format!("0x{}", hex::encode(entries[limit].0)));
+    entries.truncate(limit);
+
+    let accounts: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|(address, account)| {
+            serde_json::json!({
+                "address": format!("0x{}", hex::encode(address)),
+                "lusd_balance": account.lusd_balance,
+                "ljun_balance": account.ljun_balance,
+                "lumina_balance": account.lumina_balance,
+                "nonce": account.nonce,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "accounts": accounts, "next": next }))
+}
+
+async fn list_rwa_listings(
+    State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
+) -> Json<serde_json::Value> {
+    let start = match page.start.as_deref().map(str::parse::<u64>) {
+        None => 0,
+        Some(Ok(id)) => id,
+        Some(Err(_)) => return Json(serde_json::json!({"error": "start must be a listing id"})),
+    };
+    let limit = page.limit();
+    let mut entries = match state.storage.iterate_rwa_listings(start, limit + 1) {
+        Ok(entries) => entries,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let next = (entries.len() > limit).then(|| entries[limit].0);
+    entries.truncate(limit);
+
+    let guard = state.latest();
+    let listings: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|(id, listing)| {
+            let sealed = guard.sealed_rwa_valuations.get(id).map(|v| {
+                serde_json::json!({
+                    "commitment": hex::encode(v.commitment),
+                    "sealed_opening": hex::encode(&v.sealed_opening),
+                })
+            });
+            serde_json::json!({
+                "id": id,
+                "owner": hex::encode(listing.owner),
+                "asset_description": listing.asset_description,
+                "attested_value": listing.attested_value,
+                "maturity_date": listing.maturity_date,
+                "collateral_eligibility": listing.collateral_eligibility,
+                "is_active": listing.is_active,
+                "pledged_amount": listing.pledged_amount,
+                "sealed_valuation": sealed,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "rwa_listings": listings, "next": next }))
+}
+
+async fn list_blocks(
+    State(state): State<AppState>,
+    Query(range): Query<BlockRangeQuery>,
+) -> Json<serde_json::Value> {
+    let cap = range.from.saturating_add(MAX_PAGE_LIMIT as u64);
+    let to = range.to.unwrap_or(cap).min(cap);
+    match state.storage.iterate_blocks(range.from..to) {
+        Ok(blocks) => Json(serde_json::json!({ "blocks": blocks })),
+        Err(e) => Json(serde_json::json!({"error": e.to_string()})),
+    }
+}
+
+/// Upper bound on blocks scanned by a single event log query
+const MAX_EVENT_SCAN: u64 = 10_000;
+
+#[derive(serde::Deserialize)]
+struct EventQuery {
+    address: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// Only `MemoPayment`s settling the payment request with this memo hash
+    memo_hash: Option<String>,
+    from: u64,
+    to: Option<u64>,
+}
+
+async fn get_events(
+    State(state): State<AppState>,
+    Query(query): Query<EventQuery>,
+) -> Json<serde_json::Value> {
+    let address = match query.address.as_deref() {
+        None => None,
+        Some(s) => match hex::decode(s.trim_start_matches("0x")) {
+            Ok(bytes) if bytes.len() == 32 => {
+                let mut key = [0u8; 32];
+                key.copy_from_slice(&bytes);
+                Some(key)
+            }
+            _ => return Json(serde_json::json!({"error": "address must be 32-byte hex"})),
+        },
+    };
+    let memo_hash = match query
+        .memo_hash
+        .as_deref()
+        .map(|s| hex::decode(s.trim_start_matches("0x")))
+    {
+        None => None,
+        Some(Ok(bytes)) if bytes.len() == 32 => Some(bytes),
+        Some(_) => return Json(serde_json::json!({"error": "memo_hash must be 32-byte hex"})),
+    };
+    let kind = query
+        .kind
+        .as_deref()
+        .or(memo_hash.as_ref().map(|_| "MemoPayment"));
+    let cap = query.from.saturating_add(MAX_EVENT_SCAN);
+    let to = query.to.unwrap_or(cap).min(cap);
+    let hashes = match state.storage.iterate_canonical_hashes(query.from..to) {
+        Ok(hashes) => hashes,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+
+    let mut events = Vec::new();
+    for (height, hash) in hashes {
+        // Bloom first: most blocks are skipped without loading receipts.
+        match state.storage.load_block_bloom(&hash) {
+            Ok(Some(bloom)) if bloom.may_contain(kind, address.as_ref()) => {}
+            _ => continue,
+        }
+        let Ok(Some(receipts)) = state.storage.load_block_receipts(&hash) else {
+            continue;
+        };
+        for logged in receipts.events {
+            if kind.is_some_and(|k| k != logged.event.kind()) {
+                continue;
+            }
+            if let Some(wanted) = &memo_hash {
+                if !matches!(&logged.event, ChainEvent::MemoPayment { memo_hash, .. } if memo_hash[..] == wanted[..])
+                {
+                    continue;
+                }
+            }
+            if address.is_some_and(|a| !logged.event.addresses().contains(&a)) {
+                continue;
+            }
+            events.push(serde_json::json!({
+                "height": height,
+                "tx_index": logged.tx_index,
+                "type": logged.event.kind(),
+                "event": logged.event,
+            }));
+        }
+    }
+    Json(serde_json::json!({ "events": events, "scanned_to": to }))
+}
+
+/// Canonical audit log entries for blocks in `from..to`, in chain order.
+async fn get_audit(
+    State(state): State<AppState>,
+    Query(range): Query<BlockRangeQuery>,
+) -> Json<serde_json::Value> {
+    let cap = range.from.saturating_add(MAX_EVENT_SCAN);
+    let to = range.to.unwrap_or(cap).min(cap);
+    let hashes = match state.storage.iterate_canonical_hashes(range.from..to) {
+        Ok(hashes) => hashes,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let mut records = Vec::new();
+    for (_, hash) in hashes {
+        match state.storage.load_block_audit(&hash) {
+            Ok(Some(block_records)) => records.extend(block_records),
+            Ok(None) => {}
+            Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+        }
+    }
+    let tip_height = state
+        .storage
+        .load_tip()
+        .ok()
+        .flatten()
+        .map(|(h, _)| h)
+        .unwrap_or(0);
+    Json(serde_json::json!({
+        "records": records,
+        "scanned_to": to,
+        "tip_height": tip_height,
+        "head": hex::encode(state.latest().audit_head),
+    }))
+}
+
+/// Transactions per instruction kind for canonical blocks in `from..to`:
+/// totals over the range, and the ids in each block that has any.
+async fn get_instruction_stats(
+    State(state): State<AppState>,
+    Query(range): Query<BlockRangeQuery>,
+) -> Json<serde_json::Value> {
+    let cap = range.from.saturating_add(MAX_EVENT_SCAN);
+    let to = range.to.unwrap_or(cap).min(cap);
+    let hashes = match state.storage.iterate_canonical_hashes(range.from..to) {
+        Ok(hashes) => hashes,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let mut totals = std::collections::BTreeMap::<String, usize>::new();
+    let mut blocks = Vec::new();
+    for (height, hash) in hashes {
+        let index = match state.storage.load_block_instructions(&hash) {
+            Ok(Some(index)) => index,
+            Ok(None) => continue,
+            Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+        };
+        let mut instructions = serde_json::Map::new();
+        for (kind, tx_ids) in index {
+            *totals.entry(kind.clone()).or_default() += tx_ids.len();
+            let ids: Vec<String> = tx_ids.iter().map(hex::encode).collect();
+            instructions.insert(kind, serde_json::json!(ids));
+        }
+        blocks.push(serde_json::json!({
+            "height": height,
+            "instructions": instructions,
+        }));
+    }
+    Json(serde_json::json!({
+        "totals": totals,
+        "blocks": blocks,
+        "scanned_to": to,
+    }))
+}
+
+/// Rolling windows served by `/stats/supply`, in hourly buckets.
+const SUPPLY_WINDOWS: [(&str, u64); 3] = [("1h", 1), ("24h", 24), ("7d", 168)];
+
+async fn get_supply_stats(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let tip = match state.storage.load_tip() {
+        Ok(Some((height, hash))) => state
+            .storage
+            .load_block_supply(&hash)
+            .map(|sample| sample.map(|s| (height, s.timestamp))),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e),
+    };
+    let (height, timestamp) = match tip {
+        Ok(Some(tip)) => tip,
+        Ok(None) => return Json(serde_json::json!({"error": "No blocks committed"})),
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let hour = timestamp / lumina_types::stats::BUCKET_SECS;
+    let oldest = (hour + 1).saturating_sub(SUPPLY_WINDOWS[2].1);
+    let buckets = match state.storage.iterate_supply_buckets(oldest..hour + 1) {
+        Ok(buckets) => buckets,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let mut windows = serde_json::Map::new();
+    for (name, hours) in SUPPLY_WINDOWS {
+        let mut totals = lumina_types::stats::SupplyBucket::default();
+        for (_, bucket) in buckets.iter().filter(|(b, _)| b + hours > hour) {
+            totals.merge(bucket);
+        }
+        windows.insert(
+            name.to_string(),
+            serde_json::json!({
+                "blocks": totals.blocks,
+                "minted": totals.minted,
+                "redeemed": totals.redeemed,
+                "net_mint": totals.minted as i64 - totals.redeemed as i64,
+                "average_reserve_ratio": totals.average_reserve_ratio(),
+                "max_peg_deviation_bps": totals.max_peg_deviation_bps,
+            }),
+        );
+    }
+    Json(serde_json::json!({
+        "height": height,
+        "timestamp": timestamp,
+        "total_lusd_supply": state.latest().total_lusd_supply,
+        "windows": windows,
+    }))
+}
+
 #[derive(serde::Deserialize)]
 struct UnsignedTxRequest {
     pub sender: [u8; 32],
@@ -247,7 +878,7 @@ async fn get_account(
     State(state): State<AppState>,
     Path(address): Path<String>,
 ) -> Json<serde_json::Value> {
-    let guard = state.global_state.read().await;
+    let guard = state.latest();
     let addr_hex = address.trim_start_matches("0x");
     if let Ok(bytes) = hex::decode(addr_hex) {
         if bytes.len() == 32 {
@@ -258,6 +889,7 @@ async fn get_account(
                     "address": address,
                     "lusd_balance": account.lusd_balance,
                     "ljun_balance": account.ljun_balance,
+                    "ljun_value": guard.ljun_value_of(account.ljun_balance).min(u64::MAX as u128) as u64,
                     "lumina_balance": account.lumina_balance,
                     "nonce": account.nonce,
                     "has_passkey": account.passkey_device_key.is_some(),
@@ -266,7 +898,92 @@ async fn get_account(
                     "credit_score": account.credit_score,
                     "yield_positions": account.yield_positions.len(),
                     "active_streams": account.active_streams.len(),
+                    "streams": account.active_streams.iter().map(|s| serde_json::json!({
+                        "id": s.id,
+                        "recipient": hex::encode(s.recipient),
+                        "asset": s.asset,
+                        "amount_per_sec": s.amount_per_sec,
+                        "start_timestamp": s.start_timestamp,
+                        "end_timestamp": s.end_timestamp,
+                        "total": s.total(),
+                        "withdrawn": s.withdrawn,
+                        "proposed_rate": s.proposed_rate,
+                    })).collect::<Vec<_>>(),
                     "custom_balances": account.custom_balances,
+                    "view_key": guard.view_keys.get(&key).map(hex::encode),
+                    "confidential_note": guard.confidential_notes.get(&key).map(|n| serde_json::json!({
+                        "commitment": hex::encode(n.commitment),
+                        "view_key": hex::encode(n.view_pubkey),
+                        "sealed_note": hex::encode(&n.sealed_note),
+                        "height": n.height,
+                    })),
+                    "kyc": guard.kyc_limits(&key).map(|(tier, limits)| {
+                        let (transferred, redeemed) = guard
+                            .kyc_accounts
+                            .get(&key)
+                            .map(|a| a.usage(guard.current_epoch))
+                            .unwrap_or_default();
+                        let transfer_left = limits.max_transfer_per_epoch.saturating_sub(transferred);
+                        let redeem_left = limits.max_redeem_per_epoch.saturating_sub(redeemed);
+                        serde_json::json!({
+                            "tier": tier,
+                            "epoch": guard.current_epoch,
+                            "limits": limits,
+                            "remaining_transfer_today": transfer_left,
+                            "remaining_redeem_today": redeem_left,
+                            "max_transfer_now": transfer_left.min(limits.max_transfer_per_tx),
+                            "max_redeem_now": redeem_left.min(limits.max_redeem_per_tx),
+                        })
+                    }),
+                    "jurisdiction": guard.account_jurisdictions.get(&key).map(|id| serde_json::json!({
+                        "jurisdiction_id": id,
+                        "policy": guard.jurisdiction_policies.get(id),
+                    })),
+                    "credentials": guard.institution_credentials.get(&key).map(|held| {
+                        let tip = state.storage.load_tip().ok().flatten();
+                        let height = tip.map_or(0, |(h, _)| h);
+                        held.iter()
+                            .map(|(kind, c)| serde_json::json!({
+                                "kind": kind,
+                                "commitment": hex::encode(c.commitment),
+                                "attester": hex::encode(c.attester),
+                                "anchored_height": c.anchored_height,
+                                "expires_height": c.expires_height,
+                                "proven_height": c.proven_height,
+                                "valid": guard.has_credentials(&key, &[*kind], height),
+                            }))
+                            .collect::<Vec<_>>()
+                    }),
+                    "credit_history": guard.credit_histories.get(&key).map(|h| serde_json::json!({
+                        "native_score": h.score(guard.current_epoch),
+                        "repayments": h.repayments,
+                        "repaid": h.repaid,
+                        "redemptions_honored": h.redemptions_honored,
+                        "liquidations": h.liquidations,
+                        "last_epoch": h.last_epoch,
+                    })),
+                    "merchant": guard.merchants.get(&key).map(|m| serde_json::json!({
+                        "treasury": hex::encode(m.treasury),
+                        "pending_settlement": m.pending,
+                        "pending_payments": m.payments,
+                    })),
+                    "dust_reap_height": guard.dust_accounts.get(&key),
+                    "inheritance": guard.inheritance.get(&key).map(|d| serde_json::json!({
+                        "beneficiary": hex::encode(d.beneficiary),
+                        "inactivity_blocks": d.inactivity_blocks,
+                        "last_active_height": d.last_active_height,
+                        "claimable_at": d.last_active_height.saturating_add(d.inactivity_blocks),
+                        "claim_matures_at": d.claim_matures_at,
+                    })),
+                    "vesting": guard.vesting.get(&key).map(|schedules| {
+                        let tip = state.storage.load_tip().ok().flatten();
+                        let height = tip.map_or(0, |(h, _)| h);
+                        serde_json::json!({
+                            "locked_lumina": guard.locked_lumina(&key, height),
+                            "spendable_lumina": guard.spendable_lumina(&key, height),
+                            "schedules": schedules,
+                        })
+                    }),
                 }));
             }
         }
@@ -274,12 +991,384 @@ async fn get_account(
     Json(serde_json::json!({"error": "Account not found"}))
 }
 
+/// Balances per asset of an account at some height; zero if it did not exist.
+fn statement_balances(account: Option<&AccountState>) -> serde_json::Value {
+    let account = account.cloned().unwrap_or_default();
+    serde_json::json!({
+        "lusd": account.lusd_balance,
+        "ljun": account.ljun_balance,
+        "lumina": account.lumina_balance,
+        "custom": account.custom_balances,
+        "bridged": account.bridged_balances,
+    })
+}
+
+/// Period statement of `address` over canonical blocks `from..to` (at most
+/// `MAX_EVENT_SCAN`): balances before the first block and after the last,
+/// every transfer in or out, and the fees paid and yield earned. Blocks
+/// without transfers or events naming the account are skipped using the
+/// instruction index and event bloom.
+async fn get_account_statement(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(range): Query<BlockRangeQuery>,
+) -> Json<serde_json::Value> {
+    let Some(address) = parse_address(&address) else {
+        return Json(serde_json::json!({"error": "address must be 32 bytes of hex"}));
+    };
+    let tip = match state.storage.load_tip() {
+        Ok(tip) => tip.map_or(0, |(h, _)| h),
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let cap = range.from.saturating_add(MAX_EVENT_SCAN);
+    let to = range.to.unwrap_or(cap).min(cap).min(tip + 1);
+    if to <= range.from {
+        return Json(serde_json::json!({"error": "from must be below to and at most the tip"}));
+    }
+    let balances_after = |height: u64| match state.storage.load_state_by_height(height) {
+        Ok(Some(s)) => Ok(statement_balances(s.accounts.get(&address))),
+        Ok(None) => Err(format!("state at height {} not stored", height)),
+        Err(e) => Err(e.to_string()),
+    };
+    let opening = match range.from.checked_sub(1).map(balances_after) {
+        None => Ok(statement_balances(None)),
+        Some(balances) => balances,
+    };
+    let (opening, closing) = match (opening, balances_after(to - 1)) {
+        (Ok(opening), Ok(closing)) => (opening, closing),
+        (Err(e), _) | (_, Err(e)) => return Json(serde_json::json!({"error": e})),
+    };
+    let hashes = match state.storage.iterate_canonical_hashes(range.from..to) {
+        Ok(hashes) => hashes,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+
+    let mut transfers = Vec::new();
+    let (mut fees, mut fees_paid) = (Vec::new(), 0u64);
+    let (mut yields, mut yield_earned) = (Vec::new(), 0u64);
+    for (height, hash) in hashes {
+        let has_transfers = matches!(
+            state.storage.load_block_instructions(&hash),
+            Ok(Some(index)) if ["Transfer", "TransferWithMemo", "TransferWithComplianceProof"]
+                .iter()
+                .any(|kind| index.contains_key(*kind))
+        );
+        let has_events = matches!(
+            state.storage.load_block_bloom(&hash),
+            Ok(Some(bloom)) if bloom.may_contain(None, Some(&address))
+        );
+        if !has_transfers && !has_events {
+            continue;
+        }
+        let Ok(Some(receipts)) = state.storage.load_block_receipts(&hash) else {
+            continue;
+        };
+        let tx_id = |index: Option<u32>| {
+            index
+                .and_then(|i| receipts.receipts.get(i as usize))
+                .map(|r| hex::encode(r.tx_id))
+        };
+        let mut transfer = |tx_index: Option<u32>,
+                            outgoing: bool,
+                            counterparty: &[u8; 32],
+                            asset: &AssetType,
+                            amount: u64,
+                            memo: Option<&[u8; 32]>| {
+            transfers.push(serde_json::json!({
+                "height": height,
+                "tx_index": tx_index,
+                "tx_id": tx_id(tx_index),
+                "direction": if outgoing { "out" } else { "in" },
+                "counterparty": hex::encode(counterparty),
+                "asset": asset,
+                "amount": amount,
+                "memo": memo.map(hex::encode),
+            }));
+        };
+        if has_transfers {
+            let Ok(Some(block)) = state.storage.load_block_by_hash(&hash) else {
+                continue;
+            };
+            for (i, (tx, receipt)) in block
+                .transactions
+                .iter()
+                .zip(&receipts.receipts)
+                .enumerate()
+            {
+                let (recipient, amount, asset, memo) = match &tx.instruction {
+                    StablecoinInstruction::Transfer { to, amount, asset } => {
+                        (to, *amount, asset, None)
+                    }
+                    StablecoinInstruction::TransferWithMemo {
+                        to,
+                        amount,
+                        asset,
+                        memo_hash,
+                    } => (to, *amount, asset, Some(memo_hash)),
+                    StablecoinInstruction::TransferWithComplianceProof {
+                        to,
+                        amount,
+                        asset,
+                        ..
+                    } => (to, *amount, asset, None),
+                    _ => continue,
+                };
+                if !receipt.success || (tx.sender != address && *recipient != address) {
+                    continue;
+                }
+                let outgoing = tx.sender == address;
+                let counterparty = if outgoing { recipient } else { &tx.sender };
+                transfer(Some(i as u32), outgoing, counterparty, asset, amount, memo);
+            }
+        }
+        for logged in &receipts.events {
+            match &logged.event {
+                ChainEvent::Disbursed {
+                    from, to, amount, ..
+                } if *from == address || *to == address => {
+                    let outgoing = *from == address;
+                    let counterparty = if outgoing { to } else { from };
+                    transfer(
+                        logged.tx_index,
+                        outgoing,
+                        counterparty,
+                        &AssetType::LUSD,
+                        *amount,
+                        None,
+                    );
+                }
+                ChainEvent::MerchantSettled {
+                    merchant,
+                    treasury,
+                    amount,
+                    ..
+                } if merchant != treasury && (*merchant == address || *treasury == address) => {
+                    let outgoing = *merchant == address;
+                    let counterparty = if outgoing { treasury } else { merchant };
+                    transfer(
+                        logged.tx_index,
+                        outgoing,
+                        counterparty,
+                        &AssetType::LUSD,
+                        *amount,
+                        None,
+                    );
+                }
+                ChainEvent::FeePaid {
+                    account, amount, ..
+                } if *account == address => {
+                    fees_paid = fees_paid.saturating_add(*amount);
+                    fees.push(serde_json::json!({
+                        "height": height,
+                        "tx_id": tx_id(logged.tx_index),
+                        "amount": amount,
+                    }));
+                }
+                ChainEvent::YieldPaid {
+                    account,
+                    token_id,
+                    amount,
+                    ..
+                } if *account == address => {
+                    yield_earned = yield_earned.saturating_add(*amount);
+                    yields.push(serde_json::json!({
+                        "height": height,
+                        "tx_id": tx_id(logged.tx_index),
+                        "token_id": token_id,
+                        "amount": amount,
+                    }));
+                }
+                _ => {}
+            }
+        }
+    }
+    // Block-level settlements (no tx index) follow the block's transactions.
+    transfers.sort_by_key(|t| {
+        (
+            t["height"].as_u64(),
+            t["tx_index"].as_u64().unwrap_or(u64::MAX),
+        )
+    });
+
+    Json(serde_json::json!({
+        "address": hex::encode(address),
+        "from": range.from,
+        "scanned_to": to,
+        "opening_balances": opening,
+        "closing_balances": closing,
+        "transfers": transfers,
+        "fees_paid": fees_paid,
+        "fees": fees,
+        "yield_earned": yield_earned,
+        "yield": yields,
+    }))
+}
+
+#[derive(serde::Deserialize)]
+struct AccountTxQuery {
+    /// Cursor from the previous page's `next`
+    before_height: Option<u64>,
+    before_tx: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Committed transactions concerning `address` (sent, received or named by
+/// an event), newest first. Empty on nodes running without explorer
+/// indexes; `lumina-indexer` serves them instead.
+async fn get_account_txs(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<AccountTxQuery>,
+) -> Json<serde_json::Value> {
+    let Some(address) = parse_address(&address) else {
+        return Json(serde_json::json!({"error": "address must be 32 bytes of hex"}));
+    };
+    let before = match (query.before_height, query.before_tx.as_deref()) {
+        (None, None) => None,
+        (Some(height), Some(tx)) => match parse_address(tx) {
+            Some(tx_id) => Some((height, tx_id)),
+            None => return Json(serde_json::json!({"error": "before_tx must be 32 bytes of hex"})),
+        },
+        _ => return Json(serde_json::json!({"error": "before_height and before_tx go together"})),
+    };
+    let limit = query
+        .limit
+        .unwrap_or(MAX_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let entries = match state.storage.iterate_account_txs(&address, before, limit) {
+        Ok(entries) => entries,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let mut transactions = Vec::new();
+    for (_, tx_id) in &entries {
+        match committed_tx(&state, *tx_id) {
+            Ok(Some(committed)) => transactions.push(committed),
+            Ok(None) => {}
+            Err(e) => return Json(serde_json::json!({"error": e})),
+        }
+    }
+    let next = match entries.last() {
+        Some((height, tx_id)) if entries.len() == limit => serde_json::json!({
+            "before_height": height,
+            "before_tx": hex::encode(tx_id),
+        }),
+        _ => serde_json::Value::Null,
+    };
+    Json(serde_json::json!({
+        "address": hex::encode(address),
+        "transactions": transactions,
+        "next": next,
+    }))
+}
+
+#[derive(serde::Deserialize)]
+struct TaxLotQuery {
+    /// `fifo` (default) or `lifo`
+    method: Option<String>,
+    /// Only disposals with block timestamps in `from..=to`
+    from: Option<u64>,
+    to: Option<u64>,
+}
+
+/// Open cost-basis lots of `address` and its realized gains, as kept by a
+/// node running `--tax-lots`. Per-asset totals over the disposals in the
+/// period are what a `ZkTaxAttest` proof for that period attests to.
+async fn get_tax_lots(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+    Query(query): Query<TaxLotQuery>,
+) -> Json<serde_json::Value> {
+    let Some(address) = parse_address(&address) else {
+        return Json(serde_json::json!({"error": "address must be 32 bytes of hex"}));
+    };
+    let Some(method) = LotMethod::parse(query.method.as_deref().unwrap_or("fifo")) else {
+        return Json(serde_json::json!({"error": "method must be fifo or lifo"}));
+    };
+    let indexed_height = match state.storage.load_tax_lot_height(method) {
+        Ok(Some(height)) => height,
+        Ok(None) => {
+            return Json(serde_json::json!({
+                "error": format!("{} tax lots are not indexed by this node", method.as_str()),
+            }))
+        }
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let ledger = match state.storage.load_tax_lots(method, &address) {
+        Ok(ledger) => ledger.unwrap_or_default(),
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+
+    let lots: serde_json::Map<String, serde_json::Value> = ledger
+        .lots
+        .iter()
+        .map(|(asset, lots)| {
+            let quantity: u64 = lots.iter().map(|l| l.quantity).sum();
+            let cost: u64 = lots.iter().map(|l| l.cost).sum();
+            (
+                asset.to_string(),
+                serde_json::json!({ "quantity": quantity, "cost": cost, "lots": lots }),
+            )
+        })
+        .collect();
+    let (from, to) = (query.from.unwrap_or(0), query.to.unwrap_or(u64::MAX));
+    let mut realized = std::collections::BTreeMap::<String, (u64, u64, i128)>::new();
+    let mut disposals = Vec::new();
+    for d in ledger
+        .disposals
+        .iter()
+        .filter(|d| (from..=to).contains(&d.timestamp))
+    {
+        let totals = realized.entry(d.asset.to_string()).or_default();
+        totals.0 = totals.0.saturating_add(d.proceeds);
+        totals.1 = totals.1.saturating_add(d.cost);
+        totals.2 += d.gain();
+        disposals.push(serde_json::json!({
+            "height": d.height,
+            "timestamp": d.timestamp,
+            "asset": d.asset.to_string(),
+            "quantity": d.quantity,
+            "proceeds": d.proceeds,
+            "cost": d.cost,
+            "gain": d.gain().to_string(),
+        }));
+    }
+    let total_gain: i128 = realized.values().map(|t| t.2).sum();
+    let realized: serde_json::Map<String, serde_json::Value> = realized
+        .into_iter()
+        .map(|(asset, (proceeds, cost, gain))| {
+            (
+                asset,
+                serde_json::json!({ "proceeds": proceeds, "cost": cost, "gain": gain.to_string() }),
+            )
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "address": hex::encode(address),
+        "method": method.as_str(),
+        "indexed_height": indexed_height,
+        "open_lots": lots,
+        "disposals": disposals,
+        "realized": realized,
+        "total_realized_gain": total_gain.to_string(),
+    }))
+}
+
+const READ_ONLY_ERROR: &str = "read-only node; submit to a validator";
+
 async fn submit_tx(
     State(state): State<AppState>,
     Json(tx): Json<Transaction>,
 ) -> Json<serde_json::Value> {
     let tx_id = hex::encode(tx.id());
-    match state.tx_sender.send(tx).await {
+    let Some(tx_sender) = &state.tx_sender else {
+        return Json(serde_json::json!({
+            "status": "failed",
+            "error": READ_ONLY_ERROR,
+        }));
+    };
+    let span = info_span!("tx.submit", tx_id = %tx_id, sender = %hex::encode(tx.sender));
+    match tx_sender.send(tx).instrument(span).await {
         Ok(_) => Json(serde_json::json!({
             "status": "submitted",
             "tx_id": tx_id,
@@ -291,12 +1380,255 @@ async fn submit_tx(
     }
 }
 
-async fn faucet(
+/// Where a submitted transaction landed: `committed` with its canonical
+/// height and outcome, or `not_found` while it is pending, dropped or only on
+/// a non-canonical fork.
+async fn get_tx_status(
     State(state): State<AppState>,
-    Json(req): Json<serde_json::Value>,
+    Path(id): Path<String>,
 ) -> Json<serde_json::Value> {
-    let amount: u64 = 10_000;
-
+    let tx_id: [u8; 32] = match hex::decode(id.trim_start_matches("0x")) {
+        Ok(bytes) => match bytes.try_into() {
+            Ok(tx_id) => tx_id,
+            Err(_) => return Json(serde_json::json!({"error": "tx id must be 32 bytes"})),
+        },
+        Err(_) => return Json(serde_json::json!({"error": "invalid tx id hex"})),
+    };
+    match committed_tx(&state, tx_id) {
+        Ok(Some(committed)) => Json(committed),
+        Ok(None) => Json(serde_json::json!({
+            "tx_id": hex::encode(tx_id),
+            "status": "not_found",
+        })),
+        Err(e) => Json(serde_json::json!({"error": e})),
+    }
+}
+
+/// Where `tx_id` sits on the canonical chain, and whether it succeeded.
+fn committed_tx(state: &AppState, tx_id: [u8; 32]) -> Result<Option<serde_json::Value>, String> {
+    let locations = state
+        .storage
+        .load_tx_locations(&tx_id)
+        .map_err(|e| e.to_string())?;
+    for (block_hash, index) in locations {
+        let Ok(Some((height, _))) = state.storage.load_block_meta(&block_hash) else {
+            continue;
+        };
+        let canonical = state
+            .storage
+            .iterate_canonical_hashes(height..height + 1)
+            .ok()
+            .and_then(|hashes| hashes.first().map(|(_, hash)| *hash));
+        if canonical != Some(block_hash) {
+            continue;
+        }
+        let success = state
+            .storage
+            .load_block_receipts(&block_hash)
+            .ok()
+            .flatten()
+            .and_then(|r| r.receipts.get(index as usize).map(|r| r.success));
+        return Ok(Some(serde_json::json!({
+            "tx_id": hex::encode(tx_id),
+            "status": "committed",
+            "height": height,
+            "block_hash": hex::encode(block_hash),
+            "index": index,
+            "success": success,
+        })));
+    }
+    Ok(None)
+}
+
+/// Committed transfers to `recipient` carrying `memo`, oldest first, so a
+/// payee can match payments to the invoice or request they settle.
+async fn get_memo_transfers(
+    State(state): State<AppState>,
+    Path((recipient, memo)): Path<(String, String)>,
+) -> Json<serde_json::Value> {
+    let Some(recipient) = parse_address(&recipient) else {
+        return Json(serde_json::json!({"error": "recipient must be 32 bytes of hex"}));
+    };
+    let Some(memo) = parse_address(&memo) else {
+        return Json(serde_json::json!({"error": "memo must be 32 bytes of hex"}));
+    };
+    let tx_ids = match state.storage.load_memo_transfers(&recipient, &memo) {
+        Ok(tx_ids) => tx_ids,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let mut transfers = Vec::new();
+    for tx_id in tx_ids {
+        match committed_tx(&state, tx_id) {
+            Ok(Some(committed)) => transfers.push(committed),
+            Ok(None) => {}
+            Err(e) => return Json(serde_json::json!({"error": e})),
+        }
+    }
+    Json(serde_json::json!({
+        "recipient": hex::encode(recipient),
+        "memo": hex::encode(memo),
+        "transfers": transfers,
+    }))
+}
+
+/// The archived state of `address` and its proof against the batch root,
+/// ready to submit as `ReviveAccount`. The leaves come from the batch's
+/// `AccountsArchived` event. Live accounts report whether a keeper may
+/// archive them now.
+async fn get_archive_proof(
+    State(state): State<AppState>,
+    Path(address): Path<String>,
+) -> Json<serde_json::Value> {
+    let Some(address) = parse_address(&address) else {
+        return Json(serde_json::json!({"error": "address must be 32 bytes of hex"}));
+    };
+    let guard = state.latest();
+    let Some(batch_id) = guard.archived_accounts.get(&address).copied() else {
+        return Json(serde_json::json!({
+            "address": hex::encode(address),
+            "archived": false,
+            "archivable": guard.archivable(&address),
+        }));
+    };
+    let Some(batch) = guard.archive_batches.get(&batch_id).cloned() else {
+        return Json(serde_json::json!({"error": "unknown archive batch"}));
+    };
+    drop(guard);
+
+    let hashes = match state
+        .storage
+        .iterate_canonical_hashes(batch.height..batch.height + 1)
+    {
+        Ok(hashes) => hashes,
+        Err(e) => return Json(serde_json::json!({"error": e.to_string()})),
+    };
+    let receipts = hashes
+        .first()
+        .and_then(|(_, hash)| state.storage.load_block_receipts(hash).ok().flatten());
+    let leaves = receipts.and_then(|r| {
+        r.events.into_iter().find_map(|logged| match logged.event {
+            ChainEvent::AccountsArchived {
+                batch_id: id,
+                accounts,
+                ..
+            } if id == batch_id => Some(accounts),
+            _ => None,
+        })
+    });
+    let Some(leaves) = leaves else {
+        return Json(serde_json::json!({"error": "archive batch receipts not found"}));
+    };
+    let tree = ArchiveTree::new(&leaves);
+    let proof = leaves
+        .iter()
+        .position(|(a, _)| *a == address)
+        .and_then(|index| Some((index, tree.prove(index)?)));
+    let Some((index, proof)) = proof else {
+        return Json(serde_json::json!({"error": "account missing from its archive batch"}));
+    };
+    if tree.root() != batch.root {
+        return Json(serde_json::json!({"error": "archive batch root mismatch"}));
+    }
+    Json(serde_json::json!({
+        "address": hex::encode(address),
+        "archived": true,
+        "batch_id": batch_id,
+        "root": hex::encode(batch.root),
+        "height": batch.height,
+        "account": hex::encode(&leaves[index].1),
+        "proof": proof,
+    }))
+}
+
+/// Beacons listed by `GET /randomness`, newest first.
+const RECENT_BEACONS: usize = 10;
+
+/// Progress of the current epoch's beacon round and the latest outputs.
+async fn get_randomness(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let next_height = state
+        .storage
+        .load_tip()
+        .ok()
+        .flatten()
+        .map_or(0, |(h, _)| h)
+        .saturating_add(1);
+    let round = &guard.beacon_round;
+    let beacons: Vec<serde_json::Value> = guard
+        .randomness_beacons
+        .iter()
+        .rev()
+        .take(RECENT_BEACONS)
+        .map(|(epoch, beacon)| beacon_json(*epoch, beacon))
+        .collect();
+    Json(serde_json::json!({
+        "epoch": guard.current_epoch,
+        "phase": if lumina_execution::beacon_reveal_phase(next_height) { "reveal" } else { "commit" },
+        "commitments": round.len(),
+        "reveals": round.values().filter(|c| c.secret.is_some()).count(),
+        "beacons": beacons,
+    }))
+}
+
+async fn get_randomness_beacon(
+    State(state): State<AppState>,
+    Path(epoch): Path<u64>,
+) -> Json<serde_json::Value> {
+    match state.latest().randomness_beacons.get(&epoch) {
+        Some(beacon) => Json(beacon_json(epoch, beacon)),
+        None => Json(serde_json::json!({"error": "beacon not finalized"})),
+    }
+}
+
+fn beacon_json(epoch: u64, beacon: &RandomnessBeacon) -> serde_json::Value {
+    serde_json::json!({
+        "epoch": epoch,
+        "output": hex::encode(beacon.output),
+        "reveals": beacon.reveals,
+    })
+}
+
+/// Leading zero bits a faucet solution needs; about a million hashes.
+const FAUCET_POW_BITS: u32 = 20;
+const FAUCET_CHALLENGE_TTL: Duration = Duration::from_secs(300);
+/// Bounds the challenge table against clients that never answer.
+const MAX_OPEN_FAUCET_CHALLENGES: usize = 10_000;
+
+async fn faucet_challenge(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let now = Instant::now();
+    let mut open = state
+        .faucet_challenges
+        .lock()
+        .expect("Faucet challenges lock poisoned");
+    open.retain(|_, expires| *expires > now);
+    if open.len() >= MAX_OPEN_FAUCET_CHALLENGES {
+        return Json(serde_json::json!({
+            "error": "too many open faucet challenges, retry later"
+        }));
+    }
+    let challenge: [u8; 32] = rand::random();
+    open.insert(challenge, now + FAUCET_CHALLENGE_TTL);
+
+    Json(serde_json::json!({
+        "challenge": hex::encode(challenge),
+        "difficulty": FAUCET_POW_BITS,
+        "expires_in": FAUCET_CHALLENGE_TTL.as_secs(),
+        "algorithm": "blake3(challenge || address || nonce_le64)"
+    }))
+}
+
+async fn faucet(
+    State(state): State<AppState>,
+    Json(req): Json<serde_json::Value>,
+) -> Json<serde_json::Value> {
+    let amount: u64 = 10_000;
+    if state.tx_sender.is_none() {
+        return Json(serde_json::json!({
+            "status": "failed",
+            "error": READ_ONLY_ERROR,
+        }));
+    }
+
     let addr_hex = req
         .get("address")
         .and_then(|v| v.as_str())
@@ -319,10 +1651,43 @@ async fn faucet(
     let mut key = [0u8; 32];
     key.copy_from_slice(&bytes);
 
+    let challenge = req
+        .get("challenge")
+        .and_then(|v| v.as_str())
+        .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+        .and_then(|b| <[u8; 32]>::try_from(b).ok());
+    let nonce = req.get("nonce").and_then(|v| v.as_u64());
+    let (Some(challenge), Some(nonce)) = (challenge, nonce) else {
+        return Json(serde_json::json!({
+            "status": "failed",
+            "error": "solve GET /faucet/challenge and send its challenge and nonce"
+        }));
+    };
+    // Any attempt uses the challenge up, so the node never checks guesses
+    // on a client's behalf.
+    let expires = state
+        .faucet_challenges
+        .lock()
+        .expect("Faucet challenges lock poisoned")
+        .remove(&challenge);
+    if expires.is_none_or(|e| e <= Instant::now()) {
+        return Json(serde_json::json!({
+            "status": "failed",
+            "error": "unknown or expired challenge"
+        }));
+    }
+    if !lumina_types::faucet::meets_difficulty(&challenge, &key, nonce, FAUCET_POW_BITS) {
+        return Json(serde_json::json!({
+            "status": "failed",
+            "error": "proof of work does not meet the difficulty"
+        }));
+    }
+
     let mut guard = state.global_state.write().await;
     let account = guard.accounts.entry(key).or_default();
     account.lusd_balance = account.lusd_balance.saturating_add(amount);
     guard.total_lusd_supply = guard.total_lusd_supply.saturating_add(amount);
+    state.snapshot.send_replace(Arc::new(guard.clone()));
 
     Json(serde_json::json!({
         "status": "funded",
@@ -333,24 +1698,201 @@ async fn faucet(
 }
 
 async fn get_validators(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let guard = state.global_state.read().await;
+    let guard = state.latest();
     let validators: Vec<serde_json::Value> = guard
         .validators
         .iter()
         .map(|v| {
+            let rewards = guard.validator_rewards.get(&v.pubkey);
             serde_json::json!({
                 "pubkey": hex::encode(v.pubkey),
                 "stake": v.stake,
                 "power": v.power,
                 "is_green": v.is_green,
+                "green_verified_at": v.green_verified_at,
+                "commission_bps": rewards.map_or(0, |r| r.commission_bps),
+                "claimable_rewards": rewards.map_or(0, |r| r.accrued.saturating_add(r.unsettled(v.stake))),
+                "missed_blocks": guard.validator_liveness.get(&v.pubkey).map_or(0, |l| l.missed_count),
+            })
+        })
+        .collect();
+    let jailed: Vec<serde_json::Value> = guard
+        .jailed_validators
+        .iter()
+        .map(|(pubkey, j)| {
+            serde_json::json!({
+                "pubkey": hex::encode(pubkey),
+                "stake": j.validator.stake,
+                "jailed_height": j.jailed_height,
+                "jailed_until": j.jailed_until,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "validators": validators,
+        "jailed": jailed,
+        "fee_pool": guard.fee_pool,
+        "validator_fee_share_bps": guard.validator_fee_share_bps,
+    }))
+}
+
+/// Summary of a custodian; MPC keys are identified by the first 8 bytes of
+/// their blake3 hash.
+fn custodian_json(state: &GlobalState, custodian: &CustodianState) -> serde_json::Value {
+    let total: u64 = state.custodians.iter().map(|c| c.reserve_balance).sum();
+    let share_bps = if total == 0 {
+        0
+    } else {
+        (custodian.reserve_balance as u128 * 10_000 / total as u128) as u64
+    };
+    let fingerprints: Vec<String> = custodian
+        .mpc_pubkeys
+        .iter()
+        .map(|k| hex::encode(&blake3::hash(k).as_bytes()[..8]))
+        .collect();
+    serde_json::json!({
+        "pubkey": hex::encode(custodian.pubkey),
+        "stake": custodian.stake,
+        "zone_id": custodian.zone_id,
+        "registered_height": custodian.registered_height,
+        "reserve_balance": custodian.reserve_balance,
+        "reserve_share_bps": share_bps,
+        "mpc_key_fingerprints": fingerprints,
+        "rotations": state
+            .custodian_rotations
+            .get(&custodian.pubkey)
+            .cloned()
+            .unwrap_or_default(),
+    })
+}
+
+async fn get_custodians(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let custodians: Vec<serde_json::Value> = guard
+        .custodians
+        .iter()
+        .map(|c| custodian_json(&guard, c))
+        .collect();
+    Json(serde_json::json!({
+        "attested_reserves": guard.stabilization_pool_balance,
+        "last_por_timestamp": guard.last_por_timestamp,
+        "custodians": custodians,
+    }))
+}
+
+/// A custodian with its full MPC key set and the rotations it has pending.
+async fn get_custodian(
+    State(state): State<AppState>,
+    Path(pubkey): Path<String>,
+) -> Json<serde_json::Value> {
+    let Some(pubkey) = parse_address(&pubkey) else {
+        return Json(serde_json::json!({"error": "pubkey must be 32-byte hex"}));
+    };
+    let guard = state.latest();
+    let Some(custodian) = guard.custodians.iter().find(|c| c.pubkey == pubkey) else {
+        return Json(serde_json::json!({"error": "custodian not registered"}));
+    };
+    let pending: Vec<serde_json::Value> = guard
+        .reserve_rotations
+        .iter()
+        .filter(|(_, r)| r.from_custodian == pubkey || r.to_custodian == pubkey)
+        .map(|(id, r)| {
+            serde_json::json!({
+                "rotation_id": id,
+                "from_custodian": hex::encode(r.from_custodian),
+                "to_custodian": hex::encode(r.to_custodian),
+                "amount": r.amount,
+                "proposed_height": r.proposed_height,
+                "source_approvals": r.source_approvals.len(),
+                "destination_approvals": r.destination_approvals.len(),
+            })
+        })
+        .collect();
+    let mut body = custodian_json(&guard, custodian);
+    body["mpc_pubkeys"] = custodian.mpc_pubkeys.iter().map(hex::encode).collect();
+    body["pending_rotations"] = serde_json::json!(pending);
+    Json(body)
+}
+
+/// Registered screening oracles and the roots they last published.
+async fn get_screening_roots(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let oracles: Vec<serde_json::Value> = guard
+        .screening_oracles
+        .iter()
+        .map(|(oracle, s)| {
+            serde_json::json!({
+                "oracle": hex::encode(oracle),
+                "root": s.root.map(hex::encode),
+                "published_height": s.published_height,
             })
         })
         .collect();
-    Json(serde_json::json!({ "validators": validators }))
+    Json(serde_json::json!({ "oracles": oracles }))
+}
+
+#[derive(serde::Deserialize)]
+struct ScreeningProofRequest {
+    /// Hex addresses of the oracle's screened set
+    screened: Vec<String>,
+    address: String,
+}
+
+fn parse_address(hex_str: &str) -> Option<[u8; 32]> {
+    hex::decode(hex_str.trim_start_matches("0x"))
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// Build the root of a screened set and a proof that `address` is absent
+/// from it, for integrators assembling `TransferWithComplianceProof`.
+async fn prove_not_screened(Json(req): Json<ScreeningProofRequest>) -> Json<serde_json::Value> {
+    let Some(screened) = req
+        .screened
+        .iter()
+        .map(|a| parse_address(a))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Json(serde_json::json!({"error": "screened must be 32-byte hex addresses"}));
+    };
+    let Some(address) = parse_address(&req.address) else {
+        return Json(serde_json::json!({"error": "address must be 32-byte hex"}));
+    };
+    let tree = ScreeningTree::new(screened);
+    match tree.prove_non_membership(&address) {
+        Some(proof) => Json(serde_json::json!({
+            "root": hex::encode(tree.root()),
+            "proof": proof,
+        })),
+        None => Json(serde_json::json!({"error": "Address is screened"})),
+    }
+}
+
+/// Sync progress and build info for this node.
+async fn get_node_status(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let status = state.consensus.borrow().clone();
+    Json(serde_json::json!({
+        "height": status.height,
+        "syncing": status.syncing(),
+        "highest_seen_height": status.highest_seen_height,
+        "upgrade_required": status.upgrade_required,
+        "peer_count": state.peers.borrow().len(),
+        "mempool_size": status.mempool_size,
+        "read_only": state.tx_sender.is_none(),
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_secs": state.started_at.elapsed().as_secs(),
+    }))
+}
+
+/// Current peer connections with their direction and reputation score.
+async fn get_peers(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let peers = state.peers.borrow().clone();
+    Json(serde_json::json!({ "count": peers.len(), "peers": peers }))
 }
 
 async fn get_insurance(State(state): State<AppState>) -> Json<serde_json::Value> {
-    let guard = state.global_state.read().await;
+    let guard = state.latest();
     Json(serde_json::json!({
         "insurance_fund_balance": guard.insurance_fund_balance,
         "total_lusd_supply": guard.total_lusd_supply,
@@ -361,3 +1903,752 @@ async fn get_insurance(State(state): State<AppState>) -> Json<serde_json::Value>
         },
     }))
 }
+
+#[derive(serde::Deserialize)]
+struct EpochRangeQuery {
+    from: Option<u64>,
+    to: Option<u64>,
+}
+
+/// Treasury balance, open payout streams, and fee inflows and payouts per
+/// epoch (the most recent `MAX_PAGE_LIMIT` epochs of `from..=to`).
+async fn get_treasury(
+    State(state): State<AppState>,
+    Query(range): Query<EpochRangeQuery>,
+) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let to = range.to.unwrap_or(guard.current_epoch);
+    let from = range.from.unwrap_or(0);
+    let flows: Vec<serde_json::Value> = guard
+        .treasury_flows
+        .range(from..=to.max(from))
+        .rev()
+        .take(MAX_PAGE_LIMIT)
+        .map(|(epoch, f)| {
+            serde_json::json!({
+                "epoch": epoch,
+                "inflow": f.inflow,
+                "outflow": f.outflow,
+            })
+        })
+        .collect();
+    let streams: Vec<serde_json::Value> = guard
+        .treasury_streams
+        .iter()
+        .map(|(proposal_id, s)| {
+            serde_json::json!({
+                "proposal_id": proposal_id,
+                "recipient": hex::encode(s.recipient),
+                "total": s.total,
+                "paid": s.paid,
+                "start_height": s.start_height,
+                "end_height": s.end_height,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "balance": guard.treasury_balance,
+        "fee_share_bps": guard.treasury_fee_share_bps,
+        "current_epoch": guard.current_epoch,
+        "streams": streams,
+        "flows": flows,
+    }))
+}
+
+async fn get_auctions(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let height = state
+        .storage
+        .load_tip()
+        .ok()
+        .flatten()
+        .map_or(0, |(h, _)| h);
+    let auctions: Vec<serde_json::Value> = guard
+        .collateral_auctions
+        .iter()
+        .map(|(auction_id, a)| {
+            serde_json::json!({
+                "auction_id": auction_id,
+                "position_id": a.position_id,
+                "owner": hex::encode(a.owner),
+                "asset": a.asset,
+                "collateral_remaining": a.collateral_remaining,
+                "debt_remaining": a.debt_remaining,
+                "price": a.price_at(height),
+                "start_price": a.start_price,
+                "floor_price": a.floor_price,
+                "start_height": a.start_height,
+                "end_height": a.end_height,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "height": height,
+        "auctions": auctions,
+    }))
+}
+
+/// A hash lock awaiting its preimage or, once expired, a refund. Claimed
+/// and refunded locks are removed; their events remain.
+async fn get_hash_lock(
+    State(state): State<AppState>,
+    Path(lock_id): Path<u64>,
+) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let Some(lock) = guard.hash_locks.get(&lock_id) else {
+        return Json(serde_json::json!({"error": "hash lock not found"}));
+    };
+    Json(serde_json::json!({
+        "lock_id": lock_id,
+        "sender": hex::encode(lock.sender),
+        "recipient": hex::encode(lock.recipient),
+        "asset": lock.asset,
+        "amount": lock.amount,
+        "hash_lock": hex::encode(lock.hash_lock),
+        "timeout_height": lock.timeout_height,
+        "expired": lock.expired,
+    }))
+}
+
+/// Redemption delay tiers and the large redemptions waiting out theirs.
+async fn get_delayed_redemptions(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let redemptions: Vec<_> = guard
+        .delayed_redemptions
+        .iter()
+        .map(|(redemption_id, r)| {
+            serde_json::json!({
+                "redemption_id": redemption_id,
+                "owner": hex::encode(r.owner),
+                "amount": r.amount,
+                "requested_height": r.requested_height,
+                "release_height": r.release_height,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "tiers": guard.redemption_tiers,
+        "redemptions": redemptions,
+    }))
+}
+
+/// The emergency council and every action it has proposed, newest first.
+async fn get_council(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let council = &guard.emergency_council;
+    let actions: Vec<_> = guard
+        .council_actions
+        .iter()
+        .rev()
+        .map(|(action_id, a)| {
+            serde_json::json!({
+                "action_id": action_id,
+                "power": a.power,
+                "proposer": hex::encode(a.proposer),
+                "approvals": a.approvals.iter().map(hex::encode).collect::<Vec<_>>(),
+                "proposed_height": a.proposed_height,
+                "status": a.status,
+                "expires_height": a.expires_height,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "members": council.members.iter().map(hex::encode).collect::<Vec<_>>(),
+        "threshold": council.threshold,
+        "sunset_blocks": council.sunset_blocks,
+        "actions": actions,
+    }))
+}
+
+/// Instruction kinds refused before they run, and those governance paused;
+/// the rest are held by council actions in effect.
+async fn get_paused_instructions(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let kinds = |bits: &InstructionBitmap| -> Vec<&str> {
+        bits.indices()
+            .filter_map(|index| INSTRUCTION_KINDS.get(index).copied())
+            .collect()
+    };
+    Json(serde_json::json!({
+        "paused": kinds(&guard.paused_instructions),
+        "governance_paused": kinds(&guard.governance_paused),
+    }))
+}
+
+/// SPV bridges with their light client tips, and the deposit claims still
+/// in their challenge period. Hashes are in internal byte order.
+async fn get_spv_bridges(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let bridges: Vec<_> = guard
+        .spv_bridges
+        .iter()
+        .map(|(chain, bridge)| {
+            let tip = guard
+                .spv_clients
+                .get(chain)
+                .and_then(|client| Some((client.tip, client.headers.get(&client.tip)?)));
+            serde_json::json!({
+                "chain": chain,
+                "symbol": bridge.symbol,
+                "deposit_target": hex::encode(&bridge.deposit_target),
+                "min_confirmations": bridge.min_confirmations,
+                "challenge_blocks": bridge.challenge_blocks,
+                "checkpoint_height": bridge.checkpoint_height,
+                "tip_hash": tip.map(|(hash, _)| hex::encode(hash)),
+                "tip_height": tip.map(|(_, header)| header.height),
+            })
+        })
+        .collect();
+    let deposits: Vec<_> = guard
+        .spv_deposits
+        .iter()
+        .map(|(id, deposit)| {
+            serde_json::json!({
+                "deposit_id": id,
+                "chain": deposit.chain,
+                "block_hash": hex::encode(deposit.block_hash),
+                "recipient": hex::encode(deposit.recipient),
+                "symbol": deposit.symbol,
+                "amount": deposit.amount,
+                "claimed_height": deposit.claimed_height,
+                "finalize_height": deposit.finalize_height,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "bridges": bridges,
+        "pending_deposits": deposits,
+    }))
+}
+
+/// Remote chains LUSD can be sent to, with the LUSD outstanding on each.
+async fn get_remote_chains(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let chains: Vec<_> = guard
+        .remote_chains
+        .iter()
+        .map(|(chain_id, chain)| {
+            serde_json::json!({
+                "chain_id": chain_id,
+                "active": chain.active,
+                "outstanding": chain.outstanding,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "chains": chains }))
+}
+
+#[derive(serde::Deserialize)]
+struct BatchQuery {
+    /// First batch id to return
+    from: Option<u64>,
+    limit: Option<usize>,
+}
+
+/// Sealed outbound message batches from `from`, with the bytes validators
+/// sign and their signatures, for relayers to carry to remote chains once
+/// `attested`.
+async fn get_message_batches(
+    State(state): State<AppState>,
+    Query(query): Query<BatchQuery>,
+) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let limit = query
+        .limit
+        .unwrap_or(MAX_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let batches: Vec<_> = guard
+        .message_batches
+        .range(query.from.unwrap_or(0)..)
+        .take(limit)
+        .map(|(batch_id, batch)| {
+            let messages: Vec<_> = batch
+                .messages
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "nonce": m.nonce,
+                        "dest_chain": m.dest_chain,
+                        "sender": hex::encode(m.sender),
+                        "recipient": hex::encode(&m.recipient),
+                        "amount": m.amount,
+                    })
+                })
+                .collect();
+            let signatures: Vec<_> = batch
+                .signatures
+                .iter()
+                .map(|vote| {
+                    serde_json::json!({
+                        "validator": hex::encode(vote.validator),
+                        "signature": hex::encode(&vote.signature),
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "batch_id": batch_id,
+                "height": batch.height,
+                "messages": messages,
+                "signing_bytes": hex::encode(batch.signing_bytes()),
+                "signatures": signatures,
+                "attested": batch.has_quorum(&guard.validators),
+            })
+        })
+        .collect();
+    Json(serde_json::json!({ "batches": batches }))
+}
+
+/// Credit lines with interest accrued through the tip.
+async fn get_credit_lines(State(state): State<AppState>) -> Json<serde_json::Value> {
+    let guard = state.latest();
+    let height = state
+        .storage
+        .load_tip()
+        .ok()
+        .flatten()
+        .map_or(0, |(h, _)| h);
+    let lines: Vec<serde_json::Value> = guard
+        .credit_lines
+        .iter()
+        .map(|(line_id, line)| {
+            let mut line = line.clone();
+            line.accrue(height);
+            let guaranteed_shares = line.guaranteed_shares();
+            let guarantors: serde_json::Map<String, serde_json::Value> = line
+                .guarantors
+                .iter()
+                .map(|(g, shares)| (hex::encode(g), serde_json::json!(shares)))
+                .collect();
+            serde_json::json!({
+                "line_id": line_id,
+                "borrower": hex::encode(line.borrower),
+                "limit": line.limit,
+                "interest_bps": line.interest_bps,
+                "maturity_height": line.maturity_height,
+                "principal": line.principal,
+                "interest_owed": line.interest_owed,
+                "guaranteed_shares": guaranteed_shares,
+                "guarantee_value": guard.ljun_value_of(guaranteed_shares).min(u64::MAX as u128) as u64,
+                "defaulted": line.defaulted,
+                "guarantors": guarantors,
+            })
+        })
+        .collect();
+    Json(serde_json::json!({
+        "height": height,
+        "credit_lines": lines,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use lumina_consensus::{execute_block, ConsensusService};
+    use lumina_crypto::signatures::SigningKey;
+    use lumina_network::PeerDirection;
+    use lumina_types::balance::Balance;
+    use lumina_types::block::BlockHeader;
+    use lumina_types::state::PorAttestation;
+    use lumina_types::taxlots::{LotAsset, TaxLedger};
+    use tower::ServiceExt;
+
+    const SHOP: [u8; 32] = [9u8; 32];
+    const MEMO: [u8; 32] = [42u8; 32];
+    const CUSTODIAN: [u8; 32] = [5u8; 32];
+    /// More GOLD than a u64 holds
+    const GOLD: u128 = u64::MAX as u128 * 1_000;
+
+    /// A follower node that has committed block 1: a plain transfer and a
+    /// memo transfer from `payer` to `SHOP`.
+    struct Node {
+        state: AppState,
+        payer: [u8; 32],
+        tx_ids: Vec<[u8; 32]>,
+        _network: mpsc::Receiver<lumina_network::NetworkCommand>,
+        _blocks: mpsc::Sender<Block>,
+    }
+
+    fn signed(key: &SigningKey, nonce: u64, instruction: StablecoinInstruction) -> Transaction {
+        let mut tx = Transaction {
+            sender: key.verifying_key().to_bytes(),
+            nonce,
+            instruction,
+            signature: Vec::new(),
+            gas_limit: 0,
+            gas_price: 0,
+        };
+        tx.signature = lumina_crypto::signatures::sign(key, &tx.signing_bytes());
+        tx
+    }
+
+    async fn committed_node(name: &str) -> Node {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let payer = key.verifying_key().to_bytes();
+        let mut genesis = GlobalState {
+            total_lusd_supply: 1_000,
+            ..Default::default()
+        };
+        let account = genesis.accounts.entry(payer).or_default();
+        account.lusd_balance = 1_000;
+        account
+            .custom_balances
+            .insert("GOLD".to_string(), Balance::new(GOLD));
+        genesis.por_history.insert(
+            1_700_000_000,
+            PorAttestation {
+                height: 0,
+                total_reserves: 1_100,
+                proof_hash: [1u8; 32],
+                verifying_key_hash: [2u8; 32],
+                lusd_supply: 1_000,
+            },
+        );
+        genesis.custodians.push(CustodianState {
+            pubkey: CUSTODIAN,
+            stake: 10,
+            mpc_pubkeys: vec![[6u8; 32]],
+            registered_height: 0,
+            zone_id: 1,
+            reserve_balance: 1_100,
+        });
+
+        let txs = vec![
+            signed(
+                &key,
+                0,
+                StablecoinInstruction::Transfer {
+                    to: SHOP,
+                    amount: 100,
+                    asset: AssetType::LUSD,
+                },
+            ),
+            signed(
+                &key,
+                1,
+                StablecoinInstruction::TransferWithMemo {
+                    to: SHOP,
+                    amount: 50,
+                    asset: AssetType::LUSD,
+                    memo_hash: MEMO,
+                },
+            ),
+        ];
+        let mut block = Block {
+            header: BlockHeader {
+                height: 1,
+                prev_hash: [0u8; 32],
+                transactions_root: Block::transactions_root(&txs),
+                state_root: [0u8; 32],
+                timestamp: 1_700_000_100,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions: txs,
+            votes: Vec::new(),
+        };
+        let (next, commit) = execute_block(genesis.clone(), &block).await.unwrap();
+        block.header.state_root = commit.app_hash;
+        block.header.validator_set_hash = next.validator_set_hash();
+        block.header.audit_hash = next.audit_head;
+
+        let storage = Arc::new(Storage::new("unused").unwrap());
+        storage.save_state_at_height(0, &genesis).unwrap();
+        let global_state = Arc::new(RwLock::new(genesis.clone()));
+        let (snapshot, _) = watch::channel(Arc::new(genesis));
+        let (network_tx, network_rx) = mpsc::channel(16);
+        let (_tx_sender, tx_rx) = mpsc::channel(1);
+        let (block_sender, block_rx) = mpsc::channel(1);
+        let wal_path = std::env::temp_dir().join(format!(
+            "lumina-api-{}-{}/consensus.wal",
+            name,
+            std::process::id()
+        ));
+        let service = ConsensusService::new(
+            global_state.clone(),
+            snapshot.clone(),
+            storage.clone(),
+            network_tx,
+            tx_rx,
+            block_rx,
+            wal_path,
+        )
+        .with_block_production(false);
+        let mut consensus = service.status();
+        tokio::spawn(service.run());
+        let tx_ids = block.transactions.iter().map(|tx| tx.id()).collect();
+        block_sender.send(block).await.unwrap();
+        tokio::time::timeout(
+            Duration::from_secs(10),
+            consensus.wait_for(|status| status.height == 1),
+        )
+        .await
+        .expect("block 1 committed")
+        .unwrap();
+
+        let peers = vec![PeerInfo {
+            peer_id: "12D3KooWPeer".to_string(),
+            address: "/ip4/10.0.0.2/tcp/30333".to_string(),
+            direction: PeerDirection::Outbound,
+            score: 5,
+            last_seen: 1_700_000_000,
+        }];
+        Node {
+            state: AppState {
+                global_state,
+                snapshot,
+                storage,
+                tx_sender: None,
+                network: "lumina-test".to_string(),
+                peers: watch::channel(peers).1,
+                consensus,
+                rules: watch::channel(RuleStates::new()).1,
+                faucet_challenges: Arc::default(),
+                started_at: Instant::now(),
+            },
+            payer,
+            tx_ids,
+            _network: network_rx,
+            _blocks: block_sender,
+        }
+    }
+
+    async fn get(state: &AppState, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router(state.clone())
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json = serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null);
+        (status, json)
+    }
+
+    #[tokio::test]
+    async fn queries_are_served_while_consensus_holds_the_state_lock() {
+        let node = committed_node("snapshot").await;
+        let _consensus = node.state.global_state.write().await;
+        let (status, body) =
+            tokio::time::timeout(Duration::from_secs(5), get(&node.state, "/state"))
+                .await
+                .expect("query waited on the consensus lock");
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_lusd_supply"], 1_000);
+        assert!(body["circuit_breaker_active"].is_boolean());
+    }
+
+    #[tokio::test]
+    async fn account_balances_above_u64_are_decimal_strings() {
+        let node = committed_node("account").await;
+        let (status, body) = get(
+            &node.state,
+            &format!("/account/{}", hex::encode(node.payer)),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["lusd_balance"], 850);
+        assert_eq!(body["nonce"], 2);
+        assert_eq!(body["custom_balances"]["GOLD"], GOLD.to_string());
+
+        let (_, body) = get(&node.state, &format!("/account/{}", hex::encode([7u8; 32]))).await;
+        assert_eq!(body["error"], "Account not found");
+    }
+
+    #[tokio::test]
+    async fn events_filter_by_type_address_and_memo() {
+        let node = committed_node("events").await;
+        let (status, body) = get(&node.state, "/events?from=1&type=MemoPayment").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["scanned_to"], 10_001);
+        let events = body["events"].as_array().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["height"], 1);
+        assert_eq!(events[0]["tx_index"], 1);
+        assert_eq!(events[0]["type"], "MemoPayment");
+        assert_eq!(events[0]["event"]["MemoPayment"]["amount"], 50);
+
+        let uri = format!(
+            "/events?from=1&address={}&memo_hash={}",
+            hex::encode(SHOP),
+            hex::encode(MEMO)
+        );
+        let (_, body) = get(&node.state, &uri).await;
+        assert_eq!(body["events"].as_array().unwrap().len(), 1);
+        let uri = format!("/events?from=1&memo_hash={}", hex::encode([1u8; 32]));
+        let (_, body) = get(&node.state, &uri).await;
+        assert!(body["events"].as_array().unwrap().is_empty());
+
+        let (_, body) = get(&node.state, "/events?from=1&address=abcd").await;
+        assert_eq!(body["error"], "address must be 32-byte hex");
+        let (status, _) = get(&node.state, "/events").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn block_proofs_are_listed_per_canonical_block() {
+        let node = committed_node("proofs").await;
+        let (status, body) = get(&node.state, "/block/1/proofs").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, serde_json::json!([]));
+        let (status, body) = get(&node.state, "/block/2/proofs").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_null());
+        let (status, _) = get(&node.state, "/block/tip/proofs").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn node_status_and_peers_describe_this_node() {
+        let node = committed_node("status").await;
+        let (status, body) = get(&node.state, "/node/status").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["height"], 1);
+        assert_eq!(body["syncing"], false);
+        assert_eq!(body["read_only"], true);
+        assert_eq!(body["peer_count"], 1);
+        assert_eq!(body["mempool_size"], 0);
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+
+        let (status, body) = get(&node.state, "/node/peers").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 1);
+        assert_eq!(body["peers"][0]["peer_id"], "12D3KooWPeer");
+        assert_eq!(body["peers"][0]["direction"], "outbound");
+        assert_eq!(body["peers"][0]["score"], 5);
+    }
+
+    #[tokio::test]
+    async fn instruction_stats_count_committed_kinds() {
+        let node = committed_node("instructions").await;
+        let (status, body) = get(&node.state, "/stats/instructions?from=0&to=5").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["totals"],
+            serde_json::json!({"Transfer": 1, "TransferWithMemo": 1})
+        );
+        assert_eq!(body["scanned_to"], 5);
+        let blocks = body["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0]["height"], 1);
+        assert_eq!(
+            blocks[0]["instructions"]["TransferWithMemo"],
+            serde_json::json!([hex::encode(node.tx_ids[1])])
+        );
+    }
+
+    #[tokio::test]
+    async fn supply_stats_roll_up_committed_blocks() {
+        let node = committed_node("supply").await;
+        let (status, body) = get(&node.state, "/stats/supply").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["height"], 1);
+        assert_eq!(body["timestamp"], 1_700_000_100);
+        assert_eq!(body["total_lusd_supply"], 1_000);
+        for window in ["1h", "24h", "7d"] {
+            assert_eq!(body["windows"][window]["blocks"], 1, "{}", window);
+            assert_eq!(body["windows"][window]["net_mint"], 0, "{}", window);
+        }
+    }
+
+    #[tokio::test]
+    async fn por_history_reports_coverage_per_attestation() {
+        let node = committed_node("por").await;
+        let (status, body) = get(&node.state, "/por/history").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 1);
+        let attestation = &body["attestations"][0];
+        assert_eq!(attestation["timestamp"], 1_700_000_000);
+        assert_eq!(attestation["total_reserves"], 1_100);
+        assert_eq!(attestation["coverage_ratio"], 1.1);
+        assert_eq!(attestation["proof_hash"], hex::encode([1u8; 32]));
+
+        let (_, body) = get(&node.state, "/por/history?to=1000").await;
+        assert!(body["attestations"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn custodians_report_reserve_shares_and_keys() {
+        let node = committed_node("custodians").await;
+        let (status, body) = get(&node.state, "/custodians").await;
+        assert_eq!(status, StatusCode::OK);
+        let custodian = &body["custodians"][0];
+        assert_eq!(custodian["pubkey"], hex::encode(CUSTODIAN));
+        assert_eq!(custodian["reserve_share_bps"], 10_000);
+        assert_eq!(
+            custodian["mpc_key_fingerprints"][0].as_str().unwrap().len(),
+            16
+        );
+
+        let uri = format!("/custodians/{}", hex::encode(CUSTODIAN));
+        let (status, body) = get(&node.state, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["zone_id"], 1);
+        assert_eq!(
+            body["mpc_pubkeys"],
+            serde_json::json!([hex::encode([6u8; 32])])
+        );
+        assert_eq!(body["pending_rotations"], serde_json::json!([]));
+
+        let uri = format!("/custodians/{}", hex::encode([8u8; 32]));
+        let (_, body) = get(&node.state, &uri).await;
+        assert_eq!(body["error"], "custodian not registered");
+    }
+
+    #[tokio::test]
+    async fn statement_lists_the_period_s_transfers_and_balances() {
+        let node = committed_node("statement").await;
+        let uri = format!("/account/{}/statement?from=1", hex::encode(node.payer));
+        let (status, body) = get(&node.state, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["scanned_to"], 2);
+        assert_eq!(body["opening_balances"]["lusd"], 1_000);
+        assert_eq!(body["closing_balances"]["lusd"], 850);
+        assert_eq!(body["closing_balances"]["custom"]["GOLD"], GOLD.to_string());
+        let transfers = body["transfers"].as_array().unwrap();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0]["direction"], "out");
+        assert_eq!(transfers[0]["counterparty"], hex::encode(SHOP));
+        assert_eq!(transfers[0]["amount"], 100);
+        assert!(transfers[0]["memo"].is_null());
+        assert_eq!(transfers[1]["tx_id"], hex::encode(node.tx_ids[1]));
+        assert_eq!(transfers[1]["memo"], hex::encode(MEMO));
+
+        let uri = format!("/account/{}/statement?from=5", hex::encode(node.payer));
+        let (_, body) = get(&node.state, &uri).await;
+        assert_eq!(body["error"], "from must be below to and at most the tip");
+    }
+
+    #[tokio::test]
+    async fn tax_lots_serve_indexed_ledgers_with_gains_as_strings() {
+        let node = committed_node("taxlots").await;
+        let uri = format!("/account/{}/taxlots", hex::encode(node.payer));
+        let (status, body) = get(&node.state, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["error"], "fifo tax lots are not indexed by this node");
+
+        let mut ledger = TaxLedger::default();
+        ledger.acquire(LotAsset::Lumina, 10, 100, 1, 1_700_000_000);
+        ledger.dispose(LotMethod::Fifo, LotAsset::Lumina, 4, 30, 1, 1_700_000_100);
+        node.state
+            .storage
+            .save_tax_lots(LotMethod::Fifo, 1, &[(node.payer, ledger)])
+            .unwrap();
+        let (status, body) = get(&node.state, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["method"], "fifo");
+        assert_eq!(body["indexed_height"], 1);
+        assert_eq!(body["open_lots"]["LUMINA"]["quantity"], 6);
+        assert_eq!(body["open_lots"]["LUMINA"]["cost"], 60);
+        assert_eq!(body["disposals"][0]["gain"], "-10");
+        assert_eq!(body["realized"]["LUMINA"]["gain"], "-10");
+        assert_eq!(body["total_realized_gain"], "-10");
+
+        let (_, body) = get(&node.state, &format!("{}?method=average", uri)).await;
+        assert_eq!(body["error"], "method must be fifo or lifo");
+    }
+}
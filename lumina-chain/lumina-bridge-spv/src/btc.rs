@@ -0,0 +1,311 @@
+//! Bitcoin headers, transaction Merkle branches and deposit transactions.
+//! Hashes are in internal byte order, as they appear in block data (the
+//! reverse of how explorers display them).
+
+use anyhow::{anyhow, bail, Result};
+use sha2::{Digest, Sha256};
+
+pub const HEADER_LEN: usize = 80;
+
+/// A deposit names its Lumina recipient in an output whose script is
+/// `OP_RETURN <32 bytes>`.
+const OP_RETURN: u8 = 0x6a;
+
+pub fn double_sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    pub version: i32,
+    pub prev_block: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+    /// Double SHA-256 of the encoded header
+    pub hash: [u8; 32],
+}
+
+impl BlockHeader {
+    pub fn parse(raw: &[u8]) -> Result<Self> {
+        if raw.len() != HEADER_LEN {
+            bail!("Bitcoin header must be {} bytes", HEADER_LEN);
+        }
+        let word = |at: usize| u32::from_le_bytes(raw[at..at + 4].try_into().expect("4 bytes"));
+        Ok(BlockHeader {
+            version: word(0) as i32,
+            prev_block: raw[4..36].try_into().expect("32 bytes"),
+            merkle_root: raw[36..68].try_into().expect("32 bytes"),
+            time: word(68),
+            bits: word(72),
+            nonce: word(76),
+            hash: double_sha256(raw),
+        })
+    }
+
+    /// Check the header's hash meets the target its own `bits` encode.
+    pub fn check_pow(&self) -> Result<()> {
+        let target = target(self.bits)?;
+        let mut hash = self.hash;
+        hash.reverse();
+        if hash > target {
+            bail!("Bitcoin header does not meet its target");
+        }
+        Ok(())
+    }
+}
+
+/// Big-endian 256-bit target encoded by compact `bits`.
+pub fn target(bits: u32) -> Result<[u8; 32]> {
+    let exponent = (bits >> 24) as usize;
+    let mantissa = bits & 0x007f_ffff;
+    if bits & 0x0080_0000 != 0 || mantissa == 0 {
+        bail!("Invalid compact target {:#010x}", bits);
+    }
+    let mut target = [0u8; 32];
+    if exponent <= 3 {
+        let value = mantissa >> (8 * (3 - exponent));
+        target[28..].copy_from_slice(&value.to_be_bytes());
+    } else {
+        if exponent > 32 {
+            bail!("Invalid compact target {:#010x}", bits);
+        }
+        let start = 32 - exponent;
+        target[start..start + 3].copy_from_slice(&mantissa.to_be_bytes()[1..]);
+    }
+    Ok(target)
+}
+
+/// Approximate expected hashes to meet `bits`' target, for comparing the
+/// work of competing chains.
+pub fn work(bits: u32) -> Result<u128> {
+    target(bits)?;
+    let exponent = (bits >> 24) as i64;
+    let mantissa = (bits & 0x007f_ffff) as u128;
+    // target = mantissa * 2^(8 * (exponent - 3)), work ~ 2^256 / target
+    let shift = 256 - 8 * (exponent - 3);
+    Ok(if shift >= 128 {
+        u128::MAX / mantissa
+    } else {
+        (1u128 << shift) / mantissa
+    })
+}
+
+/// Merkle root reached from `txid` at position `index` through `branch`,
+/// the sibling hashes from the leaf up.
+pub fn merkle_root(txid: &[u8; 32], branch: &[[u8; 32]], index: u32) -> [u8; 32] {
+    let mut node = *txid;
+    let mut index = index;
+    for sibling in branch {
+        let mut pair = [0u8; 64];
+        if index & 1 == 0 {
+            pair[..32].copy_from_slice(&node);
+            pair[32..].copy_from_slice(sibling);
+        } else {
+            pair[..32].copy_from_slice(sibling);
+            pair[32..].copy_from_slice(&node);
+        }
+        node = double_sha256(&pair);
+        index >>= 1;
+    }
+    node
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxOut {
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transaction {
+    /// Double SHA-256 of the transaction without its witness data
+    pub txid: [u8; 32],
+    pub outputs: Vec<TxOut>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .at
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| anyhow!("Truncated Bitcoin transaction"))?;
+        let bytes = &self.data[self.at..end];
+        self.at = end;
+        Ok(bytes)
+    }
+
+    fn varint(&mut self) -> Result<u64> {
+        let n = self.take(1)?[0];
+        let width = match n {
+            0xfd => 2,
+            0xfe => 4,
+            0xff => 8,
+            _ => return Ok(n as u64),
+        };
+        let mut bytes = [0u8; 8];
+        bytes[..width].copy_from_slice(self.take(width)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    /// A length-prefixed byte string.
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.varint()?;
+        self.take(usize::try_from(len)?)
+    }
+}
+
+impl Transaction {
+    /// Parse a serialized transaction, with or without segwit data.
+    pub fn parse(raw: &[u8]) -> Result<Self> {
+        let mut r = Reader { data: raw, at: 0 };
+        let version = r.take(4)?;
+        let segwit = raw.get(4..6) == Some(&[0x00, 0x01][..]);
+        if segwit {
+            r.take(2)?;
+        }
+        let body_start = r.at;
+        let inputs = r.varint()?;
+        for _ in 0..inputs {
+            r.take(36)?; // previous output
+            r.bytes()?; // script_sig
+            r.take(4)?; // sequence
+        }
+        let count = r.varint()?;
+        let mut outputs = Vec::new();
+        for _ in 0..count {
+            let value = u64::from_le_bytes(r.take(8)?.try_into().expect("8 bytes"));
+            let script_pubkey = r.bytes()?.to_vec();
+            outputs.push(TxOut {
+                value,
+                script_pubkey,
+            });
+        }
+        let body_end = r.at;
+        if segwit {
+            for _ in 0..inputs {
+                for _ in 0..r.varint()? {
+                    r.bytes()?;
+                }
+            }
+        }
+        let lock_time = r.take(4)?;
+        if r.at != raw.len() {
+            bail!("Trailing bytes after Bitcoin transaction");
+        }
+        let mut stripped = Vec::with_capacity(raw.len());
+        stripped.extend_from_slice(version);
+        stripped.extend_from_slice(&raw[body_start..body_end]);
+        stripped.extend_from_slice(lock_time);
+        Ok(Transaction {
+            txid: double_sha256(&stripped),
+            outputs,
+        })
+    }
+
+    /// Amount paid to `deposit_script` and the Lumina account named by the
+    /// transaction's `OP_RETURN <32 bytes>` output.
+    pub fn deposit(&self, deposit_script: &[u8]) -> Result<(u64, [u8; 32])> {
+        let mut amount = 0u64;
+        for out in self
+            .outputs
+            .iter()
+            .filter(|o| o.script_pubkey == deposit_script)
+        {
+            amount = amount
+                .checked_add(out.value)
+                .ok_or_else(|| anyhow!("Deposit amount overflow"))?;
+        }
+        if amount == 0 {
+            bail!("Transaction pays nothing to the deposit script");
+        }
+        let recipient = self
+            .outputs
+            .iter()
+            .find_map(|o| match o.script_pubkey.as_slice() {
+                [OP_RETURN, 0x20, rest @ ..] if rest.len() == 32 => {
+                    Some(<[u8; 32]>::try_from(rest).expect("32 bytes"))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| anyhow!("Deposit names no recipient"))?;
+        Ok((amount, recipient))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The Bitcoin genesis block header.
+    const GENESIS: &str = "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c";
+
+    #[test]
+    fn genesis_header_hashes_and_meets_its_target() {
+        let header = BlockHeader::parse(&hex::decode(GENESIS).unwrap()).unwrap();
+        let mut hash = header.hash;
+        hash.reverse();
+        assert_eq!(
+            hex::encode(hash),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        assert_eq!(header.bits, 0x1d00ffff);
+        header.check_pow().unwrap();
+        assert_eq!(work(0x1d00ffff).unwrap(), 0x1_0001_0001);
+
+        let mut tampered = hex::decode(GENESIS).unwrap();
+        tampered[76] ^= 1;
+        assert!(BlockHeader::parse(&tampered).unwrap().check_pow().is_err());
+    }
+
+    #[test]
+    fn merkle_branch_reaches_root_from_either_side() {
+        let (a, b) = ([1u8; 32], [2u8; 32]);
+        let root = double_sha256(&[a, b].concat());
+        assert_eq!(merkle_root(&a, &[b], 0), root);
+        assert_eq!(merkle_root(&b, &[a], 1), root);
+        assert_ne!(merkle_root(&a, &[b], 1), root);
+    }
+
+    #[test]
+    fn segwit_txid_ignores_witness_and_deposit_names_recipient() {
+        let script = vec![
+            0x00, 0x14, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7, 7,
+        ];
+        let recipient = [9u8; 32];
+        let mut outputs = Vec::new();
+        outputs.push(2u8);
+        outputs.extend_from_slice(&50_000u64.to_le_bytes());
+        outputs.push(script.len() as u8);
+        outputs.extend_from_slice(&script);
+        outputs.extend_from_slice(&0u64.to_le_bytes());
+        outputs.extend_from_slice(&[34, OP_RETURN, 0x20]);
+        outputs.extend_from_slice(&recipient);
+        let input = [&[1u8][..], &[0u8; 36], &[0], &[0xff; 4]].concat();
+
+        let legacy = [&2u32.to_le_bytes()[..], &input, &outputs, &[0u8; 4]].concat();
+        let witness = [1u8, 2, 0xaa, 0xbb];
+        let segwit = [
+            &2u32.to_le_bytes()[..],
+            &[0x00, 0x01],
+            &input,
+            &outputs,
+            &witness,
+            &[0u8; 4],
+        ]
+        .concat();
+        let tx = Transaction::parse(&segwit).unwrap();
+        assert_eq!(tx.txid, Transaction::parse(&legacy).unwrap().txid);
+        assert_eq!(tx.txid, double_sha256(&legacy));
+        assert_eq!(tx.deposit(&script).unwrap(), (50_000, recipient));
+        assert!(tx.deposit(&[0x51]).is_err());
+        assert!(Transaction::parse(&segwit[..segwit.len() - 1]).is_err());
+    }
+}
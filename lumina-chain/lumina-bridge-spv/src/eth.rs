@@ -0,0 +1,355 @@
+//! Ethereum block headers, receipt Merkle-Patricia proofs and the bridge
+//! contract's deposit logs.
+
+use anyhow::{anyhow, bail, Result};
+use tiny_keccak::{Hasher, Keccak};
+
+/// Signature of the log the bridge contract emits per deposit, with the
+/// Lumina recipient indexed and the amount (in the contract's units) as
+/// data.
+pub const DEPOSIT_EVENT: &[u8] = b"Deposit(bytes32,uint256)";
+
+/// Deepest list nesting decoded; headers, trie nodes and receipts need
+/// three levels, and a bound keeps crafted input from exhausting the stack.
+const MAX_RLP_DEPTH: usize = 8;
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(data);
+    let mut out = [0u8; 32];
+    hasher.finalize(&mut out);
+    out
+}
+
+/// A decoded RLP item, keeping its encoding for hashing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rlp<'a> {
+    pub raw: &'a [u8],
+    pub value: RlpValue<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RlpValue<'a> {
+    Bytes(&'a [u8]),
+    List(Vec<Rlp<'a>>),
+}
+
+impl<'a> Rlp<'a> {
+    /// Decode exactly one item spanning all of `data`.
+    pub fn decode(data: &'a [u8]) -> Result<Self> {
+        let (item, rest) = Self::decode_prefix(data, 0)?;
+        if !rest.is_empty() {
+            bail!("Trailing bytes after RLP item");
+        }
+        Ok(item)
+    }
+
+    fn decode_prefix(data: &'a [u8], depth: usize) -> Result<(Self, &'a [u8])> {
+        let truncated = || anyhow!("Truncated RLP item");
+        let prefix = *data.first().ok_or_else(truncated)?;
+        let (offset, len, list) = match prefix {
+            0x00..=0x7f => (0, 1, false),
+            0x80..=0xb7 => (1, (prefix - 0x80) as usize, false),
+            0xc0..=0xf7 => (1, (prefix - 0xc0) as usize, true),
+            _ => {
+                let (width, list) = if prefix <= 0xbf {
+                    ((prefix - 0xb7) as usize, false)
+                } else {
+                    ((prefix - 0xf7) as usize, true)
+                };
+                let bytes = data.get(1..1 + width).ok_or_else(truncated)?;
+                if width > 8 || bytes[0] == 0 {
+                    bail!("Invalid RLP length");
+                }
+                let len = bytes.iter().fold(0u64, |n, b| (n << 8) | *b as u64);
+                (1 + width, usize::try_from(len)?, list)
+            }
+        };
+        let end = offset.checked_add(len).ok_or_else(truncated)?;
+        let payload = data.get(offset..end).ok_or_else(truncated)?;
+        let raw = &data[..end];
+        let value = if list {
+            if depth == MAX_RLP_DEPTH {
+                bail!("RLP nested too deeply");
+            }
+            let mut items = Vec::new();
+            let mut rest = payload;
+            while !rest.is_empty() {
+                let (item, tail) = Self::decode_prefix(rest, depth + 1)?;
+                items.push(item);
+                rest = tail;
+            }
+            RlpValue::List(items)
+        } else {
+            RlpValue::Bytes(payload)
+        };
+        Ok((Rlp { raw, value }, &data[end..]))
+    }
+
+    pub fn bytes(&self) -> Result<&'a [u8]> {
+        match self.value {
+            RlpValue::Bytes(bytes) => Ok(bytes),
+            RlpValue::List(_) => bail!("Expected RLP bytes, found a list"),
+        }
+    }
+
+    pub fn list(&self) -> Result<&[Rlp<'a>]> {
+        match &self.value {
+            RlpValue::List(items) => Ok(items),
+            RlpValue::Bytes(_) => bail!("Expected RLP list, found bytes"),
+        }
+    }
+
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        self.bytes()?
+            .try_into()
+            .map_err(|_| anyhow!("Expected a 32-byte hash"))
+    }
+
+    pub fn uint(&self) -> Result<u64> {
+        let bytes = self.bytes()?;
+        if bytes.len() > 8 {
+            bail!("Integer exceeds 64 bits");
+        }
+        Ok(bytes.iter().fold(0u64, |n, b| (n << 8) | *b as u64))
+    }
+}
+
+/// RLP encoding of a non-negative integer, the key of a receipt in its
+/// block's receipt trie.
+pub fn encode_uint(n: u64) -> Vec<u8> {
+    let bytes = n.to_be_bytes();
+    let bytes = &bytes[bytes.iter().take_while(|b| **b == 0).count()..];
+    match bytes {
+        [] => vec![0x80],
+        [b] if *b < 0x80 => vec![*b],
+        _ => [&[0x80 + bytes.len() as u8][..], bytes].concat(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    /// Keccak-256 of the RLP-encoded header
+    pub hash: [u8; 32],
+    pub parent_hash: [u8; 32],
+    pub number: u64,
+    pub receipts_root: [u8; 32],
+}
+
+impl BlockHeader {
+    pub fn parse(raw: &[u8]) -> Result<Self> {
+        let rlp = Rlp::decode(raw)?;
+        let fields = rlp.list()?;
+        if fields.len() < 15 {
+            bail!("Ethereum header has too few fields");
+        }
+        Ok(BlockHeader {
+            hash: keccak256(raw),
+            parent_hash: fields[0].hash()?,
+            receipts_root: fields[5].hash()?,
+            number: fields[8].uint()?,
+        })
+    }
+}
+
+/// Value stored under `key` in the Merkle-Patricia trie with root `root`,
+/// given the trie nodes on the path to it, root first.
+pub fn verify_proof(root: &[u8; 32], key: &[u8], proof: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let nibbles: Vec<u8> = key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect();
+    let mut at = 0;
+    let mut nodes = proof.iter();
+    let mut next_hash = Some(*root);
+    let mut inline: Option<&[u8]> = None;
+    loop {
+        let raw: &[u8] = match (next_hash.take(), inline.take()) {
+            (Some(hash), _) => {
+                let node = nodes
+                    .next()
+                    .ok_or_else(|| anyhow!("Proof is missing a node"))?;
+                if keccak256(node) != hash {
+                    bail!("Proof node does not match its hash");
+                }
+                node
+            }
+            (None, Some(raw)) => raw,
+            (None, None) => unreachable!("a child reference is always set"),
+        };
+        let node = Rlp::decode(raw)?;
+        let items = node.list()?;
+        let child = match items.len() {
+            17 => {
+                let Some(nibble) = nibbles.get(at) else {
+                    let value = items[16].bytes()?;
+                    if value.is_empty() {
+                        bail!("Key is not in the trie");
+                    }
+                    return Ok(value.to_vec());
+                };
+                at += 1;
+                &items[*nibble as usize]
+            }
+            2 => {
+                let (path, leaf) = compact_path(items[0].bytes()?)?;
+                if !nibbles[at..].starts_with(&path) {
+                    bail!("Key is not in the trie");
+                }
+                at += path.len();
+                if leaf {
+                    if at != nibbles.len() {
+                        bail!("Key is not in the trie");
+                    }
+                    return Ok(items[1].bytes()?.to_vec());
+                }
+                &items[1]
+            }
+            _ => bail!("Invalid trie node"),
+        };
+        match &child.value {
+            RlpValue::Bytes([]) => bail!("Key is not in the trie"),
+            RlpValue::Bytes(_) => next_hash = Some(child.hash()?),
+            RlpValue::List(_) => inline = Some(child.raw),
+        }
+    }
+}
+
+/// Nibbles of a hex-prefix encoded path and whether it ends in a leaf.
+fn compact_path(encoded: &[u8]) -> Result<(Vec<u8>, bool)> {
+    let first = *encoded.first().ok_or_else(|| anyhow!("Empty trie path"))?;
+    let flag = first >> 4;
+    if flag > 3 {
+        bail!("Invalid trie path prefix");
+    }
+    let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+    if flag & 1 == 1 {
+        nibbles.push(first & 0x0f);
+    }
+    nibbles.extend(encoded[1..].iter().flat_map(|b| [b >> 4, b & 0x0f]));
+    Ok((nibbles, flag >= 2))
+}
+
+/// Amount and Lumina recipient of the first deposit log `bridge` emitted
+/// in a successful receipt (typed or legacy encoding).
+pub fn receipt_deposit(receipt: &[u8], bridge: &[u8; 20]) -> Result<(u64, [u8; 32])> {
+    let body = match receipt.first() {
+        Some(kind) if *kind < 0x80 => &receipt[1..],
+        _ => receipt,
+    };
+    let rlp = Rlp::decode(body)?;
+    let fields = rlp.list()?;
+    if fields.len() != 4 {
+        bail!("Invalid receipt");
+    }
+    if fields[0].bytes()? != [1] {
+        bail!("Deposit transaction failed");
+    }
+    let topic = keccak256(DEPOSIT_EVENT);
+    for log in fields[3].list()? {
+        let [address, topics, data] = log.list()? else {
+            bail!("Invalid receipt log");
+        };
+        let topics = topics.list()?;
+        if address.bytes()? != bridge || topics.len() != 2 || topics[0].hash()? != topic {
+            continue;
+        }
+        let data = data.bytes()?;
+        if data.len() != 32 {
+            bail!("Invalid deposit log data");
+        }
+        if data[..24].iter().any(|b| *b != 0) {
+            bail!("Deposit amount exceeds 64 bits");
+        }
+        let amount = u64::from_be_bytes(data[24..].try_into().expect("8 bytes"));
+        if amount == 0 {
+            bail!("Deposit amount is zero");
+        }
+        return Ok((amount, topics[1].hash()?));
+    }
+    bail!("Receipt has no deposit from the bridge contract")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(b: &[u8]) -> Vec<u8> {
+        match b {
+            [x] if *x < 0x80 => vec![*x],
+            _ if b.len() < 56 => [&[0x80 + b.len() as u8][..], b].concat(),
+            _ => [&[0xb8, b.len() as u8][..], b].concat(),
+        }
+    }
+
+    fn list(items: &[Vec<u8>]) -> Vec<u8> {
+        let payload = items.concat();
+        if payload.len() < 56 {
+            [&[0xc0 + payload.len() as u8][..], &payload].concat()
+        } else {
+            [&[0xf8, payload.len() as u8][..], &payload].concat()
+        }
+    }
+
+    fn receipt(bridge: &[u8; 20], recipient: &[u8; 32], amount: u64) -> Vec<u8> {
+        let mut data = [0u8; 32];
+        data[24..].copy_from_slice(&amount.to_be_bytes());
+        let log = list(&[
+            bytes(bridge),
+            list(&[bytes(&keccak256(DEPOSIT_EVENT)), bytes(recipient)]),
+            bytes(&data),
+        ]);
+        let body = list(&[bytes(&[1]), bytes(&[0x52, 0x08]), bytes(&[]), list(&[log])]);
+        [&[0x02][..], &body].concat()
+    }
+
+    #[test]
+    fn rlp_round_trips_integers_and_rejects_truncation() {
+        for n in [0u64, 1, 0x7f, 0x80, 0x1234, u64::MAX] {
+            assert_eq!(Rlp::decode(&encode_uint(n)).unwrap().uint().unwrap(), n);
+        }
+        assert!(Rlp::decode(&[0x83, 1, 2]).is_err());
+        assert!(Rlp::decode(&[0xc2, 0x01]).is_err());
+        let nested: Vec<u8> = (1..=10).rev().map(|n| 0xc0 + n as u8 - 1).collect();
+        assert!(Rlp::decode(&nested).is_err());
+    }
+
+    #[test]
+    fn receipt_proof_through_a_branch_node_yields_the_deposit() {
+        let bridge = [0xb1u8; 20];
+        let (alice, bob) = ([0xa1u8; 32], [0xb0u8; 32]);
+        let first = receipt(&bridge, &alice, 1_000);
+        let second = receipt(&bridge, &bob, 2_000);
+        // Keys rlp(0) = 0x80 and rlp(1) = 0x01 split on their first nibble,
+        // leaving one nibble of path in each leaf.
+        let leaf0 = list(&[bytes(&[0x30]), bytes(&first)]);
+        let leaf1 = list(&[bytes(&[0x31]), bytes(&second)]);
+        let mut children: Vec<Vec<u8>> = vec![bytes(&[]); 17];
+        children[8] = bytes(&keccak256(&leaf0));
+        children[0] = bytes(&keccak256(&leaf1));
+        let branch = list(&children);
+        let root = keccak256(&branch);
+
+        let proof = vec![branch.clone(), leaf1.clone()];
+        let value = verify_proof(&root, &encode_uint(1), &proof).unwrap();
+        assert_eq!(receipt_deposit(&value, &bridge).unwrap(), (2_000, bob));
+        let value = verify_proof(&root, &encode_uint(0), &[branch.clone(), leaf0]).unwrap();
+        assert_eq!(receipt_deposit(&value, &bridge).unwrap(), (1_000, alice));
+
+        assert!(verify_proof(&root, &encode_uint(0), &proof).is_err());
+        assert!(verify_proof(&root, &encode_uint(2), &proof).is_err());
+        assert!(receipt_deposit(&value, &[0u8; 20]).is_err());
+    }
+
+    #[test]
+    fn header_fields_come_from_their_rlp_positions() {
+        let mut fields: Vec<Vec<u8>> = (0..15).map(|_| bytes(&[])).collect();
+        fields[0] = bytes(&[0x11; 32]);
+        fields[5] = bytes(&[0x55; 32]);
+        fields[8] = bytes(&[0x01, 0x00]);
+        let raw = list(&fields);
+        let header = BlockHeader::parse(&raw).unwrap();
+        assert_eq!(header.hash, keccak256(&raw));
+        assert_eq!(
+            (header.parent_hash, header.receipts_root, header.number),
+            ([0x11; 32], [0x55; 32], 256)
+        );
+    }
+}
@@ -0,0 +1,11 @@
+//! Light (SPV) verification of deposits made on other chains, so bridged
+//! assets can be minted from proofs rather than a custodian's word.
+//!
+//! Bitcoin deposits are checked against 80-byte block headers, whose proof
+//! of work is verified here, and a transaction Merkle branch. Ethereum
+//! deposits are checked against a block header's receipts root with a
+//! Merkle-Patricia proof of the deposit's receipt. Everything works on raw
+//! consensus encodings; which headers to trust is up to the caller.
+
+pub mod btc;
+pub mod eth;
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Labeled addresses kept as `contacts.json` next to the wallet file.
+pub struct AddressBook {
+    path: PathBuf,
+    /// Name to hex address, without `0x`
+    contacts: BTreeMap<String, String>,
+}
+
+/// An address argument after contact lookup.
+pub struct Resolved {
+    pub address: String,
+    /// The contact name, when the argument was one
+    pub contact: Option<String>,
+}
+
+fn parse_address(input: &str) -> Option<String> {
+    let hex = input.trim_start_matches("0x").to_lowercase();
+    (hex.len() == 64 && hex.bytes().all(|b| b.is_ascii_hexdigit())).then_some(hex)
+}
+
+impl AddressBook {
+    pub fn load(wallet_path: &Path) -> Result<Self> {
+        let path = wallet_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join("contacts.json");
+        let contacts = match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map_err(|e| anyhow!("Invalid {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, contacts })
+    }
+
+    fn save(&self) -> Result<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(&self.contacts)?)?;
+        Ok(())
+    }
+
+    pub fn contacts(&self) -> &BTreeMap<String, String> {
+        &self.contacts
+    }
+
+    /// Add or replace `name`. Names that parse as addresses are refused so
+    /// an address argument is never shadowed by a contact.
+    pub fn add(&mut self, name: &str, address: &str) -> Result<String> {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(anyhow!(
+                "Contact names may only use letters, digits, '-' and '_'"
+            ));
+        }
+        if parse_address(name).is_some() {
+            return Err(anyhow!("Contact name {} looks like an address", name));
+        }
+        let address = parse_address(address)
+            .ok_or_else(|| anyhow!("Address must be 32 bytes of hex: {}", address))?;
+        self.contacts.insert(name.to_string(), address.clone());
+        self.save()?;
+        Ok(address)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<String> {
+        let address = self
+            .contacts
+            .remove(name)
+            .ok_or_else(|| anyhow!("No contact named {}", name))?;
+        self.save()?;
+        Ok(address)
+    }
+
+    /// Accept a hex address or a contact name.
+    pub fn resolve(&self, input: &str) -> Result<Resolved> {
+        if let Some(address) = parse_address(input) {
+            return Ok(Resolved {
+                address,
+                contact: None,
+            });
+        }
+        let address = self
+            .contacts
+            .get(input)
+            .ok_or_else(|| anyhow!("{} is neither an address nor a contact", input))?;
+        Ok(Resolved {
+            address: address.clone(),
+            contact: Some(input.to_string()),
+        })
+    }
+}
@@ -1,14 +1,26 @@
 use anyhow::{anyhow, Result};
 use clap::{Parser, Subcommand};
+use contacts::AddressBook;
 use ed25519_dalek::SigningKey;
 use lumina_crypto::signatures::{generate_keypair, sign};
-use lumina_crypto::zk::ZkManager;
+use lumina_crypto::view_key::{derive_view_secret, open_note, view_public_key};
+use lumina_crypto::zk::{open_sealed_valuation, ZkManager};
+use lumina_types::audit::AuditRecord;
 use lumina_types::instruction::{AssetType, StablecoinInstruction};
+use lumina_types::payment::{self, PaymentRequest};
 use lumina_types::transaction::Transaction;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+mod contacts;
+mod payroll;
+mod watch;
 
 #[derive(Parser)]
 #[command(
@@ -19,10 +31,77 @@ use std::path::PathBuf;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
-    #[arg(short, long, default_value = "http://localhost:3000")]
-    node_url: String,
+    /// Defaults to the profile's node URL, then http://localhost:3000
+    #[arg(short, long, global = true)]
+    node_url: Option<String>,
     #[arg(short, long, default_value = "wallet.json")]
     wallet_path: PathBuf,
+    /// Use the named wallet kept under `profiles/` next to the wallet path
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// `json` prints one JSON object per command, errors included
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    output: OutputFormat,
+    /// After submitting a transaction, poll until it is committed
+    #[arg(long, global = true)]
+    wait: bool,
+    /// Seconds `--wait` polls before giving up
+    #[arg(long, default_value_t = 60, global = true)]
+    wait_timeout: u64,
+    /// Skip confirmation prompts
+    #[arg(long, global = true)]
+    yes: bool,
+}
+
+#[derive(Subcommand)]
+enum WalletCommand {
+    /// List the default wallet and every profile with its key and node
+    List,
+}
+
+#[derive(Subcommand)]
+enum ContactsCommand {
+    /// Save an address under a name, replacing any previous one
+    Add { name: String, address: String },
+    /// List saved contacts
+    List,
+    /// Forget a contact
+    Remove { name: String },
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// Exit status for scripts: 1 for request, API and usage errors.
+const EXIT_ERROR: u8 = 1;
+/// The transaction was committed but failed to execute.
+const EXIT_TX_FAILED: u8 = 2;
+/// `--wait` gave up before the transaction was committed.
+const EXIT_WAIT_TIMEOUT: u8 = 3;
+
+/// An error with its own exit status, and the result so far for JSON output.
+#[derive(Debug)]
+struct Failure {
+    code: u8,
+    message: String,
+    result: serde_json::Value,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+/// A command's result for each output format.
+struct Output {
+    json: serde_json::Value,
+    text: String,
 }
 
 #[derive(Subcommand)]
@@ -40,6 +119,7 @@ enum Commands {
     },
     /// Transfer tokens
     Transfer {
+        /// Address or contact name
         #[arg(long)]
         to: String,
         #[arg(long)]
@@ -49,6 +129,7 @@ enum Commands {
     },
     /// Get account balance
     Balance {
+        /// Address or contact name
         #[arg(long)]
         address: String,
     },
@@ -63,21 +144,143 @@ enum Commands {
     Insurance,
     /// Query validators
     Validators,
+    /// Export the audit log as JSON lines, verifying its hash chain
+    AuditExport {
+        #[arg(long, default_value_t = 1)]
+        from: u64,
+        #[arg(long)]
+        to: Option<u64>,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Reveal the valuation of a confidential RWA listing with its view key
+    RwaDisclose {
+        id: u64,
+        /// Hex-encoded 32-byte view key shared by the listing owner
+        #[arg(long)]
+        view_key: String,
+    },
+    /// Derive the wallet's view key, optionally registering it on-chain
+    ViewKey {
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+        #[arg(long)]
+        register: bool,
+    },
+    /// Decrypt an account's confidential balance with a view key
+    ViewDecrypt {
+        /// Address or contact name
+        address: String,
+        /// Hex-encoded view secret shared by the account holder
+        #[arg(long)]
+        view_key: String,
+    },
+    /// Manage wallet profiles
+    Wallet {
+        #[command(subcommand)]
+        action: WalletCommand,
+    },
+    /// Manage named addresses, usable wherever an address is expected
+    Contacts {
+        #[command(subcommand)]
+        action: ContactsCommand,
+    },
+    /// Live dashboard of chain height, TPS, health and recent blocks
+    Watch {
+        /// Seconds between refreshes
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+        /// Recent blocks to list
+        #[arg(long, default_value_t = 10)]
+        blocks: usize,
+    },
+    /// Request devnet funds, solving the faucet's proof-of-work challenge
+    Faucet {
+        /// Address or contact name; defaults to the wallet
+        #[arg(long)]
+        address: Option<String>,
+    },
+    /// Print a payment request URI, to show as a QR code at the point of sale
+    PayRequest {
+        #[arg(long)]
+        amount: u64,
+        #[arg(long, default_value = "lusd")]
+        asset: String,
+        /// Reference the payer's transfer carries as a hash
+        #[arg(long)]
+        memo: Option<String>,
+        /// Seconds until wallets should refuse the request
+        #[arg(long)]
+        expires_in: Option<u64>,
+        /// Address or contact name to be paid; defaults to the wallet
+        #[arg(long)]
+        address: Option<String>,
+    },
+    /// Pay a payment request URI, tagging the transfer with its memo hash
+    Pay { uri: String },
+    /// Pay LUSD to every `recipient,amount` row of a CSV in one transaction
+    Payroll {
+        file: PathBuf,
+        /// Check the file and show the total without submitting
+        #[arg(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Serialize, Deserialize)]
 struct Wallet {
     secret_key: String,
     public_key: String,
+    /// Node used when `--node-url` is not given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    node_url: Option<String>,
+}
+
+const DEFAULT_NODE_URL: &str = "http://localhost:3000";
+
+impl Cli {
+    fn profiles_dir(&self) -> PathBuf {
+        self.wallet_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join("profiles")
+    }
+
+    /// The `--profile` wallet, or `--wallet-path` without one.
+    fn wallet_file(&self) -> Result<PathBuf> {
+        let Some(name) = &self.profile else {
+            return Ok(self.wallet_path.clone());
+        };
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(anyhow!(
+                "Profile names may only use letters, digits, '-' and '_'"
+            ));
+        }
+        Ok(self.profiles_dir().join(format!("{}.json", name)))
+    }
+
+    fn node_url(&self) -> String {
+        self.node_url
+            .clone()
+            .or_else(|| {
+                let wallet = Wallet::load(&self.wallet_file().ok()?).ok()?;
+                wallet.node_url
+            })
+            .unwrap_or_else(|| DEFAULT_NODE_URL.to_string())
+    }
 }
 
 impl Wallet {
-    fn load(path: &PathBuf) -> Result<Self> {
+    fn load(path: &Path) -> Result<Self> {
         let content = fs::read_to_string(path)?;
         Ok(serde_json::from_str(&content)?)
     }
 
-    fn save(&self, path: &PathBuf) -> Result<()> {
+    fn save(&self, path: &Path) -> Result<()> {
         let content = serde_json::to_string_pretty(self)?;
         fs::write(path, content)?;
         Ok(())
@@ -90,28 +293,218 @@ impl Wallet {
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let cli = Cli::parse();
+    match run(&cli).await {
+        Ok(output) => {
+            match cli.output {
+                OutputFormat::Text => println!("{}", output.text),
+                OutputFormat::Json => println!("{}", output.json),
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            let failure = e.downcast_ref::<Failure>();
+            let code = failure.map_or(EXIT_ERROR, |f| f.code);
+            match cli.output {
+                OutputFormat::Text => eprintln!("Error: {:#}", e),
+                OutputFormat::Json => {
+                    let mut out = match failure.map(|f| &f.result) {
+                        Some(serde_json::Value::Object(result)) => result.clone(),
+                        _ => serde_json::Map::new(),
+                    };
+                    out.insert("error".to_string(), format!("{:#}", e).into());
+                    out.insert("exit_code".to_string(), code.into());
+                    println!("{}", serde_json::Value::Object(out));
+                }
+            }
+            ExitCode::from(code)
+        }
+    }
+}
+
+/// GET `url`, failing on transport errors and `{"error": ...}` bodies.
+async fn get_json(client: &Client, url: String) -> Result<serde_json::Value> {
+    let value: serde_json::Value = client.get(url).send().await?.json().await?;
+    if let Some(e) = value.get("error") {
+        return Err(anyhow!(
+            "API error: {}",
+            e.as_str().unwrap_or(&e.to_string())
+        ));
+    }
+    Ok(value)
+}
+
+/// A queried document, printed under `label` in text mode.
+fn document(label: &str, value: serde_json::Value) -> Result<Output> {
+    Ok(Output {
+        text: format!("{}:\n{}", label, serde_json::to_string_pretty(&value)?),
+        json: value,
+    })
+}
+
+fn parse_asset(asset: &str) -> Result<AssetType> {
+    match asset.to_lowercase().as_str() {
+        "lusd" => Ok(AssetType::LUSD),
+        "ljun" => Ok(AssetType::LJUN),
+        "lumina" => Ok(AssetType::Lumina),
+        _ => Err(anyhow!("Invalid asset. Use: lusd, ljun, or lumina")),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Most leading zero bits `faucet` will grind for.
+const MAX_FAUCET_DIFFICULTY: u32 = 32;
+
+/// Submit a signed transaction and, with `--wait`, poll until it commits.
+/// Ask on stderr whether to go ahead; anything but `y`/`yes` declines.
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{} [y/N] ", prompt);
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// With `--profile` or when sending to a contact, show what is about to be
+/// signed, and with a profile its name and balances, then ask to proceed.
+async fn confirm_signing(
+    client: &Client,
+    cli: &Cli,
+    node_url: &str,
+    wallet: &Wallet,
+    action: &str,
+    to_contact: bool,
+) -> Result<()> {
+    if cli.yes || (cli.profile.is_none() && !to_contact) {
+        return Ok(());
+    }
+    let mut prompt = action.to_string();
+    if let Some(profile) = &cli.profile {
+        let url = format!("{}/account/{}", node_url, wallet.public_key);
+        let balances = match get_json(client, url).await {
+            Ok(account) => format!(
+                "{} LUSD, {} LJUN, {} LUMINA",
+                account["lusd_balance"], account["ljun_balance"], account["lumina_balance"]
+            ),
+            Err(e) => format!("balance unavailable ({:#})", e),
+        };
+        prompt = format!(
+            "{}\nSigning as profile {} (0x{}): {}\nProceed?",
+            prompt, profile, wallet.public_key, balances
+        );
+    } else {
+        prompt.push('?');
+    }
+    if !confirm(&prompt)? {
+        return Err(anyhow!("Cancelled"));
+    }
+    Ok(())
+}
+
+async fn submit(client: &Client, node_url: &str, cli: &Cli, tx: &Transaction) -> Result<Output> {
+    let res: serde_json::Value = client
+        .post(format!("{}/tx", node_url))
+        .json(tx)
+        .send()
+        .await?
+        .json()
+        .await?;
+    if res["status"] != "submitted" {
+        let reason = res["error"].as_str().unwrap_or("unexpected response");
+        return Err(anyhow!("Submission failed: {}", reason));
+    }
+    let tx_id = hex::encode(tx.id());
+    let submitted = serde_json::json!({ "tx_id": tx_id, "status": "submitted" });
+    if !cli.wait {
+        return Ok(Output {
+            text: format!("Submitted tx {}", tx_id),
+            json: submitted,
+        });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(cli.wait_timeout);
+    loop {
+        let status = get_json(client, format!("{}/tx/{}", node_url, tx_id)).await?;
+        if status["status"] == "committed" {
+            let height = &status["height"];
+            if status["success"] == false {
+                return Err(Failure {
+                    code: EXIT_TX_FAILED,
+                    message: format!("Tx {} failed at height {}", tx_id, height),
+                    result: status,
+                }
+                .into());
+            }
+            return Ok(Output {
+                text: format!("Committed tx {} at height {}", tx_id, height),
+                json: status,
+            });
+        }
+        if Instant::now() >= deadline {
+            return Err(Failure {
+                code: EXIT_WAIT_TIMEOUT,
+                message: format!("Tx {} not committed after {}s", tx_id, cli.wait_timeout),
+                result: submitted,
+            }
+            .into());
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+async fn run(cli: &Cli) -> Result<Output> {
     let client = Client::new();
+    let node_url = &cli.node_url();
+    let wallet_file = cli.wallet_file()?;
 
-    match &cli.command {
+    let output = match &cli.command {
         Commands::Init => {
             let kp = generate_keypair();
             let wallet = Wallet {
                 secret_key: hex::encode(kp.to_bytes()),
                 public_key: hex::encode(kp.verifying_key().as_bytes()),
+                node_url: cli.node_url.clone(),
             };
-            wallet.save(&cli.wallet_path)?;
-            println!("Wallet initialized at {:?}", cli.wallet_path);
-            println!("Public Key: {}", wallet.public_key);
+            if cli.profile.is_some() {
+                fs::create_dir_all(cli.profiles_dir())?;
+            }
+            wallet.save(&wallet_file)?;
+            Output {
+                text: format!(
+                    "Wallet initialized at {:?}\nPublic Key: {}",
+                    wallet_file, wallet.public_key
+                ),
+                json: serde_json::json!({
+                    "wallet": wallet_file,
+                    "profile": cli.profile,
+                    "public_key": wallet.public_key,
+                    "node_url": wallet.node_url,
+                }),
+            }
         }
         Commands::Show => {
-            let wallet = Wallet::load(&cli.wallet_path)?;
-            println!("Wallet: {:?}", cli.wallet_path);
-            println!("Public Key: {}", wallet.public_key);
+            let wallet = Wallet::load(&wallet_file)?;
+            Output {
+                text: format!(
+                    "Wallet: {:?}\nPublic Key: {}",
+                    wallet_file, wallet.public_key
+                ),
+                json: serde_json::json!({
+                    "wallet": wallet_file,
+                    "profile": cli.profile,
+                    "public_key": wallet.public_key,
+                    "node_url": wallet.node_url,
+                }),
+            }
         }
         Commands::Mint { amount, asset } => {
-            let wallet = Wallet::load(&cli.wallet_path)?;
+            let wallet = Wallet::load(&wallet_file)?;
             let kp = wallet.to_keypair()?;
             let sender = kp.verifying_key().to_bytes();
 
@@ -145,35 +538,38 @@ async fn main() -> Result<()> {
                 gas_price: 1,
             };
 
+            let action = format!("Mint {} {}", amount, asset);
+            confirm_signing(&client, cli, node_url, &wallet, &action, false).await?;
             tx.signature = sign(&kp, &tx.signing_bytes());
-
-            let res = client
-                .post(format!("{}/tx", cli.node_url))
-                .json(&tx)
-                .send()
-                .await?;
-
-            println!("Response: {}", res.text().await?);
+            submit(&client, node_url, cli, &tx).await?
         }
         Commands::Transfer { to, amount, asset } => {
-            let wallet = Wallet::load(&cli.wallet_path)?;
+            let wallet = Wallet::load(&wallet_file)?;
             let kp = wallet.to_keypair()?;
             let sender = kp.verifying_key().to_bytes();
 
-            let mut to_bytes = [0u8; 32];
-            hex::decode_to_slice(to.trim_start_matches("0x"), &mut to_bytes)?;
-
-            let asset_type = match asset.to_lowercase().as_str() {
-                "lusd" => AssetType::LUSD,
-                "ljun" => AssetType::LJUN,
-                "lumina" => AssetType::Lumina,
-                _ => return Err(anyhow!("Invalid asset. Use: lusd, ljun, or lumina")),
+            let to = AddressBook::load(&cli.wallet_path)?.resolve(to)?;
+            let recipient = match &to.contact {
+                Some(name) => format!("{} (0x{})", name, to.address),
+                None => format!("0x{}", to.address),
             };
+            let action = format!("Send {} {} to {}", amount, asset, recipient);
+            confirm_signing(
+                &client,
+                cli,
+                node_url,
+                &wallet,
+                &action,
+                to.contact.is_some(),
+            )
+            .await?;
+            let mut to_bytes = [0u8; 32];
+            hex::decode_to_slice(&to.address, &mut to_bytes)?;
 
             let instruction = StablecoinInstruction::Transfer {
                 to: to_bytes,
                 amount: *amount,
-                asset: asset_type,
+                asset: parse_asset(asset)?,
             };
 
             let mut tx = Transaction {
@@ -186,68 +582,472 @@ async fn main() -> Result<()> {
             };
 
             tx.signature = sign(&kp, &tx.signing_bytes());
-
-            let res = client
-                .post(format!("{}/tx", cli.node_url))
-                .json(&tx)
-                .send()
-                .await?;
-
-            println!("Response: {}", res.text().await?);
+            submit(&client, node_url, cli, &tx).await?
         }
         Commands::Balance { address } => {
-            let res = client
-                .get(format!("{}/account/{}", cli.node_url, address))
-                .send()
-                .await?
-                .text()
-                .await?;
-
-            println!("Account Info:\n{}", res);
+            let address = AddressBook::load(&cli.wallet_path)?
+                .resolve(address)?
+                .address;
+            document(
+                "Account Info",
+                get_json(&client, format!("{}/account/{}", node_url, address)).await?,
+            )?
         }
         Commands::Block { height } => {
-            let res = client
-                .get(format!("{}/block/{}", cli.node_url, height))
-                .send()
-                .await?;
-
-            if res.status().is_success() {
-                println!("Block Info: {}", res.text().await?);
-            } else {
-                println!("Block not found");
+            let block = get_json(&client, format!("{}/block/{}", node_url, height)).await?;
+            if block.is_null() {
+                return Err(anyhow!("Block {} not found", height));
             }
+            document("Block Info", block)?
         }
-        Commands::Health => {
-            let res = client
-                .get(format!("{}/health", cli.node_url))
-                .send()
-                .await?
-                .text()
-                .await?;
+        Commands::Health => document(
+            "Lumina Health Index",
+            get_json(&client, format!("{}/health", node_url)).await?,
+        )?,
+        Commands::Insurance => document(
+            "Insurance Fund",
+            get_json(&client, format!("{}/insurance", node_url)).await?,
+        )?,
+        Commands::Validators => document(
+            "Validators",
+            get_json(&client, format!("{}/validators", node_url)).await?,
+        )?,
+        Commands::AuditExport { from, to, out } => {
+            let (from, to) = (*from, *to);
+            let mut lines = String::new();
+            let mut head: Option<[u8; 32]> = None;
+            let mut count = 0u64;
+            let mut cursor = from;
+            loop {
+                let mut url = format!("{}/audit?from={}", node_url, cursor);
+                if let Some(to) = to {
+                    url.push_str(&format!("&to={}", to));
+                }
+                let page = get_json(&client, url)
+                    .await
+                    .map_err(|e| anyhow!("Audit query failed: {}", e))?;
+                let records: Vec<AuditRecord> = serde_json::from_value(page["records"].clone())?;
+                // A range starting at genesis must chain from the zero hash
+                let start = head.unwrap_or_else(|| match records.first() {
+                    Some(r) if from > 1 => r.prev_hash,
+                    _ => [0u8; 32],
+                });
+                head = Some(
+                    AuditRecord::verify_chain(start, &records)
+                        .map_err(|seq| anyhow!("Audit chain broken at entry {}", seq))?,
+                );
+                for record in &records {
+                    lines.push_str(&serde_json::to_string(record)?);
+                    lines.push('\n');
+                }
+                count += records.len() as u64;
 
-            println!("Lumina Health Index:\n{}", res);
+                let scanned_to = page["scanned_to"].as_u64().unwrap_or(cursor);
+                let end = to.unwrap_or(page["tip_height"].as_u64().unwrap_or(0) + 1);
+                if scanned_to >= end || scanned_to <= cursor {
+                    break;
+                }
+                cursor = scanned_to;
+            }
+            fs::write(out, lines)?;
+            let head = hex::encode(head.unwrap_or([0u8; 32]));
+            Output {
+                text: format!(
+                    "Exported {} audit entries to {} (head {})",
+                    count,
+                    out.display(),
+                    head
+                ),
+                json: serde_json::json!({ "exported": count, "out": out, "head": head }),
+            }
         }
-        Commands::Insurance => {
-            let res = client
-                .get(format!("{}/insurance", cli.node_url))
-                .send()
-                .await?
-                .text()
-                .await?;
+        Commands::RwaDisclose { id, view_key } => {
+            let view_key: [u8; 32] = hex::decode(view_key)?
+                .try_into()
+                .map_err(|_| anyhow!("View key must be 32 bytes"))?;
+            let url = format!("{}/rwa_listings?start={}&limit=1", node_url, id);
+            let page = get_json(&client, url)
+                .await
+                .map_err(|e| anyhow!("RWA query failed: {}", e))?;
+            let listing = &page["rwa_listings"][0];
+            if listing["id"].as_u64() != Some(*id) {
+                return Err(anyhow!("RWA listing {} not found", id));
+            }
+            let sealed = &listing["sealed_valuation"];
+            if sealed.is_null() {
+                return Err(anyhow!("RWA listing {} is not confidential", id));
+            }
+            let field = |name: &str| hex::decode(sealed[name].as_str().unwrap_or_default());
+            let commitment: [u8; 32] = field("commitment")?
+                .try_into()
+                .map_err(|_| anyhow!("Malformed commitment"))?;
+            let value = open_sealed_valuation(&view_key, &commitment, &field("sealed_opening")?)
+                .ok_or_else(|| anyhow!("View key does not open this valuation"))?;
+            Output {
+                text: format!(
+                    "RWA {} valuation: {} (proven minimum {})",
+                    id, value, listing["attested_value"]
+                ),
+                json: serde_json::json!({
+                    "id": id,
+                    "valuation": value,
+                    "attested_value": listing["attested_value"],
+                }),
+            }
+        }
+        Commands::ViewKey { index, register } => {
+            let wallet = Wallet::load(&wallet_file)?;
+            let kp = wallet.to_keypair()?;
+            let view_secret = derive_view_secret(&kp.to_bytes(), *index);
+            let view_pubkey = view_public_key(&view_secret);
+            let mut output = Output {
+                text: format!(
+                    "View Key (share with auditors): {}\nView Public Key: {}",
+                    hex::encode(view_secret),
+                    hex::encode(view_pubkey)
+                ),
+                json: serde_json::json!({
+                    "view_key": hex::encode(view_secret),
+                    "view_public_key": hex::encode(view_pubkey),
+                    "registration": null,
+                }),
+            };
 
-            println!("Insurance Fund:\n{}", res);
+            if *register {
+                let mut tx = Transaction {
+                    sender: kp.verifying_key().to_bytes(),
+                    nonce: 0,
+                    instruction: StablecoinInstruction::RegisterViewKey { view_pubkey },
+                    signature: vec![],
+                    gas_limit: 100_000,
+                    gas_price: 1,
+                };
+                let action = format!("Register view key 0x{}", hex::encode(view_pubkey));
+                confirm_signing(&client, cli, node_url, &wallet, &action, false).await?;
+                tx.signature = sign(&kp, &tx.signing_bytes());
+                let registration = submit(&client, node_url, cli, &tx).await?;
+                output.text = format!("{}\n{}", output.text, registration.text);
+                output.json["registration"] = registration.json;
+            }
+            output
+        }
+        Commands::ViewDecrypt { address, view_key } => {
+            let view_secret: [u8; 32] = hex::decode(view_key)?
+                .try_into()
+                .map_err(|_| anyhow!("View key must be 32 bytes"))?;
+            let address = AddressBook::load(&cli.wallet_path)?
+                .resolve(address)?
+                .address;
+            let account = get_json(&client, format!("{}/account/{}", node_url, address))
+                .await
+                .map_err(|e| anyhow!("Account query failed: {}", e))?;
+            let note = &account["confidential_note"];
+            if note.is_null() {
+                return Err(anyhow!("Account has no sealed confidential balance"));
+            }
+            let field = |name: &str| hex::decode(note[name].as_str().unwrap_or_default());
+            let commitment: [u8; 32] = field("commitment")?
+                .try_into()
+                .map_err(|_| anyhow!("Malformed commitment"))?;
+            let value = open_note(&view_secret, &commitment, &field("sealed_note")?)
+                .ok_or_else(|| anyhow!("View key does not open this note"))?;
+            Output {
+                text: format!(
+                    "Confidential balance: {} (committed at height {})",
+                    value, note["height"]
+                ),
+                json: serde_json::json!({ "balance": value, "height": note["height"] }),
+            }
+        }
+        Commands::Wallet {
+            action: WalletCommand::List,
+        } => {
+            let mut wallets = Vec::new();
+            if cli.wallet_path.exists() {
+                wallets.push((None, cli.wallet_path.clone()));
+            }
+            if let Ok(entries) = fs::read_dir(cli.profiles_dir()) {
+                let mut profiles: Vec<_> = entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                    .collect();
+                profiles.sort();
+                for path in profiles {
+                    let name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+                    wallets.push((name, path));
+                }
+            }
+            let mut listed = Vec::new();
+            let mut lines = Vec::new();
+            for (profile, path) in wallets {
+                let wallet = Wallet::load(&path)
+                    .map_err(|e| anyhow!("Invalid wallet {}: {}", path.display(), e))?;
+                lines.push(format!(
+                    "{:<16} 0x{} {}",
+                    profile.as_deref().unwrap_or("(default)"),
+                    wallet.public_key,
+                    wallet.node_url.as_deref().unwrap_or(DEFAULT_NODE_URL)
+                ));
+                listed.push(serde_json::json!({
+                    "profile": profile,
+                    "wallet": path,
+                    "public_key": wallet.public_key,
+                    "node_url": wallet.node_url,
+                }));
+            }
+            Output {
+                text: if lines.is_empty() {
+                    "No wallets".to_string()
+                } else {
+                    lines.join("\n")
+                },
+                json: serde_json::json!({ "wallets": listed }),
+            }
+        }
+        Commands::Contacts { action } => {
+            let mut book = AddressBook::load(&cli.wallet_path)?;
+            match action {
+                ContactsCommand::Add { name, address } => {
+                    let address = book.add(name, address)?;
+                    Output {
+                        text: format!("Saved {} as 0x{}", name, address),
+                        json: serde_json::json!({ "name": name, "address": address }),
+                    }
+                }
+                ContactsCommand::List => {
+                    let text = book
+                        .contacts()
+                        .iter()
+                        .map(|(name, address)| format!("{:<20} 0x{}", name, address))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    Output {
+                        text: if text.is_empty() {
+                            "No contacts".to_string()
+                        } else {
+                            text
+                        },
+                        json: serde_json::json!({ "contacts": book.contacts() }),
+                    }
+                }
+                ContactsCommand::Remove { name } => {
+                    let address = book.remove(name)?;
+                    Output {
+                        text: format!("Removed {} (0x{})", name, address),
+                        json: serde_json::json!({ "name": name, "address": address }),
+                    }
+                }
+            }
+        }
+        Commands::Watch { interval, blocks } => {
+            if cli.output == OutputFormat::Json {
+                return Err(anyhow!(
+                    "watch only renders text; poll /node/status for JSON"
+                ));
+            }
+            if *interval == 0 {
+                return Err(anyhow!("--interval must be at least 1 second"));
+            }
+            watch::run(&client, node_url, Duration::from_secs(*interval), *blocks).await?;
+            unreachable!("watch runs until interrupted")
         }
-        Commands::Validators => {
-            let res = client
-                .get(format!("{}/validators", cli.node_url))
+        Commands::Faucet { address } => {
+            let address = match address {
+                Some(address) => {
+                    AddressBook::load(&cli.wallet_path)?
+                        .resolve(address)?
+                        .address
+                }
+                None => Wallet::load(&wallet_file)?.public_key,
+            };
+            let mut key = [0u8; 32];
+            hex::decode_to_slice(&address, &mut key)?;
+
+            let issued = get_json(&client, format!("{}/faucet/challenge", node_url)).await?;
+            let mut challenge = [0u8; 32];
+            hex::decode_to_slice(issued["challenge"].as_str().unwrap_or(""), &mut challenge)
+                .map_err(|e| anyhow!("Malformed faucet challenge: {}", e))?;
+            let difficulty = issued["difficulty"]
+                .as_u64()
+                .ok_or_else(|| anyhow!("Faucet challenge has no difficulty"))?
+                as u32;
+            if difficulty > MAX_FAUCET_DIFFICULTY {
+                return Err(anyhow!(
+                    "Faucet asks for {} bits of work, more than {}",
+                    difficulty,
+                    MAX_FAUCET_DIFFICULTY
+                ));
+            }
+            if cli.output == OutputFormat::Text {
+                eprintln!("Solving faucet challenge ({} bits)...", difficulty);
+            }
+            let started = Instant::now();
+            let nonce = tokio::task::spawn_blocking(move || {
+                lumina_types::faucet::solve(&challenge, &key, difficulty)
+            })
+            .await?;
+            let solve_secs = started.elapsed().as_secs_f64();
+
+            let result: serde_json::Value = client
+                .post(format!("{}/faucet", node_url))
+                .json(&serde_json::json!({
+                    "address": address,
+                    "challenge": hex::encode(challenge),
+                    "nonce": nonce,
+                }))
                 .send()
                 .await?
-                .text()
+                .json()
                 .await?;
+            if result["status"] != "funded" {
+                return Err(anyhow!(
+                    "Faucet refused: {}",
+                    result["error"].as_str().unwrap_or("unknown error")
+                ));
+            }
+            Output {
+                text: format!(
+                    "Funded 0x{} with {} {} (solved in {:.1}s)",
+                    address,
+                    result["amount"],
+                    result["asset"].as_str().unwrap_or(""),
+                    solve_secs
+                ),
+                json: serde_json::json!({
+                    "address": address,
+                    "amount": result["amount"],
+                    "asset": result["asset"],
+                    "nonce": nonce,
+                    "solve_secs": solve_secs,
+                }),
+            }
+        }
+        Commands::PayRequest {
+            amount,
+            asset,
+            memo,
+            expires_in,
+            address,
+        } => {
+            let address = match address {
+                Some(address) => {
+                    AddressBook::load(&cli.wallet_path)?
+                        .resolve(address)?
+                        .address
+                }
+                None => Wallet::load(&wallet_file)?.public_key,
+            };
+            let mut key = [0u8; 32];
+            hex::decode_to_slice(&address, &mut key)?;
+            let request = PaymentRequest {
+                address: key,
+                amount: *amount,
+                asset: parse_asset(asset)?,
+                memo: memo.clone(),
+                expires_at: expires_in.map(|secs| unix_now().saturating_add(secs)),
+            };
+            let uri = request
+                .to_uri()
+                .ok_or_else(|| anyhow!("Memo longer than {} bytes", payment::MAX_MEMO_BYTES))?;
+            let memo_hash = request.memo_hash().map(hex::encode);
+            Output {
+                text: match &memo_hash {
+                    Some(hash) => format!("{}\nMemo hash: {}", uri, hash),
+                    None => uri.clone(),
+                },
+                json: serde_json::json!({
+                    "uri": uri,
+                    "memo_hash": memo_hash,
+                    "expires_at": request.expires_at,
+                }),
+            }
+        }
+        Commands::Pay { uri } => {
+            let request = PaymentRequest::parse(uri.trim())
+                .map_err(|e| anyhow!("Invalid payment request: {}", e))?;
+            if request.is_expired(unix_now()) {
+                return Err(anyhow!("Payment request expired"));
+            }
+            let wallet = Wallet::load(&wallet_file)?;
+            let kp = wallet.to_keypair()?;
+            let instruction = match request.memo_hash() {
+                Some(memo_hash) => StablecoinInstruction::TransferWithMemo {
+                    to: request.address,
+                    amount: request.amount,
+                    asset: request.asset.clone(),
+                    memo_hash,
+                },
+                None => StablecoinInstruction::Transfer {
+                    to: request.address,
+                    amount: request.amount,
+                    asset: request.asset.clone(),
+                },
+            };
+            let mut action = format!(
+                "Pay {} {:?} to 0x{}",
+                request.amount,
+                request.asset,
+                hex::encode(request.address)
+            );
+            if let Some(memo) = &request.memo {
+                action.push_str(&format!(" for \"{}\"", memo));
+            }
+            // Requests come from someone else, so paying one always asks
+            confirm_signing(&client, cli, node_url, &wallet, &action, true).await?;
+            let mut tx = Transaction {
+                sender: kp.verifying_key().to_bytes(),
+                nonce: 0,
+                instruction,
+                signature: vec![],
+                gas_limit: 100_000,
+                gas_price: 1,
+            };
+            tx.signature = sign(&kp, &tx.signing_bytes());
+            submit(&client, node_url, cli, &tx).await?
+        }
+        Commands::Payroll { file, dry_run } => {
+            let csv = fs::read_to_string(file)
+                .map_err(|e| anyhow!("Cannot read {}: {}", file.display(), e))?;
+            let payroll = payroll::parse(&csv, &AddressBook::load(&cli.wallet_path)?)?;
+            let summary = format!(
+                "Pay {} recipients a total of {} LUSD",
+                payroll.payments.len(),
+                payroll.total
+            );
+            let mut output = Output {
+                text: summary.clone(),
+                json: serde_json::json!({
+                    "recipients": payroll.payments.len(),
+                    "total": payroll.total,
+                    "submission": null,
+                }),
+            };
+            if *dry_run {
+                return Ok(output);
+            }
 
-            println!("Validators:\n{}", res);
+            let wallet = Wallet::load(&wallet_file)?;
+            let kp = wallet.to_keypair()?;
+            let mut tx = Transaction {
+                sender: kp.verifying_key().to_bytes(),
+                nonce: 0,
+                instruction: StablecoinInstruction::BatchDisburse {
+                    payments: payroll.payments,
+                },
+                signature: vec![],
+                gas_limit: 100_000,
+                gas_price: 1,
+            };
+            // A payroll run always asks, as a transfer to a contact does
+            confirm_signing(&client, cli, node_url, &wallet, &summary, true).await?;
+            tx.signature = sign(&kp, &tx.signing_bytes());
+            let submission = submit(&client, node_url, cli, &tx).await?;
+            output.text = format!("{}\n{}", output.text, submission.text);
+            output.json["submission"] = submission.json;
+            output
         }
-    }
+    };
 
-    Ok(())
+    Ok(output)
 }
@@ -0,0 +1,56 @@
+use crate::contacts::AddressBook;
+use anyhow::{anyhow, Result};
+use lumina_types::instruction::MAX_DISBURSE_PAYMENTS;
+
+/// Payments read from a payroll CSV, ready for `BatchDisburse`.
+pub struct Payroll {
+    pub payments: Vec<([u8; 32], u64)>,
+    pub total: u64,
+}
+
+/// Parse `recipient,amount` rows, where the recipient is an address or a
+/// contact name and the amount is in LUSD base units. Blank lines, `#`
+/// comments and a leading header row are skipped.
+pub fn parse(csv: &str, book: &AddressBook) -> Result<Payroll> {
+    let mut payments = Vec::new();
+    let mut total = 0u64;
+    for (index, line) in csv.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row = index + 1;
+        let Some((recipient, amount)) = line.split_once(',') else {
+            return Err(anyhow!("Line {}: expected recipient,amount", row));
+        };
+        let (recipient, amount) = (recipient.trim(), amount.trim());
+        let amount: u64 = match amount.parse() {
+            Ok(amount) => amount,
+            Err(_) if payments.is_empty() && amount.eq_ignore_ascii_case("amount") => continue,
+            Err(_) => return Err(anyhow!("Line {}: invalid amount {}", row, amount)),
+        };
+        if amount == 0 {
+            return Err(anyhow!("Line {}: amount must be non-zero", row));
+        }
+        let resolved = book
+            .resolve(recipient)
+            .map_err(|e| anyhow!("Line {}: {}", row, e))?;
+        let mut to = [0u8; 32];
+        hex::decode_to_slice(&resolved.address, &mut to)?;
+        total = total
+            .checked_add(amount)
+            .ok_or_else(|| anyhow!("Payroll total overflows"))?;
+        payments.push((to, amount));
+    }
+    if payments.is_empty() {
+        return Err(anyhow!("Payroll has no payments"));
+    }
+    if payments.len() > MAX_DISBURSE_PAYMENTS {
+        return Err(anyhow!(
+            "Payroll has {} payments; split it into batches of at most {}",
+            payments.len(),
+            MAX_DISBURSE_PAYMENTS
+        ));
+    }
+    Ok(Payroll { payments, total })
+}
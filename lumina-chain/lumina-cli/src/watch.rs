@@ -0,0 +1,139 @@
+use anyhow::{anyhow, Result};
+use lumina_types::block::Block;
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Clear the screen and move the cursor home, so each frame redraws in place.
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Redraw a dashboard of the node at `node_url` every `interval` until
+/// interrupted, listing the latest `recent` blocks.
+pub async fn run(client: &Client, node_url: &str, interval: Duration, recent: usize) -> Result<()> {
+    let mut blocks = BTreeMap::new();
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let frame = match poll(client, node_url, recent, &mut blocks).await {
+            Ok(frame) => frame,
+            Err(e) => format!("Node unreachable: {}\n", e),
+        };
+        print!(
+            "{}LuminaChain {} (every {:?}, Ctrl+C to quit)\n\n{}",
+            CLEAR, node_url, interval, frame
+        );
+    }
+}
+
+async fn get_json(client: &Client, url: String) -> Result<serde_json::Value> {
+    let value: serde_json::Value = client.get(url).send().await?.json().await?;
+    if let Some(e) = value.get("error") {
+        return Err(anyhow!("{}", e));
+    }
+    Ok(value)
+}
+
+/// Fetch the latest figures and render one frame. `blocks` caches fetched
+/// blocks by height across frames; it keeps only the ones still shown.
+async fn poll(
+    client: &Client,
+    node_url: &str,
+    recent: usize,
+    blocks: &mut BTreeMap<u64, Block>,
+) -> Result<String> {
+    let status = get_json(client, format!("{}/node/status", node_url)).await?;
+    let state = get_json(client, format!("{}/state", node_url)).await?;
+    let height = status["height"].as_u64().unwrap_or(0);
+
+    let first = (height + 1).saturating_sub(recent as u64).max(1);
+    blocks.retain(|h, _| *h >= first && *h <= height);
+    for h in first..=height {
+        if blocks.contains_key(&h) {
+            continue;
+        }
+        let block: Option<Block> = client
+            .get(format!("{}/block/{}", node_url, h))
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(block) = block {
+            blocks.insert(h, block);
+        }
+    }
+    Ok(render(&status, &state, blocks))
+}
+
+/// Transactions per second over the cached blocks, by block timestamps. The
+/// oldest block only marks the start of the span.
+fn tps(blocks: &BTreeMap<u64, Block>) -> Option<f64> {
+    let (first, last) = (blocks.values().next()?, blocks.values().next_back()?);
+    let span = last.header.timestamp.checked_sub(first.header.timestamp)?;
+    if span == 0 {
+        return None;
+    }
+    let txs: usize = blocks.values().skip(1).map(|b| b.transactions.len()).sum();
+    Some(txs as f64 / span as f64)
+}
+
+fn render(
+    status: &serde_json::Value,
+    state: &serde_json::Value,
+    blocks: &BTreeMap<u64, Block>,
+) -> String {
+    let mut out = String::new();
+    let yes_no = |v: &serde_json::Value| {
+        if v.as_bool() == Some(true) {
+            "yes"
+        } else {
+            "no"
+        }
+    };
+    let _ = writeln!(
+        out,
+        "Height         {} (syncing: {}, peers {}, mempool {})",
+        status["height"],
+        yes_no(&status["syncing"]),
+        status["peer_count"],
+        status["mempool_size"]
+    );
+    let _ = match tps(blocks) {
+        Some(tps) => writeln!(out, "TPS            {:.2}", tps),
+        None => writeln!(out, "TPS            -"),
+    };
+    let _ = writeln!(out, "Health index   {} / 10000", state["health_index"]);
+    let _ = writeln!(
+        out,
+        "Reserve ratio  {:.4}",
+        state["reserve_ratio"].as_f64().unwrap_or(0.0)
+    );
+    let _ = writeln!(out, "Redeem queue   {}", state["pending_redeem_queue"]);
+    let _ = writeln!(
+        out,
+        "Breaker        {}",
+        if state["circuit_breaker_active"].as_bool() == Some(true) {
+            "ACTIVE"
+        } else {
+            "off"
+        }
+    );
+    let _ = writeln!(out, "LUSD supply    {}", state["total_lusd_supply"]);
+
+    let _ = writeln!(
+        out,
+        "\n{:>8}  {:>12}  {:>5}  proposer",
+        "height", "timestamp", "txs"
+    );
+    for (height, block) in blocks.iter().rev() {
+        let _ = writeln!(
+            out,
+            "{:>8}  {:>12}  {:>5}  {}",
+            height,
+            block.header.timestamp,
+            block.transactions.len(),
+            &hex::encode(block.header.proposer)[..16]
+        );
+    }
+    out
+}
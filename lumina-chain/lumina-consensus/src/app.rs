@@ -1,20 +1,21 @@
-#![cfg(feature = "malachite")]
-
 use async_trait::async_trait;
 use lumina_crypto::signatures::PublicKey;
-use lumina_execution::{end_block, execute_transaction, ExecutionContext};
+use lumina_execution::{
+    audit_actions, begin_block, end_block, execute_transaction, record_audit, record_liveness,
+    ExecutionContext,
+};
 use lumina_storage::db::Storage;
+use lumina_types::audit::AuditRecord;
+use lumina_types::receipt::{BlockReceipts, Receipt};
 use lumina_types::state::GlobalState;
 use lumina_types::transaction::Transaction;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "malachite")]
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-const F: usize = 2;
-const QUORUM: usize = 2 * F + 1;
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InitChainRequest {
     pub genesis_state: GlobalState,
@@ -26,6 +27,9 @@ pub struct BeginBlockRequest {
     pub height: u64,
     pub proposer: [u8; 32],
     pub timestamp: u64,
+    /// Validators whose commit votes for the parent block the block
+    /// carries; empty when it has none
+    pub signers: Vec<[u8; 32]>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +46,9 @@ pub struct EndBlockRequest {
 pub struct CommitResponse {
     pub height: u64,
     pub app_hash: [u8; 32],
+    pub receipts: BlockReceipts,
+    /// Audit log entries appended by the block
+    pub audit: Vec<AuditRecord>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,10 +61,170 @@ struct WalState {
 #[derive(Debug, Clone)]
 struct InflightBlock {
     height: u64,
+    proposer: [u8; 32],
     timestamp: u64,
+    signers: Vec<[u8; 32]>,
     txs: Vec<Vec<u8>>,
 }
 
+/// Deterministic, storage-free block execution behind the `Application`
+/// interface. Every consensus mode runs blocks through this so begin/end
+/// hooks, receipts and the health index are computed the same way.
+pub struct BlockExecutor {
+    pub state: GlobalState,
+    pub height: u64,
+    current: Option<BeginBlockRequest>,
+    receipts: BlockReceipts,
+    audit: Vec<AuditRecord>,
+}
+
+impl BlockExecutor {
+    /// Executor positioned on top of `state` committed at `height`.
+    pub fn new(state: GlobalState, height: u64) -> Self {
+        Self {
+            state,
+            height,
+            current: None,
+            receipts: BlockReceipts::default(),
+            audit: Vec::new(),
+        }
+    }
+
+    fn ctx(&mut self) -> Result<ExecutionContext<'_>, String> {
+        let block = self
+            .current
+            .as_ref()
+            .ok_or_else(|| "begin_block must be called first".to_string())?;
+        Ok(ExecutionContext {
+            height: block.height,
+            timestamp: block.timestamp,
+            state: &mut self.state,
+        })
+    }
+
+    /// Execute one transaction; on failure its events are discarded and no
+    /// receipt is recorded.
+    pub fn deliver(&mut self, tx: &Transaction) -> Result<(), String> {
+        let first_event = self.receipts.events.len();
+        let breaker_before = self.state.circuit_breaker_active;
+        let mut ctx = self.ctx()?;
+        let height = ctx.height;
+        if let Err(e) = execute_transaction(tx, &mut ctx) {
+            self.state.events.truncate(first_event);
+            return Err(e.to_string());
+        }
+        let tx_index = self.receipts.receipts.len() as u32;
+        let actions = audit_actions(
+            Some(tx),
+            &self.state.events[first_event..],
+            breaker_before,
+            &self.state,
+        );
+        let records = record_audit(&mut self.state, height, Some(tx_index), tx.sender, actions);
+        self.audit.extend(records);
+        for event in self.state.events[first_event..].iter().cloned() {
+            self.receipts.log_event(Some(tx_index), event);
+        }
+        self.receipts.receipts.push(Receipt {
+            tx_id: tx.id(),
+            sender: tx.sender,
+            success: true,
+            first_event: first_event as u32,
+            event_count: (self.receipts.events.len() - first_event) as u32,
+        });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Application for BlockExecutor {
+    fn state(&self) -> &GlobalState {
+        &self.state
+    }
+
+    async fn init_chain(&mut self, req: InitChainRequest) -> Result<(), String> {
+        self.state = req.genesis_state;
+        self.height = req.initial_height;
+        self.current = None;
+        Ok(())
+    }
+
+    async fn begin_block(&mut self, req: BeginBlockRequest) -> Result<(), String> {
+        if req.height <= self.height {
+            return Err("non-monotonic height".to_string());
+        }
+        self.receipts = BlockReceipts {
+            height: req.height,
+            ..Default::default()
+        };
+        self.audit.clear();
+        self.current = Some(req);
+        let mut ctx = self.ctx()?;
+        begin_block(&mut ctx);
+        Ok(())
+    }
+
+    async fn check_tx(&self, tx: &[u8]) -> bool {
+        let Ok(tx) = bincode::deserialize::<Transaction>(tx) else {
+            return false;
+        };
+        let (height, timestamp) = self
+            .current
+            .as_ref()
+            .map(|b| (b.height, b.timestamp))
+            .unwrap_or((self.height + 1, 0));
+        LuminaApp::prevalidate_tx_against_state(&self.state, &tx, height, timestamp).is_ok()
+    }
+
+    async fn deliver_tx(&mut self, req: DeliverTxRequest) -> Result<(), String> {
+        let tx: Transaction =
+            bincode::deserialize(&req.tx).map_err(|_| "invalid tx bytes".to_string())?;
+        self.deliver(&tx)
+    }
+
+    async fn end_block(&mut self, req: EndBlockRequest) -> Result<(), String> {
+        if self.current.as_ref().map(|b| b.height) != Some(req.height) {
+            return Err("end_block height mismatch".to_string());
+        }
+        let block_events = self.receipts.events.len();
+        let breaker_before = self.state.circuit_breaker_active;
+        let (proposer, signers) = self
+            .current
+            .as_ref()
+            .map(|b| (b.proposer, b.signers.clone()))
+            .unwrap_or_default();
+        let mut ctx = self.ctx()?;
+        record_liveness(&mut ctx, &proposer, &signers);
+        end_block(&mut ctx);
+        for event in self.state.events[block_events..].iter().cloned() {
+            self.receipts.log_event(None, event);
+        }
+        let actions = audit_actions(
+            None,
+            &self.state.events[block_events..],
+            breaker_before,
+            &self.state,
+        );
+        let records = record_audit(&mut self.state, req.height, None, [0u8; 32], actions);
+        self.audit.extend(records);
+        Ok(())
+    }
+
+    async fn commit(&mut self) -> Result<CommitResponse, String> {
+        let block = self
+            .current
+            .take()
+            .ok_or_else(|| "begin_block must be called first".to_string())?;
+        self.height = block.height;
+        Ok(CommitResponse {
+            height: self.height,
+            app_hash: self.state.root_hash(),
+            receipts: std::mem::take(&mut self.receipts),
+            audit: std::mem::take(&mut self.audit),
+        })
+    }
+}
+
 pub struct LuminaApp {
     pub state: GlobalState,
     pub storage: Storage,
@@ -90,7 +257,9 @@ impl LuminaApp {
         self.height = wal.height;
         self.inflight = wal.current_block.map(|b| InflightBlock {
             height: b.height,
+            proposer: b.proposer,
             timestamp: b.timestamp,
+            signers: b.signers,
             txs: wal.pending_txs,
         });
         Ok(())
@@ -101,8 +270,9 @@ impl LuminaApp {
             height: self.height,
             current_block: self.inflight.as_ref().map(|b| BeginBlockRequest {
                 height: b.height,
-                proposer: [0u8; 32],
+                proposer: b.proposer,
                 timestamp: b.timestamp,
+                signers: b.signers.clone(),
             }),
             pending_txs: self
                 .inflight
@@ -179,6 +349,8 @@ impl LuminaApp {
 
 #[async_trait]
 pub trait Application {
+    /// State as of the last commit (plus any in-flight block effects).
+    fn state(&self) -> &GlobalState;
     async fn init_chain(&mut self, req: InitChainRequest) -> Result<(), String>;
     async fn begin_block(&mut self, req: BeginBlockRequest) -> Result<(), String>;
     async fn check_tx(&self, tx: &[u8]) -> bool;
@@ -189,6 +361,10 @@ pub trait Application {
 
 #[async_trait]
 impl Application for LuminaApp {
+    fn state(&self) -> &GlobalState {
+        &self.state
+    }
+
     async fn init_chain(&mut self, req: InitChainRequest) -> Result<(), String> {
         self.state = req.genesis_state;
         self.height = req.initial_height;
@@ -208,7 +384,9 @@ impl Application for LuminaApp {
 
         self.inflight = Some(InflightBlock {
             height: req.height,
+            proposer: req.proposer,
             timestamp: req.timestamp,
+            signers: req.signers,
             txs: Vec::new(),
         });
         self.persist_wal()
@@ -262,21 +440,26 @@ impl Application for LuminaApp {
             .take()
             .ok_or_else(|| "begin_block must be called first".to_string())?;
 
-        let mut ctx = ExecutionContext {
-            state: &mut self.state,
+        // Execute on a copy so a failing tx leaves the committed state intact.
+        let mut exec = BlockExecutor::new(self.state.clone(), self.height);
+        exec.begin_block(BeginBlockRequest {
             height: inflight.height,
+            proposer: inflight.proposer,
             timestamp: inflight.timestamp,
-        };
-
-        for tx_bytes in inflight.txs {
-            let tx: Transaction = bincode::deserialize(&tx_bytes).map_err(|e| e.to_string())?;
-            execute_transaction(&tx, &mut ctx).map_err(|e| e.to_string())?;
+            signers: inflight.signers,
+        })
+        .await?;
+        for tx in inflight.txs {
+            exec.deliver_tx(DeliverTxRequest { tx }).await?;
         }
+        exec.end_block(EndBlockRequest {
+            height: inflight.height,
+        })
+        .await?;
+        let response = exec.commit().await?;
+        self.state = exec.state;
+        self.height = response.height;
 
-        end_block(&mut ctx);
-
-        self.height = inflight.height;
-        let app_hash = self.state.root_hash();
         self.storage
             .save_state(&self.state)
             .map_err(|e| e.to_string())?;
@@ -285,25 +468,25 @@ impl Application for LuminaApp {
             .map_err(|e| e.to_string())?;
         self.clear_wal()?;
 
-        Ok(CommitResponse {
-            height: self.height,
-            app_hash,
-        })
+        Ok(response)
     }
 }
 
+#[cfg(feature = "malachite")]
 #[derive(Debug, Clone)]
 pub struct LocalProposal {
     pub height: u64,
     pub txs: Vec<Vec<u8>>,
 }
 
+#[cfg(feature = "malachite")]
 pub struct LocalMalachiteEngine<A: Application + Send + Sync> {
     app: A,
     validators: Vec<[u8; 32]>,
     votes: HashMap<u64, HashSet<[u8; 32]>>,
 }
 
+#[cfg(feature = "malachite")]
 impl<A: Application + Send + Sync> LocalMalachiteEngine<A> {
     pub fn new(app: A, validators: Vec<[u8; 32]>) -> Result<Self, String> {
         if validators.len() != 7 {
@@ -330,6 +513,7 @@ impl<A: Application + Send + Sync> LocalMalachiteEngine<A> {
                 height: proposal.height,
                 proposer: self.validators[0],
                 timestamp: now,
+                signers: Vec::new(),
             })
             .await?;
 
@@ -345,7 +529,7 @@ impl<A: Application + Send + Sync> LocalMalachiteEngine<A> {
 
         let validator_power = |pk: &[u8; 32]| -> u64 {
             self.app
-                .state
+                .state()
                 .validators
                 .iter()
                 .find(|v| &v.pubkey == pk)
@@ -359,7 +543,7 @@ impl<A: Application + Send + Sync> LocalMalachiteEngine<A> {
         for validator in &self.validators {
             let entry = self.votes.entry(proposal.height).or_default();
             entry.insert(*validator);
-            let voted_power: u64 = entry.iter().map(|pk| validator_power(pk)).sum();
+            let voted_power: u64 = entry.iter().map(&validator_power).sum();
             if voted_power >= quorum_power {
                 return self.app.commit().await;
             }
@@ -373,9 +557,11 @@ impl<A: Application + Send + Sync> LocalMalachiteEngine<A> {
 mod tests {
     use super::*;
     use lumina_types::instruction::StablecoinInstruction;
+    #[cfg(feature = "malachite")]
     use tokio::time::Instant;
 
-    fn sample_tx() -> Vec<u8> {
+    /// A signed 1 LUSD transfer, plus a state in which its sender can pay it.
+    fn sample_tx() -> (Vec<u8>, GlobalState) {
         let kp = lumina_crypto::signatures::generate_keypair();
         let sender = kp.verifying_key().to_bytes();
         let tx = Transaction {
@@ -392,31 +578,74 @@ mod tests {
         };
         let mut tx = tx;
         tx.signature = lumina_crypto::signatures::sign(&kp, &tx.signing_bytes());
-        bincode::serialize(&tx).unwrap()
+        let mut state = GlobalState::default();
+        state.accounts.entry(sender).or_default().lusd_balance = 10;
+        (bincode::serialize(&tx).unwrap(), state)
     }
 
     #[tokio::test]
     async fn wal_recovery_restores_inflight_block() {
         let storage = Storage::new("/tmp/lumina-test-wal-1").unwrap();
         let wal_path = PathBuf::from("/tmp/lumina-test-wal-1/consensus.wal");
+        let (tx, state) = sample_tx();
+        storage.save_state(&state).unwrap();
 
         let mut app = LuminaApp::new(storage.clone(), &wal_path);
         app.begin_block(BeginBlockRequest {
             height: 1,
             proposer: [0u8; 32],
             timestamp: 1,
+            signers: Vec::new(),
         })
         .await
         .unwrap();
-        app.deliver_tx(DeliverTxRequest { tx: sample_tx() })
-            .await
-            .unwrap();
+        app.deliver_tx(DeliverTxRequest { tx }).await.unwrap();
 
         let recovered = LuminaApp::new(storage, &wal_path);
         assert_eq!(recovered.height, 0);
         assert!(recovered.inflight.is_some());
     }
 
+    #[tokio::test]
+    async fn lumina_app_and_block_executor_commit_identically() {
+        let (tx, state) = sample_tx();
+        let storage = Storage::new("/tmp/lumina-test-wal-4").unwrap();
+        storage.save_state(&state).unwrap();
+        let wal_path = PathBuf::from("/tmp/lumina-test-wal-4/consensus.wal");
+        let _ = fs::remove_file(&wal_path);
+        let mut app = LuminaApp::new(storage, &wal_path);
+        let mut exec = BlockExecutor::new(state, 0);
+
+        let mut responses = Vec::new();
+        for driver in [&mut app as &mut (dyn Application + Send), &mut exec] {
+            driver
+                .begin_block(BeginBlockRequest {
+                    height: 1,
+                    proposer: [0u8; 32],
+                    timestamp: 1,
+                    signers: Vec::new(),
+                })
+                .await
+                .unwrap();
+            driver
+                .deliver_tx(DeliverTxRequest { tx: tx.clone() })
+                .await
+                .unwrap();
+            driver
+                .end_block(EndBlockRequest { height: 1 })
+                .await
+                .unwrap();
+            responses.push(driver.commit().await.unwrap());
+        }
+
+        assert_eq!(responses[0].app_hash, responses[1].app_hash);
+        assert_eq!(responses[0].receipts, responses[1].receipts);
+        assert_eq!(responses[1].receipts.receipts.len(), 1);
+        assert_eq!(app.state.health_index, exec.state.health_index);
+        assert!(exec.state.health_index > 0);
+    }
+
+    #[cfg(feature = "malachite")]
     #[tokio::test]
     async fn seven_validator_finality_is_sub_900ms() {
         let storage = Storage::new("/tmp/lumina-test-wal-2").unwrap();
@@ -445,6 +674,7 @@ mod tests {
         assert!(elapsed.as_millis() < 900);
     }
 
+    #[cfg(feature = "malachite")]
     #[tokio::test]
     async fn green_validator_has_higher_weighted_voting_power() {
         let storage = Storage::new("/tmp/lumina-test-wal-3").unwrap();
@@ -462,6 +692,7 @@ mod tests {
                     power: if i == 0 { 20 } else { 10 },
                     is_green: i == 0,
                     energy_proof: if i == 0 { Some(vec![1u8; 64]) } else { None },
+                    green_verified_at: 0,
                 }
             })
             .collect();
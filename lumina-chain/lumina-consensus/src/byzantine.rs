@@ -0,0 +1,627 @@
+//! Byzantine fault injection harness.
+//!
+//! Runs a cluster of `ConsensusService` nodes under a prevote/precommit BFT
+//! round protocol over a simulated, seeded network. Blocks are validated
+//! through the real import path, so a proposal is only voted for if its
+//! state root and validator set hash check out. Faulty nodes equivocate,
+//! withhold votes, gossip late or propose bad state roots; the harness
+//! asserts honest nodes never finalize conflicting blocks (safety) and
+//! keep finalizing while at most a third of the validators misbehave
+//! (liveness).
+
+use super::tests::{signed, test_service};
+use super::*;
+use lumina_crypto::signatures::SigningKey;
+use lumina_types::instruction::{AssetType, StablecoinInstruction};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{BTreeMap, HashMap};
+
+type Hash = [u8; 32];
+
+const BASE_TIMEOUT: u64 = 20;
+const HONEST_MAX_DELAY: u64 = 3;
+const SLOW_MAX_DELAY: u64 = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Fault {
+    /// Sends conflicting proposals to the two halves of the network and
+    /// votes for every proposal it sees.
+    Equivocate,
+    /// Proposes honestly but never casts a vote.
+    WithholdVotes,
+    /// Behaves honestly but every outgoing message is heavily delayed.
+    DelayGossip,
+    /// Proposes blocks whose state root does not match their execution.
+    InvalidStateRoot,
+}
+
+const FAULTS: [Fault; 4] = [
+    Fault::Equivocate,
+    Fault::WithholdVotes,
+    Fault::DelayGossip,
+    Fault::InvalidStateRoot,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Step {
+    Propose,
+    Prevote,
+    Precommit,
+}
+
+#[derive(Clone)]
+enum Message {
+    Proposal {
+        round: u32,
+        proposer: usize,
+        block: Box<Block>,
+    },
+    Vote {
+        step: Step,
+        height: u64,
+        round: u32,
+        voter: usize,
+        hash: Option<Hash>,
+    },
+}
+
+type Tally = HashMap<(u64, u32), HashMap<Option<Hash>, HashSet<usize>>>;
+
+struct Node {
+    id: usize,
+    n: usize,
+    fault: Option<Fault>,
+    service: ConsensusService,
+    storage: Arc<Storage>,
+    sender: SigningKey,
+    height: u64,
+    round: u32,
+    deadline: u64,
+    locked: Option<(u32, Hash)>,
+    sent: HashSet<(Step, u64, u32)>,
+    blocks: HashMap<Hash, Block>,
+    validity: HashMap<Hash, bool>,
+    proposals: HashMap<(u64, u32), Hash>,
+    prevotes: Tally,
+    precommits: Tally,
+    voters: HashMap<(u64, u32), HashSet<usize>>,
+    finalized: BTreeMap<u64, Hash>,
+    /// (recipient, message); `None` broadcasts to every node including self.
+    outbox: Vec<(Option<usize>, Message)>,
+}
+
+impl Node {
+    fn quorum(&self) -> usize {
+        self.n * 2 / 3 + 1
+    }
+
+    fn max_faulty(&self) -> usize {
+        (self.n - 1) / 3
+    }
+
+    fn proposer(&self, height: u64, round: u32) -> usize {
+        (height as usize + round as usize) % self.n
+    }
+
+    fn parent_hash(&self) -> Hash {
+        self.finalized
+            .get(&(self.height - 1))
+            .copied()
+            .unwrap_or([0u8; 32])
+    }
+
+    fn count(tally: &Tally, height: u64, round: u32, hash: Option<Hash>) -> usize {
+        tally
+            .get(&(height, round))
+            .and_then(|votes| votes.get(&hash))
+            .map_or(0, HashSet::len)
+    }
+
+    fn vote(&mut self, step: Step, round: u32, hash: Option<Hash>) {
+        if !self.sent.insert((step, self.height, round)) {
+            return;
+        }
+        if self.fault == Some(Fault::WithholdVotes) {
+            return;
+        }
+        self.outbox.push((
+            None,
+            Message::Vote {
+                step,
+                height: self.height,
+                round,
+                voter: self.id,
+                hash,
+            },
+        ));
+    }
+
+    /// Import the block through the consensus path once it extends our
+    /// finalized chain; `None` until it can be judged.
+    async fn validate(&mut self, hash: Hash) -> Option<bool> {
+        if let Some(valid) = self.validity.get(&hash) {
+            return Some(*valid);
+        }
+        let block = self.blocks.get(&hash)?.clone();
+        if block.header.height != self.height {
+            return None;
+        }
+        let valid = block.header.prev_hash == self.parent_hash()
+            && self
+                .service
+                .import_block_and_maybe_reorg(&block)
+                .await
+                .is_ok();
+        self.validity.insert(hash, valid);
+        Some(valid)
+    }
+
+    async fn build(&self, amount: u64) -> Block {
+        let parent = if self.height == 1 {
+            self.storage.load_state_by_height(0).unwrap().unwrap()
+        } else {
+            self.storage
+                .load_state_by_hash(&self.parent_hash())
+                .unwrap()
+                .unwrap()
+        };
+        let tx = signed(
+            &self.sender,
+            self.height - 1,
+            StablecoinInstruction::Transfer {
+                to: [9u8; 32],
+                amount,
+                asset: AssetType::LUSD,
+            },
+        );
+        build_block_from_parent(
+            parent,
+            vec![tx],
+            self.height,
+            self.parent_hash(),
+            self.height,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Re-propose the block with the most recent polka, else a fresh one.
+    async fn propose(&mut self) {
+        let (height, round) = (self.height, self.round);
+        let quorum = self.quorum();
+        let mut polka: Option<(u32, Hash)> = None;
+        for r in 0..round {
+            if let Some(votes) = self.prevotes.get(&(height, r)) {
+                for (hash, voters) in votes {
+                    if let (Some(hash), true) = (hash, voters.len() >= quorum) {
+                        if self.blocks.contains_key(hash) {
+                            polka = Some((r, *hash));
+                        }
+                    }
+                }
+            }
+        }
+        let block = match polka.or(self.locked) {
+            Some((_, hash)) => self.blocks[&hash].clone(),
+            None => self.build(1).await,
+        };
+
+        match self.fault {
+            Some(Fault::Equivocate) => {
+                let other = self.build(2).await;
+                for to in 0..self.n {
+                    let block = if to % 2 == 0 { &block } else { &other };
+                    self.outbox.push((
+                        Some(to),
+                        Message::Proposal {
+                            round,
+                            proposer: self.id,
+                            block: Box::new(block.clone()),
+                        },
+                    ));
+                }
+            }
+            Some(Fault::InvalidStateRoot) => {
+                let mut bad = block;
+                bad.header.state_root = [0xBA; 32];
+                self.outbox.push((
+                    None,
+                    Message::Proposal {
+                        round,
+                        proposer: self.id,
+                        block: Box::new(bad),
+                    },
+                ));
+            }
+            _ => self.outbox.push((
+                None,
+                Message::Proposal {
+                    round,
+                    proposer: self.id,
+                    block: Box::new(block),
+                },
+            )),
+        }
+    }
+
+    fn on_message(&mut self, msg: Message) {
+        match msg {
+            Message::Proposal {
+                round,
+                proposer,
+                block,
+            } => {
+                let hash = block.hash();
+                let height = block.header.height;
+                if self.blocks.insert(hash, (*block).clone()).is_none() {
+                    // Gossip: relay every new proposal once.
+                    if self.fault.is_none() {
+                        self.outbox.push((
+                            None,
+                            Message::Proposal {
+                                round,
+                                proposer,
+                                block,
+                            },
+                        ));
+                    }
+                    if self.fault == Some(Fault::Equivocate) {
+                        for step in [Step::Prevote, Step::Precommit] {
+                            self.outbox.push((
+                                None,
+                                Message::Vote {
+                                    step,
+                                    height,
+                                    round,
+                                    voter: self.id,
+                                    hash: Some(hash),
+                                },
+                            ));
+                        }
+                    }
+                }
+                if proposer == self.proposer(height, round) {
+                    self.proposals.entry((height, round)).or_insert(hash);
+                }
+            }
+            Message::Vote {
+                step,
+                height,
+                round,
+                voter,
+                hash,
+            } => {
+                let tally = match step {
+                    Step::Prevote => &mut self.prevotes,
+                    Step::Precommit => &mut self.precommits,
+                    Step::Propose => return,
+                };
+                tally
+                    .entry((height, round))
+                    .or_default()
+                    .entry(hash)
+                    .or_default()
+                    .insert(voter);
+                self.voters
+                    .entry((height, round))
+                    .or_default()
+                    .insert(voter);
+            }
+        }
+    }
+
+    fn on_tick(&mut self, now: u64) {
+        if now < self.deadline {
+            return;
+        }
+        self.vote(Step::Prevote, self.round, None);
+        self.vote(Step::Precommit, self.round, None);
+        self.round += 1;
+        self.deadline = now + BASE_TIMEOUT * (self.round as u64 + 1);
+    }
+
+    /// Apply protocol rules until none fires.
+    async fn step(&mut self, now: u64) {
+        loop {
+            let (height, round, quorum) = (self.height, self.round, self.quorum());
+
+            // Finalize on a precommit quorum from any round.
+            let decided: Vec<Hash> = self
+                .precommits
+                .iter()
+                .filter(|((h, _), _)| *h == height)
+                .flat_map(|(_, votes)| votes.iter())
+                .filter_map(|(hash, voters)| hash.filter(|_| voters.len() >= quorum))
+                .collect();
+            let mut finalized = false;
+            for hash in decided {
+                if self.validate(hash).await == Some(true) {
+                    self.finalized.insert(height, hash);
+                    self.height += 1;
+                    self.round = 0;
+                    self.locked = None;
+                    self.deadline = now + BASE_TIMEOUT;
+                    finalized = true;
+                    break;
+                }
+            }
+            if finalized {
+                continue;
+            }
+
+            // Skip ahead once more than f validators are in a later round.
+            let skip = self
+                .voters
+                .iter()
+                .filter(|((h, r), voters)| {
+                    *h == height && *r > round && voters.len() > self.max_faulty()
+                })
+                .map(|((_, r), _)| *r)
+                .max();
+            if let Some(r) = skip {
+                self.round = r;
+                self.deadline = now + BASE_TIMEOUT * (r as u64 + 1);
+                continue;
+            }
+
+            if self.proposer(height, round) == self.id
+                && self.sent.insert((Step::Propose, height, round))
+            {
+                self.propose().await;
+            }
+            if self.fault == Some(Fault::Equivocate) {
+                return;
+            }
+
+            if !self.sent.contains(&(Step::Prevote, height, round)) {
+                if let Some(hash) = self.proposals.get(&(height, round)).copied() {
+                    if let Some(valid) = self.validate(hash).await {
+                        let unlocked = match self.locked {
+                            None => true,
+                            Some((_, locked)) if locked == hash => true,
+                            Some((locked_round, _)) => (locked_round + 1..round).any(|r| {
+                                Self::count(&self.prevotes, height, r, Some(hash)) >= quorum
+                            }),
+                        };
+                        self.vote(
+                            Step::Prevote,
+                            round,
+                            Some(hash).filter(|_| valid && unlocked),
+                        );
+                    }
+                }
+            }
+
+            if !self.sent.contains(&(Step::Precommit, height, round)) {
+                let polka = self
+                    .prevotes
+                    .get(&(height, round))
+                    .into_iter()
+                    .flat_map(|votes| votes.iter())
+                    .find_map(|(hash, voters)| hash.filter(|_| voters.len() >= quorum));
+                if let Some(hash) = polka {
+                    if self.validate(hash).await == Some(true) {
+                        self.locked = Some((round, hash));
+                        self.vote(Step::Precommit, round, Some(hash));
+                    }
+                } else if Self::count(&self.prevotes, height, round, None) >= quorum {
+                    self.vote(Step::Precommit, round, None);
+                }
+            }
+            return;
+        }
+    }
+}
+
+struct Cluster {
+    nodes: Vec<Node>,
+    rng: StdRng,
+    queue: BTreeMap<(u64, u64), (usize, Message)>,
+    seq: u64,
+    now: u64,
+}
+
+impl Cluster {
+    fn new(seed: u64, faults: &[Option<Fault>]) -> Self {
+        let sender = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(sender.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 1_000_000;
+
+        let nodes = faults
+            .iter()
+            .enumerate()
+            .map(|(id, fault)| {
+                let (service, storage) = test_service(&genesis, &format!("byz-{seed}-{id}"));
+                Node {
+                    id,
+                    n: faults.len(),
+                    fault: *fault,
+                    service,
+                    storage,
+                    sender: sender.clone(),
+                    height: 1,
+                    round: 0,
+                    deadline: BASE_TIMEOUT,
+                    locked: None,
+                    sent: HashSet::new(),
+                    blocks: HashMap::new(),
+                    validity: HashMap::new(),
+                    proposals: HashMap::new(),
+                    prevotes: HashMap::new(),
+                    precommits: HashMap::new(),
+                    voters: HashMap::new(),
+                    finalized: BTreeMap::new(),
+                    outbox: Vec::new(),
+                }
+            })
+            .collect();
+        Self {
+            nodes,
+            rng: StdRng::seed_from_u64(seed),
+            queue: BTreeMap::new(),
+            seq: 0,
+            now: 0,
+        }
+    }
+
+    fn flush(&mut self, from: usize) {
+        let max_delay = if self.nodes[from].fault == Some(Fault::DelayGossip) {
+            SLOW_MAX_DELAY
+        } else {
+            HONEST_MAX_DELAY
+        };
+        for (to, msg) in std::mem::take(&mut self.nodes[from].outbox) {
+            let recipients = match to {
+                Some(to) => vec![to],
+                None => (0..self.nodes.len()).collect(),
+            };
+            for to in recipients {
+                let delay = if to == from {
+                    0
+                } else {
+                    self.rng.gen_range(1..=max_delay)
+                };
+                self.seq += 1;
+                self.queue
+                    .insert((self.now + delay, self.seq), (to, msg.clone()));
+            }
+        }
+    }
+
+    fn honest(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter().filter(|n| n.fault.is_none())
+    }
+
+    /// Run until every honest node has finalized `target` heights or
+    /// `max_ticks` elapse.
+    async fn run(&mut self, target: u64, max_ticks: u64) {
+        while self.now < max_ticks {
+            while let Some(entry) = self.queue.first_entry() {
+                if entry.key().0 > self.now {
+                    break;
+                }
+                let (to, msg) = entry.remove();
+                self.nodes[to].on_message(msg);
+                self.nodes[to].step(self.now).await;
+                self.flush(to);
+            }
+            for id in 0..self.nodes.len() {
+                self.nodes[id].on_tick(self.now);
+                self.nodes[id].step(self.now).await;
+                self.flush(id);
+            }
+            if self.honest().all(|n| n.finalized.len() as u64 >= target) {
+                return;
+            }
+            self.now += 1;
+        }
+    }
+
+    /// No two honest nodes finalized different blocks at the same height.
+    fn assert_safety(&self) {
+        let mut decided: BTreeMap<u64, Hash> = BTreeMap::new();
+        for node in self.honest() {
+            for (height, hash) in &node.finalized {
+                let first = decided.entry(*height).or_insert(*hash);
+                assert_eq!(
+                    first, hash,
+                    "conflicting blocks finalized at height {height}"
+                );
+                // Only blocks that passed the import checks are ever finalized.
+                let block = &node.blocks[hash];
+                let state = node.storage.load_state_by_hash(hash).unwrap().unwrap();
+                assert_eq!(block.header.state_root, state.root_hash());
+            }
+        }
+    }
+}
+
+fn seeded_faults(seed: u64, n: usize) -> Vec<Option<Fault>> {
+    let mut rng = StdRng::seed_from_u64(seed ^ 0xB12A);
+    let mut faults = vec![None; n];
+    let mut placed = 0;
+    while placed < (n - 1) / 3 {
+        let id = rng.gen_range(0..n);
+        if faults[id].is_none() {
+            faults[id] = Some(FAULTS[rng.gen_range(0..FAULTS.len())]);
+            placed += 1;
+        }
+    }
+    faults
+}
+
+#[tokio::test]
+async fn byzantine_minority_preserves_safety_and_liveness() {
+    for seed in 0..12u64 {
+        let n = if seed % 2 == 0 { 4 } else { 7 };
+        let faults = seeded_faults(seed, n);
+        let mut cluster = Cluster::new(seed, &faults);
+        cluster.run(4, 3_000).await;
+
+        cluster.assert_safety();
+        for node in cluster.honest() {
+            assert!(
+                node.finalized.len() >= 4,
+                "seed {seed} faults {faults:?}: node {} finalized only {} blocks",
+                node.id,
+                node.finalized.len()
+            );
+        }
+    }
+}
+
+#[tokio::test]
+async fn every_fault_kind_is_tolerated_alone() {
+    for (i, fault) in FAULTS.iter().enumerate() {
+        // Node 1 proposes height 1 in round 0, so each fault hits first.
+        let mut faults = vec![None; 4];
+        faults[1] = Some(*fault);
+        let mut cluster = Cluster::new(100 + i as u64, &faults);
+        cluster.run(3, 3_000).await;
+
+        cluster.assert_safety();
+        assert!(
+            cluster.honest().all(|n| n.finalized.len() >= 3),
+            "{fault:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn equivocating_minority_never_splits_the_chain() {
+    for seed in 200..206u64 {
+        let faults = vec![
+            None,
+            Some(Fault::Equivocate),
+            None,
+            None,
+            None,
+            Some(Fault::Equivocate),
+            None,
+        ];
+        let mut cluster = Cluster::new(seed, &faults);
+        cluster.run(6, 4_000).await;
+        cluster.assert_safety();
+        assert!(cluster.honest().all(|n| n.finalized.len() >= 6));
+    }
+}
+
+#[tokio::test]
+async fn more_than_one_third_faulty_stalls_without_breaking_safety() {
+    let faults = vec![
+        None,
+        Some(Fault::WithholdVotes),
+        None,
+        Some(Fault::WithholdVotes),
+    ];
+    let mut cluster = Cluster::new(300, &faults);
+    cluster.run(1, 500).await;
+
+    cluster.assert_safety();
+    assert!(cluster.honest().all(|n| n.finalized.is_empty()));
+}
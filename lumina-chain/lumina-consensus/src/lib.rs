@@ -1,50 +1,233 @@
-#[cfg(feature = "malachite")]
 pub mod app;
+#[cfg(test)]
+mod byzantine;
 
-use anyhow::{bail, Context, Result};
-use lumina_execution::{execute_transaction, ExecutionContext};
+use anyhow::{anyhow, bail, Context, Result};
+use app::{
+    Application, BeginBlockRequest, BlockExecutor, CommitResponse, DeliverTxRequest,
+    EndBlockRequest,
+};
 use lumina_network::NetworkCommand;
 use lumina_storage::db::Storage;
 use lumina_types::block::{Block, BlockHeader};
-use lumina_types::state::GlobalState;
+use lumina_types::instruction::StablecoinInstruction;
+use lumina_types::proof::BlockProof;
+use lumina_types::receipt::BlockReceipts;
+use lumina_types::state::{GlobalState, UpgradePlan};
+use lumina_types::stats::SupplySample;
 use lumina_types::transaction::Transaction;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::{mpsc, RwLock};
-use tracing::{error, info, warn};
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::{debug, error, info, info_span, warn, Instrument};
 
-/// Epoch length in blocks for velocity reward epochs
-const EPOCH_LENGTH: u64 = 8640; // ~1 day at 10s/block
+/// Intent record for a block commit, fsynced before the multi-key write
+/// (block, meta, state, receipts, canonical index, tip). If one is present
+/// at startup the commit was interrupted and the block is re-imported; all
+/// commit writes are idempotent puts.
+#[derive(Serialize, Deserialize)]
+struct CommitIntent {
+    block: Block,
+}
+
+struct CommitWal {
+    path: PathBuf,
+}
+
+impl CommitWal {
+    fn record(&self, block: &Block) -> Result<()> {
+        let bytes = bincode::serialize(&CommitIntent {
+            block: block.clone(),
+        })?;
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write-then-rename so a torn write never leaves a corrupt record.
+        let tmp = self.path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(&bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<Block>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let intent: CommitIntent = bincode::deserialize(&fs::read(&self.path)?)?;
+        Ok(Some(intent.block))
+    }
+
+    fn clear(&self) -> Result<()> {
+        if self.path.exists() {
+            fs::remove_file(&self.path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Governance-scheduled upgrades this binary implements. Import and block
+/// production halt at the height of any scheduled plan not listed here.
+pub const SUPPORTED_UPGRADES: &[&str] = &[];
+
+/// The first upgrade scheduled in `state` that is active at `height` but not
+/// in `supported`.
+pub fn required_upgrade<'a>(
+    state: &'a GlobalState,
+    height: u64,
+    supported: &[&str],
+) -> Option<&'a UpgradePlan> {
+    state
+        .upgrade_plans
+        .iter()
+        .filter(|plan| plan.height <= height && !supported.contains(&plan.name.as_str()))
+        .min_by_key(|plan| plan.height)
+}
+
+/// Fault injection for recovery testing; everything is off by default.
+#[derive(Debug, Clone, Default)]
+pub struct ChaosConfig {
+    /// Percent of blocks from the network discarded unseen
+    pub drop_blocks_pct: u8,
+    /// Pause between logging a commit intent and the commit writes
+    pub delay_commit: Duration,
+    /// Abort the process part way through committing this many-th block,
+    /// leaving the intent in the WAL
+    pub crash_after: Option<u64>,
+}
+
+impl ChaosConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.drop_blocks_pct > 0 || !self.delay_commit.is_zero() || self.crash_after.is_some()
+    }
+
+    fn drop_block(&self) -> bool {
+        self.drop_blocks_pct > 0 && rand::thread_rng().gen_range(0..100) < self.drop_blocks_pct
+    }
+}
+
+/// Sync and mempool progress, published for node introspection.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsensusStatus {
+    pub height: u64,
+    pub mempool_size: usize,
+    /// Highest block height seen from the network
+    pub highest_seen_height: u64,
+    /// Set once the chain reaches an upgrade this binary does not support
+    pub upgrade_required: Option<String>,
+    /// Time to build and commit the last block this node proposed
+    pub last_block_millis: u64,
+}
+
+impl ConsensusStatus {
+    /// Behind a block we have seen but could not yet import.
+    pub fn syncing(&self) -> bool {
+        self.highest_seen_height > self.height
+    }
+}
 
 pub struct ConsensusService {
     state: Arc<RwLock<GlobalState>>,
+    snapshot: watch::Sender<Arc<GlobalState>>,
     storage: Arc<Storage>,
+    wal: CommitWal,
     network_tx: mpsc::Sender<NetworkCommand>,
     tx_rx: mpsc::Receiver<Transaction>,
     block_rx: mpsc::Receiver<Block>,
     mempool: Vec<Transaction>,
     seen_blocks: HashSet<[u8; 32]>,
+    status: watch::Sender<ConsensusStatus>,
+    explorer_indexes: bool,
+    produce_blocks: bool,
+    chaos: ChaosConfig,
+    /// Blocks this service has started committing, for `chaos.crash_after`
+    commits: AtomicU64,
 }
 
 impl ConsensusService {
     pub fn new(
         state: Arc<RwLock<GlobalState>>,
+        snapshot: watch::Sender<Arc<GlobalState>>,
         storage: Arc<Storage>,
         network_tx: mpsc::Sender<NetworkCommand>,
         tx_rx: mpsc::Receiver<Transaction>,
         block_rx: mpsc::Receiver<Block>,
+        wal_path: impl AsRef<Path>,
     ) -> Self {
         Self {
             state,
+            snapshot,
             storage,
+            wal: CommitWal {
+                path: wal_path.as_ref().to_path_buf(),
+            },
             network_tx,
             tx_rx,
             block_rx,
             mempool: Vec::new(),
             seen_blocks: HashSet::new(),
+            status: watch::channel(ConsensusStatus::default()).0,
+            explorer_indexes: true,
+            produce_blocks: true,
+            chaos: ChaosConfig::default(),
+            commits: AtomicU64::new(0),
         }
     }
 
+    /// Whether to keep the indexes only explorer queries need (instruction
+    /// and account transaction indexes, memo transfers, supply stats). Lean
+    /// validators turn them off and leave them to `lumina-indexer`.
+    pub fn with_explorer_indexes(mut self, enabled: bool) -> Self {
+        self.explorer_indexes = enabled;
+        self
+    }
+
+    /// Whether to admit transactions and propose blocks. Followers only
+    /// import blocks from peers.
+    pub fn with_block_production(mut self, enabled: bool) -> Self {
+        self.produce_blocks = enabled;
+        self
+    }
+
+    /// Inject faults to exercise WAL replay, tip repair and reorg handling.
+    pub fn with_chaos(mut self, chaos: ChaosConfig) -> Self {
+        self.chaos = chaos;
+        self
+    }
+
+    pub fn status(&self) -> watch::Receiver<ConsensusStatus> {
+        self.status.subscribe()
+    }
+
+    /// Refuse to execute `height` on `parent_state` if it activates an
+    /// unsupported upgrade, flagging the halt in the published status.
+    fn check_upgrade(&self, parent_state: &GlobalState, height: u64) -> Result<()> {
+        let Some(plan) = required_upgrade(parent_state, height, SUPPORTED_UPGRADES) else {
+            return Ok(());
+        };
+        let required = format!("{} at height {}", plan.name, plan.height);
+        self.status
+            .send_modify(|s| s.upgrade_required = Some(required.clone()));
+        bail!("Upgrade {} required", required);
+    }
+
+    fn publish_status(&self, height: u64) {
+        let mempool_size = self.mempool.len();
+        self.status.send_modify(|status| {
+            status.height = height;
+            status.mempool_size = mempool_size;
+            status.highest_seen_height = status.highest_seen_height.max(height);
+        });
+    }
+
     pub async fn run(mut self) {
         info!("Starting Consensus Service...");
 
@@ -52,12 +235,16 @@ impl ConsensusService {
         let mut current_height = 0u64;
         let mut last_block_hash = [0u8; 32];
 
+        // Finish any block commit interrupted by a crash before reading the tip
+        self.recover_from_wal().await;
+
         // Load canonical chain tip from storage for crash recovery
         if let Ok(Some((h, hash))) = self.storage.load_tip() {
             current_height = h;
             last_block_hash = hash;
             info!("Recovered chain tip at height {}", current_height);
         }
+        self.publish_status(current_height);
 
         // Ensure genesis state snapshot exists
         {
@@ -84,9 +271,23 @@ impl ConsensusService {
         loop {
             tokio::select! {
                 Some(tx) = self.tx_rx.recv() => {
+                    if !self.produce_blocks {
+                        continue;
+                    }
+                    info_span!("tx.mempool", tx_id = %hex::encode(tx.id()))
+                        .in_scope(|| debug!(mempool_size = self.mempool.len() + 1, "accepted"));
                     self.mempool.push(tx);
+                    self.publish_status(current_height);
                 }
                 Some(block) = self.block_rx.recv() => {
+                    if self.chaos.drop_block() {
+                        warn!("Chaos: dropped block {}", block.header.height);
+                        continue;
+                    }
+                    let seen = block.header.height;
+                    self.status.send_modify(|s| {
+                        s.highest_seen_height = s.highest_seen_height.max(seen)
+                    });
                     let bh = block.hash();
                     if self.seen_blocks.insert(bh) {
                         match self.import_block_and_maybe_reorg(&block).await {
@@ -103,6 +304,7 @@ impl ConsensusService {
                             }
                         }
                     }
+                    self.publish_status(current_height);
                 }
                 _ = interval.tick() => {
                     if self.mempool.is_empty() {
@@ -110,6 +312,8 @@ impl ConsensusService {
                     }
 
                     let txs: Vec<Transaction> = self.mempool.drain(..).collect();
+                    self.publish_status(current_height);
+                    let started = std::time::Instant::now();
                     let height = current_height.saturating_add(1);
                     info!(
                         "Consensus: Proposing block {} with {} txs",
@@ -140,6 +344,10 @@ impl ConsensusService {
                             continue;
                         }
                     };
+                    if let Err(e) = self.check_upgrade(&parent_state, height) {
+                        error!("Halting block production: {}", e);
+                        continue;
+                    }
 
                     let proposed = match build_block_from_parent(
                         parent_state,
@@ -147,7 +355,10 @@ impl ConsensusService {
                         height,
                         last_block_hash,
                         timestamp,
-                    ) {
+                    )
+                    .instrument(info_span!("block.build", height))
+                    .await
+                    {
                         Ok(b) => b,
                         Err(e) => {
                             error!("Failed to build block {}: {}", height, e);
@@ -182,18 +393,49 @@ impl ConsensusService {
                             error!("Block import failed at height {}: {}", height, e);
                         }
                     }
+                    let millis = started.elapsed().as_millis() as u64;
+                    self.status.send_modify(|s| s.last_block_millis = millis);
+                    self.publish_status(current_height);
                 }
             }
         }
     }
 
-    async fn import_block_and_maybe_reorg(&self, block: &Block) -> Result<bool> {
-        let block_hash = block.hash();
+    async fn recover_from_wal(&self) {
+        match self.wal.load() {
+            Ok(None) => return,
+            Ok(Some(block)) => {
+                warn!(
+                    "Recovering interrupted commit of block {}",
+                    block.header.height
+                );
+                if let Err(e) = self.import_block(&block).await {
+                    error!("WAL recovery failed: {}", e);
+                }
+            }
+            Err(e) => error!("Unreadable consensus WAL: {}", e),
+        }
+        if let Err(e) = self.wal.clear() {
+            error!("Failed to clear consensus WAL: {}", e);
+        }
+    }
 
+    async fn import_block_and_maybe_reorg(&self, block: &Block) -> Result<bool> {
         // Fast-path: already imported
-        if self.storage.load_block_meta(&block_hash)?.is_some() {
+        if self.storage.load_block_meta(&block.hash())?.is_some() {
             return Ok(false);
         }
+        let span = info_span!(
+            "block.import",
+            height = block.header.height,
+            hash = %hex::encode(block.hash())
+        );
+        self.import_block(block).instrument(span).await
+    }
+
+    /// Validate, execute and commit a block, then apply fork choice.
+    async fn import_block(&self, block: &Block) -> Result<bool> {
+        let block_hash = block.hash();
 
         if block.header.height == 0 {
             bail!("Invalid block height 0");
@@ -201,10 +443,8 @@ impl ConsensusService {
 
         // Ensure parent is known (or genesis)
         let parent_hash = block.header.prev_hash;
-        if block.header.height > 1 {
-            if self.storage.load_block_meta(&parent_hash)?.is_none() {
-                bail!("Unknown parent block");
-            }
+        if block.header.height > 1 && self.storage.load_block_meta(&parent_hash)?.is_none() {
+            bail!("Unknown parent block");
         }
 
         // Verify tx root
@@ -221,61 +461,124 @@ impl ConsensusService {
                 .load_state_by_hash(&parent_hash)?
                 .ok_or_else(|| anyhow::anyhow!("Missing parent state (by hash)"))?
         };
+        self.check_upgrade(&parent_state, block.header.height)?;
 
-        // Execute txs to compute expected state root
-        let mut next_state = parent_state;
-        {
-            let mut ctx = ExecutionContext {
-                state: &mut next_state,
-                height: block.header.height,
-                timestamp: block.header.timestamp,
-            };
-            for tx in &block.transactions {
-                execute_transaction(tx, &mut ctx)?;
-            }
-
-            // End-of-block: verify flash mints are fully burned
-            if next_state.pending_flash_mints > 0 {
-                bail!(
-                    "Unresolved flash mints: {} LUSD not burned",
-                    next_state.pending_flash_mints
-                );
-            }
-
-            // Epoch transition for velocity rewards
-            if block.header.height % EPOCH_LENGTH == 0 {
-                next_state.current_epoch = next_state
-                    .current_epoch
-                    .checked_add(1)
-                    .unwrap_or(next_state.current_epoch);
-            }
-        }
+        let (next_state, commit) = execute_block(parent_state, block).await?;
+        let commit_hash = commit.app_hash;
+        let receipts = commit.receipts;
+        let audit = commit.audit;
 
-        let expected_state_root = next_state.root_hash();
-        if block.header.state_root != expected_state_root {
+        if block.header.state_root != commit_hash {
             bail!("Invalid state_root");
         }
+        if block.header.validator_set_hash != next_state.validator_set_hash() {
+            bail!("Invalid validator_set_hash");
+        }
+        if block.header.audit_hash != next_state.audit_head {
+            bail!("Invalid audit_hash");
+        }
 
-        // Persist fork block
+        // Persist fork block (intent logged first so a crash mid-commit is replayed)
+        self.wal.record(block)?;
+        if !self.chaos.delay_commit.is_zero() {
+            tokio::time::sleep(self.chaos.delay_commit).await;
+        }
         self.storage.save_block(block)?;
         self.storage
             .save_block_meta(block_hash, block.header.height, parent_hash)?;
         self.storage.save_state_by_hash(block_hash, &next_state)?;
+        if let Some(limit) = self.chaos.crash_after {
+            if self.commits.fetch_add(1, Ordering::SeqCst) + 1 >= limit {
+                error!(
+                    "Chaos: crashing mid-commit of block {}",
+                    block.header.height
+                );
+                std::process::abort();
+            }
+        }
+        self.storage.save_block_receipts(block_hash, &receipts)?;
+        let proofs = BlockProof::extract(block);
+        if !proofs.is_empty() {
+            self.storage.save_block_proofs(block_hash, &proofs)?;
+        }
+        if !audit.is_empty() {
+            self.storage.save_block_audit(block_hash, &audit)?;
+        }
+        for (index, tx) in block.transactions.iter().enumerate() {
+            self.storage
+                .add_tx_location(tx.id(), block_hash, index as u32)?;
+        }
+        if self.explorer_indexes {
+            self.save_explorer_indexes(block_hash, block, &receipts, &next_state)?;
+        }
 
         // Fork-choice: choose best tip by (height, hash)
         let (cur_tip_h, cur_tip_hash) = self.storage.load_tip()?.unwrap_or((0, [0u8; 32]));
         let better = (block.header.height > cur_tip_h)
             || (block.header.height == cur_tip_h && block_hash > cur_tip_hash);
-        if !better {
-            return Ok(false);
+        if better {
+            // Reorg canonical mapping
+            self.reorg_to_tip(block_hash, block.header.height).await?;
+        }
+        self.wal.clear()?;
+        for tx in &block.transactions {
+            info_span!(
+                "tx.commit",
+                tx_id = %hex::encode(tx.id()),
+                height = block.header.height,
+                canonical = better
+            )
+            .in_scope(|| debug!("committed"));
+        }
+        Ok(better)
+    }
+
+    fn save_explorer_indexes(
+        &self,
+        block_hash: [u8; 32],
+        block: &Block,
+        receipts: &BlockReceipts,
+        next_state: &GlobalState,
+    ) -> Result<()> {
+        let height = block.header.height;
+        if !block.transactions.is_empty() {
+            self.storage
+                .save_block_instructions(block_hash, &block.instruction_index())?;
         }
+        for (index, tx) in block.transactions.iter().enumerate() {
+            if let StablecoinInstruction::TransferWithMemo { to, memo_hash, .. } = &tx.instruction {
+                self.storage.add_memo_transfer(*to, *memo_hash, tx.id())?;
+            }
+            for address in tx_accounts(tx, index as u32, receipts) {
+                self.storage.add_account_tx(&address, height, &tx.id())?;
+            }
+        }
+        self.storage
+            .save_block_supply(block_hash, &SupplySample::from_block(block, next_state))
+    }
 
-        // Reorg canonical mapping
-        self.reorg_to_tip(block_hash, block.header.height).await?;
-        Ok(true)
+    /// Add a block's supply sample to its bucket as it becomes canonical, or
+    /// take it out as it is reorged away.
+    fn shift_supply_bucket(&self, block_hash: &[u8; 32], canonical: bool) -> Result<()> {
+        let Some(sample) = self.storage.load_block_supply(block_hash)? else {
+            return Ok(());
+        };
+        let mut bucket = self
+            .storage
+            .load_supply_bucket(sample.bucket())?
+            .unwrap_or_default();
+        if canonical {
+            bucket.add(&sample);
+        } else {
+            bucket.remove(&sample);
+        }
+        self.storage.save_supply_bucket(sample.bucket(), &bucket)
     }
 
     async fn reorg_to_tip(&self, new_tip_hash: [u8; 32], new_tip_height: u64) -> Result<()> {
+        // Walk back to the first ancestor already canonical; below it the
+        // mapping is unchanged, and a node bootstrapped from a checkpoint
+        // has no blocks there.
         let mut chain: Vec<(u64, [u8; 32])> = Vec::new();
         let mut cursor_hash = new_tip_hash;
         loop {
@@ -283,6 +586,14 @@ impl ConsensusService {
                 .storage
                 .load_block_meta(&cursor_hash)?
                 .ok_or_else(|| anyhow::anyhow!("Missing block meta during reorg"))?;
+            let canonical = self
+                .storage
+                .iterate_canonical_hashes(h..h + 1)?
+                .first()
+                .map(|(_, hash)| *hash);
+            if canonical == Some(cursor_hash) {
+                break;
+            }
             chain.push((h, cursor_hash));
             if h <= 1 {
                 break;
@@ -292,6 +603,17 @@ impl ConsensusService {
         chain.reverse();
 
         for (h, hash) in &chain {
+            let previous = self
+                .storage
+                .iterate_canonical_hashes(*h..*h + 1)?
+                .first()
+                .map(|(_, hash)| *hash);
+            if previous != Some(*hash) {
+                if let Some(previous) = previous {
+                    self.shift_supply_bucket(&previous, false)?;
+                }
+                self.shift_supply_bucket(hash, true)?;
+            }
             self.storage.save_canonical_block_at_height(*h, *hash)?;
             let st = self
                 .storage
@@ -307,6 +629,9 @@ impl ConsensusService {
         self.storage.save_state(&tip_state)?;
         self.storage.save_tip(new_tip_height, new_tip_hash)?;
 
+        // Publish the read-only snapshot before taking the write lock so
+        // queries never wait on it.
+        self.snapshot.send_replace(Arc::new(tip_state.clone()));
         {
             let mut guard = self.state.write().await;
             *guard = tip_state;
@@ -316,49 +641,865 @@ impl ConsensusService {
     }
 }
 
-fn build_block_from_parent(
-    mut parent_state: GlobalState,
+/// Accounts a transaction concerns: its sender, a transfer recipient and
+/// every account named by the events it emitted.
+fn tx_accounts(tx: &Transaction, index: u32, receipts: &BlockReceipts) -> Vec<[u8; 32]> {
+    let mut accounts = vec![tx.sender];
+    if let StablecoinInstruction::Transfer { to, .. }
+    | StablecoinInstruction::TransferWithMemo { to, .. } = &tx.instruction
+    {
+        accounts.push(*to);
+    }
+    for logged in &receipts.events {
+        if logged.tx_index == Some(index) {
+            accounts.extend(logged.event.addresses());
+        }
+    }
+    accounts.sort();
+    accounts.dedup();
+    accounts
+}
+
+/// Drive one block through `app` (begin_block, deliver_tx, end_block, commit).
+/// With `skip_failed`, failing transactions are left out of the block
+/// (proposing); otherwise any failure rejects the block (importing).
+async fn run_block<A: Application + Send>(
+    app: &mut A,
+    header: &BlockHeader,
+    signers: Vec<[u8; 32]>,
     txs: Vec<Transaction>,
-    height: u64,
-    prev_hash: [u8; 32],
-    timestamp: u64,
-) -> Result<Block> {
-    let mut valid_txs = Vec::new();
+    skip_failed: bool,
+) -> Result<(Vec<Transaction>, CommitResponse)> {
+    app.begin_block(BeginBlockRequest {
+        height: header.height,
+        proposer: header.proposer,
+        timestamp: header.timestamp,
+        signers,
+    })
+    .await
+    .map_err(|e| anyhow!(e))?;
 
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut parent_state,
-            height,
-            timestamp,
-        };
+    let mut included = Vec::with_capacity(txs.len());
+    for tx in txs {
+        let bytes = bincode::serialize(&tx)?;
+        let span = info_span!("tx.execute", tx_id = %hex::encode(tx.id()), height = header.height);
+        match app
+            .deliver_tx(DeliverTxRequest { tx: bytes })
+            .instrument(span)
+            .await
+        {
+            Ok(()) => included.push(tx),
+            Err(e) if skip_failed => warn!("Tx execution failed during block build: {}", e),
+            Err(e) => bail!(e),
+        }
+    }
 
-        for tx in txs {
-            match execute_transaction(&tx, &mut ctx) {
-                Ok(()) => valid_txs.push(tx),
-                Err(e) => {
-                    warn!("Tx execution failed during block build: {}", e);
-                }
-            }
+    // Flash settlement, epoch transition, staged validator changes, health index
+    app.end_block(EndBlockRequest {
+        height: header.height,
+    })
+    .await
+    .map_err(|e| anyhow!(e))?;
+    let commit = app.commit().await.map_err(|e| anyhow!(e))?;
+    Ok((included, commit))
+}
+
+/// Check a block's commit votes: one per validator of `parent_state`, in
+/// ascending key order, each signing the parent's hash, and matching the
+/// header's `signers_hash`.
+pub fn verify_votes(parent_state: &GlobalState, block: &Block) -> Result<()> {
+    if block.header.signers_hash != Block::signers_hash(&block.votes) {
+        bail!("Invalid signers_hash");
+    }
+    for (i, vote) in block.votes.iter().enumerate() {
+        if i > 0 && block.votes[i - 1].validator >= vote.validator {
+            bail!("Duplicate or unordered vote");
+        }
+        if !parent_state
+            .validators
+            .iter()
+            .any(|v| v.pubkey == vote.validator)
+        {
+            bail!("Vote from non-validator {}", hex::encode(vote.validator));
         }
+        lumina_crypto::signatures::verify_signature(
+            &vote.validator,
+            &block.header.prev_hash,
+            &vote.signature,
+        )
+        .with_context(|| format!("Invalid vote from {}", hex::encode(vote.validator)))?;
     }
+    Ok(())
+}
+
+/// Re-execute `block` on top of `parent_state` through the Application
+/// interface, rejecting it if a commit vote is invalid or any transaction
+/// fails. The remaining header commitments are left for the caller to check
+/// against the returned state.
+pub async fn execute_block(
+    parent_state: GlobalState,
+    block: &Block,
+) -> Result<(GlobalState, CommitResponse)> {
+    verify_votes(&parent_state, block)?;
+    let mut app = BlockExecutor::new(parent_state, block.header.height.saturating_sub(1));
+    let signers = block.votes.iter().map(|v| v.validator).collect();
+    let txs = block.transactions.clone();
+    let (_, commit) = run_block(&mut app, &block.header, signers, txs, false).await?;
+    Ok((app.state, commit))
+}
+
+async fn build_block_from_parent(
+    parent_state: GlobalState,
+    txs: Vec<Transaction>,
+    height: u64,
+    prev_hash: [u8; 32],
+    timestamp: u64,
+) -> Result<Block> {
+    let mut header = BlockHeader {
+        height,
+        prev_hash,
+        transactions_root: [0u8; 32],
+        state_root: [0u8; 32],
+        timestamp,
+        proposer: [0u8; 32],
+        validator_set_hash: [0u8; 32],
+        audit_hash: [0u8; 32],
+        signers_hash: [0u8; 32],
+    };
+    let mut app = BlockExecutor::new(parent_state, height - 1);
+    let (valid_txs, commit) = run_block(&mut app, &header, Vec::new(), txs, true).await?;
 
     if valid_txs.is_empty() {
         bail!("No valid transactions");
     }
 
-    let transactions_root = Block::transactions_root(&valid_txs);
-    let state_root = parent_state.root_hash();
+    header.transactions_root = Block::transactions_root(&valid_txs);
+    header.state_root = commit.app_hash;
+    header.validator_set_hash = app.state.validator_set_hash();
+    header.audit_hash = app.state.audit_head;
 
     Ok(Block {
-        header: BlockHeader {
-            height,
-            prev_hash,
-            transactions_root,
-            state_root,
-            timestamp,
-            proposer: [0u8; 32],
-        },
+        header,
         transactions: valid_txs,
         votes: Vec::new(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_crypto::signatures::SigningKey;
+    use lumina_types::instruction::{AssetType, StablecoinInstruction};
+
+    pub(crate) fn signed(
+        key: &SigningKey,
+        nonce: u64,
+        instruction: StablecoinInstruction,
+    ) -> Transaction {
+        let mut tx = Transaction {
+            sender: key.verifying_key().to_bytes(),
+            nonce,
+            instruction,
+            signature: Vec::new(),
+            gas_limit: 0,
+            gas_price: 0,
+        };
+        tx.signature = lumina_crypto::signatures::sign(key, &tx.signing_bytes());
+        tx
+    }
+
+    pub(crate) fn test_service(
+        genesis: &GlobalState,
+        name: &str,
+    ) -> (ConsensusService, Arc<Storage>) {
+        let storage = Arc::new(Storage::new("unused").unwrap());
+        storage.save_state_at_height(0, genesis).unwrap();
+        let wal_path = std::env::temp_dir().join(format!(
+            "lumina-consensus-{}-{}/consensus.wal",
+            name,
+            std::process::id()
+        ));
+        let (network_tx, _network_rx) = mpsc::channel(1);
+        let (_tx_sender, tx_rx) = mpsc::channel(1);
+        let (_block_sender, block_rx) = mpsc::channel(1);
+        let (snapshot, _) = watch::channel(Arc::new(genesis.clone()));
+        let service = ConsensusService::new(
+            Arc::new(RwLock::new(genesis.clone())),
+            snapshot,
+            storage.clone(),
+            network_tx,
+            tx_rx,
+            block_rx,
+            wal_path,
+        );
+        (service, storage)
+    }
+
+    #[tokio::test]
+    async fn wal_recovery_completes_interrupted_commit() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let (service, storage) = test_service(&genesis, "wal");
+
+        let tx = signed(
+            &key,
+            0,
+            StablecoinInstruction::Transfer {
+                to: [9u8; 32],
+                amount: 40,
+                asset: AssetType::LUSD,
+            },
+        );
+        let block = build_block_from_parent(genesis, vec![tx], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        let block_hash = block.hash();
+
+        // Crash after the intent and the first writes: meta alone would make
+        // the fast path skip this block forever.
+        service.wal.record(&block).unwrap();
+        storage.save_block(&block).unwrap();
+        storage.save_block_meta(block_hash, 1, [0u8; 32]).unwrap();
+        assert!(!service.import_block_and_maybe_reorg(&block).await.unwrap());
+        assert_eq!(storage.load_tip().unwrap(), None);
+
+        service.recover_from_wal().await;
+        assert_eq!(storage.load_tip().unwrap(), Some((1, block_hash)));
+        assert!(storage.load_state_by_hash(&block_hash).unwrap().is_some());
+        assert_eq!(
+            service.state.read().await.accounts[&[9u8; 32]].lusd_balance,
+            40
+        );
+        assert!(!service.wal.path.exists());
+    }
+
+    #[tokio::test]
+    async fn unburned_flash_mint_does_not_leak_into_next_block() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let minter = key.verifying_key().to_bytes();
+        let accomplice = [8u8; 32];
+        let genesis = GlobalState {
+            stabilization_pool_balance: 1_000_000,
+            total_lusd_supply: 1_000_000,
+            ..Default::default()
+        };
+        let (service, storage) = test_service(&genesis, "flash");
+
+        let flash_mint = |nonce| {
+            signed(
+                &key,
+                nonce,
+                StablecoinInstruction::FlashMint {
+                    amount: 1_000,
+                    collateral_asset: AssetType::Lumina,
+                    collateral_amount: 1_200,
+                    commitment: [1u8; 32],
+                },
+            )
+        };
+        let leak = signed(
+            &key,
+            1,
+            StablecoinInstruction::Transfer {
+                to: accomplice,
+                amount: 600,
+                asset: AssetType::LUSD,
+            },
+        );
+        let block1 = build_block_from_parent(genesis, vec![flash_mint(0), leak], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        assert!(service.import_block_and_maybe_reorg(&block1).await.unwrap());
+
+        // end_block ran before the state root: the mint was clawed back.
+        let state1 = storage.load_state_by_hash(&block1.hash()).unwrap().unwrap();
+        assert_eq!(block1.header.state_root, state1.root_hash());
+        assert_eq!(state1.pending_flash_mints, 0);
+        assert!(state1.flash_minters.is_empty());
+        assert_eq!(state1.accounts[&minter].pending_flash_mint, 0);
+        assert_eq!(state1.accounts[&minter].lusd_balance, 0);
+        assert_eq!(state1.total_lusd_supply, 1_000_600);
+        assert!(state1.health_index > 0);
+
+        // A fresh flash mint in the next block starts from zero.
+        let block2 = build_block_from_parent(state1, vec![flash_mint(2)], 2, block1.hash(), 2)
+            .await
+            .unwrap();
+        assert!(service.import_block_and_maybe_reorg(&block2).await.unwrap());
+        let state2 = storage.load_state_by_hash(&block2.hash()).unwrap().unwrap();
+        assert_eq!(state2.pending_flash_mints, 0);
+        assert_eq!(state2.accounts[&minter].lusd_balance, 0);
+        assert_eq!(state2.total_lusd_supply, 1_000_600);
+
+        let receipts = storage
+            .load_block_receipts(&block1.hash())
+            .unwrap()
+            .unwrap();
+        assert!(receipts.events.iter().any(|e| e.tx_index.is_none()
+            && matches!(
+                e.event,
+                lumina_types::ChainEvent::FlashMintClawedBack { .. }
+            )));
+    }
+
+    #[tokio::test]
+    async fn audit_log_is_committed_in_the_block_header() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let genesis = GlobalState {
+            stabilization_pool_balance: 1_000_000,
+            total_lusd_supply: 1_000_000,
+            ..Default::default()
+        };
+        let (service, storage) = test_service(&genesis, "audit");
+
+        let flash_mint = signed(
+            &key,
+            0,
+            StablecoinInstruction::FlashMint {
+                amount: 1_000,
+                collateral_asset: AssetType::Lumina,
+                collateral_amount: 1_200,
+                commitment: [1u8; 32],
+            },
+        );
+        let block = build_block_from_parent(genesis, vec![flash_mint], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+
+        let mut forged = block.clone();
+        forged.header.audit_hash = [9u8; 32];
+        let err = service.import_block(&forged).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid audit_hash"));
+
+        assert!(service.import_block_and_maybe_reorg(&block).await.unwrap());
+        let state = storage.load_state_by_hash(&block.hash()).unwrap().unwrap();
+        assert_eq!(block.header.audit_hash, state.audit_head);
+
+        // The unburned flash mint is clawed back at end of block.
+        let records = storage.load_block_audit(&block.hash()).unwrap().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tx_index, Some(0));
+        assert_eq!(records[1].tx_index, None);
+        assert!(matches!(
+            records[1].action,
+            lumina_types::audit::AuditAction::Burn { amount: 1_000, .. }
+        ));
+        assert_eq!(
+            lumina_types::audit::AuditRecord::verify_chain([0u8; 32], &records),
+            Ok(block.header.audit_hash)
+        );
+    }
+
+    #[tokio::test]
+    async fn committed_blocks_index_transactions_by_instruction() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let genesis = GlobalState {
+            stabilization_pool_balance: 1_000_000,
+            total_lusd_supply: 1_000_000,
+            ..Default::default()
+        };
+        let (service, storage) = test_service(&genesis, "instructions");
+        let txs = vec![
+            signed(
+                &key,
+                0,
+                StablecoinInstruction::FlashMint {
+                    amount: 1_000,
+                    collateral_asset: AssetType::Lumina,
+                    collateral_amount: 1_200,
+                    commitment: [1u8; 32],
+                },
+            ),
+            signed(&key, 1, StablecoinInstruction::FlashBurn { amount: 1_000 }),
+        ];
+        let ids: Vec<_> = txs.iter().map(|tx| tx.id()).collect();
+        let block = build_block_from_parent(genesis, txs, 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        assert!(service.import_block_and_maybe_reorg(&block).await.unwrap());
+
+        let index = storage
+            .load_block_instructions(&block.hash())
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            index,
+            lumina_types::block::InstructionIndex::from([
+                ("FlashBurn".to_string(), vec![ids[1]]),
+                ("FlashMint".to_string(), vec![ids[0]]),
+            ])
+        );
+        assert_eq!(
+            storage.load_tx_locations(&ids[1]).unwrap(),
+            [(block.hash(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn committed_transfers_are_indexed_by_recipient_and_memo() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let (service, storage) = test_service(&genesis, "memo");
+        let (shop, memo) = ([9u8; 32], [42u8; 32]);
+        let pay = |nonce, memo: Option<[u8; 32]>| {
+            let instruction = match memo {
+                Some(memo_hash) => StablecoinInstruction::TransferWithMemo {
+                    to: shop,
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                    memo_hash,
+                },
+                None => StablecoinInstruction::Transfer {
+                    to: shop,
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                },
+            };
+            signed(&key, nonce, instruction)
+        };
+        let txs = vec![pay(0, Some(memo)), pay(1, None), pay(2, Some(memo))];
+        let ids: Vec<_> = txs.iter().map(|tx| tx.id()).collect();
+        let block = build_block_from_parent(genesis, txs, 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        assert!(service.import_block_and_maybe_reorg(&block).await.unwrap());
+
+        assert_eq!(
+            storage.load_memo_transfers(&shop, &memo).unwrap(),
+            [ids[0], ids[2]]
+        );
+        assert!(storage
+            .load_memo_transfers(&[8u8; 32], &memo)
+            .unwrap()
+            .is_empty());
+
+        let mut sorted = ids.clone();
+        sorted.sort();
+        let shop_txs = storage.iterate_account_txs(&shop, None, 10).unwrap();
+        assert_eq!(
+            shop_txs,
+            sorted.iter().rev().map(|id| (1, *id)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            storage
+                .iterate_account_txs(&key.verifying_key().to_bytes(), None, 2)
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(storage
+            .iterate_account_txs(&shop, Some((1, sorted[0])), 10)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            storage
+                .iterate_account_txs(&shop, Some(shop_txs[0]), 10)
+                .unwrap(),
+            shop_txs[1..]
+        );
+    }
+
+    /// A running service built like `test_service`, with the senders kept.
+    #[allow(clippy::type_complexity)]
+    fn running_service(
+        genesis: &GlobalState,
+        name: &str,
+        configure: impl FnOnce(ConsensusService) -> ConsensusService,
+    ) -> (
+        Arc<Storage>,
+        mpsc::Sender<Transaction>,
+        mpsc::Sender<Block>,
+        watch::Receiver<ConsensusStatus>,
+    ) {
+        let storage = Arc::new(Storage::new("unused").unwrap());
+        storage.save_state_at_height(0, genesis).unwrap();
+        let (network_tx, _network_rx) = mpsc::channel(1);
+        let (tx_sender, tx_rx) = mpsc::channel(1);
+        let (block_sender, block_rx) = mpsc::channel(1);
+        let (snapshot, _) = watch::channel(Arc::new(genesis.clone()));
+        let service = configure(ConsensusService::new(
+            Arc::new(RwLock::new(genesis.clone())),
+            snapshot,
+            storage.clone(),
+            network_tx,
+            tx_rx,
+            block_rx,
+            std::env::temp_dir().join(format!(
+                "lumina-consensus-{}-{}/consensus.wal",
+                name,
+                std::process::id()
+            )),
+        ));
+        let status = service.status();
+        tokio::spawn(service.run());
+        (storage, tx_sender, block_sender, status)
+    }
+
+    #[tokio::test]
+    async fn chaos_drops_network_blocks_unseen() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let tx = signed(
+            &key,
+            0,
+            StablecoinInstruction::Transfer {
+                to: [9u8; 32],
+                amount: 10,
+                asset: AssetType::LUSD,
+            },
+        );
+        let block = build_block_from_parent(genesis.clone(), vec![tx], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        let dropping = ChaosConfig {
+            drop_blocks_pct: 100,
+            ..Default::default()
+        };
+        assert!(dropping.is_enabled() && !ChaosConfig::default().is_enabled());
+        let (storage, _tx_sender, block_sender, status) =
+            running_service(&genesis, "chaos-drop", |s| s.with_chaos(dropping));
+        block_sender.send(block.clone()).await.unwrap();
+        block_sender.send(block.clone()).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert_eq!(storage.load_tip().unwrap(), None);
+        assert_eq!(status.borrow().highest_seen_height, 0);
+
+        // A delayed commit still lands.
+        let delaying = ChaosConfig {
+            delay_commit: std::time::Duration::from_millis(50),
+            ..Default::default()
+        };
+        let (storage, _tx_sender, block_sender, mut status) =
+            running_service(&genesis, "chaos-delay", |s| s.with_chaos(delaying));
+        block_sender.send(block.clone()).await.unwrap();
+        status.wait_for(|s| s.height == 1).await.unwrap();
+        assert_eq!(storage.load_tip().unwrap(), Some((1, block.hash())));
+    }
+
+    #[tokio::test]
+    async fn followers_import_blocks_but_drop_transactions() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let (storage, tx_sender, block_sender, mut status) =
+            running_service(&genesis, "follower", |s| s.with_block_production(false));
+
+        let transfer = |nonce| {
+            signed(
+                &key,
+                nonce,
+                StablecoinInstruction::Transfer {
+                    to: [9u8; 32],
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                },
+            )
+        };
+        tx_sender.send(transfer(0)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let block = build_block_from_parent(genesis, vec![transfer(0)], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        block_sender.send(block.clone()).await.unwrap();
+
+        let imported = status.wait_for(|s| s.height == 1).await.unwrap().clone();
+        assert_eq!(imported.mempool_size, 0);
+        assert_eq!(storage.load_tip().unwrap(), Some((1, block.hash())));
+    }
+
+    #[tokio::test]
+    async fn forged_or_stripped_votes_are_rejected() {
+        use lumina_crypto::signatures::{generate_keypair, sign};
+        use lumina_types::block::Vote;
+        use lumina_types::state::ValidatorState;
+
+        let key = generate_keypair();
+        let mut validators: Vec<SigningKey> = (0..2).map(|_| generate_keypair()).collect();
+        validators.sort_by_key(|k| k.verifying_key().to_bytes());
+        let outsider = generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        for v in &validators {
+            genesis.validators.push(ValidatorState {
+                pubkey: v.verifying_key().to_bytes(),
+                stake: 10,
+                power: 10,
+                is_green: false,
+                energy_proof: None,
+                green_verified_at: 0,
+            });
+        }
+        let transfer = |nonce| {
+            signed(
+                &key,
+                nonce,
+                StablecoinInstruction::Transfer {
+                    to: [9u8; 32],
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                },
+            )
+        };
+        let one = build_block_from_parent(genesis.clone(), vec![transfer(0)], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        let (state_one, _) = execute_block(genesis, &one).await.unwrap();
+        let two = build_block_from_parent(state_one.clone(), vec![transfer(1)], 2, one.hash(), 2)
+            .await
+            .unwrap();
+        let vote = |key: &SigningKey, message: &[u8]| Vote {
+            validator: key.verifying_key().to_bytes(),
+            signature: sign(key, message),
+        };
+        let with_votes = |votes: Vec<Vote>| {
+            let mut block = two.clone();
+            block.header.signers_hash = Block::signers_hash(&votes);
+            block.votes = votes;
+            block
+        };
+        let rejection = |block: Block| {
+            let state = state_one.clone();
+            async move { execute_block(state, &block).await.unwrap_err().to_string() }
+        };
+
+        let honest = with_votes(validators.iter().map(|v| vote(v, &one.hash())).collect());
+        execute_block(state_one.clone(), &honest).await.unwrap();
+
+        // Stripping a vote no longer matches the header, and fixing the
+        // header changes the block hash
+        let mut stripped = honest.clone();
+        stripped.votes.pop();
+        assert_eq!(rejection(stripped).await, "Invalid signers_hash");
+        let restamped = with_votes(honest.votes[..1].to_vec());
+        assert_ne!(restamped.hash(), honest.hash());
+
+        let forged = with_votes(vec![vote(&validators[0], &two.hash())]);
+        assert!(rejection(forged).await.starts_with("Invalid vote from"));
+        let foreign = with_votes(vec![vote(&outsider, &one.hash())]);
+        assert!(rejection(foreign)
+            .await
+            .starts_with("Vote from non-validator"));
+        let repeated = with_votes(vec![
+            vote(&validators[0], &one.hash()),
+            vote(&validators[0], &one.hash()),
+        ]);
+        assert_eq!(rejection(repeated).await, "Duplicate or unordered vote");
+    }
+
+    #[tokio::test]
+    async fn blocks_import_on_top_of_a_bootstrapped_checkpoint() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let transfer = |nonce| {
+            signed(
+                &key,
+                nonce,
+                StablecoinInstruction::Transfer {
+                    to: [9u8; 32],
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                },
+            )
+        };
+        let one = build_block_from_parent(genesis.clone(), vec![transfer(0)], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        let (state_one, _) = execute_block(genesis.clone(), &one).await.unwrap();
+        let two = build_block_from_parent(state_one.clone(), vec![transfer(1)], 2, one.hash(), 2)
+            .await
+            .unwrap();
+        let (checkpoint_state, _) = execute_block(state_one, &two).await.unwrap();
+        let three = build_block_from_parent(
+            checkpoint_state.clone(),
+            vec![transfer(2)],
+            3,
+            two.hash(),
+            3,
+        )
+        .await
+        .unwrap();
+
+        // A fresh node holding only genesis and the checkpoint at height 2.
+        let storage = Arc::new(Storage::new("unused").unwrap());
+        lumina_storage::snapshot::StateSnapshot::new(two.clone(), &checkpoint_state)
+            .unwrap()
+            .install(&storage, &genesis, &checkpoint_state)
+            .unwrap();
+        let (service, _) = test_service(&genesis, "bootstrap");
+        let service = ConsensusService {
+            storage: storage.clone(),
+            ..service
+        };
+        assert!(service.import_block_and_maybe_reorg(&three).await.unwrap());
+        assert_eq!(storage.load_tip().unwrap(), Some((3, three.hash())));
+        assert_eq!(
+            storage.load_state_by_height(3).unwrap().unwrap().accounts[&[9u8; 32]].lusd_balance,
+            30
+        );
+    }
+
+    #[tokio::test]
+    async fn lean_services_skip_explorer_indexes() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let (service, storage) = test_service(&genesis, "lean");
+        let service = service.with_explorer_indexes(false);
+        let (shop, memo) = ([9u8; 32], [42u8; 32]);
+        let tx = signed(
+            &key,
+            0,
+            StablecoinInstruction::TransferWithMemo {
+                to: shop,
+                amount: 10,
+                asset: AssetType::LUSD,
+                memo_hash: memo,
+            },
+        );
+        let id = tx.id();
+        let block = build_block_from_parent(genesis, vec![tx], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        assert!(service.import_block_and_maybe_reorg(&block).await.unwrap());
+
+        assert_eq!(storage.load_tx_locations(&id).unwrap(), [(block.hash(), 0)]);
+        assert!(storage
+            .load_memo_transfers(&shop, &memo)
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .iterate_account_txs(&shop, None, 10)
+            .unwrap()
+            .is_empty());
+        assert!(storage
+            .load_block_instructions(&block.hash())
+            .unwrap()
+            .is_none());
+        assert!(storage.load_block_supply(&block.hash()).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn supply_buckets_follow_the_canonical_chain() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        let (service, storage) = test_service(&genesis, "supply");
+        let transfer = || {
+            vec![signed(
+                &key,
+                0,
+                StablecoinInstruction::Transfer {
+                    to: [9u8; 32],
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                },
+            )]
+        };
+        let a = build_block_from_parent(genesis.clone(), transfer(), 1, [0u8; 32], 3_700)
+            .await
+            .unwrap();
+        let b = build_block_from_parent(genesis.clone(), transfer(), 1, [0u8; 32], 7_300)
+            .await
+            .unwrap();
+        let (first, second) = if a.hash() < b.hash() { (a, b) } else { (b, a) };
+        let hour = |block: &Block| block.header.timestamp / lumina_types::stats::BUCKET_SECS;
+        let blocks_in = |hour| {
+            storage
+                .load_supply_bucket(hour)
+                .unwrap()
+                .map_or(0, |bucket| bucket.blocks)
+        };
+        assert!(service.import_block_and_maybe_reorg(&first).await.unwrap());
+        assert_eq!(blocks_in(hour(&first)), 1);
+
+        // The higher-hash sibling takes over height 1 and moves the count
+        assert!(service.import_block_and_maybe_reorg(&second).await.unwrap());
+        assert_eq!(blocks_in(hour(&first)), 0);
+        assert_eq!(blocks_in(hour(&second)), 1);
+    }
+
+    #[tokio::test]
+    async fn unsupported_upgrade_halts_import_at_its_height() {
+        let key = lumina_crypto::signatures::generate_keypair();
+        let mut genesis = GlobalState::default();
+        genesis
+            .accounts
+            .entry(key.verifying_key().to_bytes())
+            .or_default()
+            .lusd_balance = 100;
+        genesis.upgrade_plans.push(UpgradePlan {
+            name: "v2".to_string(),
+            height: 2,
+        });
+        let (service, storage) = test_service(&genesis, "upgrade");
+        let transfer = |nonce| {
+            signed(
+                &key,
+                nonce,
+                StablecoinInstruction::Transfer {
+                    to: [9u8; 32],
+                    amount: 10,
+                    asset: AssetType::LUSD,
+                },
+            )
+        };
+
+        let block1 = build_block_from_parent(genesis, vec![transfer(0)], 1, [0u8; 32], 1)
+            .await
+            .unwrap();
+        assert!(service.import_block_and_maybe_reorg(&block1).await.unwrap());
+        assert_eq!(service.status().borrow().upgrade_required, None);
+
+        let state1 = storage.load_state_by_hash(&block1.hash()).unwrap().unwrap();
+        assert!(required_upgrade(&state1, 2, &["v2"]).is_none());
+        let block2 = build_block_from_parent(state1, vec![transfer(1)], 2, block1.hash(), 2)
+            .await
+            .unwrap();
+        let err = service
+            .import_block_and_maybe_reorg(&block2)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Upgrade v2 at height 2 required");
+        assert_eq!(
+            service.status().borrow().upgrade_required.as_deref(),
+            Some("v2 at height 2")
+        );
+        assert_eq!(storage.load_tip().unwrap(), Some((1, block1.hash())));
+    }
+}
@@ -3,4 +3,5 @@ pub mod hashing;
 pub mod pq;
 pub mod signatures;
 pub mod threshold;
+pub mod view_key;
 pub mod zk;
@@ -73,12 +73,11 @@ pub fn kyber_encapsulate(public_key: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "pq-crypto"))]
 mod tests {
     use super::*;
 
     #[test]
-    #[cfg(feature = "pq-crypto")]
     fn dilithium_sign_and_verify_roundtrip() {
         use pqcrypto_dilithium::dilithium3;
         use pqcrypto_traits::sign::{DetachedSignature as _, PublicKey as _, SecretKey as _};
@@ -0,0 +1,120 @@
+//! Account view keys: read access to confidential balances without spend
+//! capability.
+//!
+//! A view secret is derived one-way from the account's signing secret, so
+//! handing it to an auditor reveals nothing that can sign. Openings of
+//! confidential commitments are sealed to the registered view public key
+//! with an ephemeral Ristretto Diffie-Hellman exchange.
+
+use bulletproofs::PedersenGens;
+use curve25519_dalek_ng::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek_ng::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek_ng::scalar::Scalar;
+
+const VIEW_KEY_CONTEXT: &str = "lumina-chain account view key v1";
+const NOTE_DOMAIN: &[u8] = b"lumina-view-note-v1";
+const OPENING_LEN: usize = 40;
+/// `ephemeral_pubkey[32] || encrypted(value[8] || blinding[32])`
+pub const SEALED_NOTE_LEN: usize = 32 + OPENING_LEN;
+
+/// View secret `index` of the account with `signing_secret`. Rotating to a
+/// new index leaves notes sealed to earlier keys readable only with those.
+pub fn derive_view_secret(signing_secret: &[u8; 32], index: u32) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new_derive_key(VIEW_KEY_CONTEXT);
+    hasher.update(signing_secret);
+    hasher.update(&index.to_le_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+pub fn view_public_key(view_secret: &[u8; 32]) -> [u8; 32] {
+    (Scalar::from_bytes_mod_order(*view_secret) * RISTRETTO_BASEPOINT_POINT)
+        .compress()
+        .to_bytes()
+}
+
+pub fn is_valid_view_public_key(view_pubkey: &[u8; 32]) -> bool {
+    CompressedRistretto(*view_pubkey).decompress().is_some()
+}
+
+fn keystream(
+    shared: &RistrettoPoint,
+    ephemeral_pubkey: &[u8; 32],
+    commitment: &[u8; 32],
+) -> [u8; OPENING_LEN] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(NOTE_DOMAIN);
+    hasher.update(shared.compress().as_bytes());
+    hasher.update(ephemeral_pubkey);
+    hasher.update(commitment);
+    let mut out = [0u8; OPENING_LEN];
+    hasher.finalize_xof().fill(&mut out);
+    out
+}
+
+/// Seal the opening (`value`, `blinding`) of `commitment` to `view_pubkey`.
+/// `ephemeral_secret` must be fresh randomness for every note.
+pub fn seal_note(
+    view_pubkey: &[u8; 32],
+    commitment: &[u8; 32],
+    value: u64,
+    blinding: [u8; 32],
+    ephemeral_secret: [u8; 32],
+) -> Option<Vec<u8>> {
+    let view_point = CompressedRistretto(*view_pubkey).decompress()?;
+    let ephemeral = Scalar::from_bytes_mod_order(ephemeral_secret);
+    let ephemeral_pubkey = (ephemeral * RISTRETTO_BASEPOINT_POINT)
+        .compress()
+        .to_bytes();
+    let keystream = keystream(&(ephemeral * view_point), &ephemeral_pubkey, commitment);
+
+    let mut out = Vec::with_capacity(SEALED_NOTE_LEN);
+    out.extend_from_slice(&ephemeral_pubkey);
+    let opening = value.to_le_bytes().into_iter().chain(blinding);
+    out.extend(opening.zip(keystream).map(|(b, k)| b ^ k));
+    Some(out)
+}
+
+/// Off-chain decryption of a sealed note: the value behind `commitment`,
+/// or `None` if `view_secret` is not the key it was sealed to.
+pub fn open_note(view_secret: &[u8; 32], commitment: &[u8; 32], sealed: &[u8]) -> Option<u64> {
+    if sealed.len() != SEALED_NOTE_LEN {
+        return None;
+    }
+    let (ephemeral_pubkey, ciphertext) = sealed.split_at(32);
+    let ephemeral_pubkey: [u8; 32] = ephemeral_pubkey.try_into().ok()?;
+    let ephemeral_point = CompressedRistretto(ephemeral_pubkey).decompress()?;
+    let shared = Scalar::from_bytes_mod_order(*view_secret) * ephemeral_point;
+    let keystream = keystream(&shared, &ephemeral_pubkey, commitment);
+
+    let opening: Vec<u8> = ciphertext
+        .iter()
+        .zip(keystream)
+        .map(|(b, k)| b ^ k)
+        .collect();
+    let value = u64::from_le_bytes(opening[..8].try_into().ok()?);
+    let blinding = Scalar::from_bytes_mod_order(opening[8..].try_into().ok()?);
+    let recomputed = PedersenGens::default().commit(Scalar::from(value), blinding);
+    (recomputed.compress().to_bytes() == *commitment).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zk::prove_confidential_transfer;
+
+    #[test]
+    fn view_key_opens_confidential_commitments() {
+        let view_secret = derive_view_secret(&[1u8; 32], 0);
+        assert_ne!(view_secret, derive_view_secret(&[1u8; 32], 1));
+        let view_pubkey = view_public_key(&view_secret);
+        assert!(is_valid_view_public_key(&view_pubkey));
+
+        let (commitment, _) = prove_confidential_transfer(1_234, [7u8; 32]);
+        let sealed = seal_note(&view_pubkey, &commitment, 1_234, [7u8; 32], [3u8; 32]).unwrap();
+        assert_eq!(sealed.len(), SEALED_NOTE_LEN);
+        assert_eq!(open_note(&view_secret, &commitment, &sealed), Some(1_234));
+
+        let rotated = derive_view_secret(&[1u8; 32], 1);
+        assert_eq!(open_note(&rotated, &commitment, &sealed), None);
+    }
+}
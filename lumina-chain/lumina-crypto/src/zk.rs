@@ -15,6 +15,10 @@ const MAX_RESERVES: usize = 100;
 const RANGE_BITS: usize = 64;
 const BULLETPROOF_DOMAIN: &[u8] = b"lumina-confidential-transfer-v1";
 const INSURANCE_BULLETPROOF_DOMAIN: &[u8] = b"lumina-insurance-claim-v1";
+const RWA_VALUATION_DOMAIN: &[u8] = b"lumina-rwa-valuation-v1";
+const RWA_VIEW_DOMAIN: &[u8] = b"lumina-rwa-view-v1";
+/// `value[8] || blinding[32]`, encrypted under a view key.
+pub const SEALED_OPENING_LEN: usize = 40;
 
 fn alloc_u64_bits(
     cs: ConstraintSystemRef<Fr>,
@@ -35,7 +39,7 @@ fn alloc_u64_bits(
         )?;
 
         let coeff = Fr::from(1u64 << bit_idx);
-        lc = lc + (coeff, bit);
+        lc += (coeff, bit);
     }
     Ok((lc, RANGE_BITS))
 }
@@ -115,6 +119,13 @@ impl ConstraintSynthesizer<Fr> for RangeProofCircuit {
     }
 }
 
+type ZkParams = (
+    ProvingKey<Bls12_381>,
+    VerifyingKey<Bls12_381>,
+    ProvingKey<Bls12_381>,
+    VerifyingKey<Bls12_381>,
+);
+
 pub struct ZkManager {
     por_pk: ProvingKey<Bls12_381>,
     por_vk: VerifyingKey<Bls12_381>,
@@ -124,12 +135,7 @@ pub struct ZkManager {
 
 impl ZkManager {
     pub fn setup() -> Self {
-        static ZK_PARAMS: OnceLock<(
-            ProvingKey<Bls12_381>,
-            VerifyingKey<Bls12_381>,
-            ProvingKey<Bls12_381>,
-            VerifyingKey<Bls12_381>,
-        )> = OnceLock::new();
+        static ZK_PARAMS: OnceLock<ZkParams> = OnceLock::new();
 
         let (por_pk, por_vk, range_pk, range_vk) = ZK_PARAMS.get_or_init(|| {
             // Deterministic setup so proofs generated by external signers/CLI
@@ -188,6 +194,16 @@ impl ZkManager {
         bytes
     }
 
+    /// blake3 of the compressed PoR verifying key, identifying the setup a
+    /// proof was checked against.
+    pub fn por_verifying_key_hash(&self) -> [u8; 32] {
+        let mut bytes = Vec::new();
+        self.por_vk
+            .serialize_compressed(&mut bytes)
+            .expect("PoR verifying key serialization");
+        *blake3::hash(&bytes).as_bytes()
+    }
+
     pub fn verify_zk_por(&self, proof_bytes: &[u8], total_reserve: u64) -> bool {
         let proof = match Proof::<Bls12_381>::deserialize_compressed(proof_bytes) {
             Ok(p) => p,
@@ -271,8 +287,75 @@ pub fn verify_tax_attestation_proof(period: u64, proof: &[u8]) -> bool {
     verify_bound_context_hash(&blake3::hash(&period.to_le_bytes()).into(), proof)
 }
 
-pub fn verify_multi_jurisdictional_proof(jurisdiction_id: u32, proof: &[u8]) -> bool {
-    verify_bound_context_hash(&blake3::hash(&jurisdiction_id.to_le_bytes()).into(), proof)
+/// Commitment an attester anchors for an institution credential `value`,
+/// such as an LEI or the hash of a license, hidden behind `salt`.
+pub fn credential_commitment(value: &[u8], salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_CREDENTIAL");
+    hasher.update(salt);
+    hasher.update(value);
+    *hasher.finalize().as_bytes()
+}
+
+/// Context a proof that `holder` can open credential `commitment` is bound
+/// to, so the proof discloses nothing about the value and cannot be
+/// replayed by another account.
+pub fn credential_context(commitment: &[u8; 32], holder: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_CREDENTIAL_PROOF");
+    hasher.update(commitment);
+    hasher.update(holder);
+    *hasher.finalize().as_bytes()
+}
+
+pub fn verify_credential_proof(commitment: &[u8; 32], holder: &[u8; 32], proof: &[u8]) -> bool {
+    verify_bound_context_hash(&credential_context(commitment, holder), proof)
+}
+
+/// Context a proof that `subject` belongs to `jurisdiction_id` is bound to,
+/// so it cannot be replayed to tag another account.
+pub fn jurisdiction_context(jurisdiction_id: u32, subject: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_JURISDICTION");
+    hasher.update(&jurisdiction_id.to_le_bytes());
+    hasher.update(subject);
+    *hasher.finalize().as_bytes()
+}
+
+pub fn verify_multi_jurisdictional_proof(
+    jurisdiction_id: u32,
+    subject: &[u8; 32],
+    proof: &[u8],
+) -> bool {
+    verify_bound_context_hash(&jurisdiction_context(jurisdiction_id, subject), proof)
+}
+
+/// Context an attestation that `subject` satisfies compliance circuit
+/// `circuit_id` is bound to. Covers the verifier key, so re-uploading a
+/// circuit invalidates proofs made against the old key.
+pub fn circuit_attestation_context(
+    circuit_id: u64,
+    verifier_key: &[u8],
+    subject: &[u8; 32],
+) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_CIRCUIT_ATTESTATION");
+    hasher.update(&circuit_id.to_le_bytes());
+    hasher.update(verifier_key);
+    hasher.update(subject);
+    *hasher.finalize().as_bytes()
+}
+
+pub fn verify_circuit_attestation(
+    circuit_id: u64,
+    verifier_key: &[u8],
+    subject: &[u8; 32],
+    proof: &[u8],
+) -> bool {
+    verify_bound_context_hash(
+        &circuit_attestation_context(circuit_id, verifier_key, subject),
+        proof,
+    )
 }
 
 /// Build a ZK insurance claim proof envelope.
@@ -326,6 +409,107 @@ pub fn verify_insurance_loss_proof(proof: &[u8], claimed_amount: u64) -> bool {
         .is_ok()
 }
 
+/// Commit to an RWA valuation and prove it is at least `min_value`.
+///
+/// Returns the Pedersen commitment to `value` and a range proof that
+/// `value - min_value` is a non-negative 64-bit integer, or `None` if
+/// `value < min_value`.
+pub fn prove_min_valuation(
+    value: u64,
+    min_value: u64,
+    blinding: [u8; 32],
+) -> Option<([u8; 32], Vec<u8>)> {
+    let excess = value.checked_sub(min_value)?;
+    let blinding_scalar = Scalar::from_bytes_mod_order(blinding);
+    let bp_gens = BulletproofGens::new(RANGE_BITS, 1);
+    let pc_gens = PedersenGens::default();
+    let mut transcript = Transcript::new(RWA_VALUATION_DOMAIN);
+
+    let (proof, _) = RangeProof::prove_single(
+        &bp_gens,
+        &pc_gens,
+        &mut transcript,
+        excess,
+        &blinding_scalar,
+        RANGE_BITS,
+    )
+    .expect("valuation bulletproof generation");
+
+    let commitment = pc_gens.commit(Scalar::from(value), blinding_scalar);
+    Some((commitment.compress().to_bytes(), proof.to_bytes()))
+}
+
+/// Check that `commitment` hides a value of at least `min_value`.
+pub fn verify_min_valuation(commitment: &[u8; 32], min_value: u64, proof: &[u8]) -> bool {
+    let Ok(range_proof) = RangeProof::from_bytes(proof) else {
+        return false;
+    };
+    let Some(point) = CompressedRistretto(*commitment).decompress() else {
+        return false;
+    };
+
+    // Shift the commitment down by the public bound; the result commits to
+    // the excess under the same blinding.
+    let bp_gens = BulletproofGens::new(RANGE_BITS, 1);
+    let pc_gens = PedersenGens::default();
+    let excess = (point - pc_gens.B * Scalar::from(min_value)).compress();
+    let mut transcript = Transcript::new(RWA_VALUATION_DOMAIN);
+
+    range_proof
+        .verify_single(&bp_gens, &pc_gens, &mut transcript, &excess, RANGE_BITS)
+        .is_ok()
+}
+
+/// Attestation over a committed valuation, for listings that keep the
+/// attested value private.
+pub fn verify_sealed_rwa_attestation(proof: &[u8], commitment: &[u8; 32]) -> bool {
+    verify_bound_context_hash(&blake3::hash(commitment).into(), proof)
+}
+
+fn view_keystream(view_key: &[u8; 32], commitment: &[u8; 32]) -> [u8; SEALED_OPENING_LEN] {
+    let mut hasher = blake3::Hasher::new_keyed(view_key);
+    hasher.update(RWA_VIEW_DOMAIN);
+    hasher.update(commitment);
+    let mut out = [0u8; SEALED_OPENING_LEN];
+    hasher.finalize_xof().fill(&mut out);
+    out
+}
+
+/// Encrypt the opening of a valuation commitment under `view_key`, so
+/// whoever the owner shares the key with can recover the value.
+pub fn seal_valuation(
+    view_key: &[u8; 32],
+    commitment: &[u8; 32],
+    value: u64,
+    blinding: [u8; 32],
+) -> Vec<u8> {
+    let mut opening = [0u8; SEALED_OPENING_LEN];
+    opening[..8].copy_from_slice(&value.to_le_bytes());
+    opening[8..].copy_from_slice(&blinding);
+    let keystream = view_keystream(view_key, commitment);
+    opening.iter().zip(keystream).map(|(b, k)| b ^ k).collect()
+}
+
+/// Recover the value behind `commitment` from its sealed opening. Returns
+/// `None` for a wrong view key or an opening that does not match.
+pub fn open_sealed_valuation(
+    view_key: &[u8; 32],
+    commitment: &[u8; 32],
+    sealed: &[u8],
+) -> Option<u64> {
+    if sealed.len() != SEALED_OPENING_LEN {
+        return None;
+    }
+    let keystream = view_keystream(view_key, commitment);
+    let opening: Vec<u8> = sealed.iter().zip(keystream).map(|(b, k)| b ^ k).collect();
+    let value = u64::from_le_bytes(opening[..8].try_into().ok()?);
+    let blinding = Scalar::from_bytes_mod_order(opening[8..].try_into().ok()?);
+    let recomputed = PedersenGens::default()
+        .commit(Scalar::from(value), blinding)
+        .compress();
+    (recomputed.to_bytes() == *commitment).then_some(value)
+}
+
 pub fn verify_credit_score_proof(proof: &[u8]) -> bool {
     verify_bound_context_hash(&blake3::hash(b"credit-score").into(), proof)
 }
@@ -381,6 +565,26 @@ mod tests {
         assert!(!verify_insurance_loss_proof(&proof, 1_235));
     }
 
+    #[test]
+    fn sealed_valuation_proves_lower_bound_and_opens_with_view_key() {
+        let (commitment, proof) = prove_min_valuation(50_000, 40_000, [5u8; 32]).unwrap();
+        assert!(verify_min_valuation(&commitment, 40_000, &proof));
+        assert!(!verify_min_valuation(&commitment, 45_000, &proof));
+        assert!(prove_min_valuation(39_999, 40_000, [5u8; 32]).is_none());
+
+        let view_key = [9u8; 32];
+        let sealed = seal_valuation(&view_key, &commitment, 50_000, [5u8; 32]);
+        assert_eq!(sealed.len(), SEALED_OPENING_LEN);
+        assert_eq!(
+            open_sealed_valuation(&view_key, &commitment, &sealed),
+            Some(50_000)
+        );
+        assert_eq!(
+            open_sealed_valuation(&[8u8; 32], &commitment, &sealed),
+            None
+        );
+    }
+
     #[test]
     fn groth16_por_and_range_work() {
         let manager = ZkManager::setup();
@@ -0,0 +1,157 @@
+use crate::{checked_add_u64, paid_accounts, ExecutionContext};
+use anyhow::{anyhow, bail, Result};
+use lumina_types::archive::{
+    decode_account, encode_account, ArchiveProof, ArchiveTree, ArchivedLeaf, MAX_ARCHIVE_BATCH,
+};
+use lumina_types::balance::Balance;
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::StablecoinInstruction;
+use lumina_types::state::ArchiveBatch;
+use lumina_types::transaction::Transaction;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub fn archive(ctx: &mut ExecutionContext, accounts: &[[u8; 32]]) -> Result<()> {
+    if accounts.is_empty() {
+        bail!("No accounts");
+    }
+    if accounts.len() > MAX_ARCHIVE_BATCH {
+        bail!("At most {} accounts per batch", MAX_ARCHIVE_BATCH);
+    }
+    if accounts.iter().collect::<BTreeSet<_>>().len() != accounts.len() {
+        bail!("Duplicate account");
+    }
+    if let Some(index) = accounts.iter().position(|a| !ctx.state.archivable(a)) {
+        bail!("Account {} of the batch cannot be archived", index);
+    }
+
+    let mut lusd = 0u64;
+    let leaves: Vec<ArchivedLeaf> = accounts
+        .iter()
+        .map(|address| {
+            let account = ctx.state.accounts.remove(address).unwrap_or_default();
+            ctx.state.archive_activity.remove(address);
+            lusd = lusd.saturating_add(account.lusd_balance);
+            (*address, encode_account(&account))
+        })
+        .collect();
+    ctx.state.archived_lusd = ctx.state.archived_lusd.saturating_add(lusd);
+    let root = ArchiveTree::new(&leaves).root();
+    let batch_id = ctx.state.next_archive_batch;
+    ctx.state.next_archive_batch += 1;
+    ctx.state.archive_batches.insert(
+        batch_id,
+        ArchiveBatch {
+            root,
+            height: ctx.height,
+            accounts: leaves.len() as u64,
+        },
+    );
+    for address in accounts {
+        ctx.state.archived_accounts.insert(*address, batch_id);
+    }
+    ctx.state.events.push(ChainEvent::AccountsArchived {
+        height: ctx.height,
+        batch_id,
+        root,
+        accounts: leaves,
+    });
+    Ok(())
+}
+
+fn merge_balances(
+    into: &mut BTreeMap<String, Balance>,
+    from: &BTreeMap<String, Balance>,
+    label: &str,
+) -> Result<()> {
+    for (asset, amount) in from {
+        let balance = into.entry(asset.clone()).or_default();
+        *balance = balance
+            .checked_add_balance(*amount)
+            .ok_or_else(|| anyhow!("{} overflow", label))?;
+    }
+    Ok(())
+}
+
+/// Restore `address` from its archived bytes. Anything paid to it while
+/// archived accrued in a fresh account, whose balances are added back.
+pub fn revive(
+    ctx: &mut ExecutionContext,
+    address: &[u8; 32],
+    bytes: &[u8],
+    proof: &ArchiveProof,
+) -> Result<()> {
+    let Some(batch_id) = ctx.state.archived_accounts.get(address).copied() else {
+        bail!("Account is not archived");
+    };
+    let batch = ctx
+        .state
+        .archive_batches
+        .get(&batch_id)
+        .ok_or_else(|| anyhow!("Unknown archive batch"))?;
+    if !proof.verify(&batch.root, address, bytes) {
+        bail!("Invalid archive proof");
+    }
+    let mut account = decode_account(bytes).ok_or_else(|| anyhow!("Invalid archived account"))?;
+    let archived_lusd = account.lusd_balance;
+    if let Some(live) = ctx.state.accounts.get(address) {
+        account.lusd_balance = checked_add_u64(account.lusd_balance, live.lusd_balance, "Balance")?;
+        account.ljun_balance = checked_add_u64(account.ljun_balance, live.ljun_balance, "Balance")?;
+        account.lumina_balance =
+            checked_add_u64(account.lumina_balance, live.lumina_balance, "Balance")?;
+        merge_balances(
+            &mut account.custom_balances,
+            &live.custom_balances,
+            "Balance",
+        )?;
+        merge_balances(
+            &mut account.bridged_balances,
+            &live.bridged_balances,
+            "Balance",
+        )?;
+    }
+
+    ctx.state.archived_accounts.remove(address);
+    ctx.state.archived_lusd = ctx.state.archived_lusd.saturating_sub(archived_lusd);
+    ctx.state.accounts.insert(*address, account);
+    if ctx.state.archive_policy.inactivity_epochs > 0 {
+        let epoch = ctx.state.current_epoch;
+        ctx.state.archive_activity.insert(*address, epoch);
+    }
+    ctx.state.events.push(ChainEvent::AccountRevived {
+        height: ctx.height,
+        account: *address,
+        batch_id,
+    });
+    Ok(())
+}
+
+/// An archived sender may only revive itself, before its signature and
+/// nonce are checked against the revived state. Returns whether `tx` was
+/// that revival.
+pub fn revive_sender(ctx: &mut ExecutionContext, tx: &Transaction) -> Result<bool> {
+    if !ctx.state.archived_accounts.contains_key(&tx.sender) {
+        return Ok(false);
+    }
+    match &tx.instruction {
+        StablecoinInstruction::ReviveAccount {
+            address,
+            account,
+            proof,
+        } if *address == tx.sender => {
+            revive(ctx, address, account, proof)?;
+            Ok(true)
+        }
+        _ => bail!("Account is archived; revive it first"),
+    }
+}
+
+/// Mark the sender of `tx` and the accounts it paid active this epoch.
+pub fn record_activity(ctx: &mut ExecutionContext, tx: &Transaction) {
+    if ctx.state.archive_policy.inactivity_epochs == 0 {
+        return;
+    }
+    let epoch = ctx.state.current_epoch;
+    for address in std::iter::once(tx.sender).chain(paid_accounts(&tx.instruction)) {
+        ctx.state.archive_activity.insert(address, epoch);
+    }
+}
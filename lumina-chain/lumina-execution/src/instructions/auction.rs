@@ -0,0 +1,209 @@
+use crate::instructions::keeper;
+use crate::{
+    checked_add_u64, checked_sub_u64, collateral_value, credit_collateral, is_liquidatable,
+    recalculate_ratios, ExecutionContext,
+};
+use anyhow::{bail, Result};
+use lumina_math::{Ratio, Rounding};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::CollateralAsset;
+use lumina_types::state::{CollateralAuction, CollateralPosition, KeeperTask};
+
+/// Blocks over which an auction's asking price falls to its floor.
+pub const AUCTION_DURATION_BLOCKS: u64 = 360;
+/// Opening asking price, in bps of the collateral's oracle price.
+pub const AUCTION_START_PRICE_BPS: u64 = 12_000;
+/// Lowest asking price, in bps of the oracle price at the start.
+pub const AUCTION_FLOOR_PRICE_BPS: u64 = 5_000;
+
+const PRICE_SCALE: u128 = 1_000_000;
+
+fn scaled(price: u64, bps: u64) -> u64 {
+    Ratio::from_bps(bps)
+        .apply(price, Rounding::Down)
+        .unwrap_or(u64::MAX)
+}
+
+/// Close an undercollateralized position and auction its collateral, which
+/// stays in custody until it is bought or returned.
+pub fn start(ctx: &mut ExecutionContext, sender: &[u8; 32], position_id: u64) -> Result<()> {
+    let Some(position) = ctx.state.collateral_positions.get(&position_id).cloned() else {
+        bail!("Collateral position not found");
+    };
+    if !is_liquidatable(ctx.state, &position)? {
+        bail!("Position is sufficiently collateralized");
+    }
+    let Some(unit_price) = collateral_value(ctx.state, &position.asset, PRICE_SCALE as u64) else {
+        bail!("No oracle price for collateral");
+    };
+    let auction_id = ctx.state.next_auction_id;
+    ctx.state.next_auction_id = checked_add_u64(auction_id, 1, "Auction id")?;
+
+    ctx.state.collateral_positions.remove(&position_id);
+    if let Some(account) = ctx.state.accounts.get_mut(&position.owner) {
+        account
+            .collateral_position_ids
+            .retain(|id| *id != position_id);
+    }
+    let auction = CollateralAuction {
+        position_id,
+        owner: position.owner,
+        asset: position.asset,
+        collateral_remaining: position.collateral_amount,
+        debt_remaining: position.minted,
+        start_price: scaled(unit_price, AUCTION_START_PRICE_BPS),
+        floor_price: scaled(unit_price, AUCTION_FLOOR_PRICE_BPS).max(1),
+        start_height: ctx.height,
+        end_height: ctx.height.saturating_add(AUCTION_DURATION_BLOCKS),
+    };
+    ctx.state.events.push(ChainEvent::AuctionStarted {
+        height: ctx.height,
+        auction_id,
+        position_id,
+        owner: auction.owner,
+        collateral: auction.collateral_remaining,
+        debt: auction.debt_remaining,
+        start_price: auction.start_price,
+    });
+    let epoch = ctx.state.current_epoch;
+    ctx.state
+        .credit_histories
+        .entry(auction.owner)
+        .or_default()
+        .record_liquidation(epoch);
+    ctx.state.collateral_auctions.insert(auction_id, auction);
+    keeper::reward(ctx, sender, KeeperTask::Liquidation, position.minted);
+    Ok(())
+}
+
+/// Sell `sender` up to `max_collateral` at the current asking price. The
+/// part of the payment that repays the debt is burned; anything above it
+/// goes to the position's owner. Pledged RWA is sold as a whole lot, and
+/// the listing passes to the buyer.
+pub fn bid(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    auction_id: u64,
+    max_collateral: u64,
+) -> Result<()> {
+    let Some(auction) = ctx.state.collateral_auctions.get(&auction_id).cloned() else {
+        bail!("Auction not found");
+    };
+    if ctx.height > auction.end_height {
+        bail!("Auction has ended");
+    }
+    if auction.debt_remaining == 0 || auction.collateral_remaining == 0 {
+        bail!("Auction has nothing left to sell");
+    }
+    let price = auction.price_at(ctx.height);
+    let quantity = if let CollateralAsset::PledgedRWA(rwa_id) = &auction.asset {
+        if max_collateral < auction.collateral_remaining {
+            bail!("Pledged RWA must be bought as a whole lot");
+        }
+        let listing = ctx
+            .state
+            .rwa_listings
+            .get(rwa_id)
+            .ok_or_else(|| anyhow::anyhow!("RWA asset not found"))?;
+        if listing.pledged_amount > auction.collateral_remaining {
+            bail!("RWA listing backs other positions");
+        }
+        auction.collateral_remaining
+    } else {
+        // Collateral that repays the remaining debt, rounded up
+        let covering = (auction.debt_remaining as u128 * PRICE_SCALE).div_ceil(price as u128);
+        (max_collateral as u128)
+            .min(auction.collateral_remaining as u128)
+            .min(covering) as u64
+    };
+    let cost = ((quantity as u128) * (price as u128))
+        .div_ceil(PRICE_SCALE)
+        .min(u64::MAX as u128) as u64;
+    if cost == 0 {
+        bail!("Bid is too small");
+    }
+    let burned = cost.min(auction.debt_remaining);
+    let surplus = cost - burned;
+    let bidder = ctx.state.accounts.entry(*sender).or_default();
+    if bidder.lusd_balance < cost {
+        bail!("Insufficient LUSD for bid");
+    }
+    bidder.lusd_balance -= cost;
+    let owner = ctx.state.accounts.entry(auction.owner).or_default();
+    owner.lusd_balance = owner.lusd_balance.saturating_add(surplus);
+    ctx.state.total_lusd_supply =
+        checked_sub_u64(ctx.state.total_lusd_supply, burned, "LUSD supply")?;
+    ctx.state.stabilization_pool_balance =
+        ctx.state.stabilization_pool_balance.saturating_sub(burned);
+    if let Some(auction) = ctx.state.collateral_auctions.get_mut(&auction_id) {
+        auction.collateral_remaining -= quantity;
+        auction.debt_remaining -= burned;
+    }
+    credit_collateral(ctx, sender, &auction.asset, quantity)?;
+    if let CollateralAsset::PledgedRWA(rwa_id) = &auction.asset {
+        if let Some(listing) = ctx.state.rwa_listings.get_mut(rwa_id) {
+            listing.owner = *sender;
+        }
+    }
+    ctx.state.events.push(ChainEvent::AuctionBid {
+        height: ctx.height,
+        auction_id,
+        bidder: *sender,
+        collateral: quantity,
+        price,
+        paid: cost,
+        burned,
+    });
+    recalculate_ratios(ctx);
+    Ok(())
+}
+
+/// Close a finished auction. Unsold collateral goes back to the owner once
+/// the debt is repaid; an auction that expired with both debt and
+/// collateral left reopens the position so it can be auctioned again.
+pub fn settle(ctx: &mut ExecutionContext, auction_id: u64) -> Result<()> {
+    let Some(auction) = ctx.state.collateral_auctions.get(&auction_id).cloned() else {
+        bail!("Auction not found");
+    };
+    let sold_out = auction.debt_remaining == 0 || auction.collateral_remaining == 0;
+    if !sold_out && ctx.height <= auction.end_height {
+        bail!(
+            "Auction is still running until height {}",
+            auction.end_height
+        );
+    }
+    ctx.state.collateral_auctions.remove(&auction_id);
+
+    let (collateral_returned, bad_debt) = if sold_out {
+        credit_collateral(
+            ctx,
+            &auction.owner,
+            &auction.asset,
+            auction.collateral_remaining,
+        )?;
+        (auction.collateral_remaining, auction.debt_remaining)
+    } else {
+        ctx.state.collateral_positions.insert(
+            auction.position_id,
+            CollateralPosition {
+                owner: auction.owner,
+                asset: auction.asset.clone(),
+                collateral_amount: auction.collateral_remaining,
+                minted: auction.debt_remaining,
+            },
+        );
+        let account = ctx.state.accounts.entry(auction.owner).or_default();
+        account.collateral_position_ids.push(auction.position_id);
+        (0, 0)
+    };
+    ctx.state.events.push(ChainEvent::AuctionSettled {
+        height: ctx.height,
+        auction_id,
+        owner: auction.owner,
+        collateral_returned,
+        position_reopened: !sold_out,
+        bad_debt,
+    });
+    recalculate_ratios(ctx);
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use crate::{beacon_reveal_phase, ExecutionContext};
+use anyhow::{bail, Result};
+use lumina_types::beacon;
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{BeaconContribution, RandomnessBeacon};
+
+fn check_epoch(ctx: &ExecutionContext, epoch: u64) -> Result<()> {
+    if epoch != ctx.state.current_epoch {
+        bail!("Beacon round is for epoch {}", ctx.state.current_epoch);
+    }
+    Ok(())
+}
+
+pub fn commit(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    epoch: u64,
+    commitment: [u8; 32],
+) -> Result<()> {
+    if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Only validators contribute randomness");
+    }
+    check_epoch(ctx, epoch)?;
+    if beacon_reveal_phase(ctx.height) {
+        bail!("Commit phase has ended");
+    }
+    if ctx.state.beacon_round.contains_key(sender) {
+        bail!("Already committed this epoch");
+    }
+    ctx.state.beacon_round.insert(
+        *sender,
+        BeaconContribution {
+            commitment,
+            secret: None,
+        },
+    );
+    Ok(())
+}
+
+/// A committed validator may reveal even if it has since been jailed, so
+/// jailing cannot be used to drop its secret from the mix.
+pub fn reveal(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    epoch: u64,
+    secret: [u8; 32],
+) -> Result<()> {
+    check_epoch(ctx, epoch)?;
+    if !beacon_reveal_phase(ctx.height) {
+        bail!("Reveal phase has not started");
+    }
+    let Some(contribution) = ctx.state.beacon_round.get_mut(sender) else {
+        bail!("No commitment this epoch");
+    };
+    if contribution.secret.is_some() {
+        bail!("Already revealed");
+    }
+    if beacon::commitment(epoch, sender, &secret) != contribution.commitment {
+        bail!("Secret does not match the commitment");
+    }
+    contribution.secret = Some(secret);
+    Ok(())
+}
+
+/// Produce the closing epoch's beacon from the secrets revealed into it,
+/// chained to the previous output. Runs before the epoch counter moves.
+pub fn finalize(ctx: &mut ExecutionContext) {
+    let epoch = ctx.state.current_epoch;
+    let round = std::mem::take(&mut ctx.state.beacon_round);
+    let reveals: Vec<_> = round
+        .iter()
+        .filter_map(|(validator, c)| Some((*validator, c.secret?)))
+        .collect();
+    let withheld: Vec<_> = round
+        .iter()
+        .filter(|(_, c)| c.secret.is_none())
+        .map(|(validator, _)| *validator)
+        .collect();
+    let previous = ctx
+        .state
+        .randomness_beacons
+        .last_key_value()
+        .map_or([0u8; 32], |(_, b)| b.output);
+    let output = beacon::mix(epoch, &previous, &reveals);
+    ctx.state.randomness_beacons.insert(
+        epoch,
+        RandomnessBeacon {
+            output,
+            reveals: reveals.len() as u64,
+        },
+    );
+    ctx.state.events.push(ChainEvent::RandomnessFinalized {
+        height: ctx.height,
+        epoch,
+        output,
+        reveals: reveals.len() as u64,
+        withheld,
+    });
+}
@@ -0,0 +1,91 @@
+use anyhow::{bail, Result};
+use lumina_types::state::{ConcentrationBreach, ConcentrationLimits, GlobalState, PoolAsset};
+use std::collections::BTreeSet;
+
+pub fn validate(limits: &ConcentrationLimits) -> Result<()> {
+    for bps in [limits.max_custodian_bps, limits.max_asset_bps] {
+        if bps == 0 || bps > 10_000 {
+            bail!("Concentration limits must be 1-10000 bps");
+        }
+    }
+    Ok(())
+}
+
+fn exceeds(part: u128, total: u128, limit_bps: u64) -> bool {
+    limit_bps < 10_000 && part * 10_000 > total * limit_bps as u128
+}
+
+/// Reject adding `value` (LUSD) of `asset` to the pool if that puts the
+/// asset above its limit, measured against the valuation of the last ratio
+/// recalculation. Holdings of the asset left unvalued for want of a price
+/// cannot be measured, so they are rejected too.
+pub fn check_asset(state: &GlobalState, asset: &PoolAsset, value: u64) -> Result<()> {
+    let limit = state.concentration_limits.max_asset_bps;
+    let total: u128 = state.pool_valuation.values().map(|v| *v as u128).sum();
+    let held = match state.pool_valuation.get(asset) {
+        Some(held) => *held as u128,
+        None if state.pool_composition.contains_key(asset) => {
+            bail!("No price for collateral asset");
+        }
+        None => 0,
+    };
+    if exceeds(held + value as u128, total + value as u128, limit) {
+        bail!(
+            "Mint would put {} above {} bps of reserves",
+            asset.label(),
+            limit
+        );
+    }
+    Ok(())
+}
+
+/// Reject moving `amount` of reserves to `custodian` if that puts it above
+/// its share of all custodian reserves.
+pub fn check_custodian(state: &GlobalState, custodian: &[u8; 32], amount: u64) -> Result<()> {
+    let limit = state.concentration_limits.max_custodian_bps;
+    let total: u128 = state
+        .custodians
+        .iter()
+        .map(|c| c.reserve_balance as u128)
+        .sum();
+    let held = state
+        .custodians
+        .iter()
+        .find(|c| c.pubkey == *custodian)
+        .map_or(0, |c| c.reserve_balance as u128);
+    if exceeds(held + amount as u128, total, limit) {
+        bail!(
+            "Rotation would put custodian above {} bps of reserves",
+            limit
+        );
+    }
+    Ok(())
+}
+
+/// Custodians and pool assets currently above their limits.
+pub fn breaches(state: &GlobalState) -> BTreeSet<ConcentrationBreach> {
+    let limits = &state.concentration_limits;
+    let reserves: u128 = state
+        .custodians
+        .iter()
+        .map(|c| c.reserve_balance as u128)
+        .sum();
+    let pool: u128 = state.pool_valuation.values().map(|v| *v as u128).sum();
+    let custodians = state
+        .custodians
+        .iter()
+        .filter(|c| {
+            exceeds(
+                c.reserve_balance as u128,
+                reserves,
+                limits.max_custodian_bps,
+            )
+        })
+        .map(|c| ConcentrationBreach::Custodian(c.pubkey));
+    let assets = state
+        .pool_valuation
+        .iter()
+        .filter(|(_, value)| exceeds(**value as u128, pool, limits.max_asset_bps))
+        .map(|(asset, _)| ConcentrationBreach::Asset(asset.clone()));
+    custodians.chain(assets).collect()
+}
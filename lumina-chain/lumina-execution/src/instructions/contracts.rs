@@ -0,0 +1,154 @@
+use crate::ExecutionContext;
+use anyhow::{anyhow, bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{ContractState, GlobalState};
+use lumina_vm::{Host, Module};
+use std::collections::BTreeMap;
+
+/// Most gas a single contract call may request.
+pub const MAX_CONTRACT_GAS: u64 = 10_000_000;
+
+pub fn deploy(ctx: &mut ExecutionContext, deployer: &[u8; 32], code: &[u8]) -> Result<()> {
+    Module::new(code)?;
+    let code_hash = *blake3::hash(code).as_bytes();
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_CONTRACT");
+    hasher.update(deployer);
+    hasher.update(&code_hash);
+    hasher.update(&(ctx.state.contracts.len() as u64).to_le_bytes());
+    let contract = *hasher.finalize().as_bytes();
+    if ctx.state.contracts.contains_key(&contract) {
+        bail!("Contract address already in use");
+    }
+
+    ctx.state.contracts.insert(
+        contract,
+        ContractState {
+            deployer: *deployer,
+            code_hash,
+            code: code.to_vec(),
+            storage: BTreeMap::new(),
+        },
+    );
+    ctx.state.events.push(ChainEvent::ContractDeployed {
+        height: ctx.height,
+        contract,
+        deployer: *deployer,
+        code_hash,
+    });
+    Ok(())
+}
+
+pub fn call(
+    ctx: &mut ExecutionContext,
+    caller: &[u8; 32],
+    contract: &[u8; 32],
+    input: &[u8],
+    gas_limit: u64,
+) -> Result<()> {
+    if gas_limit == 0 || gas_limit > MAX_CONTRACT_GAS {
+        bail!("Invalid contract gas limit");
+    }
+    let code = &ctx
+        .state
+        .contracts
+        .get(contract)
+        .ok_or_else(|| anyhow!("Unknown contract"))?
+        .code;
+    let module = Module::new(code)?;
+
+    let mut host = StateHost {
+        state: ctx.state,
+        caller: *caller,
+        contract: *contract,
+        input,
+        balances: BTreeMap::new(),
+        storage: BTreeMap::new(),
+    };
+    let outcome = lumina_vm::call(&module, &mut host, gas_limit)?;
+    if outcome.status != 0 {
+        bail!("Contract reverted with status {}", outcome.status);
+    }
+
+    // Effects are buffered so a trap or revert leaves state untouched.
+    let StateHost {
+        balances, storage, ..
+    } = host;
+    for (account, balance) in balances {
+        ctx.state.accounts.entry(account).or_default().lusd_balance = balance;
+    }
+    if let Some(state) = ctx.state.contracts.get_mut(contract) {
+        state.storage.extend(storage);
+    }
+    ctx.state.events.push(ChainEvent::ContractCalled {
+        height: ctx.height,
+        contract: *contract,
+        caller: *caller,
+        gas_used: outcome.gas_used,
+    });
+    Ok(())
+}
+
+/// Read-through view of chain state with the call's pending writes.
+struct StateHost<'a> {
+    state: &'a GlobalState,
+    caller: [u8; 32],
+    contract: [u8; 32],
+    input: &'a [u8],
+    balances: BTreeMap<[u8; 32], u64>,
+    storage: BTreeMap<[u8; 32], [u8; 32]>,
+}
+
+impl Host for StateHost<'_> {
+    fn caller(&self) -> [u8; 32] {
+        self.caller
+    }
+
+    fn address(&self) -> [u8; 32] {
+        self.contract
+    }
+
+    fn input(&self) -> &[u8] {
+        self.input
+    }
+
+    fn balance(&self, account: &[u8; 32]) -> u64 {
+        self.balances.get(account).copied().unwrap_or_else(|| {
+            self.state
+                .accounts
+                .get(account)
+                .map_or(0, |a| a.lusd_balance)
+        })
+    }
+
+    fn transfer(&mut self, to: [u8; 32], amount: u64) -> bool {
+        let from = self.balance(&self.contract);
+        let Some(remaining) = from.checked_sub(amount) else {
+            return false;
+        };
+        self.balances.insert(self.contract, remaining);
+        let Some(credited) = self.balance(&to).checked_add(amount) else {
+            self.balances.insert(self.contract, from);
+            return false;
+        };
+        self.balances.insert(to, credited);
+        true
+    }
+
+    fn oracle_price(&self, asset: &str) -> Option<u64> {
+        self.state.oracle_prices.get(asset).copied()
+    }
+
+    fn storage_read(&self, key: &[u8; 32]) -> Option<[u8; 32]> {
+        self.storage.get(key).copied().or_else(|| {
+            self.state
+                .contracts
+                .get(&self.contract)
+                .and_then(|c| c.storage.get(key).copied())
+        })
+    }
+
+    fn storage_write(&mut self, key: [u8; 32], value: [u8; 32]) {
+        self.storage.insert(key, value);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::instructions::pause;
+use crate::{checked_add_u64, ExecutionContext};
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{kind_index, MAX_COUNCIL_MEMBERS};
+use lumina_types::state::{
+    CouncilAction, CouncilActionStatus, CouncilPower, EmergencyCouncil, GlobalState,
+    InstructionBitmap,
+};
+use std::collections::{BTreeSet, HashSet};
+
+/// Longest a council action lasts unratified (~7 days at 10s blocks).
+pub const MAX_COUNCIL_SUNSET_BLOCKS: u64 = 60_480;
+
+/// Instructions moving value across a bridge, stopped by `FreezeBridge`.
+/// Challenges stay open so fraudulent claims can still be struck.
+const BRIDGE_INSTRUCTIONS: &[&str] = &[
+    "SubmitSpvHeaders",
+    "ClaimSpvDeposit",
+    "SendLusdCrossChain",
+    "AttestLusdReturn",
+];
+
+pub fn validate_council(council: &EmergencyCouncil) -> Result<()> {
+    if council.members.len() > MAX_COUNCIL_MEMBERS {
+        bail!("At most {} council members", MAX_COUNCIL_MEMBERS);
+    }
+    let unique: HashSet<_> = council.members.iter().collect();
+    if unique.len() != council.members.len() {
+        bail!("Duplicate council member");
+    }
+    if council.members.is_empty() {
+        return Ok(());
+    }
+    if council.threshold == 0 || council.threshold as usize > council.members.len() {
+        bail!("Council threshold must be 1 to the number of members");
+    }
+    if council.sunset_blocks == 0 || council.sunset_blocks > MAX_COUNCIL_SUNSET_BLOCKS {
+        bail!(
+            "Council sunset must be 1 to {} blocks",
+            MAX_COUNCIL_SUNSET_BLOCKS
+        );
+    }
+    Ok(())
+}
+
+fn validate_power(power: &CouncilPower) -> Result<()> {
+    if let CouncilPower::PauseInstructions(kinds) = power {
+        pause::validate(kinds)?;
+    }
+    Ok(())
+}
+
+fn is_member(state: &GlobalState, account: &[u8; 32]) -> bool {
+    state.emergency_council.members.contains(account)
+}
+
+/// Whether a council action in effect freezes the bridges.
+pub fn bridge_frozen(state: &GlobalState) -> bool {
+    state
+        .council_actions
+        .values()
+        .any(|a| a.in_effect() && a.power == CouncilPower::FreezeBridge)
+}
+
+/// Instructions paused or frozen by council actions in effect.
+pub fn paused(state: &GlobalState) -> InstructionBitmap {
+    let mut paused = InstructionBitmap::default();
+    for action in state.council_actions.values().filter(|a| a.in_effect()) {
+        let kinds: Vec<&str> = match &action.power {
+            CouncilPower::PauseInstructions(kinds) => kinds.iter().map(String::as_str).collect(),
+            CouncilPower::FreezeBridge => BRIDGE_INSTRUCTIONS.to_vec(),
+            CouncilPower::TripBreaker => Vec::new(),
+        };
+        for index in kinds.into_iter().filter_map(kind_index) {
+            paused.insert(index);
+        }
+    }
+    paused
+}
+
+/// Record a member's proposal, which takes effect at once if their
+/// approval alone meets the threshold.
+pub fn propose(ctx: &mut ExecutionContext, sender: &[u8; 32], power: &CouncilPower) -> Result<()> {
+    if !is_member(ctx.state, sender) {
+        bail!("Only council members can propose council actions");
+    }
+    validate_power(power)?;
+    let action_id = ctx.state.next_council_action_id;
+    let next_id = checked_add_u64(action_id, 1, "Council action id")?;
+    let expires_height = checked_add_u64(
+        ctx.height,
+        ctx.state.emergency_council.sunset_blocks,
+        "Council action expiry",
+    )?;
+    ctx.state.next_council_action_id = next_id;
+    ctx.state.council_actions.insert(
+        action_id,
+        CouncilAction {
+            power: power.clone(),
+            proposer: *sender,
+            approvals: BTreeSet::from([*sender]),
+            proposed_height: ctx.height,
+            status: CouncilActionStatus::Proposed,
+            expires_height,
+            breaker_was_active: false,
+        },
+    );
+    ctx.state.events.push(ChainEvent::CouncilActionProposed {
+        height: ctx.height,
+        action_id,
+        member: *sender,
+        power: power.clone(),
+    });
+    activate_if_approved(ctx, action_id)
+}
+
+pub fn approve(ctx: &mut ExecutionContext, sender: &[u8; 32], action_id: u64) -> Result<()> {
+    if !is_member(ctx.state, sender) {
+        bail!("Only council members can approve council actions");
+    }
+    let Some(action) = ctx.state.council_actions.get_mut(&action_id) else {
+        bail!("Council action not found");
+    };
+    if action.status != CouncilActionStatus::Proposed {
+        bail!("Council action is no longer awaiting approval");
+    }
+    if !action.approvals.insert(*sender) {
+        bail!("Council action already approved by this member");
+    }
+    ctx.state.events.push(ChainEvent::CouncilActionApproved {
+        height: ctx.height,
+        action_id,
+        member: *sender,
+    });
+    activate_if_approved(ctx, action_id)
+}
+
+/// Put an action into effect once current members' approvals meet the
+/// threshold; its sunset runs from now.
+fn activate_if_approved(ctx: &mut ExecutionContext, action_id: u64) -> Result<()> {
+    let council = &ctx.state.emergency_council;
+    let action = &ctx.state.council_actions[&action_id];
+    let approvals = action
+        .approvals
+        .iter()
+        .filter(|member| council.members.contains(member))
+        .count();
+    if approvals < council.threshold as usize {
+        return Ok(());
+    }
+    let expires_height = checked_add_u64(ctx.height, council.sunset_blocks, "Council expiry")?;
+    let breaker_was_active = ctx.state.circuit_breaker_active;
+    let action = ctx
+        .state
+        .council_actions
+        .get_mut(&action_id)
+        .expect("council action");
+    action.status = CouncilActionStatus::Active;
+    action.expires_height = expires_height;
+    if action.power == CouncilPower::TripBreaker {
+        action.breaker_was_active = breaker_was_active;
+        ctx.state.circuit_breaker_active = true;
+    }
+    pause::refresh(ctx.state);
+    ctx.state.events.push(ChainEvent::CouncilActionActivated {
+        height: ctx.height,
+        action_id,
+        expires_height,
+    });
+    Ok(())
+}
+
+/// Undo an action's effect. Its pauses and freezes end unless governance
+/// or another action in effect holds the same instructions.
+fn lift(state: &mut GlobalState, action_id: u64, status: CouncilActionStatus) {
+    let action = state
+        .council_actions
+        .get_mut(&action_id)
+        .expect("council action");
+    let was_active = action.in_effect();
+    action.status = status;
+    if was_active && action.power == CouncilPower::TripBreaker && !action.breaker_was_active {
+        state.circuit_breaker_active = false;
+    }
+    pause::refresh(state);
+}
+
+/// Check a ruling can apply: only an action in effect can be lifted, and
+/// only an active one ratified.
+pub fn validate_ruling(state: &GlobalState, action_id: u64, ratify: bool) -> Result<()> {
+    let Some(action) = state.council_actions.get(&action_id) else {
+        bail!("Council action not found");
+    };
+    let open = if ratify {
+        action.status == CouncilActionStatus::Active
+    } else {
+        action.in_effect()
+    };
+    if !open {
+        bail!("Council action is not in effect");
+    }
+    Ok(())
+}
+
+/// Apply a passed `RuleCouncilAction` proposal.
+pub fn rule(
+    ctx: &mut ExecutionContext,
+    proposal_id: u64,
+    action_id: u64,
+    ratify: bool,
+) -> Result<()> {
+    validate_ruling(ctx.state, action_id, ratify)?;
+    if ratify {
+        ctx.state
+            .council_actions
+            .get_mut(&action_id)
+            .expect("council action")
+            .status = CouncilActionStatus::Ratified;
+    } else {
+        lift(ctx.state, action_id, CouncilActionStatus::Rejected);
+    }
+    ctx.state.events.push(ChainEvent::CouncilActionRuled {
+        height: ctx.height,
+        proposal_id,
+        action_id,
+        ratified: ratify,
+    });
+    Ok(())
+}
+
+/// Lapse unratified actions that reached their expiry, whether they took
+/// effect or never gathered enough approvals; runs in `end_block`.
+pub fn sunset(ctx: &mut ExecutionContext) {
+    let due: Vec<(u64, bool)> = ctx
+        .state
+        .council_actions
+        .iter()
+        .filter(|(_, a)| {
+            matches!(
+                a.status,
+                CouncilActionStatus::Proposed | CouncilActionStatus::Active
+            ) && a.expires_height <= ctx.height
+        })
+        .map(|(id, a)| (*id, a.status == CouncilActionStatus::Active))
+        .collect();
+    for (action_id, was_active) in due {
+        lift(ctx.state, action_id, CouncilActionStatus::Lapsed);
+        ctx.state.events.push(ChainEvent::CouncilActionLapsed {
+            height: ctx.height,
+            action_id,
+            was_active,
+        });
+    }
+}
@@ -0,0 +1,131 @@
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_crypto::zk::verify_credential_proof;
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{AnchoredCredential, CredentialKind, GlobalState};
+
+pub fn anchor(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    institution: &[u8; 32],
+    kind: CredentialKind,
+    commitment: &[u8; 32],
+    expires_height: u64,
+) -> Result<()> {
+    if !ctx.state.credential_attesters.contains(sender) {
+        bail!("Sender is not a credential attester");
+    }
+    if *institution == [0u8; 32] || *commitment == [0u8; 32] {
+        bail!("Invalid credential");
+    }
+    if expires_height != 0 && expires_height <= ctx.height {
+        bail!("Credential expiry must be in the future");
+    }
+    ctx.state
+        .institution_credentials
+        .entry(*institution)
+        .or_default()
+        .insert(
+            kind,
+            AnchoredCredential {
+                commitment: *commitment,
+                attester: *sender,
+                anchored_height: ctx.height,
+                expires_height,
+                proven_height: None,
+            },
+        );
+    ctx.state.events.push(ChainEvent::CredentialAnchored {
+        height: ctx.height,
+        institution: *institution,
+        attester: *sender,
+        kind,
+        expires_height,
+    });
+    Ok(())
+}
+
+/// Any trusted attester may revoke a credential, so one whose anchoring
+/// attester has gone silent can still be withdrawn.
+pub fn revoke(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    institution: &[u8; 32],
+    kind: CredentialKind,
+) -> Result<()> {
+    if !ctx.state.credential_attesters.contains(sender) {
+        bail!("Sender is not a credential attester");
+    }
+    let Some(held) = ctx.state.institution_credentials.get_mut(institution) else {
+        bail!("Credential not found");
+    };
+    if held.remove(&kind).is_none() {
+        bail!("Credential not found");
+    }
+    if held.is_empty() {
+        ctx.state.institution_credentials.remove(institution);
+    }
+    ctx.state.events.push(ChainEvent::CredentialRevoked {
+        height: ctx.height,
+        institution: *institution,
+        attester: *sender,
+        kind,
+    });
+    Ok(())
+}
+
+/// Record that `sender` proved it can open its anchored credential of
+/// `kind`, without disclosing the credential.
+pub fn prove(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    kind: CredentialKind,
+    proof: &[u8],
+) -> Result<()> {
+    let height = ctx.height;
+    let Some(credential) = ctx
+        .state
+        .institution_credentials
+        .get_mut(sender)
+        .and_then(|c| c.get_mut(&kind))
+    else {
+        bail!("No {:?} credential anchored for sender", kind);
+    };
+    if !ctx
+        .state
+        .credential_attesters
+        .contains(&credential.attester)
+    {
+        bail!("Credential attester is no longer trusted");
+    }
+    if !credential.is_live(height) {
+        bail!("Credential has expired");
+    }
+    if !verify_credential_proof(&credential.commitment, sender, proof) {
+        bail!("Invalid credential proof");
+    }
+    credential.proven_height = Some(height);
+    ctx.state.events.push(ChainEvent::InstitutionStatusProven {
+        height,
+        institution: *sender,
+        kind,
+    });
+    Ok(())
+}
+
+/// Fail unless `account` has proven a live credential of every kind in
+/// `kinds`.
+pub fn require(
+    state: &GlobalState,
+    account: &[u8; 32],
+    kinds: &[CredentialKind],
+    height: u64,
+) -> Result<()> {
+    if let Some(kind) = kinds
+        .iter()
+        .find(|k| !state.has_credentials(account, std::slice::from_ref(*k), height))
+    {
+        bail!("Account has not proven a {:?} credential", kind);
+    }
+    Ok(())
+}
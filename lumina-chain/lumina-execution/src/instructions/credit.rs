@@ -0,0 +1,295 @@
+use crate::instructions::keeper;
+use crate::{
+    checked_add_u64, checked_sub_u64, ljun_shares_to_value, ljun_value_to_shares,
+    recalculate_ratios, ExecutionContext,
+};
+use anyhow::{bail, Result};
+use lumina_math::split;
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{CreditLine, KeeperTask};
+use std::collections::BTreeMap;
+
+/// Highest yearly interest a credit line may charge.
+pub const MAX_CREDIT_INTEREST_BPS: u64 = 5_000;
+
+/// A copy of the line with interest charged through the current height,
+/// written back only once the instruction has passed its checks.
+fn accrued_line(ctx: &ExecutionContext, line_id: u64) -> Result<CreditLine> {
+    let Some(mut line) = ctx.state.credit_lines.get(&line_id).cloned() else {
+        bail!("Credit line not found");
+    };
+    line.accrue(ctx.height);
+    Ok(line)
+}
+
+/// LUSD value of `shares` LJUN shares at the current exchange rate.
+fn guarantee_value(ctx: &ExecutionContext, shares: u64) -> u64 {
+    ctx.state.ljun_value_of(shares).min(u64::MAX as u128) as u64
+}
+
+pub fn open(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    limit: u64,
+    interest_bps: u64,
+    maturity_height: u64,
+) -> Result<()> {
+    if limit == 0 {
+        bail!("Credit limit must be non-zero");
+    }
+    if interest_bps > MAX_CREDIT_INTEREST_BPS {
+        bail!("Interest above {} bps", MAX_CREDIT_INTEREST_BPS);
+    }
+    if maturity_height <= ctx.height {
+        bail!("Maturity height already passed");
+    }
+    let line_id = ctx.state.next_credit_line_id;
+    ctx.state.next_credit_line_id = checked_add_u64(line_id, 1, "Credit line id")?;
+    ctx.state.credit_lines.insert(
+        line_id,
+        CreditLine {
+            borrower: *sender,
+            limit,
+            interest_bps,
+            maturity_height,
+            principal: 0,
+            interest_owed: 0,
+            accrued_through: ctx.height,
+            guarantors: BTreeMap::new(),
+            defaulted: false,
+        },
+    );
+    ctx.state.events.push(ChainEvent::CreditLineOpened {
+        height: ctx.height,
+        line_id,
+        borrower: *sender,
+        limit,
+    });
+    Ok(())
+}
+
+/// Move `shares` of the sender's LJUN into the line's guarantee.
+pub fn guarantee(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    line_id: u64,
+    shares: u64,
+) -> Result<()> {
+    if shares == 0 {
+        bail!("Shares must be non-zero");
+    }
+    let mut line = accrued_line(ctx, line_id)?;
+    if line.borrower == *sender {
+        bail!("Borrowers cannot guarantee their own line");
+    }
+    if line.defaulted || ctx.height >= line.maturity_height {
+        bail!("Credit line is closed");
+    }
+    let balance = ctx.state.accounts.get(sender).map_or(0, |a| a.ljun_balance);
+    if balance < shares {
+        bail!("Insufficient LJUN balance");
+    }
+    let staked = line.guarantors.entry(*sender).or_default();
+    *staked = checked_add_u64(*staked, shares, "Guarantee")?;
+
+    ctx.state.accounts.entry(*sender).or_default().ljun_balance -= shares;
+    ctx.state.credit_lines.insert(line_id, line);
+    Ok(())
+}
+
+/// Return guarantee shares to the sender, as long as what stays locked
+/// still covers the line's debt.
+pub fn release(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    line_id: u64,
+    shares: u64,
+) -> Result<()> {
+    if shares == 0 {
+        bail!("Shares must be non-zero");
+    }
+    let mut line = accrued_line(ctx, line_id)?;
+    let staked = line.guarantors.get(sender).copied().unwrap_or(0);
+    if staked < shares {
+        bail!("Guarantee smaller than requested release");
+    }
+    let remaining = line.guaranteed_shares() - shares;
+    if guarantee_value(ctx, remaining) < line.debt() {
+        bail!("Guarantee still backs the line's debt");
+    }
+    let balance = ctx.state.accounts.get(sender).map_or(0, |a| a.ljun_balance);
+    let balance = checked_add_u64(balance, shares, "LJUN balance")?;
+
+    if staked == shares {
+        line.guarantors.remove(sender);
+    } else {
+        line.guarantors.insert(*sender, staked - shares);
+    }
+    ctx.state.accounts.entry(*sender).or_default().ljun_balance = balance;
+    // A line that can no longer be drawn is dropped once fully unwound
+    let closed = line.defaulted || ctx.height >= line.maturity_height;
+    if closed && line.guarantors.is_empty() && line.debt() == 0 {
+        ctx.state.credit_lines.remove(&line_id);
+    } else {
+        ctx.state.credit_lines.insert(line_id, line);
+    }
+    Ok(())
+}
+
+/// Mint `amount` LUSD to the borrower, within the line's limit and the
+/// current value of its guarantee.
+pub fn draw(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    line_id: u64,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        bail!("Amount must be non-zero");
+    }
+    if ctx.state.circuit_breaker_active {
+        bail!("Circuit breaker active: credit draws paused");
+    }
+    let mut line = accrued_line(ctx, line_id)?;
+    if line.borrower != *sender {
+        bail!("Only the borrower can draw on a credit line");
+    }
+    if line.defaulted || ctx.height >= line.maturity_height {
+        bail!("Credit line is closed");
+    }
+    // New principal must not be charged for blocks before it was drawn
+    line.accrued_through = ctx.height;
+    line.principal = checked_add_u64(line.principal, amount, "Credit principal")?;
+    if line.principal > line.limit {
+        bail!("Draw exceeds credit limit");
+    }
+    if line.debt() > guarantee_value(ctx, line.guaranteed_shares()) {
+        bail!("Draw exceeds guarantee");
+    }
+    let balance = ctx.state.accounts.get(sender).map_or(0, |a| a.lusd_balance);
+    let balance = checked_add_u64(balance, amount, "LUSD balance")?;
+    let supply = checked_add_u64(ctx.state.total_lusd_supply, amount, "LUSD supply")?;
+
+    ctx.state.credit_lines.insert(line_id, line);
+    ctx.state.accounts.entry(*sender).or_default().lusd_balance = balance;
+    ctx.state.total_lusd_supply = supply;
+    recalculate_ratios(ctx);
+    Ok(())
+}
+
+/// Split `interest` over guarantors by locked shares; `None` when no
+/// guarantor holds any.
+fn guarantor_payouts(
+    guarantors: &BTreeMap<[u8; 32], u64>,
+    interest: u64,
+) -> Option<Vec<([u8; 32], u64)>> {
+    let shares: Vec<u64> = guarantors.values().copied().collect();
+    let payouts = split(interest, &shares).ok()?;
+    Some(guarantors.keys().copied().zip(payouts).collect())
+}
+
+/// Pay up to `amount` of the line's debt from the borrower: accrued
+/// interest goes to the guarantors, principal is burned.
+pub fn repay(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    line_id: u64,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        bail!("Amount must be non-zero");
+    }
+    let mut line = accrued_line(ctx, line_id)?;
+    if line.borrower != *sender {
+        bail!("Only the borrower can repay a credit line");
+    }
+    let amount = amount.min(line.debt());
+    if amount == 0 {
+        bail!("Credit line has no debt");
+    }
+    let balance = ctx.state.accounts.get(sender).map_or(0, |a| a.lusd_balance);
+    if balance < amount {
+        bail!("Insufficient LUSD balance");
+    }
+    let interest = amount.min(line.interest_owed);
+    // Interest with nobody left to receive it is burned with the principal
+    let payouts = guarantor_payouts(&line.guarantors, interest);
+    let burned = if payouts.is_none() {
+        amount
+    } else {
+        amount - interest
+    };
+    let supply = checked_sub_u64(ctx.state.total_lusd_supply, burned, "LUSD supply")?;
+
+    line.interest_owed -= interest;
+    line.principal -= amount - interest;
+    ctx.state.accounts.entry(*sender).or_default().lusd_balance -= amount;
+    for (guarantor, payout) in payouts.unwrap_or_default() {
+        let account = ctx.state.accounts.entry(guarantor).or_default();
+        account.lusd_balance = account.lusd_balance.saturating_add(payout);
+    }
+    ctx.state.total_lusd_supply = supply;
+    ctx.state.credit_lines.insert(line_id, line);
+    let epoch = ctx.state.current_epoch;
+    ctx.state
+        .credit_histories
+        .entry(*sender)
+        .or_default()
+        .record_repayment(amount, epoch);
+    recalculate_ratios(ctx);
+    Ok(())
+}
+
+/// Cover a matured line's unpaid principal by slashing its guarantors pro
+/// rata. The slashed junior value is reassigned to senior backing; accrued
+/// interest, owed to the guarantors themselves, is forgiven.
+pub fn declare_default(ctx: &mut ExecutionContext, sender: &[u8; 32], line_id: u64) -> Result<()> {
+    let mut line = accrued_line(ctx, line_id)?;
+    if line.defaulted || ctx.height < line.maturity_height || line.principal == 0 {
+        bail!("Credit line is not in default");
+    }
+    let total = line.guaranteed_shares();
+    let needed = ljun_value_to_shares(ctx.state, line.principal)?.min(total);
+    let mut slashed_shares = 0u64;
+    if total > 0 {
+        for staked in line.guarantors.values_mut() {
+            let cut = ((needed as u128) * (*staked as u128) / (total as u128)) as u64;
+            *staked -= cut;
+            slashed_shares += cut;
+        }
+        // Rounding leaves less than a share per guarantor still to take
+        for staked in line.guarantors.values_mut() {
+            let cut = (needed - slashed_shares).min(*staked);
+            *staked -= cut;
+            slashed_shares += cut;
+        }
+    }
+    line.guarantors.retain(|_, staked| *staked > 0);
+    let slashed = ljun_shares_to_value(ctx.state, slashed_shares)?;
+    let pool = checked_add_u64(ctx.state.stabilization_pool_balance, slashed, "Pool")?;
+
+    let (borrower, principal) = (line.borrower, line.principal);
+    line.principal = 0;
+    line.interest_owed = 0;
+    line.defaulted = true;
+    ctx.state.credit_lines.insert(line_id, line);
+    ctx.state.total_ljun_shares = ctx.state.total_ljun_shares.saturating_sub(slashed_shares);
+    ctx.state.total_ljun_supply = ctx.state.total_ljun_supply.saturating_sub(slashed);
+    ctx.state.stabilization_pool_balance = pool;
+    let epoch = ctx.state.current_epoch;
+    ctx.state
+        .credit_histories
+        .entry(borrower)
+        .or_default()
+        .record_liquidation(epoch);
+    ctx.state.events.push(ChainEvent::CreditLineDefaulted {
+        height: ctx.height,
+        line_id,
+        borrower,
+        slashed,
+        shortfall: principal.saturating_sub(slashed),
+    });
+    keeper::reward(ctx, sender, KeeperTask::Liquidation, slashed);
+    recalculate_ratios(ctx);
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use crate::instructions::merchant;
+use crate::{checked_add_u64, checked_sub_u64, ExecutionContext};
+use anyhow::{bail, Result};
+use lumina_crypto::signatures::verify_signature;
+use lumina_types::block::Vote;
+use lumina_types::crosschain::{return_key, MessageBatch, OutboundMessage};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::MAX_REMOTE_RECIPIENT_LEN;
+use lumina_types::state::PendingReturn;
+use std::collections::BTreeSet;
+
+/// Blocks a sealed batch stays in state for relayers (~11 days at 10s
+/// blocks); its messages remain in the `LusdSentCrossChain` events.
+pub const MESSAGE_BATCH_RETENTION: u64 = 100_000;
+
+/// Apply a passed `SetRemoteChain` proposal. A stopped chain keeps its
+/// outstanding LUSD so holders can still return it.
+pub fn configure(ctx: &mut ExecutionContext, proposal_id: u64, chain_id: u64, active: bool) {
+    ctx.state.remote_chains.entry(chain_id).or_default().active = active;
+    ctx.state.events.push(ChainEvent::RemoteChainChanged {
+        height: ctx.height,
+        proposal_id,
+        chain_id,
+        active,
+    });
+}
+
+/// Burn the sender's LUSD and queue a message minting it on `dest_chain`.
+pub fn send(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    dest_chain: u64,
+    recipient: &[u8],
+    amount: u64,
+) -> Result<()> {
+    if !ctx
+        .state
+        .remote_chains
+        .get(&dest_chain)
+        .is_some_and(|chain| chain.active)
+    {
+        bail!("Remote chain {} is not active", dest_chain);
+    }
+    if recipient.is_empty() || recipient.len() > MAX_REMOTE_RECIPIENT_LEN {
+        bail!(
+            "Remote recipient must be 1 to {} bytes",
+            MAX_REMOTE_RECIPIENT_LEN
+        );
+    }
+    if amount == 0 {
+        bail!("Amount must be greater than zero");
+    }
+    if ctx.state.accounts.get(sender).map_or(0, |a| a.lusd_balance) < amount {
+        bail!("Insufficient LUSD");
+    }
+    let nonce = ctx.state.next_message_nonce;
+    let next_nonce = checked_add_u64(nonce, 1, "Message nonce")?;
+    let chain = ctx
+        .state
+        .remote_chains
+        .get(&dest_chain)
+        .expect("remote chain");
+    let outstanding = checked_add_u64(chain.outstanding, amount, "Outstanding LUSD")?;
+
+    let account = ctx.state.accounts.entry(*sender).or_default();
+    account.lusd_balance -= amount;
+    ctx.state.total_lusd_supply = ctx.state.total_lusd_supply.saturating_sub(amount);
+    ctx.state
+        .remote_chains
+        .get_mut(&dest_chain)
+        .expect("remote chain")
+        .outstanding = outstanding;
+    ctx.state.next_message_nonce = next_nonce;
+    ctx.state.outbound_messages.push(OutboundMessage {
+        nonce,
+        dest_chain,
+        sender: *sender,
+        recipient: recipient.to_vec(),
+        amount,
+    });
+    ctx.state.events.push(ChainEvent::LusdSentCrossChain {
+        height: ctx.height,
+        nonce,
+        dest_chain,
+        sender: *sender,
+        amount,
+    });
+    Ok(())
+}
+
+/// Seal the block's messages into a batch for validators to sign, and drop
+/// batches past their retention; runs in `end_block`.
+pub fn seal(ctx: &mut ExecutionContext) {
+    while let Some(entry) = ctx.state.message_batches.first_entry() {
+        if entry.get().height.saturating_add(MESSAGE_BATCH_RETENTION) >= ctx.height {
+            break;
+        }
+        entry.remove();
+    }
+    if ctx.state.outbound_messages.is_empty() {
+        return;
+    }
+    let batch_id = ctx.state.next_message_batch_id;
+    ctx.state.next_message_batch_id = batch_id.saturating_add(1);
+    let messages = std::mem::take(&mut ctx.state.outbound_messages);
+    ctx.state.events.push(ChainEvent::MessageBatchSealed {
+        height: ctx.height,
+        batch_id,
+        messages: messages.len() as u64,
+    });
+    ctx.state.message_batches.insert(
+        batch_id,
+        MessageBatch {
+            batch_id,
+            height: ctx.height,
+            messages,
+            signatures: Vec::new(),
+        },
+    );
+}
+
+/// Add a validator's signature to a sealed batch, replacing any earlier one
+/// from it.
+pub fn attest_batch(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    batch_id: u64,
+    signature: &[u8],
+) -> Result<()> {
+    if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Only validators can attest message batches");
+    }
+    let Some(batch) = ctx.state.message_batches.get(&batch_id) else {
+        bail!("Message batch not found");
+    };
+    verify_signature(sender, &batch.signing_bytes(), signature)?;
+
+    let batch = ctx
+        .state
+        .message_batches
+        .get_mut(&batch_id)
+        .expect("message batch");
+    let had_quorum = batch.has_quorum(&ctx.state.validators);
+    batch.signatures.retain(|vote| vote.validator != *sender);
+    batch.signatures.push(Vote {
+        validator: *sender,
+        signature: signature.to_vec(),
+    });
+    if !had_quorum && batch.has_quorum(&ctx.state.validators) {
+        ctx.state.events.push(ChainEvent::MessageBatchAttested {
+            height: ctx.height,
+            batch_id,
+        });
+    }
+    Ok(())
+}
+
+/// Record a validator's attestation of a remote burn, minting the LUSD back
+/// once the attesters hold over two thirds of validator power.
+pub fn attest_return(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    source_chain: u64,
+    burn_id: &[u8; 32],
+    recipient: &[u8; 32],
+    amount: u64,
+) -> Result<()> {
+    if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Only validators can attest LUSD returns");
+    }
+    let Some(chain) = ctx.state.remote_chains.get(&source_chain) else {
+        bail!("Unknown remote chain {}", source_chain);
+    };
+    if amount == 0 {
+        bail!("Amount must be greater than zero");
+    }
+    let key = return_key(source_chain, burn_id);
+    if ctx.state.completed_returns.contains(&key) {
+        bail!("Return already minted");
+    }
+    let mut attesters = match ctx.state.pending_returns.get(&key) {
+        Some(pending) if pending.recipient != *recipient || pending.amount != amount => {
+            bail!("Return differs from earlier attestations");
+        }
+        Some(pending) => pending.attesters.clone(),
+        None => BTreeSet::new(),
+    };
+    attesters.insert(*sender);
+
+    let total: u128 = ctx
+        .state
+        .validators
+        .iter()
+        .map(|v| v.power.max(1) as u128)
+        .sum();
+    let attested: u128 = ctx
+        .state
+        .validators
+        .iter()
+        .filter(|v| attesters.contains(&v.pubkey))
+        .map(|v| v.power.max(1) as u128)
+        .sum();
+    if attested <= total * 2 / 3 {
+        ctx.state.pending_returns.insert(
+            key,
+            PendingReturn {
+                source_chain,
+                burn_id: *burn_id,
+                recipient: *recipient,
+                amount,
+                attesters,
+            },
+        );
+        return Ok(());
+    }
+
+    if amount > chain.outstanding {
+        bail!(
+            "Return exceeds the LUSD outstanding on chain {}",
+            source_chain
+        );
+    }
+    let outstanding = checked_sub_u64(chain.outstanding, amount, "Outstanding LUSD")?;
+    merchant::receive(ctx.state, recipient, amount, 1)?;
+    ctx.state.total_lusd_supply = ctx.state.total_lusd_supply.saturating_add(amount);
+    ctx.state
+        .remote_chains
+        .get_mut(&source_chain)
+        .expect("remote chain")
+        .outstanding = outstanding;
+    ctx.state.pending_returns.remove(&key);
+    ctx.state.completed_returns.insert(key);
+    ctx.state.events.push(ChainEvent::LusdReturnedCrossChain {
+        height: ctx.height,
+        source_chain,
+        burn_id: *burn_id,
+        recipient: *recipient,
+        amount,
+    });
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use crate::{paid_accounts, ExecutionContext};
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{AccountState, DustPolicy, GlobalState};
+use lumina_types::transaction::Transaction;
+
+pub fn validate(policy: &DustPolicy) -> Result<()> {
+    if policy.existential_deposit > 0 && policy.grace_blocks == 0 {
+        bail!("Grace period must be at least one block");
+    }
+    Ok(())
+}
+
+/// An account that never signed a transaction, so removing it cannot
+/// reopen its nonces to replay, and holds nothing but LUSD below `deposit`.
+fn is_dust(account: &AccountState, deposit: u64) -> bool {
+    account.nonce == 0
+        && account.lusd_balance < deposit
+        && account.ljun_balance == 0
+        && account.lumina_balance == 0
+        && account.custom_balances.values().all(|b| b.is_zero())
+        && account.bridged_balances.values().all(|b| b.is_zero())
+        && account.commitment.is_none()
+        && account.passkey_device_key.is_none()
+        && account.guardians.is_empty()
+        && account.pq_pubkey.is_none()
+        && account.active_streams.is_empty()
+        && account.yield_positions.is_empty()
+        && account.pending_flash_mint == 0
+        && account.pending_flash_collateral == 0
+        && account.pending_flash_loan == 0
+        && account.insurance_coverage.is_none()
+        && account.collateral_position_ids.is_empty()
+        && account.credit_score == 0
+}
+
+fn reapable(state: &GlobalState, address: &[u8; 32], deposit: u64) -> bool {
+    !state.contracts.contains_key(address)
+        && !state.archived_accounts.contains_key(address)
+        && state
+            .accounts
+            .get(address)
+            .is_some_and(|a| is_dust(a, deposit))
+}
+
+/// Start the grace period of the accounts `tx` paid that it left as dust.
+pub fn track(ctx: &mut ExecutionContext, tx: &Transaction) {
+    let policy = &ctx.state.dust_policy;
+    if policy.existential_deposit == 0 {
+        return;
+    }
+    let (deposit, due) = (
+        policy.existential_deposit,
+        ctx.height.saturating_add(policy.grace_blocks),
+    );
+    for address in paid_accounts(&tx.instruction) {
+        if ctx.state.dust_accounts.contains_key(&address) || !reapable(ctx.state, &address, deposit)
+        {
+            continue;
+        }
+        ctx.state.dust_accounts.insert(address, due);
+        ctx.state.dust_reaps.entry(due).or_default().insert(address);
+    }
+}
+
+/// Reap the tracked accounts whose grace period ends at this height, unless
+/// they have since been topped up or signed a transaction.
+pub fn reap(ctx: &mut ExecutionContext) {
+    let deposit = ctx.state.dust_policy.existential_deposit;
+    while let Some(entry) = ctx.state.dust_reaps.first_entry() {
+        if *entry.key() > ctx.height {
+            break;
+        }
+        for address in entry.remove() {
+            ctx.state.dust_accounts.remove(&address);
+            if !reapable(ctx.state, &address, deposit) {
+                continue;
+            }
+            let swept = ctx
+                .state
+                .accounts
+                .remove(&address)
+                .map_or(0, |a| a.lusd_balance);
+            let state = &mut *ctx.state;
+            state.archive_activity.remove(&address);
+            state.insurance_fund_balance = state.insurance_fund_balance.saturating_add(swept);
+            state.total_lusd_supply = state.total_lusd_supply.saturating_sub(swept);
+            state.events.push(ChainEvent::AccountReaped {
+                height: ctx.height,
+                account: address,
+                swept,
+            });
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_crypto::zk::verify_circuit_attestation;
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::StablecoinInstruction;
+use lumina_types::state::{
+    CircuitStatus, CircuitVersion, CircuitVersions, ExecutionHook, GlobalState,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Gas charged to start evaluating a hook.
+pub const HOOK_GAS_BASE: u64 = 1_000;
+/// Gas charged per subject checked against the hook's circuit.
+pub const HOOK_GAS_PER_SUBJECT: u64 = 10_000;
+/// Largest gas budget governance may grant a single hook.
+pub const MAX_HOOK_GAS: u64 = 1_000_000;
+/// Longest window a circuit version may give its predecessor to keep
+/// verifying.
+pub const MAX_DEPRECATION_BLOCKS: u64 = 1_000_000;
+
+/// Instructions no hook may guard, so governance and attestation can never
+/// be locked out by a hook that nobody is able to satisfy.
+pub const UNGUARDED: &[&str] = &[
+    "Vote",
+    "ProposeUpgrade",
+    "ProposeHook",
+    "ProposeHookRemoval",
+    "UploadComplianceCircuit",
+    "UploadComplianceCircuitV2",
+    "AttestCompliance",
+    "ProposeComplianceOfficer",
+    "ProposeCreditOracle",
+    "ProposeKycTiers",
+    "ProposeTreasurySpend",
+    "ProposeTreasuryFeeShare",
+    "ProposeValidatorFeeShare",
+    "ProposeHealthParams",
+    "ProposeConcentrationLimits",
+    "ProposeDustPolicy",
+    "ProposeArchivePolicy",
+    "ReviveAccount",
+    "CommitRandomness",
+    "RevealRandomness",
+    "ProposeStabilizerBounds",
+    "ProposeJurisdictionPolicy",
+    "ProposeCredentialAttester",
+    "ProveInstitutionStatus",
+    "ProposeCredentialRequirements",
+    "ProposeProtocolParam",
+];
+
+pub fn validate(state: &GlobalState, hook: &ExecutionHook) -> Result<()> {
+    if hook.name.is_empty() || hook.name.len() > 64 {
+        bail!("Invalid hook name");
+    }
+    if !state.compliance_circuits.contains_key(&hook.circuit_id) {
+        bail!("Unknown compliance circuit");
+    }
+    if hook.instructions.is_empty() {
+        bail!("Hook must guard at least one instruction");
+    }
+    if let Some(kind) = hook
+        .instructions
+        .iter()
+        .find(|k| UNGUARDED.contains(&k.as_str()))
+    {
+        bail!("Hooks cannot guard {}", kind);
+    }
+    if hook.gas_budget < HOOK_GAS_BASE || hook.gas_budget > MAX_HOOK_GAS {
+        bail!("Hook gas budget must be {}-{}", HOOK_GAS_BASE, MAX_HOOK_GAS);
+    }
+    Ok(())
+}
+
+/// The version the next upload of `circuit_id` must carry.
+pub fn next_version(state: &GlobalState, circuit_id: u64) -> u32 {
+    state
+        .circuit_versions
+        .get(&circuit_id)
+        .and_then(|registry| registry.versions.keys().next_back())
+        .map_or(1, |v| v + 1)
+}
+
+/// Upload `version` of `circuit_id`. The first upload makes the sender the
+/// circuit's publisher and activates at once; later versions must come from
+/// the publisher, one at a time and in sequence, and wait for
+/// `activation_height`.
+pub fn upload(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    circuit_id: u64,
+    verifier_key: &[u8],
+    version: u32,
+    activation_height: u64,
+    deprecation_blocks: u64,
+) -> Result<()> {
+    if verifier_key.is_empty() {
+        bail!("Verifier key cannot be empty");
+    }
+    if deprecation_blocks > MAX_DEPRECATION_BLOCKS {
+        bail!(
+            "Deprecation window exceeds {} blocks",
+            MAX_DEPRECATION_BLOCKS
+        );
+    }
+    let height = ctx.height;
+    let Some(registry) = ctx.state.circuit_versions.get_mut(&circuit_id) else {
+        // Circuits uploaded before versioning are re-registered as version
+        // 1, which a bound hook still prevents
+        if let Some(hook) = ctx
+            .state
+            .hooks
+            .values()
+            .find(|h| h.circuit_id == circuit_id)
+        {
+            bail!("Circuit is bound to hook {}", hook.name);
+        }
+        if version != 1 {
+            bail!("Circuit versions start at 1");
+        }
+        let first = CircuitVersion {
+            verifier_key: verifier_key.to_vec(),
+            activation_height: height,
+            deprecation_blocks,
+            status: CircuitStatus::Active,
+            attested: BTreeSet::new(),
+        };
+        ctx.state.circuit_versions.insert(
+            circuit_id,
+            CircuitVersions {
+                publisher: *sender,
+                active: 1,
+                versions: BTreeMap::from([(1, first)]),
+            },
+        );
+        ctx.state.compliance_attestations.remove(&circuit_id);
+        ctx.state
+            .compliance_circuits
+            .insert(circuit_id, verifier_key.to_vec());
+        ctx.state.events.push(ChainEvent::CircuitVersionUploaded {
+            height,
+            circuit_id,
+            version,
+            publisher: *sender,
+            activation_height: height,
+        });
+        ctx.state.events.push(ChainEvent::CircuitVersionActivated {
+            height,
+            circuit_id,
+            version,
+        });
+        return Ok(());
+    };
+    if registry.publisher != *sender {
+        bail!("Only the circuit publisher can upload new versions");
+    }
+    let next = registry.versions.keys().next_back().map_or(1, |v| v + 1);
+    if version != next {
+        bail!("Next version of circuit {} is {}", circuit_id, next);
+    }
+    if registry
+        .versions
+        .values()
+        .any(|v| v.status == CircuitStatus::Pending)
+    {
+        bail!("Circuit {} already has a pending version", circuit_id);
+    }
+    if activation_height < height {
+        bail!("Activation height has passed");
+    }
+    // Attestation proofs are bound to the key, so each version needs its own
+    if registry
+        .versions
+        .values()
+        .any(|v| v.verifier_key == verifier_key)
+    {
+        bail!(
+            "Verifier key is already uploaded for circuit {}",
+            circuit_id
+        );
+    }
+    registry.versions.insert(
+        version,
+        CircuitVersion {
+            verifier_key: verifier_key.to_vec(),
+            activation_height,
+            deprecation_blocks,
+            status: CircuitStatus::Pending,
+            attested: BTreeSet::new(),
+        },
+    );
+    ctx.state.events.push(ChainEvent::CircuitVersionUploaded {
+        height,
+        circuit_id,
+        version,
+        publisher: *sender,
+        activation_height,
+    });
+    Ok(())
+}
+
+/// Record that `sender` satisfies a version of `circuit_id`. Proofs against
+/// a pending version are kept and count once it activates; proofs against a
+/// retired version are rejected.
+pub fn attest(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    circuit_id: u64,
+    proof: &[u8],
+) -> Result<()> {
+    let Some(registry) = ctx.state.circuit_versions.get_mut(&circuit_id) else {
+        let Some(verifier_key) = ctx.state.compliance_circuits.get(&circuit_id) else {
+            bail!("Unknown compliance circuit");
+        };
+        if !verify_circuit_attestation(circuit_id, verifier_key, sender, proof) {
+            bail!("Invalid compliance attestation");
+        }
+        ctx.state
+            .compliance_attestations
+            .entry(circuit_id)
+            .or_default()
+            .insert(*sender);
+        return Ok(());
+    };
+    let Some((version, entry)) = registry
+        .versions
+        .iter_mut()
+        .rev()
+        .find(|(_, v)| verify_circuit_attestation(circuit_id, &v.verifier_key, sender, proof))
+    else {
+        bail!("Invalid compliance attestation");
+    };
+    match entry.status {
+        CircuitStatus::Retired => bail!("Circuit {} version {} is retired", circuit_id, version),
+        CircuitStatus::Pending => {
+            entry.attested.insert(*sender);
+        }
+        CircuitStatus::Active | CircuitStatus::Deprecated { .. } => {
+            entry.attested.insert(*sender);
+            ctx.state
+                .compliance_attestations
+                .entry(circuit_id)
+                .or_default()
+                .insert(*sender);
+        }
+    }
+    Ok(())
+}
+
+/// Activate circuit versions whose activation height has come, deprecating
+/// the version each replaces, and retire deprecated versions whose window
+/// has closed. Run at the end of every block.
+pub fn advance_circuits(ctx: &mut ExecutionContext) {
+    let height = ctx.height;
+    let mut events = Vec::new();
+    for (&circuit_id, registry) in ctx.state.circuit_versions.iter_mut() {
+        let mut changed = false;
+        let due = registry
+            .versions
+            .iter_mut()
+            .find(|(_, v)| v.status == CircuitStatus::Pending && v.activation_height <= height);
+        if let Some((&version, entry)) = due {
+            entry.status = CircuitStatus::Active;
+            let retire_height = height.saturating_add(entry.deprecation_blocks);
+            ctx.state
+                .compliance_circuits
+                .insert(circuit_id, entry.verifier_key.clone());
+            events.push(ChainEvent::CircuitVersionActivated {
+                height,
+                circuit_id,
+                version,
+            });
+            let previous = std::mem::replace(&mut registry.active, version);
+            if let Some(entry) = registry.versions.get_mut(&previous) {
+                entry.status = CircuitStatus::Deprecated { retire_height };
+                events.push(ChainEvent::CircuitVersionDeprecated {
+                    height,
+                    circuit_id,
+                    version: previous,
+                    retire_height,
+                });
+            }
+            changed = true;
+        }
+        for (&version, entry) in registry.versions.iter_mut() {
+            if matches!(entry.status, CircuitStatus::Deprecated { retire_height } if retire_height <= height)
+            {
+                entry.status = CircuitStatus::Retired;
+                entry.attested.clear();
+                events.push(ChainEvent::CircuitVersionRetired {
+                    height,
+                    circuit_id,
+                    version,
+                });
+                changed = true;
+            }
+        }
+        if changed {
+            let live = registry
+                .versions
+                .values()
+                .filter(|v| {
+                    matches!(
+                        v.status,
+                        CircuitStatus::Active | CircuitStatus::Deprecated { .. }
+                    )
+                })
+                .flat_map(|v| v.attested.iter().copied())
+                .collect();
+            ctx.state.compliance_attestations.insert(circuit_id, live);
+        }
+    }
+    ctx.state.events.extend(events);
+}
+
+/// Evaluate every hook guarding `si`. Protocol-initiated instructions
+/// (zero sender) are not subject to hooks.
+pub fn run(si: &StablecoinInstruction, sender: &[u8; 32], ctx: &ExecutionContext) -> Result<()> {
+    if ctx.state.hooks.is_empty() || *sender == [0u8; 32] {
+        return Ok(());
+    }
+    let kind = si.kind();
+    let subjects = subjects(si, sender);
+    for hook in ctx
+        .state
+        .hooks
+        .values()
+        .filter(|h| h.instructions.iter().any(|k| k == kind))
+    {
+        let attested = ctx.state.compliance_attestations.get(&hook.circuit_id);
+        let mut gas = HOOK_GAS_BASE;
+        for subject in &subjects {
+            gas = gas.saturating_add(HOOK_GAS_PER_SUBJECT);
+            if gas > hook.gas_budget {
+                bail!("Hook {} exceeded its gas budget", hook.name);
+            }
+            if !attested.is_some_and(|a| a.contains(subject)) {
+                bail!(
+                    "Hook {} rejected an account not attested for circuit {}",
+                    hook.name,
+                    hook.circuit_id
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Accounts an instruction moves value between.
+fn subjects(si: &StablecoinInstruction, sender: &[u8; 32]) -> Vec<[u8; 32]> {
+    let mut subjects = vec![*sender];
+    match si {
+        StablecoinInstruction::Transfer { to, .. }
+        | StablecoinInstruction::TransferWithMemo { to, .. }
+        | StablecoinInstruction::TransferWithComplianceProof { to, .. }
+        | StablecoinInstruction::StreamPayment { to, .. }
+        | StablecoinInstruction::StreamAsset { to, .. }
+        | StablecoinInstruction::LockWithHash { recipient: to, .. } => subjects.push(*to),
+        StablecoinInstruction::CreateStreamBatch { streams, .. } => {
+            subjects.extend(streams.iter().map(|(to, _, _)| *to))
+        }
+        _ => {}
+    }
+    subjects.dedup();
+    subjects
+}
@@ -0,0 +1,147 @@
+use crate::{checked_add_u64, ensure_vested_lumina, release_escrow, ExecutionContext};
+use anyhow::{anyhow, bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::AssetType;
+use lumina_types::state::HashLock;
+use sha2::{Digest, Sha256};
+
+/// Furthest ahead a hash lock may time out (~115 days at 10s blocks).
+pub const MAX_LOCK_BLOCKS: u64 = 1_000_000;
+
+/// Escrow `amount` of `asset` from the sender until `recipient` claims it
+/// or `timeout_height` passes.
+pub fn lock(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    recipient: &[u8; 32],
+    asset: &AssetType,
+    amount: u64,
+    hash_lock: [u8; 32],
+    timeout_height: u64,
+) -> Result<()> {
+    if amount == 0 {
+        bail!("Amount must be greater than zero");
+    }
+    if *recipient == *sender || *recipient == [0u8; 32] {
+        bail!("Invalid recipient");
+    }
+    if timeout_height < ctx.height || timeout_height - ctx.height > MAX_LOCK_BLOCKS {
+        bail!("Timeout must be within {} blocks from now", MAX_LOCK_BLOCKS);
+    }
+    if *asset == AssetType::Lumina {
+        ensure_vested_lumina(ctx, sender, amount)?;
+    }
+    let account = ctx.state.accounts.entry(*sender).or_default();
+    if account.balance(asset) < amount {
+        bail!("Insufficient {}", asset);
+    }
+    account
+        .debit(asset, amount)
+        .ok_or_else(|| anyhow!("Hash lock escrow underflow"))?;
+
+    let lock_id = ctx.state.next_hash_lock_id;
+    ctx.state.next_hash_lock_id = checked_add_u64(lock_id, 1, "Hash lock id")?;
+    ctx.state.hash_locks.insert(
+        lock_id,
+        HashLock {
+            sender: *sender,
+            recipient: *recipient,
+            asset: asset.clone(),
+            amount,
+            hash_lock,
+            timeout_height,
+            expired: false,
+        },
+    );
+    ctx.state
+        .hash_lock_timeouts
+        .entry(timeout_height)
+        .or_default()
+        .push(lock_id);
+    ctx.state.events.push(ChainEvent::HashLocked {
+        height: ctx.height,
+        lock_id,
+        sender: *sender,
+        recipient: *recipient,
+        asset: asset.clone(),
+        amount,
+        hash_lock,
+        timeout_height,
+    });
+    Ok(())
+}
+
+/// Pay an unexpired lock to its recipient. Anyone holding the preimage
+/// may submit it; the funds only ever go to the recipient.
+pub fn claim(ctx: &mut ExecutionContext, lock_id: u64, preimage: &[u8; 32]) -> Result<()> {
+    let Some(lock) = ctx.state.hash_locks.get(&lock_id) else {
+        bail!("Hash lock not found");
+    };
+    if lock.expired {
+        bail!("Hash lock has timed out");
+    }
+    if <[u8; 32]>::from(Sha256::digest(preimage)) != lock.hash_lock {
+        bail!("Preimage does not match hash lock");
+    }
+    let lock = ctx.state.hash_locks.remove(&lock_id).expect("hash lock");
+    unindex(ctx, lock_id, lock.timeout_height);
+    release_escrow(ctx, &lock.recipient, &lock.asset, lock.amount)?;
+    ctx.state.events.push(ChainEvent::HashLockClaimed {
+        height: ctx.height,
+        lock_id,
+        sender: lock.sender,
+        recipient: lock.recipient,
+        preimage: *preimage,
+    });
+    Ok(())
+}
+
+/// Return an expired lock to the account that locked it.
+pub fn refund(ctx: &mut ExecutionContext, sender: &[u8; 32], lock_id: u64) -> Result<()> {
+    let Some(lock) = ctx.state.hash_locks.get(&lock_id) else {
+        bail!("Hash lock not found");
+    };
+    if lock.sender != *sender {
+        bail!("Only the locking account can refund");
+    }
+    if !lock.expired {
+        bail!("Hash lock has not timed out");
+    }
+    let lock = ctx.state.hash_locks.remove(&lock_id).expect("hash lock");
+    release_escrow(ctx, sender, &lock.asset, lock.amount)?;
+    ctx.state.events.push(ChainEvent::HashLockRefunded {
+        height: ctx.height,
+        lock_id,
+        sender: lock.sender,
+        recipient: lock.recipient,
+    });
+    Ok(())
+}
+
+/// Expire the locks whose timeout height this block reaches, so they can
+/// no longer be claimed; runs in `end_block`.
+pub fn expire(ctx: &mut ExecutionContext) {
+    let later = ctx.state.hash_lock_timeouts.split_off(&(ctx.height + 1));
+    let due = std::mem::replace(&mut ctx.state.hash_lock_timeouts, later);
+    for lock_id in due.into_values().flatten() {
+        let Some(lock) = ctx.state.hash_locks.get_mut(&lock_id) else {
+            continue;
+        };
+        lock.expired = true;
+        ctx.state.events.push(ChainEvent::HashLockExpired {
+            height: ctx.height,
+            lock_id,
+            sender: lock.sender,
+            recipient: lock.recipient,
+        });
+    }
+}
+
+fn unindex(ctx: &mut ExecutionContext, lock_id: u64, timeout_height: u64) {
+    if let Some(ids) = ctx.state.hash_lock_timeouts.get_mut(&timeout_height) {
+        ids.retain(|id| *id != lock_id);
+        if ids.is_empty() {
+            ctx.state.hash_lock_timeouts.remove(&timeout_height);
+        }
+    }
+}
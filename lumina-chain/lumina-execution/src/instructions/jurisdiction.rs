@@ -0,0 +1,95 @@
+use super::hooks::UNGUARDED;
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_crypto::zk::verify_multi_jurisdictional_proof;
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::StablecoinInstruction;
+use lumina_types::state::{GlobalState, JurisdictionPolicy};
+
+/// Most compliance circuits one policy may require.
+pub const MAX_REQUIRED_CIRCUITS: usize = 16;
+/// Most instruction kinds one policy may block.
+pub const MAX_BLOCKED_INSTRUCTIONS: usize = 64;
+
+/// Whether a policy leaves `kind` alone, so a tagged account can always
+/// govern, attest and re-prove its jurisdiction.
+fn exempt(kind: &str) -> bool {
+    UNGUARDED.contains(&kind) || kind == "MultiJurisdictionalCheck"
+}
+
+pub fn validate(state: &GlobalState, policy: &JurisdictionPolicy) -> Result<()> {
+    if policy.max_transfer_per_tx > policy.max_transfer_per_epoch {
+        bail!("Jurisdiction per-transaction cap exceeds its daily cap");
+    }
+    if policy.required_circuits.len() > MAX_REQUIRED_CIRCUITS {
+        bail!("At most {} required circuits", MAX_REQUIRED_CIRCUITS);
+    }
+    if let Some(circuit_id) = policy
+        .required_circuits
+        .iter()
+        .find(|c| !state.compliance_circuits.contains_key(c))
+    {
+        bail!("Unknown compliance circuit {}", circuit_id);
+    }
+    if policy.blocked_instructions.len() > MAX_BLOCKED_INSTRUCTIONS {
+        bail!("At most {} blocked instructions", MAX_BLOCKED_INSTRUCTIONS);
+    }
+    if let Some(kind) = policy.blocked_instructions.iter().find(|k| exempt(k)) {
+        bail!("Jurisdictions cannot block {}", kind);
+    }
+    Ok(())
+}
+
+/// Tag `sender` with the jurisdiction it proved it belongs to, replacing
+/// any earlier tag.
+pub fn tag(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    jurisdiction_id: u32,
+    proof: &[u8],
+) -> Result<()> {
+    if !verify_multi_jurisdictional_proof(jurisdiction_id, sender, proof) {
+        bail!("Invalid multi-jurisdictional proof");
+    }
+    ctx.state
+        .account_jurisdictions
+        .insert(*sender, jurisdiction_id);
+    ctx.state.events.push(ChainEvent::JurisdictionTagged {
+        height: ctx.height,
+        account: *sender,
+        jurisdiction_id,
+    });
+    Ok(())
+}
+
+/// Hold `si` to the blocked instructions and required attestations of the
+/// sender's jurisdiction. Its transfer caps are checked with the KYC limits.
+/// Protocol-initiated instructions (zero sender) are not subject to policy.
+pub fn check(si: &StablecoinInstruction, sender: &[u8; 32], state: &GlobalState) -> Result<()> {
+    if *sender == [0u8; 32] {
+        return Ok(());
+    }
+    let kind = si.kind();
+    let Some((jurisdiction_id, policy)) = state.jurisdiction_policy(sender) else {
+        return Ok(());
+    };
+    if exempt(kind) {
+        return Ok(());
+    }
+    if policy.blocked_instructions.iter().any(|k| k == kind) {
+        bail!("{} is blocked in jurisdiction {}", kind, jurisdiction_id);
+    }
+    if let Some(circuit_id) = policy.required_circuits.iter().find(|c| {
+        !state
+            .compliance_attestations
+            .get(c)
+            .is_some_and(|a| a.contains(sender))
+    }) {
+        bail!(
+            "Jurisdiction {} requires an attestation for circuit {}",
+            jurisdiction_id,
+            circuit_id
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::ExecutionContext;
+use lumina_math::{Bps, Rounding};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::KeeperTask;
+
+/// Bounty paid per call, in bps of the LUSD the call moved.
+pub const KEEPER_BOUNTY_BPS: u64 = 10;
+/// Largest bounty a single call can earn.
+pub const MAX_KEEPER_BOUNTY: u64 = 1_000;
+/// Blocks between bounties for the pool-wide tasks, which anyone can repeat
+/// at will; queue processing and liquidations are bounded by their backlog.
+pub const KEEPER_COOLDOWN_BLOCKS: u64 = 100;
+
+fn rate_limited(task: KeeperTask) -> bool {
+    matches!(
+        task,
+        KeeperTask::RebalanceTranches | KeeperTask::TriggerStabilizer
+    )
+}
+
+/// Pay `keeper` for a maintenance call that moved `work` LUSD, out of the
+/// insurance fund. Calls that did nothing, protocol-initiated calls and
+/// repeats within the cooldown earn nothing; the call itself still succeeds.
+pub fn reward(ctx: &mut ExecutionContext, keeper: &[u8; 32], task: KeeperTask, work: u64) {
+    if work == 0 || *keeper == [0u8; 32] {
+        return;
+    }
+    if rate_limited(task)
+        && ctx
+            .state
+            .keeper_last_paid
+            .get(&task)
+            .is_some_and(|paid| ctx.height < paid.saturating_add(KEEPER_COOLDOWN_BLOCKS))
+    {
+        return;
+    }
+    let amount = Bps::constant(KEEPER_BOUNTY_BPS)
+        .of(work, Rounding::Down)
+        .min(MAX_KEEPER_BOUNTY)
+        .min(ctx.state.insurance_fund_balance);
+    if amount == 0 {
+        return;
+    }
+    let state = &mut *ctx.state;
+    state.insurance_fund_balance -= amount;
+    let account = state.accounts.entry(*keeper).or_default();
+    account.lusd_balance = account.lusd_balance.saturating_add(amount);
+    state.total_lusd_supply = state.total_lusd_supply.saturating_add(amount);
+    if rate_limited(task) {
+        state.keeper_last_paid.insert(task, ctx.height);
+    }
+    state.events.push(ChainEvent::KeeperRewarded {
+        height: ctx.height,
+        keeper: *keeper,
+        task,
+        amount,
+    });
+}
@@ -0,0 +1,177 @@
+use anyhow::{bail, Result};
+use lumina_types::instruction::{AssetType, StablecoinInstruction};
+use lumina_types::state::{GlobalState, KycTierLimits};
+
+/// Highest number of tiers governance may configure (tiers are `u8`).
+pub const MAX_KYC_TIERS: usize = 16;
+
+/// LUSD an instruction moves out of the sender's control, by limit kind.
+#[derive(Clone, Copy)]
+pub enum Usage {
+    Transfer(u64),
+    Redeem(u64),
+}
+
+pub fn validate_tiers(tiers: &[KycTierLimits]) -> Result<()> {
+    if tiers.len() > MAX_KYC_TIERS {
+        bail!("At most {} KYC tiers", MAX_KYC_TIERS);
+    }
+    if tiers.iter().any(|t| {
+        t.max_transfer_per_tx > t.max_transfer_per_epoch
+            || t.max_redeem_per_tx > t.max_redeem_per_epoch
+    }) {
+        bail!("KYC per-transaction limit exceeds its daily limit");
+    }
+    Ok(())
+}
+
+/// Check `si` against the sender's tier limits and its jurisdiction's
+/// transfer caps, returning the usage to record once it succeeds.
+/// Protocol-initiated instructions (zero sender) are not limited.
+pub fn check(
+    si: &StablecoinInstruction,
+    sender: &[u8; 32],
+    state: &GlobalState,
+    timestamp: u64,
+) -> Result<Option<Usage>> {
+    if *sender == [0u8; 32] {
+        return Ok(None);
+    }
+    let Some(usage) = usage(si, sender, state, timestamp) else {
+        return Ok(None);
+    };
+    let kyc = state.kyc_limits(sender);
+    let jurisdiction = state.jurisdiction_policy(sender);
+    if kyc.is_none() && jurisdiction.is_none() {
+        return Ok(None);
+    }
+    let (transferred, redeemed) = state
+        .kyc_accounts
+        .get(sender)
+        .map(|a| a.usage(state.current_epoch))
+        .unwrap_or_default();
+    if let Some((_, limits)) = kyc {
+        match usage {
+            Usage::Transfer(amount) => {
+                if amount > limits.max_transfer_per_tx {
+                    bail!("Transfer exceeds KYC tier per-transaction limit");
+                }
+                if transferred.saturating_add(amount) > limits.max_transfer_per_epoch {
+                    bail!("Transfer exceeds KYC tier daily limit");
+                }
+            }
+            Usage::Redeem(amount) => {
+                if amount > limits.max_redeem_per_tx {
+                    bail!("Redemption exceeds KYC tier per-transaction limit");
+                }
+                if redeemed.saturating_add(amount) > limits.max_redeem_per_epoch {
+                    bail!("Redemption exceeds KYC tier daily limit");
+                }
+            }
+        }
+    }
+    if let (Usage::Transfer(amount), Some((jurisdiction_id, policy))) = (usage, jurisdiction) {
+        if amount > policy.max_transfer_per_tx {
+            bail!(
+                "Transfer exceeds jurisdiction {} per-transaction cap",
+                jurisdiction_id
+            );
+        }
+        if transferred.saturating_add(amount) > policy.max_transfer_per_epoch {
+            bail!(
+                "Transfer exceeds jurisdiction {} daily cap",
+                jurisdiction_id
+            );
+        }
+    }
+    Ok(Some(usage))
+}
+
+/// Count a successful instruction's usage against the current epoch.
+pub fn record(state: &mut GlobalState, sender: &[u8; 32], usage: Usage) {
+    let epoch = state.current_epoch;
+    let account = state.kyc_accounts.entry(*sender).or_default();
+    if account.epoch != epoch {
+        account.epoch = epoch;
+        account.transferred = 0;
+        account.redeemed = 0;
+    }
+    match usage {
+        Usage::Transfer(amount) => account.transferred = account.transferred.saturating_add(amount),
+        Usage::Redeem(amount) => account.redeemed = account.redeemed.saturating_add(amount),
+    }
+}
+
+/// LUSD transfers (a compliance-proven or memo transfer is counted through
+/// the `Transfer` it executes, a stream top-up or raise through the LUSD it
+/// escrows) and redemptions.
+fn usage(
+    si: &StablecoinInstruction,
+    sender: &[u8; 32],
+    state: &GlobalState,
+    timestamp: u64,
+) -> Option<Usage> {
+    match si {
+        StablecoinInstruction::Transfer {
+            amount,
+            asset: AssetType::LUSD,
+            ..
+        } => Some(Usage::Transfer(*amount)),
+        StablecoinInstruction::LockWithHash {
+            amount,
+            asset: AssetType::LUSD,
+            ..
+        }
+        | StablecoinInstruction::SendLusdCrossChain { amount, .. } => {
+            Some(Usage::Transfer(*amount))
+        }
+        StablecoinInstruction::StreamPayment {
+            amount_per_sec,
+            duration,
+            ..
+        }
+        | StablecoinInstruction::StreamAsset {
+            amount_per_sec,
+            duration,
+            asset: AssetType::LUSD,
+            ..
+        } => Some(Usage::Transfer(amount_per_sec.saturating_mul(*duration))),
+        StablecoinInstruction::TopUpStream {
+            stream_id,
+            duration,
+        } => state
+            .stream(sender, *stream_id)
+            .filter(|s| s.asset == AssetType::LUSD)
+            .map(|s| Usage::Transfer(s.amount_per_sec.saturating_mul(*duration))),
+        StablecoinInstruction::ModifyStreamRate {
+            payer,
+            stream_id,
+            amount_per_sec,
+        } if payer == sender => state
+            .stream(sender, *stream_id)
+            .filter(|s| s.asset == AssetType::LUSD && *amount_per_sec > s.amount_per_sec)
+            .map(|s| {
+                Usage::Transfer(
+                    (amount_per_sec - s.amount_per_sec).saturating_mul(s.remaining(timestamp)),
+                )
+            }),
+        StablecoinInstruction::CreateStreamBatch {
+            asset: AssetType::LUSD,
+            streams,
+        } => Some(Usage::Transfer(streams.iter().fold(
+            0u64,
+            |total, (_, amount_per_sec, duration)| {
+                total.saturating_add(amount_per_sec.saturating_mul(*duration))
+            },
+        ))),
+        StablecoinInstruction::BatchDisburse { payments } => Some(Usage::Transfer(
+            payments
+                .iter()
+                .fold(0u64, |total, (_, amount)| total.saturating_add(*amount)),
+        )),
+        StablecoinInstruction::RedeemSenior { amount }
+        | StablecoinInstruction::RedeemPosition { amount, .. }
+        | StablecoinInstruction::InstantRedeem { amount, .. } => Some(Usage::Redeem(*amount)),
+        _ => None,
+    }
+}
@@ -0,0 +1,120 @@
+use crate::instructions::rewards;
+use crate::{ExecutionContext, EPOCH_LENGTH};
+use anyhow::{bail, Result};
+use lumina_math::{Bps, Rounding};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::JailedValidator;
+
+/// Blocks with commit votes covered by each validator's liveness window.
+pub const SIGNED_BLOCKS_WINDOW: u64 = 1_000;
+/// Share of its window a validator must sign to stay in the active set.
+pub const MIN_SIGNED_PER_WINDOW_BPS: u64 = 5_000;
+/// Share of stake (and power) slashed when a validator is jailed.
+pub const DOWNTIME_SLASH_BPS: u64 = 100;
+/// Blocks a jailed validator must wait before sending `Unjail`.
+pub const JAIL_COOLDOWN_BLOCKS: u64 = EPOCH_LENGTH;
+
+const WORDS: usize = SIGNED_BLOCKS_WINDOW.div_ceil(64) as usize;
+/// Fewest blocks of the window a validator must sign; a requirement, so
+/// it rounds up.
+const MIN_SIGNED: u64 =
+    Bps::constant(MIN_SIGNED_PER_WINDOW_BPS).of(SIGNED_BLOCKS_WINDOW, Rounding::Up);
+const MAX_MISSED: u64 = SIGNED_BLOCKS_WINDOW - MIN_SIGNED;
+
+/// Record which active validators signed the parent block, as the votes
+/// this block carries show, and jail those whose full window falls below
+/// the liveness threshold. Blocks that carry no votes (single-proposer
+/// production) are not counted against anyone. The last active validator
+/// is never jailed.
+pub fn record(ctx: &mut ExecutionContext, proposer: &[u8; 32], signers: &[[u8; 32]]) {
+    if signers.is_empty() {
+        return;
+    }
+    let state = &mut *ctx.state;
+    let mut offline = Vec::new();
+    for validator in &state.validators {
+        let signed = validator.pubkey == *proposer || signers.contains(&validator.pubkey);
+        let liveness = state
+            .validator_liveness
+            .entry(validator.pubkey)
+            .or_default();
+        liveness.missed.resize(WORDS, 0);
+        let slot = liveness.observed % SIGNED_BLOCKS_WINDOW;
+        let (word, bit) = ((slot / 64) as usize, 1u64 << (slot % 64));
+        let was_missed = liveness.missed[word] & bit != 0;
+        if was_missed && signed {
+            liveness.missed[word] &= !bit;
+            liveness.missed_count = liveness.missed_count.saturating_sub(1);
+        } else if !was_missed && !signed {
+            liveness.missed[word] |= bit;
+            liveness.missed_count = liveness.missed_count.saturating_add(1);
+        }
+        liveness.observed = liveness.observed.saturating_add(1);
+        if liveness.observed >= SIGNED_BLOCKS_WINDOW && liveness.missed_count > MAX_MISSED {
+            offline.push((validator.pubkey, liveness.missed_count));
+        }
+    }
+    for (pubkey, missed) in offline {
+        if ctx.state.validators.len() > 1 {
+            jail(ctx, &pubkey, missed);
+        }
+    }
+}
+
+fn jail(ctx: &mut ExecutionContext, pubkey: &[u8; 32], missed: u64) {
+    let Some(idx) = ctx
+        .state
+        .validators
+        .iter()
+        .position(|v| v.pubkey == *pubkey)
+    else {
+        return;
+    };
+    let mut validator = ctx.state.validators.remove(idx);
+    rewards::settle(ctx.state, pubkey, validator.stake);
+    // Slashed stake is burned; the slash rounds down, so it never takes
+    // more than its share
+    let slash = Bps::constant(DOWNTIME_SLASH_BPS);
+    let slashed = slash.of(validator.stake, Rounding::Down);
+    let power_slashed = slash.of(validator.power, Rounding::Down);
+    validator.stake -= slashed;
+    validator.power -= power_slashed;
+
+    let jailed_until = ctx.height.saturating_add(JAIL_COOLDOWN_BLOCKS);
+    ctx.state.validator_liveness.remove(pubkey);
+    ctx.state.jailed_validators.insert(
+        *pubkey,
+        JailedValidator {
+            validator,
+            jailed_height: ctx.height,
+            jailed_until,
+        },
+    );
+    ctx.state.events.push(ChainEvent::ValidatorJailed {
+        height: ctx.height,
+        validator: *pubkey,
+        missed,
+        slashed,
+        jailed_until,
+    });
+}
+
+/// Return the sender to the active set with a fresh liveness window.
+pub fn unjail(ctx: &mut ExecutionContext, sender: &[u8; 32]) -> Result<()> {
+    let Some(jailed) = ctx.state.jailed_validators.get(sender) else {
+        bail!("Validator is not jailed");
+    };
+    if ctx.height < jailed.jailed_until {
+        bail!("Validator is jailed until height {}", jailed.jailed_until);
+    }
+    if jailed.validator.stake == 0 {
+        bail!("Validator has no stake left");
+    }
+    let jailed = ctx.state.jailed_validators.remove(sender);
+    ctx.state.validators.extend(jailed.map(|j| j.validator));
+    ctx.state.events.push(ChainEvent::ValidatorUnjailed {
+        height: ctx.height,
+        validator: *sender,
+    });
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::{checked_add_u64, ExecutionContext};
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{GlobalState, MerchantAccount};
+
+pub fn register(ctx: &mut ExecutionContext, sender: &[u8; 32], treasury: &[u8; 32]) -> Result<()> {
+    if *treasury == [0u8; 32] {
+        bail!("Invalid treasury");
+    }
+    let merchant = ctx.state.merchants.entry(*sender).or_default();
+    if merchant.treasury == *treasury {
+        bail!("Merchant already settles to this treasury");
+    }
+    merchant.treasury = *treasury;
+    ctx.state.events.push(ChainEvent::MerchantRegistered {
+        height: ctx.height,
+        merchant: *sender,
+        treasury: *treasury,
+    });
+    Ok(())
+}
+
+pub fn deregister(ctx: &mut ExecutionContext, sender: &[u8; 32]) -> Result<()> {
+    let Some(merchant) = ctx.state.merchants.remove(sender) else {
+        bail!("Not a registered merchant");
+    };
+    settle(ctx, sender, merchant);
+    Ok(())
+}
+
+/// LUSD `to` can still take before an incoming payment overflows: its
+/// settlement account for a merchant, its balance otherwise.
+pub fn incoming_balance(state: &GlobalState, to: &[u8; 32]) -> u64 {
+    match state.merchants.get(to) {
+        Some(merchant) => merchant.pending,
+        None => state.accounts.get(to).map_or(0, |a| a.lusd_balance),
+    }
+}
+
+/// Credit LUSD paid to `to` by `payments` payments, netting it for a
+/// registered merchant.
+pub fn receive(state: &mut GlobalState, to: &[u8; 32], amount: u64, payments: u64) -> Result<()> {
+    if let Some(merchant) = state.merchants.get_mut(to) {
+        merchant.pending = checked_add_u64(merchant.pending, amount, "Merchant settlement")?;
+        merchant.payments = merchant.payments.saturating_add(payments);
+        return Ok(());
+    }
+    let account = state.accounts.entry(*to).or_default();
+    account.lusd_balance = checked_add_u64(account.lusd_balance, amount, "Balance")?;
+    Ok(())
+}
+
+/// Pay `merchant`'s netted payments to its treasury in one write.
+fn settle(ctx: &mut ExecutionContext, merchant: &[u8; 32], account: MerchantAccount) {
+    if account.pending == 0 {
+        return;
+    }
+    let treasury = ctx.state.accounts.entry(account.treasury).or_default();
+    treasury.lusd_balance = treasury.lusd_balance.saturating_add(account.pending);
+    ctx.state.events.push(ChainEvent::MerchantSettled {
+        height: ctx.height,
+        epoch: ctx.state.current_epoch,
+        merchant: *merchant,
+        treasury: account.treasury,
+        amount: account.pending,
+        payments: account.payments,
+    });
+}
+
+/// Settle every merchant at the close of the epoch.
+pub fn settle_epoch(ctx: &mut ExecutionContext) {
+    let due: Vec<([u8; 32], MerchantAccount)> = ctx
+        .state
+        .merchants
+        .iter_mut()
+        .filter(|(_, m)| m.pending > 0)
+        .map(|(key, m)| {
+            let account = m.clone();
+            (m.pending, m.payments) = (0, 0);
+            (*key, account)
+        })
+        .collect();
+    for (merchant, account) in due {
+        settle(ctx, &merchant, account);
+    }
+}
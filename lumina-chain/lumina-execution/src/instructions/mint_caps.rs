@@ -0,0 +1,85 @@
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{GlobalState, MintCaps};
+
+pub fn validate(caps: &MintCaps) -> Result<()> {
+    if caps.per_block != 0 && caps.per_account > caps.per_block {
+        bail!("Account mint cap cannot exceed the block cap");
+    }
+    Ok(())
+}
+
+/// Refuse a capped mint of `amount` to `minter` while the circuit breaker
+/// is active, or if it would take the block or the minter past a cap.
+pub fn check(state: &GlobalState, minter: &[u8; 32], amount: u64) -> Result<()> {
+    if state.circuit_breaker_active {
+        bail!("Circuit breaker active: minting paused");
+    }
+    let caps = &state.mint_caps;
+    if caps.per_block != 0 && state.block_minted.saturating_add(amount) > caps.per_block {
+        bail!(
+            "Mint exceeds the block cap: {} of {} left",
+            caps.per_block.saturating_sub(state.block_minted),
+            caps.per_block
+        );
+    }
+    let minted = state.block_account_mints.get(minter).copied().unwrap_or(0);
+    if caps.per_account != 0 && minted.saturating_add(amount) > caps.per_account {
+        bail!(
+            "Mint exceeds the account cap: {} of {} left",
+            caps.per_account.saturating_sub(minted),
+            caps.per_account
+        );
+    }
+    Ok(())
+}
+
+/// Count a completed capped mint against the block and the minter.
+pub fn record(state: &mut GlobalState, minter: &[u8; 32], amount: u64) {
+    state.block_minted = state.block_minted.saturating_add(amount);
+    let minted = state.block_account_mints.entry(*minter).or_default();
+    *minted = minted.saturating_add(amount);
+}
+
+/// Give back the headroom of a flash mint burned in the same block, so
+/// flash mints count net.
+pub fn release(state: &mut GlobalState, minter: &[u8; 32], amount: u64) {
+    state.block_minted = state.block_minted.saturating_sub(amount);
+    if let Some(minted) = state.block_account_mints.get_mut(minter) {
+        *minted = minted.saturating_sub(amount);
+    }
+}
+
+/// Reset the block's counters, extending the run of blocks that reached a
+/// cap and tripping the circuit breaker once it is `breaker_blocks` long;
+/// runs in `end_block`.
+pub fn roll(ctx: &mut ExecutionContext) {
+    let caps = &ctx.state.mint_caps;
+    let reached = (caps.per_block != 0 && ctx.state.block_minted >= caps.per_block)
+        || (caps.per_account != 0
+            && ctx
+                .state
+                .block_account_mints
+                .values()
+                .any(|minted| *minted >= caps.per_account));
+    let breaker_blocks = caps.breaker_blocks;
+    ctx.state.block_minted = 0;
+    ctx.state.block_account_mints.clear();
+    if !reached {
+        ctx.state.mint_cap_streak = 0;
+        return;
+    }
+    ctx.state.mint_cap_streak = ctx.state.mint_cap_streak.saturating_add(1);
+    if breaker_blocks != 0
+        && ctx.state.mint_cap_streak >= breaker_blocks
+        && !ctx.state.circuit_breaker_active
+    {
+        ctx.state.circuit_breaker_active = true;
+        ctx.state.events.push(ChainEvent::MintBreakerTripped {
+            height: ctx.height,
+            blocks: ctx.state.mint_cap_streak,
+        });
+        ctx.state.mint_cap_streak = 0;
+    }
+}
@@ -0,0 +1,124 @@
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{MAX_INSURANCE_TIERS, MAX_RESERVE_ZONES};
+use lumina_types::state::{GlobalState, ProtocolParam};
+use std::collections::BTreeMap;
+
+/// Range of the reserve ratio (bps) below which the junior tranche is
+/// written down.
+pub const WRITEDOWN_FLOOR_RANGE_BPS: (u64, u64) = (10_000, 20_000);
+
+/// Range of the collateral ratio (bps) below which a position can be
+/// liquidated.
+pub const LIQUIDATION_THRESHOLD_RANGE_BPS: (u64, u64) = (10_000, 20_000);
+
+/// Range of how long (seconds) a green validator's energy proof stays valid.
+pub const GREEN_PROOF_VALIDITY_RANGE_SECS: (u64, u64) = (86_400, 365 * 86_400);
+
+/// Check `param` may replace its current value.
+pub fn validate(state: &GlobalState, param: &ProtocolParam) -> Result<()> {
+    match param {
+        ProtocolParam::InsuranceTiers(tiers) => {
+            if tiers.is_empty() || tiers.len() > MAX_INSURANCE_TIERS {
+                bail!("Between 1 and {} insurance tiers", MAX_INSURANCE_TIERS);
+            }
+            // Policies name their tier by index, so a tier stays once offered
+            if tiers.len() < state.params.insurance_tiers.len() {
+                bail!("Insurance tiers cannot be removed");
+            }
+            if tiers.iter().any(|t| t.premium_per_epoch == 0) {
+                bail!("Insurance premiums must be non-zero");
+            }
+        }
+        ProtocolParam::UninsuredClaimCap(_) => {}
+        ProtocolParam::JuniorWritedownFloorBps(bps) => {
+            let (min, max) = WRITEDOWN_FLOOR_RANGE_BPS;
+            if !(min..=max).contains(bps) {
+                bail!("Junior write-down floor must be {}-{} bps", min, max);
+            }
+        }
+        ProtocolParam::MintFeeBps(bps) | ProtocolParam::RedeemFeeBps(bps) => {
+            if *bps > 10_000 {
+                bail!("Fees must be at most 10000 bps");
+            }
+            // The stabilizer would only pull an out-of-bounds fee back in
+            let bounds = &state.stabilizer.bounds;
+            let range = if matches!(param, ProtocolParam::MintFeeBps(_)) {
+                &bounds.mint_fee_bps
+            } else {
+                &bounds.redeem_fee_bps
+            };
+            if bounds.step_bps > 0 && range.clamp(*bps) != *bps {
+                bail!("Fee must lie within the stabilizer bounds");
+            }
+        }
+        ProtocolParam::VolatileFeeSurchargeBps(bps) | ProtocolParam::FlashLoanFeeBps(bps) => {
+            if *bps > 10_000 {
+                bail!("Fees must be at most 10000 bps");
+            }
+        }
+        ProtocolParam::VolatilePegDeviationBps(bps) => {
+            if *bps == 0 || *bps >= state.params.stress_peg_deviation_bps {
+                bail!("Volatile peg deviation must be non-zero and below the stress deviation");
+            }
+        }
+        ProtocolParam::StressPegDeviationBps(bps) => {
+            if *bps > 10_000 || *bps <= state.params.volatile_peg_deviation_bps {
+                bail!("Stress peg deviation must exceed the volatile deviation, up to 10000 bps");
+            }
+        }
+        ProtocolParam::ZoneReserveWeights(weights) => validate_zone_weights(weights)?,
+        ProtocolParam::GreenProofValiditySecs(secs) => {
+            let (min, max) = GREEN_PROOF_VALIDITY_RANGE_SECS;
+            if !(min..=max).contains(secs) {
+                bail!("Green proof validity must be {}-{} seconds", min, max);
+            }
+        }
+        ProtocolParam::LiquidationThresholdBps(bps) => {
+            let (min, max) = LIQUIDATION_THRESHOLD_RANGE_BPS;
+            if !(min..=max).contains(bps) {
+                bail!("Liquidation threshold must be {}-{} bps", min, max);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check a reserve weight table: zone 0 stands for unassigned reserves and
+/// cannot be weighted.
+fn validate_zone_weights(weights: &BTreeMap<u32, u64>) -> Result<()> {
+    if weights.len() > MAX_RESERVE_ZONES {
+        bail!("At most {} weighted zones", MAX_RESERVE_ZONES);
+    }
+    if weights.contains_key(&0) {
+        bail!("Zone 0 cannot be weighted");
+    }
+    Ok(())
+}
+
+/// Apply a passed `SetProtocolParam` proposal.
+pub fn set(ctx: &mut ExecutionContext, proposal_id: u64, param: ProtocolParam) -> Result<()> {
+    validate(ctx.state, &param)?;
+    ctx.state.events.push(ChainEvent::ProtocolParamChanged {
+        height: ctx.height,
+        proposal_id,
+        param: param.clone(),
+    });
+    let params = &mut ctx.state.params;
+    match param {
+        ProtocolParam::InsuranceTiers(tiers) => params.insurance_tiers = tiers,
+        ProtocolParam::UninsuredClaimCap(cap) => params.uninsured_claim_cap = cap,
+        ProtocolParam::JuniorWritedownFloorBps(bps) => params.junior_writedown_floor_bps = bps,
+        ProtocolParam::MintFeeBps(bps) => params.mint_fee_bps = bps,
+        ProtocolParam::RedeemFeeBps(bps) => params.redeem_fee_bps = bps,
+        ProtocolParam::VolatileFeeSurchargeBps(bps) => params.volatile_fee_surcharge_bps = bps,
+        ProtocolParam::VolatilePegDeviationBps(bps) => params.volatile_peg_deviation_bps = bps,
+        ProtocolParam::StressPegDeviationBps(bps) => params.stress_peg_deviation_bps = bps,
+        ProtocolParam::LiquidationThresholdBps(bps) => params.liquidation_threshold_bps = bps,
+        ProtocolParam::FlashLoanFeeBps(bps) => params.flash_loan_fee_bps = bps,
+        ProtocolParam::ZoneReserveWeights(weights) => params.zone_reserve_weights = weights,
+        ProtocolParam::GreenProofValiditySecs(secs) => params.green_proof_validity_secs = secs,
+    }
+    Ok(())
+}
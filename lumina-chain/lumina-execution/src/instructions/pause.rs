@@ -0,0 +1,79 @@
+use crate::instructions::council;
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{kind_index, StablecoinInstruction, MAX_PAUSED_KINDS};
+use lumina_types::state::{GlobalState, InstructionBitmap};
+
+/// Instructions no pause may name, so neither governance nor the council
+/// can stop governance from overruling a pause.
+const UNPAUSABLE: &[&str] = &[
+    "Vote",
+    "ProposeEmergencyCouncil",
+    "CouncilPropose",
+    "CouncilApprove",
+    "ProposeCouncilRuling",
+    "ProposeInstructionPause",
+];
+
+/// The bits of `kinds`, which must be known, pausable instruction names.
+pub fn validate(kinds: &[String]) -> Result<InstructionBitmap> {
+    if kinds.is_empty() || kinds.len() > MAX_PAUSED_KINDS {
+        bail!("A pause must name 1 to {} instructions", MAX_PAUSED_KINDS);
+    }
+    let mut bits = InstructionBitmap::default();
+    for kind in kinds {
+        if UNPAUSABLE.contains(&kind.as_str()) {
+            bail!("{} cannot be paused", kind);
+        }
+        let Some(index) = kind_index(kind) else {
+            bail!("Unknown instruction {}", kind);
+        };
+        bits.insert(index);
+    }
+    Ok(bits)
+}
+
+/// Refuse paused instructions before they run.
+pub fn check(si: &StablecoinInstruction, state: &GlobalState) -> Result<()> {
+    let kind = si.kind();
+    if kind_index(kind).is_some_and(|index| state.paused_instructions.contains(index)) {
+        bail!("{} is paused", kind);
+    }
+    Ok(())
+}
+
+/// Recompute the paused instructions from governance's pauses and the
+/// council actions in effect, after either changes.
+pub fn refresh(state: &mut GlobalState) {
+    let mut paused = state.governance_paused.clone();
+    paused.union(&council::paused(state));
+    state.paused_instructions = paused;
+}
+
+/// Apply a passed `SetInstructionPause` proposal. Resuming an instruction
+/// the council holds paused leaves it paused until the council action ends
+/// or is ruled on.
+pub fn set(
+    ctx: &mut ExecutionContext,
+    proposal_id: u64,
+    kinds: Vec<String>,
+    paused: bool,
+) -> Result<()> {
+    let bits = validate(&kinds)?;
+    for index in bits.indices() {
+        if paused {
+            ctx.state.governance_paused.insert(index);
+        } else {
+            ctx.state.governance_paused.remove(index);
+        }
+    }
+    refresh(ctx.state);
+    ctx.state.events.push(ChainEvent::InstructionPauseChanged {
+        height: ctx.height,
+        proposal_id,
+        kinds,
+        paused,
+    });
+    Ok(())
+}
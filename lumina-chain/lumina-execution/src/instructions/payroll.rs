@@ -0,0 +1,67 @@
+use crate::instructions::merchant;
+use crate::{checked_add_u64, ExecutionContext};
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::MAX_DISBURSE_PAYMENTS;
+use std::collections::BTreeMap;
+
+/// Pay each recipient its LUSD from the sender. The whole batch is checked
+/// before any balance moves: the sender is debited once for the total, and
+/// repeated recipients are merged so each account is written once. Every
+/// payment still gets its own `Disbursed` event.
+pub fn disburse(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    payments: &[([u8; 32], u64)],
+) -> Result<()> {
+    if payments.is_empty() {
+        bail!("No payments");
+    }
+    if payments.len() > MAX_DISBURSE_PAYMENTS {
+        bail!("At most {} payments per batch", MAX_DISBURSE_PAYMENTS);
+    }
+    let mut credits: BTreeMap<[u8; 32], (u64, u64)> = BTreeMap::new();
+    let mut total = 0u64;
+    for (to, amount) in payments {
+        if *amount == 0 {
+            bail!("Amount must be greater than zero");
+        }
+        if *to == *sender || *to == [0u8; 32] {
+            bail!("Invalid recipient");
+        }
+        total = checked_add_u64(total, *amount, "Batch total")?;
+        let (credit, count) = credits.entry(*to).or_default();
+        *credit = checked_add_u64(*credit, *amount, "Recipient payment")?;
+        *count += 1;
+    }
+    let account = ctx.state.accounts.get(sender);
+    if account.map_or(0, |a| a.lusd_balance) < total {
+        bail!("Insufficient LUSD");
+    }
+    let volume = account.map_or(0, |a| a.epoch_tx_volume);
+    let volume = checked_add_u64(volume, total, "Sender epoch tx volume")?;
+    for (to, (credit, _)) in &credits {
+        checked_add_u64(
+            merchant::incoming_balance(ctx.state, to),
+            *credit,
+            "Balance",
+        )?;
+    }
+
+    let sender_account = ctx.state.accounts.entry(*sender).or_default();
+    sender_account.lusd_balance -= total;
+    sender_account.epoch_tx_volume = volume;
+    for (to, (credit, count)) in credits {
+        merchant::receive(ctx.state, &to, credit, count)?;
+    }
+    let height = ctx.height;
+    ctx.state
+        .events
+        .extend(payments.iter().map(|(to, amount)| ChainEvent::Disbursed {
+            height,
+            from: *sender,
+            to: *to,
+            amount: *amount,
+        }));
+    Ok(())
+}
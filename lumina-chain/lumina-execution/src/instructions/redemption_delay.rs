@@ -0,0 +1,147 @@
+use crate::{
+    checked_add_u64, checked_sub_u64, redemptions_stressed, settle_senior_redemption,
+    ExecutionContext,
+};
+use anyhow::{bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::MAX_REDEMPTION_TIERS;
+use lumina_types::state::{DelayedRedemption, RedemptionRequest, RedemptionTier};
+
+/// Longest delay a tier may impose (~7 days at 10s blocks).
+pub const MAX_REDEMPTION_DELAY_BLOCKS: u64 = 60_480;
+
+/// Tiers must rise in both size and delay, so a larger redemption never
+/// waits less than a smaller one.
+pub fn validate(tiers: &[RedemptionTier]) -> Result<()> {
+    if tiers.len() > MAX_REDEMPTION_TIERS {
+        bail!("At most {} redemption tiers", MAX_REDEMPTION_TIERS);
+    }
+    for tier in tiers {
+        if tier.min_amount == 0 {
+            bail!("Redemption tier threshold must be greater than zero");
+        }
+        if tier.delay_blocks == 0 || tier.delay_blocks > MAX_REDEMPTION_DELAY_BLOCKS {
+            bail!(
+                "Redemption delay must be 1 to {} blocks",
+                MAX_REDEMPTION_DELAY_BLOCKS
+            );
+        }
+    }
+    for pair in tiers.windows(2) {
+        if pair[1].min_amount <= pair[0].min_amount || pair[1].delay_blocks < pair[0].delay_blocks {
+            bail!("Redemption tiers must rise in threshold and delay");
+        }
+    }
+    Ok(())
+}
+
+/// Blocks a redemption of `amount` LUSD must wait; zero below every tier.
+pub fn delay_for(tiers: &[RedemptionTier], amount: u64) -> u64 {
+    tiers
+        .iter()
+        .rev()
+        .find(|tier| amount >= tier.min_amount)
+        .map_or(0, |tier| tier.delay_blocks)
+}
+
+/// Escrow the owner's LUSD for a redemption that must wait `delay_blocks`.
+/// The caller has checked the balance.
+pub fn delay(
+    ctx: &mut ExecutionContext,
+    owner: &[u8; 32],
+    amount: u64,
+    delay_blocks: u64,
+) -> Result<()> {
+    let redemption_id = ctx.state.next_delayed_redemption_id;
+    let next_id = checked_add_u64(redemption_id, 1, "Delayed redemption id")?;
+    let release_height = checked_add_u64(ctx.height, delay_blocks, "Release height")?;
+    let account = ctx.state.accounts.entry(*owner).or_default();
+    account.lusd_balance = checked_sub_u64(account.lusd_balance, amount, "LUSD balance")?;
+    ctx.state.next_delayed_redemption_id = next_id;
+    ctx.state.delayed_redemptions.insert(
+        redemption_id,
+        DelayedRedemption {
+            owner: *owner,
+            amount,
+            requested_height: ctx.height,
+            release_height,
+        },
+    );
+    ctx.state.events.push(ChainEvent::RedemptionDelayed {
+        height: ctx.height,
+        redemption_id,
+        owner: *owner,
+        amount,
+        release_height,
+    });
+    Ok(())
+}
+
+/// Return a delayed redemption's LUSD to its owner. The owner may cancel
+/// their own; validators may cancel any under review.
+pub fn cancel(ctx: &mut ExecutionContext, sender: &[u8; 32], redemption_id: u64) -> Result<()> {
+    let Some(redemption) = ctx.state.delayed_redemptions.get(&redemption_id) else {
+        bail!("Delayed redemption not found");
+    };
+    if redemption.owner != *sender && !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Only the owner or a validator can cancel a delayed redemption");
+    }
+    let (owner, amount) = (redemption.owner, redemption.amount);
+    let balance = ctx.state.accounts.get(&owner).map_or(0, |a| a.lusd_balance);
+    let balance = checked_add_u64(balance, amount, "LUSD balance")?;
+
+    ctx.state.delayed_redemptions.remove(&redemption_id);
+    ctx.state.accounts.entry(owner).or_default().lusd_balance = balance;
+    ctx.state
+        .events
+        .push(ChainEvent::DelayedRedemptionCancelled {
+            height: ctx.height,
+            redemption_id,
+            owner,
+            by: *sender,
+            amount,
+        });
+    Ok(())
+}
+
+/// Settle redemptions whose delay has ended, queueing them instead while
+/// the system is under stress; runs in `end_block`.
+pub fn release(ctx: &mut ExecutionContext) {
+    let due: Vec<u64> = ctx
+        .state
+        .delayed_redemptions
+        .iter()
+        .filter(|(_, r)| r.release_height <= ctx.height)
+        .map(|(id, _)| *id)
+        .collect();
+    for redemption_id in due {
+        let redemption = ctx
+            .state
+            .delayed_redemptions
+            .remove(&redemption_id)
+            .expect("delayed redemption");
+        let mut queued = redemptions_stressed(ctx.state);
+        if !queued {
+            if let Err(e) = settle_senior_redemption(ctx, &redemption.owner, redemption.amount) {
+                tracing::warn!("Delayed redemption {} failed: {}", redemption_id, e);
+                queued = true;
+            }
+        }
+        if queued {
+            ctx.state.fair_redeem_queue.push(RedemptionRequest {
+                address: redemption.owner,
+                amount: redemption.amount,
+                timestamp: ctx.timestamp,
+            });
+        }
+        ctx.state
+            .events
+            .push(ChainEvent::DelayedRedemptionReleased {
+                height: ctx.height,
+                redemption_id,
+                owner: redemption.owner,
+                amount: redemption.amount,
+                queued,
+            });
+    }
+}
@@ -0,0 +1,93 @@
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_math::{Bps, Rounding};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{GlobalState, REWARD_INDEX_SCALE};
+
+/// Highest commission a validator may charge on its rewards.
+pub const MAX_COMMISSION_BPS: u64 = 10_000;
+
+/// Split the fee pool among active validators by power. Each share pays
+/// the validator's commission and raises its reward index by the rest;
+/// rounding dust stays in the pool for the next epoch.
+pub fn distribute(ctx: &mut ExecutionContext) {
+    let state = &mut *ctx.state;
+    let total_power: u128 = state.validators.iter().map(|v| v.power as u128).sum();
+    if state.fee_pool == 0 || total_power == 0 {
+        return;
+    }
+    let pool = state.fee_pool as u128;
+    let mut distributed = 0u64;
+    for validator in &state.validators {
+        let share = pool * validator.power as u128 / total_power;
+        if share == 0 || validator.stake == 0 {
+            continue;
+        }
+        let rewards = state.validator_rewards.entry(validator.pubkey).or_default();
+        let commission = Bps::saturating(rewards.commission_bps.min(MAX_COMMISSION_BPS))
+            .of(share as u64, Rounding::Down) as u128;
+        let stake = validator.stake as u128;
+        let index_delta = (share - commission) * REWARD_INDEX_SCALE / stake;
+        // Only what the index can represent leaves the pool
+        let staked_share = index_delta * stake / REWARD_INDEX_SCALE;
+        rewards.accrued = rewards.accrued.saturating_add(commission as u64);
+        rewards.reward_index = rewards.reward_index.saturating_add(index_delta);
+        distributed = distributed.saturating_add((commission + staked_share) as u64);
+    }
+    state.fee_pool = state.fee_pool.saturating_sub(distributed);
+    state.events.push(ChainEvent::FeePoolDistributed {
+        height: ctx.height,
+        epoch: state.current_epoch,
+        amount: distributed,
+    });
+}
+
+/// Move what `stake` has earned through the reward index into `accrued`.
+pub fn settle(state: &mut GlobalState, validator: &[u8; 32], stake: u64) {
+    let Some(rewards) = state.validator_rewards.get_mut(validator) else {
+        return;
+    };
+    rewards.accrued = rewards.accrued.saturating_add(rewards.unsettled(stake));
+    rewards.claimed_index = rewards.reward_index;
+}
+
+pub fn set_commission(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    commission_bps: u64,
+) -> Result<()> {
+    if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Sender is not a validator");
+    }
+    if commission_bps > MAX_COMMISSION_BPS {
+        bail!("Commission must be at most {} bps", MAX_COMMISSION_BPS);
+    }
+    let rewards = ctx.state.validator_rewards.entry(*sender).or_default();
+    rewards.commission_bps = commission_bps;
+    Ok(())
+}
+
+/// Pay the sender's commission and stake rewards. Fee pool LUSD is outside
+/// the supply until it is paid out.
+pub fn claim(ctx: &mut ExecutionContext, sender: &[u8; 32]) -> Result<()> {
+    let stake = ctx
+        .state
+        .validators
+        .iter()
+        .find(|v| v.pubkey == *sender)
+        .map(|v| v.stake);
+    if let Some(stake) = stake {
+        settle(ctx.state, sender, stake);
+    }
+    let amount = match ctx.state.validator_rewards.get_mut(sender) {
+        Some(rewards) => std::mem::take(&mut rewards.accrued),
+        None => 0,
+    };
+    if amount == 0 {
+        bail!("No validator rewards to claim");
+    }
+    let account = ctx.state.accounts.entry(*sender).or_default();
+    account.lusd_balance = account.lusd_balance.saturating_add(amount);
+    ctx.state.total_lusd_supply = ctx.state.total_lusd_supply.saturating_add(amount);
+    Ok(())
+}
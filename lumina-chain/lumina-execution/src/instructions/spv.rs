@@ -0,0 +1,418 @@
+use crate::instructions::council;
+use crate::{checked_add_u64, ExecutionContext};
+use anyhow::{anyhow, bail, Result};
+use lumina_bridge_spv::{btc, eth};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{SpvDepositProof, MAX_SPV_HEADERS};
+use lumina_types::state::{SpvBridge, SpvChain, SpvDeposit, SpvHeader, SpvLightClient};
+
+/// Most confirmations a bridge may require.
+pub const MAX_CONFIRMATIONS: u64 = 1_000;
+
+/// Longest challenge period a bridge may set (~28 hours at 10s blocks).
+pub const MAX_CHALLENGE_BLOCKS: u64 = 10_000;
+
+/// Deepest below the tip a deposit's block may be, when claimed and when
+/// minted; older deposits are not found on the best chain.
+pub const MAX_SPV_DEPTH: u64 = 100_000;
+
+/// Check a proposed bridge's parameters and checkpoint.
+pub fn validate(chain: SpvChain, bridge: &SpvBridge) -> Result<()> {
+    if bridge.symbol.is_empty() {
+        bail!("Bridge symbol is required");
+    }
+    match chain {
+        SpvChain::Bitcoin if bridge.deposit_target.is_empty() => {
+            bail!("Deposit script is required")
+        }
+        SpvChain::Ethereum if bridge.deposit_target.len() != 20 => {
+            bail!("Bridge contract address must be 20 bytes")
+        }
+        _ => {}
+    }
+    if bridge.min_confirmations == 0 || bridge.min_confirmations > MAX_CONFIRMATIONS {
+        bail!("Confirmations must be between 1 and {}", MAX_CONFIRMATIONS);
+    }
+    if bridge.challenge_blocks == 0 || bridge.challenge_blocks > MAX_CHALLENGE_BLOCKS {
+        bail!(
+            "Challenge period must be between 1 and {} blocks",
+            MAX_CHALLENGE_BLOCKS
+        );
+    }
+    checkpoint(chain, bridge)?;
+    Ok(())
+}
+
+/// The checkpoint header's hash and its light client entry.
+fn checkpoint(chain: SpvChain, bridge: &SpvBridge) -> Result<([u8; 32], SpvHeader)> {
+    match chain {
+        SpvChain::Bitcoin => {
+            let header = btc::BlockHeader::parse(&bridge.checkpoint)?;
+            header.check_pow()?;
+            Ok((
+                header.hash,
+                SpvHeader {
+                    parent: header.prev_block,
+                    height: bridge.checkpoint_height,
+                    root: header.merkle_root,
+                    work: 0,
+                },
+            ))
+        }
+        SpvChain::Ethereum => {
+            let header = eth::BlockHeader::parse(&bridge.checkpoint)?;
+            if header.number != bridge.checkpoint_height {
+                bail!("Checkpoint height does not match its header");
+            }
+            Ok((
+                header.hash,
+                SpvHeader {
+                    parent: header.parent_hash,
+                    height: header.number,
+                    root: header.receipts_root,
+                    work: 0,
+                },
+            ))
+        }
+    }
+}
+
+/// Apply a passed `SetSpvBridge` proposal, restarting the chain's light
+/// client from the new checkpoint. Pending claims against the old client
+/// are left to lapse unminted.
+pub fn configure(
+    ctx: &mut ExecutionContext,
+    proposal_id: u64,
+    chain: SpvChain,
+    bridge: Option<SpvBridge>,
+) -> Result<()> {
+    ctx.state.events.push(ChainEvent::SpvBridgeChanged {
+        height: ctx.height,
+        proposal_id,
+        chain,
+        active: bridge.is_some(),
+    });
+    match bridge {
+        Some(bridge) => {
+            let (hash, header) = checkpoint(chain, &bridge)?;
+            ctx.state.spv_clients.insert(
+                chain,
+                SpvLightClient {
+                    headers: [(hash, header)].into(),
+                    tip: hash,
+                },
+            );
+            ctx.state.spv_bridges.insert(chain, bridge);
+        }
+        None => {
+            ctx.state.spv_bridges.remove(&chain);
+            ctx.state.spv_clients.remove(&chain);
+        }
+    }
+    Ok(())
+}
+
+/// Extend `chain`'s light client with `headers`, each a child of a header it
+/// already holds or of an earlier one in the batch. Known headers are
+/// skipped; the tip moves to the header with the most cumulative work.
+///
+/// Bitcoin headers must meet their own target, which may be no easier than
+/// the checkpoint's; difficulty retargets are not recomputed. Ethereum
+/// headers are trusted from validators and weigh one each.
+pub fn submit_headers(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    chain: SpvChain,
+    headers: &[Vec<u8>],
+) -> Result<()> {
+    let Some(bridge) = ctx.state.spv_bridges.get(&chain) else {
+        bail!("No SPV bridge for {:?}", chain);
+    };
+    if chain == SpvChain::Ethereum && !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Only validators can relay Ethereum headers");
+    }
+    if headers.is_empty() || headers.len() > MAX_SPV_HEADERS {
+        bail!("Submit between 1 and {} headers", MAX_SPV_HEADERS);
+    }
+    let easiest = match chain {
+        SpvChain::Bitcoin => Some(btc::target(
+            btc::BlockHeader::parse(&bridge.checkpoint)?.bits,
+        )?),
+        SpvChain::Ethereum => None,
+    };
+    let Some(client) = ctx.state.spv_clients.get(&chain) else {
+        bail!("No SPV bridge for {:?}", chain);
+    };
+
+    let mut accepted: Vec<([u8; 32], SpvHeader)> = Vec::new();
+    for raw in headers {
+        let (hash, parent, root, number, bits) = match chain {
+            SpvChain::Bitcoin => {
+                let header = btc::BlockHeader::parse(raw)?;
+                header.check_pow()?;
+                (
+                    header.hash,
+                    header.prev_block,
+                    header.merkle_root,
+                    None,
+                    Some(header.bits),
+                )
+            }
+            SpvChain::Ethereum => {
+                let header = eth::BlockHeader::parse(raw)?;
+                (
+                    header.hash,
+                    header.parent_hash,
+                    header.receipts_root,
+                    Some(header.number),
+                    None,
+                )
+            }
+        };
+        if client.headers.contains_key(&hash) || accepted.iter().any(|(h, _)| *h == hash) {
+            continue;
+        }
+        let parent_header = accepted
+            .iter()
+            .rev()
+            .find(|(h, _)| *h == parent)
+            .map(|(_, header)| header)
+            .or_else(|| client.headers.get(&parent))
+            .ok_or_else(|| anyhow!("Header's parent is unknown"))?;
+        let height = checked_add_u64(parent_header.height, 1, "Header height")?;
+        if number.is_some_and(|n| n != height) {
+            bail!("Header number does not follow its parent");
+        }
+        let work = match (bits, easiest) {
+            (Some(bits), Some(easiest)) => {
+                if btc::target(bits)? > easiest {
+                    bail!("Header target is easier than the checkpoint's");
+                }
+                btc::work(bits)?
+            }
+            _ => 1,
+        };
+        accepted.push((
+            hash,
+            SpvHeader {
+                parent,
+                height,
+                root,
+                work: parent_header
+                    .work
+                    .checked_add(work)
+                    .ok_or_else(|| anyhow!("Chain work overflow"))?,
+            },
+        ));
+    }
+    if accepted.is_empty() {
+        bail!("No new headers");
+    }
+
+    let count = accepted.len() as u64;
+    let client = ctx.state.spv_clients.get_mut(&chain).expect("light client");
+    for (hash, header) in accepted {
+        if header.work > client.headers[&client.tip].work {
+            client.tip = hash;
+        }
+        client.headers.insert(hash, header);
+    }
+    let tip_height = client.headers[&client.tip].height;
+    ctx.state.events.push(ChainEvent::SpvHeadersAccepted {
+        height: ctx.height,
+        chain,
+        relayer: *sender,
+        count,
+        tip_height,
+    });
+    Ok(())
+}
+
+/// Verify a deposit's inclusion in a confirmed block and open its claim.
+/// Anyone may claim; the deposit is only ever minted to the recipient it
+/// names.
+pub fn claim(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    chain: SpvChain,
+    block_hash: &[u8; 32],
+    proof: &SpvDepositProof,
+) -> Result<()> {
+    let (Some(bridge), Some(client)) = (
+        ctx.state.spv_bridges.get(&chain),
+        ctx.state.spv_clients.get(&chain),
+    ) else {
+        bail!("No SPV bridge for {:?}", chain);
+    };
+    let Some(header) = client.headers.get(block_hash) else {
+        bail!("Block is not in the light client");
+    };
+    let confirmations = client
+        .confirmations(block_hash, MAX_SPV_DEPTH)
+        .ok_or_else(|| anyhow!("Block is not on the best chain"))?;
+    if confirmations < bridge.min_confirmations {
+        bail!(
+            "Deposit has {} of {} confirmations",
+            confirmations,
+            bridge.min_confirmations
+        );
+    }
+
+    let mut hasher = blake3::Hasher::new();
+    let (amount, recipient) = match (chain, proof) {
+        (
+            SpvChain::Bitcoin,
+            SpvDepositProof::Bitcoin {
+                tx,
+                merkle_branch,
+                index,
+            },
+        ) => {
+            // A 64-byte transaction could pass for an inner Merkle node
+            if tx.len() == 64 {
+                bail!("64-byte transactions cannot be proven");
+            }
+            let tx = btc::Transaction::parse(tx)?;
+            if btc::merkle_root(&tx.txid, merkle_branch, *index) != header.root {
+                bail!("Merkle proof does not match the block");
+            }
+            hasher.update(b"spv-btc");
+            hasher.update(&tx.txid);
+            tx.deposit(&bridge.deposit_target)?
+        }
+        (
+            SpvChain::Ethereum,
+            SpvDepositProof::Ethereum {
+                tx_index,
+                receipt_proof,
+            },
+        ) => {
+            let receipt =
+                eth::verify_proof(&header.root, &eth::encode_uint(*tx_index), receipt_proof)?;
+            let contract: [u8; 20] = bridge
+                .deposit_target
+                .as_slice()
+                .try_into()
+                .expect("validated address");
+            hasher.update(b"spv-eth");
+            hasher.update(block_hash);
+            hasher.update(&tx_index.to_le_bytes());
+            eth::receipt_deposit(&receipt, &contract)?
+        }
+        _ => bail!("Proof is not for {:?}", chain),
+    };
+    if amount == 0 {
+        bail!("Deposit amount must be greater than zero");
+    }
+    if recipient == [0u8; 32] {
+        bail!("Invalid recipient");
+    }
+    let key = *hasher.finalize().as_bytes();
+    if ctx.state.spv_claimed.contains(&key) {
+        bail!("Deposit already claimed");
+    }
+    let symbol = bridge.symbol.clone();
+    let finalize_height = checked_add_u64(ctx.height, bridge.challenge_blocks, "Finalize height")?;
+
+    let deposit_id = ctx.state.next_spv_deposit_id;
+    ctx.state.next_spv_deposit_id = checked_add_u64(deposit_id, 1, "SPV deposit id")?;
+    ctx.state.spv_claimed.insert(key);
+    ctx.state.spv_deposits.insert(
+        deposit_id,
+        SpvDeposit {
+            chain,
+            block_hash: *block_hash,
+            key,
+            recipient,
+            symbol: symbol.clone(),
+            amount,
+            claimed_height: ctx.height,
+            finalize_height,
+        },
+    );
+    ctx.state.events.push(ChainEvent::SpvDepositClaimed {
+        height: ctx.height,
+        deposit_id,
+        chain,
+        claimant: *sender,
+        recipient,
+        symbol,
+        amount,
+        finalize_height,
+    });
+    Ok(())
+}
+
+/// Void a claim in its challenge period. The deposit may be claimed again,
+/// so a wrongful challenge only delays it.
+pub fn challenge(ctx: &mut ExecutionContext, sender: &[u8; 32], deposit_id: u64) -> Result<()> {
+    if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+        bail!("Only validators can challenge deposits");
+    }
+    let Some(deposit) = ctx.state.spv_deposits.remove(&deposit_id) else {
+        bail!("Deposit claim not found");
+    };
+    ctx.state.spv_claimed.remove(&deposit.key);
+    ctx.state.events.push(ChainEvent::SpvDepositChallenged {
+        height: ctx.height,
+        deposit_id,
+        challenger: *sender,
+        recipient: deposit.recipient,
+    });
+    Ok(())
+}
+
+/// Mint the claims whose challenge period ends this block, provided their
+/// block is still on the best chain of an unchanged bridge; runs in
+/// `end_block`. Claims that fail are released to be claimed again; while
+/// the council freezes the bridge, claims wait.
+pub fn finalize(ctx: &mut ExecutionContext) {
+    if council::bridge_frozen(ctx.state) {
+        return;
+    }
+    let due: Vec<u64> = ctx
+        .state
+        .spv_deposits
+        .iter()
+        .filter(|(_, deposit)| deposit.finalize_height <= ctx.height)
+        .map(|(id, _)| *id)
+        .collect();
+    for deposit_id in due {
+        let deposit = ctx.state.spv_deposits.remove(&deposit_id).expect("deposit");
+        let minted = ctx
+            .state
+            .spv_bridges
+            .get(&deposit.chain)
+            .is_some_and(|bridge| bridge.symbol == deposit.symbol)
+            && ctx
+                .state
+                .spv_clients
+                .get(&deposit.chain)
+                .is_some_and(|client| {
+                    client
+                        .confirmations(&deposit.block_hash, MAX_SPV_DEPTH)
+                        .is_some()
+                });
+        if minted {
+            let balance = ctx
+                .state
+                .accounts
+                .entry(deposit.recipient)
+                .or_default()
+                .bridged_balances
+                .entry(deposit.symbol.clone())
+                .or_default();
+            *balance = balance.saturating_add(deposit.amount);
+        } else {
+            ctx.state.spv_claimed.remove(&deposit.key);
+        }
+        ctx.state.events.push(ChainEvent::SpvDepositFinalized {
+            height: ctx.height,
+            deposit_id,
+            recipient: deposit.recipient,
+            symbol: deposit.symbol,
+            amount: deposit.amount,
+            minted,
+        });
+    }
+}
@@ -0,0 +1,96 @@
+use crate::ExecutionContext;
+use anyhow::{bail, Result};
+use lumina_math::{Bps, Rounding};
+use lumina_types::event::ChainEvent;
+use lumina_types::state::{ControlledParam, GlobalState, OracleRegime, StabilizerBounds};
+
+pub fn validate(bounds: &StabilizerBounds) -> Result<()> {
+    for range in [
+        &bounds.mint_fee_bps,
+        &bounds.redeem_fee_bps,
+        &bounds.savings_rate_bps,
+        &bounds.redeem_cap_bps,
+    ] {
+        if range.min > range.max {
+            bail!("Bound minimum exceeds its maximum");
+        }
+        if range.max > 10_000 {
+            bail!("Bounds must be at most 10000 bps");
+        }
+    }
+    if bounds.step_bps > 0 && bounds.interval_blocks == 0 {
+        bail!("Adjustment interval must be at least one block");
+    }
+    // A zero cap would stop every senior redemption
+    if bounds.step_bps > 0 && bounds.redeem_cap_bps.min == 0 {
+        bail!("Redemption cap minimum must be non-zero");
+    }
+    if bounds.target_health > 10_000 {
+        bail!("Target health must be at most 10000");
+    }
+    Ok(())
+}
+
+fn value(state: &mut GlobalState, param: ControlledParam) -> &mut u64 {
+    match param {
+        ControlledParam::MintFee => &mut state.params.mint_fee_bps,
+        ControlledParam::RedeemFee => &mut state.params.redeem_fee_bps,
+        ControlledParam::SavingsRate => &mut state.stabilizer.savings_rate_bps,
+        ControlledParam::RedeemCap => &mut state.stabilizer.redeem_cap_bps,
+    }
+}
+
+/// Whether a senior redemption of `amount` fits under this block's cap,
+/// a share of the supply before the block's redemptions.
+pub fn within_redeem_cap(state: &GlobalState, amount: u64) -> bool {
+    let redeemed = state.stabilizer.block_redeemed;
+    let opening_supply = state.total_lusd_supply.saturating_add(redeemed);
+    let cap = Bps::saturating(state.stabilizer.redeem_cap_bps).of(opening_supply, Rounding::Down);
+    redeemed.saturating_add(amount) <= cap
+}
+
+/// Count a settled senior redemption against the block's cap.
+pub fn record_redemption(state: &mut GlobalState, amount: u64) {
+    state.stabilizer.block_redeemed = state.stabilizer.block_redeemed.saturating_add(amount);
+}
+
+/// Move each controlled parameter a step toward its maximum under stress
+/// and toward its minimum otherwise: dearer mints and redemptions refill
+/// the insurance fund and slow a run, and a higher savings rate pays
+/// holders to stay. The redemption cap moves the other way, throttling a
+/// run. Runs after the block's health index is computed.
+pub fn adjust(ctx: &mut ExecutionContext) {
+    let bounds = ctx.state.stabilizer.bounds.clone();
+    if bounds.step_bps == 0 || !ctx.height.is_multiple_of(bounds.interval_blocks) {
+        return;
+    }
+    let (health_index, regime) = (ctx.state.health_index, ctx.state.oracle_regime);
+    let stressed = health_index < bounds.target_health || regime != OracleRegime::Stable;
+    for (param, range) in [
+        (ControlledParam::MintFee, bounds.mint_fee_bps),
+        (ControlledParam::RedeemFee, bounds.redeem_fee_bps),
+        (ControlledParam::SavingsRate, bounds.savings_rate_bps),
+        (ControlledParam::RedeemCap, bounds.redeem_cap_bps),
+    ] {
+        let raise = stressed != (param == ControlledParam::RedeemCap);
+        let slot = value(ctx.state, param);
+        let from_bps = *slot;
+        let to_bps = range.clamp(if raise {
+            from_bps.saturating_add(bounds.step_bps)
+        } else {
+            from_bps.saturating_sub(bounds.step_bps)
+        });
+        if to_bps == from_bps {
+            continue;
+        }
+        *slot = to_bps;
+        ctx.state.events.push(ChainEvent::ParameterAdjusted {
+            height: ctx.height,
+            param,
+            from_bps,
+            to_bps,
+            health_index,
+            regime,
+        });
+    }
+}
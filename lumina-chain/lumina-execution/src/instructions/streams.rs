@@ -0,0 +1,307 @@
+use crate::{checked_add_u64, ensure_vested_lumina, release_escrow, ExecutionContext};
+use anyhow::{anyhow, bail, Result};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{AssetType, MAX_BATCH_STREAMS};
+use lumina_types::state::StreamState;
+
+/// Escrow `amount_per_sec * duration` of `asset` from the sender and open a
+/// stream releasing it to `to` from now.
+pub fn create(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    to: &[u8; 32],
+    amount_per_sec: u64,
+    duration: u64,
+    asset: &AssetType,
+) -> Result<()> {
+    create_batch(ctx, sender, asset, &[(*to, amount_per_sec, duration)])
+}
+
+/// Open a stream of `asset` from the sender for each `(recipient,
+/// amount_per_sec, duration)`. The batch is validated and its total
+/// escrowed once before any stream is opened, so it opens all or none;
+/// ids are consecutive in batch order.
+pub fn create_batch(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    asset: &AssetType,
+    specs: &[([u8; 32], u64, u64)],
+) -> Result<()> {
+    if specs.is_empty() {
+        bail!("No streams");
+    }
+    if specs.len() > MAX_BATCH_STREAMS {
+        bail!("At most {} streams per batch", MAX_BATCH_STREAMS);
+    }
+    let mut total = 0u64;
+    let mut ends = Vec::with_capacity(specs.len());
+    for (_, amount_per_sec, duration) in specs {
+        if *amount_per_sec == 0 || *duration == 0 {
+            bail!("Stream amount and duration must be non-zero");
+        }
+        let amount = amount_per_sec
+            .checked_mul(*duration)
+            .ok_or_else(|| anyhow!("Stream total overflow"))?;
+        total = checked_add_u64(total, amount, "Stream batch total")?;
+        ends.push(
+            ctx.timestamp
+                .checked_add(*duration)
+                .ok_or_else(|| anyhow!("Stream end overflow"))?,
+        );
+    }
+    let first_id = ctx.state.next_stream_id;
+    let next_id = checked_add_u64(first_id, specs.len() as u64, "Stream id")?;
+    escrow(ctx, sender, asset, total)?;
+    ctx.state.next_stream_id = next_id;
+
+    let (height, timestamp) = (ctx.height, ctx.timestamp);
+    let streams: Vec<StreamState> = specs
+        .iter()
+        .zip(ends)
+        .zip(first_id..)
+        .map(
+            |(((to, amount_per_sec, _), end_timestamp), id)| StreamState {
+                recipient: *to,
+                amount_per_sec: *amount_per_sec,
+                start_timestamp: timestamp,
+                end_timestamp,
+                withdrawn: 0,
+                asset: asset.clone(),
+                id,
+                accrued: 0,
+                proposed_rate: None,
+            },
+        )
+        .collect();
+    ctx.state
+        .events
+        .extend(streams.iter().map(|s| ChainEvent::StreamCreated {
+            height,
+            stream_id: s.id,
+            payer: *sender,
+            recipient: s.recipient,
+            asset: asset.clone(),
+            amount_per_sec: s.amount_per_sec,
+            end_timestamp: s.end_timestamp,
+        }));
+    ctx.state
+        .accounts
+        .entry(*sender)
+        .or_default()
+        .active_streams
+        .extend(streams);
+    Ok(())
+}
+
+/// Pay the recipient what `payer`'s stream has released, closing the
+/// stream once it has ended and been paid in full.
+pub fn withdraw(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    payer: &[u8; 32],
+    stream_id: u64,
+) -> Result<()> {
+    let timestamp = ctx.timestamp;
+    let index = position(ctx, payer, stream_id)?;
+    let account = ctx.state.accounts.get_mut(payer).expect("stream payer");
+    let stream = &mut account.active_streams[index];
+    if stream.recipient != *sender {
+        bail!("Only the stream recipient can withdraw");
+    }
+    let amount = stream.withdrawable(timestamp);
+    if amount == 0 {
+        bail!("Nothing to withdraw");
+    }
+    stream.withdrawn = checked_add_u64(stream.withdrawn, amount, "Stream withdrawn")?;
+    let asset = stream.asset.clone();
+    if stream.withdrawn >= stream.total() {
+        account.active_streams.remove(index);
+    }
+    release_escrow(ctx, sender, &asset, amount)?;
+    ctx.state.events.push(ChainEvent::StreamWithdrawn {
+        height: ctx.height,
+        stream_id,
+        payer: *payer,
+        recipient: *sender,
+        asset,
+        amount,
+    });
+    Ok(())
+}
+
+/// End one of the sender's streams: the recipient gets what it has
+/// released so far and the sender the rest of the escrow.
+pub fn cancel(ctx: &mut ExecutionContext, sender: &[u8; 32], stream_id: u64) -> Result<()> {
+    let timestamp = ctx.timestamp;
+    let index = position(ctx, sender, stream_id)?;
+    let account = ctx.state.accounts.get_mut(sender).expect("stream payer");
+    let stream = account.active_streams.remove(index);
+    let paid = stream.withdrawable(timestamp);
+    let refunded = stream
+        .total()
+        .saturating_sub(stream.withdrawn)
+        .saturating_sub(paid);
+    if paid > 0 {
+        release_escrow(ctx, &stream.recipient, &stream.asset, paid)?;
+    }
+    if refunded > 0 {
+        release_escrow(ctx, sender, &stream.asset, refunded)?;
+    }
+    ctx.state.events.push(ChainEvent::StreamCancelled {
+        height: ctx.height,
+        stream_id,
+        payer: *sender,
+        recipient: stream.recipient,
+        asset: stream.asset,
+        paid,
+        refunded,
+    });
+    Ok(())
+}
+
+/// Extend one of the sender's streams by `duration` seconds at its current
+/// rate. A stream that has already ended restarts from now.
+pub fn top_up(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    stream_id: u64,
+    duration: u64,
+) -> Result<()> {
+    if duration == 0 {
+        bail!("Top-up duration must be non-zero");
+    }
+    let index = position(ctx, sender, stream_id)?;
+    let stream = &ctx.state.accounts[sender].active_streams[index];
+    let amount = stream
+        .amount_per_sec
+        .checked_mul(duration)
+        .ok_or_else(|| anyhow!("Stream total overflow"))?;
+    let asset = stream.asset.clone();
+    escrow(ctx, sender, &asset, amount)?;
+
+    let timestamp = ctx.timestamp;
+    let account = ctx.state.accounts.get_mut(sender).expect("stream payer");
+    let stream = &mut account.active_streams[index];
+    stream.rebase(timestamp);
+    stream.end_timestamp = stream
+        .end_timestamp
+        .checked_add(duration)
+        .ok_or_else(|| anyhow!("Stream end overflow"))?;
+    let (recipient, end_timestamp) = (stream.recipient, stream.end_timestamp);
+    ctx.state.events.push(ChainEvent::StreamToppedUp {
+        height: ctx.height,
+        stream_id,
+        payer: *sender,
+        recipient,
+        amount,
+        end_timestamp,
+    });
+    Ok(())
+}
+
+/// Re-rate `payer`'s stream for the rest of its life, keeping its end. The
+/// payer escrows the extra for a raise; a reduction is only offered by the
+/// payer and applied when the recipient accepts it, refunding the
+/// difference.
+pub fn modify_rate(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    payer: &[u8; 32],
+    stream_id: u64,
+    amount_per_sec: u64,
+) -> Result<()> {
+    if amount_per_sec == 0 {
+        bail!("Stream rate must be non-zero");
+    }
+    let timestamp = ctx.timestamp;
+    let index = position(ctx, payer, stream_id)?;
+    let stream = &ctx.state.accounts[payer].active_streams[index];
+    let (old_rate, recipient) = (stream.amount_per_sec, stream.recipient);
+    let remaining = stream.remaining(timestamp);
+    if remaining == 0 {
+        bail!("Stream has ended");
+    }
+    if sender == payer {
+        if amount_per_sec == old_rate {
+            bail!("Stream already pays that rate");
+        }
+        if amount_per_sec < old_rate {
+            let account = ctx.state.accounts.get_mut(payer).expect("stream payer");
+            account.active_streams[index].proposed_rate = Some(amount_per_sec);
+            ctx.state.events.push(ChainEvent::StreamRateProposed {
+                height: ctx.height,
+                stream_id,
+                payer: *payer,
+                recipient,
+                amount_per_sec,
+            });
+            return Ok(());
+        }
+    } else if *sender == recipient {
+        if stream.proposed_rate != Some(amount_per_sec) {
+            bail!("Recipient can only accept a rate reduction the payer proposed");
+        }
+    } else {
+        bail!("Only the stream payer or recipient can change its rate");
+    }
+
+    let unpaid = |rate: u64| {
+        rate.checked_mul(remaining)
+            .ok_or_else(|| anyhow!("Stream total overflow"))
+    };
+    let (before, after) = (unpaid(old_rate)?, unpaid(amount_per_sec)?);
+    let asset = stream.asset.clone();
+    if after > before {
+        escrow(ctx, payer, &asset, after - before)?;
+    } else {
+        release_escrow(ctx, payer, &asset, before - after)?;
+    }
+    let account = ctx.state.accounts.get_mut(payer).expect("stream payer");
+    let stream = &mut account.active_streams[index];
+    stream.rebase(timestamp);
+    stream.amount_per_sec = amount_per_sec;
+    stream.proposed_rate = None;
+    ctx.state.events.push(ChainEvent::StreamRateChanged {
+        height: ctx.height,
+        stream_id,
+        payer: *payer,
+        recipient,
+        old_rate,
+        new_rate: amount_per_sec,
+    });
+    Ok(())
+}
+
+/// Index of `payer`'s stream `stream_id` among its active streams.
+fn position(ctx: &ExecutionContext, payer: &[u8; 32], stream_id: u64) -> Result<usize> {
+    ctx.state
+        .accounts
+        .get(payer)
+        .and_then(|a| a.active_streams.iter().position(|s| s.id == stream_id))
+        .ok_or_else(|| anyhow!("Stream not found"))
+}
+
+/// Move `amount` of `asset` out of `owner`'s balance into stream escrow.
+/// Custom assets must be registered; bridged assets cannot be streamed.
+fn escrow(
+    ctx: &mut ExecutionContext,
+    owner: &[u8; 32],
+    asset: &AssetType,
+    amount: u64,
+) -> Result<()> {
+    match asset {
+        AssetType::Lumina => ensure_vested_lumina(ctx, owner, amount)?,
+        AssetType::Custom(ticker) if !ctx.state.oracle_prices.contains_key(ticker) => {
+            bail!("Unknown asset {}", ticker);
+        }
+        AssetType::Bridged(_) => bail!("Bridged assets cannot be streamed"),
+        _ => {}
+    }
+    let account = ctx.state.accounts.entry(*owner).or_default();
+    if account.balance(asset) < amount {
+        bail!("Insufficient {} for stream escrow", asset);
+    }
+    account
+        .debit(asset, amount)
+        .ok_or_else(|| anyhow!("Stream escrow underflow"))
+}
@@ -0,0 +1,131 @@
+//! Cross-instruction invariants, checked after every `execute_si` when the
+//! `strict-invariants` feature is on (fuzzing, simulation and testnets).
+//! States are often built by hand with supplies nothing backs, so each
+//! invariant is checked only if it held before the instruction: LUSD
+//! supply must move exactly with the holdings that make it up, flash
+//! accounting must stay consistent, and the reserve ratio and health index
+//! in range. A violation panics with a state dump.
+
+use lumina_types::instruction::{AssetType, StablecoinInstruction};
+use lumina_types::state::GlobalState;
+
+/// LUSD outside the insurance fund and treasury, which supply excludes:
+/// balances, yield-token principal, stream and hash-lock escrow, queued
+/// and delayed redemptions, unsettled merchant receipts and archived
+/// accounts.
+/// Confidential commitments carry no balance of their own.
+fn lusd_held(state: &GlobalState) -> i128 {
+    let accounts: i128 = state
+        .accounts
+        .values()
+        .map(|a| {
+            let escrow: i128 = a
+                .active_streams
+                .iter()
+                .filter(|s| s.asset == AssetType::LUSD)
+                .map(|s| s.total() as i128 - s.withdrawn as i128)
+                .sum();
+            let principal: i128 = a.yield_positions.iter().map(|p| p.principal as i128).sum();
+            a.lusd_balance as i128 + escrow + principal
+        })
+        .sum();
+    let queued: i128 = state
+        .fair_redeem_queue
+        .iter()
+        .map(|r| r.amount as i128)
+        .sum();
+    let delayed: i128 = state
+        .delayed_redemptions
+        .values()
+        .map(|r| r.amount as i128)
+        .sum();
+    let merchants: i128 = state.merchants.values().map(|m| m.pending as i128).sum();
+    let locked: i128 = state
+        .hash_locks
+        .values()
+        .filter(|l| l.asset == AssetType::LUSD)
+        .map(|l| l.amount as i128)
+        .sum();
+    accounts + queued + delayed + merchants + locked + state.archived_lusd as i128
+}
+
+fn flash_mints_consistent(state: &GlobalState) -> bool {
+    let pending: u128 = state
+        .accounts
+        .values()
+        .map(|a| a.pending_flash_mint as u128)
+        .sum();
+    pending == state.pending_flash_mints as u128
+        && state
+            .accounts
+            .iter()
+            .filter(|(_, a)| a.pending_flash_mint > 0)
+            .all(|(address, _)| state.flash_minters.contains(address))
+}
+
+fn flash_loans_consistent(state: &GlobalState) -> bool {
+    let pending: u128 = state
+        .accounts
+        .values()
+        .map(|a| a.pending_flash_loan as u128)
+        .sum();
+    pending == state.pending_flash_loans as u128
+        && state
+            .accounts
+            .iter()
+            .filter(|(_, a)| a.pending_flash_loan > 0)
+            .all(|(address, _)| state.flash_loan_borrowers.contains(address))
+}
+
+fn ratios_valid(state: &GlobalState) -> bool {
+    state.reserve_ratio.is_finite() && state.reserve_ratio >= 0.0 && state.health_index <= 10_000
+}
+
+pub(crate) struct Snapshot {
+    lusd_unbacked: i128,
+    flash_mints: bool,
+    flash_loans: bool,
+    ratios: bool,
+}
+
+impl Snapshot {
+    pub(crate) fn take(state: &GlobalState) -> Self {
+        Self {
+            lusd_unbacked: state.total_lusd_supply as i128 - lusd_held(state),
+            flash_mints: flash_mints_consistent(state),
+            flash_loans: flash_loans_consistent(state),
+            ratios: ratios_valid(state),
+        }
+    }
+}
+
+/// Panic if `si` broke an invariant that held in `before`, whether or not
+/// it succeeded: failed instructions are not rolled back.
+pub(crate) fn check(before: &Snapshot, state: &GlobalState, si: &StablecoinInstruction) {
+    let after = Snapshot::take(state);
+    let mut violations = Vec::new();
+    if after.lusd_unbacked != before.lusd_unbacked {
+        violations.push(format!(
+            "LUSD supply moved {} against its holdings",
+            after.lusd_unbacked - before.lusd_unbacked
+        ));
+    }
+    if before.flash_mints && !after.flash_mints {
+        violations.push("pending flash mints disagree with their accounts".to_string());
+    }
+    if before.flash_loans && !after.flash_loans {
+        violations.push("pending flash loans disagree with their accounts".to_string());
+    }
+    if before.ratios && !after.ratios {
+        violations.push("reserve ratio or health index out of range".to_string());
+    }
+    if !violations.is_empty() {
+        panic!(
+            "Invariant violated by {}: {}\n{:#?}\nstate: {:#?}",
+            si.kind(),
+            violations.join("; "),
+            si,
+            state
+        );
+    }
+}
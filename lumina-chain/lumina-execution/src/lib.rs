@@ -4,20 +4,91 @@
 
 use anyhow::{bail, Result};
 use lumina_crypto::signatures::PublicKey;
+use lumina_crypto::view_key::{is_valid_view_public_key, SEALED_NOTE_LEN};
 use lumina_crypto::zk::{
     verify_compliance_proof, verify_confidential_proof, verify_credit_score_proof,
-    verify_green_energy_proof, verify_insurance_loss_proof, verify_multi_jurisdictional_proof,
-    verify_rwa_attestation, verify_tax_attestation_proof, ZkManager,
+    verify_green_energy_proof, verify_insurance_loss_proof, verify_min_valuation,
+    verify_rwa_attestation, verify_sealed_rwa_attestation, verify_tax_attestation_proof, ZkManager,
+    SEALED_OPENING_LEN,
 };
-use lumina_types::instruction::{AssetType, StablecoinInstruction};
+use lumina_math::{mul_div, split, Bps, Ratio, Rounding, BPS_SCALE};
+use lumina_types::audit::{AuditAction, AuditRecord, Role};
+use lumina_types::balance::Balance;
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{AssetType, CollateralAsset, StablecoinInstruction};
 use lumina_types::state::{
-    CustodianState, GlobalState, RWAListing, RedemptionRequest, StreamState, ValidatorState,
-    YieldPosition,
+    credit_asset, debit_asset, CollateralPosition, ConfidentialNote, CustodianState, GlobalState,
+    GovernanceProposal, HealthComponents, HealthIndexParams, InheritanceDesignation,
+    InsuranceCoverage, KeeperTask, OracleRegime, PoolAsset, PorAttestation, ProposalAction,
+    RWAListing, RedemptionRequest, ReserveRotation, SealedValuation, TreasuryPayout,
+    TreasuryStream, UpgradePlan, ValidatorChange, ValidatorState, VestingSchedule, YieldPosition,
+    LJUN_RATE_SCALE,
 };
 use lumina_types::transaction::Transaction;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[cfg(feature = "strict-invariants")]
+mod invariants;
 
 mod instructions {
+    pub mod archive;
+    pub mod auction;
+    pub mod beacon;
+    pub mod concentration;
+    #[cfg(feature = "contracts")]
+    pub mod contracts;
+    pub mod council;
+    pub mod credentials;
+    pub mod credit;
+    pub mod crosschain;
+    pub mod dust;
+    pub mod hooks;
+    pub mod htlc;
+    pub mod jurisdiction;
+    pub mod keeper;
+    pub mod limits;
+    pub mod liveness;
+    pub mod merchant;
+    pub mod mint_caps;
+    pub mod params;
     pub mod passkey;
+    pub mod pause;
+    pub mod payroll;
+    pub mod redemption_delay;
+    pub mod rewards;
+    pub mod spv;
+    pub mod stabilizer;
+    pub mod streams;
+}
+
+/// Epoch length in blocks (velocity rewards, insurance, validator set changes)
+pub const EPOCH_LENGTH: u64 = 8640; // ~1 day at 10s/block
+
+/// Minimum blocks between proposing an upgrade and its activation height,
+/// so operators have time to install a supporting binary
+pub const UPGRADE_MIN_NOTICE: u64 = 8640;
+
+/// Shortest inactivity period an inheritance designation may use (~30 days)
+pub const MIN_INHERITANCE_INACTIVITY: u64 = 30 * EPOCH_LENGTH;
+
+/// Blocks between an inheritance claim and its settlement, during which any
+/// transaction from the owner cancels it (~7 days)
+pub const INHERITANCE_CHALLENGE_BLOCKS: u64 = 7 * EPOCH_LENGTH;
+
+/// Most unfinished vesting schedules an account may hold
+pub const MAX_VESTING_SCHEDULES: usize = 32;
+
+/// Most streaming treasury spends that may be paying out at once
+pub const MAX_TREASURY_STREAMS: usize = 64;
+
+/// Blocks closing each epoch in which validators reveal their beacon
+/// secrets; commitments are accepted only before them
+pub const BEACON_REVEAL_BLOCKS: u64 = EPOCH_LENGTH / 2;
+
+/// Whether the block at `height` falls in its epoch's beacon reveal phase.
+/// Epochs end on multiples of `EPOCH_LENGTH`, so that block is their last.
+pub fn beacon_reveal_phase(height: u64) -> bool {
+    height.saturating_sub(1) % EPOCH_LENGTH >= EPOCH_LENGTH - BEACON_REVEAL_BLOCKS
 }
 
 /// Immutable context for deterministic execution (height + timestamp frozen per block).
@@ -27,9 +98,978 @@ pub struct ExecutionContext<'a> {
     pub timestamp: u64,
 }
 
+/// A consensus-visible state change that activates at `height`, raising
+/// `GlobalState::version` to `version`.
+pub struct StateUpgrade {
+    pub version: u32,
+    pub height: u64,
+    pub description: &'static str,
+    pub apply: fn(&mut GlobalState),
+}
+
+/// Scheduled state upgrades, in ascending version order. Every node applies
+/// them in `begin_block` of the first block at or above the activation
+/// height, so the upgraded state is part of that block's state root.
+pub const STATE_UPGRADES: &[StateUpgrade] = &[];
+
+/// Apply every upgrade in `upgrades` that is due at `ctx.height` and newer
+/// than the state's version.
+pub fn apply_state_upgrades(ctx: &mut ExecutionContext, upgrades: &[StateUpgrade]) {
+    for upgrade in upgrades {
+        if ctx.height >= upgrade.height && ctx.state.version < upgrade.version {
+            (upgrade.apply)(ctx.state);
+            ctx.state.version = upgrade.version;
+        }
+    }
+}
+
+/// Reset per-block bookkeeping before the first transaction of a block.
+pub fn begin_block(ctx: &mut ExecutionContext) {
+    ctx.state.events.clear();
+    ctx.state.flash_loan_utilization_bps = 0;
+    ctx.state.stabilizer.block_redeemed = 0;
+    apply_state_upgrades(ctx, STATE_UPGRADES);
+}
+
+/// Track which validators voted for the block, jailing any that fell below
+/// the liveness threshold. Runs just before `end_block`.
+pub fn record_liveness(ctx: &mut ExecutionContext, proposer: &[u8; 32], signers: &[[u8; 32]]) {
+    instructions::liveness::record(ctx, proposer, signers);
+}
+
 pub fn end_block(ctx: &mut ExecutionContext) {
+    instructions::mint_caps::roll(ctx);
+    instructions::council::sunset(ctx);
+    settle_flash_mints(ctx);
+    settle_flash_loans(ctx);
+    apply_oracle_regime(ctx);
+    settle_inheritance_claims(ctx);
+    release_treasury_streams(ctx);
+    instructions::hooks::advance_circuits(ctx);
+    instructions::htlc::expire(ctx);
+    instructions::spv::finalize(ctx);
+    instructions::crosschain::seal(ctx);
+    instructions::redemption_delay::release(ctx);
+    instructions::dust::reap(ctx);
+    if ctx.height.is_multiple_of(EPOCH_LENGTH) {
+        advance_epoch(ctx);
+    }
     compute_health_index(ctx);
+    instructions::stabilizer::adjust(ctx);
+}
+
+/// Privileged and monetary actions taken by a successfully executed `tx`, or
+/// by end-of-block processing when `tx` is `None`, given the events it
+/// emitted and whether the circuit breaker was active before it ran.
+pub fn audit_actions(
+    tx: Option<&Transaction>,
+    events: &[ChainEvent],
+    breaker_before: bool,
+    state: &GlobalState,
+) -> Vec<AuditAction> {
+    use StablecoinInstruction as SI;
+    let mint = |asset, amount: &u64| AuditAction::Mint {
+        asset,
+        amount: *amount,
+    };
+    let burn = |asset, amount: &u64| AuditAction::Burn {
+        asset,
+        amount: *amount,
+    };
+    let role = |role, granted| AuditAction::RoleChanged { role, granted };
+
+    let mut actions = Vec::new();
+    let explicit_breaker = matches!(
+        tx.map(|t| &t.instruction),
+        Some(SI::RunCircuitBreaker { .. })
+    );
+    if let Some(tx) = tx {
+        actions.extend(match &tx.instruction {
+            SI::MintSenior { amount, .. }
+            | SI::MintSeniorWithCollateral { amount, .. }
+            | SI::MintWithCreditScore { amount, .. }
+            | SI::MintWithNativeCreditScore { amount, .. }
+            | SI::DrawCreditLine { amount, .. }
+            | SI::FlashMint { amount, .. } => Some(mint(AssetType::LUSD, amount)),
+            SI::MintJunior { amount, .. } => Some(mint(AssetType::LJUN, amount)),
+            SI::RedeemSenior { amount }
+            | SI::RedeemPosition { amount, .. }
+            | SI::FlashBurn { amount }
+            | SI::InstantRedeem { amount, .. }
+            | SI::RepayCreditLine { amount, .. }
+            | SI::SendLusdCrossChain { amount, .. } => Some(burn(AssetType::LUSD, amount)),
+            SI::RedeemJunior { amount } => Some(burn(AssetType::LJUN, amount)),
+            SI::Burn { amount, asset } => Some(burn(asset.clone(), amount)),
+            SI::ClaimInsurance { claimed_amount, .. } => Some(AuditAction::InsuranceClaim {
+                amount: *claimed_amount,
+            }),
+            SI::Vote {
+                proposal_id,
+                approve,
+            } => Some(AuditAction::GovernanceVote {
+                proposal_id: *proposal_id,
+                approve: *approve,
+            }),
+            SI::RegisterValidator { .. } => Some(role(Role::Validator, true)),
+            SI::DeregisterValidator => Some(role(Role::Validator, false)),
+            SI::RegisterCustodian { .. } => Some(role(Role::Custodian, true)),
+            SI::RegisterGreenValidator { .. } => Some(role(Role::GreenValidator, true)),
+            _ => None,
+        });
+    }
+    for event in events {
+        match event {
+            ChainEvent::FlashMintClawedBack { burned, .. }
+            | ChainEvent::AuctionBid { burned, .. } => actions.push(burn(AssetType::LUSD, burned)),
+            ChainEvent::SpvDepositFinalized {
+                symbol,
+                amount,
+                minted: true,
+                ..
+            } => actions.push(mint(AssetType::Bridged(symbol.clone()), amount)),
+            ChainEvent::LusdReturnedCrossChain { amount, .. } => {
+                actions.push(mint(AssetType::LUSD, amount))
+            }
+            ChainEvent::CouncilActionActivated { action_id, .. } => {
+                actions.push(AuditAction::CouncilAction {
+                    action_id: *action_id,
+                    active: true,
+                })
+            }
+            ChainEvent::CouncilActionLapsed {
+                action_id,
+                was_active: true,
+                ..
+            } => actions.push(AuditAction::CouncilAction {
+                action_id: *action_id,
+                active: false,
+            }),
+            ChainEvent::CouncilActionRuled {
+                proposal_id,
+                action_id,
+                ratified,
+                ..
+            } => {
+                actions.push(AuditAction::GovernanceExecuted {
+                    proposal_id: *proposal_id,
+                });
+                if !ratified {
+                    actions.push(AuditAction::CouncilAction {
+                        action_id: *action_id,
+                        active: false,
+                    });
+                }
+            }
+            ChainEvent::UpgradeScheduled { proposal_id, .. }
+            | ChainEvent::HookRegistered { proposal_id, .. }
+            | ChainEvent::HookRemoved { proposal_id, .. }
+            | ChainEvent::ScreeningOracleChanged { proposal_id, .. }
+            | ChainEvent::ComplianceOfficerChanged { proposal_id, .. }
+            | ChainEvent::CreditOracleChanged { proposal_id, .. }
+            | ChainEvent::KycTiersUpdated { proposal_id, .. }
+            | ChainEvent::TreasurySpendExecuted { proposal_id, .. }
+            | ChainEvent::TreasuryFeeShareChanged { proposal_id, .. }
+            | ChainEvent::ValidatorFeeShareChanged { proposal_id, .. }
+            | ChainEvent::HealthParamsChanged { proposal_id, .. }
+            | ChainEvent::ConcentrationLimitsChanged { proposal_id, .. }
+            | ChainEvent::DustPolicyChanged { proposal_id, .. }
+            | ChainEvent::ArchivePolicyChanged { proposal_id, .. }
+            | ChainEvent::StabilizerBoundsChanged { proposal_id, .. }
+            | ChainEvent::JurisdictionPolicyChanged { proposal_id, .. }
+            | ChainEvent::CredentialAttesterChanged { proposal_id, .. }
+            | ChainEvent::CredentialRequirementsChanged { proposal_id, .. }
+            | ChainEvent::SpvBridgeChanged { proposal_id, .. }
+            | ChainEvent::RemoteChainChanged { proposal_id, .. }
+            | ChainEvent::MintCapsChanged { proposal_id, .. }
+            | ChainEvent::RedemptionTiersChanged { proposal_id, .. }
+            | ChainEvent::EmergencyCouncilChanged { proposal_id, .. }
+            | ChainEvent::InstructionPauseChanged { proposal_id, .. }
+            | ChainEvent::ProtocolParamChanged { proposal_id, .. } => {
+                actions.push(AuditAction::GovernanceExecuted {
+                    proposal_id: *proposal_id,
+                })
+            }
+            _ => {}
+        }
+    }
+    if explicit_breaker || state.circuit_breaker_active != breaker_before {
+        actions.push(AuditAction::CircuitBreaker {
+            active: state.circuit_breaker_active,
+        });
+    }
+    actions
+}
+
+/// Append `actions` to the audit chain rooted in `state.audit_head`.
+pub fn record_audit(
+    state: &mut GlobalState,
+    height: u64,
+    tx_index: Option<u32>,
+    actor: [u8; 32],
+    actions: Vec<AuditAction>,
+) -> Vec<AuditRecord> {
+    actions
+        .into_iter()
+        .map(|action| {
+            let record = AuditRecord::new(
+                state.audit_head,
+                state.audit_seq,
+                height,
+                tx_index,
+                actor,
+                action,
+            );
+            state.audit_head = record.hash;
+            state.audit_seq = state.audit_seq.saturating_add(1);
+            record
+        })
+        .collect()
+}
+
+/// Roll the epoch counter and apply staged validator set changes in order.
+fn advance_epoch(ctx: &mut ExecutionContext) {
+    // The closing epoch's validator set earns the fees it collected
+    instructions::rewards::distribute(ctx);
+    instructions::merchant::settle_epoch(ctx);
+    instructions::beacon::finalize(ctx);
+    ctx.state.current_epoch = ctx.state.current_epoch.saturating_add(1);
+
+    for change in std::mem::take(&mut ctx.state.pending_validator_changes) {
+        match change {
+            ValidatorChange::Add(validator) => {
+                if !ctx
+                    .state
+                    .validators
+                    .iter()
+                    .any(|v| v.pubkey == validator.pubkey)
+                {
+                    ctx.state.validators.push(validator);
+                }
+            }
+            ValidatorChange::Remove { pubkey } => {
+                if let Some(idx) = ctx.state.validators.iter().position(|v| v.pubkey == pubkey) {
+                    let removed = ctx.state.validators.remove(idx);
+                    ctx.state.validator_liveness.remove(&pubkey);
+                    instructions::rewards::settle(ctx.state, &pubkey, removed.stake);
+                    let account = ctx.state.accounts.entry(pubkey).or_default();
+                    account.lumina_balance = account.lumina_balance.saturating_add(removed.stake);
+                } else if let Some(jailed) = ctx.state.jailed_validators.remove(&pubkey) {
+                    // Rewards were settled when it was jailed
+                    let account = ctx.state.accounts.entry(pubkey).or_default();
+                    account.lumina_balance = account
+                        .lumina_balance
+                        .saturating_add(jailed.validator.stake);
+                }
+            }
+            ValidatorChange::UpdatePower { pubkey, power } => {
+                if let Some(v) = ctx.state.validators.iter_mut().find(|v| v.pubkey == pubkey) {
+                    v.power = power;
+                }
+            }
+        }
+    }
+
+    // Stale green proofs lose their 2x power bonus.
+    let validity = ctx.state.params.green_proof_validity_secs;
+    for v in ctx.state.validators.iter_mut() {
+        if v.is_green && ctx.timestamp.saturating_sub(v.green_verified_at) > validity {
+            v.is_green = false;
+            v.power = v.stake;
+        }
+    }
+}
+
+/// Claw back any flash mint not burned within the block so it cannot become
+/// permanent unbacked supply. Whatever LUSD the minter still holds is burned
+/// (up to the pending amount) and the locked collateral stays in the pool.
+fn settle_flash_mints(ctx: &mut ExecutionContext) {
+    let minters = std::mem::take(&mut ctx.state.flash_minters);
+    for minter in minters {
+        let Some(account) = ctx.state.accounts.get_mut(&minter) else {
+            continue;
+        };
+        let pending = std::mem::take(&mut account.pending_flash_mint);
+        let collateral = std::mem::take(&mut account.pending_flash_collateral);
+        if pending == 0 {
+            continue;
+        }
+        let burned = account.lusd_balance.min(pending);
+        account.lusd_balance -= burned;
+        ctx.state.total_lusd_supply = ctx.state.total_lusd_supply.saturating_sub(burned);
+        ctx.state.events.push(ChainEvent::FlashMintClawedBack {
+            height: ctx.height,
+            account: minter,
+            burned,
+            unrecovered: pending - burned,
+            collateral_seized: collateral,
+        });
+    }
     ctx.state.pending_flash_mints = 0;
+    recalculate_ratios(ctx);
+}
+
+/// Claw back any flash loan not repaid within the block. Seized LUSD repays
+/// principal first, then the fee; an unrecoverable shortfall is covered by
+/// the insurance fund where possible and reported as an event.
+fn settle_flash_loans(ctx: &mut ExecutionContext) {
+    let borrowers = std::mem::take(&mut ctx.state.flash_loan_borrowers);
+    let fee_bps = ctx.state.params.flash_loan_fee_bps;
+    for borrower in borrowers {
+        let Some(account) = ctx.state.accounts.get_mut(&borrower) else {
+            continue;
+        };
+        let principal = std::mem::take(&mut account.pending_flash_loan);
+        if principal == 0 {
+            continue;
+        }
+        let owed = principal.saturating_add(fee_of(principal, fee_bps));
+        let seized = account.lusd_balance.min(owed);
+        account.lusd_balance -= seized;
+
+        let repaid = seized.min(principal);
+        let fee_paid = seized - repaid;
+        let state = &mut *ctx.state;
+        state.pending_flash_loans = state.pending_flash_loans.saturating_sub(principal);
+        state.total_lusd_supply = state.total_lusd_supply.saturating_sub(seized);
+        state.stabilization_pool_balance = state
+            .stabilization_pool_balance
+            .saturating_add(repaid - fee_paid);
+        state.insurance_fund_balance = state.insurance_fund_balance.saturating_add(fee_paid);
+        state.flash_loan_fees = state.flash_loan_fees.saturating_add(fee_paid);
+        if fee_paid > 0 {
+            state.events.push(ChainEvent::FeePaid {
+                height: ctx.height,
+                account: borrower,
+                amount: fee_paid,
+            });
+        }
+
+        let shortfall = principal - repaid;
+        if shortfall > 0 {
+            let cover = shortfall.min(state.insurance_fund_balance);
+            state.insurance_fund_balance -= cover;
+            state.stabilization_pool_balance =
+                state.stabilization_pool_balance.saturating_add(cover);
+            state.events.push(ChainEvent::FlashLoanDefaulted {
+                height: ctx.height,
+                borrower,
+                shortfall,
+            });
+        }
+    }
+    recalculate_ratios(ctx);
+}
+
+/// Move the balances of every owner whose inheritance claim matured by this
+/// block to its beneficiary. The designation is consumed.
+fn settle_inheritance_claims(ctx: &mut ExecutionContext) {
+    let later = ctx.state.inheritance_claims.split_off(&(ctx.height + 1));
+    let due = std::mem::replace(&mut ctx.state.inheritance_claims, later);
+    for owner in due.into_values().flatten() {
+        let Some(designation) = ctx.state.inheritance.remove(&owner) else {
+            continue;
+        };
+        let locked = ctx.state.locked_lumina(&owner, ctx.height);
+        let account = ctx.state.accounts.entry(owner).or_default();
+        let lusd = std::mem::take(&mut account.lusd_balance);
+        let ljun_shares = std::mem::take(&mut account.ljun_balance);
+        // Unvested LUMINA stays with the owner's schedule
+        let lumina = account.lumina_balance.saturating_sub(locked);
+        account.lumina_balance -= lumina;
+        let custom = std::mem::take(&mut account.custom_balances);
+
+        let heir = ctx
+            .state
+            .accounts
+            .entry(designation.beneficiary)
+            .or_default();
+        heir.lusd_balance = heir.lusd_balance.saturating_add(lusd);
+        heir.ljun_balance = heir.ljun_balance.saturating_add(ljun_shares);
+        heir.lumina_balance = heir.lumina_balance.saturating_add(lumina);
+        for (asset, amount) in custom {
+            let balance = heir.custom_balances.entry(asset).or_default();
+            *balance = balance.checked_add_balance(amount).unwrap_or(Balance::MAX);
+        }
+        ctx.state.events.push(ChainEvent::InheritanceSettled {
+            height: ctx.height,
+            owner,
+            beneficiary: designation.beneficiary,
+            lusd,
+            ljun_shares,
+            lumina,
+        });
+    }
+}
+
+/// Pay every treasury stream what has accrued to it by this block, dropping
+/// streams that are fully paid.
+fn release_treasury_streams(ctx: &mut ExecutionContext) {
+    let height = ctx.height;
+    let mut released = Vec::new();
+    ctx.state.treasury_streams.retain(|_, stream| {
+        let elapsed = height.saturating_sub(stream.start_height) as u128;
+        let duration = stream.end_height.saturating_sub(stream.start_height).max(1) as u128;
+        let accrued = ((stream.total as u128) * elapsed.min(duration) / duration) as u64;
+        let due = accrued.saturating_sub(stream.paid);
+        if due > 0 {
+            stream.paid += due;
+            released.push((stream.recipient, due));
+        }
+        stream.paid < stream.total
+    });
+    for (recipient, amount) in released {
+        pay_from_treasury(ctx.state, &recipient, amount);
+    }
+}
+
+/// Route the treasury's and validators' shares out of a mint fee; returns
+/// the remainder, which goes to the insurance fund.
+fn split_mint_fee(state: &mut GlobalState, fee: u64) -> u64 {
+    let share = Bps::saturating(state.treasury_fee_share_bps).of(fee, Rounding::Down);
+    state.treasury_balance = state.treasury_balance.saturating_add(share);
+    let flows = state.treasury_flows.entry(state.current_epoch).or_default();
+    flows.inflow = flows.inflow.saturating_add(share);
+
+    let validators = Bps::saturating(state.validator_fee_share_bps)
+        .of(fee, Rounding::Down)
+        .min(fee - share);
+    state.fee_pool = state.fee_pool.saturating_add(validators);
+    fee - share - validators
+}
+
+/// Log a fee charged to `account`, for its statements.
+fn record_fee(ctx: &mut ExecutionContext, account: &[u8; 32], amount: u64) {
+    if amount > 0 {
+        ctx.state.events.push(ChainEvent::FeePaid {
+            height: ctx.height,
+            account: *account,
+            amount,
+        });
+    }
+}
+
+/// Credit `amount` of already-withdrawn treasury LUSD to `recipient`. Fee
+/// LUSD is outside the supply until it is paid out.
+fn pay_from_treasury(state: &mut GlobalState, recipient: &[u8; 32], amount: u64) {
+    let account = state.accounts.entry(*recipient).or_default();
+    account.lusd_balance = account.lusd_balance.saturating_add(amount);
+    state.total_lusd_supply = state.total_lusd_supply.saturating_add(amount);
+    let flows = state.treasury_flows.entry(state.current_epoch).or_default();
+    flows.outflow = flows.outflow.saturating_add(amount);
+}
+
+/// Withdraw `owner`'s pending inheritance claim, if any.
+fn cancel_inheritance_claim(ctx: &mut ExecutionContext, owner: &[u8; 32]) {
+    let Some(designation) = ctx.state.inheritance.get_mut(owner) else {
+        return;
+    };
+    let Some(matures_at) = designation.claim_matures_at.take() else {
+        return;
+    };
+    let beneficiary = designation.beneficiary;
+    if let Some(owners) = ctx.state.inheritance_claims.get_mut(&matures_at) {
+        owners.remove(owner);
+        if owners.is_empty() {
+            ctx.state.inheritance_claims.remove(&matures_at);
+        }
+    }
+    ctx.state
+        .events
+        .push(ChainEvent::InheritanceClaimCancelled {
+            height: ctx.height,
+            owner: *owner,
+            beneficiary,
+        });
+}
+
+/// Resolve the block's oracle regime and run the matching peg defense.
+/// The effective regime is the more severe of the feed-reported regime and
+/// the one implied by the on-chain LUSD-USD peg deviation.
+fn apply_oracle_regime(ctx: &mut ExecutionContext) {
+    let params = &ctx.state.params;
+    let lusd_price = ctx
+        .state
+        .oracle_prices
+        .get("LUSD-USD")
+        .copied()
+        .unwrap_or(1_000_000);
+    let peg_dev_bps = lusd_price.abs_diff(1_000_000) / 100;
+    let peg_regime = if peg_dev_bps >= params.stress_peg_deviation_bps {
+        OracleRegime::Stress
+    } else if peg_dev_bps >= params.volatile_peg_deviation_bps {
+        OracleRegime::Volatile
+    } else {
+        OracleRegime::Stable
+    };
+    let regime = peg_regime.max(ctx.state.reported_oracle_regime);
+
+    let previous = ctx.state.oracle_regime;
+    if regime != previous {
+        ctx.state.oracle_regime = regime;
+        ctx.state.events.push(ChainEvent::OracleRegimeChanged {
+            height: ctx.height,
+            from: previous,
+            to: regime,
+        });
+    }
+
+    if regime == OracleRegime::Stress {
+        if let Err(e) = execute_si(&StablecoinInstruction::TriggerStabilizer, &[0u8; 32], ctx) {
+            tracing::warn!("Stress-mode stabilizer failed: {}", e);
+        }
+    }
+}
+
+/// Fee in bps for `base_bps`, widened by the surcharge outside the Stable regime.
+fn regime_fee_bps(state: &GlobalState, base_bps: u64) -> u64 {
+    if state.oracle_regime == OracleRegime::Stable {
+        base_bps
+    } else {
+        base_bps.saturating_add(state.params.volatile_fee_surcharge_bps)
+    }
+}
+
+/// Fail if debiting `amount` LUMINA from `account` would dip into its
+/// unvested balance.
+fn ensure_vested_lumina(ctx: &ExecutionContext, account: &[u8; 32], amount: u64) -> Result<()> {
+    let balance = ctx
+        .state
+        .accounts
+        .get(account)
+        .map_or(0, |a| a.lumina_balance);
+    if balance >= amount && ctx.state.spendable_lumina(account, ctx.height) < amount {
+        bail!("LUMINA is still vesting");
+    }
+    Ok(())
+}
+
+/// A fee of `bps` on `amount`, rounded half to even.
+fn fee_of(amount: u64, bps: u64) -> u64 {
+    Bps::saturating(bps).of(amount, Rounding::HalfEven)
+}
+
+/// Move `amount` of a collateral asset out of `owner`'s control into custody.
+fn debit_collateral(
+    ctx: &mut ExecutionContext,
+    owner: &[u8; 32],
+    asset: &CollateralAsset,
+    amount: u64,
+) -> Result<()> {
+    if *asset == CollateralAsset::Lumina {
+        ensure_vested_lumina(ctx, owner, amount)?;
+    }
+    let account = ctx.state.accounts.entry(*owner).or_default();
+    let (debited, label) = match asset {
+        CollateralAsset::Lumina => (account.debit(&AssetType::Lumina, amount), "LUMINA"),
+        CollateralAsset::Custom(ticker) => (
+            debit_asset(&mut account.custom_balances, ticker, amount),
+            ticker.as_str(),
+        ),
+        CollateralAsset::Bridged(symbol) => (
+            debit_asset(&mut account.bridged_balances, symbol, amount),
+            symbol.as_str(),
+        ),
+        CollateralAsset::PledgedRWA(rwa_id) => {
+            let listing = ctx
+                .state
+                .rwa_listings
+                .get_mut(rwa_id)
+                .ok_or_else(|| anyhow::anyhow!("RWA asset not found"))?;
+            if listing.owner != *owner {
+                bail!("Only the RWA owner can pledge it as collateral");
+            }
+            if !listing.is_active || !listing.collateral_eligibility {
+                bail!("RWA listing not eligible as collateral");
+            }
+            let remaining = listing
+                .attested_value
+                .saturating_sub(listing.pledged_amount);
+            if amount > remaining {
+                bail!("Pledge exceeds RWA remaining collateral capacity");
+            }
+            listing.pledged_amount = checked_add_u64(listing.pledged_amount, amount, "Pledge")?;
+            return Ok(());
+        }
+    };
+    if debited.is_none() {
+        bail!("Insufficient {} collateral", label);
+    }
+    Ok(())
+}
+
+/// Return `amount` of a custodied collateral asset to `owner`.
+fn credit_collateral(
+    ctx: &mut ExecutionContext,
+    owner: &[u8; 32],
+    asset: &CollateralAsset,
+    amount: u64,
+) -> Result<()> {
+    withhold_collateral(ctx.state, asset, amount);
+    let account = ctx.state.accounts.entry(*owner).or_default();
+    let credited = match asset {
+        CollateralAsset::Lumina => account.credit(&AssetType::Lumina, amount),
+        CollateralAsset::Custom(ticker) => {
+            credit_asset(&mut account.custom_balances, ticker, amount)
+        }
+        CollateralAsset::Bridged(symbol) => {
+            credit_asset(&mut account.bridged_balances, symbol, amount)
+        }
+        CollateralAsset::PledgedRWA(rwa_id) => {
+            if let Some(listing) = ctx.state.rwa_listings.get_mut(rwa_id) {
+                listing.pledged_amount = listing.pledged_amount.saturating_sub(amount);
+            }
+            return Ok(());
+        }
+    };
+    credited.ok_or_else(|| anyhow::anyhow!("Collateral balance overflow"))
+}
+
+/// Pay `amount` of escrowed `asset` (a stream or hash lock) out to `to`.
+/// LUSD is netted for merchants like any other payment.
+pub(crate) fn release_escrow(
+    ctx: &mut ExecutionContext,
+    to: &[u8; 32],
+    asset: &AssetType,
+    amount: u64,
+) -> Result<()> {
+    if *asset == AssetType::LUSD {
+        return instructions::merchant::receive(ctx.state, to, amount, 1);
+    }
+    ctx.state
+        .accounts
+        .entry(*to)
+        .or_default()
+        .credit(asset, amount)
+        .ok_or_else(|| anyhow::anyhow!("Balance overflow"))
+}
+
+/// Take `amount` of a custodied collateral asset out of the pool without
+/// handing it to anyone. Pledged RWA capacity stays pledged.
+fn withhold_collateral(state: &mut GlobalState, asset: &CollateralAsset, amount: u64) {
+    let pool_asset = PoolAsset::from(asset);
+    if let Some(held) = state.pool_composition.get_mut(&pool_asset) {
+        *held = held.saturating_sub(amount);
+        if *held == 0 {
+            state.pool_composition.remove(&pool_asset);
+        }
+    }
+}
+
+/// Release collateral backing `lusd_amount` of `owner`'s senior mints, oldest
+/// position first and pro-rata within a position, withholding `fee_bps` of it.
+/// LUSD the owner did not mint (e.g. received by transfer) has no position and
+/// releases nothing on-chain.
+fn release_senior_collateral(
+    ctx: &mut ExecutionContext,
+    owner: &[u8; 32],
+    lusd_amount: u64,
+    fee_bps: u64,
+) -> Result<()> {
+    let position_ids = ctx
+        .state
+        .accounts
+        .get(owner)
+        .map(|a| a.collateral_position_ids.clone())
+        .unwrap_or_default();
+    let mut remaining = lusd_amount;
+    for position_id in position_ids {
+        if remaining == 0 {
+            break;
+        }
+        let minted = ctx
+            .state
+            .collateral_positions
+            .get(&position_id)
+            .map(|p| p.minted)
+            .unwrap_or(0);
+        let take = remaining.min(minted);
+        release_from_position(ctx, position_id, take, owner, fee_bps)?;
+        remaining -= take;
+    }
+    Ok(())
+}
+
+/// Whether redemptions join the fair redeem queue instead of settling.
+fn redemptions_stressed(state: &GlobalState) -> bool {
+    state.circuit_breaker_active
+        || state.reserve_ratio < 0.95
+        || state.oracle_regime == OracleRegime::Stress
+}
+
+/// Burn `amount` LUSD already taken from `owner` and release their senior
+/// collateral, withholding the redemption fee into insurance.
+fn settle_senior_redemption(
+    ctx: &mut ExecutionContext,
+    owner: &[u8; 32],
+    amount: u64,
+) -> Result<()> {
+    ctx.state.total_lusd_supply =
+        checked_sub_u64(ctx.state.total_lusd_supply, amount, "LUSD supply")?;
+
+    // The pool pays out `amount`: all but the fee to the redeemer, the fee
+    // to insurance
+    let fee_bps = regime_fee_bps(ctx.state, ctx.state.params.redeem_fee_bps);
+    let fee = fee_of(amount, fee_bps);
+    ctx.state.stabilization_pool_balance =
+        ctx.state.stabilization_pool_balance.saturating_sub(amount);
+    release_senior_collateral(ctx, owner, amount, fee_bps)?;
+    ctx.state.insurance_fund_balance =
+        checked_add_u64(ctx.state.insurance_fund_balance, fee, "Insurance fund")?;
+    record_fee(ctx, owner, fee);
+    instructions::stabilizer::record_redemption(ctx.state, amount);
+
+    let epoch = ctx.state.current_epoch;
+    ctx.state
+        .credit_histories
+        .entry(*owner)
+        .or_default()
+        .record_redemption(epoch);
+
+    recalculate_ratios(ctx);
+    Ok(())
+}
+
+/// Reduce a position by `lusd_amount` of minted LUSD and hand the pro-rata
+/// collateral, less `fee_bps` of it, to `recipient`. Fully repaid positions
+/// are closed.
+fn release_from_position(
+    ctx: &mut ExecutionContext,
+    position_id: u64,
+    lusd_amount: u64,
+    recipient: &[u8; 32],
+    fee_bps: u64,
+) -> Result<()> {
+    let position = ctx
+        .state
+        .collateral_positions
+        .get_mut(&position_id)
+        .ok_or_else(|| anyhow::anyhow!("Collateral position not found"))?;
+    if lusd_amount > position.minted {
+        bail!("Amount exceeds position debt");
+    }
+    let collateral = if lusd_amount == position.minted {
+        position.collateral_amount
+    } else {
+        Ratio::new(lusd_amount, position.minted)?
+            .apply(position.collateral_amount, Rounding::Down)?
+    };
+    position.minted -= lusd_amount;
+    position.collateral_amount -= collateral;
+    let asset = position.asset.clone();
+    let owner = position.owner;
+
+    if position.minted == 0 {
+        ctx.state.collateral_positions.remove(&position_id);
+        if let Some(account) = ctx.state.accounts.get_mut(&owner) {
+            account
+                .collateral_position_ids
+                .retain(|id| *id != position_id);
+        }
+    }
+    let withheld = fee_of(collateral, fee_bps);
+    withhold_collateral(ctx.state, &asset, withheld);
+    credit_collateral(ctx, recipient, &asset, collateral - withheld)
+}
+
+/// USD value (1e6 fixed point per unit) of collateral, priced via "<SYMBOL>-USD".
+/// Pledged RWA capacity is carried at its attested value. `None` when the
+/// asset has no (or a zero) oracle price and so cannot be valued.
+fn collateral_value(state: &GlobalState, asset: &CollateralAsset, amount: u64) -> Option<u64> {
+    let price = match asset {
+        CollateralAsset::Lumina => state.oracle_prices.get("LUMINA-USD").copied(),
+        CollateralAsset::Custom(symbol) | CollateralAsset::Bridged(symbol) => {
+            state.oracle_prices.get(&format!("{}-USD", symbol)).copied()
+        }
+        CollateralAsset::PledgedRWA(_) => Some(1_000_000),
+    }
+    .filter(|price| *price > 0)?;
+    Some(mul_div(amount, price, 1_000_000, Rounding::Down).unwrap_or(u64::MAX))
+}
+
+/// Whether a position's collateral is worth less than the liquidation
+/// threshold of its debt. Fails when the collateral cannot be valued.
+fn is_liquidatable(state: &GlobalState, position: &CollateralPosition) -> Result<bool> {
+    let Some(value) = collateral_value(state, &position.asset, position.collateral_amount) else {
+        bail!("No oracle price for collateral");
+    };
+    let threshold = Ratio::from_bps(state.params.liquidation_threshold_bps)
+        .apply(position.minted, Rounding::Up)
+        .unwrap_or(u64::MAX);
+    Ok(value < threshold)
+}
+
+/// Mint `amount` LUSD less the mint fee against `collateral_amount` of
+/// `collateral_asset`, proven held and taken into custody as a new
+/// collateral position.
+fn mint_senior(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    amount: u64,
+    collateral_asset: &CollateralAsset,
+    collateral_amount: u64,
+    proof: &[u8],
+) -> Result<()> {
+    if amount == 0 || collateral_amount == 0 {
+        bail!("Amount and collateral_amount must be greater than zero");
+    }
+    if proof.is_empty() {
+        bail!("MintSenior requires a non-empty reserve proof");
+    }
+    if ctx.state.circuit_breaker_active {
+        bail!("Circuit breaker active: senior mints paused");
+    }
+    instructions::mint_caps::check(ctx.state, sender, amount)?;
+
+    let zk_manager = ZkManager::setup();
+    if !zk_manager.verify_zk_por(proof, collateral_amount) {
+        bail!("Invalid MintSenior reserve proof");
+    }
+
+    let Some(value) = collateral_value(ctx.state, collateral_asset, collateral_amount) else {
+        bail!("No price for collateral asset");
+    };
+    instructions::concentration::check_asset(ctx.state, &collateral_asset.into(), value)?;
+
+    // Collateral must be a real on-chain asset taken into custody
+    debit_collateral(ctx, sender, collateral_asset, collateral_amount)?;
+
+    // Mint fee (5% base, widened outside the Stable regime) goes to the
+    // insurance fund, less the treasury's and validators' shares
+    let fee = fee_of(
+        amount,
+        regime_fee_bps(ctx.state, ctx.state.params.mint_fee_bps),
+    );
+    let insurance_fee = split_mint_fee(ctx.state, fee);
+    record_fee(ctx, sender, fee);
+    ctx.state.insurance_fund_balance = ctx
+        .state
+        .insurance_fund_balance
+        .checked_add(insurance_fee)
+        .ok_or_else(|| anyhow::anyhow!("Insurance fund overflow"))?;
+
+    // Lock collateral into stabilization pool
+    ctx.state.stabilization_pool_balance = ctx
+        .state
+        .stabilization_pool_balance
+        .checked_add(collateral_amount)
+        .ok_or_else(|| anyhow::anyhow!("Collateral overflow"))?;
+    let held = ctx
+        .state
+        .pool_composition
+        .entry(collateral_asset.into())
+        .or_default();
+    *held = held.saturating_add(collateral_amount);
+
+    let net_amount = checked_sub_u64(amount, fee, "Net mint amount")?;
+    let account = ctx.state.accounts.entry(*sender).or_default();
+    account.lusd_balance = account
+        .lusd_balance
+        .checked_add(net_amount)
+        .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
+    let position_id = ctx.state.next_position_id;
+    ctx.state.next_position_id = checked_add_u64(position_id, 1, "Position id")?;
+    account.collateral_position_ids.push(position_id);
+    ctx.state.collateral_positions.insert(
+        position_id,
+        CollateralPosition {
+            owner: *sender,
+            asset: collateral_asset.clone(),
+            collateral_amount,
+            minted: net_amount,
+        },
+    );
+
+    ctx.state.total_lusd_supply = ctx
+        .state
+        .total_lusd_supply
+        .checked_add(net_amount)
+        .ok_or_else(|| anyhow::anyhow!("Supply overflow"))?;
+
+    // Track volume for velocity rewards
+    let acct = ctx.state.accounts.entry(*sender).or_default();
+    acct.epoch_tx_volume = checked_add_u64(acct.epoch_tx_volume, amount, "Epoch tx volume")?;
+
+    instructions::mint_caps::record(ctx.state, sender, amount);
+    recalculate_ratios(ctx);
+    Ok(())
+}
+
+/// Mint `amount` LUSD against `collateral_amount`, at a collateral ratio
+/// that falls as `score` rises.
+fn scored_mint(
+    ctx: &mut ExecutionContext,
+    sender: &[u8; 32],
+    amount: u64,
+    collateral_amount: u64,
+    score: u16,
+) -> Result<()> {
+    let required_bps = if score >= 800 {
+        10200
+    } else if score >= 750 {
+        10500
+    } else {
+        11000
+    };
+    let required_collateral = Ratio::from_bps(required_bps).apply(amount, Rounding::Up)?;
+    if collateral_amount < required_collateral {
+        bail!(
+            "Collateral too low for scored mint: need >= {}",
+            required_collateral
+        );
+    }
+    instructions::mint_caps::check(ctx.state, sender, amount)?;
+
+    ctx.state.stabilization_pool_balance = ctx
+        .state
+        .stabilization_pool_balance
+        .checked_add(collateral_amount)
+        .ok_or_else(|| anyhow::anyhow!("Collateral overflow"))?;
+
+    let account = ctx.state.accounts.entry(*sender).or_default();
+    account.lusd_balance = account
+        .lusd_balance
+        .checked_add(amount)
+        .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
+
+    ctx.state.total_lusd_supply = ctx
+        .state
+        .total_lusd_supply
+        .checked_add(amount)
+        .ok_or_else(|| anyhow::anyhow!("Supply overflow"))?;
+
+    instructions::mint_caps::record(ctx.state, sender, amount);
+    recalculate_ratios(ctx);
+    Ok(())
+}
+
+/// Value each asset backing the stabilization pool in LUSD. Attested fiat
+/// and RWA capacity count at face value; other assets need an oracle price
+/// and are left unvalued without one.
+fn value_pool_composition(state: &mut GlobalState) {
+    let valuation = state
+        .pool_composition
+        .iter()
+        .filter_map(|(asset, amount)| {
+            let collateral = match asset {
+                PoolAsset::FiatAttested => return Some((asset.clone(), *amount)),
+                PoolAsset::Lumina => CollateralAsset::Lumina,
+                PoolAsset::Rwa => CollateralAsset::PledgedRWA(0),
+                PoolAsset::Bridged(symbol) => CollateralAsset::Bridged(symbol.clone()),
+                PoolAsset::Custom(ticker) => CollateralAsset::Custom(ticker.clone()),
+            };
+            let value = collateral_value(state, &collateral, *amount)?;
+            Some((asset.clone(), value))
+        })
+        .collect();
+    state.pool_valuation = valuation;
+}
+
+/// Store `listing` under the next RWA id and return the id.
+fn insert_rwa_listing(ctx: &mut ExecutionContext, listing: RWAListing) -> Result<u64> {
+    let rwa_id = ctx.state.next_rwa_id;
+    ctx.state.next_rwa_id = checked_add_u64(rwa_id, 1, "RWA id")?;
+    ctx.state.rwa_listings.insert(rwa_id, listing);
+    Ok(rwa_id)
 }
 
 fn checked_add_u64(lhs: u64, rhs: u64, ctx: &str) -> Result<u64> {
@@ -42,6 +1082,38 @@ fn checked_sub_u64(lhs: u64, rhs: u64, ctx: &str) -> Result<u64> {
         .ok_or_else(|| anyhow::anyhow!("{} underflow", ctx))
 }
 
+/// Convert LJUN shares into tranche value at the current exchange rate (rounds down).
+fn ljun_shares_to_value(state: &GlobalState, shares: u64) -> Result<u64> {
+    u64::try_from(state.ljun_value_of(shares)).map_err(|_| anyhow::anyhow!("LJUN value overflow"))
+}
+
+/// Convert tranche value into LJUN shares at the current exchange rate (rounds down).
+fn ljun_value_to_shares(state: &GlobalState, value: u64) -> Result<u64> {
+    let shares =
+        (value as u128) * (LJUN_RATE_SCALE as u128) / (state.effective_ljun_rate() as u128);
+    u64::try_from(shares).map_err(|_| anyhow::anyhow!("LJUN shares overflow"))
+}
+
+/// Migrate LJUN balances from the legacy rebasing model to share accounting.
+/// Existing balances become shares and the exchange rate is set so that they
+/// represent the recorded `total_ljun_supply`. No-op once the index is set.
+pub fn migrate_ljun_to_shares(state: &mut GlobalState) {
+    if state.ljun_exchange_rate != 0 {
+        return;
+    }
+    let shares: u64 = state
+        .accounts
+        .values()
+        .fold(0u64, |acc, a| acc.saturating_add(a.ljun_balance));
+    state.total_ljun_shares = shares;
+    state.ljun_exchange_rate = if shares == 0 {
+        LJUN_RATE_SCALE
+    } else {
+        let rate = (state.total_ljun_supply as u128) * (LJUN_RATE_SCALE as u128) / (shares as u128);
+        rate.clamp(1, u64::MAX as u128) as u64
+    };
+}
+
 fn non_conflicting_transfer(tx: &Transaction) -> Option<([u8; 32], [u8; 32])> {
     if let StablecoinInstruction::Transfer { to, .. } = &tx.instruction {
         return Some((tx.sender, *to));
@@ -49,9 +1121,25 @@ fn non_conflicting_transfer(tx: &Transaction) -> Option<([u8; 32], [u8; 32])> {
     None
 }
 
+/// Accounts an instruction pays that need not have signed anything.
+pub(crate) fn paid_accounts(si: &StablecoinInstruction) -> Vec<[u8; 32]> {
+    match si {
+        StablecoinInstruction::Transfer { to, .. }
+        | StablecoinInstruction::TransferWithMemo { to, .. }
+        | StablecoinInstruction::TransferWithComplianceProof { to, .. } => vec![*to],
+        StablecoinInstruction::BatchDisburse { payments } => {
+            payments.iter().map(|(to, _)| *to).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
 /// Single entry point for any transaction.
 /// Guarantees: signature valid + nonce correct + atomic state change.
 pub fn execute_transaction(tx: &Transaction, ctx: &mut ExecutionContext) -> Result<()> {
+    // 0. An archived account can only revive itself
+    let revived = instructions::archive::revive_sender(ctx, tx)?;
+
     // 1. Signature verification
     let account = ctx.state.accounts.entry(tx.sender).or_default();
 
@@ -78,8 +1166,24 @@ pub fn execute_transaction(tx: &Transaction, ctx: &mut ExecutionContext) -> Resu
         .checked_add(1)
         .ok_or_else(|| anyhow::anyhow!("Nonce overflow"))?;
 
-    // 3. Execute the native instruction
-    execute_si(&tx.instruction, &tx.sender, ctx)
+    // 3. Any signed transaction proves the owner is alive
+    if let Some(designation) = ctx.state.inheritance.get_mut(&tx.sender) {
+        designation.last_active_height = ctx.height;
+        cancel_inheritance_claim(ctx, &tx.sender);
+    }
+
+    // 4. Execute the native instruction
+    let result = if revived {
+        Ok(())
+    } else {
+        execute_si(&tx.instruction, &tx.sender, ctx)
+    };
+
+    // 5. Start the grace period of any account it paid only dust, and
+    //    note the accounts it touched for state expiry
+    instructions::dust::track(ctx, tx);
+    instructions::archive::record_activity(ctx, tx);
+    result
 }
 
 /// Executes transactions with a rayon-assisted pre-check for non-conflicting transfers.
@@ -143,6 +1247,35 @@ pub fn execute_si(
     si: &StablecoinInstruction,
     sender: &[u8; 32],
     ctx: &mut ExecutionContext,
+) -> Result<()> {
+    #[cfg(feature = "strict-invariants")]
+    let before = invariants::Snapshot::take(ctx.state);
+    let result = execute_checked_si(si, sender, ctx);
+    #[cfg(feature = "strict-invariants")]
+    invariants::check(&before, ctx.state, si);
+    result
+}
+
+fn execute_checked_si(
+    si: &StablecoinInstruction,
+    sender: &[u8; 32],
+    ctx: &mut ExecutionContext,
+) -> Result<()> {
+    instructions::pause::check(si, ctx.state)?;
+    instructions::hooks::run(si, sender, ctx)?;
+    instructions::jurisdiction::check(si, sender, ctx.state)?;
+    let usage = instructions::limits::check(si, sender, ctx.state, ctx.timestamp)?;
+    dispatch(si, sender, ctx)?;
+    if let Some(usage) = usage {
+        instructions::limits::record(ctx.state, sender, usage);
+    }
+    Ok(())
+}
+
+fn dispatch(
+    si: &StablecoinInstruction,
+    sender: &[u8; 32],
+    ctx: &mut ExecutionContext,
 ) -> Result<()> {
     match si {
         // ══════════════════════════════════════════════════════════
@@ -164,60 +1297,28 @@ pub fn execute_si(
             amount,
             collateral_amount,
             proof,
-        } => {
-            if *amount == 0 || *collateral_amount == 0 {
-                bail!("Amount and collateral_amount must be greater than zero");
-            }
-            if proof.is_empty() {
-                bail!("MintSenior requires a non-empty reserve proof");
-            }
-            if ctx.state.circuit_breaker_active {
-                bail!("Circuit breaker active: senior mints paused");
-            }
-
-            let zk_manager = ZkManager::setup();
-            if !zk_manager.verify_zk_por(proof, *collateral_amount) {
-                bail!("Invalid MintSenior reserve proof");
-            }
-
-            // 5% mint fee goes to insurance fund
-            let fee = amount
-                .checked_div(20)
-                .ok_or_else(|| anyhow::anyhow!("Fee division error"))?;
-            ctx.state.insurance_fund_balance = ctx
-                .state
-                .insurance_fund_balance
-                .checked_add(fee)
-                .ok_or_else(|| anyhow::anyhow!("Insurance fund overflow"))?;
-
-            // Lock collateral into stabilization pool
-            ctx.state.stabilization_pool_balance = ctx
-                .state
-                .stabilization_pool_balance
-                .checked_add(*collateral_amount)
-                .ok_or_else(|| anyhow::anyhow!("Collateral overflow"))?;
-
-            let net_amount = checked_sub_u64(*amount, fee, "Net mint amount")?;
-            let account = ctx.state.accounts.entry(*sender).or_default();
-            account.lusd_balance = account
-                .lusd_balance
-                .checked_add(net_amount)
-                .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
-
-            ctx.state.total_lusd_supply = ctx
-                .state
-                .total_lusd_supply
-                .checked_add(net_amount)
-                .ok_or_else(|| anyhow::anyhow!("Supply overflow"))?;
-
-            // Track volume for velocity rewards
-            let acct = ctx.state.accounts.entry(*sender).or_default();
-            acct.epoch_tx_volume =
-                checked_add_u64(acct.epoch_tx_volume, *amount, "Epoch tx volume")?;
+        } => mint_senior(
+            ctx,
+            sender,
+            *amount,
+            &CollateralAsset::Lumina,
+            *collateral_amount,
+            proof,
+        ),
 
-            recalculate_ratios(ctx);
-            Ok(())
-        }
+        StablecoinInstruction::MintSeniorWithCollateral {
+            amount,
+            collateral_asset,
+            collateral_amount,
+            proof,
+        } => mint_senior(
+            ctx,
+            sender,
+            *amount,
+            collateral_asset,
+            *collateral_amount,
+            proof,
+        ),
 
         StablecoinInstruction::RedeemSenior { amount } => {
             if *amount == 0 {
@@ -229,8 +1330,16 @@ pub fn execute_si(
                 bail!("Insufficient LUSD balance");
             }
 
-            // Under stress, queue redemptions
-            if ctx.state.circuit_breaker_active || ctx.state.reserve_ratio < 0.95 {
+            // Large redemptions wait out their tier's delay; under stress or
+            // past the block's redemption cap, queue redemptions
+            let delay_blocks =
+                instructions::redemption_delay::delay_for(&ctx.state.redemption_tiers, *amount);
+            if delay_blocks > 0 {
+                return instructions::redemption_delay::delay(ctx, sender, *amount, delay_blocks);
+            }
+            if redemptions_stressed(ctx.state)
+                || !instructions::stabilizer::within_redeem_cap(ctx.state, *amount)
+            {
                 ctx.state.fair_redeem_queue.push(RedemptionRequest {
                     address: *sender,
                     amount: *amount,
@@ -243,10 +1352,55 @@ pub fn execute_si(
 
             let acct = ctx.state.accounts.entry(*sender).or_default();
             acct.lusd_balance = checked_sub_u64(acct.lusd_balance, *amount, "LUSD balance")?;
+            settle_senior_redemption(ctx, sender, *amount)
+        }
+
+        StablecoinInstruction::RedeemPosition {
+            position_id,
+            amount,
+        } => {
+            if *amount == 0 {
+                bail!("Amount must be greater than zero");
+            }
+            let position = ctx
+                .state
+                .collateral_positions
+                .get(position_id)
+                .ok_or_else(|| anyhow::anyhow!("Collateral position not found"))?;
+            if position.owner != *sender {
+                bail!("Only the position owner can redeem it");
+            }
+            if ctx.state.circuit_breaker_active
+                || ctx.state.reserve_ratio < 0.95
+                || ctx.state.oracle_regime == OracleRegime::Stress
+            {
+                bail!("Position redemptions paused under stress; use RedeemSenior");
+            }
+
+            let acct = ctx.state.accounts.entry(*sender).or_default();
+            if acct.lusd_balance < *amount {
+                bail!("Insufficient LUSD balance");
+            }
+            acct.lusd_balance -= *amount;
             ctx.state.total_lusd_supply =
                 checked_sub_u64(ctx.state.total_lusd_supply, *amount, "LUSD supply")?;
+
+            // As in RedeemSenior, the fee is withheld from the released collateral
+            let fee_bps = regime_fee_bps(ctx.state, ctx.state.params.redeem_fee_bps);
+            let fee = fee_of(*amount, fee_bps);
             ctx.state.stabilization_pool_balance =
                 ctx.state.stabilization_pool_balance.saturating_sub(*amount);
+            release_from_position(ctx, *position_id, *amount, sender, fee_bps)?;
+            ctx.state.insurance_fund_balance =
+                checked_add_u64(ctx.state.insurance_fund_balance, fee, "Insurance fund")?;
+            record_fee(ctx, sender, fee);
+
+            let epoch = ctx.state.current_epoch;
+            ctx.state
+                .credit_histories
+                .entry(*sender)
+                .or_default()
+                .record_repayment(*amount, epoch);
 
             recalculate_ratios(ctx);
             Ok(())
@@ -266,11 +1420,16 @@ pub fn execute_si(
                 .checked_add(*collateral_amount)
                 .ok_or_else(|| anyhow::anyhow!("Collateral overflow"))?;
 
+            // New junior capital enters at the current index so it does not
+            // inherit losses already absorbed by existing holders.
+            let shares = ljun_value_to_shares(ctx.state, *amount)?;
             let account = ctx.state.accounts.entry(*sender).or_default();
             account.ljun_balance = account
                 .ljun_balance
-                .checked_add(*amount)
+                .checked_add(shares)
                 .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
+            ctx.state.total_ljun_shares =
+                checked_add_u64(ctx.state.total_ljun_shares, shares, "LJUN shares")?;
 
             ctx.state.total_ljun_supply = ctx
                 .state
@@ -293,8 +1452,9 @@ pub fn execute_si(
             }
 
             account.ljun_balance = checked_sub_u64(account.ljun_balance, *amount, "LJUN balance")?;
-            ctx.state.total_ljun_supply =
-                checked_sub_u64(ctx.state.total_ljun_supply, *amount, "LJUN supply")?;
+            let value = ljun_shares_to_value(ctx.state, *amount)?;
+            ctx.state.total_ljun_shares = ctx.state.total_ljun_shares.saturating_sub(*amount);
+            ctx.state.total_ljun_supply = ctx.state.total_ljun_supply.saturating_sub(value);
             recalculate_ratios(ctx);
             Ok(())
         }
@@ -304,6 +1464,9 @@ pub fn execute_si(
                 bail!("Amount must be greater than zero");
             }
 
+            if *asset == AssetType::Lumina {
+                ensure_vested_lumina(ctx, sender, *amount)?;
+            }
             let account = ctx.state.accounts.entry(*sender).or_default();
             match asset {
                 AssetType::LUSD => {
@@ -321,8 +1484,10 @@ pub fn execute_si(
                     }
                     account.ljun_balance =
                         checked_sub_u64(account.ljun_balance, *amount, "LJUN balance")?;
-                    ctx.state.total_ljun_supply =
-                        ctx.state.total_ljun_supply.saturating_sub(*amount);
+                    let value = ljun_shares_to_value(ctx.state, *amount)?;
+                    ctx.state.total_ljun_shares =
+                        ctx.state.total_ljun_shares.saturating_sub(*amount);
+                    ctx.state.total_ljun_supply = ctx.state.total_ljun_supply.saturating_sub(value);
                 }
                 AssetType::Lumina => {
                     if account.lumina_balance < *amount {
@@ -331,12 +1496,10 @@ pub fn execute_si(
                     account.lumina_balance =
                         checked_sub_u64(account.lumina_balance, *amount, "LUMINA balance")?;
                 }
-                AssetType::Custom(ticker) => {
-                    let bal = account.custom_balances.entry(ticker.clone()).or_insert(0);
-                    if *bal < *amount {
-                        bail!("Insufficient {}", ticker);
+                AssetType::Custom(_) | AssetType::Bridged(_) => {
+                    if account.debit(asset, *amount).is_none() {
+                        bail!("Insufficient {}", asset);
                     }
-                    *bal = checked_sub_u64(*bal, *amount, "Custom asset balance")?;
                 }
             }
 
@@ -364,13 +1527,11 @@ pub fn execute_si(
                             "Sender epoch tx volume",
                         )?;
                     }
-                    let receiver = ctx.state.accounts.entry(*to).or_default();
-                    receiver.lusd_balance = receiver
-                        .lusd_balance
-                        .checked_add(*amount)
-                        .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
+                    instructions::merchant::receive(ctx.state, to, *amount, 1)?;
                 }
                 AssetType::LJUN => {
+                    // LJUN moves as shares; velocity volume is tracked in value terms.
+                    let value = ljun_shares_to_value(ctx.state, *amount)?;
                     {
                         let sender_account = ctx.state.accounts.entry(*sender).or_default();
                         if sender_account.ljun_balance < *amount {
@@ -380,7 +1541,7 @@ pub fn execute_si(
                             checked_sub_u64(sender_account.ljun_balance, *amount, "Sender LJUN")?;
                         sender_account.epoch_tx_volume = checked_add_u64(
                             sender_account.epoch_tx_volume,
-                            *amount,
+                            value,
                             "Sender epoch tx volume",
                         )?;
                     }
@@ -391,6 +1552,7 @@ pub fn execute_si(
                         .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
                 }
                 AssetType::Lumina => {
+                    ensure_vested_lumina(ctx, sender, *amount)?;
                     {
                         let sender_account = ctx.state.accounts.entry(*sender).or_default();
                         if sender_account.lumina_balance < *amount {
@@ -408,22 +1570,16 @@ pub fn execute_si(
                         .checked_add(*amount)
                         .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
                 }
-                AssetType::Custom(ticker) => {
-                    {
-                        let sender_account = ctx.state.accounts.entry(*sender).or_default();
-                        let sender_bal = sender_account
-                            .custom_balances
-                            .entry(ticker.clone())
-                            .or_insert(0);
-                        if *sender_bal < *amount {
-                            bail!("Insufficient {}", ticker);
-                        }
-                        *sender_bal = checked_sub_u64(*sender_bal, *amount, "Sender custom asset")?;
+                AssetType::Custom(_) | AssetType::Bridged(_) => {
+                    let sender_account = ctx.state.accounts.entry(*sender).or_default();
+                    if sender_account.debit(asset, *amount).is_none() {
+                        bail!("Insufficient {}", asset);
                     }
-                    let receiver = ctx.state.accounts.entry(*to).or_default();
-                    let recv_bal = receiver.custom_balances.entry(ticker.clone()).or_insert(0);
-                    *recv_bal = recv_bal
-                        .checked_add(*amount)
+                    ctx.state
+                        .accounts
+                        .entry(*to)
+                        .or_default()
+                        .credit(asset, *amount)
                         .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
                 }
             }
@@ -431,6 +1587,29 @@ pub fn execute_si(
             Ok(())
         }
 
+        StablecoinInstruction::TransferWithMemo {
+            to,
+            amount,
+            asset,
+            memo_hash,
+        } => {
+            let transfer = StablecoinInstruction::Transfer {
+                to: *to,
+                amount: *amount,
+                asset: asset.clone(),
+            };
+            execute_si(&transfer, sender, ctx)?;
+            ctx.state.events.push(ChainEvent::MemoPayment {
+                height: ctx.height,
+                from: *sender,
+                to: *to,
+                amount: *amount,
+                asset: asset.clone(),
+                memo_hash: *memo_hash,
+            });
+            Ok(())
+        }
+
         // ══════════════════════════════════════════════════════════
         // Stability & Tranche Management
         // ══════════════════════════════════════════════════════════
@@ -444,6 +1623,7 @@ pub fn execute_si(
                 .state
                 .total_lusd_supply
                 .saturating_add(ctx.state.total_ljun_supply);
+            let mut redirected = 0;
             if total_supply > 0 {
                 let junior_pct = (ctx.state.total_ljun_supply as f64) / (total_supply as f64);
                 if junior_pct > 0.40 {
@@ -451,13 +1631,15 @@ pub fn execute_si(
                     let excess = ctx
                         .state
                         .total_ljun_supply
-                        .saturating_sub(total_supply.saturating_mul(40) / 100);
+                        .saturating_sub(Bps::constant(4_000).of(total_supply, Rounding::Down));
                     ctx.state.stabilization_pool_balance =
                         ctx.state.stabilization_pool_balance.saturating_add(excess);
+                    redirected = excess;
                 }
             }
 
             ctx.state.last_rebalance_height = ctx.height;
+            instructions::keeper::reward(ctx, sender, KeeperTask::RebalanceTranches, redirected);
             recalculate_ratios(ctx);
             Ok(())
         }
@@ -468,11 +1650,8 @@ pub fn execute_si(
             }
 
             // 80% to junior tranche holders pro-rata, 15% to stabilization pool, 5% to insurance
-            let junior_share = total_yield.saturating_mul(80) / 100;
-            let pool_share = total_yield.saturating_mul(15) / 100;
-            let insurance_share = total_yield
-                .saturating_sub(junior_share)
-                .saturating_sub(pool_share);
+            let shares = split(*total_yield, &[80, 15, 5])?;
+            let (junior_share, pool_share, insurance_share) = (shares[0], shares[1], shares[2]);
 
             ctx.state.stabilization_pool_balance = ctx
                 .state
@@ -486,29 +1665,15 @@ pub fn execute_si(
                 .checked_add(insurance_share)
                 .ok_or_else(|| anyhow::anyhow!("Insurance overflow"))?;
 
-            // Distribute junior_share pro-rata to all LJUN holders
+            // Accrue junior_share to LJUN holders pro-rata by raising the exchange rate
             if ctx.state.total_ljun_supply > 0 {
-                let accounts_snapshot: Vec<([u8; 32], u64)> = ctx
-                    .state
-                    .accounts
-                    .iter()
-                    .filter(|(_, a)| a.ljun_balance > 0)
-                    .map(|(k, a)| (*k, a.ljun_balance))
-                    .collect();
-
-                for (addr, balance) in accounts_snapshot {
-                    let share = junior_share
-                        .checked_mul(balance)
-                        .unwrap_or(0)
-                        .checked_div(ctx.state.total_ljun_supply)
-                        .unwrap_or(0);
-                    if share > 0 {
-                        let acct = ctx.state.accounts.entry(addr).or_default();
-                        acct.ljun_balance = acct.ljun_balance.saturating_add(share);
-                    }
-                }
-                ctx.state.total_ljun_supply =
-                    ctx.state.total_ljun_supply.saturating_add(junior_share);
+                let old_supply = ctx.state.total_ljun_supply;
+                let new_supply = checked_add_u64(old_supply, junior_share, "LJUN supply")?;
+                let rate = (ctx.state.effective_ljun_rate() as u128) * (new_supply as u128)
+                    / (old_supply as u128);
+                ctx.state.ljun_exchange_rate = u64::try_from(rate)
+                    .map_err(|_| anyhow::anyhow!("LJUN exchange rate overflow"))?;
+                ctx.state.total_ljun_supply = new_supply;
             } else {
                 // No junior holders, all goes to stabilization pool
                 ctx.state.stabilization_pool_balance = ctx
@@ -523,6 +1688,7 @@ pub fn execute_si(
 
         StablecoinInstruction::TriggerStabilizer => {
             recalculate_ratios(ctx);
+            let mut restored = 0;
 
             // If under-collateralized, use insurance fund to top up
             if ctx.state.reserve_ratio < 1.0 && ctx.state.insurance_fund_balance > 0 {
@@ -535,9 +1701,38 @@ pub fn execute_si(
                     ctx.state.stabilization_pool_balance.saturating_add(topup);
                 ctx.state.insurance_fund_balance =
                     ctx.state.insurance_fund_balance.saturating_sub(topup);
+                restored = topup;
                 recalculate_ratios(ctx);
             }
 
+            // WriteDown: once insurance is exhausted, the junior tranche absorbs
+            // the remaining shortfall by haircutting the LJUN exchange rate.
+            // Junior collateral already sits in the pool, so the cut moves no
+            // assets: it shrinks the junior claim to what the pool holds
+            // beyond the senior floor, leaving the rest to back LUSD.
+            let floor_bps = ctx.state.params.junior_writedown_floor_bps;
+            let required = Ratio::from_bps(floor_bps)
+                .apply(ctx.state.total_lusd_supply, Rounding::Up)
+                .unwrap_or(u64::MAX);
+            let junior_backing = ctx
+                .state
+                .stabilization_pool_balance
+                .saturating_sub(required);
+            if ctx.state.insurance_fund_balance == 0 && ctx.state.total_ljun_supply > junior_backing
+            {
+                let old_supply = ctx.state.total_ljun_supply;
+                let new_supply = junior_backing;
+                let haircut = old_supply - new_supply;
+
+                let rate = (ctx.state.effective_ljun_rate() as u128) * (new_supply as u128)
+                    / (old_supply as u128);
+                // Floor at 1 so a fully wiped tranche is not mistaken for par.
+                ctx.state.ljun_exchange_rate = (rate as u64).max(1);
+                ctx.state.total_ljun_supply = new_supply;
+                restored = restored.saturating_add(haircut);
+            }
+
+            instructions::keeper::reward(ctx, sender, KeeperTask::TriggerStabilizer, restored);
             Ok(())
         }
 
@@ -551,16 +1746,69 @@ pub fn execute_si(
                 bail!("Circuit breaker active: cannot process redeem queue");
             }
 
+            // The queue drains in order, stopping at the block's redemption cap
             let to_process = std::cmp::min(*batch_size as usize, ctx.state.fair_redeem_queue.len());
+            let mut redeemed = 0u64;
             for _ in 0..to_process {
+                let amount = ctx.state.fair_redeem_queue[0].amount;
+                if !instructions::stabilizer::within_redeem_cap(ctx.state, amount) {
+                    break;
+                }
                 let req = ctx.state.fair_redeem_queue.remove(0);
+                instructions::stabilizer::record_redemption(ctx.state, req.amount);
+                redeemed = redeemed.saturating_add(req.amount);
                 ctx.state.total_lusd_supply =
                     ctx.state.total_lusd_supply.saturating_sub(req.amount);
                 ctx.state.stabilization_pool_balance = ctx
                     .state
                     .stabilization_pool_balance
                     .saturating_sub(req.amount);
+                release_senior_collateral(ctx, &req.address, req.amount, 0)?;
+                let epoch = ctx.state.current_epoch;
+                ctx.state
+                    .credit_histories
+                    .entry(req.address)
+                    .or_default()
+                    .record_redemption(epoch);
+            }
+            instructions::keeper::reward(ctx, sender, KeeperTask::FairRedeemQueue, redeemed);
+            recalculate_ratios(ctx);
+            Ok(())
+        }
+
+        StablecoinInstruction::LiquidatePosition { position_id } => {
+            let position = ctx
+                .state
+                .collateral_positions
+                .get(position_id)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Collateral position not found"))?;
+
+            if !is_liquidatable(ctx.state, &position)? {
+                bail!("Position is sufficiently collateralized");
+            }
+
+            // Liquidator repays the position's debt and takes its collateral.
+            let liquidator = ctx.state.accounts.entry(*sender).or_default();
+            if liquidator.lusd_balance < position.minted {
+                bail!("Insufficient LUSD to repay position");
             }
+            liquidator.lusd_balance -= position.minted;
+            ctx.state.total_lusd_supply =
+                checked_sub_u64(ctx.state.total_lusd_supply, position.minted, "LUSD supply")?;
+            ctx.state.stabilization_pool_balance = ctx
+                .state
+                .stabilization_pool_balance
+                .saturating_sub(position.minted);
+            release_from_position(ctx, *position_id, position.minted, sender, 0)?;
+            let epoch = ctx.state.current_epoch;
+            ctx.state
+                .credit_histories
+                .entry(position.owner)
+                .or_default()
+                .record_liquidation(epoch);
+            instructions::keeper::reward(ctx, sender, KeeperTask::Liquidation, position.minted);
+
             recalculate_ratios(ctx);
             Ok(())
         }
@@ -572,6 +1820,10 @@ pub fn execute_si(
             if !verify_confidential_proof(commitment, proof) {
                 bail!("Invalid confidential transfer proof");
             }
+            // Accounts that registered a view key keep it able to open every commitment
+            if ctx.state.view_keys.contains_key(sender) {
+                bail!("Account has a view key; use SealedConfidentialTransfer");
+            }
             let account = ctx.state.accounts.entry(*sender).or_default();
             account.commitment = Some(*commitment);
             Ok(())
@@ -594,12 +1846,7 @@ pub fn execute_si(
         StablecoinInstruction::MultiJurisdictionalCheck {
             jurisdiction_id,
             proof,
-        } => {
-            if !verify_multi_jurisdictional_proof(*jurisdiction_id, proof) {
-                bail!("Invalid multi-jurisdictional proof");
-            }
-            Ok(())
-        }
+        } => instructions::jurisdiction::tag(ctx, sender, *jurisdiction_id, proof),
 
         // ══════════════════════════════════════════════════════════
         // Oracle & Reserves
@@ -630,8 +1877,25 @@ pub fn execute_si(
             }
 
             ctx.state.stabilization_pool_balance = *total_reserves;
+            ctx.state
+                .pool_composition
+                .insert(PoolAsset::FiatAttested, *total_reserves);
+            // Attested reserves are what custodians hold between them
+            if zones_have_custodians(ctx.state) {
+                rebalance_zones(ctx)?;
+            }
             ctx.state.last_por_timestamp = *timestamp;
             ctx.state.last_por_hash = Some(proof_id);
+            ctx.state.por_history.insert(
+                *timestamp,
+                PorAttestation {
+                    height: ctx.height,
+                    total_reserves: *total_reserves,
+                    proof_hash: proof_id,
+                    verifying_key_hash: zk_manager.por_verifying_key_hash(),
+                    lusd_supply: ctx.state.total_lusd_supply,
+                },
+            );
             recalculate_ratios(ctx);
             Ok(())
         }
@@ -710,13 +1974,10 @@ pub fn execute_si(
             if *zone_id == 0 {
                 bail!("Invalid zone_id");
             }
-            // Geo-rebalancing redistributes custodian weights by zone.
-            // Deterministic: rotate custodian ordering by zone_id.
-            if !ctx.state.custodians.is_empty() {
-                let rotation = (*zone_id as usize) % ctx.state.custodians.len();
-                ctx.state.custodians.rotate_left(rotation);
+            if !ctx.state.params.zone_reserve_weights.contains_key(zone_id) {
+                bail!("Zone has no reserve weight");
             }
-            Ok(())
+            rebalance_zones(ctx)
         }
 
         StablecoinInstruction::VelocityIncentive { multiplier_bps } => {
@@ -724,8 +1985,12 @@ pub fn execute_si(
                 bail!("Multiplier must be 1-5000 bps");
             }
             // Add to the velocity reward pool based on multiplier
-            let reward_addition =
-                ctx.state.total_lusd_supply.saturating_mul(*multiplier_bps) / 1_000_000;
+            let reward_addition = mul_div(
+                ctx.state.total_lusd_supply,
+                *multiplier_bps,
+                1_000_000,
+                Rounding::Down,
+            )?;
             ctx.state.velocity_reward_pool = ctx
                 .state
                 .velocity_reward_pool
@@ -737,29 +2002,232 @@ pub fn execute_si(
             to,
             amount_per_sec,
             duration,
-        } => {
-            if *amount_per_sec == 0 || *duration == 0 {
-                bail!("Stream amount and duration must be non-zero");
+        } => instructions::streams::create(
+            ctx,
+            sender,
+            to,
+            *amount_per_sec,
+            *duration,
+            &AssetType::LUSD,
+        ),
+
+        StablecoinInstruction::StreamAsset {
+            to,
+            amount_per_sec,
+            duration,
+            asset,
+        } => instructions::streams::create(ctx, sender, to, *amount_per_sec, *duration, asset),
+
+        StablecoinInstruction::WithdrawStream { payer, stream_id } => {
+            instructions::streams::withdraw(ctx, sender, payer, *stream_id)
+        }
+
+        StablecoinInstruction::CancelStream { stream_id } => {
+            instructions::streams::cancel(ctx, sender, *stream_id)
+        }
+
+        StablecoinInstruction::TopUpStream {
+            stream_id,
+            duration,
+        } => instructions::streams::top_up(ctx, sender, *stream_id, *duration),
+
+        StablecoinInstruction::ModifyStreamRate {
+            payer,
+            stream_id,
+            amount_per_sec,
+        } => instructions::streams::modify_rate(ctx, sender, payer, *stream_id, *amount_per_sec),
+
+        // ══════════════════════════════════════════════════════════
+        // Conditional Transfers
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::LockWithHash {
+            recipient,
+            asset,
+            amount,
+            hash_lock,
+            timeout_height,
+        } => instructions::htlc::lock(
+            ctx,
+            sender,
+            recipient,
+            asset,
+            *amount,
+            *hash_lock,
+            *timeout_height,
+        ),
+
+        StablecoinInstruction::ClaimWithPreimage { lock_id, preimage } => {
+            instructions::htlc::claim(ctx, *lock_id, preimage)
+        }
+
+        StablecoinInstruction::RefundAfterTimeout { lock_id } => {
+            instructions::htlc::refund(ctx, sender, *lock_id)
+        }
+
+        // ══════════════════════════════════════════════════════════
+        // SPV Bridge
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeSpvBridge { chain, bridge } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose SPV bridges");
             }
-            let total_stream = amount_per_sec
-                .checked_mul(*duration)
-                .ok_or_else(|| anyhow::anyhow!("Stream total overflow"))?;
-            let account = ctx.state.accounts.entry(*sender).or_default();
-            if account.lusd_balance < total_stream {
-                bail!("Insufficient LUSD for stream escrow");
+            if let Some(bridge) = bridge {
+                instructions::spv::validate(*chain, bridge)?;
             }
-            account.lusd_balance = account.lusd_balance.saturating_sub(total_stream);
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetSpvBridge(*chain, bridge.clone()),
+            )
+        }
 
-            let stream = StreamState {
-                recipient: *to,
-                amount_per_sec: *amount_per_sec,
-                start_timestamp: ctx.timestamp,
-                end_timestamp: ctx.timestamp.saturating_add(*duration),
-                withdrawn: 0,
-            };
-            let acct = ctx.state.accounts.entry(*sender).or_default();
-            acct.active_streams.push(stream);
-            Ok(())
+        StablecoinInstruction::SubmitSpvHeaders { chain, headers } => {
+            instructions::spv::submit_headers(ctx, sender, *chain, headers)
+        }
+
+        StablecoinInstruction::ClaimSpvDeposit {
+            chain,
+            block_hash,
+            proof,
+        } => instructions::spv::claim(ctx, sender, *chain, block_hash, proof),
+
+        StablecoinInstruction::ChallengeSpvDeposit { deposit_id } => {
+            instructions::spv::challenge(ctx, sender, *deposit_id)
+        }
+
+        // ══════════════════════════════════════════════════════════
+        // Cross-Chain Messages
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeRemoteChain { chain_id, active } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose remote chains");
+            }
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetRemoteChain {
+                    chain_id: *chain_id,
+                    active: *active,
+                },
+            )
+        }
+
+        StablecoinInstruction::SendLusdCrossChain {
+            dest_chain,
+            recipient,
+            amount,
+        } => instructions::crosschain::send(ctx, sender, *dest_chain, recipient, *amount),
+
+        StablecoinInstruction::AttestMessageBatch {
+            batch_id,
+            signature,
+        } => instructions::crosschain::attest_batch(ctx, sender, *batch_id, signature),
+
+        StablecoinInstruction::AttestLusdReturn {
+            source_chain,
+            burn_id,
+            recipient,
+            amount,
+        } => instructions::crosschain::attest_return(
+            ctx,
+            sender,
+            *source_chain,
+            burn_id,
+            recipient,
+            *amount,
+        ),
+
+        // ══════════════════════════════════════════════════════════
+        // Mint Caps
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeMintCaps { caps } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose mint caps");
+            }
+            instructions::mint_caps::validate(caps)?;
+            submit_proposal(ctx, sender, ProposalAction::SetMintCaps(caps.clone()))
+        }
+
+        // ══════════════════════════════════════════════════════════
+        // Redemption Delays
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeRedemptionTiers { tiers } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose redemption tiers");
+            }
+            instructions::redemption_delay::validate(tiers)?;
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetRedemptionTiers(tiers.clone()),
+            )
+        }
+        StablecoinInstruction::CancelDelayedRedemption { redemption_id } => {
+            instructions::redemption_delay::cancel(ctx, sender, *redemption_id)
+        }
+
+        // ══════════════════════════════════════════════════════════
+        // Emergency Council
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeEmergencyCouncil { council } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose the emergency council");
+            }
+            instructions::council::validate_council(council)?;
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetEmergencyCouncil(council.clone()),
+            )
+        }
+        StablecoinInstruction::CouncilPropose { power } => {
+            instructions::council::propose(ctx, sender, power)
+        }
+        StablecoinInstruction::CouncilApprove { action_id } => {
+            instructions::council::approve(ctx, sender, *action_id)
+        }
+        StablecoinInstruction::ProposeCouncilRuling { action_id, ratify } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose council rulings");
+            }
+            instructions::council::validate_ruling(ctx.state, *action_id, *ratify)?;
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::RuleCouncilAction {
+                    action_id: *action_id,
+                    ratify: *ratify,
+                },
+            )
+        }
+
+        // ══════════════════════════════════════════════════════════
+        // Instruction Pauses
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeInstructionPause { kinds, paused } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose instruction pauses");
+            }
+            instructions::pause::validate(kinds)?;
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetInstructionPause {
+                    kinds: kinds.clone(),
+                    paused: *paused,
+                },
+            )
+        }
+
+        // ══════════════════════════════════════════════════════════
+        // Protocol Parameters
+        // ══════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeProtocolParam { param } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose protocol parameters");
+            }
+            instructions::params::validate(ctx.state, param)?;
+            submit_proposal(ctx, sender, ProposalAction::SetProtocolParam(param.clone()))
         }
 
         // ══════════════════════════════════════════════════════════
@@ -774,15 +2242,46 @@ pub fn execute_si(
             if account.lumina_balance < *stake {
                 bail!("Insufficient Lumina for validator stake");
             }
+            let already_staged = ctx
+                .state
+                .pending_validator_changes
+                .iter()
+                .any(|c| matches!(c, ValidatorChange::Add(v) if v.pubkey == *pubkey));
+            if already_staged || ctx.state.validators.iter().any(|v| v.pubkey == *pubkey) {
+                bail!("Validator already registered");
+            }
             account.lumina_balance = account.lumina_balance.saturating_sub(*stake);
 
-            ctx.state.validators.push(ValidatorState {
-                pubkey: *pubkey,
-                stake: *stake,
-                power: *stake,
-                is_green: false,
-                energy_proof: None,
-            });
+            // Joins the active set at the next epoch boundary, not mid-block.
+            ctx.state
+                .pending_validator_changes
+                .push(ValidatorChange::Add(ValidatorState {
+                    pubkey: *pubkey,
+                    stake: *stake,
+                    power: *stake,
+                    is_green: false,
+                    energy_proof: None,
+                    green_verified_at: 0,
+                }));
+            Ok(())
+        }
+
+        StablecoinInstruction::DeregisterValidator => {
+            let active = ctx.state.validators.iter().any(|v| v.pubkey == *sender);
+            if !active && !ctx.state.jailed_validators.contains_key(sender) {
+                bail!("Sender is not a registered validator");
+            }
+            if ctx
+                .state
+                .pending_validator_changes
+                .iter()
+                .any(|c| matches!(c, ValidatorChange::Remove { pubkey } if pubkey == sender))
+            {
+                bail!("Validator removal already staged");
+            }
+            ctx.state
+                .pending_validator_changes
+                .push(ValidatorChange::Remove { pubkey: *sender });
             Ok(())
         }
 
@@ -795,12 +2294,41 @@ pub fn execute_si(
             if !is_validator {
                 bail!("Only validators can vote");
             }
-            // Votes are recorded as on-chain state transitions.
-            // In production, a proposal registry tracks tallies.
-            let _ = (*proposal_id, *approve);
+            let proposal = ctx
+                .state
+                .proposals
+                .get_mut(proposal_id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown proposal"))?;
+            if proposal.executed {
+                bail!("Proposal already executed");
+            }
+            proposal.votes.insert(*sender, *approve);
+            if proposal_passed(ctx.state, *proposal_id) {
+                execute_proposal(ctx, *proposal_id)?;
+            }
             Ok(())
         }
 
+        StablecoinInstruction::ProposeUpgrade { name, height } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose upgrades");
+            }
+            if name.is_empty() || name.len() > 64 {
+                bail!("Invalid upgrade name");
+            }
+            if *height < ctx.height.saturating_add(UPGRADE_MIN_NOTICE) {
+                bail!("Upgrade height too soon");
+            }
+            if ctx.state.upgrade_plans.iter().any(|p| p.name == *name) {
+                bail!("Upgrade already scheduled");
+            }
+            let plan = UpgradePlan {
+                name: name.clone(),
+                height: *height,
+            };
+            submit_proposal(ctx, sender, ProposalAction::ScheduleUpgrade(plan))
+        }
+
         // ══════════════════════════════════════════════════════════
         // Phase 1: Seedless Security & Dynamic Economics
         // ══════════════════════════════════════════════════════════
@@ -862,6 +2390,12 @@ pub fn execute_si(
             if mpc_pubkeys.is_empty() || mpc_pubkeys.len() > 7 {
                 bail!("MPC key set must be 1-7 keys");
             }
+            instructions::credentials::require(
+                ctx.state,
+                sender,
+                &ctx.state.credential_requirements.custodian,
+                ctx.height,
+            )?;
 
             // Deduct LJUN stake from sender
             let account = ctx.state.accounts.entry(*sender).or_default();
@@ -875,23 +2409,31 @@ pub fn execute_si(
                 stake: *stake,
                 mpc_pubkeys: mpc_pubkeys.clone(),
                 registered_height: ctx.height,
+                zone_id: 0,
+                reserve_balance: 0,
             });
             Ok(())
         }
 
+        StablecoinInstruction::SetCustodianZone { zone_id } => {
+            if *zone_id == 0 {
+                bail!("Invalid zone_id");
+            }
+            let custodian = ctx
+                .state
+                .custodians
+                .iter_mut()
+                .find(|c| c.pubkey == *sender)
+                .ok_or_else(|| anyhow::anyhow!("Sender is not a registered custodian"))?;
+            custodian.zone_id = *zone_id;
+            Ok(())
+        }
+
         StablecoinInstruction::RotateReserves { new_custodian_set } => {
             if new_custodian_set.is_empty() {
                 bail!("New custodian set cannot be empty");
             }
-            // Only rotate every 30 days (~259200 blocks at 10s/block)
-            let rotation_interval = 259200u64;
-            if ctx
-                .height
-                .saturating_sub(ctx.state.last_reserve_rotation_height)
-                < rotation_interval
-            {
-                bail!("Reserve rotation too frequent");
-            }
+            ensure_rotation_window(ctx)?;
 
             // Validate all new custodians are registered
             for pubkey in new_custodian_set {
@@ -904,18 +2446,169 @@ pub fn execute_si(
             Ok(())
         }
 
-        StablecoinInstruction::ClaimInsurance {
-            loss_proof,
-            claimed_amount,
+        StablecoinInstruction::ProposeReserveRotation {
+            from_custodian,
+            to_custodian,
+            amount,
         } => {
-            if !verify_insurance_loss_proof(loss_proof, *claimed_amount) {
-                bail!("Invalid insurance loss proof");
+            if *amount == 0 {
+                bail!("Rotation amount must be non-zero");
             }
-            if *claimed_amount > ctx.state.insurance_fund_balance {
-                bail!("Claim exceeds insurance fund balance");
+            if from_custodian == to_custodian {
+                bail!("Rotation source and destination must differ");
             }
+            if sender != from_custodian && sender != to_custodian {
+                bail!("Only a participating custodian can propose a rotation");
+            }
+            ensure_rotation_window(ctx)?;
 
-            ctx.state.insurance_fund_balance = ctx
+            let source = ctx
+                .state
+                .custodians
+                .iter()
+                .find(|c| c.pubkey == *from_custodian)
+                .ok_or_else(|| anyhow::anyhow!("Source custodian not registered"))?;
+            if source.reserve_balance < *amount {
+                bail!("Rotation exceeds source custodian reserves");
+            }
+            if !ctx
+                .state
+                .custodians
+                .iter()
+                .any(|c| c.pubkey == *to_custodian)
+            {
+                bail!("Destination custodian not registered");
+            }
+            instructions::concentration::check_custodian(ctx.state, to_custodian, *amount)?;
+
+            let rotation_id = ctx.state.next_rotation_id;
+            ctx.state.next_rotation_id = checked_add_u64(rotation_id, 1, "Rotation id")?;
+            ctx.state.reserve_rotations.insert(
+                rotation_id,
+                ReserveRotation {
+                    from_custodian: *from_custodian,
+                    to_custodian: *to_custodian,
+                    amount: *amount,
+                    proposed_height: ctx.height,
+                    source_approvals: Vec::new(),
+                    destination_approvals: Vec::new(),
+                },
+            );
+            Ok(())
+        }
+
+        StablecoinInstruction::ApproveReserveRotation {
+            rotation_id,
+            mpc_pubkey,
+            signature,
+        } => {
+            let rotation = ctx
+                .state
+                .reserve_rotations
+                .get(rotation_id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown reserve rotation"))?;
+            let mpc_set = |pubkey: &[u8; 32]| {
+                ctx.state
+                    .custodians
+                    .iter()
+                    .find(|c| c.pubkey == *pubkey)
+                    .map(|c| c.mpc_pubkeys.clone())
+                    .unwrap_or_default()
+            };
+            let source_set = mpc_set(&rotation.from_custodian);
+            let destination_set = mpc_set(&rotation.to_custodian);
+            let in_source = source_set.contains(mpc_pubkey);
+            let in_destination = destination_set.contains(mpc_pubkey);
+            if !in_source && !in_destination {
+                bail!("Key is not in either custodian's MPC set");
+            }
+            if (!in_source || rotation.source_approvals.contains(mpc_pubkey))
+                && (!in_destination || rotation.destination_approvals.contains(mpc_pubkey))
+            {
+                bail!("Rotation already approved by this key");
+            }
+            let payload = rotation.signing_payload(*rotation_id);
+            lumina_crypto::signatures::verify_signature(mpc_pubkey, &payload, signature)?;
+
+            // Majority of each MPC set, matching social recovery thresholds.
+            let source_count = rotation.source_approvals.len()
+                + (in_source && !rotation.source_approvals.contains(mpc_pubkey)) as usize;
+            let destination_count = rotation.destination_approvals.len()
+                + (in_destination && !rotation.destination_approvals.contains(mpc_pubkey)) as usize;
+            if source_count > source_set.len() / 2 && destination_count > destination_set.len() / 2
+            {
+                return finalize_reserve_rotation(ctx, *rotation_id);
+            }
+
+            let rotation = ctx
+                .state
+                .reserve_rotations
+                .get_mut(rotation_id)
+                .ok_or_else(|| anyhow::anyhow!("Unknown reserve rotation"))?;
+            if in_source && !rotation.source_approvals.contains(mpc_pubkey) {
+                rotation.source_approvals.push(*mpc_pubkey);
+            }
+            if in_destination && !rotation.destination_approvals.contains(mpc_pubkey) {
+                rotation.destination_approvals.push(*mpc_pubkey);
+            }
+            Ok(())
+        }
+
+        StablecoinInstruction::ClaimInsurance {
+            loss_proof,
+            claimed_amount,
+        } => {
+            if !verify_insurance_loss_proof(loss_proof, *claimed_amount) {
+                bail!("Invalid insurance loss proof");
+            }
+            if *claimed_amount > ctx.state.insurance_fund_balance {
+                bail!("Claim exceeds insurance fund balance");
+            }
+
+            // Active policyholders get priority and their tier cap during a
+            // depeg/shortfall event; everyone else is held to the uninsured cap.
+            let shortfall = shortfall_event_active(ctx.state);
+            let epoch = ctx.state.current_epoch;
+            let coverage = ctx
+                .state
+                .accounts
+                .get(sender)
+                .and_then(|a| a.insurance_coverage.clone())
+                .filter(|c| epoch < c.paid_through_epoch);
+
+            match coverage {
+                Some(coverage) if shortfall => {
+                    let tier = ctx
+                        .state
+                        .params
+                        .insurance_tiers
+                        .get(coverage.tier as usize)
+                        .ok_or_else(|| anyhow::anyhow!("Unknown insurance tier"))?;
+                    let remaining = tier.coverage_cap.saturating_sub(coverage.claimed);
+                    if *claimed_amount > remaining {
+                        bail!("Claim exceeds remaining coverage");
+                    }
+                    if let Some(c) = ctx
+                        .state
+                        .accounts
+                        .get_mut(sender)
+                        .and_then(|a| a.insurance_coverage.as_mut())
+                    {
+                        c.claimed =
+                            checked_add_u64(c.claimed, *claimed_amount, "Coverage claimed")?;
+                    }
+                }
+                _ => {
+                    if shortfall {
+                        bail!("Only insured accounts may claim during a shortfall event");
+                    }
+                    if *claimed_amount > ctx.state.params.uninsured_claim_cap {
+                        bail!("Claim exceeds uninsured claim cap");
+                    }
+                }
+            }
+
+            ctx.state.insurance_fund_balance = ctx
                 .state
                 .insurance_fund_balance
                 .saturating_sub(*claimed_amount);
@@ -934,6 +2627,53 @@ pub fn execute_si(
             Ok(())
         }
 
+        StablecoinInstruction::OptInInsurance { tier } => {
+            let premium = ctx
+                .state
+                .params
+                .insurance_tiers
+                .get(*tier as usize)
+                .ok_or_else(|| anyhow::anyhow!("Unknown insurance tier"))?
+                .premium_per_epoch;
+            let epoch = ctx.state.current_epoch;
+
+            let account = ctx.state.accounts.entry(*sender).or_default();
+            if account.lusd_balance < premium {
+                bail!("Insufficient LUSD for insurance premium");
+            }
+            account.lusd_balance -= premium;
+
+            // Paying the same tier while covered extends the policy by one epoch;
+            // a new, lapsed, or changed tier starts a fresh coverage period.
+            let coverage = match account.insurance_coverage.take() {
+                Some(mut c) if c.tier == *tier && epoch < c.paid_through_epoch => {
+                    c.paid_through_epoch =
+                        checked_add_u64(c.paid_through_epoch, 1, "Coverage epoch")?;
+                    c.total_premiums_paid =
+                        checked_add_u64(c.total_premiums_paid, premium, "Premiums paid")?;
+                    c
+                }
+                previous => InsuranceCoverage {
+                    tier: *tier,
+                    paid_through_epoch: checked_add_u64(epoch, 1, "Coverage epoch")?,
+                    claimed: 0,
+                    total_premiums_paid: checked_add_u64(
+                        previous.map(|c| c.total_premiums_paid).unwrap_or(0),
+                        premium,
+                        "Premiums paid",
+                    )?,
+                },
+            };
+            account.insurance_coverage = Some(coverage);
+
+            ctx.state.total_lusd_supply = ctx.state.total_lusd_supply.saturating_sub(premium);
+            ctx.state.insurance_fund_balance =
+                checked_add_u64(ctx.state.insurance_fund_balance, premium, "Insurance fund")?;
+
+            recalculate_ratios(ctx);
+            Ok(())
+        }
+
         // ══════════════════════════════════════════════════════════
         // Phase 2: Security & Compliance Excellence
         // ══════════════════════════════════════════════════════════
@@ -952,20 +2692,25 @@ pub fn execute_si(
             }
 
             // Find validator by sender pubkey and flag as green
-            let mut found = false;
-            for v in ctx.state.validators.iter_mut() {
-                if v.pubkey == *sender {
-                    v.is_green = true;
-                    v.energy_proof = Some(energy_proof.clone());
-                    // Green validators get 2x voting power
-                    v.power = v.stake.saturating_mul(2);
-                    found = true;
-                    break;
-                }
-            }
-            if !found {
+            let Some(v) = ctx
+                .state
+                .validators
+                .iter_mut()
+                .find(|v| v.pubkey == *sender)
+            else {
                 bail!("Sender is not a registered validator");
-            }
+            };
+            v.is_green = true;
+            v.energy_proof = Some(energy_proof.clone());
+            v.green_verified_at = ctx.timestamp;
+            // Green validators get 2x voting power from the next epoch
+            let power = v.stake.saturating_mul(2);
+            ctx.state
+                .pending_validator_changes
+                .push(ValidatorChange::UpdatePower {
+                    pubkey: *sender,
+                    power,
+                });
             Ok(())
         }
 
@@ -976,19 +2721,64 @@ pub fn execute_si(
             Ok(())
         }
 
+        StablecoinInstruction::RenewGreenProof { energy_proof } => {
+            if !verify_green_energy_proof(energy_proof) {
+                bail!("Invalid green energy proof");
+            }
+            let Some(v) = ctx
+                .state
+                .validators
+                .iter_mut()
+                .find(|v| v.pubkey == *sender)
+            else {
+                bail!("Sender is not a registered validator");
+            };
+            if v.energy_proof.as_deref() == Some(energy_proof.as_slice()) {
+                bail!("Renewal requires a fresh energy proof");
+            }
+            let decayed = !v.is_green;
+            v.is_green = true;
+            v.energy_proof = Some(energy_proof.clone());
+            v.green_verified_at = ctx.timestamp;
+            // Power already doubled unless the old proof decayed at an epoch boundary
+            if decayed {
+                let power = v.stake.saturating_mul(2);
+                ctx.state
+                    .pending_validator_changes
+                    .push(ValidatorChange::UpdatePower {
+                        pubkey: *sender,
+                        power,
+                    });
+            }
+            Ok(())
+        }
+
         StablecoinInstruction::UploadComplianceCircuit {
             circuit_id,
             verifier_key,
         } => {
-            if verifier_key.is_empty() {
-                bail!("Verifier key cannot be empty");
-            }
-            ctx.state
-                .compliance_circuits
-                .insert(*circuit_id, verifier_key.clone());
-            Ok(())
+            // The next version, with no overlap, as uploads before versioning
+            let version = instructions::hooks::next_version(ctx.state, *circuit_id);
+            let height = ctx.height;
+            instructions::hooks::upload(ctx, sender, *circuit_id, verifier_key, version, height, 0)
         }
 
+        StablecoinInstruction::UploadComplianceCircuitV2 {
+            circuit_id,
+            verifier_key,
+            version,
+            activation_height,
+            deprecation_blocks,
+        } => instructions::hooks::upload(
+            ctx,
+            sender,
+            *circuit_id,
+            verifier_key,
+            *version,
+            *activation_height,
+            *deprecation_blocks,
+        ),
+
         // ══════════════════════════════════════════════════════════
         // Phase 3: Capital Efficiency & RWA
         // ══════════════════════════════════════════════════════════
@@ -1005,7 +2795,7 @@ pub fn execute_si(
                 bail!("Flash mint collateral must be non-zero");
             }
 
-            let min_collateral = amount.saturating_mul(110) / 100;
+            let min_collateral = Ratio::from_bps(11_000).apply(*amount, Rounding::Up)?;
             if *collateral_amount < min_collateral {
                 bail!(
                     "Insufficient collateral for flash mint: need >= {}",
@@ -1016,6 +2806,7 @@ pub fn execute_si(
             // Domain-bind the collateral lock (commitment is stored off-chain/on-chain by custody
             // subsystems; here we only enforce accounting and end-of-block burn).
             let _ = (collateral_asset, commitment);
+            instructions::mint_caps::check(ctx.state, sender, *amount)?;
 
             ctx.state.stabilization_pool_balance = ctx
                 .state
@@ -1024,6 +2815,9 @@ pub fn execute_si(
                 .ok_or_else(|| anyhow::anyhow!("Collateral overflow"))?;
 
             let account = ctx.state.accounts.entry(*sender).or_default();
+            if account.pending_flash_mint == 0 {
+                ctx.state.flash_minters.push(*sender);
+            }
             account.pending_flash_mint = account
                 .pending_flash_mint
                 .checked_add(*amount)
@@ -1049,6 +2843,7 @@ pub fn execute_si(
                 .pending_flash_mints
                 .checked_add(*amount)
                 .ok_or_else(|| anyhow::anyhow!("Flash mint overflow"))?;
+            instructions::mint_caps::record(ctx.state, sender, *amount);
             Ok(())
         }
 
@@ -1078,6 +2873,68 @@ pub fn execute_si(
                 .saturating_sub(collateral_to_release);
             account.pending_flash_mint = 0;
             account.pending_flash_collateral = 0;
+            instructions::mint_caps::release(ctx.state, sender, *amount);
+            Ok(())
+        }
+
+        StablecoinInstruction::FlashLoan { amount } => {
+            if *amount == 0 {
+                bail!("Flash loan amount must be non-zero");
+            }
+            if *amount > ctx.state.stabilization_pool_balance {
+                bail!("Flash loan exceeds stabilization pool liquidity");
+            }
+
+            ctx.state.stabilization_pool_balance -= *amount;
+            ctx.state.total_lusd_supply =
+                checked_add_u64(ctx.state.total_lusd_supply, *amount, "LUSD supply")?;
+            ctx.state.pending_flash_loans =
+                checked_add_u64(ctx.state.pending_flash_loans, *amount, "Flash loans")?;
+            ctx.state.flash_loan_volume = ctx.state.flash_loan_volume.saturating_add(*amount);
+
+            let outstanding = ctx.state.pending_flash_loans;
+            let liquidity = outstanding.saturating_add(ctx.state.stabilization_pool_balance);
+            let utilization = mul_div(outstanding, BPS_SCALE, liquidity, Rounding::Down)?;
+            ctx.state.flash_loan_utilization_bps =
+                ctx.state.flash_loan_utilization_bps.max(utilization);
+
+            let account = ctx.state.accounts.entry(*sender).or_default();
+            if account.pending_flash_loan == 0 {
+                ctx.state.flash_loan_borrowers.push(*sender);
+            }
+            account.pending_flash_loan =
+                checked_add_u64(account.pending_flash_loan, *amount, "Account flash loan")?;
+            account.lusd_balance = checked_add_u64(account.lusd_balance, *amount, "Balance")?;
+            Ok(())
+        }
+
+        StablecoinInstruction::FlashRepay { amount } => {
+            let account = ctx.state.accounts.entry(*sender).or_default();
+            if account.pending_flash_loan == 0 {
+                bail!("No pending flash loan to repay");
+            }
+            if *amount != account.pending_flash_loan {
+                bail!("Flash repay must repay the full pending flash loan");
+            }
+            let fee = fee_of(*amount, ctx.state.params.flash_loan_fee_bps);
+            let owed = checked_add_u64(*amount, fee, "Flash repay")?;
+            if account.lusd_balance < owed {
+                bail!("Insufficient LUSD for flash repay plus fee");
+            }
+            account.lusd_balance -= owed;
+            account.pending_flash_loan = 0;
+
+            ctx.state.pending_flash_loans = ctx.state.pending_flash_loans.saturating_sub(*amount);
+            ctx.state.total_lusd_supply =
+                checked_sub_u64(ctx.state.total_lusd_supply, owed, "LUSD supply")?;
+            // Burning the fee frees its backing in the pool, which moves to
+            // insurance
+            ctx.state.stabilization_pool_balance =
+                checked_add_u64(ctx.state.stabilization_pool_balance, *amount - fee, "Pool")?;
+            ctx.state.insurance_fund_balance =
+                checked_add_u64(ctx.state.insurance_fund_balance, fee, "Insurance fund")?;
+            ctx.state.flash_loan_fees = ctx.state.flash_loan_fees.saturating_add(fee);
+            record_fee(ctx, sender, fee);
             Ok(())
         }
 
@@ -1095,7 +2952,12 @@ pub fn execute_si(
                 bail!("Insufficient LUSD balance");
             }
 
-            if ctx.state.circuit_breaker_active || ctx.state.reserve_ratio < 0.95 {
+            let delay_blocks =
+                instructions::redemption_delay::delay_for(&ctx.state.redemption_tiers, *amount);
+            if delay_blocks > 0 {
+                return instructions::redemption_delay::delay(ctx, sender, *amount, delay_blocks);
+            }
+            if redemptions_stressed(ctx.state) {
                 ctx.state.fair_redeem_queue.push(RedemptionRequest {
                     address: *sender,
                     amount: *amount,
@@ -1108,13 +2970,7 @@ pub fn execute_si(
 
             let acct = ctx.state.accounts.entry(*sender).or_default();
             acct.lusd_balance = checked_sub_u64(acct.lusd_balance, *amount, "LUSD balance")?;
-            ctx.state.total_lusd_supply =
-                checked_sub_u64(ctx.state.total_lusd_supply, *amount, "LUSD supply")?;
-            ctx.state.stabilization_pool_balance =
-                ctx.state.stabilization_pool_balance.saturating_sub(*amount);
-
-            recalculate_ratios(ctx);
-            Ok(())
+            settle_senior_redemption(ctx, sender, *amount)
         }
 
         StablecoinInstruction::MintWithCreditScore {
@@ -1133,14 +2989,10 @@ pub fn execute_si(
             let proof_id = *blake3::hash(credit_score_proof).as_bytes();
             let is_replay = ctx.state.used_credit_proofs.contains(&proof_id);
 
+            // Without an accepted score there is no collateral asset to fall
+            // back to; the sender mints through MintSenior instead
             if !oracle_allowed || !proof_ok || is_replay {
-                // Fallback to normal mint semantics.
-                let fallback = StablecoinInstruction::MintSenior {
-                    amount: *amount,
-                    collateral_amount: *collateral_amount,
-                    proof: Vec::new(),
-                };
-                return execute_si(&fallback, sender, ctx);
+                bail!("Credit score proof not accepted; use MintSenior");
             }
 
             // Deterministically derive the disclosed score from the proof bytes.
@@ -1148,53 +3000,47 @@ pub fn execute_si(
             let raw = u16::from_le_bytes([score_bytes.as_bytes()[0], score_bytes.as_bytes()[1]]);
             let score = 300 + (raw % 551);
             if score < *min_score_threshold {
-                let fallback = StablecoinInstruction::MintSenior {
-                    amount: *amount,
-                    collateral_amount: *collateral_amount,
-                    proof: Vec::new(),
-                };
-                return execute_si(&fallback, sender, ctx);
-            }
-
-            // Dynamic collateral ratio (bps) based on score.
-            let required_bps = if score >= 800 {
-                10200
-            } else if score >= 750 {
-                10500
-            } else {
-                11000
-            };
-            let required_collateral = amount.saturating_mul(required_bps) / 10_000;
-            if *collateral_amount < required_collateral {
-                bail!(
-                    "Collateral too low for scored mint: need >= {}",
-                    required_collateral
-                );
+                bail!("Credit score below threshold; use MintSenior");
             }
 
+            scored_mint(ctx, sender, *amount, *collateral_amount, score)?;
             ctx.state.used_credit_proofs.push(proof_id);
+            ctx.state.accounts.entry(*sender).or_default().credit_score = score;
+            Ok(())
+        }
 
-            ctx.state.stabilization_pool_balance = ctx
-                .state
-                .stabilization_pool_balance
-                .checked_add(*collateral_amount)
-                .ok_or_else(|| anyhow::anyhow!("Collateral overflow"))?;
-
-            let account = ctx.state.accounts.entry(*sender).or_default();
-            account.lusd_balance = account
-                .lusd_balance
-                .checked_add(*amount)
-                .ok_or_else(|| anyhow::anyhow!("Balance overflow"))?;
-            account.credit_score = score;
-
-            ctx.state.total_lusd_supply = ctx
+        StablecoinInstruction::MintWithNativeCreditScore {
+            amount,
+            collateral_amount,
+            min_score_threshold,
+        } => {
+            if *amount == 0 {
+                bail!("Amount must be non-zero");
+            }
+            let score = ctx
                 .state
-                .total_lusd_supply
-                .checked_add(*amount)
-                .ok_or_else(|| anyhow::anyhow!("Supply overflow"))?;
+                .credit_histories
+                .get(sender)
+                .map(|h| h.score(ctx.state.current_epoch))
+                .filter(|score| score >= min_score_threshold);
+            let Some(score) = score else {
+                bail!("No credit score at the threshold; use MintSenior");
+            };
+            scored_mint(ctx, sender, *amount, *collateral_amount, score)
+        }
 
-            recalculate_ratios(ctx);
-            Ok(())
+        StablecoinInstruction::ProposeCreditOracle { oracle, member } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose credit oracles");
+            }
+            if ctx.state.trusted_credit_oracles.contains(oracle) == *member {
+                bail!("Credit oracle membership unchanged");
+            }
+            let action = ProposalAction::SetCreditOracle {
+                oracle: *oracle,
+                member: *member,
+            };
+            submit_proposal(ctx, sender, action)
         }
 
         StablecoinInstruction::WrapToYieldToken {
@@ -1242,17 +3088,23 @@ pub fn execute_si(
                 bail!("Yield token has not reached maturity");
             }
 
-            // Calculate yield: 5% annualized, prorated by blocks held
-            // Assuming 10s blocks, ~3_153_600 blocks/year
+            // Yield at the current savings rate, prorated by blocks held and
+            // rounded down as a payout. Assuming 10s blocks, ~3_153_600
+            // blocks/year
             let blocks_held = ctx.height.saturating_sub(position.issued_height);
-            let yield_earned = position
-                .principal
-                .saturating_mul(5)
-                .saturating_mul(blocks_held)
-                / (100u64.saturating_mul(3_153_600));
+            let yield_earned = mul_div(
+                position.principal,
+                ctx.state
+                    .stabilizer
+                    .savings_rate_bps
+                    .saturating_mul(blocks_held),
+                BPS_SCALE * 3_153_600,
+                Rounding::Down,
+            )
+            .unwrap_or(u64::MAX);
 
             // Route a junior-yield contribution to insurance automatically.
-            let insurance_cut = yield_earned.saturating_mul(10) / 100;
+            let insurance_cut = fee_of(yield_earned, 1_000);
             let user_yield = yield_earned.saturating_sub(insurance_cut);
             let total_return = position.principal.saturating_add(user_yield);
 
@@ -1269,10 +3121,20 @@ pub fn execute_si(
                 .checked_add(insurance_cut)
                 .ok_or_else(|| anyhow::anyhow!("Insurance overflow"))?;
 
+            if user_yield > 0 {
+                ctx.state.events.push(ChainEvent::YieldPaid {
+                    height: ctx.height,
+                    account: *sender,
+                    token_id: *token_id,
+                    amount: user_yield,
+                });
+            }
+
+            // The insurance cut leaves circulation like any fee
             ctx.state.total_lusd_supply = ctx
                 .state
                 .total_lusd_supply
-                .checked_add(yield_earned)
+                .checked_add(user_yield)
                 .ok_or_else(|| anyhow::anyhow!("Supply overflow"))?;
 
             recalculate_ratios(ctx);
@@ -1296,15 +3158,8 @@ pub fn execute_si(
                 bail!("Invalid RWA attestation proof");
             }
 
-            let rwa_id = ctx.state.next_rwa_id;
-            ctx.state.next_rwa_id = ctx
-                .state
-                .next_rwa_id
-                .checked_add(1)
-                .ok_or_else(|| anyhow::anyhow!("RWA id overflow"))?;
-
-            ctx.state.rwa_listings.insert(
-                rwa_id,
+            insert_rwa_listing(
+                ctx,
                 RWAListing {
                     owner: *sender,
                     asset_description: asset_description.clone(),
@@ -1315,7 +3170,7 @@ pub fn execute_si(
                     is_active: true,
                     pledged_amount: 0,
                 },
-            );
+            )?;
             Ok(())
         }
 
@@ -1326,6 +3181,7 @@ pub fn execute_si(
             if *amount_to_pledge == 0 {
                 bail!("Pledge amount must be non-zero");
             }
+            instructions::mint_caps::check(ctx.state, sender, *amount_to_pledge)?;
             let listing = ctx
                 .state
                 .rwa_listings
@@ -1369,6 +3225,7 @@ pub fn execute_si(
                 .checked_add(*amount_to_pledge)
                 .ok_or_else(|| anyhow::anyhow!("Pool overflow"))?;
 
+            instructions::mint_caps::record(ctx.state, sender, *amount_to_pledge);
             recalculate_ratios(ctx);
             Ok(())
         }
@@ -1377,87 +3234,1308 @@ pub fn execute_si(
             compute_health_index(ctx);
             Ok(())
         }
-    }
-}
 
-/// Core stability math — called after every monetary operation.
-/// Auto-triggers circuit breaker at <85% reserves.
-fn recalculate_ratios(ctx: &mut ExecutionContext) {
-    if ctx.state.total_lusd_supply == 0 {
-        ctx.state.reserve_ratio = 1.0;
-        return;
-    }
+        // ══════════════════════════════════════════════════════════════
+        // Smart Contracts
+        // ══════════════════════════════════════════════════════════════
+        #[cfg(feature = "contracts")]
+        StablecoinInstruction::DeployContract { code } => {
+            instructions::contracts::deploy(ctx, sender, code)
+        }
+        #[cfg(feature = "contracts")]
+        StablecoinInstruction::CallContract {
+            contract,
+            input,
+            gas_limit,
+        } => instructions::contracts::call(ctx, sender, contract, input, *gas_limit),
+        #[cfg(not(feature = "contracts"))]
+        StablecoinInstruction::DeployContract { .. }
+        | StablecoinInstruction::CallContract { .. } => {
+            bail!("Smart contracts are not enabled")
+        }
 
-    ctx.state.reserve_ratio =
-        (ctx.state.stabilization_pool_balance as f64) / (ctx.state.total_lusd_supply as f64);
+        // ══════════════════════════════════════════════════════════════
+        // Execution Hooks
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeHook { hook } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose hooks");
+            }
+            instructions::hooks::validate(ctx.state, hook)?;
+            submit_proposal(ctx, sender, ProposalAction::RegisterHook(hook.clone()))
+        }
 
-    if ctx.state.reserve_ratio < 0.85 {
-        ctx.state.circuit_breaker_active = true;
-    }
-}
+        StablecoinInstruction::ProposeHookRemoval { name } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose hooks");
+            }
+            if !ctx.state.hooks.contains_key(name) {
+                bail!("Unknown hook");
+            }
+            submit_proposal(ctx, sender, ProposalAction::RemoveHook(name.clone()))
+        }
 
-/// Compute the Lumina Health Index (0..10000 = 0.00%..100.00%)
-fn compute_health_index(ctx: &mut ExecutionContext) {
-    let mut score: u64 = 0;
+        StablecoinInstruction::AttestCompliance { circuit_id, proof } => {
+            instructions::hooks::attest(ctx, sender, *circuit_id, proof)
+        }
 
-    // Reserve ratio component (0-3000): 30% weight
-    let reserve_clamped = ctx.state.reserve_ratio.clamp(0.0, 2.0);
-    let reserve_score = (reserve_clamped * 1500.0) as u64;
-    score = score.saturating_add(reserve_score.min(3000));
+        // ══════════════════════════════════════════════════════════════
+        // Confidential RWA
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ListConfidentialRWA {
+            asset_description,
+            value_commitment,
+            min_value,
+            range_proof,
+            attestation_proof,
+            sealed_opening,
+            maturity_date,
+            collateral_eligibility,
+        } => {
+            if asset_description.is_empty() {
+                bail!("Asset description must be non-empty");
+            }
+            if *min_value == 0 {
+                bail!("Minimum value must be non-zero");
+            }
+            if !verify_min_valuation(value_commitment, *min_value, range_proof) {
+                bail!("Invalid minimum valuation proof");
+            }
+            if !verify_sealed_rwa_attestation(attestation_proof, value_commitment) {
+                bail!("Invalid RWA attestation proof");
+            }
+            if sealed_opening.len() != SEALED_OPENING_LEN {
+                bail!("Malformed sealed opening");
+            }
 
-    // Peg health (0-2500): 25% weight — based on LUSD-USD oracle price
-    let lusd_price = ctx
-        .state
-        .oracle_prices
-        .get("LUSD-USD")
-        .copied()
-        .unwrap_or(1_000_000);
-    let peg_dev = if lusd_price > 1_000_000 {
-        lusd_price.saturating_sub(1_000_000)
-    } else {
-        1_000_000u64.saturating_sub(lusd_price)
-    };
-    let peg_score = if peg_dev < 50_000 {
-        2500
-    } else if peg_dev < 100_000 {
-        1500
-    } else {
-        500
-    };
-    score = score.saturating_add(peg_score);
+            // Capacity is enforced against the proven bound, never the hidden value
+            let rwa_id = insert_rwa_listing(
+                ctx,
+                RWAListing {
+                    owner: *sender,
+                    asset_description: asset_description.clone(),
+                    attestation_proof: attestation_proof.clone(),
+                    attested_value: *min_value,
+                    maturity_date: *maturity_date,
+                    collateral_eligibility: *collateral_eligibility,
+                    is_active: true,
+                    pledged_amount: 0,
+                },
+            )?;
+            ctx.state.sealed_rwa_valuations.insert(
+                rwa_id,
+                SealedValuation {
+                    commitment: *value_commitment,
+                    range_proof: range_proof.clone(),
+                    sealed_opening: sealed_opening.clone(),
+                },
+            );
+            Ok(())
+        }
 
-    // Circuit breaker status (0-1500): 15% weight
-    if !ctx.state.circuit_breaker_active {
-        score = score.saturating_add(1500);
-    }
+        // ══════════════════════════════════════════════════════════════
+        // View Keys
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::RegisterViewKey { view_pubkey } => {
+            if !is_valid_view_public_key(view_pubkey) {
+                bail!("Invalid view key");
+            }
+            ctx.state.view_keys.insert(*sender, *view_pubkey);
+            Ok(())
+        }
 
-    // Insurance fund adequacy (0-1500): 15% weight
-    if ctx.state.total_lusd_supply > 0 {
-        let insurance_ratio =
-            (ctx.state.insurance_fund_balance as f64) / (ctx.state.total_lusd_supply as f64);
-        let insurance_score = (insurance_ratio * 30000.0) as u64;
-        score = score.saturating_add(insurance_score.min(1500));
-    } else {
-        score = score.saturating_add(1500);
-    }
+        StablecoinInstruction::SealedConfidentialTransfer {
+            commitment,
+            proof,
+            sealed_note,
+        } => {
+            let Some(view_pubkey) = ctx.state.view_keys.get(sender).copied() else {
+                bail!("No view key registered");
+            };
+            if sealed_note.len() != SEALED_NOTE_LEN {
+                bail!("Malformed sealed note");
+            }
+            if !verify_confidential_proof(commitment, proof) {
+                bail!("Invalid confidential transfer proof");
+            }
+            let account = ctx.state.accounts.entry(*sender).or_default();
+            account.commitment = Some(*commitment);
+            ctx.state.confidential_notes.insert(
+                *sender,
+                ConfidentialNote {
+                    commitment: *commitment,
+                    view_pubkey,
+                    sealed_note: sealed_note.clone(),
+                    height: ctx.height,
+                },
+            );
+            Ok(())
+        }
 
-    // Green validator percentage (0-1000): 10% weight
-    let total_validators = ctx.state.validators.len() as u64;
-    if total_validators > 0 {
-        let green_count = ctx.state.validators.iter().filter(|v| v.is_green).count() as u64;
-        let green_pct = green_count.saturating_mul(1000) / total_validators;
-        score = score.saturating_add(green_pct.min(1000));
-    } else {
-        score = score.saturating_add(500);
+        // ══════════════════════════════════════════════════════════════
+        // Sanctions Screening
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeScreeningOracle { oracle, member } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose screening oracles");
+            }
+            if ctx.state.screening_oracles.contains_key(oracle) == *member {
+                bail!("Screening oracle membership unchanged");
+            }
+            let action = ProposalAction::SetScreeningOracle {
+                oracle: *oracle,
+                member: *member,
+            };
+            submit_proposal(ctx, sender, action)
+        }
+
+        StablecoinInstruction::PublishScreeningRoot { root } => {
+            let Some(oracle) = ctx.state.screening_oracles.get_mut(sender) else {
+                bail!("Sender is not a screening oracle");
+            };
+            oracle.root = Some(*root);
+            oracle.published_height = ctx.height;
+            ctx.state.events.push(ChainEvent::ScreeningRootPublished {
+                height: ctx.height,
+                oracle: *sender,
+                root: *root,
+            });
+            Ok(())
+        }
+
+        StablecoinInstruction::TransferWithComplianceProof {
+            to,
+            amount,
+            asset,
+            proofs,
+        } => {
+            let roots: Vec<_> = ctx
+                .state
+                .screening_oracles
+                .iter()
+                .filter_map(|(oracle, s)| s.root.map(|root| (*oracle, root)))
+                .collect();
+            if roots.is_empty() {
+                bail!("No screening roots published");
+            }
+            for (oracle, root) in roots {
+                let Some(proof) = proofs.iter().find(|p| p.oracle == oracle) else {
+                    bail!("Missing screening proof");
+                };
+                if !proof.sender.verify(&root, sender) || !proof.recipient.verify(&root, to) {
+                    bail!("Invalid screening proof");
+                }
+            }
+            let transfer = StablecoinInstruction::Transfer {
+                to: *to,
+                amount: *amount,
+                asset: asset.clone(),
+            };
+            execute_si(&transfer, sender, ctx)
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // KYC Tiers
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeComplianceOfficer { officer, member } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose compliance officers");
+            }
+            if ctx.state.compliance_officers.contains(officer) == *member {
+                bail!("Compliance officer membership unchanged");
+            }
+            let action = ProposalAction::SetComplianceOfficer {
+                officer: *officer,
+                member: *member,
+            };
+            submit_proposal(ctx, sender, action)
+        }
+
+        StablecoinInstruction::ProposeKycTiers { tiers } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose KYC tiers");
+            }
+            instructions::limits::validate_tiers(tiers)?;
+            submit_proposal(ctx, sender, ProposalAction::SetKycTiers(tiers.clone()))
+        }
+
+        StablecoinInstruction::ProposeJurisdictionPolicy {
+            jurisdiction_id,
+            policy,
+        } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose jurisdiction policies");
+            }
+            if let Some(policy) = policy {
+                instructions::jurisdiction::validate(ctx.state, policy)?;
+            }
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetJurisdictionPolicy(*jurisdiction_id, policy.clone()),
+            )
+        }
+
+        StablecoinInstruction::SetKycTier { account, tier } => {
+            if !ctx.state.compliance_officers.contains(sender) {
+                bail!("Sender is not a compliance officer");
+            }
+            if *tier as usize >= ctx.state.kyc_tiers.len() {
+                bail!("Unknown KYC tier");
+            }
+            let requirements = &ctx.state.credential_requirements;
+            if *tier >= requirements.kyc_min_tier {
+                instructions::credentials::require(
+                    ctx.state,
+                    account,
+                    &requirements.kyc_kinds,
+                    ctx.height,
+                )?;
+            }
+            ctx.state.kyc_accounts.entry(*account).or_default().tier = *tier;
+            ctx.state.events.push(ChainEvent::KycTierAssigned {
+                height: ctx.height,
+                account: *account,
+                tier: *tier,
+                officer: *sender,
+            });
+            Ok(())
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Vesting
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::CreateVestingAccount {
+            beneficiary,
+            total,
+            cliff_height,
+            end_height,
+        } => {
+            if *total == 0 {
+                bail!("Amount must be greater than zero");
+            }
+            // start (now) <= cliff <= end, with end in the future
+            if *cliff_height < ctx.height
+                || *end_height <= ctx.height
+                || *end_height < *cliff_height
+            {
+                bail!("Invalid vesting schedule");
+            }
+            let height = ctx.height;
+            let schedules = ctx.state.vesting.entry(*beneficiary).or_default();
+            schedules.retain(|s| s.end_height > height);
+            if schedules.len() >= MAX_VESTING_SCHEDULES {
+                bail!("Too many vesting schedules");
+            }
+            ensure_vested_lumina(ctx, sender, *total)?;
+            let funder = ctx.state.accounts.entry(*sender).or_default();
+            if funder.lumina_balance < *total {
+                bail!("Insufficient Lumina");
+            }
+            funder.lumina_balance -= *total;
+            let account = ctx.state.accounts.entry(*beneficiary).or_default();
+            account.lumina_balance = checked_add_u64(account.lumina_balance, *total, "LUMINA")?;
+            ctx.state
+                .vesting
+                .entry(*beneficiary)
+                .or_default()
+                .push(VestingSchedule {
+                    funder: *sender,
+                    total: *total,
+                    start_height: height,
+                    cliff_height: *cliff_height,
+                    end_height: *end_height,
+                });
+            ctx.state.events.push(ChainEvent::VestingCreated {
+                height,
+                funder: *sender,
+                beneficiary: *beneficiary,
+                total: *total,
+                cliff_height: *cliff_height,
+                end_height: *end_height,
+            });
+            Ok(())
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Treasury
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeTreasurySpend { spend } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose treasury spends");
+            }
+            if spend.amount == 0 {
+                bail!("Amount must be greater than zero");
+            }
+            if spend.memo.len() > 256 {
+                bail!("Memo too long");
+            }
+            if spend.payout == (TreasuryPayout::Stream { blocks: 0 }) {
+                bail!("Stream must last at least one block");
+            }
+            submit_proposal(ctx, sender, ProposalAction::SpendTreasury(spend.clone()))
+        }
+
+        StablecoinInstruction::ProposeTreasuryFeeShare { share_bps } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose the treasury fee share");
+            }
+            if share_bps.saturating_add(ctx.state.validator_fee_share_bps) > 10_000 {
+                bail!("Fee shares must total at most 10000 bps");
+            }
+            submit_proposal(ctx, sender, ProposalAction::SetTreasuryFeeShare(*share_bps))
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Validator Rewards
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeValidatorFeeShare { share_bps } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose the validator fee share");
+            }
+            if share_bps.saturating_add(ctx.state.treasury_fee_share_bps) > 10_000 {
+                bail!("Fee shares must total at most 10000 bps");
+            }
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetValidatorFeeShare(*share_bps),
+            )
+        }
+
+        StablecoinInstruction::SetCommission { commission_bps } => {
+            instructions::rewards::set_commission(ctx, sender, *commission_bps)
+        }
+
+        StablecoinInstruction::ClaimValidatorRewards => instructions::rewards::claim(ctx, sender),
+
+        // ══════════════════════════════════════════════════════════════
+        // Validator Liveness
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::Unjail => instructions::liveness::unjail(ctx, sender),
+
+        // ══════════════════════════════════════════════════════════════
+        // Health Index
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeHealthParams { params } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose health index parameters");
+            }
+            validate_health_params(params)?;
+            submit_proposal(ctx, sender, ProposalAction::SetHealthParams(params.clone()))
+        }
+
+        StablecoinInstruction::ProposeConcentrationLimits { limits } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose concentration limits");
+            }
+            instructions::concentration::validate(limits)?;
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetConcentrationLimits(limits.clone()),
+            )
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Collateral Auctions
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::StartAuction { position_id } => {
+            instructions::auction::start(ctx, sender, *position_id)
+        }
+
+        StablecoinInstruction::Bid {
+            auction_id,
+            max_collateral,
+        } => instructions::auction::bid(ctx, sender, *auction_id, *max_collateral),
+
+        StablecoinInstruction::SettleAuction { auction_id } => {
+            instructions::auction::settle(ctx, *auction_id)
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Credit Lines
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::OpenCreditLine {
+            limit,
+            interest_bps,
+            maturity_height,
+        } => instructions::credit::open(ctx, sender, *limit, *interest_bps, *maturity_height),
+
+        StablecoinInstruction::GuaranteeCreditLine { line_id, shares } => {
+            instructions::credit::guarantee(ctx, sender, *line_id, *shares)
+        }
+
+        StablecoinInstruction::ReleaseGuarantee { line_id, shares } => {
+            instructions::credit::release(ctx, sender, *line_id, *shares)
+        }
+
+        StablecoinInstruction::DrawCreditLine { line_id, amount } => {
+            instructions::credit::draw(ctx, sender, *line_id, *amount)
+        }
+
+        StablecoinInstruction::RepayCreditLine { line_id, amount } => {
+            instructions::credit::repay(ctx, sender, *line_id, *amount)
+        }
+
+        StablecoinInstruction::DeclareCreditDefault { line_id } => {
+            instructions::credit::declare_default(ctx, sender, *line_id)
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Payroll
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::BatchDisburse { payments } => {
+            instructions::payroll::disburse(ctx, sender, payments)
+        }
+
+        StablecoinInstruction::CreateStreamBatch { asset, streams } => {
+            instructions::streams::create_batch(ctx, sender, asset, streams)
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Merchant Settlement
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::RegisterMerchant { treasury } => {
+            instructions::merchant::register(ctx, sender, treasury)
+        }
+
+        StablecoinInstruction::DeregisterMerchant => {
+            instructions::merchant::deregister(ctx, sender)
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Dust Policy
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeDustPolicy { policy } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose a dust policy");
+            }
+            instructions::dust::validate(policy)?;
+            submit_proposal(ctx, sender, ProposalAction::SetDustPolicy(policy.clone()))
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // State Expiry
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeArchivePolicy { inactivity_epochs } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose an archive policy");
+            }
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetArchivePolicy {
+                    inactivity_epochs: *inactivity_epochs,
+                },
+            )
+        }
+
+        StablecoinInstruction::ArchiveAccounts { accounts } => {
+            instructions::archive::archive(ctx, accounts)
+        }
+
+        StablecoinInstruction::ReviveAccount {
+            address,
+            account,
+            proof,
+        } => instructions::archive::revive(ctx, address, account, proof),
+
+        // ══════════════════════════════════════════════════════════════
+        // Randomness Beacon
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::CommitRandomness { epoch, commitment } => {
+            instructions::beacon::commit(ctx, sender, *epoch, *commitment)
+        }
+
+        StablecoinInstruction::RevealRandomness { epoch, secret } => {
+            instructions::beacon::reveal(ctx, sender, *epoch, *secret)
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Parameter Controller
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeStabilizerBounds { bounds } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose stabilizer bounds");
+            }
+            instructions::stabilizer::validate(bounds)?;
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetStabilizerBounds(bounds.clone()),
+            )
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Institution Credentials
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::ProposeCredentialAttester { attester, member } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose credential attesters");
+            }
+            if ctx.state.credential_attesters.contains(attester) == *member {
+                bail!("Credential attester membership unchanged");
+            }
+            let action = ProposalAction::SetCredentialAttester {
+                attester: *attester,
+                member: *member,
+            };
+            submit_proposal(ctx, sender, action)
+        }
+
+        StablecoinInstruction::AnchorCredential {
+            institution,
+            kind,
+            commitment,
+            expires_height,
+        } => instructions::credentials::anchor(
+            ctx,
+            sender,
+            institution,
+            *kind,
+            commitment,
+            *expires_height,
+        ),
+
+        StablecoinInstruction::RevokeCredential { institution, kind } => {
+            instructions::credentials::revoke(ctx, sender, institution, *kind)
+        }
+
+        StablecoinInstruction::ProveInstitutionStatus { kind, proof } => {
+            instructions::credentials::prove(ctx, sender, *kind, proof)
+        }
+
+        StablecoinInstruction::ProposeCredentialRequirements { requirements } => {
+            if !ctx.state.validators.iter().any(|v| v.pubkey == *sender) {
+                bail!("Only validators can propose credential requirements");
+            }
+            submit_proposal(
+                ctx,
+                sender,
+                ProposalAction::SetCredentialRequirements(requirements.clone()),
+            )
+        }
+
+        // ══════════════════════════════════════════════════════════════
+        // Inheritance
+        // ══════════════════════════════════════════════════════════════
+        StablecoinInstruction::DesignateBeneficiary {
+            beneficiary,
+            inactivity_blocks,
+        } => {
+            if *beneficiary == *sender || *beneficiary == [0u8; 32] {
+                bail!("Invalid beneficiary");
+            }
+            if *inactivity_blocks < MIN_INHERITANCE_INACTIVITY {
+                bail!(
+                    "Inactivity period must be at least {} blocks",
+                    MIN_INHERITANCE_INACTIVITY
+                );
+            }
+            cancel_inheritance_claim(ctx, sender);
+            ctx.state.inheritance.insert(
+                *sender,
+                InheritanceDesignation {
+                    beneficiary: *beneficiary,
+                    inactivity_blocks: *inactivity_blocks,
+                    last_active_height: ctx.height,
+                    claim_matures_at: None,
+                },
+            );
+            Ok(())
+        }
+
+        StablecoinInstruction::RevokeBeneficiary => {
+            cancel_inheritance_claim(ctx, sender);
+            if ctx.state.inheritance.remove(sender).is_none() {
+                bail!("No beneficiary designated");
+            }
+            Ok(())
+        }
+
+        StablecoinInstruction::ClaimInheritance { owner } => {
+            let Some(designation) = ctx.state.inheritance.get_mut(owner) else {
+                bail!("No beneficiary designated");
+            };
+            if designation.beneficiary != *sender {
+                bail!("Sender is not the designated beneficiary");
+            }
+            if designation.claim_matures_at.is_some() {
+                bail!("Inheritance claim already pending");
+            }
+            let inactive_since = designation.last_active_height;
+            if ctx.height.saturating_sub(inactive_since) < designation.inactivity_blocks {
+                bail!("Owner has not been inactive long enough");
+            }
+            let matures_at =
+                checked_add_u64(ctx.height, INHERITANCE_CHALLENGE_BLOCKS, "Claim height")?;
+            designation.claim_matures_at = Some(matures_at);
+            ctx.state
+                .inheritance_claims
+                .entry(matures_at)
+                .or_default()
+                .insert(*owner);
+            ctx.state.events.push(ChainEvent::InheritanceClaimStarted {
+                height: ctx.height,
+                owner: *owner,
+                beneficiary: *sender,
+                matures_at,
+            });
+            Ok(())
+        }
     }
+}
 
-    // Custodian diversity (0-500): 5% weight
-    let custodian_score = (ctx.state.custodians.len() as u64)
-        .min(10)
-        .saturating_mul(50);
-    score = score.saturating_add(custodian_score);
+/// Core stability math — called after every monetary operation.
+/// Auto-triggers circuit breaker at <85% reserves.
+fn recalculate_ratios(ctx: &mut ExecutionContext) {
+    value_pool_composition(ctx.state);
 
-    ctx.state.health_index = score.min(10000);
+    // Outstanding flash loans are an intra-block receivable of the pool.
+    let supply = ctx
+        .state
+        .total_lusd_supply
+        .saturating_sub(ctx.state.pending_flash_loans);
+    if supply == 0 {
+        ctx.state.reserve_ratio = 1.0;
+        return;
+    }
+
+    let pool = ctx
+        .state
+        .stabilization_pool_balance
+        .saturating_add(ctx.state.pending_flash_loans);
+    ctx.state.reserve_ratio = (pool as f64) / (supply as f64);
+
+    if ctx.state.reserve_ratio < 0.85 {
+        ctx.state.circuit_breaker_active = true;
+    }
+}
+
+/// Whether validators approving `proposal_id` hold more than 2/3 of the
+/// active voting power.
+fn proposal_passed(state: &GlobalState, proposal_id: u64) -> bool {
+    let Some(proposal) = state.proposals.get(&proposal_id) else {
+        return false;
+    };
+    let total: u128 = state.validators.iter().map(|v| v.power as u128).sum();
+    let approving: u128 = state
+        .validators
+        .iter()
+        .filter(|v| proposal.votes.get(&v.pubkey) == Some(&true))
+        .map(|v| v.power as u128)
+        .sum();
+    total > 0 && approving * 3 > total * 2
+}
+
+/// Open a proposal carrying the proposer's approval, executing it at once
+/// if that alone is a supermajority.
+fn submit_proposal(
+    ctx: &mut ExecutionContext,
+    proposer: &[u8; 32],
+    action: ProposalAction,
+) -> Result<()> {
+    let id = ctx.state.next_proposal_id;
+    ctx.state.next_proposal_id = checked_add_u64(id, 1, "Proposal id")?;
+    ctx.state.proposals.insert(
+        id,
+        GovernanceProposal {
+            proposer: *proposer,
+            action,
+            proposed_height: ctx.height,
+            votes: BTreeMap::from([(*proposer, true)]),
+            executed: false,
+        },
+    );
+    if proposal_passed(ctx.state, id) {
+        execute_proposal(ctx, id)?;
+    }
+    Ok(())
+}
+
+fn execute_proposal(ctx: &mut ExecutionContext, proposal_id: u64) -> Result<()> {
+    let Some(proposal) = ctx.state.proposals.get_mut(&proposal_id) else {
+        bail!("Unknown proposal");
+    };
+    proposal.executed = true;
+    match proposal.action.clone() {
+        ProposalAction::ScheduleUpgrade(plan) => {
+            if plan.height <= ctx.height {
+                bail!("Upgrade height already passed");
+            }
+            ctx.state.events.push(ChainEvent::UpgradeScheduled {
+                height: ctx.height,
+                proposal_id,
+                name: plan.name.clone(),
+                upgrade_height: plan.height,
+            });
+            ctx.state.upgrade_plans.push(plan);
+        }
+        ProposalAction::RegisterHook(hook) => {
+            // The circuit may have changed since the proposal was made
+            instructions::hooks::validate(ctx.state, &hook)?;
+            ctx.state.events.push(ChainEvent::HookRegistered {
+                height: ctx.height,
+                proposal_id,
+                name: hook.name.clone(),
+                circuit_id: hook.circuit_id,
+            });
+            ctx.state.hooks.insert(hook.name.clone(), hook);
+        }
+        ProposalAction::SetScreeningOracle { oracle, member } => {
+            if member {
+                ctx.state.screening_oracles.entry(oracle).or_default();
+            } else {
+                ctx.state.screening_oracles.remove(&oracle);
+            }
+            ctx.state.events.push(ChainEvent::ScreeningOracleChanged {
+                height: ctx.height,
+                proposal_id,
+                oracle,
+                member,
+            });
+        }
+        ProposalAction::SetComplianceOfficer { officer, member } => {
+            if member {
+                ctx.state.compliance_officers.insert(officer);
+            } else {
+                ctx.state.compliance_officers.remove(&officer);
+            }
+            ctx.state.events.push(ChainEvent::ComplianceOfficerChanged {
+                height: ctx.height,
+                proposal_id,
+                officer,
+                member,
+            });
+        }
+        ProposalAction::SetCreditOracle { oracle, member } => {
+            if !member {
+                ctx.state.trusted_credit_oracles.retain(|o| *o != oracle);
+            } else if !ctx.state.trusted_credit_oracles.contains(&oracle) {
+                ctx.state.trusted_credit_oracles.push(oracle);
+            }
+            ctx.state.events.push(ChainEvent::CreditOracleChanged {
+                height: ctx.height,
+                proposal_id,
+                oracle,
+                member,
+            });
+        }
+        ProposalAction::SetKycTiers(tiers) => {
+            ctx.state.events.push(ChainEvent::KycTiersUpdated {
+                height: ctx.height,
+                proposal_id,
+                tiers: tiers.len() as u64,
+            });
+            ctx.state.kyc_tiers = tiers;
+        }
+        ProposalAction::SpendTreasury(spend) => {
+            if spend.amount > ctx.state.treasury_balance {
+                bail!("Insufficient treasury balance");
+            }
+            let stream_end_height = match spend.payout {
+                TreasuryPayout::LumpSum => None,
+                TreasuryPayout::Stream { blocks } => {
+                    if ctx.state.treasury_streams.len() >= MAX_TREASURY_STREAMS {
+                        bail!("Too many treasury streams");
+                    }
+                    Some(checked_add_u64(ctx.height, blocks, "Stream end")?)
+                }
+            };
+            ctx.state.treasury_balance -= spend.amount;
+            match stream_end_height {
+                None => pay_from_treasury(ctx.state, &spend.recipient, spend.amount),
+                Some(end_height) => {
+                    ctx.state.treasury_streams.insert(
+                        proposal_id,
+                        TreasuryStream {
+                            recipient: spend.recipient,
+                            total: spend.amount,
+                            paid: 0,
+                            start_height: ctx.height,
+                            end_height,
+                        },
+                    );
+                }
+            }
+            ctx.state.events.push(ChainEvent::TreasurySpendExecuted {
+                height: ctx.height,
+                proposal_id,
+                recipient: spend.recipient,
+                amount: spend.amount,
+                stream_end_height,
+            });
+        }
+        ProposalAction::SetTreasuryFeeShare(share_bps) => {
+            ctx.state.treasury_fee_share_bps = share_bps;
+            ctx.state.events.push(ChainEvent::TreasuryFeeShareChanged {
+                height: ctx.height,
+                proposal_id,
+                share_bps,
+            });
+        }
+        ProposalAction::SetValidatorFeeShare(share_bps) => {
+            ctx.state.validator_fee_share_bps = share_bps;
+            ctx.state.events.push(ChainEvent::ValidatorFeeShareChanged {
+                height: ctx.height,
+                proposal_id,
+                share_bps,
+            });
+        }
+        ProposalAction::SetHealthParams(params) => {
+            ctx.state.health_params = params;
+            ctx.state.events.push(ChainEvent::HealthParamsChanged {
+                height: ctx.height,
+                proposal_id,
+            });
+        }
+        ProposalAction::SetDustPolicy(policy) => {
+            ctx.state.events.push(ChainEvent::DustPolicyChanged {
+                height: ctx.height,
+                proposal_id,
+                existential_deposit: policy.existential_deposit,
+                grace_blocks: policy.grace_blocks,
+            });
+            ctx.state.dust_policy = policy;
+        }
+        ProposalAction::SetArchivePolicy { inactivity_epochs } => {
+            let epoch = ctx.state.current_epoch;
+            let policy = &mut ctx.state.archive_policy;
+            if inactivity_epochs == 0 {
+                ctx.state.archive_activity.clear();
+            } else if policy.inactivity_epochs == 0 {
+                policy.enabled_epoch = epoch;
+            }
+            policy.inactivity_epochs = inactivity_epochs;
+            ctx.state.events.push(ChainEvent::ArchivePolicyChanged {
+                height: ctx.height,
+                proposal_id,
+                inactivity_epochs,
+            });
+        }
+        ProposalAction::SetJurisdictionPolicy(jurisdiction_id, policy) => {
+            ctx.state
+                .events
+                .push(ChainEvent::JurisdictionPolicyChanged {
+                    height: ctx.height,
+                    proposal_id,
+                    jurisdiction_id,
+                    active: policy.is_some(),
+                });
+            match policy {
+                Some(policy) => {
+                    ctx.state
+                        .jurisdiction_policies
+                        .insert(jurisdiction_id, policy);
+                }
+                None => {
+                    ctx.state.jurisdiction_policies.remove(&jurisdiction_id);
+                }
+            }
+        }
+        ProposalAction::SetSpvBridge(chain, bridge) => {
+            instructions::spv::configure(ctx, proposal_id, chain, bridge)?;
+        }
+        ProposalAction::SetMintCaps(caps) => {
+            ctx.state.events.push(ChainEvent::MintCapsChanged {
+                height: ctx.height,
+                proposal_id,
+                per_block: caps.per_block,
+                per_account: caps.per_account,
+                breaker_blocks: caps.breaker_blocks,
+            });
+            ctx.state.mint_caps = caps;
+        }
+        ProposalAction::SetRedemptionTiers(tiers) => {
+            ctx.state.events.push(ChainEvent::RedemptionTiersChanged {
+                height: ctx.height,
+                proposal_id,
+                tiers: tiers.clone(),
+            });
+            ctx.state.redemption_tiers = tiers;
+        }
+        ProposalAction::SetEmergencyCouncil(council) => {
+            ctx.state.events.push(ChainEvent::EmergencyCouncilChanged {
+                height: ctx.height,
+                proposal_id,
+                members: council.members.clone(),
+                threshold: council.threshold,
+                sunset_blocks: council.sunset_blocks,
+            });
+            ctx.state.emergency_council = council;
+        }
+        ProposalAction::RuleCouncilAction { action_id, ratify } => {
+            instructions::council::rule(ctx, proposal_id, action_id, ratify)?;
+        }
+        ProposalAction::SetInstructionPause { kinds, paused } => {
+            instructions::pause::set(ctx, proposal_id, kinds, paused)?;
+        }
+        ProposalAction::SetProtocolParam(param) => {
+            instructions::params::set(ctx, proposal_id, param)?;
+        }
+        ProposalAction::SetRemoteChain { chain_id, active } => {
+            instructions::crosschain::configure(ctx, proposal_id, chain_id, active);
+        }
+        ProposalAction::SetCredentialAttester { attester, member } => {
+            if member {
+                ctx.state.credential_attesters.insert(attester);
+            } else {
+                ctx.state.credential_attesters.remove(&attester);
+            }
+            ctx.state
+                .events
+                .push(ChainEvent::CredentialAttesterChanged {
+                    height: ctx.height,
+                    proposal_id,
+                    attester,
+                    member,
+                });
+        }
+        ProposalAction::SetCredentialRequirements(requirements) => {
+            ctx.state.credential_requirements = requirements;
+            ctx.state
+                .events
+                .push(ChainEvent::CredentialRequirementsChanged {
+                    height: ctx.height,
+                    proposal_id,
+                });
+        }
+        ProposalAction::SetStabilizerBounds(bounds) => {
+            ctx.state.stabilizer.bounds = bounds;
+            ctx.state.events.push(ChainEvent::StabilizerBoundsChanged {
+                height: ctx.height,
+                proposal_id,
+            });
+        }
+        ProposalAction::SetConcentrationLimits(limits) => {
+            ctx.state
+                .events
+                .push(ChainEvent::ConcentrationLimitsChanged {
+                    height: ctx.height,
+                    proposal_id,
+                    max_custodian_bps: limits.max_custodian_bps,
+                    max_asset_bps: limits.max_asset_bps,
+                });
+            ctx.state.concentration_limits = limits;
+        }
+        ProposalAction::RemoveHook(name) => {
+            if ctx.state.hooks.remove(&name).is_none() {
+                bail!("Unknown hook");
+            }
+            ctx.state.events.push(ChainEvent::HookRemoved {
+                height: ctx.height,
+                proposal_id,
+                name,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// A depeg or custodian shortfall is in progress when the breaker has tripped
+/// or outstanding LUSD is no longer fully reserved.
+fn shortfall_event_active(state: &GlobalState) -> bool {
+    state.circuit_breaker_active || (state.total_lusd_supply > 0 && state.reserve_ratio < 1.0)
+}
+
+/// Reserve rotations may only finalize once per rotation interval
+/// (30 days, ~259200 blocks at 10s/block).
+fn ensure_rotation_window(ctx: &ExecutionContext) -> Result<()> {
+    let rotation_interval = 259200u64;
+    if ctx
+        .height
+        .saturating_sub(ctx.state.last_reserve_rotation_height)
+        < rotation_interval
+    {
+        bail!("Reserve rotation too frequent");
+    }
+    Ok(())
+}
+
+/// Move the approved amount between the two custodians' reserve balances.
+/// Validates everything before mutating so a failed finalize leaves the
+/// rotation pending.
+fn finalize_reserve_rotation(ctx: &mut ExecutionContext, rotation_id: u64) -> Result<()> {
+    ensure_rotation_window(ctx)?;
+    let rotation = ctx
+        .state
+        .reserve_rotations
+        .get(&rotation_id)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Unknown reserve rotation"))?;
+    let source_idx = ctx
+        .state
+        .custodians
+        .iter()
+        .position(|c| c.pubkey == rotation.from_custodian)
+        .ok_or_else(|| anyhow::anyhow!("Source custodian not registered"))?;
+    let destination_idx = ctx
+        .state
+        .custodians
+        .iter()
+        .position(|c| c.pubkey == rotation.to_custodian)
+        .ok_or_else(|| anyhow::anyhow!("Destination custodian not registered"))?;
+    let source_balance = checked_sub_u64(
+        ctx.state.custodians[source_idx].reserve_balance,
+        rotation.amount,
+        "Custodian reserve",
+    )?;
+    let destination_balance = checked_add_u64(
+        ctx.state.custodians[destination_idx].reserve_balance,
+        rotation.amount,
+        "Custodian reserve",
+    )?;
+    instructions::concentration::check_custodian(
+        ctx.state,
+        &rotation.to_custodian,
+        rotation.amount,
+    )?;
+
+    ctx.state.custodians[source_idx].reserve_balance = source_balance;
+    ctx.state.custodians[destination_idx].reserve_balance = destination_balance;
+    ctx.state.reserve_rotations.remove(&rotation_id);
+    ctx.state.last_reserve_rotation_height = ctx.height;
+    let source = ctx
+        .state
+        .custodian_rotations
+        .entry(rotation.from_custodian)
+        .or_default();
+    source.rotations_out += 1;
+    source.rotated_out = source.rotated_out.saturating_add(rotation.amount);
+    source.last_rotation_height = ctx.height;
+    let destination = ctx
+        .state
+        .custodian_rotations
+        .entry(rotation.to_custodian)
+        .or_default();
+    destination.rotations_in += 1;
+    destination.rotated_in = destination.rotated_in.saturating_add(rotation.amount);
+    destination.last_rotation_height = ctx.height;
+    ctx.state.events.push(ChainEvent::ReserveRotationFinalized {
+        height: ctx.height,
+        rotation_id,
+        from_custodian: rotation.from_custodian,
+        to_custodian: rotation.to_custodian,
+        amount: rotation.amount,
+    });
+    Ok(())
+}
+
+/// Whether some zone with a reserve weight has a custodian to hold it.
+fn zones_have_custodians(state: &GlobalState) -> bool {
+    state.custodians.iter().any(|c| {
+        state
+            .params
+            .zone_reserve_weights
+            .get(&c.zone_id)
+            .is_some_and(|weight| *weight > 0)
+    })
+}
+
+/// Recompute per-zone reserve targets from the weight table and reassign
+/// custodian balances (pro-rata to stake within a zone). Zones without
+/// custodians are skipped and their weight is spread over the rest. The
+/// off-chain transfers needed to get there are emitted as
+/// `ReserveMovementRequired` events, matching surpluses to deficits in zone
+/// order; zone 0 is the unallocated remainder.
+fn rebalance_zones(ctx: &mut ExecutionContext) -> Result<()> {
+    let total_reserves = ctx.state.stabilization_pool_balance;
+
+    let mut current: BTreeMap<u32, u64> = BTreeMap::new();
+    let mut zone_stake: BTreeMap<u32, u64> = BTreeMap::new();
+    for custodian in &ctx.state.custodians {
+        let held = current.entry(custodian.zone_id).or_default();
+        *held = held.saturating_add(custodian.reserve_balance);
+        let stake = zone_stake.entry(custodian.zone_id).or_default();
+        *stake = stake.saturating_add(custodian.stake);
+    }
+
+    let weights: Vec<(u32, u64)> = ctx
+        .state
+        .params
+        .zone_reserve_weights
+        .iter()
+        .filter(|(zone, weight)| **weight > 0 && zone_stake.contains_key(zone))
+        .map(|(zone, weight)| (*zone, *weight))
+        .collect();
+    if weights.is_empty() {
+        bail!("No weighted zone has custodians");
+    }
+
+    let shares = split(
+        total_reserves,
+        &weights.iter().map(|(_, w)| *w).collect::<Vec<_>>(),
+    )?;
+    let target: BTreeMap<u32, u64> = weights.iter().map(|(zone, _)| *zone).zip(shares).collect();
+
+    // Surplus / deficit per zone; unallocated reserves sit in zone 0.
+    let held_total = current.values().fold(0u64, |acc, v| acc.saturating_add(*v));
+    let mut surplus: Vec<(u32, u64)> = Vec::new();
+    let mut deficit: Vec<(u32, u64)> = Vec::new();
+    if total_reserves > held_total {
+        surplus.push((0, total_reserves - held_total));
+    } else if held_total > total_reserves {
+        deficit.push((0, held_total - total_reserves));
+    }
+    let zones: BTreeSet<u32> = current.keys().chain(target.keys()).copied().collect();
+    for zone in zones {
+        let have = current.get(&zone).copied().unwrap_or(0);
+        let want = target.get(&zone).copied().unwrap_or(0);
+        if have > want {
+            surplus.push((zone, have - want));
+        } else if want > have {
+            deficit.push((zone, want - have));
+        }
+    }
+
+    let (mut si, mut di) = (0, 0);
+    while si < surplus.len() && di < deficit.len() {
+        let amount = surplus[si].1.min(deficit[di].1);
+        ctx.state.events.push(ChainEvent::ReserveMovementRequired {
+            height: ctx.height,
+            from_zone: surplus[si].0,
+            to_zone: deficit[di].0,
+            amount,
+        });
+        surplus[si].1 -= amount;
+        deficit[di].1 -= amount;
+        if surplus[si].1 == 0 {
+            si += 1;
+        }
+        if deficit[di].1 == 0 {
+            di += 1;
+        }
+    }
+
+    // Split each zone's target across its custodians by stake; dust to the first.
+    let mut remaining = target.clone();
+    for custodian in ctx.state.custodians.iter_mut() {
+        let zone_target = target.get(&custodian.zone_id).copied().unwrap_or(0);
+        let stake = zone_stake.get(&custodian.zone_id).copied().unwrap_or(0);
+        custodian.reserve_balance = if stake == 0 {
+            0
+        } else {
+            ((zone_target as u128) * (custodian.stake as u128) / (stake as u128)) as u64
+        };
+        if let Some(left) = remaining.get_mut(&custodian.zone_id) {
+            *left -= custodian.reserve_balance;
+        }
+    }
+    for (zone, dust) in remaining {
+        if let Some(first) = ctx.state.custodians.iter_mut().find(|c| c.zone_id == zone) {
+            first.reserve_balance += dust;
+        }
+    }
+    Ok(())
+}
+
+/// Compute the Lumina Health Index (0..10000 = 0.00%..100.00%)
+fn compute_health_index(ctx: &mut ExecutionContext) {
+    let breaches = instructions::concentration::breaches(ctx.state);
+    let params = &ctx.state.health_params;
+    let share = |weight: u64, value: u128, full: u128| -> u64 {
+        match (weight as u128 * value.min(full)).checked_div(full) {
+            Some(points) => points as u64,
+            None => weight,
+        }
+    };
+
+    // Reserve ratio, full weight at `full_reserve_ratio_bps`
+    let points_per_ratio =
+        params.reserve_weight as f64 * 10_000.0 / params.full_reserve_ratio_bps.max(1) as f64;
+    let mut reserve =
+        ((ctx.state.reserve_ratio.max(0.0) * points_per_ratio) as u64).min(params.reserve_weight);
+    // Concentrated reserves earn half
+    if !breaches.is_empty() {
+        reserve /= 2;
+    }
+
+    // Peg health, based on the LUSD-USD oracle price (1e6 = $1)
+    let lusd_price = ctx
+        .state
+        .oracle_prices
+        .get("LUSD-USD")
+        .copied()
+        .unwrap_or(1_000_000);
+    let peg_dev = lusd_price.abs_diff(1_000_000);
+    let peg = if peg_dev < params.peg_tight_bps.saturating_mul(100) {
+        params.peg_weight
+    } else if peg_dev < params.peg_loose_bps.saturating_mul(100) {
+        params.peg_weight * 3 / 5
+    } else {
+        params.peg_weight / 5
+    };
+
+    // Circuit breaker status
+    let breaker = if ctx.state.circuit_breaker_active {
+        0
+    } else {
+        params.breaker_weight
+    };
+
+    // Insurance fund adequacy, full weight at `full_insurance_bps` of supply
+    let insurance = if ctx.state.total_lusd_supply > 0 {
+        share(
+            params.insurance_weight,
+            ctx.state.insurance_fund_balance as u128 * 10_000,
+            ctx.state.total_lusd_supply as u128 * params.full_insurance_bps as u128,
+        )
+    } else {
+        params.insurance_weight
+    };
+
+    // Green validator share — only proofs still within their validity
+    // window count; half weight with no validators
+    let total_validators = ctx.state.validators.len() as u128;
+    let green_count = ctx
+        .state
+        .validators
+        .iter()
+        .filter(|v| ctx.state.green_proof_fresh(v, ctx.timestamp))
+        .count() as u128;
+    let green = if total_validators > 0 {
+        share(params.green_weight, green_count, total_validators)
+    } else {
+        params.green_weight / 2
+    };
+
+    // Custodian diversity
+    let custodian = share(
+        params.custodian_weight,
+        ctx.state.custodians.len() as u128,
+        params.full_custodian_count as u128,
+    );
+
+    let components = HealthComponents {
+        reserve,
+        peg,
+        breaker,
+        insurance,
+        green,
+        custodian,
+    };
+    ctx.state.health_index = components.total().min(10000);
+    ctx.state.health_components = components;
+    ctx.state.concentration_breaches = breaches;
+    ctx.state
+        .health_history
+        .insert(ctx.state.current_epoch, components);
+}
+
+/// Health index weights must total 10000 so the index keeps its scale.
+fn validate_health_params(params: &HealthIndexParams) -> Result<()> {
+    if params.total_weight() != 10_000 {
+        bail!("Health index weights must total 10000");
+    }
+    if params.full_reserve_ratio_bps == 0
+        || params.full_insurance_bps == 0
+        || params.full_custodian_count == 0
+        || params.peg_tight_bps > params.peg_loose_bps
+    {
+        bail!("Invalid health index thresholds");
+    }
+    Ok(())
 }
 
 #[cfg(test)]
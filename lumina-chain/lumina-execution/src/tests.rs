@@ -1,6 +1,13 @@
 use super::*;
-use lumina_types::instruction::StablecoinInstruction;
-use lumina_types::state::{AccountState, GlobalState};
+use lumina_types::event::ChainEvent;
+use lumina_types::instruction::{AssetType, CollateralAsset, StablecoinInstruction};
+use lumina_types::state::{
+    AccountState, ArchivePolicy, CredentialKind, CredentialRequirements, CreditHistory,
+    CustodianState, DustPolicy, ExecutionHook, GlobalState, GovernanceProposal, HealthIndexParams,
+    InsuranceTierParams, JurisdictionPolicy, KycTierLimits, MerchantAccount, MintCaps,
+    OracleRegime, ProposalAction, ProtocolParam, RedemptionTier, TreasuryPayout, TreasurySpend,
+    UpgradePlan, ValidatorState, CREDIT_LINE_YEAR_BLOCKS,
+};
 use lumina_types::transaction::Transaction;
 
 fn new_sender() -> ([u8; 32], lumina_crypto::signatures::SigningKey) {
@@ -30,7 +37,7 @@ fn test_stabilization_rebalance() {
     state.stabilization_pool_balance = 500_000;
     state
         .oracle_prices
-        .insert("ETH-USD".to_string(), 3000_000_000);
+        .insert("ETH-USD".to_string(), 3_000_000_000);
 
     {
         let mut ctx = ExecutionContext {
@@ -52,6 +59,10 @@ fn test_circuit_breaker_logic() {
 
     state.total_lusd_supply = 1_000_000;
     state.stabilization_pool_balance = 100_000;
+    state.accounts.entry(sender).or_default().lumina_balance = 10;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
 
     let manager = lumina_crypto::zk::ZkManager::setup();
     let mint_si = StablecoinInstruction::MintSenior {
@@ -233,6 +244,10 @@ fn test_social_recovery_threshold_and_uniqueness() {
 fn test_insurance_fund_mechanics() {
     let mut state = GlobalState::default();
     let sender = [6u8; 32];
+    state.accounts.entry(sender).or_default().lumina_balance = 1200;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
 
     // Mint senior — 5% should go to insurance fund
     let mut ctx = ExecutionContext {
@@ -256,519 +271,5215 @@ fn test_insurance_fund_mechanics() {
 }
 
 #[test]
-fn test_yield_token_wrap_unwrap() {
+fn test_opt_in_insurance_tiers_and_priority_claims() {
     let mut state = GlobalState::default();
-    let sender = [7u8; 32];
+    let insured = [21u8; 32];
+    let uninsured = [22u8; 32];
+    for who in [insured, uninsured] {
+        state.accounts.insert(
+            who,
+            AccountState {
+                lusd_balance: 1_000,
+                ..Default::default()
+            },
+        );
+    }
+    state.total_lusd_supply = 2_000;
+    state.stabilization_pool_balance = 2_000;
+    state.insurance_fund_balance = 50_000;
 
-    state.accounts.insert(
-        sender,
-        AccountState {
-            lusd_balance: 10000,
-            ..Default::default()
-        },
-    );
-    state.total_lusd_supply = 10000;
-    state.stabilization_pool_balance = 10000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
 
-    // Wrap
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 100,
-            timestamp: 1000,
-        };
-        let si = StablecoinInstruction::WrapToYieldToken {
-            amount: 5000,
-            maturity_blocks: 100,
-        };
-        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
-    }
+    // Tier 1 premium (50 LUSD) moves into the insurance fund.
+    let opt_in = StablecoinInstruction::OptInInsurance { tier: 1 };
+    execute_si(&opt_in, &insured, &mut ctx).unwrap();
+    let coverage = ctx.state.accounts[&insured]
+        .insurance_coverage
+        .clone()
+        .unwrap();
+    assert_eq!(coverage.paid_through_epoch, 1);
+    assert_eq!(ctx.state.accounts[&insured].lusd_balance, 950);
+    assert_eq!(ctx.state.insurance_fund_balance, 50_050);
+    assert_eq!(ctx.state.total_lusd_supply, 1_950);
 
-    assert_eq!(state.accounts.get(&sender).unwrap().lusd_balance, 5000);
+    // Paying again while covered extends the policy.
+    execute_si(&opt_in, &insured, &mut ctx).unwrap();
     assert_eq!(
-        state.accounts.get(&sender).unwrap().yield_positions.len(),
-        1
+        ctx.state.accounts[&insured]
+            .insurance_coverage
+            .as_ref()
+            .unwrap()
+            .paid_through_epoch,
+        2
     );
+    assert!(execute_si(
+        &StablecoinInstruction::OptInInsurance { tier: 9 },
+        &insured,
+        &mut ctx
+    )
+    .is_err());
 
-    // Unwrap (at maturity)
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 3_153_800,
-            timestamp: 2500,
-        };
-        let si = StablecoinInstruction::UnwrapYieldToken { token_id: 0 };
-        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
-    }
-
-    // Should have principal back plus net yield, and insurance pool should receive junior-yield cut.
-    assert!(state.accounts.get(&sender).unwrap().lusd_balance > 10000);
-    assert!(state.insurance_fund_balance > 0);
+    // Depeg: only policyholders can claim, up to their tier cap.
+    ctx.state.circuit_breaker_active = true;
+    let claim = |amount: u64| StablecoinInstruction::ClaimInsurance {
+        loss_proof: lumina_crypto::zk::create_insurance_loss_proof(amount, [4u8; 32]),
+        claimed_amount: amount,
+    };
+    assert!(execute_si(&claim(500), &uninsured, &mut ctx).is_err());
+    execute_si(&claim(30_000), &insured, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&insured].lusd_balance, 30_900);
     assert_eq!(
-        state.accounts.get(&sender).unwrap().yield_positions.len(),
-        0
+        ctx.state.accounts[&insured]
+            .insurance_coverage
+            .as_ref()
+            .unwrap()
+            .claimed,
+        30_000
     );
+
+    // Lapsed coverage loses priority.
+    ctx.state.current_epoch = 2;
+    assert!(execute_si(&claim(100), &insured, &mut ctx).is_err());
 }
 
 #[test]
-fn test_health_index_computation() {
+fn test_governance_sets_insurance_params() {
     let mut state = GlobalState::default();
-    state.total_lusd_supply = 1_000_000;
-    state.stabilization_pool_balance = 1_000_000;
-    state.reserve_ratio = 1.0;
+    let (validator, _) = new_sender();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let claimant = [24u8; 32];
     state.insurance_fund_balance = 50_000;
-    state
-        .oracle_prices
-        .insert("LUSD-USD".to_string(), 1_000_000);
-
-    let sender = [8u8; 32];
     let mut ctx = ExecutionContext {
         state: &mut state,
         height: 1,
         timestamp: 100,
     };
+    let propose = |param| StablecoinInstruction::ProposeProtocolParam { param };
+    let tier = |premium_per_epoch| InsuranceTierParams {
+        premium_per_epoch,
+        coverage_cap: 5_000,
+    };
 
-    let si = StablecoinInstruction::ComputeHealthIndex;
-    assert!(execute_si(&si, &sender, &mut ctx).is_ok());
+    let err = execute_si(
+        &propose(ProtocolParam::InsuranceTiers(vec![tier(10)])),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Insurance tiers cannot be removed");
+    let err = execute_si(
+        &propose(ProtocolParam::InsuranceTiers(vec![tier(0); 3])),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Insurance premiums must be non-zero");
+    let err = execute_si(
+        &propose(ProtocolParam::UninsuredClaimCap(400)),
+        &claimant,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only validators can propose protocol parameters"
+    );
 
-    // Should be a reasonably high health index (above 5000)
-    assert!(state.health_index > 5000);
+    let tiers = vec![tier(10), tier(20), tier(30), tier(40)];
+    execute_si(
+        &propose(ProtocolParam::InsuranceTiers(tiers.clone())),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.params.insurance_tiers, tiers);
+    execute_si(
+        &propose(ProtocolParam::UninsuredClaimCap(400)),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::ProtocolParamChanged {
+            height: 1,
+            proposal_id: 1,
+            param: ProtocolParam::UninsuredClaimCap(400),
+        }));
+
+    let claim = StablecoinInstruction::ClaimInsurance {
+        loss_proof: lumina_crypto::zk::create_insurance_loss_proof(500, [4u8; 32]),
+        claimed_amount: 500,
+    };
+    let err = execute_si(&claim, &claimant, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Claim exceeds uninsured claim cap");
 }
 
 #[test]
-fn test_parallel_non_conflicting_transfers() {
+fn test_junior_writedown_absorbs_shortfall() {
     let mut state = GlobalState::default();
-    let (s1, k1) = new_sender();
-    let (s2, k2) = new_sender();
-    let r1 = [9u8; 32];
-    let r2 = [10u8; 32];
-
-    state.accounts.entry(s1).or_default().lusd_balance = 100;
-    state.accounts.entry(s2).or_default().lusd_balance = 100;
-
-    let mut tx1 = Transaction {
-        sender: s1,
-        nonce: 0,
-        instruction: StablecoinInstruction::Transfer {
-            to: r1,
-            amount: 10,
-            asset: lumina_types::instruction::AssetType::LUSD,
+    let junior = [23u8; 32];
+    state.accounts.insert(
+        junior,
+        AccountState {
+            ljun_balance: 4_000,
+            ..Default::default()
         },
-        signature: vec![0; 64],
-        gas_limit: 1_000_000,
-        gas_price: 1,
+    );
+    state.total_ljun_supply = 4_000;
+    state.total_ljun_shares = 4_000;
+    state.total_lusd_supply = 10_000;
+    // The pool holds both tranches' collateral: 9_000 backs LUSD after
+    // the junior claim, 1_000 short of the floor
+    state.stabilization_pool_balance = 13_000;
+    state.insurance_fund_balance = 0;
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
     };
-    tx1.signature = lumina_crypto::signatures::sign(&k1, &tx1.signing_bytes());
 
-    let mut tx2 = Transaction {
-        sender: s2,
-        nonce: 0,
-        instruction: StablecoinInstruction::Transfer {
-            to: r2,
-            amount: 20,
-            asset: lumina_types::instruction::AssetType::LUSD,
+    execute_si(&StablecoinInstruction::TriggerStabilizer, &junior, &mut ctx).unwrap();
+
+    // 1_000 shortfall is taken pro-rata from LJUN: 4_000 -> 3_000 (rate 0.75).
+    assert_eq!(ctx.state.total_ljun_supply, 3_000);
+    assert_eq!(ctx.state.ljun_exchange_rate, 750_000);
+    // The cut moves no assets, so the pool and reserve ratio are unchanged.
+    assert_eq!(ctx.state.stabilization_pool_balance, 13_000);
+    assert!((ctx.state.reserve_ratio - 1.3).abs() < f64::EPSILON);
+    // Balances are untouched; their value is marked down by the index.
+    assert_eq!(ctx.state.accounts[&junior].ljun_balance, 4_000);
+
+    // Running it again finds the senior floor covered and cuts nothing more.
+    execute_si(&StablecoinInstruction::TriggerStabilizer, &junior, &mut ctx).unwrap();
+    assert_eq!(ctx.state.total_ljun_supply, 3_000);
+    assert_eq!(ctx.state.ljun_exchange_rate, 750_000);
+
+    // New junior capital enters at the written-down rate.
+    let newcomer = [24u8; 32];
+    execute_si(
+        &StablecoinInstruction::MintJunior {
+            amount: 750,
+            collateral_amount: 750,
         },
-        signature: vec![0; 64],
-        gas_limit: 1_000_000,
-        gas_price: 1,
+        &newcomer,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&newcomer].ljun_balance, 1_000);
+    assert_eq!(ctx.state.total_ljun_supply, 3_750);
+
+    // Redeeming all original units releases only their written-down value.
+    execute_si(
+        &StablecoinInstruction::RedeemJunior { amount: 4_000 },
+        &junior,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.total_ljun_supply, 750);
+    assert_eq!(ctx.state.total_ljun_shares, 1_000);
+}
+
+#[test]
+fn test_junior_writedown_does_not_raise_the_reserve_ratio() {
+    let mut state = GlobalState {
+        total_ljun_supply: 4_000,
+        total_ljun_shares: 4_000,
+        total_lusd_supply: 10_000,
+        stabilization_pool_balance: 9_000,
+        ..Default::default()
     };
-    tx2.signature = lumina_crypto::signatures::sign(&k2, &tx2.signing_bytes());
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+
+    // Even the whole junior tranche cannot cover LUSD, so it is wiped out,
+    // but the pool is still 1_000 short.
+    execute_si(
+        &StablecoinInstruction::TriggerStabilizer,
+        &[23u8; 32],
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.total_ljun_supply, 0);
+    assert_eq!(ctx.state.ljun_exchange_rate, 1);
+    assert_eq!(ctx.state.stabilization_pool_balance, 9_000);
+    assert!((ctx.state.reserve_ratio - 0.9).abs() < f64::EPSILON);
+}
 
+#[test]
+fn test_protocol_params_stay_in_range() {
+    let mut state = GlobalState::default();
+    let (validator, _) = new_sender();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
     let mut ctx = ExecutionContext {
         state: &mut state,
         height: 1,
-        timestamp: 1,
+        timestamp: 100,
+    };
+    let propose = |ctx: &mut ExecutionContext, param| {
+        execute_si(
+            &StablecoinInstruction::ProposeProtocolParam { param },
+            &validator,
+            ctx,
+        )
+        .map_err(|e| e.to_string())
     };
 
-    execute_transactions_parallel_non_conflicting(&[tx1, tx2], &mut ctx).unwrap();
+    assert_eq!(
+        propose(&mut ctx, ProtocolParam::JuniorWritedownFloorBps(9_000)),
+        Err("Junior write-down floor must be 10000-20000 bps".to_string())
+    );
+    propose(&mut ctx, ProtocolParam::JuniorWritedownFloorBps(11_000)).unwrap();
+    assert_eq!(ctx.state.params.junior_writedown_floor_bps, 11_000);
 
-    assert_eq!(ctx.state.accounts.get(&r1).unwrap().lusd_balance, 10);
-    assert_eq!(ctx.state.accounts.get(&r2).unwrap().lusd_balance, 20);
+    assert!(propose(&mut ctx, ProtocolParam::RedeemFeeBps(10_001)).is_err());
+    propose(&mut ctx, ProtocolParam::RedeemFeeBps(50)).unwrap();
+    assert_eq!(ctx.state.params.redeem_fee_bps, 50);
+    assert_eq!(
+        propose(&mut ctx, ProtocolParam::VolatilePegDeviationBps(300)),
+        Err("Volatile peg deviation must be non-zero and below the stress deviation".to_string())
+    );
+    propose(&mut ctx, ProtocolParam::StressPegDeviationBps(500)).unwrap();
+    propose(&mut ctx, ProtocolParam::VolatilePegDeviationBps(300)).unwrap();
+    assert_eq!(ctx.state.params.volatile_peg_deviation_bps, 300);
+
+    assert_eq!(
+        propose(&mut ctx, ProtocolParam::LiquidationThresholdBps(9_999)),
+        Err("Liquidation threshold must be 10000-20000 bps".to_string())
+    );
+    propose(&mut ctx, ProtocolParam::LiquidationThresholdBps(12_000)).unwrap();
+    assert_eq!(ctx.state.params.liquidation_threshold_bps, 12_000);
+
+    assert!(propose(&mut ctx, ProtocolParam::FlashLoanFeeBps(10_001)).is_err());
+    propose(&mut ctx, ProtocolParam::FlashLoanFeeBps(5)).unwrap();
+    assert_eq!(ctx.state.params.flash_loan_fee_bps, 5);
+
+    let weights = BTreeMap::from([(0, 1), (1, 1)]);
+    assert_eq!(
+        propose(&mut ctx, ProtocolParam::ZoneReserveWeights(weights)),
+        Err("Zone 0 cannot be weighted".to_string())
+    );
+    let weights = BTreeMap::from([(1, 1), (2, 3)]);
+    propose(&mut ctx, ProtocolParam::ZoneReserveWeights(weights.clone())).unwrap();
+    assert_eq!(ctx.state.params.zone_reserve_weights, weights);
+
+    assert!(propose(&mut ctx, ProtocolParam::GreenProofValiditySecs(3_600)).is_err());
+    propose(&mut ctx, ProtocolParam::GreenProofValiditySecs(30 * 86_400)).unwrap();
+    assert_eq!(ctx.state.params.green_proof_validity_secs, 30 * 86_400);
 }
 
 #[test]
-fn test_flash_mint_and_flash_burn_same_block() {
+fn test_ljun_share_accounting_and_migration() {
     let mut state = GlobalState::default();
-    let sender = [12u8; 32];
+    let a = [25u8; 32];
+    let b = [26u8; 32];
+    // Legacy rebased balances whose sum drifted from the recorded supply.
+    for (who, bal) in [(a, 3_000u64), (b, 1_000u64)] {
+        state.accounts.insert(
+            who,
+            AccountState {
+                ljun_balance: bal,
+                ..Default::default()
+            },
+        );
+    }
+    state.total_ljun_supply = 6_000;
 
-    // Provide some base collateral so reserve ratio doesn't instantly trip circuit breaker.
-    state.stabilization_pool_balance = 1_000_000;
-    state.total_lusd_supply = 1_000_000;
-    state.reserve_ratio = 1.0;
+    migrate_ljun_to_shares(&mut state);
+    assert_eq!(state.total_ljun_shares, 4_000);
+    assert_eq!(state.ljun_exchange_rate, 1_500_000);
+    // Migration is idempotent.
+    migrate_ljun_to_shares(&mut state);
+    assert_eq!(state.ljun_exchange_rate, 1_500_000);
 
-    // Flash mint
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 10,
-            timestamp: 1,
-        };
-        let si = StablecoinInstruction::FlashMint {
-            amount: 1000,
-            collateral_asset: lumina_types::instruction::AssetType::Lumina,
-            collateral_amount: 1200,
-            commitment: [9u8; 32],
-        };
-        execute_si(&si, &sender, &mut ctx).unwrap();
-        assert_eq!(ctx.state.pending_flash_mints, 1000);
-        let acct = ctx.state.accounts.get(&sender).unwrap();
-        assert_eq!(acct.pending_flash_mint, 1000);
-        assert_eq!(acct.pending_flash_collateral, 1200);
-    }
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
 
-    // Must burn full amount
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 10,
-            timestamp: 2,
-        };
-        let burn = StablecoinInstruction::FlashBurn { amount: 1000 };
-        execute_si(&burn, &sender, &mut ctx).unwrap();
-        assert_eq!(ctx.state.pending_flash_mints, 0);
-        let acct = ctx.state.accounts.get(&sender).unwrap();
-        assert_eq!(acct.pending_flash_mint, 0);
-        assert_eq!(acct.pending_flash_collateral, 0);
-    }
+    // 80% of yield accrues to LJUN through the rate; balances are not rebased.
+    execute_si(
+        &StablecoinInstruction::DistributeYield { total_yield: 7_500 },
+        &a,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.total_ljun_supply, 12_000);
+    assert_eq!(ctx.state.ljun_exchange_rate, 3_000_000);
+    assert_eq!(ctx.state.accounts[&a].ljun_balance, 3_000);
+
+    // Transfers move shares; the receiver's value follows the rate.
+    execute_si(
+        &StablecoinInstruction::Transfer {
+            to: b,
+            amount: 1_000,
+            asset: AssetType::LJUN,
+        },
+        &a,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&b].ljun_balance, 2_000);
+    assert_eq!(ctx.state.accounts[&a].epoch_tx_volume, 3_000);
+    assert_eq!(ctx.state.ljun_value_of(2_000), 6_000);
+
+    // Redeeming shares releases their accrued value.
+    execute_si(
+        &StablecoinInstruction::RedeemJunior { amount: 2_000 },
+        &b,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.total_ljun_supply, 6_000);
+    assert_eq!(ctx.state.total_ljun_shares, 2_000);
 }
 
 #[test]
-fn test_flash_burn_rejects_partial_repayment_fail_closed() {
+fn test_oracle_regime_drives_end_block_peg_defense() {
     let mut state = GlobalState::default();
-    let sender = [21u8; 32];
+    let holder = [27u8; 32];
+    state.accounts.insert(
+        holder,
+        AccountState {
+            lusd_balance: 1_000,
+            lumina_balance: 1_200,
+            ..Default::default()
+        },
+    );
+    state.total_lusd_supply = 10_000;
+    state.stabilization_pool_balance = 9_600;
+    state.reserve_ratio = 0.96;
+    state.insurance_fund_balance = 1_000;
+    state.oracle_prices.insert("LUSD-USD".to_string(), 960_000);
 
-    state.stabilization_pool_balance = 1_000_000;
-    state.total_lusd_supply = 1_000_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 5,
+        timestamp: 500,
+    };
 
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 42,
-            timestamp: 1,
-        };
-        let flash = StablecoinInstruction::FlashMint {
+    // 4% off peg: Stress. The stabilizer runs automatically from end_block.
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.oracle_regime, OracleRegime::Stress);
+    assert_eq!(
+        ctx.state.events,
+        vec![ChainEvent::OracleRegimeChanged {
+            height: 5,
+            from: OracleRegime::Stable,
+            to: OracleRegime::Stress,
+        }]
+    );
+    assert_eq!(ctx.state.stabilization_pool_balance, 10_000);
+    assert_eq!(ctx.state.insurance_fund_balance, 600);
+
+    // Redemptions are queued while in Stress, even though reserves are restored.
+    execute_si(
+        &StablecoinInstruction::RedeemSenior { amount: 100 },
+        &holder,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.fair_redeem_queue.len(), 1);
+
+    // 1.5% off peg: Volatile widens the mint fee from 5% to 6%.
+    ctx.state
+        .oracle_prices
+        .insert("LUSD-USD".to_string(), 985_000);
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.oracle_regime, OracleRegime::Volatile);
+    assert_eq!(ctx.state.events.len(), 2);
+
+    ctx.state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    execute_si(
+        &StablecoinInstruction::MintSenior {
             amount: 1_000,
-            collateral_asset: lumina_types::instruction::AssetType::Lumina,
-            collateral_amount: 1_100,
-            commitment: [1u8; 32],
-        };
-        execute_si(&flash, &sender, &mut ctx).unwrap();
+            collateral_amount: 1_200,
+            proof: manager.prove_reserves(vec![1_200], 1_200),
+        },
+        &holder,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.insurance_fund_balance, 660);
+    assert_eq!(ctx.state.accounts[&holder].lusd_balance, 1_840);
+}
+
+#[test]
+fn test_mint_senior_custodies_real_collateral() {
+    let mut state = GlobalState::default();
+    let minter = [28u8; 32];
+    {
+        let acct = state.accounts.entry(minter).or_default();
+        acct.lumina_balance = 500;
+        acct.custom_balances
+            .insert("GOLD".to_string(), 2_000.into());
     }
+    state
+        .oracle_prices
+        .insert("GOLD-USD".to_string(), 1_000_000);
 
+    let manager = lumina_crypto::zk::ZkManager::setup();
     let mut ctx = ExecutionContext {
         state: &mut state,
-        height: 42,
-        timestamp: 2,
+        height: 1,
+        timestamp: 100,
     };
-    let partial_burn = StablecoinInstruction::FlashBurn { amount: 999 };
-    let err = execute_si(&partial_burn, &sender, &mut ctx).unwrap_err();
 
-    assert!(err
-        .to_string()
-        .contains("Flash burn must burn full pending flash mint in this block"));
+    // Collateral the minter does not hold is rejected.
+    let too_much = StablecoinInstruction::MintSenior {
+        amount: 1_000,
+        collateral_amount: 1_200,
+        proof: manager.prove_reserves(vec![1_200], 1_200),
+    };
+    assert!(execute_si(&too_much, &minter, &mut ctx).is_err());
+    assert_eq!(ctx.state.accounts[&minter].lumina_balance, 500);
+
+    let mint = StablecoinInstruction::MintSeniorWithCollateral {
+        amount: 1_000,
+        collateral_asset: CollateralAsset::Custom("GOLD".to_string()),
+        collateral_amount: 1_200,
+        proof: manager.prove_reserves(vec![1_200], 1_200),
+    };
+    execute_si(&mint, &minter, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&minter].custom_balances["GOLD"], 800);
+    assert_eq!(ctx.state.accounts[&minter].collateral_position_ids, vec![0]);
+    assert_eq!(ctx.state.collateral_positions[&0].minted, 950);
+
+    // Redeeming returns the custodied collateral pro-rata.
+    execute_si(
+        &StablecoinInstruction::RedeemSenior { amount: 475 },
+        &minter,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&minter].custom_balances["GOLD"], 1_400);
+    execute_si(
+        &StablecoinInstruction::RedeemSenior { amount: 475 },
+        &minter,
+        &mut ctx,
+    )
+    .unwrap();
+    let acct = &ctx.state.accounts[&minter];
+    assert_eq!(acct.custom_balances["GOLD"], 2_000);
+    assert!(acct.collateral_position_ids.is_empty());
+    assert!(ctx.state.collateral_positions.is_empty());
 }
 
 #[test]
-fn test_flash_burn_rejects_when_balance_missing_fail_closed() {
+fn test_position_ledger_partial_redeem_and_liquidation() {
     let mut state = GlobalState::default();
-    let sender = [22u8; 32];
-
-    state.stabilization_pool_balance = 1_000_000;
-    state.total_lusd_supply = 1_000_000;
-
+    let minter = [29u8; 32];
+    let keeper = [30u8; 32];
     {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 7,
-            timestamp: 1,
-        };
-        let flash = StablecoinInstruction::FlashMint {
-            amount: 500,
-            collateral_asset: lumina_types::instruction::AssetType::Lumina,
-            collateral_amount: 550,
-            commitment: [2u8; 32],
-        };
-        execute_si(&flash, &sender, &mut ctx).unwrap();
-
-        // Spend 1 LUSD so the wallet can no longer close the full flash position.
-        let drain = StablecoinInstruction::Transfer {
-            to: [99u8; 32],
-            amount: 1,
-            asset: lumina_types::instruction::AssetType::LUSD,
-        };
-        execute_si(&drain, &sender, &mut ctx).unwrap();
+        let acct = state.accounts.entry(minter).or_default();
+        acct.lumina_balance = 1_200;
+        acct.bridged_balances
+            .insert("BTC".to_string(), 1_200.into());
     }
+    state.accounts.entry(keeper).or_default().lusd_balance = 10_000;
+    state.total_lusd_supply = 10_000;
+    state.stabilization_pool_balance = 10_000;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 2_000_000);
+    state.oracle_prices.insert("BTC-USD".to_string(), 2_000_000);
 
+    let manager = lumina_crypto::zk::ZkManager::setup();
     let mut ctx = ExecutionContext {
         state: &mut state,
-        height: 7,
-        timestamp: 2,
+        height: 1,
+        timestamp: 100,
     };
-    let burn = StablecoinInstruction::FlashBurn { amount: 500 };
-    let err = execute_si(&burn, &sender, &mut ctx).unwrap_err();
+    for asset in [
+        CollateralAsset::Lumina,
+        CollateralAsset::Bridged("BTC".to_string()),
+    ] {
+        execute_si(
+            &StablecoinInstruction::MintSeniorWithCollateral {
+                amount: 1_000,
+                collateral_asset: asset,
+                collateral_amount: 1_200,
+                proof: manager.prove_reserves(vec![1_200], 1_200),
+            },
+            &minter,
+            &mut ctx,
+        )
+        .unwrap();
+    }
+    assert_eq!(ctx.state.collateral_positions.len(), 2);
 
-    assert!(err.to_string().contains("Insufficient LUSD for flash burn"));
+    // Partial redemption of a specific position releases that position's asset.
+    execute_si(
+        &StablecoinInstruction::RedeemPosition {
+            position_id: 1,
+            amount: 475,
+        },
+        &minter,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&minter].bridged_balances["BTC"], 600);
+    assert_eq!(ctx.state.accounts[&minter].lumina_balance, 0);
+    assert_eq!(ctx.state.collateral_positions[&1].minted, 475);
+    let history = &ctx.state.credit_histories[&minter];
+    assert_eq!((history.repayments, history.repaid), (1, 475));
+    assert!(execute_si(
+        &StablecoinInstruction::RedeemPosition {
+            position_id: 1,
+            amount: 10,
+        },
+        &keeper,
+        &mut ctx,
+    )
+    .is_err());
+
+    // Healthy positions cannot be liquidated.
+    let liquidate = StablecoinInstruction::LiquidatePosition { position_id: 0 };
+    assert!(execute_si(&liquidate, &keeper, &mut ctx).is_err());
+
+    // LUMINA crashes: position 0 (1_200 LUMINA @ $0.50 vs 950 LUSD) is liquidatable.
+    ctx.state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 500_000);
+    execute_si(&liquidate, &keeper, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&keeper].lusd_balance, 9_050);
+    assert_eq!(ctx.state.accounts[&keeper].lumina_balance, 1_200);
+    assert!(!ctx.state.collateral_positions.contains_key(&0));
+    assert_eq!(ctx.state.accounts[&minter].collateral_position_ids, vec![1]);
+    assert_eq!(ctx.state.credit_histories[&minter].liquidations, 1);
+    assert!(!ctx.state.credit_histories.contains_key(&keeper));
 }
 
 #[test]
-fn test_instant_redeem_queues_under_stress() {
+fn test_unpriced_collateral_cannot_be_liquidated() {
     let mut state = GlobalState::default();
-    let sender = [13u8; 32];
-
-    state.accounts.insert(
-        sender,
-        AccountState {
-            lusd_balance: 5000,
-            ..Default::default()
-        },
-    );
-    state.total_lusd_supply = 5000;
-    state.reserve_ratio = 0.90;
-    state.stabilization_pool_balance = 4500;
-
+    let (minter, keeper) = ([31u8; 32], [32u8; 32]);
+    state.accounts.entry(minter).or_default().lumina_balance = 1_200;
+    state.accounts.entry(keeper).or_default().lusd_balance = 1_000;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
     let mut ctx = ExecutionContext {
         state: &mut state,
         height: 1,
         timestamp: 100,
     };
-    let redeem = StablecoinInstruction::InstantRedeem {
-        amount: 1000,
-        destination: [0u8; 32],
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    execute_si(
+        &StablecoinInstruction::MintSenior {
+            amount: 1_000,
+            collateral_amount: 1_200,
+            proof: manager.prove_reserves(vec![1_200], 1_200),
+        },
+        &minter,
+        &mut ctx,
+    )
+    .unwrap();
+
+    // Without a LUMINA-USD feed the position cannot be valued, so it is not
+    // liquidatable, and no more LUMINA can be minted against
+    ctx.state.oracle_prices.remove("LUMINA-USD");
+    let liquidate = StablecoinInstruction::LiquidatePosition { position_id: 0 };
+    assert_eq!(
+        execute_si(&liquidate, &keeper, &mut ctx)
+            .unwrap_err()
+            .to_string(),
+        "No oracle price for collateral"
+    );
+    assert!(ctx.state.collateral_positions.contains_key(&0));
+    assert_eq!(ctx.state.accounts[&keeper].lusd_balance, 1_000);
+    let mint = StablecoinInstruction::MintSenior {
+        amount: 100,
+        collateral_amount: 120,
+        proof: manager.prove_reserves(vec![120], 120),
     };
-    execute_si(&redeem, &sender, &mut ctx).unwrap();
-    assert_eq!(ctx.state.fair_redeem_queue.len(), 1);
-    assert_eq!(ctx.state.accounts.get(&sender).unwrap().lusd_balance, 4000);
+    assert_eq!(
+        execute_si(&mint, &minter, &mut ctx)
+            .unwrap_err()
+            .to_string(),
+        "No price for collateral asset"
+    );
 }
 
 #[test]
-fn test_mint_with_credit_score_allowlist_and_replay_protection() {
+fn test_collateral_auction_repays_debt_and_returns_surplus() {
     let mut state = GlobalState::default();
-    let sender = [14u8; 32];
-    let oracle = [7u8; 32];
-    state.trusted_credit_oracles.push(oracle);
+    let minter = [34u8; 32];
+    let early = [35u8; 32];
+    let late = [36u8; 32];
+    {
+        let acct = state.accounts.entry(minter).or_default();
+        acct.lumina_balance = 1_200;
+        acct.bridged_balances
+            .insert("BTC".to_string(), 1_200.into());
+    }
+    state.accounts.entry(early).or_default().lusd_balance = 5_000;
+    state.accounts.entry(late).or_default().lusd_balance = 5_000;
+    state.total_lusd_supply = 10_000;
+    state.stabilization_pool_balance = 10_000;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 2_000_000);
+    state.oracle_prices.insert("BTC-USD".to_string(), 2_000_000);
 
     let manager = lumina_crypto::zk::ZkManager::setup();
-    let raw = manager.prove_range(500, 1000);
-    let proof = bound_proof(*blake3::hash(b"credit-score").as_bytes(), raw);
-
-    // Derive the score the execution will compute so we can set a threshold it passes.
-    let score_bytes = blake3::hash(&proof);
-    let raw_score = u16::from_le_bytes([score_bytes.as_bytes()[0], score_bytes.as_bytes()[1]]);
-    let score = 300 + (raw_score % 551);
-    let threshold = score.saturating_sub(1);
-
     let mut ctx = ExecutionContext {
         state: &mut state,
         height: 1,
         timestamp: 100,
     };
+    for asset in [
+        CollateralAsset::Lumina,
+        CollateralAsset::Bridged("BTC".to_string()),
+    ] {
+        execute_si(
+            &StablecoinInstruction::MintSeniorWithCollateral {
+                amount: 1_000,
+                collateral_asset: asset,
+                collateral_amount: 1_200,
+                proof: manager.prove_reserves(vec![1_200], 1_200),
+            },
+            &minter,
+            &mut ctx,
+        )
+        .unwrap();
+    }
+    let start = |position_id| StablecoinInstruction::StartAuction { position_id };
+    assert!(execute_si(&start(0), &early, &mut ctx).is_err());
 
-    let mint = StablecoinInstruction::MintWithCreditScore {
-        amount: 1000,
-        collateral_amount: 1200,
-        credit_score_proof: proof.clone(),
-        min_score_threshold: threshold,
-        oracle,
-    };
-    execute_si(&mint, &sender, &mut ctx).unwrap();
-    assert_eq!(ctx.state.accounts.get(&sender).unwrap().lusd_balance, 1000);
+    // LUMINA at $0.80: 1_200 LUMINA no longer covers 110% of 950 LUSD.
+    ctx.state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 800_000);
+    ctx.state
+        .oracle_prices
+        .insert("BTC-USD".to_string(), 800_000);
+    execute_si(&start(0), &early, &mut ctx).unwrap();
+    execute_si(&start(1), &early, &mut ctx).unwrap();
+    assert!(ctx.state.collateral_positions.is_empty());
+    let auction = &ctx.state.collateral_auctions[&0];
+    assert_eq!(
+        (auction.start_price, auction.floor_price),
+        (960_000, 400_000)
+    );
+    assert_eq!(auction.end_height, 361);
+    let settle = |auction_id| StablecoinInstruction::SettleAuction { auction_id };
+    assert!(execute_si(&settle(0), &early, &mut ctx).is_err());
 
-    // Replaying the same proof should fall back to MintSenior (and thus charge 5% fee).
-    let mut ctx2 = ExecutionContext {
-        state: &mut state,
-        height: 2,
-        timestamp: 200,
+    // A quarter of the way down the price is $0.82.
+    ctx.height = 91;
+    let supply = ctx.state.total_lusd_supply;
+    let minter_lusd = ctx.state.accounts[&minter].lusd_balance;
+    let bid = |auction_id, max_collateral| StablecoinInstruction::Bid {
+        auction_id,
+        max_collateral,
     };
-    execute_si(&mint, &sender, &mut ctx2).unwrap();
-    // MintSenior mints net of fee: 1000 - 50 = 950
-    assert_eq!(ctx2.state.accounts.get(&sender).unwrap().lusd_balance, 1950);
+    execute_si(&bid(0, 500), &early, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&early].lusd_balance, 4_590);
+    assert_eq!(ctx.state.accounts[&early].lumina_balance, 500);
+    // The second bid is capped at the collateral that repays the rest.
+    execute_si(&bid(0, 10_000), &late, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&late].lumina_balance, 659);
+    assert_eq!(ctx.state.accounts[&late].lusd_balance, 4_459);
+    assert_eq!(ctx.state.collateral_auctions[&0].debt_remaining, 0);
+    assert_eq!(ctx.state.total_lusd_supply, supply - 950);
+    assert!(execute_si(&bid(0, 1), &late, &mut ctx).is_err());
+
+    // Unsold collateral and rounding surplus go back to the owner.
+    execute_si(&settle(0), &early, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&minter].lumina_balance, 41);
+    assert_eq!(ctx.state.accounts[&minter].lusd_balance, minter_lusd + 1);
+    assert!(!ctx.state.collateral_auctions.contains_key(&0));
+
+    // An auction nobody bid on reopens the position once it expires.
+    ctx.height = 362;
+    assert!(execute_si(&bid(1, 100), &late, &mut ctx).is_err());
+    execute_si(&settle(1), &early, &mut ctx).unwrap();
+    let reopened = &ctx.state.collateral_positions[&1];
+    assert_eq!((reopened.collateral_amount, reopened.minted), (1_200, 950));
+    assert_eq!(ctx.state.accounts[&minter].collateral_position_ids, vec![1]);
+    assert!(ctx.state.collateral_auctions.is_empty());
 }
 
 #[test]
-fn test_rwa_listing_and_pledge() {
+fn test_keepers_earn_bounties_only_for_real_work() {
     let mut state = GlobalState::default();
-    let sender = [15u8; 32];
+    let keeper = [37u8; 32];
+    let redeemer = [38u8; 32];
+    state.total_lusd_supply = 100_000;
+    state.stabilization_pool_balance = 90_000;
+    state.insurance_fund_balance = 50_000;
 
-    let manager = lumina_crypto::zk::ZkManager::setup();
-    let raw = manager.prove_range(1, 10);
-    let attested_value = 10_000u64;
-    let proof = bound_proof(*blake3::hash(&attested_value.to_le_bytes()).as_bytes(), raw);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let lusd = |ctx: &ExecutionContext| {
+        ctx.state
+            .accounts
+            .get(&keeper)
+            .map_or(0, |a| a.lusd_balance)
+    };
 
-    // List RWA
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 1,
-            timestamp: 100,
-        };
-        let list = StablecoinInstruction::ListRWA {
-            asset_description: "invoice #123".to_string(),
-            attested_value,
-            attestation_proof: proof,
-            maturity_date: Some(1_000_000),
-            collateral_eligibility: true,
-        };
-        execute_si(&list, &sender, &mut ctx).unwrap();
-        assert_eq!(ctx.state.rwa_listings.len(), 1);
-        assert_eq!(ctx.state.next_rwa_id, 1);
-    }
+    // Restoring 10_000 of backing pays 0.1% of it.
+    execute_si(&StablecoinInstruction::TriggerStabilizer, &keeper, &mut ctx).unwrap();
+    assert_eq!(lusd(&ctx), 10);
+    assert_eq!(ctx.state.insurance_fund_balance, 39_990);
+    assert_eq!(ctx.state.total_lusd_supply, 100_010);
 
-    // Pledge against it
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 2,
-            timestamp: 200,
-        };
-        let pledge = StablecoinInstruction::UseRWAAsCollateral {
-            rwa_id: 0,
-            amount_to_pledge: 2500,
-        };
-        execute_si(&pledge, &sender, &mut ctx).unwrap();
-        assert_eq!(ctx.state.accounts.get(&sender).unwrap().lusd_balance, 2500);
-        assert_eq!(ctx.state.rwa_listings.get(&0).unwrap().pledged_amount, 2500);
-    }
+    // Within the cooldown the stabilizer still runs but pays nothing.
+    ctx.height = 50;
+    ctx.state.stabilization_pool_balance = 95_000;
+    execute_si(&StablecoinInstruction::TriggerStabilizer, &keeper, &mut ctx).unwrap();
+    assert_eq!(ctx.state.insurance_fund_balance, 34_980);
+    assert_eq!(lusd(&ctx), 10);
+
+    ctx.height = 101;
+    ctx.state.stabilization_pool_balance = 95_000;
+    execute_si(&StablecoinInstruction::TriggerStabilizer, &keeper, &mut ctx).unwrap();
+    assert_eq!(lusd(&ctx), 15);
+    assert_eq!(
+        ctx.state.keeper_last_paid[&KeeperTask::TriggerStabilizer],
+        101
+    );
+
+    // Processing an empty queue or rebalancing balanced tranches is unpaid.
+    let process = StablecoinInstruction::FairRedeemQueue { batch_size: 10 };
+    execute_si(&process, &keeper, &mut ctx).unwrap();
+    execute_si(&StablecoinInstruction::RebalanceTranches, &keeper, &mut ctx).unwrap();
+    assert_eq!(lusd(&ctx), 15);
+
+    ctx.state.fair_redeem_queue.push(RedemptionRequest {
+        address: redeemer,
+        amount: 2_000,
+        timestamp: 100,
+    });
+    execute_si(&process, &keeper, &mut ctx).unwrap();
+    assert_eq!(lusd(&ctx), 17);
+    let rewarded = ctx
+        .state
+        .events
+        .iter()
+        .filter(|e| matches!(e, ChainEvent::KeeperRewarded { .. }))
+        .count();
+    assert_eq!(rewarded, 3);
 }
 
 #[test]
-fn test_submit_zk_por_requires_valid_proof_and_no_replay() {
+fn test_flash_loan_repay_and_end_block_clawback() {
     let mut state = GlobalState::default();
-    let sender = [12u8; 32];
-    let manager = lumina_crypto::zk::ZkManager::setup();
-    let proof = manager.prove_reserves(vec![40, 60], 100);
+    let arb = [31u8; 32];
+    let defaulter = [32u8; 32];
+    let sink = [33u8; 32];
+    state.accounts.entry(arb).or_default().lusd_balance = 100;
+    state.accounts.entry(defaulter).or_default().lusd_balance = 500;
+    state.total_lusd_supply = 10_000;
+    state.stabilization_pool_balance = 10_000;
 
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 1,
-            timestamp: 100,
-        };
-        let si = StablecoinInstruction::SubmitZkPoR {
-            proof: proof.clone(),
-            total_reserves: 100,
-            timestamp: 1,
-        };
-        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
-    }
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 7,
+        timestamp: 700,
+    };
+    begin_block(&mut ctx);
 
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 2,
-            timestamp: 200,
-        };
-        let replay = StablecoinInstruction::SubmitZkPoR {
-            proof: proof.clone(),
-            total_reserves: 100,
-            timestamp: 2,
-        };
-        assert!(execute_si(&replay, &sender, &mut ctx).is_err());
-    }
+    execute_si(
+        &StablecoinInstruction::FlashLoan { amount: 5_000 },
+        &arb,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.stabilization_pool_balance, 5_000);
+    assert_eq!(ctx.state.flash_loan_utilization_bps, 5_000);
+    assert!(execute_si(
+        &StablecoinInstruction::FlashLoan { amount: 6_000 },
+        &defaulter,
+        &mut ctx
+    )
+    .is_err());
+
+    // Repayment returns principal to the pool and burns the 9 bps fee, whose
+    // backing moves from the pool to insurance.
+    assert!(execute_si(
+        &StablecoinInstruction::FlashRepay { amount: 4_000 },
+        &arb,
+        &mut ctx
+    )
+    .is_err());
+    execute_si(
+        &StablecoinInstruction::FlashRepay { amount: 5_000 },
+        &arb,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&arb].lusd_balance, 96);
+    assert_eq!(ctx.state.stabilization_pool_balance, 9_996);
+    assert_eq!(ctx.state.total_lusd_supply, 9_996);
+    assert_eq!(ctx.state.insurance_fund_balance, 4);
+    assert_eq!(ctx.state.pending_flash_loans, 0);
+    assert!(matches!(
+        ctx.state.events.last(),
+        Some(ChainEvent::FeePaid { account, amount: 4, .. }) if *account == arb
+    ));
+
+    // A borrower that moves the funds away is clawed back in end_block.
+    execute_si(
+        &StablecoinInstruction::FlashLoan { amount: 1_000 },
+        &defaulter,
+        &mut ctx,
+    )
+    .unwrap();
+    execute_si(
+        &StablecoinInstruction::Transfer {
+            to: sink,
+            amount: 1_200,
+            asset: AssetType::LUSD,
+        },
+        &defaulter,
+        &mut ctx,
+    )
+    .unwrap();
+    end_block(&mut ctx);
+
+    assert_eq!(ctx.state.accounts[&defaulter].lusd_balance, 0);
+    assert_eq!(ctx.state.accounts[&defaulter].pending_flash_loan, 0);
+    assert_eq!(ctx.state.pending_flash_loans, 0);
+    assert_eq!(ctx.state.insurance_fund_balance, 0);
+    assert_eq!(ctx.state.stabilization_pool_balance, 9_300);
+    assert!(ctx.state.events.contains(&ChainEvent::FlashLoanDefaulted {
+        height: 7,
+        borrower: defaulter,
+        shortfall: 700,
+    }));
+    assert_eq!(ctx.state.flash_loan_volume, 6_000);
 }
 
 #[test]
-fn test_zero_slip_batch_match_blocks_duplicates_and_replay() {
+fn test_yield_token_wrap_unwrap() {
     let mut state = GlobalState::default();
-    let sender = [13u8; 32];
+    let sender = [7u8; 32];
 
-    let orders = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+    state.accounts.insert(
+        sender,
+        AccountState {
+            lusd_balance: 10000,
+            ..Default::default()
+        },
+    );
+    state.total_lusd_supply = 10000;
+    state.stabilization_pool_balance = 10000;
+
+    // Wrap
     {
         let mut ctx = ExecutionContext {
             state: &mut state,
-            height: 1,
-            timestamp: 100,
+            height: 100,
+            timestamp: 1000,
         };
-        let si = StablecoinInstruction::ZeroSlipBatchMatch {
-            orders: orders.clone(),
+        let si = StablecoinInstruction::WrapToYieldToken {
+            amount: 5000,
+            maturity_blocks: 100,
         };
         assert!(execute_si(&si, &sender, &mut ctx).is_ok());
     }
 
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 2,
-            timestamp: 200,
-        };
-        let replay = StablecoinInstruction::ZeroSlipBatchMatch {
-            orders: orders.clone(),
-        };
-        assert!(execute_si(&replay, &sender, &mut ctx).is_err());
-    }
+    assert_eq!(state.accounts.get(&sender).unwrap().lusd_balance, 5000);
+    assert_eq!(
+        state.accounts.get(&sender).unwrap().yield_positions.len(),
+        1
+    );
 
+    // Unwrap (at maturity)
     {
         let mut ctx = ExecutionContext {
             state: &mut state,
-            height: 3,
-            timestamp: 300,
-        };
-        let dup = StablecoinInstruction::ZeroSlipBatchMatch {
-            orders: vec![[9u8; 32], [9u8; 32]],
+            height: 3_153_800,
+            timestamp: 2500,
         };
-        assert!(execute_si(&dup, &sender, &mut ctx).is_err());
+        let si = StablecoinInstruction::UnwrapYieldToken { token_id: 0 };
+        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
     }
-}
 
-#[test]
-fn test_custom_asset_transfer_and_burn() {
+    // Should have principal back plus net yield, and insurance pool should receive junior-yield cut.
+    assert!(state.accounts.get(&sender).unwrap().lusd_balance > 10000);
+    let net_yield = state.accounts[&sender].lusd_balance - 10000;
+    assert!(matches!(
+        state.events.last(),
+        Some(ChainEvent::YieldPaid { account, token_id: 0, amount, .. })
+            if *account == sender && *amount == net_yield
+    ));
+    assert!(state.insurance_fund_balance > 0);
+    assert_eq!(
+        state.accounts.get(&sender).unwrap().yield_positions.len(),
+        0
+    );
+}
+
+#[test]
+fn test_health_index_computation() {
+    let mut state = GlobalState {
+        total_lusd_supply: 1_000_000,
+        stabilization_pool_balance: 1_000_000,
+        reserve_ratio: 1.0,
+        insurance_fund_balance: 50_000,
+        ..Default::default()
+    };
+    state
+        .oracle_prices
+        .insert("LUSD-USD".to_string(), 1_000_000);
+
+    let sender = [8u8; 32];
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+
+    let si = StablecoinInstruction::ComputeHealthIndex;
+    assert!(execute_si(&si, &sender, &mut ctx).is_ok());
+
+    // Should be a reasonably high health index (above 5000)
+    assert!(state.health_index > 5000);
+}
+
+#[test]
+fn test_parallel_non_conflicting_transfers() {
+    let mut state = GlobalState::default();
+    let (s1, k1) = new_sender();
+    let (s2, k2) = new_sender();
+    let r1 = [9u8; 32];
+    let r2 = [10u8; 32];
+
+    state.accounts.entry(s1).or_default().lusd_balance = 100;
+    state.accounts.entry(s2).or_default().lusd_balance = 100;
+
+    let mut tx1 = Transaction {
+        sender: s1,
+        nonce: 0,
+        instruction: StablecoinInstruction::Transfer {
+            to: r1,
+            amount: 10,
+            asset: lumina_types::instruction::AssetType::LUSD,
+        },
+        signature: vec![0; 64],
+        gas_limit: 1_000_000,
+        gas_price: 1,
+    };
+    tx1.signature = lumina_crypto::signatures::sign(&k1, &tx1.signing_bytes());
+
+    let mut tx2 = Transaction {
+        sender: s2,
+        nonce: 0,
+        instruction: StablecoinInstruction::Transfer {
+            to: r2,
+            amount: 20,
+            asset: lumina_types::instruction::AssetType::LUSD,
+        },
+        signature: vec![0; 64],
+        gas_limit: 1_000_000,
+        gas_price: 1,
+    };
+    tx2.signature = lumina_crypto::signatures::sign(&k2, &tx2.signing_bytes());
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 1,
+    };
+
+    execute_transactions_parallel_non_conflicting(&[tx1, tx2], &mut ctx).unwrap();
+
+    assert_eq!(ctx.state.accounts.get(&r1).unwrap().lusd_balance, 10);
+    assert_eq!(ctx.state.accounts.get(&r2).unwrap().lusd_balance, 20);
+}
+
+#[test]
+fn test_flash_mint_and_flash_burn_same_block() {
+    let mut state = GlobalState::default();
+    let sender = [12u8; 32];
+
+    // Provide some base collateral so reserve ratio doesn't instantly trip circuit breaker.
+    state.stabilization_pool_balance = 1_000_000;
+    state.total_lusd_supply = 1_000_000;
+    state.reserve_ratio = 1.0;
+
+    // Flash mint
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 10,
+            timestamp: 1,
+        };
+        let si = StablecoinInstruction::FlashMint {
+            amount: 1000,
+            collateral_asset: lumina_types::instruction::AssetType::Lumina,
+            collateral_amount: 1200,
+            commitment: [9u8; 32],
+        };
+        execute_si(&si, &sender, &mut ctx).unwrap();
+        assert_eq!(ctx.state.pending_flash_mints, 1000);
+        let acct = ctx.state.accounts.get(&sender).unwrap();
+        assert_eq!(acct.pending_flash_mint, 1000);
+        assert_eq!(acct.pending_flash_collateral, 1200);
+    }
+
+    // Must burn full amount
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 10,
+            timestamp: 2,
+        };
+        let burn = StablecoinInstruction::FlashBurn { amount: 1000 };
+        execute_si(&burn, &sender, &mut ctx).unwrap();
+        assert_eq!(ctx.state.pending_flash_mints, 0);
+        let acct = ctx.state.accounts.get(&sender).unwrap();
+        assert_eq!(acct.pending_flash_mint, 0);
+        assert_eq!(acct.pending_flash_collateral, 0);
+    }
+}
+
+#[test]
+fn test_unburned_flash_mint_is_clawed_back_in_end_block() {
+    let mut state = GlobalState::default();
+    let attacker = [34u8; 32];
+    let accomplice = [35u8; 32];
+    state.stabilization_pool_balance = 1_000_000;
+    state.total_lusd_supply = 1_000_000;
+    state.reserve_ratio = 1.0;
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 11,
+        timestamp: 1,
+    };
+    begin_block(&mut ctx);
+    execute_si(
+        &StablecoinInstruction::FlashMint {
+            amount: 1_000,
+            collateral_asset: AssetType::Lumina,
+            collateral_amount: 1_200,
+            commitment: [9u8; 32],
+        },
+        &attacker,
+        &mut ctx,
+    )
+    .unwrap();
+
+    // Attack: move most of the flash-minted LUSD away and never burn it.
+    execute_si(
+        &StablecoinInstruction::Transfer {
+            to: accomplice,
+            amount: 600,
+            asset: AssetType::LUSD,
+        },
+        &attacker,
+        &mut ctx,
+    )
+    .unwrap();
+    end_block(&mut ctx);
+
+    let acct = &ctx.state.accounts[&attacker];
+    assert_eq!(acct.lusd_balance, 0);
+    assert_eq!(acct.pending_flash_mint, 0);
+    assert_eq!(acct.pending_flash_collateral, 0);
+    assert_eq!(ctx.state.pending_flash_mints, 0);
+    // 400 burned; the 600 that escaped stays backed by the forfeited collateral.
+    assert_eq!(ctx.state.total_lusd_supply, 1_000_600);
+    assert_eq!(ctx.state.stabilization_pool_balance, 1_001_200);
+    assert!(ctx.state.events.contains(&ChainEvent::FlashMintClawedBack {
+        height: 11,
+        account: attacker,
+        burned: 400,
+        unrecovered: 600,
+        collateral_seized: 1_200,
+    }));
+}
+
+#[test]
+fn test_flash_burn_rejects_partial_repayment_fail_closed() {
     let mut state = GlobalState::default();
     let sender = [21u8; 32];
-    let receiver = [22u8; 32];
 
-    let mut sender_state = AccountState::default();
-    sender_state.custom_balances.insert("BTC".to_string(), 25);
-    state.accounts.insert(sender, sender_state);
+    state.stabilization_pool_balance = 1_000_000;
+    state.total_lusd_supply = 1_000_000;
 
     {
         let mut ctx = ExecutionContext {
             state: &mut state,
-            height: 1,
-            timestamp: 100,
+            height: 42,
+            timestamp: 1,
         };
-        let si = StablecoinInstruction::Transfer {
-            to: receiver,
-            amount: 10,
-            asset: lumina_types::instruction::AssetType::Custom("BTC".to_string()),
+        let flash = StablecoinInstruction::FlashMint {
+            amount: 1_000,
+            collateral_asset: lumina_types::instruction::AssetType::Lumina,
+            collateral_amount: 1_100,
+            commitment: [1u8; 32],
         };
-        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
+        execute_si(&flash, &sender, &mut ctx).unwrap();
     }
 
-    assert_eq!(
-        state
-            .accounts
-            .get(&sender)
-            .and_then(|a| a.custom_balances.get("BTC"))
-            .copied(),
-        Some(15)
-    );
-    assert_eq!(
-        state
-            .accounts
-            .get(&receiver)
-            .and_then(|a| a.custom_balances.get("BTC"))
-            .copied(),
-        Some(10)
-    );
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 42,
+        timestamp: 2,
+    };
+    let partial_burn = StablecoinInstruction::FlashBurn { amount: 999 };
+    let err = execute_si(&partial_burn, &sender, &mut ctx).unwrap_err();
+
+    assert!(err
+        .to_string()
+        .contains("Flash burn must burn full pending flash mint in this block"));
+}
+
+#[test]
+fn test_flash_burn_rejects_when_balance_missing_fail_closed() {
+    let mut state = GlobalState::default();
+    let sender = [22u8; 32];
+
+    state.stabilization_pool_balance = 1_000_000;
+    state.total_lusd_supply = 1_000_000;
 
     {
         let mut ctx = ExecutionContext {
             state: &mut state,
-            height: 2,
-            timestamp: 200,
+            height: 7,
+            timestamp: 1,
         };
-        let burn = StablecoinInstruction::Burn {
-            amount: 5,
-            asset: lumina_types::instruction::AssetType::Custom("BTC".to_string()),
+        let flash = StablecoinInstruction::FlashMint {
+            amount: 500,
+            collateral_asset: lumina_types::instruction::AssetType::Lumina,
+            collateral_amount: 550,
+            commitment: [2u8; 32],
         };
-        assert!(execute_si(&burn, &sender, &mut ctx).is_ok());
+        execute_si(&flash, &sender, &mut ctx).unwrap();
+
+        // Spend 1 LUSD so the wallet can no longer close the full flash position.
+        let drain = StablecoinInstruction::Transfer {
+            to: [99u8; 32],
+            amount: 1,
+            asset: lumina_types::instruction::AssetType::LUSD,
+        };
+        execute_si(&drain, &sender, &mut ctx).unwrap();
     }
 
-    assert_eq!(
-        state
-            .accounts
-            .get(&sender)
-            .and_then(|a| a.custom_balances.get("BTC"))
-            .copied(),
-        Some(10)
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 7,
+        timestamp: 2,
+    };
+    let burn = StablecoinInstruction::FlashBurn { amount: 500 };
+    let err = execute_si(&burn, &sender, &mut ctx).unwrap_err();
+
+    assert!(err.to_string().contains("Insufficient LUSD for flash burn"));
+}
+
+#[test]
+fn test_instant_redeem_queues_under_stress() {
+    let mut state = GlobalState::default();
+    let sender = [13u8; 32];
+
+    state.accounts.insert(
+        sender,
+        AccountState {
+            lusd_balance: 5000,
+            ..Default::default()
+        },
     );
+    state.total_lusd_supply = 5000;
+    state.reserve_ratio = 0.90;
+    state.stabilization_pool_balance = 4500;
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let redeem = StablecoinInstruction::InstantRedeem {
+        amount: 1000,
+        destination: [0u8; 32],
+    };
+    execute_si(&redeem, &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.fair_redeem_queue.len(), 1);
+    assert_eq!(ctx.state.accounts.get(&sender).unwrap().lusd_balance, 4000);
+}
+
+#[test]
+fn test_mint_with_credit_score_allowlist_and_replay_protection() {
+    let mut state = GlobalState::default();
+    let sender = [14u8; 32];
+    let oracle = [7u8; 32];
+    state.trusted_credit_oracles.push(oracle);
+
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let raw = manager.prove_range(500, 1000);
+    let proof = bound_proof(*blake3::hash(b"credit-score").as_bytes(), raw);
+
+    // Derive the score the execution will compute so we can set a threshold it passes.
+    let score_bytes = blake3::hash(&proof);
+    let raw_score = u16::from_le_bytes([score_bytes.as_bytes()[0], score_bytes.as_bytes()[1]]);
+    let score = 300 + (raw_score % 551);
+    let threshold = score.saturating_sub(1);
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+
+    let mint = StablecoinInstruction::MintWithCreditScore {
+        amount: 1000,
+        collateral_amount: 1200,
+        credit_score_proof: proof.clone(),
+        min_score_threshold: threshold,
+        oracle,
+    };
+    execute_si(&mint, &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts.get(&sender).unwrap().lusd_balance, 1000);
+
+    // Replaying the same proof is rejected rather than minted against unpriced collateral.
+    let mut ctx2 = ExecutionContext {
+        state: &mut state,
+        height: 2,
+        timestamp: 200,
+    };
+    assert_eq!(
+        execute_si(&mint, &sender, &mut ctx2)
+            .unwrap_err()
+            .to_string(),
+        "Credit score proof not accepted; use MintSenior"
+    );
+    assert_eq!(ctx2.state.accounts.get(&sender).unwrap().lusd_balance, 1000);
+}
+
+#[test]
+fn test_native_credit_score_prices_mints_and_decays() {
+    let history = CreditHistory {
+        repayments: 30,
+        redemptions_honored: 25,
+        last_epoch: 2,
+        ..Default::default()
+    };
+    assert_eq!(history.score(2), 850);
+    assert_eq!(history.score(22), 750);
+    assert_eq!(history.score(1_000), 300);
+    let liquidated = CreditHistory {
+        liquidations: 2,
+        ..history.clone()
+    };
+    assert_eq!(liquidated.score(2), 650);
+
+    let mut state = GlobalState::default();
+    let (borrower, stranger) = ([98u8; 32], [99u8; 32]);
+    state.credit_histories.insert(borrower, history);
+    state.current_epoch = 2;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let mint = |min_score_threshold| StablecoinInstruction::MintWithNativeCreditScore {
+        amount: 1_000,
+        collateral_amount: 1_020,
+        min_score_threshold,
+    };
+    execute_si(&mint(800), &borrower, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&borrower].lusd_balance, 1_000);
+    assert_eq!(ctx.state.total_lusd_supply, 1_000);
+
+    // Without history, or below the threshold, the mint is rejected.
+    assert_eq!(
+        execute_si(&mint(0), &stranger, &mut ctx)
+            .unwrap_err()
+            .to_string(),
+        "No credit score at the threshold; use MintSenior"
+    );
+    assert!(execute_si(&mint(851), &borrower, &mut ctx).is_err());
+
+    // Twenty idle epochs later the score has decayed into a costlier band.
+    ctx.state.current_epoch = 22;
+    let err = execute_si(&mint(700), &borrower, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Collateral too low for scored mint: need >= 1050"
+    );
+    assert_eq!(ctx.state.accounts[&borrower].lusd_balance, 1_000);
+}
+
+#[test]
+fn test_credit_line_guarantors_earn_interest_and_cover_default() {
+    let mut state = GlobalState::default();
+    let (borrower, g1, g2, keeper) = ([91u8; 32], [92u8; 32], [93u8; 32], [94u8; 32]);
+    state.total_ljun_shares = 10_000;
+    state.total_ljun_supply = 10_000;
+    state.stabilization_pool_balance = 100_000;
+    state.accounts.entry(g1).or_default().ljun_balance = 6_000;
+    state.accounts.entry(g2).or_default().ljun_balance = 4_000;
+    let maturity = 1 + CREDIT_LINE_YEAR_BLOCKS;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+    let open = StablecoinInstruction::OpenCreditLine {
+        limit: 10_000,
+        interest_bps: 1_000,
+        maturity_height: maturity,
+    };
+    execute_si(&open, &borrower, &mut ctx).unwrap();
+    let guarantee = |shares| StablecoinInstruction::GuaranteeCreditLine { line_id: 0, shares };
+    assert!(execute_si(&guarantee(1), &borrower, &mut ctx).is_err());
+    execute_si(&guarantee(6_000), &g1, &mut ctx).unwrap();
+    execute_si(&guarantee(2_000), &g2, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&g2].ljun_balance, 2_000);
+
+    let draw = |amount| StablecoinInstruction::DrawCreditLine { line_id: 0, amount };
+    let err = execute_si(&draw(9_000), &borrower, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Draw exceeds guarantee");
+    execute_si(&draw(6_000), &borrower, &mut ctx).unwrap();
+    let err = execute_si(&draw(5_000), &borrower, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Draw exceeds credit limit");
+    assert_eq!(ctx.state.total_lusd_supply, 6_000);
+    let release = StablecoinInstruction::ReleaseGuarantee {
+        line_id: 0,
+        shares: 6_000,
+    };
+    let err = execute_si(&release, &g1, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Guarantee still backs the line's debt");
+
+    // Half a year on, 300 LUSD of interest is split 3:1 between guarantors.
+    ctx.height = 1 + CREDIT_LINE_YEAR_BLOCKS / 2;
+    let default = StablecoinInstruction::DeclareCreditDefault { line_id: 0 };
+    let err = execute_si(&default, &keeper, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Credit line is not in default");
+    let repay = StablecoinInstruction::RepayCreditLine {
+        line_id: 0,
+        amount: 1_300,
+    };
+    execute_si(&repay, &borrower, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&borrower].lusd_balance, 4_700);
+    assert_eq!(ctx.state.accounts[&g1].lusd_balance, 225);
+    assert_eq!(ctx.state.accounts[&g2].lusd_balance, 75);
+    assert_eq!(ctx.state.credit_lines[&0].principal, 5_000);
+    assert_eq!(ctx.state.total_lusd_supply, 5_000);
+    assert_eq!(ctx.state.credit_histories[&borrower].repayments, 1);
+
+    // At maturity the unpaid principal is slashed from guarantors pro rata.
+    ctx.height = maturity;
+    assert!(execute_si(&draw(1), &borrower, &mut ctx).is_err());
+    execute_si(&default, &keeper, &mut ctx).unwrap();
+    let line = &ctx.state.credit_lines[&0];
+    assert!(line.defaulted);
+    assert_eq!(line.debt(), 0);
+    assert_eq!(line.guarantors[&g1], 2_250);
+    assert_eq!(line.guarantors[&g2], 750);
+    assert_eq!(ctx.state.stabilization_pool_balance, 105_000);
+    assert_eq!(ctx.state.total_ljun_shares, 5_000);
+    assert_eq!(ctx.state.total_ljun_supply, 5_000);
+    assert_eq!(ctx.state.credit_histories[&borrower].liquidations, 1);
+    assert!(matches!(
+        ctx.state.events.last(),
+        Some(ChainEvent::CreditLineDefaulted {
+            slashed: 5_000,
+            shortfall: 0,
+            ..
+        })
+    ));
+
+    // Guarantors withdraw what is left, and the settled line is dropped.
+    let release = |shares| StablecoinInstruction::ReleaseGuarantee { line_id: 0, shares };
+    execute_si(&release(2_250), &g1, &mut ctx).unwrap();
+    execute_si(&release(750), &g2, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&g1].ljun_balance, 2_250);
+    assert_eq!(ctx.state.accounts[&g2].ljun_balance, 2_750);
+    assert!(ctx.state.credit_lines.is_empty());
+}
+
+#[test]
+fn test_batch_disburse_is_all_or_nothing() {
+    let mut state = GlobalState::default();
+    let (employer, alice, bob) = ([81u8; 32], [82u8; 32], [83u8; 32]);
+    state.accounts.entry(employer).or_default().lusd_balance = 1_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 3,
+        timestamp: 0,
+    };
+    let disburse =
+        |payments: Vec<([u8; 32], u64)>| StablecoinInstruction::BatchDisburse { payments };
+    let err = execute_si(
+        &disburse(vec![(alice, 600), (bob, 401)]),
+        &employer,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Insufficient LUSD");
+    assert!(execute_si(&disburse(vec![(alice, 1), (bob, 0)]), &employer, &mut ctx).is_err());
+    assert!(execute_si(&disburse(vec![(employer, 1)]), &employer, &mut ctx).is_err());
+    assert!(execute_si(&disburse(Vec::new()), &employer, &mut ctx).is_err());
+    assert_eq!(ctx.state.accounts[&employer].lusd_balance, 1_000);
+    assert!(ctx.state.events.is_empty());
+
+    let payments = vec![(alice, 300), (bob, 200), (alice, 100)];
+    execute_si(&disburse(payments), &employer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&employer].lusd_balance, 400);
+    assert_eq!(ctx.state.accounts[&employer].epoch_tx_volume, 600);
+    assert_eq!(ctx.state.accounts[&alice].lusd_balance, 400);
+    assert_eq!(ctx.state.accounts[&bob].lusd_balance, 200);
+    assert_eq!(ctx.state.events.len(), 3);
+    assert_eq!(
+        ctx.state.events[2],
+        ChainEvent::Disbursed {
+            height: 3,
+            from: employer,
+            to: alice,
+            amount: 100,
+        }
+    );
+}
+
+#[test]
+fn test_merchant_payments_net_into_one_settlement_per_epoch() {
+    let mut state = GlobalState::default();
+    let (merchant, treasury, buyer) = ([84u8; 32], [85u8; 32], [86u8; 32]);
+    state.accounts.entry(buyer).or_default().lusd_balance = 1_000;
+    state.total_lusd_supply = 1_000;
+    state.stabilization_pool_balance = 1_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: EPOCH_LENGTH - 1,
+        timestamp: 0,
+    };
+    let register = StablecoinInstruction::RegisterMerchant { treasury };
+    execute_si(&register, &merchant, &mut ctx).unwrap();
+    let err = execute_si(&register, &merchant, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Merchant already settles to this treasury");
+
+    let pay = |amount| StablecoinInstruction::Transfer {
+        to: merchant,
+        amount,
+        asset: AssetType::LUSD,
+    };
+    execute_si(&pay(100), &buyer, &mut ctx).unwrap();
+    let batch = StablecoinInstruction::BatchDisburse {
+        payments: vec![(merchant, 50), (merchant, 25)],
+    };
+    execute_si(&batch, &buyer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.merchants[&merchant].pending, 175);
+    assert_eq!(ctx.state.merchants[&merchant].payments, 3);
+    assert!(!ctx.state.accounts.contains_key(&merchant));
+
+    // The epoch boundary pays the net amount to the treasury at once.
+    ctx.height = EPOCH_LENGTH;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.accounts[&treasury].lusd_balance, 175);
+    assert_eq!(
+        ctx.state.merchants[&merchant],
+        MerchantAccount {
+            treasury,
+            pending: 0,
+            payments: 0,
+        }
+    );
+    assert!(ctx.state.events.contains(&ChainEvent::MerchantSettled {
+        height: EPOCH_LENGTH,
+        epoch: 0,
+        merchant,
+        treasury,
+        amount: 175,
+        payments: 3,
+    }));
+
+    // Deregistering settles early, and later payments land directly.
+    execute_si(&pay(10), &buyer, &mut ctx).unwrap();
+    execute_si(
+        &StablecoinInstruction::DeregisterMerchant,
+        &merchant,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&treasury].lusd_balance, 185);
+    assert!(ctx.state.merchants.is_empty());
+    execute_si(&pay(5), &buyer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&merchant].lusd_balance, 5);
+    assert_eq!(ctx.state.accounts[&buyer].lusd_balance, 810);
+}
+
+#[test]
+fn test_transfer_with_memo_tags_the_payment() {
+    let mut state = GlobalState::default();
+    let (payer, shop) = ([87u8; 32], [88u8; 32]);
+    state.accounts.entry(payer).or_default().lusd_balance = 100;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 5,
+        timestamp: 0,
+    };
+    let memo_hash = lumina_types::payment::memo_hash("Order 42");
+    let pay = |amount| StablecoinInstruction::TransferWithMemo {
+        to: shop,
+        amount,
+        asset: AssetType::LUSD,
+        memo_hash,
+    };
+    assert!(execute_si(&pay(101), &payer, &mut ctx).is_err());
+    let plain = StablecoinInstruction::Transfer {
+        to: shop,
+        amount: 10,
+        asset: AssetType::LUSD,
+    };
+    execute_si(&plain, &payer, &mut ctx).unwrap();
+    assert!(ctx.state.events.is_empty());
+    execute_si(&pay(60), &payer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&shop].lusd_balance, 70);
+    assert_eq!(
+        ctx.state.events,
+        vec![ChainEvent::MemoPayment {
+            height: 5,
+            from: payer,
+            to: shop,
+            amount: 60,
+            asset: AssetType::LUSD,
+            memo_hash,
+        }]
+    );
+}
+
+#[test]
+fn test_dust_accounts_are_reaped_after_the_grace_period() {
+    let mut state = GlobalState::default();
+    let (payer, kp) = new_sender();
+    let (dust, topped, rich, validator) = ([89u8; 32], [90u8; 32], [91u8; 32], [92u8; 32]);
+    state.accounts.entry(payer).or_default().lusd_balance = 1_000;
+    state.total_lusd_supply = 1_000;
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+    let propose = |existential_deposit, grace_blocks| StablecoinInstruction::ProposeDustPolicy {
+        policy: DustPolicy {
+            existential_deposit,
+            grace_blocks,
+        },
+    };
+    assert!(execute_si(&propose(100, 10), &payer, &mut ctx).is_err());
+    let err = execute_si(&propose(100, 0), &validator, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Grace period must be at least one block");
+    ctx.state.dust_policy = DustPolicy {
+        existential_deposit: 100,
+        grace_blocks: 10,
+    };
+
+    let mut nonce = 0;
+    let mut pay = |ctx: &mut ExecutionContext, to, amount| {
+        let mut tx = Transaction {
+            sender: payer,
+            nonce,
+            instruction: StablecoinInstruction::Transfer {
+                to,
+                amount,
+                asset: AssetType::LUSD,
+            },
+            signature: Vec::new(),
+            gas_limit: 1000,
+            gas_price: 1,
+        };
+        tx.signature = lumina_crypto::signatures::sign(&kp, &tx.signing_bytes());
+        nonce += 1;
+        execute_transaction(&tx, ctx).unwrap();
+    };
+    pay(&mut ctx, dust, 5);
+    pay(&mut ctx, topped, 5);
+    pay(&mut ctx, rich, 500);
+    assert_eq!(
+        ctx.state.dust_accounts,
+        BTreeMap::from([(dust, 11), (topped, 11)])
+    );
+    ctx.height = 2;
+    pay(&mut ctx, topped, 200);
+    assert_eq!(ctx.state.dust_reaps[&11].len(), 2);
+
+    ctx.height = 10;
+    end_block(&mut ctx);
+    assert!(ctx.state.accounts.contains_key(&dust));
+    ctx.height = 11;
+    end_block(&mut ctx);
+    assert!(!ctx.state.accounts.contains_key(&dust));
+    assert_eq!(ctx.state.accounts[&topped].lusd_balance, 205);
+    assert!(ctx.state.dust_accounts.is_empty() && ctx.state.dust_reaps.is_empty());
+    assert_eq!(ctx.state.insurance_fund_balance, 5);
+    assert_eq!(ctx.state.total_lusd_supply, 995);
+    assert!(ctx.state.events.contains(&ChainEvent::AccountReaped {
+        height: 11,
+        account: dust,
+        swept: 5,
+    }));
+}
+
+#[test]
+fn test_inactive_accounts_are_archived_and_revived_with_a_proof() {
+    use lumina_types::archive::{decode_account, encode_account, ArchiveTree};
+
+    let mut state = GlobalState::default();
+    let (owner, kp) = new_sender();
+    let (keeper, active) = ([93u8; 32], [94u8; 32]);
+    state.accounts.insert(
+        owner,
+        AccountState {
+            nonce: 3,
+            lusd_balance: 500,
+            lumina_balance: 20,
+            ..Default::default()
+        },
+    );
+    state.accounts.entry(active).or_default().lusd_balance = 100;
+    state.accounts.entry(keeper).or_default().lusd_balance = 100;
+    state.archive_policy = ArchivePolicy {
+        inactivity_epochs: 2,
+        enabled_epoch: 0,
+    };
+    state.archive_activity.insert(active, 3);
+    state.current_epoch = 4;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 50,
+        timestamp: 0,
+    };
+    let archive = |accounts| StablecoinInstruction::ArchiveAccounts { accounts };
+    let err = execute_si(&archive(vec![owner, active]), &keeper, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Account 1 of the batch cannot be archived");
+    execute_si(&archive(vec![owner]), &keeper, &mut ctx).unwrap();
+    assert!(!ctx.state.accounts.contains_key(&owner));
+    assert_eq!(ctx.state.archived_accounts[&owner], 0);
+    let Some(ChainEvent::AccountsArchived {
+        root,
+        accounts: leaves,
+        ..
+    }) = ctx.state.events.last().cloned()
+    else {
+        panic!("no AccountsArchived event");
+    };
+    assert_eq!(ctx.state.archive_batches[&0].root, root);
+
+    // Paid while archived, and unable to sign anything but its revival.
+    let pay = |to, amount| StablecoinInstruction::Transfer {
+        to,
+        amount,
+        asset: AssetType::LUSD,
+    };
+    execute_si(&pay(owner, 7), &keeper, &mut ctx).unwrap();
+    let signed = |instruction| {
+        let mut tx = Transaction {
+            sender: owner,
+            nonce: 3,
+            instruction,
+            signature: Vec::new(),
+            gas_limit: 1000,
+            gas_price: 1,
+        };
+        tx.signature = lumina_crypto::signatures::sign(&kp, &tx.signing_bytes());
+        tx
+    };
+    let err = execute_transaction(&signed(pay(keeper, 1)), &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Account is archived; revive it first");
+
+    let proof = ArchiveTree::new(&leaves).prove(0).unwrap();
+    let revive = |account| StablecoinInstruction::ReviveAccount {
+        address: owner,
+        account,
+        proof: proof.clone(),
+    };
+    let mut forged = decode_account(&leaves[0].1).unwrap();
+    forged.lusd_balance = 10_000;
+    let err = execute_si(&revive(encode_account(&forged)), &keeper, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid archive proof");
+    execute_transaction(&signed(revive(leaves[0].1.clone())), &mut ctx).unwrap();
+    let revived = &ctx.state.accounts[&owner];
+    assert_eq!(
+        (revived.nonce, revived.lusd_balance, revived.lumina_balance),
+        (4, 507, 20)
+    );
+    assert!(ctx.state.archived_accounts.is_empty());
+    assert_eq!(ctx.state.archive_activity[&owner], 4);
+    assert!(execute_si(&revive(leaves[0].1.clone()), &keeper, &mut ctx).is_err());
+}
+
+#[test]
+fn test_credit_oracles_are_trusted_by_governance() {
+    let mut state = GlobalState::default();
+    let (validator, oracle) = ([96u8; 32], [97u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 4,
+        timestamp: 0,
+    };
+    let propose = |member| StablecoinInstruction::ProposeCreditOracle { oracle, member };
+    assert!(execute_si(&propose(true), &oracle, &mut ctx).is_err());
+    let err = execute_si(&propose(false), &validator, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Credit oracle membership unchanged");
+
+    execute_si(&propose(true), &validator, &mut ctx).unwrap();
+    assert_eq!(ctx.state.trusted_credit_oracles, vec![oracle]);
+    assert!(matches!(
+        ctx.state.events.last(),
+        Some(ChainEvent::CreditOracleChanged { member: true, .. })
+    ));
+
+    execute_si(&propose(false), &validator, &mut ctx).unwrap();
+    assert!(ctx.state.trusted_credit_oracles.is_empty());
+    assert!(matches!(
+        ctx.state.events.last(),
+        Some(ChainEvent::CreditOracleChanged { member: false, .. })
+    ));
+}
+
+#[test]
+fn test_rwa_listing_and_pledge() {
+    let mut state = GlobalState::default();
+    let sender = [15u8; 32];
+
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let raw = manager.prove_range(1, 10);
+    let attested_value = 10_000u64;
+    let proof = bound_proof(*blake3::hash(&attested_value.to_le_bytes()).as_bytes(), raw);
+
+    // List RWA
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 1,
+            timestamp: 100,
+        };
+        let list = StablecoinInstruction::ListRWA {
+            asset_description: "invoice #123".to_string(),
+            attested_value,
+            attestation_proof: proof,
+            maturity_date: Some(1_000_000),
+            collateral_eligibility: true,
+        };
+        execute_si(&list, &sender, &mut ctx).unwrap();
+        assert_eq!(ctx.state.rwa_listings.len(), 1);
+        assert_eq!(ctx.state.next_rwa_id, 1);
+    }
+
+    // Pledge against it
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 2,
+            timestamp: 200,
+        };
+        let pledge = StablecoinInstruction::UseRWAAsCollateral {
+            rwa_id: 0,
+            amount_to_pledge: 2500,
+        };
+        execute_si(&pledge, &sender, &mut ctx).unwrap();
+        assert_eq!(ctx.state.accounts.get(&sender).unwrap().lusd_balance, 2500);
+        assert_eq!(ctx.state.rwa_listings.get(&0).unwrap().pledged_amount, 2500);
+    }
+}
+
+#[test]
+fn test_submit_zk_por_requires_valid_proof_and_no_replay() {
+    let mut state = GlobalState::default();
+    let sender = [12u8; 32];
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let proof = manager.prove_reserves(vec![40, 60], 100);
+    state.total_lusd_supply = 80;
+
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 1,
+            timestamp: 100,
+        };
+        let si = StablecoinInstruction::SubmitZkPoR {
+            proof: proof.clone(),
+            total_reserves: 100,
+            timestamp: 1,
+        };
+        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
+    }
+    let attestation = &state.por_history[&1];
+    assert_eq!(attestation.height, 1);
+    assert_eq!(attestation.total_reserves, 100);
+    assert_eq!(attestation.proof_hash, *blake3::hash(&proof).as_bytes());
+    assert_eq!(
+        attestation.verifying_key_hash,
+        manager.por_verifying_key_hash()
+    );
+    assert_eq!(attestation.lusd_supply, 80);
+
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 2,
+            timestamp: 200,
+        };
+        let replay = StablecoinInstruction::SubmitZkPoR {
+            proof: proof.clone(),
+            total_reserves: 100,
+            timestamp: 2,
+        };
+        assert!(execute_si(&replay, &sender, &mut ctx).is_err());
+    }
+    assert_eq!(state.por_history.len(), 1);
+}
+
+#[test]
+fn test_zero_slip_batch_match_blocks_duplicates_and_replay() {
+    let mut state = GlobalState::default();
+    let sender = [13u8; 32];
+
+    let orders = vec![[1u8; 32], [2u8; 32], [3u8; 32]];
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 1,
+            timestamp: 100,
+        };
+        let si = StablecoinInstruction::ZeroSlipBatchMatch {
+            orders: orders.clone(),
+        };
+        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
+    }
+
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 2,
+            timestamp: 200,
+        };
+        let replay = StablecoinInstruction::ZeroSlipBatchMatch {
+            orders: orders.clone(),
+        };
+        assert!(execute_si(&replay, &sender, &mut ctx).is_err());
+    }
+
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 3,
+            timestamp: 300,
+        };
+        let dup = StablecoinInstruction::ZeroSlipBatchMatch {
+            orders: vec![[9u8; 32], [9u8; 32]],
+        };
+        assert!(execute_si(&dup, &sender, &mut ctx).is_err());
+    }
+}
+
+#[test]
+fn test_custom_asset_transfer_and_burn() {
+    let mut state = GlobalState::default();
+    let sender = [21u8; 32];
+    let receiver = [22u8; 32];
+
+    let mut sender_state = AccountState::default();
+    sender_state
+        .custom_balances
+        .insert("BTC".to_string(), 25.into());
+    state.accounts.insert(sender, sender_state);
+
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 1,
+            timestamp: 100,
+        };
+        let si = StablecoinInstruction::Transfer {
+            to: receiver,
+            amount: 10,
+            asset: lumina_types::instruction::AssetType::Custom("BTC".to_string()),
+        };
+        assert!(execute_si(&si, &sender, &mut ctx).is_ok());
+    }
+
+    assert_eq!(
+        state
+            .accounts
+            .get(&sender)
+            .and_then(|a| a.custom_balances.get("BTC"))
+            .copied(),
+        Some(15.into())
+    );
+    assert_eq!(
+        state
+            .accounts
+            .get(&receiver)
+            .and_then(|a| a.custom_balances.get("BTC"))
+            .copied(),
+        Some(10.into())
+    );
+
+    {
+        let mut ctx = ExecutionContext {
+            state: &mut state,
+            height: 2,
+            timestamp: 200,
+        };
+        let burn = StablecoinInstruction::Burn {
+            amount: 5,
+            asset: lumina_types::instruction::AssetType::Custom("BTC".to_string()),
+        };
+        assert!(execute_si(&burn, &sender, &mut ctx).is_ok());
+    }
+
+    assert_eq!(
+        state
+            .accounts
+            .get(&sender)
+            .and_then(|a| a.custom_balances.get("BTC"))
+            .copied(),
+        Some(10.into())
+    );
+}
+
+#[test]
+fn test_custom_asset_balances_grow_past_u64() {
+    use lumina_types::balance::Balance;
+
+    let mut state = GlobalState::default();
+    let sender = [23u8; 32];
+    let receiver = [24u8; 32];
+    let whale = [25u8; 32];
+    let balances = |units: u128| {
+        let mut account = AccountState::default();
+        account
+            .custom_balances
+            .insert("WETH".to_string(), Balance::new(units));
+        account
+    };
+    state.accounts.insert(sender, balances(1_000));
+    state.accounts.insert(receiver, balances(u64::MAX as u128));
+    state.accounts.insert(whale, balances(u128::MAX - 5));
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let transfer = |to| StablecoinInstruction::Transfer {
+        to,
+        amount: 10,
+        asset: AssetType::Custom("WETH".to_string()),
+    };
+
+    execute_si(&transfer(receiver), &sender, &mut ctx).unwrap();
+    let held = ctx.state.accounts[&receiver].custom_balances["WETH"];
+    assert_eq!(held.get(), u64::MAX as u128 + 10);
+
+    let err = execute_si(&transfer(whale), &sender, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Balance overflow");
+}
+
+#[test]
+fn test_geo_rebalance_allocates_reserves_by_zone_weight() {
+    let mut state = GlobalState::default();
+    let a = [41u8; 32];
+    let b = [42u8; 32];
+    let c = [43u8; 32];
+    for (who, stake) in [(a, 100), (b, 100), (c, 300)] {
+        state.accounts.entry(who).or_default().ljun_balance = stake;
+    }
+    state.stabilization_pool_balance = 1_000;
+    state.params.zone_reserve_weights.insert(1, 1);
+    state.params.zone_reserve_weights.insert(2, 3);
+    state.params.zone_reserve_weights.insert(3, 5); // no custodians: skipped
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 5,
+        timestamp: 1,
+    };
+    let set_zone = |zone_id| StablecoinInstruction::SetCustodianZone { zone_id };
+    assert!(execute_si(&set_zone(1), &a, &mut ctx).is_err());
+    for (who, stake, zone_id) in [(a, 100, 1), (b, 100, 2), (c, 300, 2)] {
+        execute_si(
+            &StablecoinInstruction::RegisterCustodian {
+                stake,
+                mpc_pubkeys: vec![who],
+            },
+            &who,
+            &mut ctx,
+        )
+        .unwrap();
+        execute_si(&set_zone(zone_id), &who, &mut ctx).unwrap();
+    }
+    assert!(execute_si(&set_zone(0), &a, &mut ctx).is_err());
+
+    assert!(execute_si(
+        &StablecoinInstruction::GeoRebalance { zone_id: 9 },
+        &a,
+        &mut ctx
+    )
+    .is_err());
+    execute_si(
+        &StablecoinInstruction::GeoRebalance { zone_id: 1 },
+        &a,
+        &mut ctx,
+    )
+    .unwrap();
+
+    let balances: Vec<u64> = ctx
+        .state
+        .custodians
+        .iter()
+        .map(|c| c.reserve_balance)
+        .collect();
+    assert_eq!(balances, vec![250, 188, 562]);
+    assert_eq!(
+        ctx.state.events,
+        vec![
+            ChainEvent::ReserveMovementRequired {
+                height: 5,
+                from_zone: 0,
+                to_zone: 1,
+                amount: 250,
+            },
+            ChainEvent::ReserveMovementRequired {
+                height: 5,
+                from_zone: 0,
+                to_zone: 2,
+                amount: 750,
+            },
+        ]
+    );
+
+    // Re-weighting to 1:1 moves reserves from zone 2 to zone 1.
+    ctx.state.events.clear();
+    ctx.state.params.zone_reserve_weights.insert(2, 1);
+    execute_si(
+        &StablecoinInstruction::GeoRebalance { zone_id: 2 },
+        &b,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(
+        ctx.state.events,
+        vec![ChainEvent::ReserveMovementRequired {
+            height: 5,
+            from_zone: 2,
+            to_zone: 1,
+            amount: 250,
+        }]
+    );
+    let balances: Vec<u64> = ctx
+        .state
+        .custodians
+        .iter()
+        .map(|c| c.reserve_balance)
+        .collect();
+    assert_eq!(balances, vec![500, 125, 375]);
+}
+
+#[test]
+fn test_por_seeds_custodian_reserve_balances() {
+    let mut state = GlobalState::default();
+    let (a, b) = ([44u8; 32], [45u8; 32]);
+    for who in [a, b] {
+        state.accounts.entry(who).or_default().ljun_balance = 100;
+    }
+    state.params.zone_reserve_weights.insert(1, 1);
+    state.params.zone_reserve_weights.insert(2, 3);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 5,
+        timestamp: 1,
+    };
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let por = |total_reserves, timestamp| StablecoinInstruction::SubmitZkPoR {
+        proof: manager.prove_reserves(vec![total_reserves], total_reserves),
+        total_reserves,
+        timestamp,
+    };
+
+    // Custodians without a zone hold nothing yet
+    for who in [a, b] {
+        execute_si(
+            &StablecoinInstruction::RegisterCustodian {
+                stake: 100,
+                mpc_pubkeys: vec![who],
+            },
+            &who,
+            &mut ctx,
+        )
+        .unwrap();
+    }
+    execute_si(&por(800, 1), &a, &mut ctx).unwrap();
+    assert!(ctx.state.custodians.iter().all(|c| c.reserve_balance == 0));
+
+    // Once zoned, the next attestation spreads the reserves by zone weight
+    for (who, zone_id) in [(a, 1), (b, 2)] {
+        execute_si(
+            &StablecoinInstruction::SetCustodianZone { zone_id },
+            &who,
+            &mut ctx,
+        )
+        .unwrap();
+    }
+    execute_si(&por(1_000, 2), &a, &mut ctx).unwrap();
+    let balances: Vec<u64> = ctx
+        .state
+        .custodians
+        .iter()
+        .map(|c| c.reserve_balance)
+        .collect();
+    assert_eq!(balances, vec![250, 750]);
+}
+
+#[test]
+fn test_reserve_rotation_requires_both_mpc_thresholds() {
+    let source = [51u8; 32];
+    let destination = [52u8; 32];
+    let source_keys: Vec<_> = (0..3)
+        .map(|_| lumina_crypto::signatures::generate_keypair())
+        .collect();
+    let destination_key = lumina_crypto::signatures::generate_keypair();
+
+    let mut state = GlobalState::default();
+    for (pubkey, mpc_pubkeys, reserve_balance) in [
+        (
+            source,
+            source_keys
+                .iter()
+                .map(|k| k.verifying_key().to_bytes())
+                .collect::<Vec<_>>(),
+            1_000,
+        ),
+        (
+            destination,
+            vec![destination_key.verifying_key().to_bytes()],
+            0,
+        ),
+    ] {
+        state.custodians.push(CustodianState {
+            pubkey,
+            stake: 100,
+            mpc_pubkeys,
+            registered_height: 0,
+            zone_id: 1,
+            reserve_balance,
+        });
+    }
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 300_000,
+        timestamp: 1,
+    };
+    let propose = |amount| StablecoinInstruction::ProposeReserveRotation {
+        from_custodian: source,
+        to_custodian: destination,
+        amount,
+    };
+    assert!(execute_si(&propose(1_001), &source, &mut ctx).is_err());
+    assert!(execute_si(&propose(400), &[53u8; 32], &mut ctx).is_err());
+    execute_si(&propose(400), &source, &mut ctx).unwrap();
+    let payload = ctx.state.reserve_rotations[&0].signing_payload(0);
+
+    let approve = |key: &lumina_crypto::signatures::SigningKey, message: &[u8]| {
+        StablecoinInstruction::ApproveReserveRotation {
+            rotation_id: 0,
+            mpc_pubkey: key.verifying_key().to_bytes(),
+            signature: lumina_crypto::signatures::sign(key, message),
+        }
+    };
+    assert!(execute_si(&approve(&source_keys[0], b"wrong"), &source, &mut ctx).is_err());
+    execute_si(&approve(&source_keys[0], &payload), &source, &mut ctx).unwrap();
+    assert!(execute_si(&approve(&source_keys[0], &payload), &source, &mut ctx).is_err());
+    execute_si(&approve(&destination_key, &payload), &destination, &mut ctx).unwrap();
+    assert_eq!(ctx.state.custodians[0].reserve_balance, 1_000);
+
+    // Second source signature reaches 2-of-3; destination already at 1-of-1.
+    execute_si(&approve(&source_keys[1], &payload), &source, &mut ctx).unwrap();
+    assert!(ctx.state.reserve_rotations.is_empty());
+    assert_eq!(ctx.state.custodians[0].reserve_balance, 600);
+    assert_eq!(ctx.state.custodians[1].reserve_balance, 400);
+    assert_eq!(ctx.state.last_reserve_rotation_height, 300_000);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::ReserveRotationFinalized {
+            height: 300_000,
+            rotation_id: 0,
+            from_custodian: source,
+            to_custodian: destination,
+            amount: 400,
+        }));
+    let (sent, received) = (
+        &ctx.state.custodian_rotations[&source],
+        &ctx.state.custodian_rotations[&destination],
+    );
+    assert_eq!(
+        (sent.rotations_out, sent.rotated_out, sent.rotations_in),
+        (1, 400, 0)
+    );
+    assert_eq!((received.rotations_in, received.rotated_in), (1, 400));
+    assert_eq!(received.last_rotation_height, 300_000);
+
+    assert!(execute_si(&propose(100), &source, &mut ctx).is_err());
+
+    // A RotateReserves of the custodian set is held to the same interval
+    let rotate_set = StablecoinInstruction::RotateReserves {
+        new_custodian_set: vec![destination],
+    };
+    assert!(execute_si(&rotate_set, &source, &mut ctx).is_err());
+    ctx.height += 259_200;
+    execute_si(&rotate_set, &source, &mut ctx).unwrap();
+}
+
+#[test]
+fn test_validator_set_changes_apply_at_epoch_boundary() {
+    let mut state = GlobalState::default();
+    let operator = [61u8; 32];
+    state.accounts.entry(operator).or_default().lumina_balance = 1_000;
+    let genesis_hash = state.validator_set_hash();
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: EPOCH_LENGTH - 1,
+        timestamp: 1,
+    };
+    let register = StablecoinInstruction::RegisterValidator {
+        pubkey: operator,
+        stake: 600,
+    };
+    execute_si(&register, &operator, &mut ctx).unwrap();
+    assert!(execute_si(&register, &operator, &mut ctx).is_err());
+
+    // Staged only: no voting power in the block it joined.
+    assert!(ctx.state.validators.is_empty());
+    let vote = StablecoinInstruction::Vote {
+        proposal_id: 1,
+        approve: true,
+    };
+    assert!(execute_si(&vote, &operator, &mut ctx).is_err());
+    end_block(&mut ctx);
+    assert!(ctx.state.validators.is_empty());
+    assert_eq!(ctx.state.validator_set_hash(), genesis_hash);
+
+    ctx.height = EPOCH_LENGTH;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.current_epoch, 1);
+    assert_eq!(ctx.state.validators.len(), 1);
+    assert_eq!(ctx.state.validators[0].power, 600);
+    assert_ne!(ctx.state.validator_set_hash(), genesis_hash);
+    ctx.state.proposals.insert(
+        1,
+        GovernanceProposal {
+            proposer: operator,
+            action: ProposalAction::ScheduleUpgrade(UpgradePlan {
+                name: "v2".to_string(),
+                height: 10 * EPOCH_LENGTH,
+            }),
+            proposed_height: EPOCH_LENGTH,
+            votes: Default::default(),
+            executed: false,
+        },
+    );
+    execute_si(&vote, &operator, &mut ctx).unwrap();
+
+    // Removal is staged too; stake is returned when it takes effect.
+    ctx.height = EPOCH_LENGTH + 1;
+    execute_si(
+        &StablecoinInstruction::DeregisterValidator,
+        &operator,
+        &mut ctx,
+    )
+    .unwrap();
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.validators.len(), 1);
+    assert_eq!(ctx.state.accounts[&operator].lumina_balance, 400);
+
+    ctx.height = 2 * EPOCH_LENGTH;
+    end_block(&mut ctx);
+    assert!(ctx.state.validators.is_empty());
+    assert!(ctx.state.pending_validator_changes.is_empty());
+    assert_eq!(ctx.state.accounts[&operator].lumina_balance, 1_000);
+    assert_eq!(ctx.state.validator_set_hash(), genesis_hash);
+}
+
+fn green_proof(payload: &[u8]) -> Vec<u8> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"green-energy");
+    hasher.update(payload);
+    let mut proof = hasher.finalize().as_bytes().to_vec();
+    proof.extend_from_slice(payload);
+    proof
+}
+
+#[test]
+fn test_green_proof_expires_and_renews() {
+    let validator = [62u8; 32];
+    let mut state = GlobalState::default();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let validity = state.params.green_proof_validity_secs;
+
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: EPOCH_LENGTH - 1,
+        timestamp: 1_000,
+    };
+    execute_si(
+        &StablecoinInstruction::RegisterGreenValidator {
+            energy_proof: green_proof(b"q1"),
+        },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    ctx.height = EPOCH_LENGTH;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.validators[0].power, 200);
+    let fresh_health = ctx.state.health_index;
+
+    // Past the validity window: power decays to stake at the next epoch.
+    ctx.height = 2 * EPOCH_LENGTH;
+    ctx.timestamp = 1_000 + validity + 1;
+    end_block(&mut ctx);
+    assert!(!ctx.state.validators[0].is_green);
+    assert_eq!(ctx.state.validators[0].power, 100);
+    assert!(ctx.state.health_index < fresh_health);
+
+    let renew = |payload: &[u8]| StablecoinInstruction::RenewGreenProof {
+        energy_proof: green_proof(payload),
+    };
+    assert!(execute_si(&renew(b"q1"), &validator, &mut ctx).is_err());
+    execute_si(&renew(b"q2"), &validator, &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.validators[0].green_verified_at,
+        1_000 + validity + 1
+    );
+    assert_eq!(ctx.state.validators[0].power, 100);
+
+    ctx.height = 3 * EPOCH_LENGTH;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.validators[0].power, 200);
+    assert_eq!(ctx.state.health_index, fresh_health);
+}
+
+#[test]
+fn test_audit_actions_cover_privileged_ops_and_chain() {
+    use lumina_types::audit::{AuditAction, AuditRecord, Role};
+
+    let (sender, _kp) = new_sender();
+    let tx = |instruction| Transaction {
+        sender,
+        nonce: 0,
+        instruction,
+        signature: Vec::new(),
+        gas_limit: 0,
+        gas_price: 0,
+    };
+    let mut state = GlobalState::default();
+
+    let mint = tx(StablecoinInstruction::MintJunior {
+        amount: 50,
+        collateral_amount: 60,
+    });
+    assert_eq!(
+        audit_actions(Some(&mint), &[], false, &state),
+        vec![AuditAction::Mint {
+            asset: AssetType::LJUN,
+            amount: 50
+        }]
+    );
+    let transfer = tx(StablecoinInstruction::Transfer {
+        to: [2u8; 32],
+        amount: 5,
+        asset: AssetType::LUSD,
+    });
+    assert!(audit_actions(Some(&transfer), &[], false, &state).is_empty());
+    assert_eq!(
+        audit_actions(
+            Some(&tx(StablecoinInstruction::DeregisterValidator)),
+            &[],
+            false,
+            &state
+        ),
+        vec![AuditAction::RoleChanged {
+            role: Role::Validator,
+            granted: false
+        }]
+    );
+
+    // An explicit breaker call is audited even when it changes nothing;
+    // an automatic trip at end of block is audited as a protocol action.
+    let breaker = tx(StablecoinInstruction::RunCircuitBreaker { active: false });
+    assert_eq!(
+        audit_actions(Some(&breaker), &[], false, &state),
+        vec![AuditAction::CircuitBreaker { active: false }]
+    );
+    state.circuit_breaker_active = true;
+    let clawback = ChainEvent::FlashMintClawedBack {
+        height: 1,
+        account: sender,
+        burned: 7,
+        unrecovered: 0,
+        collateral_seized: 0,
+    };
+    let end_of_block = audit_actions(None, &[clawback], false, &state);
+    assert_eq!(
+        end_of_block,
+        vec![
+            AuditAction::Burn {
+                asset: AssetType::LUSD,
+                amount: 7
+            },
+            AuditAction::CircuitBreaker { active: true },
+        ]
+    );
+
+    let first = record_audit(
+        &mut state,
+        1,
+        Some(0),
+        sender,
+        vec![end_of_block[0].clone()],
+    );
+    let second = record_audit(
+        &mut state,
+        2,
+        None,
+        [0u8; 32],
+        vec![end_of_block[1].clone()],
+    );
+    assert_eq!(state.audit_seq, 2);
+    assert_eq!(second[0].prev_hash, first[0].hash);
+    assert_eq!(
+        AuditRecord::verify_chain([0u8; 32], &[first[0].clone(), second[0].clone()]),
+        Ok(state.audit_head)
+    );
+}
+
+#[test]
+fn test_state_upgrades_activate_at_their_height_once() {
+    fn raise_fee(state: &mut GlobalState) {
+        state.params.mint_fee_bps += 10;
+    }
+    fn seed_pool(state: &mut GlobalState) {
+        state.stabilization_pool_balance += 1_000;
+    }
+    let upgrades = [
+        StateUpgrade {
+            version: 1,
+            height: 10,
+            description: "raise mint fee",
+            apply: raise_fee,
+        },
+        StateUpgrade {
+            version: 2,
+            height: 20,
+            description: "seed stabilization pool",
+            apply: seed_pool,
+        },
+    ];
+    let mut state = GlobalState::default();
+    let base_fee = state.params.mint_fee_bps;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 9,
+        timestamp: 0,
+    };
+
+    apply_state_upgrades(&mut ctx, &upgrades);
+    assert_eq!(ctx.state.version, 0);
+
+    // A node catching up past both heights applies them in order.
+    ctx.height = 25;
+    apply_state_upgrades(&mut ctx, &upgrades);
+    apply_state_upgrades(&mut ctx, &upgrades);
+    assert_eq!(ctx.state.version, 2);
+    assert_eq!(ctx.state.params.mint_fee_bps, base_fee + 10);
+    assert_eq!(ctx.state.stabilization_pool_balance, 1_000);
+}
+
+#[test]
+fn test_upgrade_proposal_schedules_plan_after_supermajority() {
+    let mut state = GlobalState::default();
+    let validators = [[71u8; 32], [72u8; 32], [73u8; 32]];
+    for (pubkey, power) in validators.iter().zip([40u64, 20, 40]) {
+        state.validators.push(ValidatorState {
+            pubkey: *pubkey,
+            stake: power,
+            power,
+            is_green: false,
+            energy_proof: None,
+            green_verified_at: 0,
+        });
+    }
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 100,
+        timestamp: 0,
+    };
+    let propose = |name: &str, height| StablecoinInstruction::ProposeUpgrade {
+        name: name.to_string(),
+        height,
+    };
+    let upgrade_height = 100 + UPGRADE_MIN_NOTICE;
+    assert!(execute_si(&propose("v2", upgrade_height - 1), &validators[0], &mut ctx).is_err());
+    assert!(execute_si(&propose("v2", upgrade_height), &[9u8; 32], &mut ctx).is_err());
+    execute_si(&propose("v2", upgrade_height), &validators[0], &mut ctx).unwrap();
+
+    // The proposer's 40% plus another 20% is not a supermajority...
+    let vote = |approve| StablecoinInstruction::Vote {
+        proposal_id: 0,
+        approve,
+    };
+    execute_si(&vote(true), &validators[1], &mut ctx).unwrap();
+    assert!(ctx.state.upgrade_plans.is_empty());
+    execute_si(&vote(false), &validators[2], &mut ctx).unwrap();
+    assert!(ctx.state.upgrade_plans.is_empty());
+
+    // ...all three is, and the plan is scheduled exactly once.
+    execute_si(&vote(true), &validators[2], &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.upgrade_plans,
+        vec![UpgradePlan {
+            name: "v2".to_string(),
+            height: upgrade_height
+        }]
+    );
+    assert!(ctx.state.proposals[&0].executed);
+    assert!(ctx.state.events.contains(&ChainEvent::UpgradeScheduled {
+        height: 100,
+        proposal_id: 0,
+        name: "v2".to_string(),
+        upgrade_height,
+    }));
+    assert!(execute_si(&vote(true), &validators[1], &mut ctx).is_err());
+    assert!(execute_si(&propose("v2", upgrade_height + 1), &validators[0], &mut ctx).is_err());
+}
+
+#[cfg(not(feature = "contracts"))]
+#[test]
+fn test_contracts_disabled_without_feature() {
+    let mut state = GlobalState::default();
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+    let deploy = StablecoinInstruction::DeployContract { code: Vec::new() };
+    let err = execute_si(&deploy, &[1u8; 32], &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Smart contracts are not enabled");
+}
+
+/// A contract paying its caller half of its LUSD balance.
+#[cfg(feature = "contracts")]
+fn payout_contract() -> Vec<u8> {
+    use wasm_encoder::{
+        CodeSection, EntityType, ExportKind, ExportSection, Function, FunctionSection,
+        ImportSection, Instruction as I, MemorySection, MemoryType, Module, TypeSection,
+        ValType as T,
+    };
+    let mut types = TypeSection::new();
+    types.ty().function([T::I32], []);
+    types.ty().function([T::I32], [T::I64]);
+    types.ty().function([T::I32, T::I64], [T::I32]);
+    types.ty().function([], [T::I32]);
+    let mut imports = ImportSection::new();
+    imports.import("lumina", "caller", EntityType::Function(0));
+    imports.import("lumina", "address", EntityType::Function(0));
+    imports.import("lumina", "balance", EntityType::Function(1));
+    imports.import("lumina", "transfer", EntityType::Function(2));
+    let mut functions = FunctionSection::new();
+    functions.function(3);
+    let mut memory = MemorySection::new();
+    memory.memory(MemoryType {
+        minimum: 1,
+        maximum: Some(1),
+        memory64: false,
+        shared: false,
+        page_size_log2: None,
+    });
+    let mut exports = ExportSection::new();
+    exports.export("call", ExportKind::Func, 4);
+    let mut body = Function::new([]);
+    for op in [
+        I::I32Const(0),
+        I::Call(0),
+        I::I32Const(32),
+        I::Call(1),
+        I::I32Const(0),
+        I::I32Const(32),
+        I::Call(2),
+        I::I64Const(2),
+        I::I64DivU,
+        I::Call(3),
+        I::End,
+    ] {
+        body.instruction(&op);
+    }
+    let mut code = CodeSection::new();
+    code.function(&body);
+    let mut module = Module::new();
+    module
+        .section(&types)
+        .section(&imports)
+        .section(&functions)
+        .section(&memory)
+        .section(&exports)
+        .section(&code);
+    module.finish()
+}
+
+#[cfg(feature = "contracts")]
+#[test]
+fn test_contract_deploy_and_call_moves_lusd_atomically() {
+    let mut state = GlobalState::default();
+    let (deployer, caller) = ([1u8; 32], [2u8; 32]);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 5,
+        timestamp: 0,
+    };
+    let bogus = StablecoinInstruction::DeployContract {
+        code: b"not wasm".to_vec(),
+    };
+    assert!(execute_si(&bogus, &deployer, &mut ctx).is_err());
+    assert!(ctx.state.contracts.is_empty());
+
+    let root_before = ctx.state.root_hash();
+    let deploy = StablecoinInstruction::DeployContract {
+        code: payout_contract(),
+    };
+    execute_si(&deploy, &deployer, &mut ctx).unwrap();
+    let contract = *ctx.state.contracts.keys().next().unwrap();
+    assert_ne!(ctx.state.root_hash(), root_before);
+    assert!(matches!(
+        ctx.state.events.last(),
+        Some(ChainEvent::ContractDeployed { contract: c, .. }) if *c == contract
+    ));
+    ctx.state.accounts.entry(contract).or_default().lusd_balance = 1_000;
+
+    let call = |gas_limit| StablecoinInstruction::CallContract {
+        contract,
+        input: Vec::new(),
+        gas_limit,
+    };
+    // Running out of gas leaves balances untouched.
+    let err = execute_si(&call(2_100), &caller, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Out of gas");
+    assert_eq!(ctx.state.accounts[&contract].lusd_balance, 1_000);
+    assert!(!ctx.state.accounts.contains_key(&caller));
+
+    execute_si(&call(100_000), &caller, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&contract].lusd_balance, 500);
+    assert_eq!(ctx.state.accounts[&caller].lusd_balance, 500);
+    assert!(matches!(
+        ctx.state.events.last(),
+        Some(ChainEvent::ContractCalled { caller: c, gas_used, .. })
+            if *c == caller && *gas_used > 0
+    ));
+
+    assert!(execute_si(&call(0), &caller, &mut ctx).is_err());
+    let unknown = StablecoinInstruction::CallContract {
+        contract: [9u8; 32],
+        input: Vec::new(),
+        gas_limit: 100_000,
+    };
+    assert!(execute_si(&unknown, &caller, &mut ctx).is_err());
+}
+
+#[test]
+fn test_execution_hook_gates_transfers_on_circuit_attestations() {
+    let mut state = GlobalState::default();
+    let (validator, alice, bob) = ([81u8; 32], [82u8; 32], [83u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.accounts.entry(alice).or_default().lusd_balance = 1_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 5,
+        timestamp: 0,
+    };
+    let verifier_key = vec![1u8, 2, 3];
+    let upload = StablecoinInstruction::UploadComplianceCircuit {
+        circuit_id: 7,
+        verifier_key: verifier_key.clone(),
+    };
+    let hook = |name: &str, instructions: &[&str], gas_budget| ExecutionHook {
+        name: name.to_string(),
+        circuit_id: 7,
+        instructions: instructions.iter().map(|k| k.to_string()).collect(),
+        gas_budget,
+    };
+    let propose = |hook| StablecoinInstruction::ProposeHook { hook };
+    let checked = hook("jurisdiction", &["Transfer"], 21_000);
+    assert!(execute_si(&propose(checked.clone()), &validator, &mut ctx).is_err());
+    execute_si(&upload, &validator, &mut ctx).unwrap();
+    assert!(execute_si(&propose(checked.clone()), &alice, &mut ctx).is_err());
+    assert!(execute_si(
+        &propose(hook("lockout", &["Vote"], 21_000)),
+        &validator,
+        &mut ctx
+    )
+    .is_err());
+
+    // A lone validator is a supermajority, so the hook registers at once.
+    execute_si(&propose(checked), &validator, &mut ctx).unwrap();
+    assert!(ctx.state.events.contains(&ChainEvent::HookRegistered {
+        height: 5,
+        proposal_id: 0,
+        name: "jurisdiction".to_string(),
+        circuit_id: 7,
+    }));
+    assert!(execute_si(&upload, &validator, &mut ctx).is_err());
+
+    // Both sides of a transfer must be attested against the circuit's key.
+    let transfer = StablecoinInstruction::Transfer {
+        to: bob,
+        amount: 100,
+        asset: AssetType::LUSD,
+    };
+    let raw = lumina_crypto::zk::ZkManager::setup().prove_range(500, 1000);
+    let attest = |subject: &[u8; 32]| StablecoinInstruction::AttestCompliance {
+        circuit_id: 7,
+        proof: bound_proof(
+            lumina_crypto::zk::circuit_attestation_context(7, &verifier_key, subject),
+            raw.clone(),
+        ),
+    };
+    assert!(execute_si(&transfer, &alice, &mut ctx).is_err());
+    assert!(execute_si(&attest(&bob), &alice, &mut ctx).is_err());
+    execute_si(&attest(&alice), &alice, &mut ctx).unwrap();
+    let err = execute_si(&transfer, &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Hook jurisdiction rejected an account not attested for circuit 7"
+    );
+    execute_si(&attest(&bob), &bob, &mut ctx).unwrap();
+    execute_si(&transfer, &alice, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&bob].lusd_balance, 100);
+
+    // A hook whose budget covers only one subject fails closed.
+    execute_si(
+        &propose(hook("tight", &["Transfer"], 11_000)),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    let err = execute_si(&transfer, &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Hook tight exceeded its gas budget");
+    let remove = StablecoinInstruction::ProposeHookRemoval {
+        name: "tight".to_string(),
+    };
+    execute_si(&remove, &validator, &mut ctx).unwrap();
+    execute_si(&transfer, &alice, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&bob].lusd_balance, 200);
+}
+
+#[test]
+fn test_compliance_circuit_versions_overlap_until_retired() {
+    let mut state = GlobalState::default();
+    let (publisher, alice, bob) = ([84u8; 32], [85u8; 32], [86u8; 32]);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 10,
+        timestamp: 0,
+    };
+    let (v1_key, v2_key) = (vec![1u8, 2, 3], vec![4u8, 5, 6]);
+    let upload = |key: &Vec<u8>, version, activation_height| {
+        StablecoinInstruction::UploadComplianceCircuitV2 {
+            circuit_id: 9,
+            verifier_key: key.clone(),
+            version,
+            activation_height,
+            deprecation_blocks: 5,
+        }
+    };
+    let raw = lumina_crypto::zk::ZkManager::setup().prove_range(500, 1000);
+    let attest = |key: &Vec<u8>, subject: &[u8; 32]| StablecoinInstruction::AttestCompliance {
+        circuit_id: 9,
+        proof: bound_proof(
+            lumina_crypto::zk::circuit_attestation_context(9, key, subject),
+            raw.clone(),
+        ),
+    };
+    let attested = |state: &GlobalState, who: &[u8; 32]| {
+        state
+            .compliance_attestations
+            .get(&9)
+            .is_some_and(|a| a.contains(who))
+    };
+
+    execute_si(&upload(&v1_key, 1, 0), &publisher, &mut ctx).unwrap();
+    execute_si(&attest(&v1_key, &alice), &alice, &mut ctx).unwrap();
+    let err = execute_si(&upload(&v2_key, 2, 20), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only the circuit publisher can upload new versions"
+    );
+    let err = execute_si(&upload(&v2_key, 1, 20), &publisher, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Next version of circuit 9 is 2");
+    execute_si(&upload(&v2_key, 2, 20), &publisher, &mut ctx).unwrap();
+    assert!(execute_si(&upload(&vec![7u8], 3, 30), &publisher, &mut ctx).is_err());
+
+    // A proof against the pending version is kept but not yet counted.
+    execute_si(&attest(&v2_key, &bob), &bob, &mut ctx).unwrap();
+    assert!(attested(ctx.state, &alice) && !attested(ctx.state, &bob));
+    ctx.height = 20;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.compliance_circuits[&9], v2_key);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::CircuitVersionDeprecated {
+            height: 20,
+            circuit_id: 9,
+            version: 1,
+            retire_height: 25,
+        }));
+
+    // Both versions verify during the deprecation window.
+    assert!(attested(ctx.state, &alice) && attested(ctx.state, &bob));
+    execute_si(&attest(&v1_key, &publisher), &publisher, &mut ctx).unwrap();
+    ctx.height = 25;
+    end_block(&mut ctx);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::CircuitVersionRetired {
+            height: 25,
+            circuit_id: 9,
+            version: 1,
+        }));
+    assert!(!attested(ctx.state, &alice) && attested(ctx.state, &bob));
+    let err = execute_si(&attest(&v1_key, &alice), &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Circuit 9 version 1 is retired");
+    execute_si(&attest(&v2_key, &alice), &alice, &mut ctx).unwrap();
+    assert!(attested(ctx.state, &alice));
+}
+
+#[test]
+fn test_unversioned_circuit_upload_replaces_the_active_version() {
+    let mut state = GlobalState::default();
+    let (publisher, alice) = ([87u8; 32], [88u8; 32]);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 10,
+        timestamp: 0,
+    };
+    let (v1_key, v2_key) = (vec![1u8, 2, 3], vec![4u8, 5, 6]);
+    let upload = |key: &Vec<u8>| StablecoinInstruction::UploadComplianceCircuit {
+        circuit_id: 11,
+        verifier_key: key.clone(),
+    };
+    let raw = lumina_crypto::zk::ZkManager::setup().prove_range(500, 1000);
+    let attest = StablecoinInstruction::AttestCompliance {
+        circuit_id: 11,
+        proof: bound_proof(
+            lumina_crypto::zk::circuit_attestation_context(11, &v1_key, &alice),
+            raw,
+        ),
+    };
+
+    execute_si(&upload(&v1_key), &publisher, &mut ctx).unwrap();
+    execute_si(&attest, &alice, &mut ctx).unwrap();
+    let err = execute_si(&upload(&v2_key), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only the circuit publisher can upload new versions"
+    );
+
+    // Version 2 takes over at the end of the block and version 1 retires
+    // with it, taking its attestations along.
+    execute_si(&upload(&v2_key), &publisher, &mut ctx).unwrap();
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.compliance_circuits[&11], v2_key);
+    assert_eq!(ctx.state.circuit_versions[&11].active, 2);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::CircuitVersionRetired {
+            height: 10,
+            circuit_id: 11,
+            version: 1,
+        }));
+    assert!(!ctx
+        .state
+        .compliance_attestations
+        .get(&11)
+        .is_some_and(|a| a.contains(&alice)));
+}
+
+#[test]
+fn test_confidential_rwa_capacity_is_the_proven_lower_bound() {
+    use lumina_crypto::zk::{open_sealed_valuation, prove_min_valuation, seal_valuation};
+
+    let mut state = GlobalState::default();
+    let owner = [16u8; 32];
+    let (commitment, range_proof) = prove_min_valuation(50_000, 40_000, [4u8; 32]).unwrap();
+    let raw = lumina_crypto::zk::ZkManager::setup().prove_range(1, 10);
+    let view_key = [6u8; 32];
+    let list = |min_value| StablecoinInstruction::ListConfidentialRWA {
+        asset_description: "warehouse receipt".to_string(),
+        value_commitment: commitment,
+        min_value,
+        range_proof: range_proof.clone(),
+        attestation_proof: bound_proof(*blake3::hash(&commitment).as_bytes(), raw.clone()),
+        sealed_opening: seal_valuation(&view_key, &commitment, 50_000, [4u8; 32]),
+        maturity_date: None,
+        collateral_eligibility: true,
+    };
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let err = execute_si(&list(45_000), &owner, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid minimum valuation proof");
+    execute_si(&list(40_000), &owner, &mut ctx).unwrap();
+    assert_eq!(ctx.state.rwa_listings[&0].attested_value, 40_000);
+
+    let pledge = |amount_to_pledge| StablecoinInstruction::UseRWAAsCollateral {
+        rwa_id: 0,
+        amount_to_pledge,
+    };
+    assert!(execute_si(&pledge(40_001), &owner, &mut ctx).is_err());
+    execute_si(&pledge(40_000), &owner, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&owner].lusd_balance, 40_000);
+
+    // Only holders of the view key learn the hidden valuation.
+    let sealed = &ctx.state.sealed_rwa_valuations[&0];
+    assert_eq!(
+        open_sealed_valuation(&view_key, &sealed.commitment, &sealed.sealed_opening),
+        Some(50_000)
+    );
+}
+
+#[test]
+fn test_view_key_lets_auditor_open_sealed_confidential_balance() {
+    use lumina_crypto::view_key::{derive_view_secret, open_note, seal_note, view_public_key};
+    use lumina_crypto::zk::prove_confidential_transfer;
+
+    let mut state = GlobalState::default();
+    let (sender, kp) = new_sender();
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 3,
+        timestamp: 0,
+    };
+    let (commitment, proof) = prove_confidential_transfer(750, [2u8; 32]);
+    let view_secret = derive_view_secret(&kp.to_bytes(), 0);
+    let view_pubkey = view_public_key(&view_secret);
+    let sealed = |view_pubkey| StablecoinInstruction::SealedConfidentialTransfer {
+        commitment,
+        proof: proof.clone(),
+        sealed_note: seal_note(&view_pubkey, &commitment, 750, [2u8; 32], [8u8; 32]).unwrap(),
+    };
+    let err = execute_si(&sealed(view_pubkey), &sender, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "No view key registered");
+
+    let register = |view_pubkey| StablecoinInstruction::RegisterViewKey { view_pubkey };
+    assert!(execute_si(&register([0xffu8; 32]), &sender, &mut ctx).is_err());
+    execute_si(&register(view_pubkey), &sender, &mut ctx).unwrap();
+
+    // Once registered, unsealed confidential transfers are refused.
+    let plain = StablecoinInstruction::ConfidentialTransfer {
+        commitment,
+        proof: proof.clone(),
+    };
+    assert!(execute_si(&plain, &sender, &mut ctx).is_err());
+    execute_si(&sealed(view_pubkey), &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&sender].commitment, Some(commitment));
+    let note = &ctx.state.confidential_notes[&sender];
+    assert_eq!(
+        open_note(&view_secret, &note.commitment, &note.sealed_note),
+        Some(750)
+    );
+
+    // After rotation the old note stays bound to the key it was sealed to.
+    let rotated = view_public_key(&derive_view_secret(&kp.to_bytes(), 1));
+    execute_si(&register(rotated), &sender, &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.confidential_notes[&sender].view_pubkey,
+        view_pubkey
+    );
+}
+
+#[test]
+fn test_transfer_with_compliance_proof_checks_every_published_root() {
+    use lumina_types::screening::{ScreeningProof, ScreeningTree};
+
+    let mut state = GlobalState::default();
+    let (validator, oracle, alice, bob, sanctioned) =
+        ([91u8; 32], [92u8; 32], [93u8; 32], [94u8; 32], [95u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.accounts.entry(alice).or_default().lusd_balance = 1_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 9,
+        timestamp: 0,
+    };
+    let tree = ScreeningTree::new([[1u8; 32], sanctioned, [200u8; 32]]);
+    let publish = StablecoinInstruction::PublishScreeningRoot { root: tree.root() };
+    assert!(execute_si(&publish, &oracle, &mut ctx).is_err());
+    let add = StablecoinInstruction::ProposeScreeningOracle {
+        oracle,
+        member: true,
+    };
+    execute_si(&add, &validator, &mut ctx).unwrap();
+
+    let transfer = |to: [u8; 32], proofs| StablecoinInstruction::TransferWithComplianceProof {
+        to,
+        amount: 100,
+        asset: AssetType::LUSD,
+        proofs,
+    };
+    let err = execute_si(&transfer(bob, Vec::new()), &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "No screening roots published");
+    execute_si(&publish, &oracle, &mut ctx).unwrap();
+
+    let proof = |to: &[u8; 32]| ScreeningProof {
+        oracle,
+        sender: tree.prove_non_membership(&alice).unwrap(),
+        recipient: tree.prove_non_membership(to).unwrap(),
+    };
+    assert!(execute_si(&transfer(bob, Vec::new()), &alice, &mut ctx).is_err());
+    execute_si(&transfer(bob, vec![proof(&bob)]), &alice, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&bob].lusd_balance, 100);
+
+    // A screened recipient has no valid proof; reusing another's is rejected.
+    assert!(tree.prove_non_membership(&sanctioned).is_none());
+    let err = execute_si(&transfer(sanctioned, vec![proof(&bob)]), &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid screening proof");
+}
+
+#[test]
+fn test_kyc_tier_limits_reset_each_epoch() {
+    let mut state = GlobalState::default();
+    let (validator, officer, alice, bob, carol) = (
+        [101u8; 32],
+        [102u8; 32],
+        [103u8; 32],
+        [104u8; 32],
+        [105u8; 32],
+    );
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.accounts.entry(alice).or_default().lusd_balance = 20_000;
+    state.accounts.entry(carol).or_default().lusd_balance = 50;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 3,
+        timestamp: 0,
+    };
+    let transfer = |amount| StablecoinInstruction::Transfer {
+        to: bob,
+        amount,
+        asset: AssetType::LUSD,
+    };
+    // No tier table: unlimited.
+    execute_si(&transfer(5_000), &alice, &mut ctx).unwrap();
+
+    let tiers = vec![
+        KycTierLimits {
+            max_transfer_per_tx: 100,
+            max_transfer_per_epoch: 150,
+            max_redeem_per_tx: 0,
+            max_redeem_per_epoch: 0,
+        },
+        KycTierLimits {
+            max_transfer_per_tx: 1_000,
+            max_transfer_per_epoch: 5_000,
+            max_redeem_per_tx: 500,
+            max_redeem_per_epoch: 500,
+        },
+    ];
+    execute_si(
+        &StablecoinInstruction::ProposeKycTiers { tiers },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    let add = StablecoinInstruction::ProposeComplianceOfficer {
+        officer,
+        member: true,
+    };
+    execute_si(&add, &validator, &mut ctx).unwrap();
+
+    execute_si(&transfer(100), &alice, &mut ctx).unwrap();
+    let err = execute_si(&transfer(60), &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Transfer exceeds KYC tier daily limit");
+    // A transfer that fails in execution is not counted.
+    assert!(execute_si(&transfer(80), &carol, &mut ctx).is_err());
+    assert!(!ctx.state.kyc_accounts.contains_key(&carol));
+
+    let upgrade = StablecoinInstruction::SetKycTier {
+        account: alice,
+        tier: 1,
+    };
+    assert!(execute_si(&upgrade, &alice, &mut ctx).is_err());
+    execute_si(&upgrade, &officer, &mut ctx).unwrap();
+    let err = execute_si(&transfer(1_001), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Transfer exceeds KYC tier per-transaction limit"
+    );
+    execute_si(&transfer(1_000), &alice, &mut ctx).unwrap();
+    let redeem = StablecoinInstruction::InstantRedeem {
+        amount: 501,
+        destination: alice,
+    };
+    assert!(execute_si(&redeem, &alice, &mut ctx).is_err());
+    assert_eq!(ctx.state.kyc_accounts[&alice].usage(0), (1_100, 0));
+
+    // Counters lapse with the epoch.
+    ctx.state.current_epoch += 1;
+    assert_eq!(ctx.state.kyc_accounts[&alice].usage(1), (0, 0));
+    for _ in 0..4 {
+        execute_si(&transfer(1_000), &alice, &mut ctx).unwrap();
+    }
+    assert_eq!(ctx.state.kyc_accounts[&alice].usage(1), (4_000, 0));
+}
+
+#[test]
+fn test_jurisdiction_policy_applies_to_tagged_accounts() {
+    let mut state = GlobalState::default();
+    let (validator, alice, bob) = ([112u8; 32], [113u8; 32], [114u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.accounts.entry(alice).or_default().lusd_balance = 2_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 4,
+        timestamp: 0,
+    };
+    let verifier_key = vec![3u8; 4];
+    let upload = StablecoinInstruction::UploadComplianceCircuit {
+        circuit_id: 3,
+        verifier_key: verifier_key.clone(),
+    };
+    execute_si(&upload, &validator, &mut ctx).unwrap();
+    let policy = |blocked: &str| JurisdictionPolicy {
+        max_transfer_per_tx: 500,
+        max_transfer_per_epoch: 800,
+        required_circuits: vec![3],
+        blocked_instructions: vec![blocked.to_string()],
+    };
+    let propose = |policy| StablecoinInstruction::ProposeJurisdictionPolicy {
+        jurisdiction_id: 44,
+        policy,
+    };
+    let err = execute_si(&propose(Some(policy("Vote"))), &validator, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Jurisdictions cannot block Vote");
+    execute_si(
+        &propose(Some(policy("BatchDisburse"))),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+
+    let raw = lumina_crypto::zk::ZkManager::setup().prove_range(500, 1000);
+    let tag = |subject: &[u8; 32]| StablecoinInstruction::MultiJurisdictionalCheck {
+        jurisdiction_id: 44,
+        proof: bound_proof(
+            lumina_crypto::zk::jurisdiction_context(44, subject),
+            raw.clone(),
+        ),
+    };
+    let transfer = |amount| StablecoinInstruction::Transfer {
+        to: bob,
+        amount,
+        asset: AssetType::LUSD,
+    };
+    // Untagged accounts are unaffected, and a proof only tags its subject.
+    execute_si(&transfer(600), &alice, &mut ctx).unwrap();
+    assert!(execute_si(&tag(&alice), &bob, &mut ctx).is_err());
+    execute_si(&tag(&alice), &alice, &mut ctx).unwrap();
+    assert_eq!(ctx.state.account_jurisdictions[&alice], 44);
+
+    let err = execute_si(&transfer(100), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Jurisdiction 44 requires an attestation for circuit 3"
+    );
+    let attest = StablecoinInstruction::AttestCompliance {
+        circuit_id: 3,
+        proof: bound_proof(
+            lumina_crypto::zk::circuit_attestation_context(3, &verifier_key, &alice),
+            raw.clone(),
+        ),
+    };
+    execute_si(&attest, &alice, &mut ctx).unwrap();
+    let err = execute_si(&transfer(501), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Transfer exceeds jurisdiction 44 per-transaction cap"
+    );
+    execute_si(&transfer(500), &alice, &mut ctx).unwrap();
+    let err = execute_si(&transfer(301), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Transfer exceeds jurisdiction 44 daily cap"
+    );
+    let batch = StablecoinInstruction::BatchDisburse {
+        payments: vec![(bob, 1)],
+    };
+    let err = execute_si(&batch, &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "BatchDisburse is blocked in jurisdiction 44"
+    );
+
+    // Removing the policy lifts it from every tagged account.
+    execute_si(&propose(None), &validator, &mut ctx).unwrap();
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::JurisdictionPolicyChanged {
+            height: 4,
+            proposal_id: 1,
+            jurisdiction_id: 44,
+            active: false,
+        }));
+    execute_si(&batch, &alice, &mut ctx).unwrap();
+    execute_si(&transfer(301), &alice, &mut ctx).unwrap();
+}
+
+#[test]
+fn test_proven_credentials_gate_custodians_and_kyc_tiers() {
+    let mut state = GlobalState::default();
+    let (validator, attester, bank, officer) = ([115u8; 32], [116u8; 32], [117u8; 32], [118u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.accounts.entry(bank).or_default().ljun_balance = 1_000;
+    state.compliance_officers.insert(officer);
+    state.kyc_tiers = vec![
+        KycTierLimits {
+            max_transfer_per_tx: 100,
+            max_transfer_per_epoch: 100,
+            max_redeem_per_tx: 100,
+            max_redeem_per_epoch: 100,
+        };
+        2
+    ];
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 6,
+        timestamp: 0,
+    };
+    let requirements = CredentialRequirements {
+        custodian: vec![CredentialKind::License],
+        kyc_min_tier: 1,
+        kyc_kinds: vec![CredentialKind::Lei],
+    };
+    execute_si(
+        &StablecoinInstruction::ProposeCredentialRequirements { requirements },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    let register = StablecoinInstruction::RegisterCustodian {
+        stake: 100,
+        mpc_pubkeys: vec![[1u8; 32]],
+    };
+    let err = execute_si(&register, &bank, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Account has not proven a License credential"
+    );
+
+    let commitment = lumina_crypto::zk::credential_commitment(b"license-42", &[9u8; 32]);
+    let anchor = StablecoinInstruction::AnchorCredential {
+        institution: bank,
+        kind: CredentialKind::License,
+        commitment,
+        expires_height: 100,
+    };
+    assert!(execute_si(&anchor, &attester, &mut ctx).is_err());
+    let trust = StablecoinInstruction::ProposeCredentialAttester {
+        attester,
+        member: true,
+    };
+    execute_si(&trust, &validator, &mut ctx).unwrap();
+    execute_si(&anchor, &attester, &mut ctx).unwrap();
+    // Anchoring alone is not enough: the institution must prove it.
+    assert!(execute_si(&register, &bank, &mut ctx).is_err());
+
+    let raw = lumina_crypto::zk::ZkManager::setup().prove_range(1, 10);
+    let prove = |holder: &[u8; 32]| StablecoinInstruction::ProveInstitutionStatus {
+        kind: CredentialKind::License,
+        proof: bound_proof(
+            lumina_crypto::zk::credential_context(&commitment, holder),
+            raw.clone(),
+        ),
+    };
+    let err = execute_si(&prove(&officer), &bank, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Invalid credential proof");
+    execute_si(&prove(&bank), &bank, &mut ctx).unwrap();
+    execute_si(&register, &bank, &mut ctx).unwrap();
+    assert_eq!(ctx.state.custodians.len(), 1);
+
+    // Higher KYC tiers need an LEI; tier 0 does not.
+    let assign = |tier| StablecoinInstruction::SetKycTier {
+        account: bank,
+        tier,
+    };
+    execute_si(&assign(0), &officer, &mut ctx).unwrap();
+    let err = execute_si(&assign(1), &officer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Account has not proven a Lei credential");
+
+    // Distrusting the attester voids what it anchored.
+    assert!(ctx
+        .state
+        .has_credentials(&bank, &[CredentialKind::License], 6));
+    let distrust = StablecoinInstruction::ProposeCredentialAttester {
+        attester,
+        member: false,
+    };
+    execute_si(&distrust, &validator, &mut ctx).unwrap();
+    assert!(!ctx
+        .state
+        .has_credentials(&bank, &[CredentialKind::License], 6));
+    assert!(ctx.state.has_credentials(&bank, &[], 6));
+}
+
+#[test]
+fn test_streams_escrow_any_asset_until_withdrawn_or_cancelled() {
+    let mut state = GlobalState::default();
+    let (payer, payee) = ([119u8; 32], [120u8; 32]);
+    let account = state.accounts.entry(payer).or_default();
+    account.lumina_balance = 1_000;
+    account
+        .custom_balances
+        .insert("GOLD".to_string(), 500.into());
+    account
+        .bridged_balances
+        .insert("BTC".to_string(), 500.into());
+    state.oracle_prices.insert("GOLD".to_string(), 0);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 1_000,
+    };
+    let stream = |asset, amount_per_sec, duration| StablecoinInstruction::StreamAsset {
+        to: payee,
+        amount_per_sec,
+        duration,
+        asset,
+    };
+    execute_si(&stream(AssetType::Lumina, 2, 100), &payer, &mut ctx).unwrap();
+    let err = execute_si(
+        &stream(AssetType::Custom("SILVER".to_string()), 1, 10),
+        &payer,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Unknown asset SILVER");
+    assert!(execute_si(
+        &stream(AssetType::Bridged("BTC".to_string()), 1, 10),
+        &payer,
+        &mut ctx
+    )
+    .is_err());
+    let gold = AssetType::Custom("GOLD".to_string());
+    let err = execute_si(&stream(gold.clone(), 6, 100), &payer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Insufficient GOLD for stream escrow");
+    execute_si(&stream(gold.clone(), 5, 40), &payer, &mut ctx).unwrap();
+    let escrowed = &ctx.state.accounts[&payer];
+    assert_eq!(escrowed.lumina_balance, 800);
+    assert_eq!(escrowed.custom_balances["GOLD"], 300);
+    assert_eq!(escrowed.active_streams[1].id, 1);
+
+    let withdraw = StablecoinInstruction::WithdrawStream {
+        payer,
+        stream_id: 0,
+    };
+    ctx.timestamp = 1_030;
+    assert!(execute_si(&withdraw, &payer, &mut ctx).is_err());
+    execute_si(&withdraw, &payee, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&payee].lumina_balance, 60);
+    let err = execute_si(&withdraw, &payee, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Nothing to withdraw");
+
+    // Cancelling pays out what was released and refunds the rest.
+    ctx.timestamp = 1_030;
+    execute_si(
+        &StablecoinInstruction::CancelStream { stream_id: 1 },
+        &payer,
+        &mut ctx,
+    )
+    .unwrap();
+    assert!(ctx.state.events.contains(&ChainEvent::StreamCancelled {
+        height: 1,
+        stream_id: 1,
+        payer,
+        recipient: payee,
+        asset: gold,
+        paid: 150,
+        refunded: 50,
+    }));
+    assert_eq!(ctx.state.accounts[&payee].custom_balances["GOLD"], 150);
+    assert_eq!(ctx.state.accounts[&payer].custom_balances["GOLD"], 350);
+
+    // The last withdrawal after the end closes the stream.
+    ctx.timestamp = 5_000;
+    execute_si(&withdraw, &payee, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&payee].lumina_balance, 200);
+    assert!(ctx.state.accounts[&payer].active_streams.is_empty());
+}
+
+#[test]
+fn test_stream_top_up_and_rate_changes_keep_id_and_history() {
+    let mut state = GlobalState::default();
+    let (payer, payee, stranger) = ([121u8; 32], [122u8; 32], [123u8; 32]);
+    state.accounts.entry(payer).or_default().lusd_balance = 10_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 1_000,
+    };
+    execute_si(
+        &StablecoinInstruction::StreamPayment {
+            to: payee,
+            amount_per_sec: 10,
+            duration: 100,
+        },
+        &payer,
+        &mut ctx,
+    )
+    .unwrap();
+    let withdraw = StablecoinInstruction::WithdrawStream {
+        payer,
+        stream_id: 0,
+    };
+    let rate = |amount_per_sec| StablecoinInstruction::ModifyStreamRate {
+        payer,
+        stream_id: 0,
+        amount_per_sec,
+    };
+    ctx.timestamp = 1_020;
+    execute_si(&withdraw, &payee, &mut ctx).unwrap();
+    execute_si(
+        &StablecoinInstruction::TopUpStream {
+            stream_id: 0,
+            duration: 50,
+        },
+        &payer,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.stream(&payer, 0).unwrap().end_timestamp, 1_150);
+
+    // A raise applies at once, escrowing the extra for the 100s left.
+    ctx.timestamp = 1_050;
+    execute_si(&rate(20), &payer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&payer].lusd_balance, 7_500);
+
+    // A reduction waits for the recipient to accept that exact rate.
+    ctx.timestamp = 1_070;
+    execute_si(&rate(5), &payer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.stream(&payer, 0).unwrap().amount_per_sec, 20);
+    let err = execute_si(&rate(8), &payee, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Recipient can only accept a rate reduction the payer proposed"
+    );
+    let err = execute_si(&rate(5), &stranger, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only the stream payer or recipient can change its rate"
+    );
+    execute_si(&rate(5), &payee, &mut ctx).unwrap();
+    assert!(ctx.state.events.contains(&ChainEvent::StreamRateChanged {
+        height: 1,
+        stream_id: 0,
+        payer,
+        recipient: payee,
+        old_rate: 20,
+        new_rate: 5,
+    }));
+    assert_eq!(ctx.state.accounts[&payer].lusd_balance, 8_700);
+    let stream = ctx.state.stream(&payer, 0).unwrap();
+    assert_eq!((stream.withdrawn, stream.vested(1_070)), (200, 900));
+
+    ctx.timestamp = 1_200;
+    let err = execute_si(&rate(50), &payer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Stream has ended");
+    execute_si(&withdraw, &payee, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&payee].lusd_balance, 1_300);
+    assert!(ctx.state.stream(&payer, 0).is_none());
+}
+
+#[test]
+fn test_stream_batch_escrows_total_once() {
+    let mut state = GlobalState::default();
+    let payer = [124u8; 32];
+    state.accounts.entry(payer).or_default().ljun_balance = 1_000;
+    state.next_stream_id = 5;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 1_000,
+    };
+    let batch = |streams| StablecoinInstruction::CreateStreamBatch {
+        asset: AssetType::LJUN,
+        streams,
+    };
+    let err = execute_si(&batch(Vec::new()), &payer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "No streams");
+    let err = execute_si(
+        &batch(vec![([1u8; 32], 5, 100), ([2u8; 32], 6, 100)]),
+        &payer,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Insufficient LJUN for stream escrow");
+    assert!(ctx.state.accounts[&payer].active_streams.is_empty());
+
+    execute_si(
+        &batch(vec![([1u8; 32], 5, 100), ([2u8; 32], 2, 200)]),
+        &payer,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&payer].ljun_balance, 100);
+    assert_eq!(ctx.state.next_stream_id, 7);
+    let created: Vec<_> = ctx
+        .state
+        .events
+        .iter()
+        .filter_map(|e| match e {
+            ChainEvent::StreamCreated {
+                stream_id,
+                recipient,
+                end_timestamp,
+                ..
+            } => Some((*stream_id, recipient[0], *end_timestamp)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(created, vec![(5, 1, 1_100), (6, 2, 1_200)]);
+}
+
+#[test]
+fn test_hash_locks_pay_on_preimage_or_refund_after_timeout() {
+    use sha2::{Digest, Sha256};
+
+    let mut state = GlobalState::default();
+    let (locker, recipient, relayer) = ([125u8; 32], [126u8; 32], [127u8; 32]);
+    state
+        .accounts
+        .entry(locker)
+        .or_default()
+        .bridged_balances
+        .insert("BTC".to_string(), 100.into());
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 1_000,
+    };
+    let preimage = [7u8; 32];
+    let hash_lock: [u8; 32] = Sha256::digest(preimage).into();
+    let btc = AssetType::Bridged("BTC".to_string());
+    let lock = |amount, timeout_height| StablecoinInstruction::LockWithHash {
+        recipient,
+        asset: btc.clone(),
+        amount,
+        hash_lock,
+        timeout_height,
+    };
+    execute_si(&lock(60, 5), &locker, &mut ctx).unwrap();
+    execute_si(&lock(40, 3), &locker, &mut ctx).unwrap();
+    let err = execute_si(&lock(1, 3), &locker, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Insufficient BTC");
+
+    let claim = |lock_id, preimage| StablecoinInstruction::ClaimWithPreimage { lock_id, preimage };
+    let refund = |lock_id| StablecoinInstruction::RefundAfterTimeout { lock_id };
+    let err = execute_si(&claim(0, [8u8; 32]), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Preimage does not match hash lock");
+    let err = execute_si(&refund(1), &locker, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Hash lock has not timed out");
+    execute_si(&claim(0, preimage), &relayer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&recipient].bridged_balances["BTC"], 60);
+    assert!(ctx.state.events.contains(&ChainEvent::HashLockClaimed {
+        height: 1,
+        lock_id: 0,
+        sender: locker,
+        recipient,
+        preimage,
+    }));
+
+    // Lock 1 can be claimed through height 3 and is expired by its end.
+    ctx.height = 3;
+    end_block(&mut ctx);
+    assert!(ctx.state.hash_locks[&1].expired);
+    assert!(ctx.state.hash_lock_timeouts.is_empty());
+    ctx.height = 4;
+    let err = execute_si(&claim(1, preimage), &recipient, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Hash lock has timed out");
+    let err = execute_si(&refund(1), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Only the locking account can refund");
+    execute_si(&refund(1), &locker, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&locker].bridged_balances["BTC"], 40);
+    assert!(ctx.state.hash_locks.is_empty());
+}
+
+#[test]
+fn test_spv_deposits_mint_after_challenge_period_on_best_chain() {
+    use lumina_bridge_spv::btc;
+    use lumina_types::instruction::SpvDepositProof;
+    use lumina_types::state::{SpvBridge, SpvChain};
+
+    // Regtest difficulty: about every other nonce meets the target
+    fn mine(prev: [u8; 32], root: [u8; 32]) -> Vec<u8> {
+        (0u32..)
+            .map(|nonce| {
+                [
+                    &1u32.to_le_bytes()[..],
+                    &prev,
+                    &root,
+                    &0u32.to_le_bytes(),
+                    &0x207f_ffffu32.to_le_bytes(),
+                    &nonce.to_le_bytes(),
+                ]
+                .concat()
+            })
+            .find(|raw| btc::BlockHeader::parse(raw).unwrap().check_pow().is_ok())
+            .unwrap()
+    }
+    let hash = |raw: &[u8]| btc::double_sha256(raw);
+
+    let mut state = GlobalState::default();
+    let (validator, recipient, relayer) = ([128u8; 32], [129u8; 32], [130u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+
+    let script = vec![0x51];
+    let tx = [
+        &2u32.to_le_bytes()[..],
+        &[1],
+        &[0u8; 36],
+        &[0],
+        &[0xff; 4],
+        &[2],
+        &50_000u64.to_le_bytes(),
+        &[1, 0x51],
+        &0u64.to_le_bytes(),
+        &[34, 0x6a, 0x20],
+        &recipient,
+        &[0u8; 4],
+    ]
+    .concat();
+    let checkpoint = mine([0u8; 32], [0u8; 32]);
+    let a = mine(hash(&checkpoint), hash(&tx));
+    let b = mine(hash(&a), [0u8; 32]);
+
+    let bridge = SpvBridge {
+        symbol: "BTC".to_string(),
+        deposit_target: script,
+        checkpoint: checkpoint.clone(),
+        checkpoint_height: 100,
+        min_confirmations: 2,
+        challenge_blocks: 5,
+    };
+    let propose = |bridge| StablecoinInstruction::ProposeSpvBridge {
+        chain: SpvChain::Bitcoin,
+        bridge: Some(bridge),
+    };
+    let err = execute_si(&propose(bridge.clone()), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Only validators can propose SPV bridges");
+    execute_si(&propose(bridge), &validator, &mut ctx).unwrap();
+
+    let submit = |headers: Vec<&Vec<u8>>| StablecoinInstruction::SubmitSpvHeaders {
+        chain: SpvChain::Bitcoin,
+        headers: headers.into_iter().cloned().collect(),
+    };
+    let claim = |tx: &[u8]| StablecoinInstruction::ClaimSpvDeposit {
+        chain: SpvChain::Bitcoin,
+        block_hash: hash(&a),
+        proof: SpvDepositProof::Bitcoin {
+            tx: tx.to_vec(),
+            merkle_branch: vec![],
+            index: 0,
+        },
+    };
+    let err = execute_si(&submit(vec![&b]), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Header's parent is unknown");
+    execute_si(&submit(vec![&a]), &relayer, &mut ctx).unwrap();
+    let err = execute_si(&claim(&tx), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Deposit has 1 of 2 confirmations");
+    execute_si(&submit(vec![&a, &b]), &relayer, &mut ctx).unwrap();
+    let mut other = tx.clone();
+    other[0] = 1;
+    let err = execute_si(&claim(&other), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Merkle proof does not match the block");
+    execute_si(&claim(&tx), &relayer, &mut ctx).unwrap();
+    let err = execute_si(&claim(&tx), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Deposit already claimed");
+
+    // A heavier fork without the deposit's block voids the claim when its
+    // challenge period ends, and releases it to be claimed again.
+    let c = mine(hash(&checkpoint), [1u8; 32]);
+    let d = mine(hash(&c), [2u8; 32]);
+    let e = mine(hash(&d), [3u8; 32]);
+    execute_si(&submit(vec![&c, &d, &e]), &relayer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.spv_clients[&SpvChain::Bitcoin].tip, hash(&e));
+    ctx.height = 6;
+    end_block(&mut ctx);
+    assert!(ctx.state.events.contains(&ChainEvent::SpvDepositFinalized {
+        height: 6,
+        deposit_id: 0,
+        recipient,
+        symbol: "BTC".to_string(),
+        amount: 50_000,
+        minted: false,
+    }));
+    assert!(!ctx.state.accounts.contains_key(&recipient));
+    let err = execute_si(&claim(&tx), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Block is not on the best chain");
+
+    // The original chain overtakes again; a challenge voids the next claim
+    // and the one after mints once unchallenged.
+    let f = mine(hash(&b), [4u8; 32]);
+    let g = mine(hash(&f), [5u8; 32]);
+    execute_si(&submit(vec![&f, &g]), &relayer, &mut ctx).unwrap();
+    execute_si(&claim(&tx), &relayer, &mut ctx).unwrap();
+    let challenge = StablecoinInstruction::ChallengeSpvDeposit { deposit_id: 1 };
+    let err = execute_si(&challenge, &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Only validators can challenge deposits");
+    execute_si(&challenge, &validator, &mut ctx).unwrap();
+    execute_si(&claim(&tx), &relayer, &mut ctx).unwrap();
+    assert_eq!(ctx.state.spv_deposits[&2].finalize_height, 11);
+    ctx.height = 10;
+    end_block(&mut ctx);
+    assert!(ctx.state.spv_deposits.contains_key(&2));
+    ctx.height = 11;
+    end_block(&mut ctx);
+    assert_eq!(
+        ctx.state.accounts[&recipient].bridged_balances["BTC"],
+        50_000
+    );
+    assert!(ctx.state.spv_deposits.is_empty());
+    let err = execute_si(&claim(&tx), &relayer, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Deposit already claimed");
+}
+
+#[test]
+fn test_cross_chain_lusd_burns_into_signed_batches_and_returns_on_quorum() {
+    let mut state = GlobalState::default();
+    let keys: Vec<_> = (0..3).map(|_| new_sender()).collect();
+    for ((pubkey, _), power) in keys.iter().zip([50, 20, 30]) {
+        state.validators.push(ValidatorState {
+            pubkey: *pubkey,
+            stake: power,
+            power,
+            is_green: false,
+            energy_proof: None,
+            green_verified_at: 0,
+        });
+    }
+    let (v1, v2, v3) = (keys[0].0, keys[1].0, keys[2].0);
+    let alice = [131u8; 32];
+    state.accounts.entry(alice).or_default().lusd_balance = 1_000;
+    state.total_lusd_supply = 1_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+
+    let send = |recipient: Vec<u8>, amount| StablecoinInstruction::SendLusdCrossChain {
+        dest_chain: 7,
+        recipient,
+        amount,
+    };
+    let err = execute_si(&send(vec![0xab; 20], 400), &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Remote chain 7 is not active");
+    let propose = StablecoinInstruction::ProposeRemoteChain {
+        chain_id: 7,
+        active: true,
+    };
+    execute_si(&propose, &v1, &mut ctx).unwrap();
+    let vote = StablecoinInstruction::Vote {
+        proposal_id: 0,
+        approve: true,
+    };
+    execute_si(&vote, &v3, &mut ctx).unwrap();
+    let err = execute_si(&send(vec![], 400), &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Remote recipient must be 1 to 64 bytes");
+    execute_si(&send(vec![0xab; 20], 400), &alice, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&alice].lusd_balance, 600);
+    assert_eq!(ctx.state.total_lusd_supply, 600);
+    assert_eq!(ctx.state.remote_chains[&7].outstanding, 400);
+
+    end_block(&mut ctx);
+    assert!(ctx.state.outbound_messages.is_empty());
+    let bytes = ctx.state.message_batches[&0].signing_bytes();
+    assert_eq!(ctx.state.message_batches[&0].messages[0].amount, 400);
+    let attest = |key, message: &[u8]| StablecoinInstruction::AttestMessageBatch {
+        batch_id: 0,
+        signature: lumina_crypto::signatures::sign(key, message),
+    };
+    let err = execute_si(&attest(&keys[1].1, b"other"), &v2, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Signature verification failed");
+    let err = execute_si(&attest(&keys[1].1, &bytes), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only validators can attest message batches"
+    );
+    execute_si(&attest(&keys[0].1, &bytes), &v1, &mut ctx).unwrap();
+    execute_si(&attest(&keys[0].1, &bytes), &v1, &mut ctx).unwrap();
+    assert!(!ctx.state.message_batches[&0].has_quorum(&ctx.state.validators));
+    execute_si(&attest(&keys[2].1, &bytes), &v3, &mut ctx).unwrap();
+    assert_eq!(ctx.state.message_batches[&0].signatures.len(), 2);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::MessageBatchAttested {
+            height: 1,
+            batch_id: 0,
+        }));
+
+    // Returns mint once validators holding a quorum attest the same burn,
+    // and never more than was sent to the chain.
+    let ret = |burn_id, amount| StablecoinInstruction::AttestLusdReturn {
+        source_chain: 7,
+        burn_id,
+        recipient: alice,
+        amount,
+    };
+    execute_si(&ret([1u8; 32], 500), &v1, &mut ctx).unwrap();
+    let err = execute_si(&ret([1u8; 32], 400), &v3, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Return differs from earlier attestations");
+    let err = execute_si(&ret([1u8; 32], 500), &v3, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Return exceeds the LUSD outstanding on chain 7"
+    );
+    execute_si(&ret([2u8; 32], 150), &v1, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&alice].lusd_balance, 600);
+    execute_si(&ret([2u8; 32], 150), &v3, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&alice].lusd_balance, 750);
+    assert_eq!(ctx.state.total_lusd_supply, 750);
+    assert_eq!(ctx.state.remote_chains[&7].outstanding, 250);
+    let err = execute_si(&ret([2u8; 32], 150), &v2, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Return already minted");
+}
+
+#[test]
+fn test_mint_caps_limit_blocks_and_accounts_and_trip_breaker() {
+    let mut state = GlobalState::default();
+    let (validator, _) = new_sender();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.stabilization_pool_balance = 1_000_000;
+    state.total_lusd_supply = 1_000_000;
+    state.reserve_ratio = 1.0;
+    let (alice, bob, carol) = ([141u8; 32], [142u8; 32], [143u8; 32]);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 10,
+        timestamp: 0,
+    };
+
+    let flash = |amount: u64| StablecoinInstruction::FlashMint {
+        amount,
+        collateral_asset: AssetType::Lumina,
+        collateral_amount: amount * 2,
+        commitment: [9u8; 32],
+    };
+    let propose = |per_account| StablecoinInstruction::ProposeMintCaps {
+        caps: MintCaps {
+            per_block: 1_000,
+            per_account,
+            breaker_blocks: 2,
+        },
+    };
+    let err = execute_si(&propose(1_001), &validator, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Account mint cap cannot exceed the block cap"
+    );
+    execute_si(&propose(600), &validator, &mut ctx).unwrap();
+    assert_eq!(ctx.state.mint_caps.per_account, 600);
+
+    // A flash mint burned in the same block gives its headroom back.
+    execute_si(&flash(600), &alice, &mut ctx).unwrap();
+    execute_si(
+        &StablecoinInstruction::FlashBurn { amount: 600 },
+        &alice,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.block_minted, 0);
+
+    let err = execute_si(&flash(700), &bob, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Mint exceeds the account cap: 600 of 600 left"
+    );
+    execute_si(&flash(500), &bob, &mut ctx).unwrap();
+    let err = execute_si(&flash(600), &carol, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Mint exceeds the block cap: 500 of 1000 left"
+    );
+    execute_si(&flash(500), &carol, &mut ctx).unwrap();
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.mint_cap_streak, 1);
+    assert_eq!(ctx.state.block_minted, 0);
+    assert!(ctx.state.block_account_mints.is_empty());
+
+    // A block below the caps ends the run.
+    ctx.height = 11;
+    execute_si(&flash(100), &bob, &mut ctx).unwrap();
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.mint_cap_streak, 0);
+
+    // Two capped blocks in a row trip the breaker.
+    for height in [12, 13] {
+        ctx.height = height;
+        execute_si(&flash(600), &alice, &mut ctx).unwrap();
+        end_block(&mut ctx);
+    }
+    assert!(ctx.state.circuit_breaker_active);
+    assert!(ctx.state.events.contains(&ChainEvent::MintBreakerTripped {
+        height: 13,
+        blocks: 2,
+    }));
+    ctx.height = 14;
+    let err = execute_si(&flash(100), &bob, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Circuit breaker active: minting paused");
+}
+
+#[test]
+fn test_large_redemptions_wait_out_their_tier_unless_cancelled() {
+    let mut state = GlobalState::default();
+    let (validator, _) = new_sender();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let minter = [151u8; 32];
+    state
+        .accounts
+        .entry(minter)
+        .or_default()
+        .custom_balances
+        .insert("GOLD".to_string(), 2_000.into());
+    state
+        .oracle_prices
+        .insert("GOLD-USD".to_string(), 1_000_000);
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let mint = StablecoinInstruction::MintSeniorWithCollateral {
+        amount: 1_000,
+        collateral_asset: CollateralAsset::Custom("GOLD".to_string()),
+        collateral_amount: 1_200,
+        proof: manager.prove_reserves(vec![1_200], 1_200),
+    };
+    execute_si(&mint, &minter, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&minter].lusd_balance, 950);
+
+    let tier = |min_amount, delay_blocks| RedemptionTier {
+        min_amount,
+        delay_blocks,
+    };
+    let propose = |tiers| StablecoinInstruction::ProposeRedemptionTiers { tiers };
+    let err = execute_si(
+        &propose(vec![tier(600, 10), tier(300, 5)]),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Redemption tiers must rise in threshold and delay"
+    );
+    execute_si(
+        &propose(vec![tier(300, 5), tier(600, 10)]),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+
+    // Below every tier a redemption settles at once.
+    let redeem = |amount| StablecoinInstruction::RedeemSenior { amount };
+    execute_si(&redeem(100), &minter, &mut ctx).unwrap();
+    assert!(ctx.state.delayed_redemptions.is_empty());
+    assert_eq!(ctx.state.collateral_positions[&0].minted, 850);
+
+    execute_si(&redeem(400), &minter, &mut ctx).unwrap();
+    let instant = StablecoinInstruction::InstantRedeem {
+        amount: 400,
+        destination: minter,
+    };
+    execute_si(&instant, &minter, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&minter].lusd_balance, 50);
+    assert_eq!(ctx.state.delayed_redemptions[&0].release_height, 6);
+    assert_eq!(ctx.state.delayed_redemptions[&1].amount, 400);
+
+    // A validator reviewing the second one cancels it; strangers cannot.
+    let cancel = StablecoinInstruction::CancelDelayedRedemption { redemption_id: 1 };
+    let err = execute_si(&cancel, &[152u8; 32], &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only the owner or a validator can cancel a delayed redemption"
+    );
+    execute_si(&cancel, &validator, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&minter].lusd_balance, 450);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::DelayedRedemptionCancelled {
+            height: 1,
+            redemption_id: 1,
+            owner: minter,
+            by: validator,
+            amount: 400,
+        }));
+
+    ctx.height = 5;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.collateral_positions[&0].minted, 850);
+    ctx.height = 6;
+    end_block(&mut ctx);
+    assert!(ctx.state.delayed_redemptions.is_empty());
+    assert_eq!(ctx.state.collateral_positions[&0].minted, 450);
+    assert!(ctx
+        .state
+        .events
+        .contains(&ChainEvent::DelayedRedemptionReleased {
+            height: 6,
+            redemption_id: 0,
+            owner: minter,
+            amount: 400,
+            queued: false,
+        }));
+}
+
+#[test]
+fn test_council_actions_take_effect_at_threshold_and_lapse_unless_ratified() {
+    use lumina_types::audit::AuditAction;
+    use lumina_types::state::{CouncilActionStatus, CouncilPower, EmergencyCouncil};
+
+    let mut state = GlobalState::default();
+    let (validator, _) = new_sender();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let (m1, m2, m3) = ([161u8; 32], [162u8; 32], [163u8; 32]);
+    let alice = [164u8; 32];
+    state.accounts.entry(alice).or_default().lusd_balance = 1_000;
+    state.total_lusd_supply = 1_000;
+    state.remote_chains.entry(7).or_default().active = true;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+
+    let appoint = |threshold| StablecoinInstruction::ProposeEmergencyCouncil {
+        council: EmergencyCouncil {
+            members: vec![m1, m2, m3],
+            threshold,
+            sunset_blocks: 10,
+        },
+    };
+    let err = execute_si(&appoint(4), &validator, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Council threshold must be 1 to the number of members"
+    );
+    execute_si(&appoint(2), &validator, &mut ctx).unwrap();
+
+    let propose = |power| StablecoinInstruction::CouncilPropose { power };
+    let approve = |action_id| StablecoinInstruction::CouncilApprove { action_id };
+    let pause = |kind: &str| propose(CouncilPower::PauseInstructions(vec![kind.to_string()]));
+    let err = execute_si(&pause("FlashMint"), &alice, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Only council members can propose council actions"
+    );
+    let err = execute_si(&pause("Vote"), &m1, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Vote cannot be paused");
+
+    // One member's proposal waits for a second approval.
+    execute_si(&pause("FlashMint"), &m1, &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.council_actions[&0].status,
+        CouncilActionStatus::Proposed
+    );
+    execute_si(&approve(0), &m2, &mut ctx).unwrap();
+    assert_eq!(ctx.state.council_actions[&0].expires_height, 11);
+    let flash = StablecoinInstruction::FlashMint {
+        amount: 100,
+        collateral_asset: AssetType::Lumina,
+        collateral_amount: 200,
+        commitment: [9u8; 32],
+    };
+    let err = execute_si(&flash, &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "FlashMint is paused");
+
+    // Freezing the bridge stops cross-chain sends but not payments.
+    execute_si(&propose(CouncilPower::FreezeBridge), &m1, &mut ctx).unwrap();
+    execute_si(&approve(1), &m3, &mut ctx).unwrap();
+    let send = StablecoinInstruction::SendLusdCrossChain {
+        dest_chain: 7,
+        recipient: vec![0xab; 20],
+        amount: 100,
+    };
+    let err = execute_si(&send, &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "SendLusdCrossChain is paused");
+    let pay = StablecoinInstruction::Transfer {
+        to: m1,
+        amount: 100,
+        asset: AssetType::LUSD,
+    };
+    execute_si(&pay, &alice, &mut ctx).unwrap();
+
+    // Governance ratifies the breaker trip, so it outlives the sunset.
+    execute_si(&propose(CouncilPower::TripBreaker), &m2, &mut ctx).unwrap();
+    execute_si(&approve(2), &m3, &mut ctx).unwrap();
+    assert!(ctx.state.circuit_breaker_active);
+    let ruling =
+        |action_id, ratify| StablecoinInstruction::ProposeCouncilRuling { action_id, ratify };
+    execute_si(&ruling(2, true), &validator, &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.council_actions[&2].status,
+        CouncilActionStatus::Ratified
+    );
+
+    ctx.height = 11;
+    end_block(&mut ctx);
+    for action_id in [0, 1] {
+        assert_eq!(
+            ctx.state.council_actions[&action_id].status,
+            CouncilActionStatus::Lapsed
+        );
+    }
+    assert!(ctx.state.circuit_breaker_active);
+    execute_si(&send, &alice, &mut ctx).unwrap();
+    let lapsed = ChainEvent::CouncilActionLapsed {
+        height: 11,
+        action_id: 0,
+        was_active: true,
+    };
+    assert!(ctx.state.events.contains(&lapsed));
+    assert_eq!(
+        audit_actions(None, &[lapsed], true, ctx.state),
+        vec![AuditAction::CouncilAction {
+            action_id: 0,
+            active: false,
+        }]
+    );
+
+    // Lifting the ratified trip releases the breaker it set.
+    execute_si(&ruling(2, false), &validator, &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.council_actions[&2].status,
+        CouncilActionStatus::Rejected
+    );
+    assert!(!ctx.state.circuit_breaker_active);
+}
+
+#[test]
+fn test_instruction_pauses_combine_governance_and_council() {
+    use lumina_types::state::{CouncilPower, EmergencyCouncil};
+
+    let mut state = GlobalState::default();
+    let (validator, _) = new_sender();
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 100,
+        power: 100,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let member = [171u8; 32];
+    state.emergency_council = EmergencyCouncil {
+        members: vec![member],
+        threshold: 1,
+        sunset_blocks: 10,
+    };
+    let alice = [172u8; 32];
+    state.accounts.entry(alice).or_default().lusd_balance = 1_000;
+    state.total_lusd_supply = 1_000;
+    state.stabilization_pool_balance = 1_000;
+    state.reserve_ratio = 1.0;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+
+    let pause = |kinds: &[&str], paused| StablecoinInstruction::ProposeInstructionPause {
+        kinds: kinds.iter().map(|k| k.to_string()).collect(),
+        paused,
+    };
+    let err = execute_si(&pause(&["Teleport"], true), &validator, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Unknown instruction Teleport");
+    let err = execute_si(
+        &pause(&["ProposeInstructionPause"], true),
+        &validator,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "ProposeInstructionPause cannot be paused");
+
+    // Governance pauses flash mints while payments keep flowing.
+    execute_si(&pause(&["FlashMint"], true), &validator, &mut ctx).unwrap();
+    let flash = StablecoinInstruction::FlashMint {
+        amount: 100,
+        collateral_asset: AssetType::Lumina,
+        collateral_amount: 200,
+        commitment: [9u8; 32],
+    };
+    let err = execute_si(&flash, &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "FlashMint is paused");
+    let pay = StablecoinInstruction::Transfer {
+        to: member,
+        amount: 100,
+        asset: AssetType::LUSD,
+    };
+    execute_si(&pay, &alice, &mut ctx).unwrap();
+
+    // A council pause of the same instruction does not end governance's.
+    let council_pause = StablecoinInstruction::CouncilPropose {
+        power: CouncilPower::PauseInstructions(vec!["FlashMint".to_string(), "Burn".to_string()]),
+    };
+    execute_si(&council_pause, &member, &mut ctx).unwrap();
+    let burn = StablecoinInstruction::Burn {
+        amount: 10,
+        asset: AssetType::LUSD,
+    };
+    let err = execute_si(&burn, &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Burn is paused");
+    ctx.height = 11;
+    end_block(&mut ctx);
+    execute_si(&burn, &alice, &mut ctx).unwrap();
+    let err = execute_si(&flash, &alice, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "FlashMint is paused");
+
+    execute_si(&pause(&["FlashMint"], false), &validator, &mut ctx).unwrap();
+    assert!(ctx.state.paused_instructions.is_empty());
+    execute_si(&flash, &alice, &mut ctx).unwrap();
+}
+
+#[test]
+fn test_inheritance_claim_settles_unless_owner_returns() {
+    let mut state = GlobalState::default();
+    let (owner, kp) = new_sender();
+    let heir = [111u8; 32];
+    let account = state.accounts.entry(owner).or_default();
+    account.lusd_balance = 700;
+    account.lumina_balance = 30;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 10,
+        timestamp: 0,
+    };
+    let designate = StablecoinInstruction::DesignateBeneficiary {
+        beneficiary: heir,
+        inactivity_blocks: MIN_INHERITANCE_INACTIVITY,
+    };
+    execute_si(&designate, &owner, &mut ctx).unwrap();
+    let claim = StablecoinInstruction::ClaimInheritance { owner };
+    ctx.height += MIN_INHERITANCE_INACTIVITY - 1;
+    let err = execute_si(&claim, &heir, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Owner has not been inactive long enough");
+    ctx.height += 1;
+    assert!(execute_si(&claim, &[112u8; 32], &mut ctx).is_err());
+    execute_si(&claim, &heir, &mut ctx).unwrap();
+
+    // Any signed transaction from the owner cancels the pending claim.
+    let mut tx = Transaction {
+        sender: owner,
+        nonce: 0,
+        instruction: StablecoinInstruction::ComputeHealthIndex,
+        signature: Vec::new(),
+        gas_limit: 1000,
+        gas_price: 1,
+    };
+    tx.signature = lumina_crypto::signatures::sign(&kp, &tx.signing_bytes());
+    ctx.height += 1;
+    execute_transaction(&tx, &mut ctx).unwrap();
+    assert!(ctx.state.inheritance_claims.is_empty());
+    assert!(execute_si(&claim, &heir, &mut ctx).is_err());
+
+    ctx.height += MIN_INHERITANCE_INACTIVITY;
+    execute_si(&claim, &heir, &mut ctx).unwrap();
+    ctx.height += INHERITANCE_CHALLENGE_BLOCKS - 1;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.accounts[&owner].lusd_balance, 700);
+    ctx.height += 1;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.accounts[&owner].lusd_balance, 0);
+    assert_eq!(ctx.state.accounts[&heir].lusd_balance, 700);
+    assert_eq!(ctx.state.accounts[&heir].lumina_balance, 30);
+    assert!(ctx.state.inheritance.is_empty() && ctx.state.inheritance_claims.is_empty());
+}
+
+#[test]
+fn test_vesting_locks_unvested_lumina() {
+    let mut state = GlobalState::default();
+    let (funder, team, other) = ([121u8; 32], [122u8; 32], [123u8; 32]);
+    state.accounts.entry(funder).or_default().lumina_balance = 1_500;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 100,
+        timestamp: 0,
+    };
+    let grant = StablecoinInstruction::CreateVestingAccount {
+        beneficiary: team,
+        total: 1_000,
+        cliff_height: 200,
+        end_height: 500,
+    };
+    execute_si(&grant, &funder, &mut ctx).unwrap();
+    assert!(execute_si(&grant, &funder, &mut ctx).is_err());
+    let transfer = |amount| StablecoinInstruction::Transfer {
+        to: other,
+        amount,
+        asset: AssetType::Lumina,
+    };
+
+    // Nothing unlocks before the cliff, then linearly from the start.
+    ctx.height = 199;
+    let err = execute_si(&transfer(1), &team, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "LUMINA is still vesting");
+    ctx.height = 200;
+    assert_eq!(ctx.state.spendable_lumina(&team, 200), 250);
+    assert!(execute_si(&transfer(251), &team, &mut ctx).is_err());
+    execute_si(&transfer(250), &team, &mut ctx).unwrap();
+    let burn = StablecoinInstruction::Burn {
+        amount: 1,
+        asset: AssetType::Lumina,
+    };
+    assert!(execute_si(&burn, &team, &mut ctx).is_err());
+
+    // Fresh deposits are not locked.
+    ctx.state.accounts.get_mut(&team).unwrap().lumina_balance += 40;
+    execute_si(&transfer(40), &team, &mut ctx).unwrap();
+    ctx.height = 500;
+    execute_si(&transfer(750), &team, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&other].lumina_balance, 1_040);
+}
+
+#[test]
+fn test_treasury_collects_mint_fees_and_pays_approved_spends() {
+    let mut state = GlobalState::default();
+    let (validator, minter, grantee) = ([131u8; 32], [132u8; 32], [133u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    state.accounts.entry(minter).or_default().lumina_balance = 1200;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let share = StablecoinInstruction::ProposeTreasuryFeeShare { share_bps: 2_000 };
+    execute_si(&share, &validator, &mut ctx).unwrap();
+
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let mint = StablecoinInstruction::MintSenior {
+        amount: 1000,
+        collateral_amount: 1200,
+        proof: manager.prove_reserves(vec![1200], 1200),
+    };
+    execute_si(&mint, &minter, &mut ctx).unwrap();
+    // 20% of the 50 LUSD mint fee
+    assert_eq!(ctx.state.treasury_balance, 10);
+    assert_eq!(ctx.state.insurance_fund_balance, 40);
+
+    let spend = |amount, payout| StablecoinInstruction::ProposeTreasurySpend {
+        spend: TreasurySpend {
+            recipient: grantee,
+            amount,
+            payout,
+            memo: "audit grant".to_string(),
+        },
+    };
+    let err = execute_si(&spend(11, TreasuryPayout::LumpSum), &validator, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Insufficient treasury balance");
+    assert!(execute_si(&spend(4, TreasuryPayout::LumpSum), &minter, &mut ctx).is_err());
+    execute_si(&spend(4, TreasuryPayout::LumpSum), &validator, &mut ctx).unwrap();
+    assert_eq!(ctx.state.accounts[&grantee].lusd_balance, 4);
+
+    let stream = spend(6, TreasuryPayout::Stream { blocks: 3 });
+    execute_si(&stream, &validator, &mut ctx).unwrap();
+    assert_eq!(ctx.state.treasury_balance, 0);
+    for height in 2..=4 {
+        ctx.height = height;
+        end_block(&mut ctx);
+        assert_eq!(
+            ctx.state.accounts[&grantee].lusd_balance,
+            4 + 2 * (height - 1)
+        );
+    }
+    assert!(ctx.state.treasury_streams.is_empty());
+    let flows = &ctx.state.treasury_flows[&0];
+    assert_eq!((flows.inflow, flows.outflow), (10, 10));
+}
+
+#[test]
+fn test_fee_pool_distributes_by_power_with_commission() {
+    let mut state = GlobalState::default();
+    let (big, small) = ([141u8; 32], [142u8; 32]);
+    for (pubkey, stake) in [(big, 300), (small, 100)] {
+        state.validators.push(ValidatorState {
+            pubkey,
+            stake,
+            power: stake,
+            is_green: false,
+            energy_proof: None,
+            green_verified_at: 0,
+        });
+    }
+    state.fee_pool = 1_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+    let commission = StablecoinInstruction::SetCommission {
+        commission_bps: 1_000,
+    };
+    execute_si(&commission, &big, &mut ctx).unwrap();
+    assert!(execute_si(&commission, &[143u8; 32], &mut ctx).is_err());
+    let err = execute_si(
+        &StablecoinInstruction::ClaimValidatorRewards,
+        &big,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "No validator rewards to claim");
+
+    ctx.height = EPOCH_LENGTH;
+    end_block(&mut ctx);
+    assert_eq!(ctx.state.fee_pool, 0);
+    // 750 to the 3x-power validator: 75 commission, 675 through the index.
+    let rewards = &ctx.state.validator_rewards[&big];
+    assert_eq!((rewards.accrued, rewards.unsettled(300)), (75, 675));
+    execute_si(
+        &StablecoinInstruction::ClaimValidatorRewards,
+        &big,
+        &mut ctx,
+    )
+    .unwrap();
+    execute_si(
+        &StablecoinInstruction::ClaimValidatorRewards,
+        &small,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&big].lusd_balance, 750);
+    assert_eq!(ctx.state.accounts[&small].lusd_balance, 250);
+    assert_eq!(ctx.state.total_lusd_supply, 1_000);
+    assert!(execute_si(
+        &StablecoinInstruction::ClaimValidatorRewards,
+        &big,
+        &mut ctx
+    )
+    .is_err());
+}
+
+#[test]
+fn test_offline_validator_jailed_and_unjailed_after_cooldown() {
+    use instructions::liveness::{JAIL_COOLDOWN_BLOCKS, SIGNED_BLOCKS_WINDOW};
+    let mut state = GlobalState::default();
+    let (live, offline) = ([151u8; 32], [152u8; 32]);
+    for pubkey in [live, offline] {
+        state.validators.push(ValidatorState {
+            pubkey,
+            stake: 10_000,
+            power: 10_000,
+            is_green: false,
+            energy_proof: None,
+            green_verified_at: 0,
+        });
+    }
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+    // Blocks without commit votes are not counted
+    record_liveness(&mut ctx, &[0u8; 32], &[]);
+    assert!(ctx.state.validator_liveness.is_empty());
+
+    for height in 1..SIGNED_BLOCKS_WINDOW {
+        ctx.height = height;
+        record_liveness(&mut ctx, &[0u8; 32], &[live]);
+    }
+    assert_eq!(ctx.state.validators.len(), 2);
+    assert_eq!(
+        ctx.state.validator_liveness[&offline].missed_count,
+        SIGNED_BLOCKS_WINDOW - 1
+    );
+
+    ctx.height = SIGNED_BLOCKS_WINDOW;
+    record_liveness(&mut ctx, &[0u8; 32], &[live]);
+    assert_eq!(ctx.state.validators.len(), 1);
+    assert_eq!(ctx.state.validator_liveness[&live].missed_count, 0);
+    let jailed = &ctx.state.jailed_validators[&offline];
+    assert_eq!(
+        (jailed.validator.stake, jailed.validator.power),
+        (9_900, 9_900)
+    );
+    assert!(ctx.state.events.iter().any(|e| matches!(
+        e,
+        ChainEvent::ValidatorJailed { validator, slashed: 100, .. } if *validator == offline
+    )));
+
+    // The last active validator is never jailed
+    for height in SIGNED_BLOCKS_WINDOW + 1..=3 * SIGNED_BLOCKS_WINDOW {
+        ctx.height = height;
+        record_liveness(&mut ctx, &[0u8; 32], &[offline]);
+    }
+    assert_eq!(ctx.state.validators.len(), 1);
+    assert!(ctx.state.validator_liveness[&live].missed_count > SIGNED_BLOCKS_WINDOW / 2);
+
+    let err = execute_si(&StablecoinInstruction::Unjail, &offline, &mut ctx).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("Validator is jailed until height"));
+    assert!(execute_si(&StablecoinInstruction::Unjail, &live, &mut ctx).is_err());
+    ctx.height = SIGNED_BLOCKS_WINDOW + JAIL_COOLDOWN_BLOCKS;
+    execute_si(&StablecoinInstruction::Unjail, &offline, &mut ctx).unwrap();
+    assert!(ctx.state.jailed_validators.is_empty());
+    assert!(ctx.state.validators.iter().any(|v| v.pubkey == offline));
+    assert!(!ctx.state.validator_liveness.contains_key(&offline));
+}
+
+#[test]
+fn test_health_params_are_governed_and_components_recorded() {
+    let mut state = GlobalState {
+        total_lusd_supply: 1_000_000,
+        reserve_ratio: 1.0,
+        insurance_fund_balance: 50_000,
+        ..Default::default()
+    };
+    let validator = [161u8; 32];
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let compute = StablecoinInstruction::ComputeHealthIndex;
+    execute_si(&compute, &validator, &mut ctx).unwrap();
+    let components = ctx.state.health_components;
+    assert_eq!(
+        (components.reserve, components.peg, components.breaker),
+        (1_500, 2_500, 1_500)
+    );
+    assert_eq!(
+        (components.insurance, components.green, components.custodian),
+        (1_500, 500, 0)
+    );
+    assert_eq!(ctx.state.health_index, 7_500);
+
+    ctx.state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 1,
+        power: 1,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let mut params = ctx.state.health_params.clone();
+    params.reserve_weight = 2_000;
+    let propose = |params: &HealthIndexParams| StablecoinInstruction::ProposeHealthParams {
+        params: params.clone(),
+    };
+    let err = execute_si(&propose(&params), &validator, &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Health index weights must total 10000");
+    params.custodian_weight = 1_500;
+    assert!(execute_si(&propose(&params), &[162u8; 32], &mut ctx).is_err());
+    execute_si(&propose(&params), &validator, &mut ctx).unwrap();
+    assert_eq!(ctx.state.health_params, params);
+
+    ctx.state.current_epoch = 1;
+    execute_si(&compute, &validator, &mut ctx).unwrap();
+    // Reserve earns half its new weight; the lone validator has no green proof
+    assert_eq!(ctx.state.health_components.reserve, 1_000);
+    assert_eq!(ctx.state.health_components.green, 0);
+    assert_eq!(ctx.state.health_index, 6_500);
+    assert_eq!(ctx.state.health_history[&0].total(), 7_500);
+    assert_eq!(ctx.state.health_history[&1].total(), 6_500);
+}
+
+#[test]
+fn test_redemption_fee_comes_out_of_released_collateral() {
+    use lumina_types::state::PoolAsset;
+    let mut state = GlobalState::default();
+    let sender = [172u8; 32];
+    state.accounts.entry(sender).or_default().lumina_balance = 1_200;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 2_000_000);
+    state.params.redeem_fee_bps = 100;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let mint = StablecoinInstruction::MintSenior {
+        amount: 1_000,
+        collateral_amount: 1_200,
+        proof: manager.prove_reserves(vec![1_200], 1_200),
+    };
+    execute_si(&mint, &sender, &mut ctx).unwrap();
+    let insurance = ctx.state.insurance_fund_balance;
+
+    // 500 of the 950 minted releases 631 LUMINA; 1% of it stays behind
+    execute_si(
+        &StablecoinInstruction::RedeemSenior { amount: 500 },
+        &sender,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&sender].lumina_balance, 625);
+    assert_eq!(ctx.state.pool_composition[&PoolAsset::Lumina], 569);
+    assert_eq!(ctx.state.stabilization_pool_balance, 700);
+    assert_eq!(ctx.state.insurance_fund_balance, insurance + 5);
+
+    // Redeeming the position directly withholds the fee the same way
+    execute_si(
+        &StablecoinInstruction::RedeemPosition {
+            position_id: 0,
+            amount: 450,
+        },
+        &sender,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&sender].lumina_balance, 625 + 563);
+    assert!(!ctx.state.pool_composition.contains_key(&PoolAsset::Lumina));
+    assert_eq!(ctx.state.stabilization_pool_balance, 250);
+    assert_eq!(ctx.state.insurance_fund_balance, insurance + 5 + 4);
+}
+
+#[test]
+fn test_redemption_cap_queues_redemptions_past_the_block_share() {
+    let mut state = GlobalState::default();
+    let sender = [173u8; 32];
+    state.accounts.entry(sender).or_default().lumina_balance = 1_200;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 2_000_000);
+    state.stabilizer.redeem_cap_bps = 2_000;
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let mint = StablecoinInstruction::MintSenior {
+        amount: 1_000,
+        collateral_amount: 1_200,
+        proof: manager.prove_reserves(vec![1_200], 1_200),
+    };
+    execute_si(&mint, &sender, &mut ctx).unwrap();
+    let supply = ctx.state.total_lusd_supply;
+
+    // A fifth of the supply may settle this block; the rest queues
+    for amount in [100, 150] {
+        execute_si(
+            &StablecoinInstruction::RedeemSenior { amount },
+            &sender,
+            &mut ctx,
+        )
+        .unwrap();
+    }
+    assert_eq!(ctx.state.stabilizer.block_redeemed, 100);
+    assert_eq!(ctx.state.total_lusd_supply, supply - 100);
+    assert_eq!(ctx.state.fair_redeem_queue.len(), 1);
+    let drain = StablecoinInstruction::FairRedeemQueue { batch_size: 10 };
+    execute_si(&drain, &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.fair_redeem_queue.len(), 1);
+
+    // The next block's share covers the queued redemption
+    ctx.height = 2;
+    begin_block(&mut ctx);
+    assert_eq!(ctx.state.stabilizer.block_redeemed, 0);
+    execute_si(&drain, &sender, &mut ctx).unwrap();
+    assert!(ctx.state.fair_redeem_queue.is_empty());
+    assert_eq!(ctx.state.stabilizer.block_redeemed, 150);
+    assert_eq!(ctx.state.total_lusd_supply, supply - 250);
+}
+
+#[test]
+fn test_pool_composition_tracks_collateral_and_attested_fiat() {
+    use lumina_types::state::PoolAsset;
+    let mut state = GlobalState::default();
+    let sender = [171u8; 32];
+    state.accounts.entry(sender).or_default().lumina_balance = 1_200;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 500_000);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 100,
+    };
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let mint = StablecoinInstruction::MintSenior {
+        amount: 1_000,
+        collateral_amount: 1_200,
+        proof: manager.prove_reserves(vec![1_200], 1_200),
+    };
+    execute_si(&mint, &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.pool_composition[&PoolAsset::Lumina], 1_200);
+    assert_eq!(ctx.state.pool_valuation[&PoolAsset::Lumina], 600);
+
+    // Redeeming half the position releases half its collateral
+    let redeem = StablecoinInstruction::RedeemPosition {
+        position_id: 0,
+        amount: 475,
+    };
+    execute_si(&redeem, &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.pool_composition[&PoolAsset::Lumina], 600);
+    assert_eq!(ctx.state.pool_valuation[&PoolAsset::Lumina], 300);
+
+    let por = StablecoinInstruction::SubmitZkPoR {
+        proof: manager.prove_reserves(vec![1_000], 1_000),
+        total_reserves: 1_000,
+        timestamp: 1,
+    };
+    execute_si(&por, &sender, &mut ctx).unwrap();
+    assert_eq!(ctx.state.pool_valuation[&PoolAsset::FiatAttested], 1_000);
+    assert_eq!(ctx.state.pool_valuation.len(), 2);
+}
+
+#[test]
+fn test_concentration_limits_reject_mints_and_rotations() {
+    use lumina_types::state::{ConcentrationBreach, ConcentrationLimits, PoolAsset};
+    let mut state = GlobalState::default();
+    let (validator, minter) = ([181u8; 32], [182u8; 32]);
+    let (big, small) = ([183u8; 32], [184u8; 32]);
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 1,
+        power: 1,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    for (pubkey, reserve_balance) in [(big, 600), (small, 400)] {
+        state.custodians.push(CustodianState {
+            pubkey,
+            stake: 100,
+            mpc_pubkeys: Vec::new(),
+            registered_height: 0,
+            zone_id: 1,
+            reserve_balance,
+        });
+    }
+    state.accounts.entry(minter).or_default().lumina_balance = 1_000;
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 300_000,
+        timestamp: 100,
+    };
+    let manager = lumina_crypto::zk::ZkManager::setup();
+    let por = StablecoinInstruction::SubmitZkPoR {
+        proof: manager.prove_reserves(vec![1_000], 1_000),
+        total_reserves: 1_000,
+        timestamp: 1,
+    };
+    execute_si(&por, &validator, &mut ctx).unwrap();
+
+    let limits =
+        |max_custodian_bps, max_asset_bps| StablecoinInstruction::ProposeConcentrationLimits {
+            limits: ConcentrationLimits {
+                max_custodian_bps,
+                max_asset_bps,
+            },
+        };
+    assert!(execute_si(&limits(0, 3_000), &validator, &mut ctx).is_err());
+    execute_si(&limits(7_000, 3_000), &validator, &mut ctx).unwrap();
+
+    // 500 LUMINA would be a third of the pool; 400 stays under 30%
+    let mint = |collateral_amount| StablecoinInstruction::MintSenior {
+        amount: collateral_amount,
+        collateral_amount,
+        proof: manager.prove_reserves(vec![collateral_amount], collateral_amount),
+    };
+    let err = execute_si(&mint(500), &minter, &mut ctx).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Mint would put lumina above 3000 bps of reserves"
+    );
+    assert_eq!(ctx.state.accounts[&minter].lumina_balance, 1_000);
+    execute_si(&mint(400), &minter, &mut ctx).unwrap();
+
+    let rotate = |amount| StablecoinInstruction::ProposeReserveRotation {
+        from_custodian: small,
+        to_custodian: big,
+        amount,
+    };
+    assert!(execute_si(&rotate(200), &small, &mut ctx).is_err());
+    execute_si(&rotate(100), &small, &mut ctx).unwrap();
+
+    // Attested fiat is still over 30% of the pool, so the health index flags it
+    let compute = StablecoinInstruction::ComputeHealthIndex;
+    execute_si(&compute, &validator, &mut ctx).unwrap();
+    assert_eq!(
+        ctx.state.concentration_breaches.iter().collect::<Vec<_>>(),
+        vec![&ConcentrationBreach::Asset(PoolAsset::FiatAttested)]
+    );
+}
+
+#[test]
+fn test_randomness_beacon_mixes_revealed_commitments_each_epoch() {
+    use lumina_types::beacon;
+
+    let mut state = GlobalState::default();
+    let validators = [[101u8; 32], [102u8; 32], [103u8; 32]];
+    for pubkey in validators {
+        state.validators.push(ValidatorState {
+            pubkey,
+            stake: 10,
+            power: 10,
+            is_green: false,
+            energy_proof: None,
+            green_verified_at: 0,
+        });
+    }
+    let secret = |v: &[u8; 32], epoch: u64| [v[0].wrapping_add(epoch as u8); 32];
+    let commit = |v: &[u8; 32], epoch| StablecoinInstruction::CommitRandomness {
+        epoch,
+        commitment: beacon::commitment(epoch, v, &secret(v, epoch)),
+    };
+    let reveal = |v: &[u8; 32], epoch| StablecoinInstruction::RevealRandomness {
+        epoch,
+        secret: secret(v, epoch),
+    };
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 10,
+        timestamp: 0,
+    };
+    for v in &validators {
+        execute_si(&commit(v, 0), v, &mut ctx).unwrap();
+    }
+    let err = execute_si(&commit(&[7u8; 32], 0), &[7u8; 32], &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Only validators contribute randomness");
+    let err = execute_si(&reveal(&validators[0], 0), &validators[0], &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Reveal phase has not started");
+
+    ctx.height = EPOCH_LENGTH - 1;
+    let err = execute_si(&commit(&validators[0], 0), &validators[0], &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Commit phase has ended");
+    let wrong = StablecoinInstruction::RevealRandomness {
+        epoch: 0,
+        secret: [0u8; 32],
+    };
+    let err = execute_si(&wrong, &validators[1], &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Secret does not match the commitment");
+    for v in &validators[..2] {
+        execute_si(&reveal(v, 0), v, &mut ctx).unwrap();
+    }
+    ctx.height = EPOCH_LENGTH;
+    end_block(&mut ctx);
+    let first = ctx.state.randomness_beacons[&0].clone();
+    assert_eq!(first.reveals, 2);
+    assert!(ctx.state.events.iter().any(|e| matches!(
+        e,
+        ChainEvent::RandomnessFinalized { epoch: 0, withheld, .. } if *withheld == [validators[2]]
+    )));
+    // Outcomes fixed in epoch 0 wait for epoch 1's beacon.
+    assert_eq!(ctx.state.randomness(0, b"lottery"), None);
+
+    ctx.height = EPOCH_LENGTH + 1;
+    let err = execute_si(&commit(&validators[0], 0), &validators[0], &mut ctx).unwrap_err();
+    assert_eq!(err.to_string(), "Beacon round is for epoch 1");
+    execute_si(&commit(&validators[0], 1), &validators[0], &mut ctx).unwrap();
+    ctx.height = 2 * EPOCH_LENGTH;
+    execute_si(&reveal(&validators[0], 1), &validators[0], &mut ctx).unwrap();
+    end_block(&mut ctx);
+
+    let output = beacon::mix(
+        1,
+        &first.output,
+        &[(validators[0], secret(&validators[0], 1))],
+    );
+    assert_eq!(ctx.state.randomness_beacons[&1].output, output);
+    let drawn = ctx.state.randomness(0, b"lottery").unwrap();
+    assert_eq!(drawn, beacon::derive(&output, b"lottery"));
+    assert_ne!(Some(drawn), ctx.state.randomness(0, b"raffle"));
+    assert!(ctx.state.beacon_round.is_empty());
+}
+
+#[test]
+fn test_stabilizer_steps_fees_and_savings_rate_within_bounds() {
+    use lumina_types::state::{BpsRange, ControlledParam, StabilizerBounds};
+
+    let mut state = GlobalState::default();
+    let validator = [104u8; 32];
+    state.validators.push(ValidatorState {
+        pubkey: validator,
+        stake: 10,
+        power: 10,
+        is_green: false,
+        energy_proof: None,
+        green_verified_at: 0,
+    });
+    let bounds = StabilizerBounds {
+        mint_fee_bps: BpsRange { min: 400, max: 700 },
+        redeem_fee_bps: BpsRange { min: 0, max: 200 },
+        savings_rate_bps: BpsRange { min: 300, max: 800 },
+        redeem_cap_bps: BpsRange {
+            min: 2_000,
+            max: 10_000,
+        },
+        step_bps: 100,
+        interval_blocks: 10,
+        target_health: 0,
+    };
+    let mut ctx = ExecutionContext {
+        state: &mut state,
+        height: 1,
+        timestamp: 0,
+    };
+    let inverted = StabilizerBounds {
+        redeem_fee_bps: BpsRange { min: 300, max: 200 },
+        ..bounds.clone()
+    };
+    let err = execute_si(
+        &StablecoinInstruction::ProposeStabilizerBounds { bounds: inverted },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Bound minimum exceeds its maximum");
+    let uncapped = StabilizerBounds {
+        redeem_cap_bps: BpsRange {
+            min: 0,
+            max: 10_000,
+        },
+        ..bounds.clone()
+    };
+    let err = execute_si(
+        &StablecoinInstruction::ProposeStabilizerBounds { bounds: uncapped },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), "Redemption cap minimum must be non-zero");
+    ctx.state.stabilizer.bounds = bounds;
+    let levels = |s: &GlobalState| {
+        (
+            s.params.mint_fee_bps,
+            s.params.redeem_fee_bps,
+            s.stabilizer.savings_rate_bps,
+            s.stabilizer.redeem_cap_bps,
+        )
+    };
+
+    // A depegged LUSD is stress: every parameter steps up and the
+    // redemption cap tightens.
+    ctx.state
+        .oracle_prices
+        .insert("LUSD-USD".to_string(), 980_000);
+    ctx.height = 10;
+    begin_block(&mut ctx);
+    end_block(&mut ctx);
+    assert_eq!(levels(ctx.state), (600, 100, 600, 9_900));
+    assert!(ctx.state.events.iter().any(|e| matches!(
+        e,
+        ChainEvent::ParameterAdjusted {
+            param: ControlledParam::SavingsRate,
+            from_bps: 500,
+            to_bps: 600,
+            regime: OracleRegime::Volatile,
+            ..
+        }
+    )));
+    ctx.height = 11;
+    end_block(&mut ctx);
+    assert_eq!(levels(ctx.state), (600, 100, 600, 9_900));
+
+    // Back on peg, everything relaxes to its floor and stays there.
+    ctx.state
+        .oracle_prices
+        .insert("LUSD-USD".to_string(), 1_000_000);
+    for height in [20, 30, 40, 50] {
+        ctx.height = height;
+        begin_block(&mut ctx);
+        end_block(&mut ctx);
+    }
+    assert_eq!(levels(ctx.state), (400, 0, 300, 10_000));
+    assert!(!ctx
+        .state
+        .events
+        .iter()
+        .any(|e| matches!(e, ChainEvent::ParameterAdjusted { .. })));
+
+    // Yield tokens accrue at the adapted rate: 3% of 10000 over a year.
+    ctx.state
+        .accounts
+        .entry(validator)
+        .or_default()
+        .lusd_balance = 10_000;
+    ctx.height = 100;
+    execute_si(
+        &StablecoinInstruction::WrapToYieldToken {
+            amount: 10_000,
+            maturity_blocks: 3_153_600,
+        },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    ctx.height += 3_153_600;
+    execute_si(
+        &StablecoinInstruction::UnwrapYieldToken { token_id: 0 },
+        &validator,
+        &mut ctx,
+    )
+    .unwrap();
+    assert_eq!(ctx.state.accounts[&validator].lusd_balance, 10_270);
+}
+
+#[cfg(feature = "strict-invariants")]
+#[test]
+#[should_panic(
+    expected = "Invariant violated by Transfer: LUSD supply moved 5 against its holdings"
+)]
+fn test_strict_invariants_catch_unbacked_supply() {
+    let mut state = GlobalState::default();
+    state.accounts.entry([1u8; 32]).or_default().lusd_balance = 100;
+    state.total_lusd_supply = 100;
+    let before = invariants::Snapshot::take(&state);
+    state.total_lusd_supply += 5;
+    let transfer = StablecoinInstruction::Transfer {
+        to: [2u8; 32],
+        amount: 1,
+        asset: AssetType::LUSD,
+    };
+    invariants::check(&before, &state, &transfer);
 }
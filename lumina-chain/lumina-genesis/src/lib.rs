@@ -1,5 +1,36 @@
-use lumina_types::state::{AccountState, GlobalState, ValidatorState};
-use std::collections::HashMap;
+use anyhow::{bail, Context, Result};
+use lumina_types::instruction::MAX_RESERVE_ZONES;
+use lumina_types::state::{
+    AccountState, GlobalState, ValidatorState, VestingSchedule, LJUN_RATE_SCALE,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// A team or investor LUMINA allocation minted at genesis and vesting from
+/// height 0.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GenesisVesting {
+    pub beneficiary: [u8; 32],
+    pub total: u64,
+    pub cliff_height: u64,
+    pub end_height: u64,
+}
+
+/// Keys holding a protocol role, and settings governance would otherwise
+/// have to propose, from height 0.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenesisAuthorities {
+    /// Oracles whose proofs `MintWithCreditScore` accepts
+    pub credit_oracles: Vec<[u8; 32]>,
+    pub screening_oracles: Vec<[u8; 32]>,
+    /// Officers who assign KYC tiers
+    pub compliance_officers: Vec<[u8; 32]>,
+    /// Attesters who anchor institution credentials
+    pub credential_attesters: Vec<[u8; 32]>,
+    /// Reserve weight per custodian zone that `GeoRebalance` allocates by
+    pub zone_reserve_weights: BTreeMap<u32, u64>,
+}
 
 /// Create the genesis state for LuminaChain.
 /// This initializes the very first state of the chain with:
@@ -8,6 +39,7 @@ use std::collections::HashMap;
 /// - Oracle price bootstraps
 /// - Insurance fund seeded
 /// - Velocity reward pool initialized
+/// - 20% of mint fees routed to the treasury
 pub fn create_genesis_state() -> GlobalState {
     let mut accounts = HashMap::new();
     let mut validators = Vec::new();
@@ -20,6 +52,7 @@ pub fn create_genesis_state() -> GlobalState {
         power: 1_000_000,
         is_green: false,
         energy_proof: None,
+        green_verified_at: 0,
     });
 
     // Deployer account with initial Lumina gas tokens
@@ -34,16 +67,106 @@ pub fn create_genesis_state() -> GlobalState {
 
     // Bootstrap oracle prices (fixed-point 1e6)
     let mut oracle_prices = HashMap::new();
-    oracle_prices.insert("ETH-USD".to_string(), 3000_000_000);
-    oracle_prices.insert("BTC-USD".to_string(), 90000_000_000);
+    oracle_prices.insert("ETH-USD".to_string(), 3_000_000_000);
+    oracle_prices.insert("BTC-USD".to_string(), 90_000_000_000);
     oracle_prices.insert("LUSD-USD".to_string(), 1_000_000); // $1.00 peg
 
     GlobalState {
         accounts,
         reserve_ratio: 1.0,
+        ljun_exchange_rate: LJUN_RATE_SCALE,
         oracle_prices,
         validators,
         health_index: 10000, // Perfect health at genesis
+        treasury_fee_share_bps: 2_000,
         ..Default::default()
     }
 }
+
+/// Genesis state with `allocations` pre-seeded as vesting accounts.
+pub fn create_genesis_state_with_vesting(allocations: &[GenesisVesting]) -> Result<GlobalState> {
+    let mut state = create_genesis_state();
+    for grant in allocations {
+        if grant.total == 0 || grant.end_height == 0 || grant.cliff_height > grant.end_height {
+            bail!("Invalid genesis vesting schedule");
+        }
+        let account = state.accounts.entry(grant.beneficiary).or_default();
+        account.lumina_balance = account
+            .lumina_balance
+            .checked_add(grant.total)
+            .ok_or_else(|| anyhow::anyhow!("Genesis LUMINA overflow"))?;
+        state
+            .vesting
+            .entry(grant.beneficiary)
+            .or_default()
+            .push(VestingSchedule {
+                funder: [0u8; 32],
+                total: grant.total,
+                start_height: 0,
+                cliff_height: grant.cliff_height,
+                end_height: grant.end_height,
+            });
+    }
+    Ok(state)
+}
+
+/// Grant `authorities` their roles in a genesis `state`.
+pub fn apply_genesis_authorities(
+    state: &mut GlobalState,
+    authorities: &GenesisAuthorities,
+) -> Result<()> {
+    for oracle in &authorities.credit_oracles {
+        if state.trusted_credit_oracles.contains(oracle) {
+            bail!("Duplicate genesis credit oracle");
+        }
+        state.trusted_credit_oracles.push(*oracle);
+    }
+    for oracle in &authorities.screening_oracles {
+        if state
+            .screening_oracles
+            .insert(*oracle, Default::default())
+            .is_some()
+        {
+            bail!("Duplicate genesis screening oracle");
+        }
+    }
+    for officer in &authorities.compliance_officers {
+        if !state.compliance_officers.insert(*officer) {
+            bail!("Duplicate genesis compliance officer");
+        }
+    }
+    for attester in &authorities.credential_attesters {
+        if !state.credential_attesters.insert(*attester) {
+            bail!("Duplicate genesis credential attester");
+        }
+    }
+    if authorities.zone_reserve_weights.len() > MAX_RESERVE_ZONES
+        || authorities.zone_reserve_weights.contains_key(&0)
+    {
+        bail!("Invalid genesis zone reserve weights");
+    }
+    state.params.zone_reserve_weights = authorities.zone_reserve_weights.clone();
+    Ok(())
+}
+
+/// Genesis state from the optional `--genesis-vesting` and
+/// `--genesis-authorities` JSON files. Every node of a chain, and any
+/// indexer following it, must build genesis from the same files.
+pub fn load_genesis_state(vesting: Option<&str>, authorities: Option<&str>) -> Result<GlobalState> {
+    let mut genesis = match vesting {
+        Some(path) => {
+            let file = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+            let allocations: Vec<GenesisVesting> =
+                serde_json::from_slice(&file).context("Invalid genesis vesting file")?;
+            create_genesis_state_with_vesting(&allocations)?
+        }
+        None => create_genesis_state(),
+    };
+    if let Some(path) = authorities {
+        let file = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+        let authorities: GenesisAuthorities =
+            serde_json::from_slice(&file).context("Invalid genesis authorities file")?;
+        apply_genesis_authorities(&mut genesis, &authorities)?;
+    }
+    Ok(genesis)
+}
@@ -0,0 +1,195 @@
+//! Follows a node's canonical chain over its HTTP API, handing each block to
+//! the local consensus service to verify, execute and index.
+
+use anyhow::{anyhow, bail, Result};
+use lumina_storage::db::Storage;
+use lumina_types::block::Block;
+use lumina_types::state::GlobalState;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Fed block hashes kept to link fetched blocks to blocks still queued for
+/// import; older parents are found in storage.
+const FED_WINDOW: u64 = 512;
+
+/// The next height to fetch, stepping back when the node has reorged below
+/// the blocks already fed.
+pub struct Cursor {
+    next: u64,
+    fed: BTreeMap<u64, [u8; 32]>,
+}
+
+impl Cursor {
+    pub fn new(tip: u64) -> Self {
+        Self {
+            next: tip + 1,
+            fed: BTreeMap::new(),
+        }
+    }
+
+    pub fn next(&self) -> u64 {
+        self.next
+    }
+
+    /// Whether `block`, fetched at `next()`, extends a block already fed or
+    /// stored. If not, the node's chain forked below it and the cursor steps
+    /// back to fetch the fork from where it diverged.
+    pub fn accept(&mut self, block: &Block, stored: impl Fn(&[u8; 32]) -> bool) -> bool {
+        let height = block.header.height;
+        let parent = block.header.prev_hash;
+        let extends =
+            height <= 1 || self.fed.get(&(height - 1)) == Some(&parent) || stored(&parent);
+        if !extends {
+            self.next = height.saturating_sub(1).max(1);
+            return false;
+        }
+        self.fed.insert(height, block.hash());
+        self.fed.retain(|h, _| h + FED_WINDOW > height);
+        self.next = height + 1;
+        true
+    }
+}
+
+/// Refuse to follow a chain whose first block does not execute on
+/// `genesis`, which otherwise fails every import.
+pub async fn check_genesis(genesis: GlobalState, block: &Block) -> Result<()> {
+    let (_, commit) = lumina_consensus::execute_block(genesis, block).await?;
+    if commit.app_hash != block.header.state_root {
+        bail!("Block 1 does not follow this genesis; pass the node's --genesis-vesting and --genesis-authorities files");
+    }
+    Ok(())
+}
+
+async fn fetch(client: &reqwest::Client, node_url: &str, height: u64) -> Result<Option<Block>> {
+    let url = format!("{}/block/{}", node_url.trim_end_matches('/'), height);
+    Ok(client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// Poll `node_url` for each block past the local tip and send it to
+/// consensus, waiting `poll` whenever the node has no newer block.
+pub async fn run(
+    node_url: String,
+    storage: Arc<Storage>,
+    genesis: GlobalState,
+    blocks: mpsc::Sender<Block>,
+    poll: Duration,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let tip = storage.load_tip()?.map_or(0, |(h, _)| h);
+    let mut cursor = Cursor::new(tip);
+    info!("Following {} from height {}", node_url, cursor.next());
+    loop {
+        let height = cursor.next();
+        let block = match fetch(&client, &node_url, height).await {
+            Ok(Some(block)) => block,
+            Ok(None) => {
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+            Err(e) => {
+                warn!("Fetching block {} from {} failed: {}", height, node_url, e);
+                tokio::time::sleep(poll).await;
+                continue;
+            }
+        };
+        if block.header.height != height {
+            bail!(
+                "Node returned block {} for height {}",
+                block.header.height,
+                height
+            );
+        }
+        if height == 1 {
+            check_genesis(genesis.clone(), &block).await?;
+        }
+        let stored = |hash: &[u8; 32]| storage.load_block_meta(hash).ok().flatten().is_some();
+        if !cursor.accept(&block, stored) {
+            info!("Node chain forked below height {}; stepping back", height);
+            continue;
+        }
+        blocks
+            .send(block)
+            .await
+            .map_err(|_| anyhow!("Consensus stopped"))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::block::BlockHeader;
+
+    async fn next_block(parent: &GlobalState, height: u64, prev_hash: [u8; 32]) -> Block {
+        let mut block = Block {
+            header: BlockHeader {
+                height,
+                prev_hash,
+                transactions_root: Block::transactions_root(&[]),
+                state_root: [0u8; 32],
+                timestamp: height,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions: Vec::new(),
+            votes: Vec::new(),
+        };
+        let (next, commit) = lumina_consensus::execute_block(parent.clone(), &block)
+            .await
+            .unwrap();
+        block.header.state_root = commit.app_hash;
+        block.header.validator_set_hash = next.validator_set_hash();
+        block.header.audit_hash = next.audit_head;
+        block
+    }
+
+    #[tokio::test]
+    async fn first_block_must_follow_the_configured_genesis() {
+        let genesis = lumina_genesis::create_genesis_state();
+        let block = next_block(&genesis, 1, [0u8; 32]).await;
+        // Blocks arrive as the node's `/block/:height` JSON.
+        let block: Block = serde_json::from_value(serde_json::to_value(&block).unwrap()).unwrap();
+        check_genesis(genesis.clone(), &block).await.unwrap();
+
+        let mut other = genesis;
+        other.accounts.entry([7u8; 32]).or_default().lumina_balance = 1;
+        assert!(check_genesis(other, &block).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cursor_steps_back_to_the_fork_point() {
+        let genesis = GlobalState::default();
+        let one = next_block(&genesis, 1, [0u8; 32]).await;
+        let two = next_block(&genesis, 2, one.hash()).await;
+        let mut fork_two = next_block(&genesis, 2, one.hash()).await;
+        fork_two.header.timestamp = 99;
+        let fork_three = next_block(&genesis, 3, fork_two.hash()).await;
+
+        let mut cursor = Cursor::new(0);
+        let nothing_stored = |_: &[u8; 32]| false;
+        assert!(cursor.accept(&one, nothing_stored));
+        assert!(cursor.accept(&two, nothing_stored));
+        assert_eq!(cursor.next(), 3);
+
+        // The node reorged onto a fork of height 2.
+        assert!(!cursor.accept(&fork_three, nothing_stored));
+        assert_eq!(cursor.next(), 2);
+        assert!(cursor.accept(&fork_two, nothing_stored));
+        assert!(cursor.accept(&fork_three, nothing_stored));
+        assert_eq!(cursor.next(), 4);
+
+        // After a restart the parent is found in storage instead.
+        let mut resumed = Cursor::new(2);
+        assert!(resumed.accept(&fork_three, |hash| *hash == fork_two.hash()));
+    }
+}
@@ -0,0 +1,120 @@
+//! Explorer indexer: follows a node's chain into its own database, verifying
+//! and indexing every block (instructions, account transactions, memo
+//! transfers, events, supply stats), and serves the query API from it so
+//! validators can run `--lean`.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::info;
+
+mod follow;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// API of the node to follow
+    #[arg(long, default_value = "http://localhost:3000")]
+    node_url: String,
+    #[arg(short, long, default_value = "./indexer-data")]
+    data_dir: String,
+    /// Address the query API listens on
+    #[arg(long, default_value = "0.0.0.0:3100")]
+    listen: SocketAddr,
+    /// Chain id of the followed network, reported by the API
+    #[arg(long, default_value = "lumina-mainnet")]
+    chain_id: String,
+    /// The followed node's genesis vesting file
+    #[arg(long)]
+    genesis_vesting: Option<String>,
+    /// The followed node's genesis authorities file
+    #[arg(long)]
+    genesis_authorities: Option<String>,
+    /// Wait between polls once caught up with the node
+    #[arg(long, default_value_t = 1000)]
+    poll_millis: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+    let args = Args::parse();
+
+    let storage = Arc::new(
+        lumina_storage::db::Storage::new(&args.data_dir).context("Failed to initialize storage")?,
+    );
+    info!("Storage initialized at {}", args.data_dir);
+    storage
+        .migrate_stored_states()
+        .context("Failed to migrate stored states")?;
+
+    let genesis = match storage.load_state_by_height(0)? {
+        Some(genesis) => genesis,
+        None => {
+            let genesis = lumina_genesis::load_genesis_state(
+                args.genesis_vesting.as_deref(),
+                args.genesis_authorities.as_deref(),
+            )?;
+            storage.save_state(&genesis)?;
+            storage.save_state_at_height(0, &genesis)?;
+            storage.save_tip(0, [0u8; 32])?;
+            genesis
+        }
+    };
+    let state = storage.load_state()?;
+
+    let network_name = lumina_network::NetworkConfig {
+        chain_id: args.chain_id.clone(),
+        genesis_hash: genesis.root_hash(),
+        allowlist: None,
+    }
+    .network_name();
+
+    let (snapshot_tx, _) = watch::channel(Arc::new(state.clone()));
+    let shared_state = Arc::new(RwLock::new(state));
+
+    // Blocks come only from the followed node, and nothing is broadcast.
+    let (network_tx, _) = mpsc::channel(1);
+    let (_consensus_tx, consensus_tx_rx) = mpsc::channel(1);
+    let (block_sender, block_receiver) = mpsc::channel(256);
+    let service = lumina_consensus::ConsensusService::new(
+        shared_state.clone(),
+        snapshot_tx.clone(),
+        storage.clone(),
+        network_tx,
+        consensus_tx_rx,
+        block_receiver,
+        std::path::Path::new(&args.data_dir).join("consensus.wal"),
+    )
+    .with_block_production(false);
+    let consensus_status = service.status();
+    tokio::spawn(service.run());
+
+    let (_, peers_rx) = watch::channel(Vec::new());
+    let (_, rule_states_rx) = watch::channel(lumina_api::RuleStates::new());
+    tokio::spawn(lumina_api::start_server(
+        shared_state,
+        snapshot_tx,
+        storage.clone(),
+        None,
+        network_name,
+        peers_rx,
+        consensus_status,
+        rule_states_rx,
+        args.listen,
+    ));
+
+    tokio::select! {
+        result = follow::run(
+            args.node_url,
+            storage,
+            genesis,
+            block_sender,
+            Duration::from_millis(args.poll_millis),
+        ) => result,
+        result = tokio::signal::ctrl_c() => Ok(result?),
+    }
+}
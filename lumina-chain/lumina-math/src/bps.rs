@@ -0,0 +1,76 @@
+use crate::{div_round, fixed_from_f64, MathError, Rounding};
+
+/// Basis points in a whole.
+pub const BPS_SCALE: u64 = 10_000;
+
+/// A share of a whole in basis points, at most 100%. Shares above that,
+/// such as collateral requirements, are a [`Ratio`](crate::Ratio).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Bps(u64);
+
+impl Bps {
+    pub const ZERO: Bps = Bps(0);
+    pub const FULL: Bps = Bps(BPS_SCALE);
+
+    pub const fn new(bps: u64) -> Result<Bps, MathError> {
+        if bps > BPS_SCALE {
+            return Err(MathError::BpsOutOfRange(bps));
+        }
+        Ok(Bps(bps))
+    }
+
+    /// A share known when compiling; out of range fails the build.
+    pub const fn constant(bps: u64) -> Bps {
+        assert!(bps <= BPS_SCALE, "bps above 100%");
+        Bps(bps)
+    }
+
+    /// A share capped at 100%.
+    pub const fn saturating(bps: u64) -> Bps {
+        if bps > BPS_SCALE {
+            Bps(BPS_SCALE)
+        } else {
+            Bps(bps)
+        }
+    }
+
+    /// The share nearest a fraction of one, rounding half to even and
+    /// capped at 100%.
+    pub fn from_f64(fraction: f64) -> Bps {
+        Bps::saturating(fixed_from_f64(fraction, BPS_SCALE))
+    }
+
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// The rest of the whole.
+    pub const fn complement(self) -> Bps {
+        Bps(BPS_SCALE - self.0)
+    }
+
+    /// This share of `amount`; never more than `amount`.
+    pub const fn of(self, amount: u64, rounding: Rounding) -> u64 {
+        div_round(amount as u128 * self.0 as u128, BPS_SCALE as u128, rounding) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_stay_within_the_whole() {
+        assert_eq!(Bps::new(10_001), Err(MathError::BpsOutOfRange(10_001)));
+        assert_eq!(Bps::saturating(20_000), Bps::FULL);
+        assert_eq!(Bps::constant(2_500).complement(), Bps::constant(7_500));
+        assert_eq!(Bps::FULL.of(u64::MAX, Rounding::Up), u64::MAX);
+        assert_eq!(Bps::constant(9).of(1_234, Rounding::Down), 1);
+        assert_eq!(Bps::constant(9).of(1_234, Rounding::Up), 2);
+        // 500 bps of 1_010 is 50.5 and of 1_030 is 51.5
+        assert_eq!(Bps::constant(500).of(1_010, Rounding::HalfEven), 50);
+        assert_eq!(Bps::constant(500).of(1_030, Rounding::HalfEven), 52);
+        assert_eq!(Bps::from_f64(0.123449), Bps::constant(1_234));
+        assert_eq!(Bps::from_f64(3.0), Bps::FULL);
+    }
+}
@@ -0,0 +1,156 @@
+//! Deterministic fixed-point arithmetic shared by execution and the oracles.
+//!
+//! Amounts are integers. Products go through a u128 intermediate, so two
+//! u64 operands never overflow before the division, and every division
+//! states how it rounds. The protocol rounds by who bears the difference:
+//! fees it charges round half to even (banker's rounding), so over many
+//! operations neither side gains from it; payouts from a finite balance
+//! round down, so they never exceed it; requirements a user must meet
+//! round up.
+
+mod bps;
+mod ratio;
+
+pub use bps::{Bps, BPS_SCALE};
+pub use ratio::Ratio;
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathError {
+    DivisionByZero,
+    /// The result does not fit in a u64
+    Overflow,
+    /// A share above 10_000 bps (100%)
+    BpsOutOfRange(u64),
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MathError::DivisionByZero => write!(f, "Division by zero"),
+            MathError::Overflow => write!(f, "Arithmetic overflow"),
+            MathError::BpsOutOfRange(bps) => write!(f, "{} bps is more than 100%", bps),
+        }
+    }
+}
+
+impl std::error::Error for MathError {}
+
+/// How a division that does not come out even is resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+    /// Toward zero
+    Down,
+    /// Away from zero
+    Up,
+    /// To the nearest integer, halves to the even one
+    HalfEven,
+}
+
+const fn div_round(numerator: u128, denominator: u128, rounding: Rounding) -> u128 {
+    let (quotient, remainder) = (numerator / denominator, numerator % denominator);
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => remainder > 0,
+        Rounding::HalfEven => {
+            let rest = denominator - remainder;
+            remainder > rest || (remainder == rest && quotient % 2 == 1)
+        }
+    };
+    quotient + round_up as u128
+}
+
+/// `value * numerator / denominator`, rounded as asked.
+pub fn mul_div(
+    value: u64,
+    numerator: u64,
+    denominator: u64,
+    rounding: Rounding,
+) -> Result<u64, MathError> {
+    if denominator == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    let product = value as u128 * numerator as u128;
+    u64::try_from(div_round(product, denominator as u128, rounding))
+        .map_err(|_| MathError::Overflow)
+}
+
+/// Divide `amount` in proportion to `weights`, exactly: each part is its
+/// share rounded down, and what that leaves goes a unit at a time to the
+/// parts with the largest remainders, earlier parts first on ties.
+pub fn split(amount: u64, weights: &[u64]) -> Result<Vec<u64>, MathError> {
+    let total: u128 = weights.iter().map(|w| *w as u128).sum();
+    if total == 0 {
+        return Err(MathError::DivisionByZero);
+    }
+    let mut parts = Vec::with_capacity(weights.len());
+    let mut remainders = Vec::with_capacity(weights.len());
+    for (i, weight) in weights.iter().enumerate() {
+        let product = amount as u128 * *weight as u128;
+        parts.push((product / total) as u64);
+        remainders.push((product % total, i));
+    }
+    let assigned: u64 = parts.iter().sum();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    for (_, i) in remainders.into_iter().take((amount - assigned) as usize) {
+        parts[i] += 1;
+    }
+    Ok(parts)
+}
+
+/// Convert a float to fixed point with `scale` units per 1.0, rounding half
+/// to even. Negative and NaN values become zero and values too large for a
+/// u64 saturate; this is where off-chain floats enter integer state.
+pub fn fixed_from_f64(value: f64, scale: u64) -> u64 {
+    (value * scale as f64).round_ties_even() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_rounds_as_asked_without_intermediate_overflow() {
+        assert_eq!(mul_div(7, 1, 2, Rounding::Down), Ok(3));
+        assert_eq!(mul_div(7, 1, 2, Rounding::Up), Ok(4));
+        assert_eq!(mul_div(5, 1, 2, Rounding::HalfEven), Ok(2));
+        assert_eq!(mul_div(7, 1, 2, Rounding::HalfEven), Ok(4));
+        assert_eq!(mul_div(8, 1, 3, Rounding::HalfEven), Ok(3));
+        assert_eq!(mul_div(6, 1, 4, Rounding::HalfEven), Ok(2));
+        assert_eq!(
+            mul_div(u64::MAX, u64::MAX, u64::MAX, Rounding::Down),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            mul_div(u64::MAX, 2, 1, Rounding::Down),
+            Err(MathError::Overflow)
+        );
+        assert_eq!(
+            mul_div(1, 1, 0, Rounding::Down),
+            Err(MathError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn split_is_exact_and_deterministic() {
+        assert_eq!(split(7, &[80, 15, 5]), Ok(vec![6, 1, 0]));
+        assert_eq!(split(100, &[80, 15, 5]), Ok(vec![80, 15, 5]));
+        assert_eq!(split(10, &[1, 1, 1]), Ok(vec![4, 3, 3]));
+        assert_eq!(
+            split(u64::MAX, &[u64::MAX, 1]).unwrap().iter().sum::<u64>(),
+            u64::MAX
+        );
+        assert_eq!(split(5, &[0, 0]), Err(MathError::DivisionByZero));
+    }
+
+    #[test]
+    fn floats_enter_fixed_point_rounded_half_to_even() {
+        assert_eq!(fixed_from_f64(1.25, 1_000_000), 1_250_000);
+        assert_eq!(fixed_from_f64(2.5, 1), 2);
+        assert_eq!(fixed_from_f64(3.5, 1), 4);
+        assert_eq!(fixed_from_f64(-1.0, 1_000_000), 0);
+        assert_eq!(fixed_from_f64(f64::NAN, 1_000_000), 0);
+        assert_eq!(fixed_from_f64(f64::INFINITY, 1), u64::MAX);
+    }
+}
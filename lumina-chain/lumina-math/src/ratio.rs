@@ -0,0 +1,67 @@
+use crate::{mul_div, MathError, Rounding, BPS_SCALE};
+
+/// A non-negative rational multiplier, which may exceed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl Ratio {
+    pub const fn new(numerator: u64, denominator: u64) -> Result<Ratio, MathError> {
+        if denominator == 0 {
+            return Err(MathError::DivisionByZero);
+        }
+        Ok(Ratio {
+            numerator,
+            denominator,
+        })
+    }
+
+    /// `bps` basis points, e.g. 11_000 for 110%.
+    pub const fn from_bps(bps: u64) -> Ratio {
+        Ratio {
+            numerator: bps,
+            denominator: BPS_SCALE,
+        }
+    }
+
+    pub const fn numerator(self) -> u64 {
+        self.numerator
+    }
+
+    pub const fn denominator(self) -> u64 {
+        self.denominator
+    }
+
+    /// `value` scaled by the ratio; fails if the result overflows a u64.
+    pub fn apply(self, value: u64, rounding: Rounding) -> Result<u64, MathError> {
+        mul_div(value, self.numerator, self.denominator, rounding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratios_scale_past_one_and_report_overflow() {
+        assert_eq!(Ratio::new(1, 0), Err(MathError::DivisionByZero));
+        assert_eq!(
+            Ratio::from_bps(11_000).apply(1_001, Rounding::Down),
+            Ok(1_101)
+        );
+        assert_eq!(
+            Ratio::from_bps(11_000).apply(1_001, Rounding::Up),
+            Ok(1_102)
+        );
+        assert_eq!(
+            Ratio::new(2, 3).unwrap().apply(u64::MAX, Rounding::Down),
+            Ok(u64::MAX / 3 * 2)
+        );
+        assert_eq!(
+            Ratio::from_bps(11_000).apply(u64::MAX, Rounding::Down),
+            Err(MathError::Overflow)
+        );
+    }
+}
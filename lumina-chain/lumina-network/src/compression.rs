@@ -0,0 +1,104 @@
+//! zstd framing for block gossip and sync responses.
+//!
+//! Payloads are either raw or a single zstd frame; receivers tell them
+//! apart by the frame magic (a raw bincode block would need a height of
+//! 0xFD2FB528 mod 2^32 to collide). The decompressed size declared in the frame
+//! header is checked against a cap before anything is allocated, and a
+//! frame that expands past its declared size is rejected.
+
+use anyhow::{bail, Result};
+use std::ffi::c_void;
+use zstd_sys::{
+    ZSTD_compress, ZSTD_compressBound, ZSTD_decompress, ZSTD_getFrameContentSize, ZSTD_isError,
+};
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const COMPRESSION_LEVEL: i32 = 3;
+
+pub fn compress(data: &[u8]) -> Result<Vec<u8>> {
+    // SAFETY: `out` has capacity `bound`, which ZSTD_compressBound guarantees
+    // is enough; the length is only set to what zstd reports it wrote.
+    unsafe {
+        let bound = ZSTD_compressBound(data.len());
+        let mut out = Vec::with_capacity(bound);
+        let written = ZSTD_compress(
+            out.as_mut_ptr() as *mut c_void,
+            bound,
+            data.as_ptr() as *const c_void,
+            data.len(),
+            COMPRESSION_LEVEL,
+        );
+        if ZSTD_isError(written) != 0 {
+            bail!("zstd compression failed");
+        }
+        out.set_len(written);
+        Ok(out)
+    }
+}
+
+pub fn is_compressed(data: &[u8]) -> bool {
+    data.starts_with(&ZSTD_MAGIC)
+}
+
+/// Decompress a single frame of at most `max_size` bytes.
+pub fn decompress(data: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    // SAFETY: zstd writes at most `size` bytes into `out`, which has that
+    // capacity; the length is only set to what it reports it wrote.
+    unsafe {
+        // Unknown and error sizes are u64::MAX and u64::MAX - 1
+        let size = ZSTD_getFrameContentSize(data.as_ptr() as *const c_void, data.len());
+        if size > max_size as u64 {
+            bail!("Compressed payload exceeds size cap");
+        }
+        let size = size as usize;
+        let mut out = Vec::with_capacity(size);
+        let written = ZSTD_decompress(
+            out.as_mut_ptr() as *mut c_void,
+            size,
+            data.as_ptr() as *const c_void,
+            data.len(),
+        );
+        if ZSTD_isError(written) != 0 || written != size {
+            bail!("Malformed compressed payload");
+        }
+        out.set_len(written);
+        Ok(out)
+    }
+}
+
+/// Raw bytes of a payload that may or may not be compressed.
+pub fn decode(data: Vec<u8>, max_size: usize) -> Result<Vec<u8>> {
+    if is_compressed(&data) {
+        return decompress(&data, max_size);
+    }
+    if data.len() > max_size {
+        bail!("Payload exceeds size cap");
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_passes_raw_payloads_through() {
+        let raw: Vec<u8> = (0..10_000u32).flat_map(|i| (i % 7).to_le_bytes()).collect();
+        let packed = compress(&raw).unwrap();
+        assert!(is_compressed(&packed));
+        assert!(packed.len() < raw.len() / 10);
+        assert_eq!(decode(packed, raw.len()).unwrap(), raw);
+        assert_eq!(decode(raw.clone(), raw.len()).unwrap(), raw);
+    }
+
+    #[test]
+    fn rejects_decompression_bombs() {
+        let bomb = compress(&vec![0u8; 64 * 1024 * 1024]).unwrap();
+        assert!(bomb.len() < 64 * 1024);
+        assert!(decode(bomb, 1024 * 1024).is_err());
+
+        let mut truncated = compress(&[1u8; 4096]).unwrap();
+        truncated.truncate(truncated.len() - 2);
+        assert!(decompress(&truncated, 4096).is_err());
+    }
+}
@@ -1,24 +1,235 @@
+pub mod compression;
+
 use anyhow::Result;
 use libp2p::core::muxing::StreamMuxerBox;
 use libp2p::futures::StreamExt;
 use libp2p::quic;
 use libp2p::{
-    gossipsub, identify, identity,
-    kad::{self, store::MemoryStore},
+    allow_block_list, gossipsub, identify, identity, kad, mdns,
     request_response::{self, ProtocolSupport},
-    swarm::{Config as SwarmConfig, NetworkBehaviour, Swarm, SwarmEvent},
-    Multiaddr, PeerId, Transport,
+    swarm::{
+        behaviour::toggle::Toggle, dial_opts::DialOpts, Config as SwarmConfig, NetworkBehaviour,
+        Swarm, SwarmEvent,
+    },
+    Multiaddr, Transport,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tracing::{error, info, warn};
 
+pub use libp2p::PeerId;
+
 const PEER_SCORE_BLACKLIST_THRESHOLD: i32 = -25;
 const PEER_SCORE_INVALID_MSG: i32 = -5;
 const PEER_SCORE_VALID_MSG: i32 = 1;
 
+/// How often the connection manager tops up outbound connections
+const CONNECTION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// How often the Kademlia routing table is re-bootstrapped
+const KAD_BOOTSTRAP_INTERVAL: Duration = Duration::from_secs(300);
+const DEFAULT_TARGET_OUTBOUND: usize = 8;
+/// How often the peer table exposed to the API is refreshed
+const PEER_STATUS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Largest gossip message on the wire, after compression
+pub const MAX_GOSSIP_MESSAGE_BYTES: usize = 1024 * 1024;
+/// Largest block or sync payload accepted after decompression
+pub const MAX_DECOMPRESSED_BYTES: usize = 16 * 1024 * 1024;
+/// Payloads smaller than this are sent raw
+const COMPRESSION_THRESHOLD: usize = 1024;
+/// Advertised in the identify agent version by nodes that accept zstd
+const ZSTD_CAPABILITY: &str = "zstd";
+
+/// Identifies the chain a node belongs to. Gossip topics and the identify
+/// protocol version are derived from it so nodes on different networks
+/// (mainnet, testnets) never exchange blocks or transactions.
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    pub chain_id: String,
+    pub genesis_hash: [u8; 32],
+    /// Permissioned mode: only these peers may connect. `None` is open.
+    pub allowlist: Option<HashSet<PeerId>>,
+}
+
+impl NetworkConfig {
+    /// `<chain_id>-<first 4 bytes of genesis hash>`, e.g. `lumina-testnet-1a2b3c4d`.
+    pub fn network_name(&self) -> String {
+        format!("{}-{}", self.chain_id, hex::encode(&self.genesis_hash[..4]))
+    }
+
+    pub fn block_topic(&self) -> String {
+        format!("lumina/{}/blocks", self.network_name())
+    }
+
+    pub fn tx_topic(&self) -> String {
+        format!("lumina/{}/txs", self.network_name())
+    }
+
+    /// Identify protocol version; peers advertising a different one are
+    /// disconnected.
+    pub fn protocol_version(&self) -> String {
+        format!("/lumina/1.0.0/{}", self.network_name())
+    }
+}
+
+/// Parse an allowlist entry: a base58 peer ID, or a hex ed25519 validator
+/// public key (the peer ID of a node whose libp2p identity is that key).
+pub fn parse_allowlist_entry(entry: &str) -> Result<PeerId> {
+    if let Ok(peer) = entry.parse::<PeerId>() {
+        return Ok(peer);
+    }
+    let bytes = hex::decode(entry.trim_start_matches("0x"))
+        .map_err(|_| anyhow::anyhow!("Invalid allowlist entry {entry}"))?;
+    let key = identity::ed25519::PublicKey::try_from_bytes(&bytes)
+        .map_err(|_| anyhow::anyhow!("Invalid validator key {entry}"))?;
+    Ok(identity::PublicKey::from(key).to_peer_id())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerDirection {
+    Inbound,
+    Outbound,
+}
+
+/// A connected peer, as exposed to the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerInfo {
+    pub peer_id: String,
+    pub address: String,
+    pub direction: PeerDirection,
+    pub score: i32,
+    /// Unix seconds of the last message or connection event from the peer
+    pub last_seen: u64,
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Automatic peer discovery settings.
+#[derive(Debug, Clone)]
+pub struct DiscoveryConfig {
+    /// Discover peers on the local network via mDNS
+    pub mdns: bool,
+    /// Outbound connections the connection manager keeps open
+    pub target_outbound: usize,
+}
+
+impl DiscoveryConfig {
+    /// `LUMINA_MDNS=0` disables mDNS; `LUMINA_TARGET_OUTBOUND` sets the target.
+    pub fn from_env() -> Self {
+        Self {
+            mdns: std::env::var("LUMINA_MDNS").map_or(true, |v| v != "0"),
+            target_outbound: std::env::var("LUMINA_TARGET_OUTBOUND")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_TARGET_OUTBOUND),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Connection {
+    pub addr: Multiaddr,
+    pub direction: PeerDirection,
+    pub last_seen: u64,
+}
+
+/// Tracks known peer addresses and open connections, and picks peers to
+/// dial until `target_outbound` outbound connections are open.
+#[derive(Debug, Default)]
+pub struct ConnectionManager {
+    target_outbound: usize,
+    known: HashMap<PeerId, Multiaddr>,
+    connected: HashMap<PeerId, Connection>,
+    dialing: HashSet<PeerId>,
+}
+
+impl ConnectionManager {
+    pub fn new(target_outbound: usize) -> Self {
+        Self {
+            target_outbound,
+            ..Default::default()
+        }
+    }
+
+    pub fn add_known(&mut self, peer: PeerId, addr: Multiaddr) {
+        self.known.insert(peer, addr);
+    }
+
+    pub fn forget(&mut self, peer: &PeerId) {
+        self.known.remove(peer);
+    }
+
+    pub fn on_connected(&mut self, peer: PeerId, addr: Multiaddr, direction: PeerDirection) {
+        self.dialing.remove(&peer);
+        self.connected.insert(
+            peer,
+            Connection {
+                addr,
+                direction,
+                last_seen: unix_now(),
+            },
+        );
+    }
+
+    /// Record activity from a connected peer.
+    pub fn touch(&mut self, peer: &PeerId) {
+        if let Some(conn) = self.connected.get_mut(peer) {
+            conn.last_seen = unix_now();
+        }
+    }
+
+    /// Called when the last connection to `peer` closed.
+    pub fn on_disconnected(&mut self, peer: &PeerId) {
+        self.connected.remove(peer);
+    }
+
+    pub fn on_dial_failed(&mut self, peer: &PeerId) {
+        self.dialing.remove(peer);
+        self.known.remove(peer);
+    }
+
+    pub fn outbound_deficit(&self) -> usize {
+        let outbound = self
+            .connected
+            .values()
+            .filter(|c| c.direction == PeerDirection::Outbound)
+            .count();
+        self.target_outbound
+            .saturating_sub(outbound + self.dialing.len())
+    }
+
+    pub fn connections(&self) -> impl Iterator<Item = (&PeerId, &Connection)> {
+        self.connected.iter()
+    }
+
+    /// Known, unconnected peers to dial now; marks them as dialing.
+    pub fn dial_candidates(&mut self, excluded: &HashSet<PeerId>) -> Vec<(PeerId, Multiaddr)> {
+        let mut candidates: Vec<(PeerId, Multiaddr)> = self
+            .known
+            .iter()
+            .filter(|(peer, _)| {
+                !self.connected.contains_key(peer)
+                    && !self.dialing.contains(peer)
+                    && !excluded.contains(peer)
+            })
+            .map(|(peer, addr)| (*peer, addr.clone()))
+            .collect();
+        candidates.sort_by_key(|(peer, _)| *peer);
+        candidates.truncate(self.outbound_deficit());
+        for (peer, _) in &candidates {
+            self.dialing.insert(*peer);
+        }
+        candidates
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SyncRequest {
     BlockByHeight(u64),
@@ -34,9 +245,11 @@ pub enum SyncResponse {
 
 #[derive(NetworkBehaviour)]
 struct LuminaBehaviour {
+    allowlist: Toggle<allow_block_list::Behaviour<allow_block_list::AllowedPeers>>,
     gossipsub: gossipsub::Behaviour,
     identify: identify::Behaviour,
     kademlia: kad::Behaviour<kad::store::MemoryStore>,
+    mdns: Toggle<mdns::tokio::Behaviour>,
     req_res: request_response::cbor::Behaviour<SyncRequest, SyncResponse>,
 }
 
@@ -48,6 +261,35 @@ pub struct P2PNetwork {
     blacklisted_peers: HashSet<PeerId>,
     block_topic: gossipsub::IdentTopic,
     tx_topic: gossipsub::IdentTopic,
+    protocol_version: String,
+    connections: ConnectionManager,
+    allowlist: Option<HashSet<PeerId>>,
+    peer_status: watch::Sender<Vec<PeerInfo>>,
+    /// Peers whose identify agent version advertises zstd support
+    zstd_peers: HashSet<PeerId>,
+}
+
+/// Decode a sync request as the `/lumina/sync/1` codec reads it.
+pub fn decode_sync_request(bytes: &[u8]) -> Result<SyncRequest> {
+    cbor4ii::serde::from_slice(bytes).map_err(|e| anyhow::anyhow!("Invalid sync request: {e}"))
+}
+
+/// Decode a sync response as the codec reads it, then decompress its
+/// payload as the swarm loop does before handing it to the node.
+pub fn decode_sync_response(bytes: &[u8]) -> Result<SyncResponse> {
+    let response: SyncResponse = cbor4ii::serde::from_slice(bytes)
+        .map_err(|e| anyhow::anyhow!("Invalid sync response: {e}"))?;
+    response.map_payload(|b| compression::decode(b, MAX_DECOMPRESSED_BYTES))
+}
+
+impl SyncResponse {
+    fn map_payload(self, f: impl FnOnce(Vec<u8>) -> Result<Vec<u8>>) -> Result<Self> {
+        Ok(match self {
+            SyncResponse::Block(Some(bytes)) => SyncResponse::Block(Some(f(bytes)?)),
+            SyncResponse::ZkProof(Some(bytes)) => SyncResponse::ZkProof(Some(f(bytes)?)),
+            other => other,
+        })
+    }
 }
 
 pub enum NetworkCommand {
@@ -62,6 +304,7 @@ pub enum NetworkCommand {
         block_hash: [u8; 32],
     },
     RespondSync {
+        peer: PeerId,
         channel: request_response::ResponseChannel<SyncResponse>,
         response: SyncResponse,
     },
@@ -86,8 +329,11 @@ pub enum NetworkEvent {
 
 impl P2PNetwork {
     pub async fn new(
+        config: &NetworkConfig,
+        discovery: &DiscoveryConfig,
         command_receiver: mpsc::Receiver<NetworkCommand>,
         event_sender: mpsc::Sender<NetworkEvent>,
+        peer_status: watch::Sender<Vec<PeerInfo>>,
     ) -> Result<Self> {
         let id_keys = identity::Keypair::generate_ed25519();
         let peer_id = PeerId::from(id_keys.public());
@@ -104,6 +350,7 @@ impl P2PNetwork {
             .mesh_n(8)
             .mesh_n_low(6)
             .mesh_n_high(12)
+            .max_transmit_size(MAX_GOSSIP_MESSAGE_BYTES)
             .build()
             .expect("valid gossipsub config");
 
@@ -113,11 +360,24 @@ impl P2PNetwork {
         )
         .map_err(|e| anyhow::anyhow!(e))?;
 
-        let identify = identify::Behaviour::new(identify::Config::new(
-            "/lumina/1.0.0".to_owned(),
-            id_keys.public(),
-        ));
+        let identify = identify::Behaviour::new(
+            identify::Config::new(config.protocol_version(), id_keys.public()).with_agent_version(
+                format!(
+                    "lumina-node/{} {}",
+                    env!("CARGO_PKG_VERSION"),
+                    ZSTD_CAPABILITY
+                ),
+            ),
+        );
         let kademlia = kad::Behaviour::new(peer_id, kad::store::MemoryStore::new(peer_id));
+        let mdns = if discovery.mdns {
+            Some(mdns::tokio::Behaviour::new(
+                mdns::Config::default(),
+                peer_id,
+            )?)
+        } else {
+            None
+        };
         let req_res = request_response::cbor::Behaviour::new(
             [(
                 libp2p::StreamProtocol::new("/lumina/sync/1"),
@@ -126,10 +386,22 @@ impl P2PNetwork {
             request_response::Config::default(),
         );
 
+        // Enforced when connections are established, before any protocol runs
+        let allowlist = config.allowlist.as_ref().map(|peers| {
+            let mut allowed =
+                allow_block_list::Behaviour::<allow_block_list::AllowedPeers>::default();
+            for peer in peers {
+                allowed.allow_peer(*peer);
+            }
+            allowed
+        });
+
         let behaviour = LuminaBehaviour {
+            allowlist: allowlist.into(),
             gossipsub,
             identify,
             kademlia,
+            mdns: mdns.into(),
             req_res,
         };
 
@@ -146,12 +418,20 @@ impl P2PNetwork {
             event_sender,
             peer_scores: HashMap::new(),
             blacklisted_peers: HashSet::new(),
-            block_topic: gossipsub::IdentTopic::new("lumina-blocks"),
-            tx_topic: gossipsub::IdentTopic::new("lumina-txs"),
+            block_topic: gossipsub::IdentTopic::new(config.block_topic()),
+            tx_topic: gossipsub::IdentTopic::new(config.tx_topic()),
+            protocol_version: config.protocol_version(),
+            connections: ConnectionManager::new(discovery.target_outbound),
+            allowlist: config.allowlist.clone(),
+            peer_status,
+            zstd_peers: HashSet::new(),
         })
     }
 
+    /// Score a message from `peer`; every scored message also counts as
+    /// activity for `last_seen`.
     fn adjust_peer_score(&mut self, peer: PeerId, delta: i32) {
+        self.connections.touch(&peer);
         if self.blacklisted_peers.contains(&peer) {
             return;
         }
@@ -171,6 +451,131 @@ impl P2PNetwork {
         self.blacklisted_peers.contains(&peer)
     }
 
+    /// Malformed messages only cost score: they are never forwarded, so
+    /// they cannot block the loop on a full event channel.
+    async fn on_gossip(&mut self, peer_id: PeerId, topic: gossipsub::TopicHash, data: Vec<u8>) {
+        if self.should_ignore_peer(peer_id) {
+            return;
+        }
+        if topic == self.block_topic.hash() {
+            match compression::decode(data, MAX_DECOMPRESSED_BYTES) {
+                Ok(data) => {
+                    let _ = self
+                        .event_sender
+                        .send(NetworkEvent::BlockReceived(data, peer_id))
+                        .await;
+                    self.adjust_peer_score(peer_id, PEER_SCORE_VALID_MSG);
+                }
+                Err(e) => {
+                    warn!(%peer_id, ?e, "Rejected block payload");
+                    self.adjust_peer_score(peer_id, PEER_SCORE_INVALID_MSG);
+                }
+            }
+        } else if topic == self.tx_topic.hash() {
+            let _ = self
+                .event_sender
+                .send(NetworkEvent::TxReceived(data, peer_id))
+                .await;
+            self.adjust_peer_score(peer_id, PEER_SCORE_VALID_MSG);
+        } else {
+            self.adjust_peer_score(peer_id, PEER_SCORE_INVALID_MSG);
+        }
+    }
+
+    async fn on_sync_response(&mut self, peer: PeerId, response: SyncResponse) {
+        if self.should_ignore_peer(peer) {
+            return;
+        }
+        match response.map_payload(|b| compression::decode(b, MAX_DECOMPRESSED_BYTES)) {
+            Ok(response) => {
+                let _ = self
+                    .event_sender
+                    .send(NetworkEvent::SyncResponse { peer, response })
+                    .await;
+                self.adjust_peer_score(peer, PEER_SCORE_VALID_MSG);
+            }
+            Err(e) => {
+                warn!(%peer, ?e, "Rejected sync response payload");
+                self.adjust_peer_score(peer, PEER_SCORE_INVALID_MSG);
+            }
+        }
+    }
+
+    /// Compress a block for gossip only if every connected peer can read it.
+    fn encode_block_for_gossip(&self, data: Vec<u8>) -> Result<Vec<u8>> {
+        let all_support_zstd = self
+            .connections
+            .connections()
+            .all(|(peer, _)| self.zstd_peers.contains(peer));
+        let data = if data.len() >= COMPRESSION_THRESHOLD && all_support_zstd {
+            compression::compress(&data)?
+        } else {
+            data
+        };
+        if data.len() > MAX_GOSSIP_MESSAGE_BYTES {
+            anyhow::bail!("Block of {} bytes exceeds gossip limit", data.len());
+        }
+        Ok(data)
+    }
+
+    fn is_allowed(&self, peer: &PeerId) -> bool {
+        self.allowlist
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(peer))
+    }
+
+    fn learn_address(&mut self, peer: PeerId, addr: Multiaddr) {
+        if self.should_ignore_peer(peer)
+            || !self.is_allowed(&peer)
+            || peer == *self.swarm.local_peer_id()
+        {
+            return;
+        }
+        self.swarm
+            .behaviour_mut()
+            .kademlia
+            .add_address(&peer, addr.clone());
+        self.connections.add_known(peer, addr);
+    }
+
+    fn publish_peer_status(&self) {
+        let peers = self
+            .connections
+            .connections()
+            .map(|(peer, conn)| PeerInfo {
+                peer_id: peer.to_string(),
+                address: conn.addr.to_string(),
+                direction: conn.direction,
+                score: self.peer_scores.get(peer).copied().unwrap_or(0),
+                last_seen: conn.last_seen,
+            })
+            .collect();
+        self.peer_status.send_replace(peers);
+    }
+
+    /// Dial known peers up to the outbound target; if none are left to
+    /// try, walk the DHT towards a random key to find more.
+    fn maintain_connections(&mut self) {
+        let deficit = self.connections.outbound_deficit();
+        if deficit == 0 {
+            return;
+        }
+        let candidates = self.connections.dial_candidates(&self.blacklisted_peers);
+        if candidates.len() < deficit {
+            self.swarm
+                .behaviour_mut()
+                .kademlia
+                .get_closest_peers(PeerId::random());
+        }
+        for (peer, addr) in candidates {
+            let opts = DialOpts::peer_id(peer).addresses(vec![addr]).build();
+            if let Err(e) = self.swarm.dial(opts) {
+                warn!(%peer, ?e, "Failed dialing discovered peer");
+                self.connections.on_dial_failed(&peer);
+            }
+        }
+    }
+
     pub async fn run(mut self) {
         if let Err(e) = self
             .swarm
@@ -189,50 +594,59 @@ impl P2PNetwork {
             error!(?e, "Failed to subscribe to tx topic");
         }
 
+        let mut connection_check = tokio::time::interval(CONNECTION_CHECK_INTERVAL);
+        let mut kad_bootstrap = tokio::time::interval(KAD_BOOTSTRAP_INTERVAL);
+        let mut peer_status = tokio::time::interval(PEER_STATUS_INTERVAL);
+
         loop {
             tokio::select! {
+                _ = connection_check.tick() => self.maintain_connections(),
+                _ = peer_status.tick() => self.publish_peer_status(),
+                _ = kad_bootstrap.tick() => {
+                    // Errors only when the routing table is still empty
+                    let _ = self.swarm.behaviour_mut().kademlia.bootstrap();
+                }
                 event = self.swarm.select_next_some() => match event {
                     SwarmEvent::Behaviour(LuminaBehaviourEvent::Gossipsub(gossipsub::Event::Message {
                         propagation_source: peer_id,
                         message,
                         ..
-                    })) => {
+                    })) => self.on_gossip(peer_id, message.topic, message.data).await,
+                    SwarmEvent::Behaviour(LuminaBehaviourEvent::Identify(identify::Event::Received { peer_id, info, .. })) => {
                         if self.should_ignore_peer(peer_id) {
                             continue;
                         }
-                        let topic = message.topic.clone();
-                        if topic == self.block_topic.hash() {
-                            let _ = self.event_sender.send(NetworkEvent::BlockReceived(message.data, peer_id)).await;
-                            self.adjust_peer_score(peer_id, PEER_SCORE_VALID_MSG);
-                        } else if topic == self.tx_topic.hash() {
-                            let _ = self.event_sender.send(NetworkEvent::TxReceived(message.data, peer_id)).await;
-                            self.adjust_peer_score(peer_id, PEER_SCORE_VALID_MSG);
-                        } else {
-                            self.adjust_peer_score(peer_id, PEER_SCORE_INVALID_MSG);
-                        }
-                    },
-                    SwarmEvent::Behaviour(LuminaBehaviourEvent::Identify(identify::Event::Received { peer_id, info, .. })) => {
-                        if self.should_ignore_peer(peer_id) {
+                        if info.protocol_version != self.protocol_version {
+                            warn!(%peer_id, theirs = %info.protocol_version, "Disconnecting peer on a different network");
+                            let _ = self.swarm.disconnect_peer_id(peer_id);
+                            self.connections.forget(&peer_id);
+                            self.swarm.behaviour_mut().kademlia.remove_peer(&peer_id);
                             continue;
                         }
+                        self.connections.touch(&peer_id);
+                        if info.agent_version.split_whitespace().any(|c| c == ZSTD_CAPABILITY) {
+                            self.zstd_peers.insert(peer_id);
+                        } else {
+                            self.zstd_peers.remove(&peer_id);
+                        }
                         for addr in info.listen_addrs {
-                            self.swarm.behaviour_mut().kademlia.add_address(&peer_id, addr);
+                            self.learn_address(peer_id, addr);
                         }
                         let _ = self.event_sender.send(NetworkEvent::PeerDiscovered(peer_id)).await;
                     },
                     SwarmEvent::Behaviour(LuminaBehaviourEvent::ReqRes(request_response::Event::Message { peer, message, .. })) => {
-                        if self.should_ignore_peer(peer) {
-                            continue;
-                        }
                         match message {
                             request_response::Message::Request { request, channel, .. } => {
+                                if self.should_ignore_peer(peer) {
+                                    continue;
+                                }
                                 let _ = self.event_sender.send(NetworkEvent::SyncRequest { peer, request, channel }).await;
+                                self.adjust_peer_score(peer, PEER_SCORE_VALID_MSG);
                             }
                             request_response::Message::Response { response, .. } => {
-                                let _ = self.event_sender.send(NetworkEvent::SyncResponse { peer, response }).await;
+                                self.on_sync_response(peer, response).await;
                             }
                         }
-                        self.adjust_peer_score(peer, PEER_SCORE_VALID_MSG);
                     }
                     SwarmEvent::Behaviour(LuminaBehaviourEvent::ReqRes(request_response::Event::OutboundFailure { peer, error, .. })) => {
                         warn!(%peer, ?error, "Outbound sync request failed");
@@ -242,6 +656,44 @@ impl P2PNetwork {
                         warn!(%peer, ?error, "Inbound sync request failed");
                         self.adjust_peer_score(peer, PEER_SCORE_INVALID_MSG);
                     }
+                    SwarmEvent::Behaviour(LuminaBehaviourEvent::Mdns(mdns::Event::Discovered(peers))) => {
+                        for (peer, addr) in peers {
+                            self.learn_address(peer, addr);
+                        }
+                        self.maintain_connections();
+                    }
+                    SwarmEvent::Behaviour(LuminaBehaviourEvent::Mdns(mdns::Event::Expired(peers))) => {
+                        for (peer, _) in peers {
+                            self.connections.forget(&peer);
+                        }
+                    }
+                    SwarmEvent::Behaviour(LuminaBehaviourEvent::Kademlia(kad::Event::RoutingUpdated { peer, addresses, .. }))
+                        if !self.should_ignore_peer(peer) =>
+                    {
+                        self.connections.add_known(peer, addresses.first().clone());
+                    }
+                    SwarmEvent::ConnectionEstablished { peer_id, endpoint, .. } => {
+                        if self.should_ignore_peer(peer_id) {
+                            let _ = self.swarm.disconnect_peer_id(peer_id);
+                            continue;
+                        }
+                        self.connections.on_connected(
+                            peer_id,
+                            endpoint.get_remote_address().clone(),
+                            if endpoint.is_dialer() {
+                                PeerDirection::Outbound
+                            } else {
+                                PeerDirection::Inbound
+                            },
+                        );
+                    }
+                    SwarmEvent::ConnectionClosed { peer_id, num_established: 0, .. } => {
+                        self.connections.on_disconnected(&peer_id);
+                        self.zstd_peers.remove(&peer_id);
+                    }
+                    SwarmEvent::OutgoingConnectionError { peer_id: Some(peer), .. } => {
+                        self.connections.on_dial_failed(&peer);
+                    }
                     SwarmEvent::NewListenAddr { address, .. } => {
                         info!(%address, "Listening on QUIC/TLS 1.3");
                     },
@@ -249,8 +701,13 @@ impl P2PNetwork {
                 },
                 command = self.command_receiver.recv() => match command {
                     Some(NetworkCommand::BroadcastBlock(data)) => {
-                        if let Err(e) = self.swarm.behaviour_mut().gossipsub.publish(self.block_topic.clone(), data) {
-                            error!(?e, "Block publish error");
+                        match self.encode_block_for_gossip(data) {
+                            Ok(data) => {
+                                if let Err(e) = self.swarm.behaviour_mut().gossipsub.publish(self.block_topic.clone(), data) {
+                                    error!(?e, "Block publish error");
+                                }
+                            }
+                            Err(e) => error!(?e, "Block not broadcast"),
                         }
                     },
                     Some(NetworkCommand::BroadcastTx(data)) => {
@@ -268,7 +725,19 @@ impl P2PNetwork {
                             self.swarm.behaviour_mut().req_res.send_request(&peer, SyncRequest::ZkProofByBlock(block_hash));
                         }
                     }
-                    Some(NetworkCommand::RespondSync { channel, response }) => {
+                    Some(NetworkCommand::RespondSync { peer, channel, response }) => {
+                        let response = if self.zstd_peers.contains(&peer) {
+                            response.map_payload(|b| {
+                                if b.len() >= COMPRESSION_THRESHOLD {
+                                    compression::compress(&b)
+                                } else {
+                                    Ok(b)
+                                }
+                            })
+                        } else {
+                            Ok(response)
+                        };
+                        let response = response.unwrap_or_else(|e| SyncResponse::Error(e.to_string()));
                         if let Err(e) = self.swarm.behaviour_mut().req_res.send_response(channel, response) {
                             error!(?e, "Sync response send error");
                         }
@@ -285,11 +754,19 @@ impl P2PNetwork {
     }
 }
 
-pub async fn start_p2p() -> Result<(mpsc::Sender<NetworkCommand>, mpsc::Receiver<NetworkEvent>)> {
+pub async fn start_p2p(
+    config: NetworkConfig,
+) -> Result<(
+    mpsc::Sender<NetworkCommand>,
+    mpsc::Receiver<NetworkEvent>,
+    watch::Receiver<Vec<PeerInfo>>,
+)> {
     let (cmd_tx, cmd_rx) = mpsc::channel(100);
     let (event_tx, event_rx) = mpsc::channel(100);
+    let (peers_tx, peers_rx) = watch::channel(Vec::new());
 
-    let mut network = P2PNetwork::new(cmd_rx, event_tx).await?;
+    let discovery = DiscoveryConfig::from_env();
+    let mut network = P2PNetwork::new(&config, &discovery, cmd_rx, event_tx, peers_tx).await?;
 
     let port: u16 = std::env::var("LUMINA_P2P_PORT")
         .ok()
@@ -310,10 +787,195 @@ pub async fn start_p2p() -> Result<(mpsc::Sender<NetworkCommand>, mpsc::Receiver
         }
     }
 
-    info!("Starting P2P Network (QUIC + TLS 1.3)...");
+    if let Some(allowed) = &config.allowlist {
+        info!(peers = allowed.len(), "Peer allowlist enabled");
+    }
+    info!(network = %config.network_name(), "Starting P2P Network (QUIC + TLS 1.3)...");
     tokio::spawn(async move {
         network.run().await;
     });
 
-    Ok((cmd_tx, event_rx))
+    Ok((cmd_tx, event_rx, peers_rx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topics_differ_across_chain_ids_and_genesis() {
+        let mainnet = NetworkConfig {
+            chain_id: "lumina-mainnet".to_string(),
+            genesis_hash: [0xAB; 32],
+            allowlist: None,
+        };
+        assert_eq!(mainnet.network_name(), "lumina-mainnet-abababab");
+        assert_eq!(
+            mainnet.block_topic(),
+            "lumina/lumina-mainnet-abababab/blocks"
+        );
+
+        let testnet = NetworkConfig {
+            chain_id: "lumina-testnet".to_string(),
+            ..mainnet.clone()
+        };
+        let regenesis = NetworkConfig {
+            genesis_hash: [0xCD; 32],
+            ..mainnet.clone()
+        };
+        for other in [&testnet, &regenesis] {
+            assert_ne!(other.block_topic(), mainnet.block_topic());
+            assert_ne!(other.tx_topic(), mainnet.tx_topic());
+            assert_ne!(other.protocol_version(), mainnet.protocol_version());
+        }
+    }
+
+    #[test]
+    fn connection_manager_dials_up_to_outbound_target() {
+        let mut manager = ConnectionManager::new(2);
+        let addr: Multiaddr = "/ip4/127.0.0.1/udp/4000/quic-v1".parse().unwrap();
+        let peers: Vec<PeerId> = (0..4).map(|_| PeerId::random()).collect();
+        for peer in &peers {
+            manager.add_known(*peer, addr.clone());
+        }
+        let banned: HashSet<PeerId> = [peers[0]].into_iter().collect();
+
+        let first = manager.dial_candidates(&banned);
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(|(p, _)| *p != peers[0]));
+        // In-flight dials count towards the target.
+        assert!(manager.dial_candidates(&banned).is_empty());
+
+        manager.on_connected(first[0].0, addr.clone(), PeerDirection::Outbound);
+        manager.on_dial_failed(&first[1].0);
+        // An inbound connection does not count as outbound.
+        manager.on_connected(peers[0], addr.clone(), PeerDirection::Inbound);
+        assert_eq!(manager.outbound_deficit(), 1);
+        let retry = manager.dial_candidates(&HashSet::new());
+        assert_eq!(retry.len(), 1);
+        assert!(retry[0].0 != first[0].0 && retry[0].0 != first[1].0 && retry[0].0 != peers[0]);
+
+        manager.on_disconnected(&first[0].0);
+        assert_eq!(manager.outbound_deficit(), 1);
+    }
+
+    #[test]
+    fn allowlist_accepts_peer_ids_and_validator_keys() {
+        let keypair = identity::Keypair::generate_ed25519();
+        let peer = keypair.public().to_peer_id();
+        assert_eq!(parse_allowlist_entry(&peer.to_string()).unwrap(), peer);
+
+        let validator_key = keypair.public().try_into_ed25519().unwrap().to_bytes();
+        assert_eq!(
+            parse_allowlist_entry(&hex::encode(validator_key)).unwrap(),
+            peer
+        );
+        assert!(parse_allowlist_entry("not-a-peer").is_err());
+    }
+
+    #[test]
+    fn sync_messages_decode_as_the_codec_writes_them() {
+        let request = cbor4ii::serde::to_vec(Vec::new(), &SyncRequest::BlockByHeight(7)).unwrap();
+        assert!(matches!(
+            decode_sync_request(&request).unwrap(),
+            SyncRequest::BlockByHeight(7)
+        ));
+        let packed = compression::compress(&[3u8; 4096]).unwrap();
+        let response =
+            cbor4ii::serde::to_vec(Vec::new(), &SyncResponse::Block(Some(packed))).unwrap();
+        assert!(matches!(
+            decode_sync_response(&response).unwrap(),
+            SyncResponse::Block(Some(bytes)) if bytes == vec![3u8; 4096]
+        ));
+
+        for garbage in [&[][..], &[0xFF; 16], &request[..request.len() - 1]] {
+            assert!(decode_sync_request(garbage).is_err());
+            assert!(decode_sync_response(garbage).is_err());
+        }
+        let bomb = compression::compress(&vec![0u8; MAX_DECOMPRESSED_BYTES + 1]).unwrap();
+        let response =
+            cbor4ii::serde::to_vec(Vec::new(), &SyncResponse::ZkProof(Some(bomb))).unwrap();
+        assert!(decode_sync_response(&response).is_err());
+    }
+
+    #[tokio::test]
+    async fn malformed_messages_only_cost_peer_score() {
+        let config = NetworkConfig {
+            chain_id: "lumina-testnet".to_string(),
+            genesis_hash: [0xAB; 32],
+            allowlist: None,
+        };
+        let discovery = DiscoveryConfig {
+            mdns: false,
+            target_outbound: 0,
+        };
+        let (_cmd_tx, cmd_rx) = mpsc::channel(1);
+        let (event_tx, mut event_rx) = mpsc::channel(1);
+        let (peers_tx, _peers_rx) = watch::channel(Vec::new());
+        let mut network = P2PNetwork::new(&config, &discovery, cmd_rx, event_tx.clone(), peers_tx)
+            .await
+            .unwrap();
+        // Nothing drains the node's events, so forwarding anything would block.
+        event_tx
+            .try_send(NetworkEvent::PeerDiscovered(PeerId::random()))
+            .unwrap();
+
+        let mut corrupt = compression::compress(&[1u8; 4096]).unwrap();
+        corrupt.truncate(corrupt.len() - 2);
+        let bomb = compression::compress(&vec![0u8; MAX_DECOMPRESSED_BYTES + 1]).unwrap();
+        let block_topic = network.block_topic.hash();
+        let peer = PeerId::random();
+        let deadline = Duration::from_secs(5);
+        enum Malformed {
+            Gossip(gossipsub::TopicHash, Vec<u8>),
+            Sync(SyncResponse),
+        }
+        let malformed = [
+            Malformed::Gossip(block_topic.clone(), corrupt.clone()),
+            Malformed::Gossip(block_topic.clone(), bomb.clone()),
+            Malformed::Gossip(
+                gossipsub::TopicHash::from_raw("lumina/other/blocks"),
+                vec![1, 2, 3],
+            ),
+            Malformed::Sync(SyncResponse::Block(Some(corrupt))),
+            Malformed::Sync(SyncResponse::ZkProof(Some(bomb))),
+        ];
+        for (round, message) in (1..).zip(malformed) {
+            let handled = tokio::time::timeout(deadline, async {
+                match message {
+                    Malformed::Gossip(topic, data) => network.on_gossip(peer, topic, data).await,
+                    Malformed::Sync(response) => network.on_sync_response(peer, response).await,
+                }
+            })
+            .await;
+            assert!(
+                handled.is_ok(),
+                "malformed message {round} stalled the loop"
+            );
+            assert_eq!(network.peer_scores[&peer], round * PEER_SCORE_INVALID_MSG);
+        }
+        assert!(network.should_ignore_peer(peer));
+
+        // Blacklisted peers are dropped before anything is decoded or sent.
+        tokio::time::timeout(
+            deadline,
+            network.on_gossip(peer, block_topic.clone(), vec![7]),
+        )
+        .await
+        .unwrap();
+        assert_eq!(network.peer_scores[&peer], 5 * PEER_SCORE_INVALID_MSG);
+        assert!(matches!(
+            event_rx.recv().await,
+            Some(NetworkEvent::PeerDiscovered(_))
+        ));
+        assert!(event_rx.try_recv().is_err());
+
+        let honest = PeerId::random();
+        network.on_gossip(honest, block_topic, vec![7]).await;
+        assert!(matches!(
+            event_rx.try_recv(),
+            Ok(NetworkEvent::BlockReceived(data, from)) if data == vec![7] && from == honest
+        ));
+        assert_eq!(network.peer_scores[&honest], PEER_SCORE_VALID_MSG);
+    }
 }
@@ -21,17 +21,7 @@ fn main() {
             lusd_balance: 1_000_000_000,
             ljun_balance: 1_000_000_000,
             lumina_balance: 1_000_000_000,
-            commitment: None,
-            passkey_device_key: None,
-            guardians: Vec::new(),
-            pq_pubkey: None,
-            epoch_tx_volume: 0,
-            last_reward_epoch: 0,
-            credit_score: 0,
-            active_streams: Vec::new(),
-            yield_positions: Vec::new(),
-            pending_flash_mint: 0,
-            pending_flash_collateral: 0,
+            ..Default::default()
         },
     );
 
@@ -0,0 +1,228 @@
+//! Fast bootstrap: start a node from a validator-signed checkpoint and a
+//! downloaded state snapshot instead of replaying the chain from genesis.
+
+use anyhow::{anyhow, bail, Context, Result};
+use ed25519_dalek::SigningKey;
+use lumina_storage::db::Storage;
+use lumina_storage::snapshot::StateSnapshot;
+use lumina_types::checkpoint::{Checkpoint, CheckpointAttestation};
+use lumina_types::state::{GlobalState, ValidatorState};
+use std::path::Path;
+
+/// `--checkpoint <height>,<block hash>,<state url>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointArg {
+    pub height: u64,
+    pub block_hash: [u8; 32],
+    /// Where to download the snapshot: an http(s) URL or a local file
+    pub state_url: String,
+}
+
+impl CheckpointArg {
+    pub fn parse(arg: &str) -> Result<Self> {
+        let mut parts = arg.splitn(3, ',');
+        let (Some(height), Some(hash), Some(state_url)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            bail!("--checkpoint must be <height>,<block hash>,<state url>");
+        };
+        Ok(Self {
+            height: height.trim().parse().context("Invalid checkpoint height")?,
+            block_hash: parse_key(hash).context("Checkpoint block hash must be 32 bytes of hex")?,
+            state_url: state_url.trim().to_string(),
+        })
+    }
+}
+
+fn parse_key(hex_str: &str) -> Result<[u8; 32]> {
+    hex::decode(hex_str.trim().trim_start_matches("0x"))
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow!("Expected 32 bytes of hex: {}", hex_str))
+}
+
+/// Validators with equal power, from hex `--trust-validator` keys.
+pub fn trusted_validators(keys: &[String]) -> Result<Vec<ValidatorState>> {
+    keys.iter()
+        .map(|key| {
+            Ok(ValidatorState {
+                pubkey: parse_key(key)?,
+                stake: 0,
+                power: 1,
+                is_green: false,
+                energy_proof: None,
+                green_verified_at: 0,
+            })
+        })
+        .collect()
+}
+
+async fn fetch(location: &str) -> Result<Vec<u8>> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        let response = reqwest::get(location).await?.error_for_status()?;
+        return Ok(response.bytes().await?.to_vec());
+    }
+    std::fs::read(location).with_context(|| format!("Failed to read {}", location))
+}
+
+fn check_attestation(
+    attestation: &CheckpointAttestation,
+    arg: &CheckpointArg,
+    trusted: &[ValidatorState],
+) -> Result<()> {
+    let checkpoint = &attestation.checkpoint;
+    if checkpoint.height != arg.height || checkpoint.block_hash != arg.block_hash {
+        bail!("Attestation is for a different checkpoint");
+    }
+    if !attestation.has_quorum(trusted) {
+        bail!("Checkpoint lacks signatures from two thirds of the trusted validators");
+    }
+    Ok(())
+}
+
+/// Verify the checkpoint attestation against `trusted`, then download the
+/// snapshot, check it against the attested state root and install it as
+/// the tip of the empty `storage`.
+pub async fn bootstrap(
+    storage: &Storage,
+    genesis: &GlobalState,
+    arg: &CheckpointArg,
+    attestation: &str,
+    trusted: &[ValidatorState],
+) -> Result<()> {
+    if storage.load_tip()?.is_some_and(|(height, _)| height > 0) {
+        bail!("Bootstrap needs an empty data directory");
+    }
+    let attestation: CheckpointAttestation = serde_json::from_slice(&fetch(attestation).await?)
+        .context("Invalid checkpoint attestation")?;
+    check_attestation(&attestation, arg, trusted)?;
+    let snapshot = StateSnapshot::decode(&fetch(&arg.state_url).await?)?;
+    let state = snapshot.verify(&attestation.checkpoint)?;
+    snapshot.install(storage, genesis, &state)
+}
+
+/// Sign the canonical block at `height` as a checkpoint, adding the
+/// signature to the attestation file at `path` (created if missing).
+pub fn sign(
+    storage: &Storage,
+    height: u64,
+    key: &SigningKey,
+    path: &Path,
+) -> Result<CheckpointAttestation> {
+    let block = storage
+        .load_block_by_height(height)?
+        .ok_or_else(|| anyhow!("No canonical block at height {}", height))?;
+    let checkpoint = Checkpoint::of(&block);
+    let mut attestation = match std::fs::read(path) {
+        Ok(bytes) => serde_json::from_slice::<CheckpointAttestation>(&bytes)
+            .context("Invalid checkpoint attestation")?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => CheckpointAttestation {
+            checkpoint,
+            signatures: Vec::new(),
+        },
+        Err(e) => return Err(e.into()),
+    };
+    if attestation.checkpoint != checkpoint {
+        bail!(
+            "{} attests a different block at height {}",
+            path.display(),
+            height
+        );
+    }
+    attestation.sign(key);
+    std::fs::write(path, serde_json::to_vec_pretty(&attestation)?)?;
+    Ok(attestation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::block::{Block, BlockHeader};
+
+    #[tokio::test]
+    async fn bootstraps_from_a_quorum_signed_checkpoint() {
+        let dir = std::env::temp_dir().join(format!("lumina-bootstrap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let keys: Vec<SigningKey> = (1..=3u8)
+            .map(|i| SigningKey::from_bytes(&[i; 32]))
+            .collect();
+        let trusted = trusted_validators(
+            &keys
+                .iter()
+                .map(|k| hex::encode(k.verifying_key().to_bytes()))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        // The source node holds the checkpoint block and its state.
+        let mut state = GlobalState::default();
+        state.accounts.entry([5u8; 32]).or_default().lusd_balance = 77;
+        let block = Block {
+            header: BlockHeader {
+                height: 9,
+                prev_hash: [3u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: state.root_hash(),
+                timestamp: 9,
+                proposer: [0u8; 32],
+                validator_set_hash: state.validator_set_hash(),
+                audit_hash: state.audit_head,
+                signers_hash: [0u8; 32],
+            },
+            transactions: Vec::new(),
+            votes: Vec::new(),
+        };
+        let source = Storage::new("unused").unwrap();
+        source.save_block(&block).unwrap();
+        source
+            .save_canonical_block_at_height(9, block.hash())
+            .unwrap();
+        let snapshot_path = dir.join("9.snapshot");
+        std::fs::write(
+            &snapshot_path,
+            StateSnapshot::new(block.clone(), &state)
+                .unwrap()
+                .encode()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let attestation_path = dir.join("9.attestation.json");
+        let _ = std::fs::remove_file(&attestation_path);
+        sign(&source, 9, &keys[0], &attestation_path).unwrap();
+        sign(&source, 9, &keys[1], &attestation_path).unwrap();
+        let arg = CheckpointArg::parse(&format!(
+            "9,{},{}",
+            hex::encode(block.hash()),
+            snapshot_path.display()
+        ))
+        .unwrap();
+        let attestation = attestation_path.to_str().unwrap();
+        let genesis = GlobalState::default();
+
+        // Two of three signatures are not over two thirds.
+        let fresh = Storage::new("unused").unwrap();
+        let err = bootstrap(&fresh, &genesis, &arg, attestation, &trusted)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("two thirds"));
+
+        sign(&source, 9, &keys[2], &attestation_path).unwrap();
+        let wrong = CheckpointArg {
+            block_hash: [1u8; 32],
+            ..arg.clone()
+        };
+        assert!(bootstrap(&fresh, &genesis, &wrong, attestation, &trusted)
+            .await
+            .is_err());
+        bootstrap(&fresh, &genesis, &arg, attestation, &trusted)
+            .await
+            .unwrap();
+        assert_eq!(fresh.load_tip().unwrap(), Some((9, block.hash())));
+        assert_eq!(
+            fresh.load_state().unwrap().accounts[&[5u8; 32]].lusd_balance,
+            77
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
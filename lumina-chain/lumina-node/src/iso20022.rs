@@ -0,0 +1,607 @@
+//! ISO 20022 export of fiat bridge settlements for custodian banks.
+//!
+//! Every successful `InstantFiatBridge` becomes a pacs.008 credit transfer
+//! to the bank named by its `target_bank_id`, and each block's settlements
+//! are listed in one camt.054 debit notification of the bridge's settlement
+//! account. Each file is written next to a `.sig` holding the hex Ed25519
+//! signature of its exact bytes under the exporter's signing key.
+
+use anyhow::{bail, Context, Result};
+use ed25519_dalek::{Signer, SigningKey};
+use lumina_storage::db::Storage;
+use lumina_types::block::Block;
+use lumina_types::instruction::StablecoinInstruction;
+use lumina_types::receipt::BlockReceipts;
+use lumina_types::state::GlobalState;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Last exported height, kept in the output directory
+const CURSOR_FILE: &str = ".last_height";
+
+fn default_currency() -> String {
+    "USD".to_string()
+}
+
+fn default_decimals() -> u32 {
+    6
+}
+
+/// Contents of the `--iso20022` file.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExportConfig {
+    pub output_dir: String,
+    /// File holding the hex Ed25519 seed that signs every message
+    pub signing_key: String,
+    /// BIC of the bank holding the bridge's settlement account
+    pub instructing_agent: String,
+    /// Identifier of the settlement account at that bank (at most 35 chars)
+    pub settlement_account: String,
+    #[serde(default = "default_currency")]
+    pub currency: String,
+    /// Decimal places of one LUSD in base units
+    #[serde(default = "default_decimals")]
+    pub lusd_decimals: u32,
+    #[serde(default, rename = "bank")]
+    pub banks: Vec<Bank>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Bank {
+    /// `target_bank_id` of the bridge instruction, as 32 hex chars
+    pub id: String,
+    pub bic: String,
+}
+
+fn valid_bic(bic: &str) -> bool {
+    (bic.len() == 8 || bic.len() == 11)
+        && bic[..6].bytes().all(|b| b.is_ascii_uppercase())
+        && bic[6..]
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+impl ExportConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text).context("Invalid ISO 20022 config")?;
+        if !valid_bic(&config.instructing_agent) {
+            bail!("Invalid BIC: {}", config.instructing_agent);
+        }
+        let account = &config.settlement_account;
+        if account.is_empty()
+            || account.len() > 35
+            || !account
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        {
+            bail!("Settlement account must be 1-35 letters, digits or dashes");
+        }
+        if config.currency.len() != 3 || !config.currency.bytes().all(|b| b.is_ascii_uppercase()) {
+            bail!("Invalid currency code: {}", config.currency);
+        }
+        if config.lusd_decimals > 18 {
+            bail!("LUSD decimals must be at most 18");
+        }
+        let mut ids = std::collections::HashSet::new();
+        for bank in &config.banks {
+            if !valid_bic(&bank.bic) {
+                bail!("Invalid BIC: {}", bank.bic);
+            }
+            let id = parse_bank_id(&bank.id)?;
+            if !ids.insert(id) {
+                bail!("Bank {} listed twice", bank.id);
+            }
+        }
+        Ok(config)
+    }
+
+    fn bics(&self) -> HashMap<[u8; 16], &str> {
+        self.banks
+            .iter()
+            .filter_map(|b| Some((parse_bank_id(&b.id).ok()?, b.bic.as_str())))
+            .collect()
+    }
+}
+
+fn parse_bank_id(id: &str) -> Result<[u8; 16]> {
+    hex::decode(id)
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .with_context(|| format!("Bank id must be 16 bytes of hex: {}", id))
+}
+
+pub fn load_signing_key(path: &Path) -> Result<SigningKey> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let seed: [u8; 32] = hex::decode(text.trim())
+        .ok()
+        .and_then(|b| b.try_into().ok())
+        .context("Signing key must be a 32-byte hex seed")?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// A successful `InstantFiatBridge`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+    pub height: u64,
+    pub timestamp: u64,
+    pub tx_index: usize,
+    pub tx_id: [u8; 32],
+    pub account: [u8; 32],
+    pub amount: u64,
+    pub bank_id: [u8; 16],
+}
+
+impl Settlement {
+    /// 32 hex chars of the tx id, within the 35 allowed for references
+    fn reference(&self) -> String {
+        hex::encode(&self.tx_id[..16])
+    }
+
+    /// The tx id's first 16 bytes as a version 4 UUID.
+    fn uetr(&self) -> String {
+        let mut b: [u8; 16] = self.tx_id[..16].try_into().expect("16 bytes");
+        b[6] = (b[6] & 0x0f) | 0x40;
+        b[8] = (b[8] & 0x3f) | 0x80;
+        let hex = hex::encode(b);
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+pub fn settlements(block: &Block, receipts: &BlockReceipts) -> Vec<Settlement> {
+    block
+        .transactions
+        .iter()
+        .zip(&receipts.receipts)
+        .enumerate()
+        .filter_map(|(tx_index, (tx, receipt))| match &tx.instruction {
+            StablecoinInstruction::InstantFiatBridge {
+                amount,
+                target_bank_id,
+                ..
+            } if receipt.success => Some(Settlement {
+                height: block.header.height,
+                timestamp: block.header.timestamp,
+                tx_index,
+                tx_id: receipt.tx_id,
+                account: tx.sender,
+                amount: *amount,
+                bank_id: *target_bank_id,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `amount` base units as a decimal with at least two fraction digits.
+fn format_amount(amount: u64, decimals: u32) -> String {
+    let scale = 10u128.pow(decimals);
+    let (whole, frac) = (amount as u128 / scale, amount as u128 % scale);
+    let mut frac = format!("{:0width$}", frac, width = decimals as usize);
+    while frac.len() > 2 && frac.ends_with('0') {
+        frac.pop();
+    }
+    while frac.len() < 2 {
+        frac.push('0');
+    }
+    format!("{}.{}", whole, frac)
+}
+
+/// Unix seconds as a UTC `YYYY-MM-DDThh:mm:ss` date and time.
+fn format_datetime(unix: u64) -> (String, String) {
+    let (days, secs) = (unix / 86_400, unix % 86_400);
+    // Civil date from days since 1970-01-01 (proleptic Gregorian).
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    let date = format!("{:04}-{:02}-{:02}", year, month, day);
+    let time = format!(
+        "{}T{:02}:{:02}:{:02}",
+        date,
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60
+    );
+    (date, time)
+}
+
+fn agent(bic: &str) -> String {
+    format!("<FinInstnId><BICFI>{}</BICFI></FinInstnId>", bic)
+}
+
+/// FI-to-FI customer credit transfer paying out one settlement.
+pub fn pacs008(config: &ExportConfig, bic: &str, s: &Settlement) -> String {
+    let (date, datetime) = format_datetime(s.timestamp);
+    let amount = format_amount(s.amount, config.lusd_decimals);
+    let mut xml = String::new();
+    let _ = write!(
+        xml,
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:pacs.008.001.08\">\n",
+            "  <FIToFICstmrCdtTrf>\n",
+            "    <GrpHdr>\n",
+            "      <MsgId>LMN{height}-{index}</MsgId>\n",
+            "      <CreDtTm>{datetime}</CreDtTm>\n",
+            "      <NbOfTxs>1</NbOfTxs>\n",
+            "      <SttlmInf><SttlmMtd>INDA</SttlmMtd></SttlmInf>\n",
+            "    </GrpHdr>\n",
+            "    <CdtTrfTxInf>\n",
+            "      <PmtId>\n",
+            "        <InstrId>LMN{height}-{index}</InstrId>\n",
+            "        <EndToEndId>{reference}</EndToEndId>\n",
+            "        <UETR>{uetr}</UETR>\n",
+            "      </PmtId>\n",
+            "      <IntrBkSttlmAmt Ccy=\"{ccy}\">{amount}</IntrBkSttlmAmt>\n",
+            "      <IntrBkSttlmDt>{date}</IntrBkSttlmDt>\n",
+            "      <ChrgBr>SLEV</ChrgBr>\n",
+            "      <InstgAgt>{instructing}</InstgAgt>\n",
+            "      <InstdAgt>{instructed}</InstdAgt>\n",
+            "      <Dbtr><Nm>LuminaChain fiat bridge</Nm></Dbtr>\n",
+            "      <DbtrAcct><Id><Othr><Id>{account}</Id></Othr></Id></DbtrAcct>\n",
+            "      <DbtrAgt>{instructing}</DbtrAgt>\n",
+            "      <CdtrAgt>{instructed}</CdtrAgt>\n",
+            "      <Cdtr><Nm>{beneficiary}</Nm></Cdtr>\n",
+            "      <RmtInf><Ustrd>LuminaChain tx {tx_id}</Ustrd></RmtInf>\n",
+            "    </CdtTrfTxInf>\n",
+            "  </FIToFICstmrCdtTrf>\n",
+            "</Document>\n",
+        ),
+        height = s.height,
+        index = s.tx_index,
+        datetime = datetime,
+        reference = s.reference(),
+        uetr = s.uetr(),
+        ccy = config.currency,
+        amount = amount,
+        date = date,
+        instructing = agent(&config.instructing_agent),
+        instructed = agent(bic),
+        account = config.settlement_account,
+        beneficiary = hex::encode(s.account),
+        tx_id = hex::encode(s.tx_id),
+    );
+    xml
+}
+
+/// Debit notification of the settlement account for one block's settlements.
+pub fn camt054(
+    config: &ExportConfig,
+    height: u64,
+    timestamp: u64,
+    settled: &[(&str, &Settlement)],
+) -> String {
+    let (_, datetime) = format_datetime(timestamp);
+    let ccy = &config.currency;
+    let mut entries = String::new();
+    for (bic, s) in settled {
+        let amount = format_amount(s.amount, config.lusd_decimals);
+        let _ = write!(
+            entries,
+            concat!(
+                "      <Ntry>\n",
+                "        <Amt Ccy=\"{ccy}\">{amount}</Amt>\n",
+                "        <CdtDbtInd>DBIT</CdtDbtInd>\n",
+                "        <Sts><Cd>BOOK</Cd></Sts>\n",
+                "        <BookgDt><DtTm>{datetime}</DtTm></BookgDt>\n",
+                "        <BkTxCd><Domn><Cd>PMNT</Cd><Fmly><Cd>ICDT</Cd><SubFmlyCd>DMCT</SubFmlyCd></Fmly></Domn></BkTxCd>\n",
+                "        <NtryDtls><TxDtls>\n",
+                "          <Refs><EndToEndId>{reference}</EndToEndId><UETR>{uetr}</UETR></Refs>\n",
+                "          <Amt Ccy=\"{ccy}\">{amount}</Amt>\n",
+                "          <CdtDbtInd>DBIT</CdtDbtInd>\n",
+                "          <RltdAgts><CdtrAgt>{creditor}</CdtrAgt></RltdAgts>\n",
+                "        </TxDtls></NtryDtls>\n",
+                "      </Ntry>\n",
+            ),
+            ccy = ccy,
+            amount = amount,
+            datetime = datetime,
+            reference = s.reference(),
+            uetr = s.uetr(),
+            creditor = agent(bic),
+        );
+    }
+    let mut xml = String::new();
+    let _ = write!(
+        xml,
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<Document xmlns=\"urn:iso:std:iso:20022:tech:xsd:camt.054.001.08\">\n",
+            "  <BkToCstmrDbtCdtNtfctn>\n",
+            "    <GrpHdr><MsgId>LMN{height}</MsgId><CreDtTm>{datetime}</CreDtTm></GrpHdr>\n",
+            "    <Ntfctn>\n",
+            "      <Id>LMN{height}</Id>\n",
+            "      <CreDtTm>{datetime}</CreDtTm>\n",
+            "      <Acct><Id><Othr><Id>{account}</Id></Othr></Id><Ccy>{ccy}</Ccy><Svcr>{servicer}</Svcr></Acct>\n",
+            "{entries}",
+            "    </Ntfctn>\n",
+            "  </BkToCstmrDbtCdtNtfctn>\n",
+            "</Document>\n",
+        ),
+        height = height,
+        datetime = datetime,
+        account = config.settlement_account,
+        ccy = ccy,
+        servicer = agent(&config.instructing_agent),
+        entries = entries,
+    );
+    xml
+}
+
+/// Messages for one block, as file names and contents. Settlements to
+/// banks missing from the config are skipped with a warning.
+pub fn messages(
+    config: &ExportConfig,
+    block: &Block,
+    receipts: &BlockReceipts,
+) -> Vec<(String, String)> {
+    let bics = config.bics();
+    let mut settled = Vec::new();
+    for s in settlements(block, receipts) {
+        match bics.get(&s.bank_id) {
+            Some(bic) => settled.push((*bic, s)),
+            None => warn!(
+                "Fiat bridge tx {} pays unknown bank {}; not exported",
+                hex::encode(s.tx_id),
+                hex::encode(s.bank_id)
+            ),
+        }
+    }
+    if settled.is_empty() {
+        return Vec::new();
+    }
+    let height = block.header.height;
+    let mut files: Vec<(String, String)> = settled
+        .iter()
+        .map(|(bic, s)| {
+            (
+                format!("{}-{}-pacs008.xml", height, s.tx_index),
+                pacs008(config, bic, s),
+            )
+        })
+        .collect();
+    let refs: Vec<(&str, &Settlement)> = settled.iter().map(|(bic, s)| (*bic, s)).collect();
+    files.push((
+        format!("{}-camt054.xml", height),
+        camt054(config, height, block.header.timestamp, &refs),
+    ));
+    files
+}
+
+fn write_signed(dir: &Path, key: &SigningKey, name: &str, xml: &str) -> Result<()> {
+    let path = dir.join(name);
+    std::fs::write(&path, xml).with_context(|| format!("Failed to write {}", path.display()))?;
+    let signature = hex::encode(key.sign(xml.as_bytes()).to_bytes());
+    let mut sig_path = path.into_os_string();
+    sig_path.push(".sig");
+    std::fs::write(&sig_path, signature)?;
+    Ok(())
+}
+
+fn export_block(
+    storage: &Storage,
+    config: &ExportConfig,
+    dir: &Path,
+    key: &SigningKey,
+    height: u64,
+) -> Result<()> {
+    let Some(block) = storage.load_block_by_height(height)? else {
+        bail!("Block not stored");
+    };
+    let Some(receipts) = storage.load_block_receipts(&block.hash())? else {
+        bail!("Receipts not stored");
+    };
+    for (name, xml) in messages(config, &block, &receipts) {
+        write_signed(dir, key, &name, &xml)?;
+    }
+    Ok(())
+}
+
+/// Export the settlements of each new canonical height, resuming after the
+/// last height exported into the output directory (the tip at first start).
+/// A block that fails to export is retried on the next commit.
+pub async fn run(
+    storage: Arc<Storage>,
+    mut snapshots: watch::Receiver<Arc<GlobalState>>,
+    config: ExportConfig,
+) {
+    let dir = PathBuf::from(&config.output_dir);
+    let key = match std::fs::create_dir_all(&dir)
+        .map_err(anyhow::Error::from)
+        .and_then(|_| load_signing_key(Path::new(&config.signing_key)))
+    {
+        Ok(key) => key,
+        Err(e) => {
+            warn!("ISO 20022 export disabled: {}", e);
+            return;
+        }
+    };
+    let cursor = dir.join(CURSOR_FILE);
+    let mut seen = match std::fs::read_to_string(&cursor) {
+        Ok(text) => text.trim().parse().unwrap_or(0),
+        Err(_) => storage.load_tip().ok().flatten().map_or(0, |(h, _)| h),
+    };
+    info!(
+        "Exporting fiat bridge settlements to {} signed by {}",
+        dir.display(),
+        hex::encode(key.verifying_key().to_bytes())
+    );
+    while snapshots.changed().await.is_ok() {
+        let tip = match storage.load_tip() {
+            Ok(tip) => tip.map_or(0, |(h, _)| h),
+            Err(e) => {
+                warn!("ISO 20022 export could not read the tip: {}", e);
+                continue;
+            }
+        };
+        for height in seen + 1..=tip {
+            if let Err(e) = export_block(&storage, &config, &dir, &key, height) {
+                warn!("ISO 20022 export stopped at block {}: {}", height, e);
+                break;
+            }
+            seen = height;
+            if let Err(e) = std::fs::write(&cursor, seen.to_string()) {
+                warn!("ISO 20022 export could not save its cursor: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+    use lumina_types::block::BlockHeader;
+    use lumina_types::receipt::Receipt;
+    use lumina_types::transaction::Transaction;
+
+    const CONFIG: &str = r#"
+        output_dir = "/tmp/iso20022"
+        signing_key = "/tmp/iso20022.key"
+        instructing_agent = "LUMNUS33XXX"
+        settlement_account = "LUMINA-BRIDGE-1"
+
+        [[bank]]
+        id = "0102030405060708090a0b0c0d0e0f10"
+        bic = "DEUTDEFF"
+    "#;
+
+    fn bridge(amount: u64, bank: u8) -> Transaction {
+        Transaction {
+            sender: [7u8; 32],
+            nonce: 0,
+            instruction: StablecoinInstruction::InstantFiatBridge {
+                amount,
+                target_bank_id: if bank == 1 {
+                    [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+                } else {
+                    [bank; 16]
+                },
+                mpc_sig: Vec::new(),
+            },
+            signature: Vec::new(),
+            gas_limit: 0,
+            gas_price: 0,
+        }
+    }
+
+    #[test]
+    fn config_rejects_bad_bics_and_duplicate_banks() {
+        let config = ExportConfig::parse(CONFIG).unwrap();
+        assert_eq!((config.currency.as_str(), config.lusd_decimals), ("USD", 6));
+
+        let bad_bic = CONFIG.replace("DEUTDEFF", "DEUT<FF");
+        assert_eq!(
+            ExportConfig::parse(&bad_bic).unwrap_err().to_string(),
+            "Invalid BIC: DEUT<FF"
+        );
+        let twice = format!(
+            "{}\n[[bank]]\nid = \"0102030405060708090a0b0c0d0e0f10\"\nbic = \"BNPAFRPP\"",
+            CONFIG
+        );
+        assert!(ExportConfig::parse(&twice).is_err());
+        let long_account = CONFIG.replace("LUMINA-BRIDGE-1", &"A".repeat(36));
+        assert!(ExportConfig::parse(&long_account).is_err());
+    }
+
+    #[test]
+    fn formats_amounts_and_dates() {
+        assert_eq!(format_amount(1_250_500_000, 6), "1250.50");
+        assert_eq!(format_amount(1_000_001, 6), "1.000001");
+        assert_eq!(format_amount(7, 0), "7.00");
+        assert_eq!(
+            format_datetime(1_767_225_600),
+            ("2026-01-01".to_string(), "2026-01-01T00:00:00".to_string())
+        );
+        assert_eq!(format_datetime(951_827_696).1, "2000-02-29T12:34:56");
+    }
+
+    #[test]
+    fn exports_successful_settlements_to_known_banks() {
+        let config = ExportConfig::parse(CONFIG).unwrap();
+        let block = Block {
+            header: BlockHeader {
+                height: 42,
+                prev_hash: [0u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: [0u8; 32],
+                timestamp: 1_767_225_600,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions: vec![bridge(2_500_000, 1), bridge(9, 1), bridge(5, 0xEE)],
+            votes: Vec::new(),
+        };
+        let receipts = BlockReceipts {
+            height: 42,
+            receipts: [true, false, true]
+                .into_iter()
+                .enumerate()
+                .map(|(i, success)| Receipt {
+                    tx_id: [0xA0 + i as u8; 32],
+                    sender: [7u8; 32],
+                    success,
+                    first_event: 0,
+                    event_count: 0,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        // The failed transfer is not a settlement; the unknown bank's is skipped.
+        assert_eq!(settlements(&block, &receipts).len(), 2);
+        let files = messages(&config, &block, &receipts);
+        let names: Vec<_> = files.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["42-0-pacs008.xml", "42-camt054.xml"]);
+
+        let pacs = &files[0].1;
+        assert!(pacs.contains("<IntrBkSttlmAmt Ccy=\"USD\">2.50</IntrBkSttlmAmt>"));
+        assert!(pacs.contains("<IntrBkSttlmDt>2026-01-01</IntrBkSttlmDt>"));
+        assert!(pacs.contains("<EndToEndId>a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0a0</EndToEndId>"));
+        assert!(pacs.contains("<UETR>a0a0a0a0-a0a0-40a0-a0a0-a0a0a0a0a0a0</UETR>"));
+        assert!(
+            pacs.contains("<CdtrAgt><FinInstnId><BICFI>DEUTDEFF</BICFI></FinInstnId></CdtrAgt>")
+        );
+        let camt = &files[1].1;
+        assert_eq!(camt.matches("<Ntry>").count(), 1);
+        assert!(camt.contains("<Id>LUMINA-BRIDGE-1</Id>"));
+
+        let dir = std::env::temp_dir().join(format!("lumina-iso20022-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        write_signed(&dir, &key, &files[0].0, pacs).unwrap();
+        let written = std::fs::read(dir.join(&files[0].0)).unwrap();
+        let sig = std::fs::read_to_string(dir.join("42-0-pacs008.xml.sig")).unwrap();
+        let sig = Signature::from_slice(&hex::decode(sig).unwrap()).unwrap();
+        assert!(key.verifying_key().verify(&written, &sig).is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,27 +1,141 @@
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand};
 use std::sync::Arc;
 use tokio::signal;
-use tokio::sync::{mpsc, RwLock};
-use tracing::{error, info, Level};
-use tracing_subscriber::FmtSubscriber;
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::{error, info};
+
+mod bootstrap;
+mod iso20022;
+mod replay;
+mod rules;
+mod soak;
+mod sync;
+mod taxlots;
+mod telemetry;
+mod webhook;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
     #[arg(short, long)]
     validator: bool,
+    /// Read replica: import blocks from peers and serve the read API, but
+    /// never admit transactions or propose blocks
+    #[arg(long, conflicts_with = "validator")]
+    follower: bool,
     #[arg(short, long, default_value = "./data")]
     data_dir: String,
+    /// Network identifier; with the genesis hash it namespaces gossip topics
+    #[arg(long, default_value = "lumina-mainnet")]
+    chain_id: String,
+    /// Permissioned mode: peer IDs or hex validator keys allowed to connect
+    #[arg(long = "allow-peer", value_delimiter = ',')]
+    allow_peers: Vec<String>,
+    /// JSON list of vesting allocations to pre-seed when creating genesis
+    #[arg(long)]
+    genesis_vesting: Option<String>,
+    /// JSON object of `credit_oracles`, `screening_oracles`,
+    /// `compliance_officers` and `credential_attesters` trusted when
+    /// creating genesis
+    #[arg(long)]
+    genesis_authorities: Option<String>,
+    /// TOML file of webhook endpoints notified of stability events
+    #[arg(long)]
+    webhooks: Option<String>,
+    /// TOML file of threshold rules over committed state, exported as
+    /// `lumina_alert_firing` and sent to the webhooks
+    #[arg(long)]
+    alert_rules: Option<String>,
+    /// TOML file configuring the signed ISO 20022 export of fiat bridge
+    /// settlements
+    #[arg(long)]
+    iso20022: Option<String>,
+    /// Keep per-account tax lots and realized gains, matched `fifo` or
+    /// `lifo`, for `/account/{address}/taxlots`
+    #[arg(long)]
+    tax_lots: Option<String>,
+    /// Skip the explorer-only indexes (instruction and account transaction
+    /// indexes, memo transfers, supply stats); run `lumina-indexer` for them
+    #[arg(long)]
+    lean: bool,
+    /// Chaos testing: percent of network blocks to drop
+    #[arg(long, hide = true, default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=100))]
+    chaos_drop_blocks: u8,
+    /// Chaos testing: pause each commit after logging its intent
+    #[arg(long, hide = true, default_value_t = 0)]
+    chaos_delay_commit_ms: u64,
+    /// Chaos testing: abort part way through committing the Nth block
+    #[arg(long, hide = true)]
+    chaos_crash_after: Option<u64>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Re-execute stored blocks and verify every state root, reporting the
+    /// first divergence with a diff of the global state
+    Replay {
+        #[arg(long, default_value_t = 0)]
+        from: u64,
+        /// Defaults to the stored chain tip
+        #[arg(long)]
+        to: Option<u64>,
+    },
+    /// Produce a fresh single-node chain under continuous synthetic load,
+    /// failing if memory, file descriptors, storage or block latency regress
+    Soak {
+        /// Run length; fractions allowed
+        #[arg(long)]
+        hours: f64,
+        /// Transfers submitted per block
+        #[arg(long, default_value_t = 100)]
+        txs_per_block: usize,
+        /// Allowed resident memory growth over the run, in MiB
+        #[arg(long, default_value_t = 256)]
+        max_memory_growth_mib: u64,
+        /// Allowed growth in open file descriptors over the run
+        #[arg(long, default_value_t = 32)]
+        max_fd_growth: u64,
+        /// Allowed rise in mean block latency, in percent of the baseline
+        #[arg(long, default_value_t = 50)]
+        max_latency_drift_pct: u64,
+        /// Allowed storage growth per block, in KiB
+        #[arg(long, default_value_t = 1024)]
+        max_storage_per_block_kib: u64,
+    },
+    /// Start from a validator-signed checkpoint instead of genesis: download
+    /// and verify its state snapshot, then run the node syncing forward
+    Bootstrap {
+        /// `<height>,<block hash>,<state snapshot URL or file>`
+        #[arg(long)]
+        checkpoint: String,
+        /// Checkpoint attestation JSON, as a URL or file
+        #[arg(long)]
+        attestation: String,
+        /// Hex validator keys whose signatures count, with equal power;
+        /// defaults to the genesis validator set
+        #[arg(long = "trust-validator", value_delimiter = ',')]
+        trust_validators: Vec<String>,
+    },
+    /// Sign the canonical block at `height` as a checkpoint, adding the
+    /// signature to an attestation file
+    SignCheckpoint {
+        #[arg(long)]
+        height: u64,
+        /// File holding the validator's 32-byte hex Ed25519 seed
+        #[arg(long)]
+        key_file: String,
+        #[arg(long)]
+        attestation: String,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // 1. Setup Logging
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    telemetry::init();
 
     let args = Args::parse();
     info!("Starting Lumina Node...");
@@ -31,13 +145,119 @@ async fn main() -> Result<()> {
         lumina_storage::db::Storage::new(&args.data_dir).context("Failed to initialize storage")?,
     );
     info!("Storage initialized at {}", args.data_dir);
+    let migrated = storage
+        .migrate_stored_states()
+        .context("Failed to migrate stored states")?;
+    if migrated > 0 {
+        info!(
+            "Migrated {} stored states to encoding v{}",
+            migrated,
+            lumina_storage::migration::STATE_ENCODING_VERSION
+        );
+    }
+
+    match args.command {
+        Some(Command::Replay { from, to }) => {
+            let to = match to {
+                Some(to) => to,
+                None => storage.load_tip()?.map(|(h, _)| h).unwrap_or(0),
+            };
+            info!("Replaying blocks {}..={}", from + 1, to);
+            return match replay::replay(&storage, from, to).await? {
+                None => {
+                    info!("Replay matched every stored block through height {}", to);
+                    Ok(())
+                }
+                Some(divergence) => {
+                    println!("{}", serde_json::to_string_pretty(&divergence)?);
+                    Err(anyhow!("Replay diverged at height {}", divergence.height))
+                }
+            };
+        }
+        Some(Command::Soak {
+            hours,
+            txs_per_block,
+            max_memory_growth_mib,
+            max_fd_growth,
+            max_latency_drift_pct,
+            max_storage_per_block_kib,
+        }) => {
+            if !hours.is_finite() || hours <= 0.0 {
+                return Err(anyhow!("--hours must be positive"));
+            }
+            let config = soak::SoakConfig {
+                duration: std::time::Duration::from_secs_f64(hours * 3600.0),
+                txs_per_block,
+                max_memory_growth_mib,
+                max_fd_growth,
+                max_latency_drift_pct,
+                max_storage_per_block_kib,
+            };
+            info!(
+                "Soaking for {} hours at {} txs per block",
+                hours, txs_per_block
+            );
+            let report = soak::run(storage, std::path::Path::new(&args.data_dir), config).await?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            return if report.regressions.is_empty() {
+                info!("Soak passed after {} blocks", report.blocks);
+                Ok(())
+            } else {
+                Err(anyhow!("Soak failed: {}", report.regressions.join("; ")))
+            };
+        }
+        Some(Command::SignCheckpoint {
+            height,
+            key_file,
+            attestation,
+        }) => {
+            let key = iso20022::load_signing_key(std::path::Path::new(&key_file))?;
+            let signed =
+                bootstrap::sign(&storage, height, &key, std::path::Path::new(&attestation))?;
+            info!(
+                "Signed checkpoint {} ({} signatures in {})",
+                height,
+                signed.signatures.len(),
+                attestation
+            );
+            return Ok(());
+        }
+        Some(Command::Bootstrap {
+            checkpoint,
+            attestation,
+            trust_validators,
+        }) => {
+            let checkpoint = bootstrap::CheckpointArg::parse(&checkpoint)?;
+            let genesis = lumina_genesis::load_genesis_state(
+                args.genesis_vesting.as_deref(),
+                args.genesis_authorities.as_deref(),
+            )?;
+            let trusted = if trust_validators.is_empty() {
+                genesis.validators.clone()
+            } else {
+                bootstrap::trusted_validators(&trust_validators)?
+            };
+            info!("Bootstrapping from checkpoint {}", checkpoint.height);
+            bootstrap::bootstrap(&storage, &genesis, &checkpoint, &attestation, &trusted)
+                .await
+                .context("Bootstrap failed")?;
+            info!(
+                "Installed checkpoint {}; syncing forward",
+                checkpoint.height
+            );
+        }
+        None => {}
+    }
 
     // 3. Load or Create State
     let state = match storage.load_state() {
         Ok(s) => {
             if s.accounts.is_empty() && s.total_lusd_supply == 0 {
                 info!("State is empty, generating Genesis block...");
-                let genesis = lumina_genesis::create_genesis_state();
+                let genesis = lumina_genesis::load_genesis_state(
+                    args.genesis_vesting.as_deref(),
+                    args.genesis_authorities.as_deref(),
+                )?;
                 storage
                     .save_state(&genesis)
                     .expect("Failed to save genesis state");
@@ -50,6 +270,8 @@ async fn main() -> Result<()> {
                 genesis
             } else {
                 info!("Loaded existing state.");
+                let mut s = s;
+                lumina_execution::migrate_ljun_to_shares(&mut s);
                 s
             }
         }
@@ -59,10 +281,35 @@ async fn main() -> Result<()> {
         }
     };
 
+    // Read-only snapshot for the API; consensus republishes it on each commit
+    let (snapshot_tx, _) = watch::channel(Arc::new(state.clone()));
     let shared_state = Arc::new(RwLock::new(state));
 
     // 4. Init Network
-    let (net_cmd_tx, mut net_event_rx) = lumina_network::start_p2p()
+    let genesis_hash = storage
+        .load_state_by_height(0)
+        .ok()
+        .flatten()
+        .unwrap_or_else(lumina_genesis::create_genesis_state)
+        .root_hash();
+    let allowlist = if args.allow_peers.is_empty() {
+        None
+    } else {
+        Some(
+            args.allow_peers
+                .iter()
+                .map(|entry| lumina_network::parse_allowlist_entry(entry))
+                .collect::<Result<_>>()?,
+        )
+    };
+    let network_config = lumina_network::NetworkConfig {
+        chain_id: args.chain_id.clone(),
+        genesis_hash,
+        allowlist,
+    };
+    let network_name = network_config.network_name();
+    info!("Joining network {}", network_name);
+    let (net_cmd_tx, mut net_event_rx, peers_rx) = lumina_network::start_p2p(network_config)
         .await
         .context("Failed to start P2P")?;
 
@@ -75,6 +322,8 @@ async fn main() -> Result<()> {
     // Handle Network Events (Blocks & Txs)
     let net_tx_sender = tx_sender.clone();
     let net_block_sender = block_sender.clone();
+    let sync_net_tx = net_cmd_tx.clone();
+    let mut sync_server = sync::SyncServer::new(storage.clone());
     tokio::spawn(async move {
         while let Some(event) = net_event_rx.recv().await {
             match event {
@@ -97,8 +346,19 @@ async fn main() -> Result<()> {
                 lumina_network::NetworkEvent::PeerDiscovered(peer) => {
                     tracing::info!("Discovered peer: {}", peer);
                 }
-                lumina_network::NetworkEvent::SyncRequest { .. } => {
-                    // Sync requests are handled inside the networking layer (request-response).
+                lumina_network::NetworkEvent::SyncRequest {
+                    peer,
+                    request,
+                    channel,
+                } => {
+                    let response = sync_server.handle(peer, request, std::time::Instant::now());
+                    let _ = sync_net_tx
+                        .send(lumina_network::NetworkCommand::RespondSync {
+                            peer,
+                            channel,
+                            response,
+                        })
+                        .await;
                 }
                 lumina_network::NetworkEvent::SyncResponse { .. } => {
                     // Sync responses are handled by the sync subsystem.
@@ -111,29 +371,99 @@ async fn main() -> Result<()> {
     });
 
     // 5. Init Consensus
+    let chaos = lumina_consensus::ChaosConfig {
+        drop_blocks_pct: args.chaos_drop_blocks,
+        delay_commit: std::time::Duration::from_millis(args.chaos_delay_commit_ms),
+        crash_after: args.chaos_crash_after,
+    };
+    if chaos.is_enabled() {
+        tracing::warn!("Chaos testing enabled: {:?}", chaos);
+    }
     let consensus_state = shared_state.clone();
+    let consensus_snapshot = snapshot_tx.clone();
     let consensus_storage = storage.clone();
     let consensus_net_tx = net_cmd_tx.clone();
     let consensus_tx_rx = tx_receiver;
     let consensus_block_rx = block_receiver;
+    let consensus_wal_path = std::path::Path::new(&args.data_dir).join("consensus.wal");
 
+    let service = lumina_consensus::ConsensusService::new(
+        consensus_state,
+        consensus_snapshot,
+        consensus_storage,
+        consensus_net_tx,
+        consensus_tx_rx,
+        consensus_block_rx,
+        consensus_wal_path,
+    )
+    .with_explorer_indexes(!args.lean)
+    .with_block_production(!args.follower)
+    .with_chaos(chaos);
+    let consensus_status = service.status();
     tokio::spawn(async move {
-        let service = lumina_consensus::ConsensusService::new(
-            consensus_state,
-            consensus_storage,
-            consensus_net_tx,
-            consensus_tx_rx,
-            consensus_block_rx,
-        );
         service.run().await;
     });
 
+    let (alert_tx, alert_rx) = mpsc::channel(256);
+    let alert_tx = match &args.webhooks {
+        Some(path) => {
+            let config = webhook::WebhookConfig::load(std::path::Path::new(path))?;
+            tokio::spawn(webhook::run(
+                storage.clone(),
+                snapshot_tx.subscribe(),
+                config,
+                alert_rx,
+            ));
+            Some(alert_tx)
+        }
+        None => None,
+    };
+    if let Some(path) = &args.iso20022 {
+        let config = iso20022::ExportConfig::load(std::path::Path::new(path))?;
+        tokio::spawn(iso20022::run(
+            storage.clone(),
+            snapshot_tx.subscribe(),
+            config,
+        ));
+    }
+    if let Some(method) = &args.tax_lots {
+        let method = lumina_types::taxlots::LotMethod::parse(method)
+            .ok_or_else(|| anyhow!("--tax-lots must be fifo or lifo"))?;
+        tokio::spawn(taxlots::run(
+            storage.clone(),
+            snapshot_tx.subscribe(),
+            method,
+        ));
+    }
+    let (rule_states_tx, rule_states_rx) = watch::channel(lumina_api::RuleStates::new());
+    if let Some(path) = &args.alert_rules {
+        let engine = rules::RuleEngine::load(std::path::Path::new(path))?;
+        tokio::spawn(rules::run(
+            storage.clone(),
+            snapshot_tx.subscribe(),
+            engine,
+            rule_states_tx,
+            alert_tx,
+        ));
+    }
+
     // 6. Init API
     let api_state = shared_state.clone();
     let api_storage = storage.clone();
-    let api_tx_sender = tx_sender.clone();
+    let api_tx_sender = (!args.follower).then(|| tx_sender.clone());
     tokio::spawn(async move {
-        lumina_api::start_server(api_state, api_storage, api_tx_sender).await;
+        lumina_api::start_server(
+            api_state,
+            snapshot_tx,
+            api_storage,
+            api_tx_sender,
+            network_name,
+            peers_rx,
+            consensus_status,
+            rule_states_rx,
+            std::net::SocketAddr::from(([0, 0, 0, 0], 3000)),
+        )
+        .await;
     });
 
     info!("Node running. Press Ctrl+C to stop.");
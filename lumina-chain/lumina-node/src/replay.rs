@@ -0,0 +1,554 @@
+use anyhow::{anyhow, Result};
+use lumina_consensus::execute_block;
+use lumina_storage::db::Storage;
+use lumina_types::state::{ContractState, CreditLine, GlobalState, GovernanceProposal, PoolAsset};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// A `GlobalState` field that differs between the stored and replayed state.
+#[derive(Debug, Serialize)]
+pub struct FieldDiff {
+    pub path: String,
+    pub stored: Value,
+    pub replayed: Value,
+}
+
+/// The first block whose replay does not reproduce what was stored.
+#[derive(Debug, Serialize)]
+pub struct Divergence {
+    pub height: u64,
+    pub reason: String,
+    pub expected_state_root: String,
+    pub replayed_state_root: Option<String>,
+    pub diff: Vec<FieldDiff>,
+}
+
+/// Re-execute canonical blocks `from+1..=to` starting from the stored state
+/// at `from`, checking each header's commitments and the stored snapshot.
+/// Returns the first divergence, if any.
+pub async fn replay(storage: &Storage, from: u64, to: u64) -> Result<Option<Divergence>> {
+    let mut state = storage
+        .load_state_by_height(from)?
+        .ok_or_else(|| anyhow!("No stored state at height {}", from))?;
+
+    for height in from.saturating_add(1)..=to {
+        let block = storage
+            .load_block_by_height(height)?
+            .ok_or_else(|| anyhow!("No canonical block at height {}", height))?;
+        let expected_state_root = hex::encode(block.header.state_root);
+
+        let (next, commit) = match execute_block(state, &block).await {
+            Ok(out) => out,
+            Err(e) => {
+                return Ok(Some(Divergence {
+                    height,
+                    reason: format!("execution failed: {}", e),
+                    expected_state_root,
+                    replayed_state_root: None,
+                    diff: Vec::new(),
+                }))
+            }
+        };
+
+        let stored = storage.load_state_by_height(height)?;
+        let diff = stored
+            .as_ref()
+            .map(|s| diff_states(s, &next))
+            .unwrap_or_default();
+        let reason = if commit.app_hash != block.header.state_root {
+            Some("state_root mismatch")
+        } else if next.validator_set_hash() != block.header.validator_set_hash {
+            Some("validator_set_hash mismatch")
+        } else if next.audit_head != block.header.audit_hash {
+            Some("audit_hash mismatch")
+        } else if !diff.is_empty() {
+            Some("stored state differs")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Ok(Some(Divergence {
+                height,
+                reason: reason.to_string(),
+                expected_state_root,
+                replayed_state_root: Some(hex::encode(commit.app_hash)),
+                diff,
+            }));
+        }
+        state = next;
+    }
+    Ok(None)
+}
+
+/// Field-level differences between two states. Maps keyed (directly or in
+/// their values) by byte arrays are compared per entry, since those keys
+/// cannot be JSON object keys.
+pub fn diff_states(stored: &GlobalState, replayed: &GlobalState) -> Vec<FieldDiff> {
+    let mut out = Vec::new();
+    let hex_key = |k: &[u8; 32]| hex::encode(k);
+    diff_keyed(
+        "accounts",
+        &stored.accounts,
+        &replayed.accounts,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "contracts",
+        &stored.contracts,
+        &replayed.contracts,
+        hex_key,
+        |c| {
+            let mut value = to_json(&ContractState {
+                storage: BTreeMap::new(),
+                ..c.clone()
+            });
+            value["storage"] = Value::Object(
+                c.storage
+                    .iter()
+                    .map(|(k, v)| (hex::encode(k), Value::String(hex::encode(v))))
+                    .collect(),
+            );
+            value
+        },
+        &mut out,
+    );
+    diff_keyed(
+        "view_keys",
+        &stored.view_keys,
+        &replayed.view_keys,
+        hex_key,
+        |k| Value::String(hex::encode(k)),
+        &mut out,
+    );
+    diff_keyed(
+        "confidential_notes",
+        &stored.confidential_notes,
+        &replayed.confidential_notes,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "screening_oracles",
+        &stored.screening_oracles,
+        &replayed.screening_oracles,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "kyc_accounts",
+        &stored.kyc_accounts,
+        &replayed.kyc_accounts,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "inheritance",
+        &stored.inheritance,
+        &replayed.inheritance,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "inheritance_claims",
+        &stored.inheritance_claims,
+        &replayed.inheritance_claims,
+        u64::to_string,
+        |owners| {
+            Value::Array(
+                owners
+                    .iter()
+                    .map(|o| Value::String(hex::encode(o)))
+                    .collect(),
+            )
+        },
+        &mut out,
+    );
+    diff_keyed(
+        "credit_histories",
+        &stored.credit_histories,
+        &replayed.credit_histories,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "merchants",
+        &stored.merchants,
+        &replayed.merchants,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "dust_accounts",
+        &stored.dust_accounts,
+        &replayed.dust_accounts,
+        hex_key,
+        |h| Value::from(*h),
+        &mut out,
+    );
+    diff_keyed(
+        "dust_reaps",
+        &stored.dust_reaps,
+        &replayed.dust_reaps,
+        u64::to_string,
+        |accounts| {
+            Value::Array(
+                accounts
+                    .iter()
+                    .map(|a| Value::String(hex::encode(a)))
+                    .collect(),
+            )
+        },
+        &mut out,
+    );
+    diff_keyed(
+        "archive_activity",
+        &stored.archive_activity,
+        &replayed.archive_activity,
+        hex_key,
+        |e| Value::from(*e),
+        &mut out,
+    );
+    diff_keyed(
+        "archive_batches",
+        &stored.archive_batches,
+        &replayed.archive_batches,
+        u64::to_string,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "archived_accounts",
+        &stored.archived_accounts,
+        &replayed.archived_accounts,
+        hex_key,
+        |b| Value::from(*b),
+        &mut out,
+    );
+    diff_keyed(
+        "beacon_round",
+        &stored.beacon_round,
+        &replayed.beacon_round,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "randomness_beacons",
+        &stored.randomness_beacons,
+        &replayed.randomness_beacons,
+        u64::to_string,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "custodian_rotations",
+        &stored.custodian_rotations,
+        &replayed.custodian_rotations,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "vesting",
+        &stored.vesting,
+        &replayed.vesting,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "validator_rewards",
+        &stored.validator_rewards,
+        &replayed.validator_rewards,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "validator_liveness",
+        &stored.validator_liveness,
+        &replayed.validator_liveness,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "jailed_validators",
+        &stored.jailed_validators,
+        &replayed.jailed_validators,
+        hex_key,
+        to_json,
+        &mut out,
+    );
+    diff_keyed(
+        "pool_composition",
+        &stored.pool_composition,
+        &replayed.pool_composition,
+        PoolAsset::label,
+        |amount| Value::from(*amount),
+        &mut out,
+    );
+    diff_keyed(
+        "pool_valuation",
+        &stored.pool_valuation,
+        &replayed.pool_valuation,
+        PoolAsset::label,
+        |value| Value::from(*value),
+        &mut out,
+    );
+    diff_keyed(
+        "proposals",
+        &stored.proposals,
+        &replayed.proposals,
+        u64::to_string,
+        |p| {
+            let mut value = to_json(&GovernanceProposal {
+                votes: BTreeMap::new(),
+                ..p.clone()
+            });
+            let votes = p.votes.iter();
+            value["votes"] = Value::Object(
+                votes
+                    .map(|(v, approve)| (hex::encode(v), Value::Bool(*approve)))
+                    .collect(),
+            );
+            value
+        },
+        &mut out,
+    );
+    diff_keyed(
+        "credit_lines",
+        &stored.credit_lines,
+        &replayed.credit_lines,
+        u64::to_string,
+        |line| {
+            let mut value = to_json(&CreditLine {
+                guarantors: BTreeMap::new(),
+                ..line.clone()
+            });
+            let guarantors = line.guarantors.iter();
+            value["guarantors"] = Value::Object(
+                guarantors
+                    .map(|(g, shares)| (hex::encode(g), Value::from(*shares)))
+                    .collect(),
+            );
+            value
+        },
+        &mut out,
+    );
+
+    let without_keyed = |s: &GlobalState| GlobalState {
+        accounts: HashMap::new(),
+        contracts: BTreeMap::new(),
+        view_keys: BTreeMap::new(),
+        confidential_notes: BTreeMap::new(),
+        screening_oracles: BTreeMap::new(),
+        kyc_accounts: BTreeMap::new(),
+        inheritance: BTreeMap::new(),
+        inheritance_claims: BTreeMap::new(),
+        vesting: BTreeMap::new(),
+        validator_rewards: BTreeMap::new(),
+        validator_liveness: BTreeMap::new(),
+        jailed_validators: BTreeMap::new(),
+        pool_composition: BTreeMap::new(),
+        pool_valuation: BTreeMap::new(),
+        proposals: BTreeMap::new(),
+        credit_histories: BTreeMap::new(),
+        credit_lines: BTreeMap::new(),
+        merchants: BTreeMap::new(),
+        dust_accounts: BTreeMap::new(),
+        dust_reaps: BTreeMap::new(),
+        archive_activity: BTreeMap::new(),
+        archive_batches: BTreeMap::new(),
+        archived_accounts: BTreeMap::new(),
+        beacon_round: BTreeMap::new(),
+        randomness_beacons: BTreeMap::new(),
+        custodian_rotations: BTreeMap::new(),
+        ..s.clone()
+    };
+    diff_values(
+        String::new(),
+        &to_json(&without_keyed(stored)),
+        &to_json(&without_keyed(replayed)),
+        &mut out,
+    );
+    out
+}
+
+fn diff_keyed<'a, K: Ord + 'a, V: 'a, M>(
+    field: &str,
+    stored: &'a M,
+    replayed: &'a M,
+    key: impl Fn(&K) -> String,
+    value: impl Fn(&V) -> Value,
+    out: &mut Vec<FieldDiff>,
+) where
+    &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+{
+    let stored: BTreeMap<_, _> = stored.into_iter().collect();
+    let replayed: BTreeMap<_, _> = replayed.into_iter().collect();
+    let keys: BTreeSet<_> = stored.keys().chain(replayed.keys()).collect();
+    for k in keys {
+        let entry = |m: &BTreeMap<&K, &V>| m.get(*k).map_or(Value::Null, |v| value(v));
+        diff_values(
+            format!("{}.{}", field, key(k)),
+            &entry(&stored),
+            &entry(&replayed),
+            out,
+        );
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or_else(|e| Value::String(format!("<{}>", e)))
+}
+
+fn diff_values(path: String, stored: &Value, replayed: &Value, out: &mut Vec<FieldDiff>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match (stored, replayed) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<_> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                diff_values(
+                    join(key),
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                    out,
+                );
+            }
+        }
+        // Lists of records are diffed per entry; byte arrays and other
+        // scalar lists are reported whole.
+        (Value::Array(a), Value::Array(b))
+            if a.len() == b.len() && a.iter().any(|v| v.is_object()) =>
+        {
+            for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                diff_values(join(&i.to_string()), x, y, out);
+            }
+        }
+        _ if stored != replayed => out.push(FieldDiff {
+            path,
+            stored: stored.clone(),
+            replayed: replayed.clone(),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::block::{Block, BlockHeader};
+    use lumina_types::state::{AccountState, ProposalAction, UpgradePlan};
+
+    /// Store a chain of empty blocks on top of `genesis` the way consensus
+    /// commits them.
+    async fn store_chain(genesis: &GlobalState, len: u64) -> Storage {
+        let storage = Storage::new("unused").unwrap();
+        storage.save_state_at_height(0, genesis).unwrap();
+        let (mut state, mut prev_hash) = (genesis.clone(), [0u8; 32]);
+        for height in 1..=len {
+            let mut block = Block {
+                header: BlockHeader {
+                    height,
+                    prev_hash,
+                    transactions_root: [0u8; 32],
+                    state_root: [0u8; 32],
+                    timestamp: height,
+                    proposer: [0u8; 32],
+                    validator_set_hash: [0u8; 32],
+                    audit_hash: [0u8; 32],
+                    signers_hash: [0u8; 32],
+                },
+                transactions: Vec::new(),
+                votes: Vec::new(),
+            };
+            let (next, commit) = execute_block(state, &block).await.unwrap();
+            block.header.state_root = commit.app_hash;
+            block.header.validator_set_hash = next.validator_set_hash();
+            block.header.audit_hash = next.audit_head;
+            storage.save_block(&block).unwrap();
+            storage
+                .save_canonical_block_at_height(height, block.hash())
+                .unwrap();
+            storage.save_state_at_height(height, &next).unwrap();
+            prev_hash = block.hash();
+            state = next;
+        }
+        storage
+    }
+
+    #[tokio::test]
+    async fn replay_reports_the_first_divergent_block() {
+        let mut genesis = GlobalState::default();
+        genesis.accounts.insert(
+            [1u8; 32],
+            AccountState {
+                lusd_balance: 500,
+                ..Default::default()
+            },
+        );
+        genesis.proposals.insert(
+            0,
+            GovernanceProposal {
+                proposer: [2u8; 32],
+                action: ProposalAction::ScheduleUpgrade(UpgradePlan {
+                    name: "v2".to_string(),
+                    height: 100_000,
+                }),
+                proposed_height: 0,
+                votes: BTreeMap::from([([2u8; 32], true)]),
+                executed: false,
+            },
+        );
+        let storage = store_chain(&genesis, 4).await;
+        assert!(replay(&storage, 0, 4).await.unwrap().is_none());
+
+        // A snapshot that execution does not reproduce is reported with its diff.
+        let mut tampered = storage.load_state_by_height(3).unwrap().unwrap();
+        tampered.total_lusd_supply = 42;
+        tampered.accounts.get_mut(&[1u8; 32]).unwrap().lusd_balance = 7;
+        tampered
+            .proposals
+            .get_mut(&0)
+            .unwrap()
+            .votes
+            .insert([3u8; 32], false);
+        storage.save_state_at_height(3, &tampered).unwrap();
+
+        let divergence = replay(&storage, 0, 4).await.unwrap().unwrap();
+        assert_eq!(divergence.height, 3);
+        assert_eq!(divergence.reason, "stored state differs");
+        let paths: Vec<_> = divergence.diff.iter().map(|d| d.path.as_str()).collect();
+        let account = format!("accounts.{}.lusd_balance", hex::encode([1u8; 32]));
+        let vote = format!("proposals.0.votes.{}", hex::encode([3u8; 32]));
+        assert_eq!(
+            paths,
+            vec![account.as_str(), vote.as_str(), "total_lusd_supply"]
+        );
+        assert_eq!(divergence.diff[2].stored, Value::from(42));
+
+        // Replaying from the tampered snapshot breaks the next header's root.
+        let divergence = replay(&storage, 3, 4).await.unwrap().unwrap();
+        assert_eq!(
+            (divergence.height, divergence.reason.as_str()),
+            (4, "state_root mismatch")
+        );
+        assert!(replay(&storage, 0, 2).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::webhook::Alert;
+use anyhow::{anyhow, bail, Context, Result};
+use lumina_api::RuleStates;
+use lumina_storage::db::Storage;
+use lumina_types::state::GlobalState;
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+/// State fields a rule can test, all read as numbers; booleans are 0 or 1.
+const FIELDS: [&str; 10] = [
+    "reserve_ratio",
+    "health_index",
+    "lusd_price",
+    "total_lusd_supply",
+    "total_ljun_supply",
+    "stabilization_pool_balance",
+    "insurance_fund_balance",
+    "redeem_queue_depth",
+    "flash_loan_utilization_bps",
+    "circuit_breaker_active",
+];
+
+fn field_value(state: &GlobalState, field: &str) -> f64 {
+    match field {
+        "reserve_ratio" => state.reserve_ratio,
+        "health_index" => state.health_index as f64,
+        "lusd_price" => state
+            .oracle_prices
+            .get("LUSD-USD")
+            .copied()
+            .unwrap_or(1_000_000) as f64,
+        "total_lusd_supply" => state.total_lusd_supply as f64,
+        "total_ljun_supply" => state.total_ljun_supply as f64,
+        "stabilization_pool_balance" => state.stabilization_pool_balance as f64,
+        "insurance_fund_balance" => state.insurance_fund_balance as f64,
+        "redeem_queue_depth" => state.fair_redeem_queue.len() as f64,
+        "flash_loan_utilization_bps" => state.flash_loan_utilization_bps as f64,
+        "circuit_breaker_active" => f64::from(u8::from(state.circuit_breaker_active)),
+        _ => unreachable!("fields are checked when rules are parsed"),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cmp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Cmp {
+    fn holds(self, lhs: f64, rhs: f64) -> bool {
+        match self {
+            Cmp::Lt => lhs < rhs,
+            Cmp::Le => lhs <= rhs,
+            Cmp::Gt => lhs > rhs,
+            Cmp::Ge => lhs >= rhs,
+            Cmp::Eq => lhs == rhs,
+            Cmp::Ne => lhs != rhs,
+        }
+    }
+}
+
+/// `<field> <op> <number>`, e.g. `reserve_ratio < 0.9`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    field: &'static str,
+    cmp: Cmp,
+    threshold: f64,
+}
+
+impl Condition {
+    pub fn parse(expr: &str) -> Result<Self> {
+        let parts: Vec<&str> = expr.split_whitespace().collect();
+        let [field, op, threshold] = parts[..] else {
+            bail!("Expected `<field> <op> <number>`: {}", expr);
+        };
+        let field = FIELDS
+            .iter()
+            .find(|f| **f == field)
+            .ok_or_else(|| anyhow!("Unknown rule field: {}", field))?;
+        let cmp = match op {
+            "<" => Cmp::Lt,
+            "<=" => Cmp::Le,
+            ">" => Cmp::Gt,
+            ">=" => Cmp::Ge,
+            "==" => Cmp::Eq,
+            "!=" => Cmp::Ne,
+            _ => bail!("Unknown rule operator: {}", op),
+        };
+        let threshold: f64 = threshold
+            .parse()
+            .ok()
+            .filter(|t: &f64| t.is_finite())
+            .ok_or_else(|| anyhow!("Invalid rule threshold: {}", threshold))?;
+        Ok(Self {
+            field,
+            cmp,
+            threshold,
+        })
+    }
+
+    fn value(&self, state: &GlobalState) -> f64 {
+        field_value(state, self.field)
+    }
+
+    fn holds(&self, state: &GlobalState) -> bool {
+        self.cmp.holds(self.value(state), self.threshold)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: String,
+    expr: String,
+    /// Consecutive blocks the condition must hold before the rule fires
+    #[serde(default = "one_block")]
+    for_blocks: u64,
+}
+
+fn one_block() -> u64 {
+    1
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<RuleSpec>,
+}
+
+struct Rule {
+    name: String,
+    condition: Condition,
+    for_blocks: u64,
+    /// Consecutive blocks the condition has held so far
+    streak: u64,
+    firing: bool,
+}
+
+/// Threshold rules over committed state, each firing once its condition has
+/// held for `for_blocks` consecutive blocks and resolving on the first block
+/// it no longer holds.
+pub struct RuleEngine {
+    rules: Vec<Rule>,
+}
+
+impl RuleEngine {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: RulesFile = toml::from_str(text).context("Invalid alert rules")?;
+        if file.rules.is_empty() {
+            bail!("Alert rules file lists no rules");
+        }
+        let mut rules: Vec<Rule> = Vec::new();
+        for spec in file.rules {
+            if spec.name.is_empty() {
+                bail!("Alert rule names must not be empty");
+            }
+            if rules.iter().any(|r| r.name == spec.name) {
+                bail!("Duplicate alert rule: {}", spec.name);
+            }
+            if spec.for_blocks == 0 {
+                bail!("Alert rule {} needs for_blocks of at least 1", spec.name);
+            }
+            rules.push(Rule {
+                condition: Condition::parse(&spec.expr)
+                    .with_context(|| format!("Alert rule {}", spec.name))?,
+                name: spec.name,
+                for_blocks: spec.for_blocks,
+                streak: 0,
+                firing: false,
+            });
+        }
+        Ok(Self { rules })
+    }
+
+    /// Advance every rule by the block committed at `height`, returning the
+    /// rules that started or stopped firing.
+    pub fn evaluate(&mut self, state: &GlobalState, height: u64) -> Vec<Alert> {
+        let mut changed = Vec::new();
+        for rule in &mut self.rules {
+            let value = rule.condition.value(state);
+            if rule.condition.holds(state) {
+                rule.streak += 1;
+                if !rule.firing && rule.streak >= rule.for_blocks {
+                    rule.firing = true;
+                    changed.push(Alert::RuleFiring {
+                        height,
+                        rule: rule.name.clone(),
+                        value,
+                    });
+                }
+            } else {
+                rule.streak = 0;
+                if rule.firing {
+                    rule.firing = false;
+                    changed.push(Alert::RuleResolved {
+                        height,
+                        rule: rule.name.clone(),
+                        value,
+                    });
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn states(&self) -> RuleStates {
+        self.rules
+            .iter()
+            .map(|r| (r.name.clone(), r.firing))
+            .collect()
+    }
+}
+
+/// Evaluate `engine` against each block committed after startup, publishing
+/// rule states for `/metrics` and forwarding transitions to the webhooks.
+pub async fn run(
+    storage: Arc<Storage>,
+    mut snapshots: watch::Receiver<Arc<GlobalState>>,
+    mut engine: RuleEngine,
+    states: watch::Sender<RuleStates>,
+    alerts: Option<mpsc::Sender<Alert>>,
+) {
+    states.send_replace(engine.states());
+    let mut seen = storage.load_tip().ok().flatten().map_or(0, |(h, _)| h);
+    while snapshots.changed().await.is_ok() {
+        let tip = match storage.load_tip() {
+            Ok(tip) => tip.map_or(0, |(h, _)| h),
+            Err(e) => {
+                warn!("Alert rules could not read the tip: {}", e);
+                continue;
+            }
+        };
+        for height in seen + 1..=tip {
+            let state = match storage.load_state_by_height(height) {
+                Ok(Some(state)) => state,
+                Ok(None) => {
+                    warn!("Alert rules skipped block {}: state not stored", height);
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Alert rules skipped block {}: {}", height, e);
+                    continue;
+                }
+            };
+            for alert in engine.evaluate(&state, height) {
+                info!("Alert transition at height {}: {:?}", height, alert);
+                if let Some(alerts) = &alerts {
+                    let _ = alerts.try_send(alert);
+                }
+            }
+        }
+        seen = tip;
+        states.send_replace(engine.states());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_fire_after_their_streak_and_resolve_at_once() {
+        let mut engine = RuleEngine::parse(
+            r#"
+            [[rule]]
+            name = "undercollateralized"
+            expr = "reserve_ratio < 0.9"
+            for_blocks = 3
+
+            [[rule]]
+            name = "breaker"
+            expr = "circuit_breaker_active == 1"
+            "#,
+        )
+        .unwrap();
+        let low = GlobalState {
+            reserve_ratio: 0.85,
+            ..Default::default()
+        };
+        let healthy = GlobalState {
+            reserve_ratio: 1.2,
+            ..Default::default()
+        };
+
+        assert!(engine.evaluate(&low, 1).is_empty());
+        assert!(engine.evaluate(&healthy, 2).is_empty());
+        assert!(engine.evaluate(&low, 3).is_empty());
+        assert!(engine.evaluate(&low, 4).is_empty());
+        assert_eq!(
+            engine.evaluate(&low, 5),
+            [Alert::RuleFiring {
+                height: 5,
+                rule: "undercollateralized".to_string(),
+                value: 0.85,
+            }]
+        );
+        assert!(engine.evaluate(&low, 6).is_empty());
+        assert_eq!(
+            engine.states(),
+            RuleStates::from([
+                ("breaker".to_string(), false),
+                ("undercollateralized".to_string(), true),
+            ])
+        );
+        assert_eq!(
+            engine.evaluate(&healthy, 7),
+            [Alert::RuleResolved {
+                height: 7,
+                rule: "undercollateralized".to_string(),
+                value: 1.2,
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        let parse = |expr: &str| {
+            RuleEngine::parse(&format!("[[rule]]\nname = \"r\"\nexpr = \"{}\"", expr))
+                .map(|_| ())
+                .map_err(|e| format!("{:#}", e))
+        };
+        assert!(parse("health_index >= 5000").is_ok());
+        assert_eq!(
+            parse("peg < 1").unwrap_err(),
+            "Alert rule r: Unknown rule field: peg"
+        );
+        assert_eq!(
+            parse("reserve_ratio ~ 1").unwrap_err(),
+            "Alert rule r: Unknown rule operator: ~"
+        );
+        assert!(parse("reserve_ratio < NaN").is_err());
+        assert!(parse("reserve_ratio<1").is_err());
+        assert!(RuleEngine::parse("").is_err());
+    }
+}
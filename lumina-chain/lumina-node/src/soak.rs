@@ -0,0 +1,303 @@
+use anyhow::{anyhow, bail, Result};
+use lumina_consensus::ConsensusService;
+use lumina_crypto::signatures::{generate_keypair, sign, SigningKey};
+use lumina_storage::db::Storage;
+use lumina_types::instruction::{AssetType, StablecoinInstruction};
+use lumina_types::state::{AccountState, GlobalState};
+use lumina_types::transaction::Transaction;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch, RwLock};
+use tracing::info;
+
+/// Blocks between samples; the first sample is the baseline.
+const SAMPLE_EVERY_BLOCKS: u64 = 60;
+/// How long to wait for a block before declaring production stalled.
+const STALL_TIMEOUT: Duration = Duration::from_secs(60);
+/// LUMINA each load wallet starts with; every transfer moves one unit.
+const WALLET_FUNDING: u64 = 1_000_000_000;
+
+/// Run length, load and the regression thresholds a soak must stay under.
+#[derive(Debug, Clone)]
+pub struct SoakConfig {
+    pub duration: Duration,
+    pub txs_per_block: usize,
+    pub max_memory_growth_mib: u64,
+    pub max_fd_growth: u64,
+    pub max_latency_drift_pct: u64,
+    pub max_storage_per_block_kib: u64,
+}
+
+/// Process and chain measurements at one point of the run.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Sample {
+    pub elapsed_secs: u64,
+    pub height: u64,
+    /// Unavailable outside Linux
+    pub rss_bytes: Option<u64>,
+    pub open_fds: Option<u64>,
+    pub storage_bytes: u64,
+    /// Mean build-and-commit time of the blocks since the previous sample
+    pub mean_block_millis: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SoakReport {
+    pub blocks: u64,
+    pub samples: Vec<Sample>,
+    /// Thresholds the run exceeded; empty when it passed
+    pub regressions: Vec<String>,
+}
+
+/// Produce a chain in `data_dir` under continuous transfer load for the
+/// configured duration, sampling memory, file descriptors, storage size and
+/// block latency as it goes. `storage` must hold no chain yet.
+pub async fn run(storage: Arc<Storage>, data_dir: &Path, config: SoakConfig) -> Result<SoakReport> {
+    if config.txs_per_block == 0 {
+        bail!("txs_per_block must be at least 1");
+    }
+    if storage.load_tip()?.is_some() {
+        bail!("Soak needs an empty data directory");
+    }
+    let wallets: Vec<SigningKey> = (0..config.txs_per_block.max(2))
+        .map(|_| generate_keypair())
+        .collect();
+    let mut genesis = lumina_genesis::create_genesis_state();
+    for key in &wallets {
+        genesis.accounts.insert(
+            key.verifying_key().to_bytes(),
+            AccountState {
+                lumina_balance: WALLET_FUNDING,
+                ..Default::default()
+            },
+        );
+    }
+    storage.save_state(&genesis)?;
+    storage.save_state_at_height(0, &genesis)?;
+    storage.save_tip(0, [0u8; 32])?;
+
+    let (snapshot_tx, _) = watch::channel(Arc::new(genesis.clone()));
+    let state = Arc::new(RwLock::new(genesis));
+    // No peers: broadcasts fail fast against the closed network channel
+    let (network_tx, _) = mpsc::channel(1);
+    let (tx_sender, tx_receiver) = mpsc::channel(config.txs_per_block.max(1000));
+    let (_block_sender, block_receiver) = mpsc::channel(1);
+    let service = ConsensusService::new(
+        state.clone(),
+        snapshot_tx,
+        storage,
+        network_tx,
+        tx_receiver,
+        block_receiver,
+        data_dir.join("consensus.wal"),
+    );
+    let mut status = service.status();
+    tokio::spawn(service.run());
+
+    let started = Instant::now();
+    let mut samples = Vec::new();
+    let mut window_millis = 0u64;
+    let mut window_blocks = 0u64;
+    let mut height = 0u64;
+    while started.elapsed() < config.duration {
+        let batch = load_batch(&*state.read().await, &wallets, height, config.txs_per_block);
+        for tx in batch {
+            tx_sender
+                .send(tx)
+                .await
+                .map_err(|_| anyhow!("Consensus stopped"))?;
+        }
+        let committed = tokio::time::timeout(STALL_TIMEOUT, status.wait_for(|s| s.height > height))
+            .await
+            .map_err(|_| anyhow!("Block production stalled at height {}", height))?
+            .map_err(|_| anyhow!("Consensus stopped"))?
+            .clone();
+        height = committed.height;
+        window_millis += committed.last_block_millis;
+        window_blocks += 1;
+
+        if window_blocks == SAMPLE_EVERY_BLOCKS {
+            let sample = take_sample(started, height, data_dir, window_millis / window_blocks);
+            info!(
+                "Soak sample at height {}: rss {:?} fds {:?} storage {} block {}ms",
+                sample.height,
+                sample.rss_bytes,
+                sample.open_fds,
+                sample.storage_bytes,
+                sample.mean_block_millis
+            );
+            samples.push(sample);
+            (window_millis, window_blocks) = (0, 0);
+        }
+    }
+    if let Some(mean) = window_millis.checked_div(window_blocks) {
+        samples.push(take_sample(started, height, data_dir, mean));
+    }
+
+    Ok(SoakReport {
+        blocks: height,
+        regressions: regressions(&samples, &config),
+        samples,
+    })
+}
+
+/// One LUMINA transfer from each of `count` wallets to the next, in a
+/// window that rotates with `height`, at the senders' current nonces.
+fn load_batch(
+    state: &GlobalState,
+    wallets: &[SigningKey],
+    height: u64,
+    count: usize,
+) -> Vec<Transaction> {
+    let n = wallets.len();
+    (0..count.min(n))
+        .map(|i| {
+            let sender = &wallets[(height as usize + i) % n];
+            let to = wallets[(height as usize + i + 1) % n]
+                .verifying_key()
+                .to_bytes();
+            let address = sender.verifying_key().to_bytes();
+            let mut tx = Transaction {
+                sender: address,
+                nonce: state.accounts.get(&address).map_or(0, |a| a.nonce),
+                instruction: StablecoinInstruction::Transfer {
+                    to,
+                    amount: 1,
+                    asset: AssetType::Lumina,
+                },
+                signature: Vec::new(),
+                gas_limit: 100_000,
+                gas_price: 1,
+            };
+            tx.signature = sign(sender, &tx.signing_bytes());
+            tx
+        })
+        .collect()
+}
+
+fn take_sample(started: Instant, height: u64, data_dir: &Path, mean_block_millis: u64) -> Sample {
+    Sample {
+        elapsed_secs: started.elapsed().as_secs(),
+        height,
+        rss_bytes: resident_bytes(),
+        open_fds: std::fs::read_dir("/proc/self/fd")
+            .ok()
+            .map(|entries| entries.count() as u64),
+        storage_bytes: dir_size(data_dir),
+        mean_block_millis,
+    }
+}
+
+fn resident_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Thresholds exceeded between the first (baseline) and last samples.
+pub fn regressions(samples: &[Sample], config: &SoakConfig) -> Vec<String> {
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return vec!["No blocks were produced".to_string()];
+    };
+    let mut found = Vec::new();
+    if let (Some(before), Some(after)) = (first.rss_bytes, last.rss_bytes) {
+        let growth_mib = after.saturating_sub(before) / (1024 * 1024);
+        if growth_mib > config.max_memory_growth_mib {
+            found.push(format!(
+                "Resident memory grew {} MiB (limit {})",
+                growth_mib, config.max_memory_growth_mib
+            ));
+        }
+    }
+    if let (Some(before), Some(after)) = (first.open_fds, last.open_fds) {
+        let growth = after.saturating_sub(before);
+        if growth > config.max_fd_growth {
+            found.push(format!(
+                "Open file descriptors grew by {} (limit {})",
+                growth, config.max_fd_growth
+            ));
+        }
+    }
+    let limit = first.mean_block_millis.max(1) * (100 + config.max_latency_drift_pct) / 100;
+    if last.mean_block_millis > limit {
+        found.push(format!(
+            "Block latency drifted from {}ms to {}ms (limit {}ms)",
+            first.mean_block_millis, last.mean_block_millis, limit
+        ));
+    }
+    if last.height > first.height {
+        let per_block_kib = last.storage_bytes.saturating_sub(first.storage_bytes)
+            / (last.height - first.height)
+            / 1024;
+        if per_block_kib > config.max_storage_per_block_kib {
+            found.push(format!(
+                "Storage grew {} KiB per block (limit {})",
+                per_block_kib, config.max_storage_per_block_kib
+            ));
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(height: u64, rss_mib: u64, fds: u64, storage_kib: u64, millis: u64) -> Sample {
+        Sample {
+            elapsed_secs: height * 5,
+            height,
+            rss_bytes: Some(rss_mib * 1024 * 1024),
+            open_fds: Some(fds),
+            storage_bytes: storage_kib * 1024,
+            mean_block_millis: millis,
+        }
+    }
+
+    #[test]
+    fn flags_each_threshold_against_the_baseline() {
+        let config = SoakConfig {
+            duration: Duration::from_secs(3600),
+            txs_per_block: 10,
+            max_memory_growth_mib: 100,
+            max_fd_growth: 10,
+            max_latency_drift_pct: 50,
+            max_storage_per_block_kib: 64,
+        };
+        let steady = [
+            sample(60, 200, 40, 6_000, 20),
+            sample(600, 290, 48, 30_000, 29),
+        ];
+        assert!(regressions(&steady, &config).is_empty());
+
+        let leaking = [
+            sample(60, 200, 40, 6_000, 20),
+            sample(600, 400, 80, 60_000, 31),
+        ];
+        let found = regressions(&leaking, &config);
+        assert_eq!(found.len(), 4, "{:?}", found);
+        assert!(found[0].starts_with("Resident memory grew 200 MiB"));
+        assert!(found[1].starts_with("Open file descriptors grew by 40"));
+        assert!(found[2].starts_with("Block latency drifted from 20ms to 31ms"));
+        assert!(found[3].starts_with("Storage grew 100 KiB per block"));
+
+        assert_eq!(regressions(&[], &config), ["No blocks were produced"]);
+    }
+}
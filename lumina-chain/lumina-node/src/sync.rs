@@ -0,0 +1,184 @@
+use lumina_network::{PeerId, SyncRequest, SyncResponse};
+use lumina_storage::db::Storage;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Largest payload returned for a single sync request.
+pub const MAX_SYNC_RESPONSE_BYTES: usize = 4 * 1024 * 1024;
+/// Requests a single peer may make per `SYNC_RATE_WINDOW`.
+pub const SYNC_REQUESTS_PER_WINDOW: u32 = 64;
+pub const SYNC_RATE_WINDOW: Duration = Duration::from_secs(10);
+const MAX_TRACKED_PEERS: usize = 1024;
+
+struct RateWindow {
+    start: Instant,
+    count: u32,
+}
+
+/// Answers peers' `SyncRequest`s from local storage.
+pub struct SyncServer {
+    storage: Arc<Storage>,
+    windows: HashMap<PeerId, RateWindow>,
+}
+
+impl SyncServer {
+    pub fn new(storage: Arc<Storage>) -> Self {
+        Self {
+            storage,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Returns false once `peer` has used up its request budget.
+    fn admit(&mut self, peer: PeerId, now: Instant) -> bool {
+        if self.windows.len() >= MAX_TRACKED_PEERS && !self.windows.contains_key(&peer) {
+            self.windows
+                .retain(|_, w| now.duration_since(w.start) < SYNC_RATE_WINDOW);
+        }
+        let window = self.windows.entry(peer).or_insert(RateWindow {
+            start: now,
+            count: 0,
+        });
+        if now.duration_since(window.start) >= SYNC_RATE_WINDOW {
+            window.start = now;
+            window.count = 0;
+        }
+        window.count += 1;
+        window.count <= SYNC_REQUESTS_PER_WINDOW
+    }
+
+    pub fn handle(&mut self, peer: PeerId, request: SyncRequest, now: Instant) -> SyncResponse {
+        if !self.admit(peer, now) {
+            return SyncResponse::Error("rate limited".to_string());
+        }
+        match request {
+            SyncRequest::BlockByHeight(height) => {
+                let block = match self.storage.load_block_by_height(height) {
+                    Ok(block) => block,
+                    Err(e) => return SyncResponse::Error(e.to_string()),
+                };
+                let bytes = match block.map(|b| bincode::serialize(&b)).transpose() {
+                    Ok(bytes) => bytes,
+                    Err(e) => return SyncResponse::Error(e.to_string()),
+                };
+                match bytes {
+                    Some(b) if b.len() > MAX_SYNC_RESPONSE_BYTES => {
+                        SyncResponse::Error("response too large".to_string())
+                    }
+                    bytes => SyncResponse::Block(bytes),
+                }
+            }
+            // Bincode-encoded `Vec<BlockProof>` for the block
+            SyncRequest::ZkProofByBlock(block_hash) => {
+                let proofs = match self.storage.load_block_proofs(&block_hash) {
+                    Ok(proofs) => proofs,
+                    Err(e) => return SyncResponse::Error(e.to_string()),
+                };
+                match proofs.map(|p| bincode::serialize(&p)).transpose() {
+                    Ok(Some(b)) if b.len() > MAX_SYNC_RESPONSE_BYTES => {
+                        SyncResponse::Error("response too large".to_string())
+                    }
+                    Ok(bytes) => SyncResponse::ZkProof(bytes),
+                    Err(e) => SyncResponse::Error(e.to_string()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::block::{Block, BlockHeader};
+    use lumina_types::proof::{BlockProof, ProofKind};
+
+    fn block_at(height: u64) -> Block {
+        Block {
+            header: BlockHeader {
+                height,
+                prev_hash: [0u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: [0u8; 32],
+                timestamp: 0,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions: Vec::new(),
+            votes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn serves_canonical_blocks_and_proofs() {
+        let storage = Arc::new(Storage::new("unused").unwrap());
+        let block = block_at(3);
+        storage.save_block(&block).unwrap();
+        storage
+            .save_canonical_block_at_height(3, block.hash())
+            .unwrap();
+        let proof = |bytes: Vec<u8>| BlockProof {
+            tx_index: 0,
+            tx_id: [0u8; 32],
+            submitter: [0u8; 32],
+            kind: ProofKind::TaxAttestation { period: 1 },
+            proof: bytes,
+        };
+        storage
+            .save_block_proofs(block.hash(), &[proof(vec![7u8; 64])])
+            .unwrap();
+        storage
+            .save_block_proofs([1u8; 32], &[proof(vec![0u8; MAX_SYNC_RESPONSE_BYTES])])
+            .unwrap();
+
+        let mut server = SyncServer::new(storage);
+        let peer = PeerId::random();
+        let now = Instant::now();
+        match server.handle(peer, SyncRequest::BlockByHeight(3), now) {
+            SyncResponse::Block(Some(bytes)) => {
+                assert_eq!(bincode::deserialize::<Block>(&bytes).unwrap(), block)
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(matches!(
+            server.handle(peer, SyncRequest::BlockByHeight(4), now),
+            SyncResponse::Block(None)
+        ));
+        assert!(matches!(
+            server.handle(peer, SyncRequest::ZkProofByBlock(block.hash()), now),
+            SyncResponse::ZkProof(Some(bytes))
+                if bincode::deserialize::<Vec<BlockProof>>(&bytes).unwrap()[0].proof == vec![7u8; 64]
+        ));
+        assert!(matches!(
+            server.handle(peer, SyncRequest::ZkProofByBlock([1u8; 32]), now),
+            SyncResponse::Error(e) if e == "response too large"
+        ));
+    }
+
+    #[test]
+    fn rate_limits_each_peer_per_window() {
+        let mut server = SyncServer::new(Arc::new(Storage::new("unused").unwrap()));
+        let (peer, other) = (PeerId::random(), PeerId::random());
+        let now = Instant::now();
+        for _ in 0..SYNC_REQUESTS_PER_WINDOW {
+            assert!(!matches!(
+                server.handle(peer, SyncRequest::BlockByHeight(1), now),
+                SyncResponse::Error(_)
+            ));
+        }
+        assert!(matches!(
+            server.handle(peer, SyncRequest::BlockByHeight(1), now),
+            SyncResponse::Error(e) if e == "rate limited"
+        ));
+        assert!(matches!(
+            server.handle(other, SyncRequest::BlockByHeight(1), now),
+            SyncResponse::Block(None)
+        ));
+        assert!(matches!(
+            server.handle(peer, SyncRequest::BlockByHeight(1), now + SYNC_RATE_WINDOW),
+            SyncResponse::Block(None)
+        ));
+    }
+}
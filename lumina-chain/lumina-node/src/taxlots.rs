@@ -0,0 +1,168 @@
+//! Tax lot indexer: maintains per-account cost-basis ledgers from the
+//! committed states, off the consensus path. Enabled with `--tax-lots`.
+
+use anyhow::{bail, Result};
+use lumina_storage::db::Storage;
+use lumina_types::state::GlobalState;
+use lumina_types::taxlots::{LotMethod, TaxLedger};
+use std::sync::Arc;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+/// Ledgers of the accounts whose tracked balances moved from `prev` to
+/// `next`. Accounts revived from the archive keep the lots they had.
+pub fn index_block(
+    method: LotMethod,
+    prev: &GlobalState,
+    next: &GlobalState,
+    height: u64,
+    timestamp: u64,
+    mut load: impl FnMut(&[u8; 32]) -> Result<TaxLedger>,
+) -> Result<Vec<([u8; 32], TaxLedger)>> {
+    let mut changed = Vec::new();
+    for (address, after) in &next.accounts {
+        let before = prev.accounts.get(address);
+        if before.is_none() && prev.archived_accounts.contains_key(address) {
+            continue;
+        }
+        if before.is_some_and(|b| {
+            b.ljun_balance == after.ljun_balance
+                && b.lumina_balance == after.lumina_balance
+                && b.custom_balances == after.custom_balances
+        }) {
+            continue;
+        }
+        let mut ledger = load(address)?;
+        if ledger.apply(method, before, after, next, height, timestamp) {
+            changed.push((*address, ledger));
+        }
+    }
+    Ok(changed)
+}
+
+fn index_height(
+    storage: &Storage,
+    method: LotMethod,
+    prev: &GlobalState,
+    height: u64,
+) -> Result<GlobalState> {
+    let Some(next) = storage.load_state_by_height(height)? else {
+        bail!("State not stored");
+    };
+    let timestamp = storage
+        .load_block_by_height(height)?
+        .map_or(0, |b| b.header.timestamp);
+    let changed = index_block(method, prev, &next, height, timestamp, |address| {
+        Ok(storage.load_tax_lots(method, address)?.unwrap_or_default())
+    })?;
+    storage.save_tax_lots(method, height, &changed)?;
+    Ok(next)
+}
+
+/// Index every committed height from genesis, resuming after the last one
+/// finished, then each new height as it commits.
+pub async fn run(
+    storage: Arc<Storage>,
+    mut snapshots: watch::Receiver<Arc<GlobalState>>,
+    method: LotMethod,
+) {
+    let mut next_height = match storage.load_tax_lot_height(method) {
+        Ok(height) => height.map_or(0, |h| h + 1),
+        Err(e) => {
+            warn!("Tax lot indexer disabled: {}", e);
+            return;
+        }
+    };
+    let mut prev = match next_height.checked_sub(1) {
+        None => Some(GlobalState::default()),
+        Some(h) => storage.load_state_by_height(h).ok().flatten(),
+    };
+    info!(
+        "Indexing {} tax lots from height {}",
+        method.as_str(),
+        next_height
+    );
+    loop {
+        let tip = storage.load_tip().ok().flatten().map_or(0, |(h, _)| h);
+        while next_height <= tip {
+            let Some(state) = prev.as_ref() else {
+                warn!(
+                    "Tax lot indexer stopped: state {} not stored",
+                    next_height - 1
+                );
+                return;
+            };
+            match index_height(&storage, method, state, next_height) {
+                Ok(next) => {
+                    prev = Some(next);
+                    next_height += 1;
+                }
+                Err(e) => {
+                    warn!("Tax lot indexer stopped at height {}: {}", next_height, e);
+                    break;
+                }
+            }
+            // Catching up from genesis must not starve the runtime.
+            tokio::task::yield_now().await;
+        }
+        if snapshots.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::state::AccountState;
+    use lumina_types::taxlots::LotAsset;
+
+    #[test]
+    fn indexes_only_accounts_whose_tracked_balances_moved() {
+        let (trader, saver, revived) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+        let mut prev = GlobalState::default();
+        prev.oracle_prices
+            .insert("LUMINA-USD".to_string(), 1_000_000);
+        prev.accounts.insert(
+            trader,
+            AccountState {
+                lumina_balance: 50,
+                ..Default::default()
+            },
+        );
+        prev.accounts.insert(saver, AccountState::default());
+        prev.archived_accounts.insert(revived, 0);
+
+        let mut next = prev.clone();
+        next.archived_accounts.clear();
+        next.accounts.get_mut(&trader).unwrap().lumina_balance = 20;
+        next.accounts.get_mut(&saver).unwrap().lusd_balance = 900;
+        next.accounts.insert(
+            revived,
+            AccountState {
+                lumina_balance: 7,
+                ..Default::default()
+            },
+        );
+
+        let mut opened = TaxLedger::default();
+        opened.acquire(LotAsset::Lumina, 50, 40, 1, 10);
+        let changed = index_block(LotMethod::Fifo, &prev, &next, 5, 50, |address| {
+            assert_eq!(*address, trader);
+            Ok(opened.clone())
+        })
+        .unwrap();
+
+        assert_eq!(changed.len(), 1);
+        let disposal = &changed[0].1.disposals[0];
+        assert_eq!(
+            (
+                disposal.height,
+                disposal.quantity,
+                disposal.proceeds,
+                disposal.cost
+            ),
+            (5, 30, 30, 24)
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use rand::Rng;
+use serde_json::{json, Value};
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{warn, Event, Subscriber};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Span field carrying the hex transaction id. Spans that record it join the
+/// transaction's trace, whose id is the first 16 bytes of the tx id, so the
+/// API, mempool, execution and commit spans correlate without propagation.
+pub const TX_ID_FIELD: &str = "tx_id";
+
+const EXPORT_QUEUE: usize = 4096;
+const EXPORT_BATCH: usize = 512;
+const EXPORT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Install the global subscriber: fmt logging, plus OTLP/HTTP span export
+/// when `OTEL_EXPORTER_OTLP_ENDPOINT` is set (e.g. `http://collector:4318`).
+pub fn init() {
+    let fmt = tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO);
+    let otlp = std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+        .ok()
+        .filter(|e| !e.is_empty())
+        .map(|endpoint| {
+            let service =
+                std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "lumina-node".to_string());
+            let (tx, rx) = mpsc::channel(EXPORT_QUEUE);
+            tokio::spawn(export_loop(endpoint, service, rx));
+            OtlpLayer { tx }.with_filter(LevelFilter::INFO)
+        });
+    tracing_subscriber::registry().with(fmt).with(otlp).init();
+}
+
+/// Trace id for a hex transaction id.
+pub fn trace_id_for_tx(tx_id: &str) -> Option<[u8; 16]> {
+    let bytes = hex::decode(tx_id).ok()?;
+    bytes.get(..16)?.try_into().ok()
+}
+
+/// A finished span, ready for export.
+#[derive(Debug, Clone)]
+pub struct SpanRecord {
+    pub trace_id: [u8; 16],
+    pub span_id: [u8; 8],
+    pub parent_span_id: Option<[u8; 8]>,
+    /// Enclosing span in another trace, e.g. the block a tx executed in
+    pub link: Option<([u8; 16], [u8; 8])>,
+    pub name: &'static str,
+    pub start_nanos: u64,
+    pub end_nanos: u64,
+    /// OTLP `KeyValue`s
+    pub attributes: Vec<Value>,
+    /// (time, message) of events logged inside the span
+    pub events: Vec<(u64, String)>,
+}
+
+/// Records spans and queues them for the exporter; drops spans when the
+/// queue is full rather than blocking the traced code.
+pub struct OtlpLayer {
+    tx: mpsc::Sender<SpanRecord>,
+}
+
+impl<S> Layer<S> for OtlpLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = FieldVisitor::default();
+        attrs.record(&mut fields);
+
+        let parent = span.parent().and_then(|p| {
+            p.extensions()
+                .get::<SpanRecord>()
+                .map(|r| (r.trace_id, r.span_id))
+        });
+        let mut rng = rand::thread_rng();
+        let (trace_id, parent_span_id, link) = match (fields.trace_id, parent) {
+            (Some(t), Some((pt, ps))) if t != pt => (t, None, Some((pt, ps))),
+            (Some(t), p) => (t, p.map(|(_, ps)| ps), None),
+            (None, Some((pt, ps))) => (pt, Some(ps), None),
+            (None, None) => (rng.gen(), None, None),
+        };
+
+        span.extensions_mut().insert(SpanRecord {
+            trace_id,
+            span_id: rng.gen(),
+            parent_span_id,
+            link,
+            name: attrs.metadata().name(),
+            start_nanos: unix_nanos(),
+            end_nanos: 0,
+            attributes: fields.attributes,
+            events: Vec::new(),
+        });
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = FieldVisitor::default();
+        values.record(&mut fields);
+        let mut extensions = span.extensions_mut();
+        if let Some(record) = extensions.get_mut::<SpanRecord>() {
+            record.attributes.extend(fields.attributes);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.event_span(event) else {
+            return;
+        };
+        let mut fields = FieldVisitor::default();
+        event.record(&mut fields);
+        let mut extensions = span.extensions_mut();
+        if let Some(record) = extensions.get_mut::<SpanRecord>() {
+            let message = fields
+                .message
+                .unwrap_or_else(|| event.metadata().name().to_string());
+            record.events.push((unix_nanos(), message));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let Some(mut record) = span.extensions_mut().remove::<SpanRecord>() else {
+            return;
+        };
+        record.end_nanos = unix_nanos();
+        let _ = self.tx.try_send(record);
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    attributes: Vec<Value>,
+    trace_id: Option<[u8; 16]>,
+    message: Option<String>,
+}
+
+impl FieldVisitor {
+    fn push(&mut self, field: &Field, value: Value) {
+        self.attributes
+            .push(json!({ "key": field.name(), "value": value }));
+    }
+
+    fn push_string(&mut self, field: &Field, value: String) {
+        match field.name() {
+            "message" => self.message = Some(value),
+            name => {
+                if name == TX_ID_FIELD {
+                    self.trace_id = trace_id_for_tx(&value);
+                }
+                self.push(field, json!({ "stringValue": value }));
+            }
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, json!({ "intValue": value.to_string() }));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, json!({ "boolValue": value }));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push_string(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push_string(field, format!("{value:?}"));
+    }
+}
+
+fn unix_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// OTLP/JSON `ExportTraceServiceRequest` body for `spans`.
+pub fn encode_batch(service: &str, spans: &[SpanRecord]) -> Value {
+    let spans: Vec<Value> = spans
+        .iter()
+        .map(|s| {
+            let mut span = json!({
+                "traceId": hex::encode(s.trace_id),
+                "spanId": hex::encode(s.span_id),
+                "name": s.name,
+                "kind": 1,
+                "startTimeUnixNano": s.start_nanos.to_string(),
+                "endTimeUnixNano": s.end_nanos.to_string(),
+                "attributes": s.attributes,
+                "events": s.events.iter().map(|(t, name)| json!({
+                    "timeUnixNano": t.to_string(),
+                    "name": name,
+                })).collect::<Vec<_>>(),
+            });
+            if let Some(parent) = s.parent_span_id {
+                span["parentSpanId"] = json!(hex::encode(parent));
+            }
+            if let Some((trace_id, span_id)) = s.link {
+                span["links"] = json!([{
+                    "traceId": hex::encode(trace_id),
+                    "spanId": hex::encode(span_id),
+                }]);
+            }
+            span
+        })
+        .collect();
+    json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{ "key": "service.name", "value": { "stringValue": service } }],
+            },
+            "scopeSpans": [{ "scope": { "name": "lumina" }, "spans": spans }],
+        }],
+    })
+}
+
+async fn export_loop(endpoint: String, service: String, mut rx: mpsc::Receiver<SpanRecord>) {
+    let client = reqwest::Client::new();
+    let url = format!("{}/v1/traces", endpoint.trim_end_matches('/'));
+    let mut batch = Vec::with_capacity(EXPORT_BATCH);
+    let mut interval = tokio::time::interval(EXPORT_INTERVAL);
+    loop {
+        let room = EXPORT_BATCH - batch.len();
+        tokio::select! {
+            n = rx.recv_many(&mut batch, room) => {
+                if n == 0 {
+                    break;
+                }
+                if batch.len() < EXPORT_BATCH {
+                    continue;
+                }
+            }
+            _ = interval.tick() => {
+                if batch.is_empty() {
+                    continue;
+                }
+            }
+        }
+        let body = encode_batch(&service, &batch);
+        batch.clear();
+        let sent = client.post(&url).json(&body).send().await;
+        if let Err(e) = sent.and_then(|r| r.error_for_status()) {
+            warn!("OTLP export failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing::info_span;
+
+    #[test]
+    fn tx_spans_share_a_trace_linked_to_their_block() {
+        let (tx, mut rx) = mpsc::channel(16);
+        let subscriber = tracing_subscriber::registry().with(OtlpLayer { tx });
+        let tx_id = hex::encode([7u8; 32]);
+        tracing::subscriber::with_default(subscriber, || {
+            info_span!("tx.submit", tx_id = %tx_id).in_scope(|| {
+                tracing::info!("queued");
+            });
+            info_span!("block.import", height = 3u64).in_scope(|| {
+                info_span!("tx.execute", tx_id = %tx_id).in_scope(|| {
+                    info_span!("inner").in_scope(|| {});
+                });
+            });
+        });
+
+        let mut spans = Vec::new();
+        while let Ok(span) = rx.try_recv() {
+            spans.push(span);
+        }
+        let by_name = |name: &str| spans.iter().find(|s| s.name == name).unwrap();
+        let (submit, block) = (by_name("tx.submit"), by_name("block.import"));
+        let (execute, inner) = (by_name("tx.execute"), by_name("inner"));
+
+        assert_eq!(submit.trace_id, [7u8; 16]);
+        assert_eq!(submit.events[0].1, "queued");
+        assert_eq!(execute.trace_id, submit.trace_id);
+        assert_eq!(execute.parent_span_id, None);
+        assert_eq!(execute.link, Some((block.trace_id, block.span_id)));
+        assert_eq!(inner.trace_id, execute.trace_id);
+        assert_eq!(inner.parent_span_id, Some(execute.span_id));
+
+        let body = encode_batch("lumina-node", &spans);
+        let exported = &body["resourceSpans"][0]["scopeSpans"][0]["spans"];
+        assert_eq!(exported.as_array().unwrap().len(), 4);
+        assert!(exported
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|s| s["traceId"] == hex::encode([7u8; 16]) && s["name"] == "tx.execute"));
+    }
+}
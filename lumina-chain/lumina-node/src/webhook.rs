@@ -0,0 +1,507 @@
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, Mac};
+use lumina_storage::db::Storage;
+use lumina_types::block::Block;
+use lumina_types::instruction::StablecoinInstruction;
+use lumina_types::receipt::BlockReceipts;
+use lumina_types::state::GlobalState;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
+use tracing::{info, warn};
+
+/// Header carrying `sha256=<hex HMAC of the body>` under the endpoint secret.
+pub const SIGNATURE_HEADER: &str = "X-Lumina-Signature";
+pub const EVENT_HEADER: &str = "X-Lumina-Event";
+
+/// Attempts per delivery; the wait doubles after each failure.
+const MAX_ATTEMPTS: u32 = 5;
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const EVENT_KINDS: [&str; 7] = [
+    "circuit_breaker_activated",
+    "health_index_crossed",
+    "large_mint",
+    "large_redemption",
+    "proof_of_reserves_failed",
+    "rule_firing",
+    "rule_resolved",
+];
+
+/// Contents of the `--webhooks` file.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    /// Health index levels (0-10000) that fire when crossed either way
+    #[serde(default)]
+    pub health_thresholds: Vec<u64>,
+    /// Smallest single mint or redemption, in LUSD base units, that fires
+    pub large_amount: Option<u64>,
+    #[serde(default, rename = "endpoint")]
+    pub endpoints: Vec<Endpoint>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Endpoint {
+    pub url: String,
+    /// HMAC key for the signature header
+    pub secret: String,
+    /// Event kinds to deliver; all when empty
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl Endpoint {
+    fn wants(&self, kind: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == kind)
+    }
+}
+
+impl WebhookConfig {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let config: Self = toml::from_str(text).context("Invalid webhook config")?;
+        if config.endpoints.is_empty() {
+            bail!("Webhook config lists no endpoints");
+        }
+        if config.health_thresholds.iter().any(|t| *t > 10_000) {
+            bail!("Health thresholds must be at most 10000");
+        }
+        for endpoint in &config.endpoints {
+            if !endpoint.url.starts_with("http://") && !endpoint.url.starts_with("https://") {
+                bail!("Webhook URL must be http(s): {}", endpoint.url);
+            }
+            if endpoint.secret.is_empty() {
+                bail!("Webhook {} has an empty secret", endpoint.url);
+            }
+            if let Some(kind) = endpoint
+                .events
+                .iter()
+                .find(|e| !EVENT_KINDS.contains(&e.as_str()))
+            {
+                bail!("Unknown webhook event: {}", kind);
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// A stability event as delivered in the webhook body.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Alert {
+    CircuitBreakerActivated {
+        height: u64,
+        reserve_ratio: f64,
+    },
+    HealthIndexCrossed {
+        height: u64,
+        threshold: u64,
+        from: u64,
+        to: u64,
+    },
+    LargeMint {
+        height: u64,
+        tx_id: String,
+        account: String,
+        amount: u64,
+    },
+    LargeRedemption {
+        height: u64,
+        tx_id: String,
+        account: String,
+        amount: u64,
+    },
+    ProofOfReservesFailed {
+        height: u64,
+        tx_id: String,
+        submitter: String,
+        total_reserves: u64,
+    },
+    /// An alert rule's condition has held for its required blocks
+    RuleFiring {
+        height: u64,
+        rule: String,
+        value: f64,
+    },
+    RuleResolved {
+        height: u64,
+        rule: String,
+        value: f64,
+    },
+}
+
+impl Alert {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Alert::CircuitBreakerActivated { .. } => "circuit_breaker_activated",
+            Alert::HealthIndexCrossed { .. } => "health_index_crossed",
+            Alert::LargeMint { .. } => "large_mint",
+            Alert::LargeRedemption { .. } => "large_redemption",
+            Alert::ProofOfReservesFailed { .. } => "proof_of_reserves_failed",
+            Alert::RuleFiring { .. } => "rule_firing",
+            Alert::RuleResolved { .. } => "rule_resolved",
+        }
+    }
+}
+
+/// Alerts raised by committing `block`, moving the chain from `prev` to `next`.
+pub fn alerts(
+    config: &WebhookConfig,
+    prev: &GlobalState,
+    next: &GlobalState,
+    block: &Block,
+    receipts: &BlockReceipts,
+) -> Vec<Alert> {
+    let height = block.header.height;
+    let mut found = Vec::new();
+    if next.circuit_breaker_active && !prev.circuit_breaker_active {
+        found.push(Alert::CircuitBreakerActivated {
+            height,
+            reserve_ratio: next.reserve_ratio,
+        });
+    }
+    for &threshold in &config.health_thresholds {
+        if (prev.health_index < threshold) != (next.health_index < threshold) {
+            found.push(Alert::HealthIndexCrossed {
+                height,
+                threshold,
+                from: prev.health_index,
+                to: next.health_index,
+            });
+        }
+    }
+    for (tx, receipt) in block.transactions.iter().zip(&receipts.receipts) {
+        let tx_id = hex::encode(receipt.tx_id);
+        let account = hex::encode(tx.sender);
+        match &tx.instruction {
+            StablecoinInstruction::SubmitZkPoR { total_reserves, .. } if !receipt.success => {
+                found.push(Alert::ProofOfReservesFailed {
+                    height,
+                    tx_id,
+                    submitter: account,
+                    total_reserves: *total_reserves,
+                });
+            }
+            StablecoinInstruction::MintSenior { amount, .. }
+            | StablecoinInstruction::MintSeniorWithCollateral { amount, .. }
+            | StablecoinInstruction::MintWithCreditScore { amount, .. }
+            | StablecoinInstruction::MintWithNativeCreditScore { amount, .. }
+            | StablecoinInstruction::DrawCreditLine { amount, .. }
+                if receipt.success && config.large_amount.is_some_and(|min| *amount >= min) =>
+            {
+                found.push(Alert::LargeMint {
+                    height,
+                    tx_id,
+                    account,
+                    amount: *amount,
+                });
+            }
+            StablecoinInstruction::RedeemSenior { amount }
+            | StablecoinInstruction::RedeemPosition { amount, .. }
+            | StablecoinInstruction::InstantRedeem { amount, .. }
+                if receipt.success && config.large_amount.is_some_and(|min| *amount >= min) =>
+            {
+                found.push(Alert::LargeRedemption {
+                    height,
+                    tx_id,
+                    account,
+                    amount: *amount,
+                });
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Value of the signature header for `body`.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Watch committed blocks and deliver alerts for each new canonical height
+/// past the tip at startup, along with alerts raised elsewhere on the node and
+/// sent through `forwarded`. Delivery is at least once: a receiver may see an
+/// alert again after a retry, and alerts from reorged-out blocks are not
+/// withdrawn.
+pub async fn run(
+    storage: Arc<Storage>,
+    mut snapshots: watch::Receiver<Arc<GlobalState>>,
+    config: WebhookConfig,
+    mut forwarded: mpsc::Receiver<Alert>,
+) {
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Webhooks disabled: {}", e);
+            return;
+        }
+    };
+    let config = Arc::new(config);
+    let mut seen = storage.load_tip().ok().flatten().map_or(0, |(h, _)| h);
+    info!(
+        "Delivering stability events to {} webhooks",
+        config.endpoints.len()
+    );
+    loop {
+        tokio::select! {
+            changed = snapshots.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+            }
+            Some(alert) = forwarded.recv() => {
+                dispatch(&client, &config, alert);
+                continue;
+            }
+        }
+        let tip = match storage.load_tip() {
+            Ok(tip) => tip.map_or(0, |(h, _)| h),
+            Err(e) => {
+                warn!("Webhooks could not read the tip: {}", e);
+                continue;
+            }
+        };
+        for height in seen + 1..=tip {
+            match block_alerts(&storage, &config, height) {
+                Ok(found) => {
+                    for alert in found {
+                        dispatch(&client, &config, alert);
+                    }
+                }
+                Err(e) => warn!("Webhooks skipped block {}: {}", height, e),
+            }
+        }
+        seen = tip;
+    }
+}
+
+fn block_alerts(storage: &Storage, config: &WebhookConfig, height: u64) -> Result<Vec<Alert>> {
+    let Some(block) = storage.load_block_by_height(height)? else {
+        bail!("Block not stored");
+    };
+    let Some(receipts) = storage.load_block_receipts(&block.hash())? else {
+        bail!("Receipts not stored");
+    };
+    let (Some(prev), Some(next)) = (
+        storage.load_state_by_height(height - 1)?,
+        storage.load_state_by_height(height)?,
+    ) else {
+        bail!("State snapshots not stored");
+    };
+    Ok(alerts(config, &prev, &next, &block, &receipts))
+}
+
+fn dispatch(client: &reqwest::Client, config: &WebhookConfig, alert: Alert) {
+    let body = match serde_json::to_vec(&alert) {
+        Ok(body) => Arc::new(body),
+        Err(e) => {
+            warn!("Failed to encode {} alert: {}", alert.kind(), e);
+            return;
+        }
+    };
+    for endpoint in config.endpoints.iter().filter(|e| e.wants(alert.kind())) {
+        tokio::spawn(deliver(
+            client.clone(),
+            endpoint.url.clone(),
+            signature(&endpoint.secret, &body),
+            alert.kind(),
+            body.clone(),
+        ));
+    }
+}
+
+async fn deliver(
+    client: reqwest::Client,
+    url: String,
+    signature: String,
+    kind: &'static str,
+    body: Arc<Vec<u8>>,
+) {
+    let mut wait = FIRST_RETRY;
+    for attempt in 1..=MAX_ATTEMPTS {
+        let sent = client
+            .post(&url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, kind)
+            .header(SIGNATURE_HEADER, &signature)
+            .body(body.as_ref().clone())
+            .send()
+            .await;
+        match sent.and_then(|r| r.error_for_status()) {
+            Ok(_) => return,
+            Err(e) if attempt == MAX_ATTEMPTS => {
+                warn!(
+                    "Dropping {} webhook to {} after {} attempts: {}",
+                    kind, url, attempt, e
+                );
+            }
+            Err(_) => {
+                tokio::time::sleep(wait).await;
+                wait *= 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::block::BlockHeader;
+    use lumina_types::receipt::Receipt;
+    use lumina_types::transaction::Transaction;
+
+    fn tx(instruction: StablecoinInstruction) -> Transaction {
+        Transaction {
+            sender: [1u8; 32],
+            nonce: 0,
+            instruction,
+            signature: Vec::new(),
+            gas_limit: 0,
+            gas_price: 0,
+        }
+    }
+
+    fn block(height: u64, transactions: Vec<Transaction>) -> Block {
+        Block {
+            header: BlockHeader {
+                height,
+                prev_hash: [0u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: [0u8; 32],
+                timestamp: 0,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions,
+            votes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn config_rejects_unknown_events_and_missing_secrets() {
+        let config = WebhookConfig::parse(
+            r#"
+            health_thresholds = [5000]
+            large_amount = 1000
+
+            [[endpoint]]
+            url = "https://ops.example/lumina"
+            secret = "s3cret"
+            events = ["circuit_breaker_activated"]
+            "#,
+        )
+        .unwrap();
+        assert!(config.endpoints[0].wants("circuit_breaker_activated"));
+        assert!(!config.endpoints[0].wants("large_mint"));
+
+        let err = WebhookConfig::parse(
+            "[[endpoint]]\nurl = \"https://a\"\nsecret = \"k\"\nevents = [\"depeg\"]",
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "Unknown webhook event: depeg");
+        assert!(WebhookConfig::parse("[[endpoint]]\nurl = \"https://a\"\nsecret = \"\"").is_err());
+        assert!(WebhookConfig::parse("large_amount = 5").is_err());
+    }
+
+    #[test]
+    fn raises_alerts_for_stability_events() {
+        let config = WebhookConfig {
+            health_thresholds: vec![5_000, 3_000],
+            large_amount: Some(1_000),
+            endpoints: Vec::new(),
+        };
+        let prev = GlobalState {
+            health_index: 6_000,
+            ..Default::default()
+        };
+        let next = GlobalState {
+            health_index: 4_000,
+            circuit_breaker_active: true,
+            reserve_ratio: 0.9,
+            ..Default::default()
+        };
+        let block = block(
+            9,
+            vec![
+                tx(StablecoinInstruction::MintSenior {
+                    amount: 5_000,
+                    collateral_amount: 6_000,
+                    proof: Vec::new(),
+                }),
+                tx(StablecoinInstruction::RedeemSenior { amount: 999 }),
+                tx(StablecoinInstruction::SubmitZkPoR {
+                    proof: Vec::new(),
+                    total_reserves: 77,
+                    timestamp: 0,
+                }),
+            ],
+        );
+        let receipts = BlockReceipts {
+            height: 9,
+            receipts: [true, true, false]
+                .into_iter()
+                .enumerate()
+                .map(|(i, success)| Receipt {
+                    tx_id: [i as u8; 32],
+                    sender: [1u8; 32],
+                    success,
+                    first_event: 0,
+                    event_count: 0,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let found = alerts(&config, &prev, &next, &block, &receipts);
+        let kinds: Vec<_> = found.iter().map(Alert::kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                "circuit_breaker_activated",
+                "health_index_crossed",
+                "large_mint",
+                "proof_of_reserves_failed"
+            ]
+        );
+        assert_eq!(
+            found[1],
+            Alert::HealthIndexCrossed {
+                height: 9,
+                threshold: 5_000,
+                from: 6_000,
+                to: 4_000
+            }
+        );
+        let body = serde_json::to_value(&found[2]).unwrap();
+        assert_eq!(body["event"], "large_mint");
+        assert_eq!(body["amount"], 5_000);
+
+        // Already tripped and steady health: only the transactions alert
+        assert_eq!(alerts(&config, &next, &next, &block, &receipts), found[2..]);
+    }
+
+    #[test]
+    fn signs_bodies_with_hmac_sha256() {
+        assert_eq!(
+            signature("key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
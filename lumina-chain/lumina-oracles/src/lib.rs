@@ -1,5 +1,6 @@
 pub mod price_feed;
 
+#[derive(Default)]
 pub struct OracleManager {
     pub reporters: Vec<[u8; 32]>, // Public keys of authorized reporters
 }
@@ -3,19 +3,16 @@
 
 use anyhow::{bail, Result};
 use lumina_crypto::signatures::verify_signature;
+use lumina_math::{fixed_from_f64, mul_div, Bps, Rounding};
 use lumina_types::state::GlobalState;
+pub use lumina_types::state::OracleRegime;
 use std::collections::{BTreeMap, VecDeque};
 
 const MIN_REPORTERS: usize = 7;
 const REPORT_STALENESS_SECONDS: u64 = 300;
-const SLASH_THRESHOLD_BPS: u64 = 1_000; // 10%
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum OracleRegime {
-    Stable,
-    Volatile,
-    Stress,
-}
+const SLASH_THRESHOLD: Bps = Bps::constant(1_000); // 10%
+const SLASH_SHARE: Bps = Bps::constant(200); // 2%
+const PRICE_SCALE: u64 = 1_000_000;
 
 #[derive(Debug, Clone)]
 pub struct PriceReport {
@@ -174,7 +171,12 @@ impl PriceFeed {
 
         let peg_dev = (final_price - 1.0).abs();
         let stability_impact = (1.0 - peg_dev * 10.0 - forecast_vol * 5.0).clamp(0.0, 1.0);
-        let confidence = ((filtered.len() as f64 / weighted_prices.len() as f64) * 100.0) as u8;
+        let confidence = mul_div(
+            filtered.len() as u64,
+            100,
+            weighted_prices.len() as u64,
+            Rounding::Down,
+        )? as u8;
         let data_hash = blake3::hash(&final_price.to_le_bytes()).into();
 
         Ok(PriceReport {
@@ -208,9 +210,8 @@ impl PriceFeed {
             reporter.reputation = (reporter.reputation * 0.9) + (0.1 * (1.0 - err_ratio.min(1.0)));
             reporter.reputation = reporter.reputation.clamp(0.0, 1.0);
 
-            let err_bps = (err_ratio * 10_000.0).round() as u64;
-            if err_bps > SLASH_THRESHOLD_BPS {
-                let slash_amount = reporter.stake / 50; // 2%
+            if Bps::from_f64(err_ratio) > SLASH_THRESHOLD {
+                let slash_amount = SLASH_SHARE.of(reporter.stake, Rounding::Down);
                 reporter.stake = reporter.stake.saturating_sub(slash_amount);
                 reporter.total_slashed = reporter.total_slashed.saturating_add(slash_amount);
                 self.slash_treasury = self.slash_treasury.saturating_add(slash_amount);
@@ -264,8 +265,9 @@ impl PriceFeed {
     }
 
     fn apply_to_state(&self, report: &PriceReport, state: &mut GlobalState) {
-        let fixed = (report.price * 1_000_000.0).round().max(0.0) as u64;
+        let fixed = fixed_from_f64(report.price, PRICE_SCALE);
         state.oracle_prices.insert(self.symbol.clone(), fixed);
+        state.reported_oracle_regime = report.regime;
 
         if report.stability_impact < 0.75 {
             state.circuit_breaker_active = true;
@@ -0,0 +1,397 @@
+use crate::profile::Op;
+use crate::MINT_AMOUNT;
+use anyhow::{bail, Context, Result};
+use lumina_types::state::{GlobalState, OracleRegime};
+use rand::Rng;
+use serde::Deserialize;
+use std::path::Path;
+
+/// One dollar at oracle precision.
+pub const PEG: u64 = 1_000_000;
+/// Peg deviation at which arbitrageurs trade.
+const ARBITRAGE_THRESHOLD_BPS: u64 = 25;
+/// Discount at which holders panic; they calm down within half of it.
+const PANIC_THRESHOLD_BPS: u64 = 200;
+/// Share of its LUSD a holder dumps when it panics.
+const PANIC_FRACTION_BPS: u64 = 2_500;
+/// Per-block chance that a redeemer redeems, and how much.
+const REDEEM_PROBABILITY_BPS: u32 = 500;
+const REDEEM_AMOUNT: u64 = 100;
+/// Spread of reported prices around the market price.
+const REPORT_NOISE_BPS: u64 = 5;
+
+fn bps(value: u64, bps: u64) -> u64 {
+    ((value as u128) * (bps as u128) / 10_000) as u64
+}
+
+/// Agent counts; wallets are assigned in this order after the exchange.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Population {
+    pub arbitrageurs: usize,
+    pub redeemers: usize,
+    pub panicked_holders: usize,
+    pub oracle_reporters: usize,
+}
+
+impl Population {
+    pub fn total(&self) -> usize {
+        self.arbitrageurs + self.redeemers + self.panicked_holders + self.oracle_reporters
+    }
+
+    /// Agents holding consecutive wallets from `first_wallet`.
+    pub fn spawn(&self, first_wallet: usize) -> Vec<Agent> {
+        [
+            (Role::Arbitrageur, self.arbitrageurs),
+            (Role::Redeemer, self.redeemers),
+            (Role::PanickedHolder, self.panicked_holders),
+            (Role::OracleReporter, self.oracle_reporters),
+        ]
+        .into_iter()
+        .flat_map(|(role, count)| std::iter::repeat_n(role, count))
+        .zip(first_wallet..)
+        .map(|(role, wallet)| Agent {
+            role,
+            wallet,
+            panicked: false,
+        })
+        .collect()
+    }
+}
+
+/// How the external LUSD price moves between blocks.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketParams {
+    /// Share of the gap to the fundamental price closed each block
+    pub reversion_bps: u64,
+    /// Largest random move per block
+    pub noise_bps: u64,
+    /// LUSD of net buying or selling that moves the price by 1%
+    pub liquidity: u64,
+    /// LUSD the exchange holds for agents to buy, besides its airdrop
+    pub exchange_lusd: u64,
+}
+
+impl Default for MarketParams {
+    fn default() -> Self {
+        Self {
+            reversion_bps: 200,
+            noise_bps: 5,
+            liquidity: 200_000,
+            exchange_lusd: 1_000_000,
+        }
+    }
+}
+
+/// An external sell-off: the fundamental price drops by `depth_bps` for
+/// `blocks` blocks from `at_block`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Shock {
+    pub at_block: u64,
+    pub depth_bps: u64,
+    pub blocks: u64,
+}
+
+/// An agent-based run, loaded from TOML:
+///
+/// ```toml
+/// name = "depeg"
+/// blocks = 400
+///
+/// [agents]
+/// arbitrageurs = 5
+/// panicked_holders = 60
+/// oracle_reporters = 3
+///
+/// [shock]
+/// at_block = 50
+/// depth_bps = 800
+/// blocks = 80
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    pub blocks: u64,
+    pub agents: Population,
+    #[serde(default)]
+    pub market: MarketParams,
+    pub shock: Option<Shock>,
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading scenario {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parsing scenario {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let scenario: Scenario = toml::from_str(text)?;
+        if scenario.blocks == 0 {
+            bail!("blocks must be at least 1");
+        }
+        if scenario.agents.total() == 0 {
+            bail!("scenario has no agents");
+        }
+        if scenario.market.liquidity == 0 {
+            bail!("market liquidity must be non-zero");
+        }
+        if scenario.market.reversion_bps > 10_000 {
+            bail!("reversion_bps must be at most 10000");
+        }
+        Ok(scenario)
+    }
+}
+
+/// The external LUSD market the agents trade against.
+pub struct Market {
+    /// Current LUSD price at oracle precision
+    pub price: u64,
+    params: MarketParams,
+    shock: Option<Shock>,
+    /// Net LUSD bought (positive) or sold by agents this block
+    flow: i64,
+}
+
+impl Market {
+    pub fn new(params: MarketParams, shock: Option<Shock>) -> Self {
+        Self {
+            price: PEG,
+            params,
+            shock,
+            flow: 0,
+        }
+    }
+
+    /// The price the market reverts to, absent agent trading.
+    pub fn fundamental(&self, block: u64) -> u64 {
+        match &self.shock {
+            Some(s) if (s.at_block..s.at_block.saturating_add(s.blocks)).contains(&block) => {
+                PEG - bps(PEG, s.depth_bps.min(10_000))
+            }
+            _ => PEG,
+        }
+    }
+
+    pub fn trade(&mut self, flow: i64) {
+        self.flow = self.flow.saturating_add(flow);
+    }
+
+    /// Move the price after `block`: part of the way to the fundamental
+    /// price, plus noise, plus the impact of the block's net trading.
+    pub fn step(&mut self, block: u64, rng: &mut impl Rng) {
+        let price = self.price as i128;
+        let gap = self.fundamental(block + 1) as i128 - price;
+        let reversion = gap * self.params.reversion_bps as i128 / 10_000;
+        let noise_range = (bps(PEG, self.params.noise_bps)) as i128;
+        let noise = rng.gen_range(-noise_range..=noise_range);
+        // 1% of the peg per `liquidity` LUSD of net flow
+        let impact = self.flow as i128 * (PEG as i128 / 100) / self.params.liquidity as i128;
+        self.price = (price + reversion + noise + impact).clamp(1, 2 * PEG as i128) as u64;
+        self.flow = 0;
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Buys below the peg to redeem at par while redemptions clear, and
+    /// mints above it to sell
+    Arbitrageur,
+    /// Redeems small amounts at random, whatever the price
+    Redeemer,
+    /// Dumps part of its LUSD once the discount passes a threshold:
+    /// redeeming while redemptions clear, selling once they queue
+    PanickedHolder,
+    /// Posts the market price to the LUSD-USD feed each block
+    OracleReporter,
+}
+
+/// What an agent does in one block. Trades settle on chain against the
+/// exchange wallet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Intent {
+    /// Buy LUSD from the exchange
+    Buy(u64),
+    /// Sell LUSD to the exchange
+    Sell(u64),
+    /// Mint `MINT_AMOUNT` LUSD against LUMINA
+    Mint,
+    Redeem(u64),
+    /// Post a LUSD-USD price
+    Report(u64),
+}
+
+impl Intent {
+    pub fn op(self) -> Op {
+        match self {
+            Intent::Buy(_) | Intent::Sell(_) => Op::Transfer,
+            Intent::Mint => Op::Mint,
+            Intent::Redeem(_) => Op::Redeem,
+            Intent::Report(_) => Op::OracleUpdate,
+        }
+    }
+
+    /// LUSD this intent buys from (positive) or sells to the market.
+    pub fn market_flow(self) -> i64 {
+        match self {
+            Intent::Buy(amount) => amount as i64,
+            Intent::Sell(amount) => -(amount as i64),
+            _ => 0,
+        }
+    }
+}
+
+pub struct Agent {
+    pub role: Role,
+    pub wallet: usize,
+    panicked: bool,
+}
+
+impl Agent {
+    /// React to the market `price` and the chain state as seen by the
+    /// agent's wallet at `address`.
+    pub fn act(
+        &mut self,
+        price: u64,
+        state: &GlobalState,
+        address: &[u8; 32],
+        rng: &mut impl Rng,
+    ) -> Vec<Intent> {
+        let discount_bps = PEG.saturating_sub(price) * 10_000 / PEG;
+        let premium_bps = price.saturating_sub(PEG) * 10_000 / PEG;
+        match self.role {
+            Role::Arbitrageur
+                if discount_bps > ARBITRAGE_THRESHOLD_BPS && !redemptions_queued(state) =>
+            {
+                vec![Intent::Buy(MINT_AMOUNT), Intent::Redeem(MINT_AMOUNT)]
+            }
+            Role::Arbitrageur if premium_bps > ARBITRAGE_THRESHOLD_BPS => {
+                vec![Intent::Mint, Intent::Sell(MINT_AMOUNT)]
+            }
+            Role::Arbitrageur => vec![],
+            Role::Redeemer if rng.gen_range(0..10_000) < REDEEM_PROBABILITY_BPS => {
+                vec![Intent::Redeem(REDEEM_AMOUNT)]
+            }
+            Role::Redeemer => vec![],
+            Role::PanickedHolder => {
+                if self.panicked {
+                    self.panicked = discount_bps > PANIC_THRESHOLD_BPS / 2;
+                    return vec![];
+                }
+                if discount_bps <= PANIC_THRESHOLD_BPS {
+                    return vec![];
+                }
+                self.panicked = true;
+                let balance = state.accounts.get(address).map_or(0, |a| a.lusd_balance);
+                let amount = bps(balance, PANIC_FRACTION_BPS);
+                if amount == 0 {
+                    vec![]
+                } else if redemptions_queued(state) {
+                    vec![Intent::Sell(amount)]
+                } else {
+                    vec![Intent::Redeem(amount)]
+                }
+            }
+            Role::OracleReporter => {
+                let spread = bps(price, REPORT_NOISE_BPS);
+                vec![Intent::Report(
+                    rng.gen_range(price.saturating_sub(spread)..=price + spread)
+                        .max(1),
+                )]
+            }
+        }
+    }
+}
+
+/// Whether senior redemptions are currently queued rather than paid out,
+/// by the same rule `RedeemSenior` applies.
+fn redemptions_queued(state: &GlobalState) -> bool {
+    state.circuit_breaker_active
+        || state.reserve_ratio < 0.95
+        || state.oracle_regime == OracleRegime::Stress
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::state::AccountState;
+
+    #[test]
+    fn market_and_agents_react_to_a_depeg() {
+        let scenario = Scenario::parse(
+            r#"
+            blocks = 100
+            [agents]
+            arbitrageurs = 1
+            panicked_holders = 1
+            [market]
+            reversion_bps = 10000
+            noise_bps = 0
+            [shock]
+            at_block = 10
+            depth_bps = 500
+            blocks = 5
+            "#,
+        )
+        .unwrap();
+        let mut market = Market::new(scenario.market.clone(), scenario.shock.clone());
+        let mut rng = rand::thread_rng();
+        market.step(8, &mut rng);
+        assert_eq!(market.price, PEG);
+        market.step(9, &mut rng);
+        assert_eq!(market.price, 950_000);
+        // Net buying lifts the price by 1% per `liquidity` LUSD
+        market.trade(200_000);
+        market.step(9, &mut rng);
+        assert_eq!(market.price, 960_000);
+        market.step(14, &mut rng);
+        assert_eq!(market.price, PEG);
+
+        let mut agents = scenario.agents.spawn(1);
+        assert_eq!(agents.iter().map(|a| a.wallet).collect::<Vec<_>>(), [1, 2]);
+        let holder = [2u8; 32];
+        let mut state = GlobalState {
+            reserve_ratio: 1.25,
+            ..Default::default()
+        };
+        state.accounts.insert(
+            holder,
+            AccountState {
+                lusd_balance: 1_000,
+                ..Default::default()
+            },
+        );
+
+        let arb = agents[0].act(950_000, &state, &[1u8; 32], &mut rng);
+        assert_eq!(arb, [Intent::Buy(MINT_AMOUNT), Intent::Redeem(MINT_AMOUNT)]);
+        assert_eq!(
+            agents[0].act(1_010_000, &state, &[1u8; 32], &mut rng),
+            [Intent::Mint, Intent::Sell(MINT_AMOUNT)]
+        );
+        assert!(agents[0].act(PEG, &state, &[1u8; 32], &mut rng).is_empty());
+        state.circuit_breaker_active = true;
+        assert!(agents[0]
+            .act(950_000, &state, &[1u8; 32], &mut rng)
+            .is_empty());
+
+        // Panics once per episode, selling when redemptions are queued
+        assert_eq!(
+            agents[1].act(950_000, &state, &holder, &mut rng),
+            [Intent::Sell(250)]
+        );
+        assert!(agents[1].act(950_000, &state, &holder, &mut rng).is_empty());
+        assert!(agents[1].act(PEG, &state, &holder, &mut rng).is_empty());
+        state.circuit_breaker_active = false;
+        assert_eq!(
+            agents[1].act(950_000, &state, &holder, &mut rng),
+            [Intent::Redeem(250)]
+        );
+
+        assert!(Scenario::parse("blocks = 10\n[agents]").is_err());
+        assert!(Scenario::parse("blocks = 0\n[agents]\nredeemers = 1").is_err());
+    }
+}
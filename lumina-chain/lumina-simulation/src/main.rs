@@ -1,24 +1,55 @@
+use agents::{Intent, Market, Scenario};
 use anyhow::{bail, Result};
 use clap::Parser;
 use lumina_crypto::signatures::{generate_keypair, sign, SigningKey};
 use lumina_crypto::zk::ZkManager;
-use lumina_execution::{execute_transaction, ExecutionContext};
+use lumina_execution::{begin_block, end_block, execute_transaction, ExecutionContext};
 use lumina_types::instruction::{AssetType, StablecoinInstruction};
 use lumina_types::state::{AccountState, GlobalState};
 use lumina_types::transaction::Transaction;
+use profile::{Op, Profile};
+use rand::seq::SliceRandom;
 use rand::Rng;
-use std::collections::HashMap;
+use series::OutputFormat;
+use stats::WorkloadStats;
+use std::io::Write;
+use std::path::PathBuf;
 use std::time::Instant;
 
+mod agents;
+mod profile;
+mod series;
+mod stats;
+
+const GENESIS_TIMESTAMP: u64 = 1_700_000_000;
+/// LUSD minted per `mint` operation, against 120% in LUMINA
+const MINT_AMOUNT: u64 = 1_000;
+/// Attested value of each listed RWA, pledged `RWA_PLEDGE` at a time
+const RWA_VALUE: u64 = 10_000;
+const RWA_PLEDGE: u64 = 1_000;
+/// LUSD flash minted (against 110% in LUMINA) and burned in the same block
+const FLASH_AMOUNT: u64 = 1_000;
+/// Largest oracle move per update, in bps of the current price
+const ORACLE_STEP_BPS: i64 = 200;
+/// Counterparty of every agent trade in scenario runs
+const EXCHANGE_WALLET: usize = 0;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about = "LuminaChain realistic simulation runner")]
 struct Args {
     /// Number of wallets created for the simulation.
     #[arg(long, default_value_t = 200)]
     wallets: usize,
-    /// Number of transfer transactions to execute.
-    #[arg(long, default_value_t = 20_000)]
-    transfers: usize,
+    /// Number of workload operations to execute.
+    #[arg(long, alias = "transfers", default_value_t = 20_000)]
+    operations: usize,
+    /// TOML workload profile; defaults to transfers only.
+    #[arg(long)]
+    profile: Option<PathBuf>,
+    /// TOML agent scenario to run instead of a random workload; it sets
+    /// its own number of blocks.
+    #[arg(long, conflicts_with_all = ["profile", "operations"])]
+    scenario: Option<PathBuf>,
     /// Starting simulated money airdropped to each wallet.
     #[arg(long, default_value_t = 50_000)]
     simulation_money: u64,
@@ -28,6 +59,13 @@ struct Args {
     /// Starting balance per custom asset per wallet.
     #[arg(long, default_value_t = 100)]
     custom_asset_amount: u64,
+    /// Also emit per-block metrics as a time series in this format.
+    #[arg(long, value_enum)]
+    output: Option<OutputFormat>,
+    /// File for the time series; defaults to stdout, which moves the
+    /// summary to stderr.
+    #[arg(long, requires = "output")]
+    output_file: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -62,6 +100,7 @@ fn seed_simulation_money(state: &mut GlobalState, wallets: &[SimWallet], amount:
             wallet.address,
             AccountState {
                 lusd_balance: amount,
+                lumina_balance: amount,
                 ..Default::default()
             },
         );
@@ -72,6 +111,17 @@ fn seed_simulation_money(state: &mut GlobalState, wallets: &[SimWallet], amount:
     state.reserve_ratio = 1.25;
 }
 
+/// Give the exchange wallet `amount` more LUSD to sell to agents, backed
+/// like the airdrop.
+fn seed_exchange(state: &mut GlobalState, exchange: &SimWallet, amount: u64) {
+    let account = state.accounts.entry(exchange.address).or_default();
+    account.lusd_balance = account.lusd_balance.saturating_add(amount);
+    state.total_lusd_supply = state.total_lusd_supply.saturating_add(amount);
+    state.stabilization_pool_balance = state
+        .stabilization_pool_balance
+        .saturating_add(amount.saturating_mul(125) / 100);
+}
+
 fn seed_custom_assets(
     state: &mut GlobalState,
     wallets: &[SimWallet],
@@ -81,48 +131,23 @@ fn seed_custom_assets(
     for wallet in wallets {
         let acct = state.accounts.entry(wallet.address).or_default();
         for ticker in custom_assets {
-            acct.custom_balances.insert(ticker.clone(), amount);
+            acct.custom_balances.insert(ticker.clone(), amount.into());
         }
     }
 }
 
-fn build_transfer_tx(
-    sender: &SimWallet,
-    receiver: [u8; 32],
-    amount: u64,
-    nonce: u64,
-    asset: AssetType,
-) -> Transaction {
-    let instruction = StablecoinInstruction::Transfer {
-        to: receiver,
-        amount,
-        asset,
-    };
-
-    let mut tx = Transaction {
-        sender: sender.address,
-        nonce,
-        instruction,
-        signature: vec![],
-        gas_limit: 1000,
-        gas_price: 1,
-    };
-
-    tx.signature = sign(&sender.keypair, &tx.signing_bytes());
-    tx
+fn seed_oracle_prices(state: &mut GlobalState, custom_assets: &[String]) {
+    state
+        .oracle_prices
+        .insert("LUMINA-USD".to_string(), 1_000_000);
+    for ticker in custom_assets {
+        state
+            .oracle_prices
+            .insert(format!("{}-USD", ticker), 1_000_000);
+    }
 }
 
-fn build_mint_tx(sender: &SimWallet, nonce: u64, amount: u64) -> Transaction {
-    let zk = ZkManager::setup();
-    let collateral = amount.saturating_mul(120) / 100;
-    let proof = zk.prove_reserves(vec![collateral], collateral);
-
-    let instruction = StablecoinInstruction::MintSenior {
-        amount,
-        collateral_amount: collateral,
-        proof,
-    };
-
+fn build_tx(sender: &SimWallet, nonce: u64, instruction: StablecoinInstruction) -> Transaction {
     let mut tx = Transaction {
         sender: sender.address,
         nonce,
@@ -140,23 +165,311 @@ fn build_register_asset_tx(sender: &SimWallet, nonce: u64, ticker: &str) -> Tran
         ticker: ticker.to_string(),
         decimals: 8,
     };
+    build_tx(sender, nonce, instruction)
+}
 
-    let mut tx = Transaction {
-        sender: sender.address,
-        nonce,
-        instruction,
-        signature: vec![],
-        gas_limit: 20_000,
-        gas_price: 1,
-    };
-    tx.signature = sign(&sender.keypair, &tx.signing_bytes());
-    tx
+/// Proofs shared by every operation that needs one; proving per
+/// transaction would dominate the measured latencies.
+struct Proofs {
+    mint: Vec<u8>,
+    rwa: Vec<u8>,
+}
+
+impl Proofs {
+    fn generate() -> Self {
+        let zk = ZkManager::setup();
+        let collateral = MINT_AMOUNT.saturating_mul(120) / 100;
+        // RWA attestations are Groth16 proofs bound to the attested value
+        let raw = zk.prove_range(1, 10);
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(blake3::hash(&RWA_VALUE.to_le_bytes()).as_bytes());
+        hasher.update(&raw);
+        let mut rwa = hasher.finalize().as_bytes().to_vec();
+        rwa.extend(raw);
+        Self {
+            mint: zk.prove_reserves(vec![collateral], collateral),
+            rwa,
+        }
+    }
+}
+
+/// Everything a workload needs besides the state it runs against.
+struct Workload<'a> {
+    wallets: &'a [SimWallet],
+    custom_assets: &'a [String],
+    proofs: Proofs,
+}
+
+impl Workload<'_> {
+    fn other_wallet(&self, sender: usize, rng: &mut impl Rng) -> [u8; 32] {
+        let mut idx = rng.gen_range(0..self.wallets.len());
+        if idx == sender {
+            idx = (idx + 1) % self.wallets.len();
+        }
+        self.wallets[idx].address
+    }
+
+    /// Instructions performing `op` for wallet `sender`, drawing on its
+    /// current positions where the operation needs one.
+    fn instructions(
+        &self,
+        op: Op,
+        sender: usize,
+        state: &GlobalState,
+        timestamp: u64,
+        rng: &mut impl Rng,
+    ) -> Vec<StablecoinInstruction> {
+        let address = self.wallets[sender].address;
+        let account = state.accounts.get(&address);
+        let instruction = match op {
+            Op::Transfer => {
+                let asset = match rng.gen_range(0..(3 + self.custom_assets.len())) {
+                    0 => AssetType::LUSD,
+                    1 => AssetType::LJUN,
+                    2 => AssetType::Lumina,
+                    idx => AssetType::Custom(self.custom_assets[idx - 3].clone()),
+                };
+                StablecoinInstruction::Transfer {
+                    to: self.other_wallet(sender, rng),
+                    amount: 1,
+                    asset,
+                }
+            }
+            Op::Mint => StablecoinInstruction::MintSenior {
+                amount: MINT_AMOUNT,
+                collateral_amount: MINT_AMOUNT.saturating_mul(120) / 100,
+                proof: self.proofs.mint.clone(),
+            },
+            Op::Redeem => StablecoinInstruction::RedeemSenior { amount: 100 },
+            Op::Stream => StablecoinInstruction::StreamPayment {
+                to: self.other_wallet(sender, rng),
+                amount_per_sec: 1,
+                duration: 60,
+            },
+            Op::WrapYield => StablecoinInstruction::WrapToYieldToken {
+                amount: 100,
+                maturity_blocks: 10,
+            },
+            Op::UnwrapYield => StablecoinInstruction::UnwrapYieldToken {
+                token_id: account
+                    .and_then(|a| a.yield_positions.first())
+                    .map_or(u64::MAX, |p| p.token_id),
+            },
+            Op::RwaList => StablecoinInstruction::ListRWA {
+                asset_description: format!("simulated invoice #{}", state.next_rwa_id),
+                attested_value: RWA_VALUE,
+                attestation_proof: self.proofs.rwa.clone(),
+                maturity_date: None,
+                collateral_eligibility: true,
+            },
+            Op::RwaPledge => StablecoinInstruction::UseRWAAsCollateral {
+                rwa_id: state
+                    .rwa_listings
+                    .iter()
+                    .find(|(_, l)| {
+                        l.owner == address && l.attested_value - l.pledged_amount >= RWA_PLEDGE
+                    })
+                    .map_or(u64::MAX, |(id, _)| *id),
+                amount_to_pledge: RWA_PLEDGE,
+            },
+            Op::FlashMint => {
+                return vec![
+                    StablecoinInstruction::FlashMint {
+                        amount: FLASH_AMOUNT,
+                        collateral_asset: AssetType::Lumina,
+                        collateral_amount: FLASH_AMOUNT.saturating_mul(110) / 100,
+                        commitment: [0u8; 32],
+                    },
+                    StablecoinInstruction::FlashBurn {
+                        amount: FLASH_AMOUNT,
+                    },
+                ]
+            }
+            Op::OracleUpdate => {
+                let idx = rng.gen_range(0..=self.custom_assets.len());
+                let asset = match idx {
+                    0 => "LUMINA-USD".to_string(),
+                    idx => format!("{}-USD", self.custom_assets[idx - 1]),
+                };
+                let current = state
+                    .oracle_prices
+                    .get(&asset)
+                    .copied()
+                    .unwrap_or(1_000_000);
+                let step = rng.gen_range(-ORACLE_STEP_BPS..=ORACLE_STEP_BPS);
+                let price = (current as i128 * (10_000 + step) as i128 / 10_000).max(1) as u64;
+                StablecoinInstruction::UpdateOracle {
+                    asset,
+                    price,
+                    timestamp,
+                    signature: vec![],
+                }
+            }
+        };
+        vec![instruction]
+    }
+
+    /// The wallet that signs `intent` for the agent at wallet `agent`, and
+    /// its instructions. Trades are settled against the exchange wallet.
+    fn intent_instructions(
+        &self,
+        intent: Intent,
+        agent: usize,
+        timestamp: u64,
+    ) -> (&SimWallet, Vec<StablecoinInstruction>) {
+        let exchange = &self.wallets[EXCHANGE_WALLET];
+        let wallet = &self.wallets[agent];
+        let lusd_transfer = |to: &SimWallet, amount| StablecoinInstruction::Transfer {
+            to: to.address,
+            amount,
+            asset: AssetType::LUSD,
+        };
+        match intent {
+            Intent::Buy(amount) => (exchange, vec![lusd_transfer(wallet, amount)]),
+            Intent::Sell(amount) => (wallet, vec![lusd_transfer(exchange, amount)]),
+            Intent::Mint => (
+                wallet,
+                vec![StablecoinInstruction::MintSenior {
+                    amount: MINT_AMOUNT,
+                    collateral_amount: MINT_AMOUNT.saturating_mul(120) / 100,
+                    proof: self.proofs.mint.clone(),
+                }],
+            ),
+            Intent::Redeem(amount) => {
+                (wallet, vec![StablecoinInstruction::RedeemSenior { amount }])
+            }
+            Intent::Report(price) => (
+                wallet,
+                vec![StablecoinInstruction::UpdateOracle {
+                    asset: "LUSD-USD".to_string(),
+                    price,
+                    timestamp,
+                    signature: vec![],
+                }],
+            ),
+        }
+    }
+}
+
+fn timestamp_at(height: u64) -> u64 {
+    GENESIS_TIMESTAMP + height * 10
+}
+
+fn block_ctx(state: &mut GlobalState, height: u64) -> ExecutionContext<'_> {
+    ExecutionContext {
+        state,
+        height,
+        timestamp: timestamp_at(height),
+    }
+}
+
+/// Run `operations` operations drawn from `profile`, `txs_per_block` to a
+/// block, starting at `first_height`. An operation succeeds only if all of
+/// its transactions do.
+fn run_workload(
+    state: &mut GlobalState,
+    workload: &Workload,
+    profile: &Profile,
+    operations: usize,
+    first_height: u64,
+) -> WorkloadStats {
+    let mut stats = WorkloadStats::default();
+    let mut rng = rand::thread_rng();
+    let mut height = first_height;
+    begin_block(&mut block_ctx(state, height));
+
+    for i in 0..operations {
+        if i > 0 && i % profile.txs_per_block == 0 {
+            end_block(&mut block_ctx(state, height));
+            stats.close_block(state, height, timestamp_at(height));
+            height += 1;
+            begin_block(&mut block_ctx(state, height));
+        }
+        let op = profile.pick(&mut rng);
+        let sender = rng.gen_range(0..workload.wallets.len());
+        let instructions = workload.instructions(op, sender, state, timestamp_at(height), &mut rng);
+        let started = Instant::now();
+        let ok = submit(state, &workload.wallets[sender], instructions, height);
+        stats.record(op, ok, started.elapsed());
+    }
+    end_block(&mut block_ctx(state, height));
+    stats.close_block(state, height, timestamp_at(height));
+    stats
+}
+
+/// Run `scenario` for its blocks from `first_height`. Wallet 0 is the
+/// exchange; agents take the wallets after it and act in random order each
+/// block, an agent's intents stopping at its first failure. Settled trades
+/// move the market price for the next block.
+fn run_agents(
+    state: &mut GlobalState,
+    workload: &Workload,
+    scenario: &Scenario,
+    first_height: u64,
+) -> WorkloadStats {
+    let mut stats = WorkloadStats::default();
+    let mut rng = rand::thread_rng();
+    let mut market = Market::new(scenario.market.clone(), scenario.shock.clone());
+    let mut agents = scenario.agents.spawn(EXCHANGE_WALLET + 1);
+
+    for block in 0..scenario.blocks {
+        let height = first_height + block;
+        let timestamp = timestamp_at(height);
+        begin_block(&mut block_ctx(state, height));
+        agents.shuffle(&mut rng);
+        for agent in &mut agents {
+            let address = workload.wallets[agent.wallet].address;
+            for intent in agent.act(market.price, state, &address, &mut rng) {
+                let (sender, instructions) =
+                    workload.intent_instructions(intent, agent.wallet, timestamp);
+                let started = Instant::now();
+                let ok = submit(state, sender, instructions, height);
+                stats.record(intent.op(), ok, started.elapsed());
+                if !ok {
+                    break;
+                }
+                market.trade(intent.market_flow());
+            }
+        }
+        end_block(&mut block_ctx(state, height));
+        stats.close_block(state, height, timestamp);
+        market.step(block, &mut rng);
+    }
+    stats
+}
+
+/// Execute `instructions` as consecutive transactions from `wallet`,
+/// stopping at the first failure.
+fn submit(
+    state: &mut GlobalState,
+    wallet: &SimWallet,
+    instructions: Vec<StablecoinInstruction>,
+    height: u64,
+) -> bool {
+    let nonce = state.accounts.get(&wallet.address).map_or(0, |a| a.nonce);
+    (0u64..).zip(instructions).all(|(i, instruction)| {
+        let tx = build_tx(wallet, nonce + i, instruction);
+        execute_transaction(&tx, &mut block_ctx(state, height)).is_ok()
+    })
 }
 
 fn run_simulation(args: &Args) -> Result<()> {
     if args.wallets < 2 {
         bail!("wallets must be at least 2");
     }
+    let scenario = args.scenario.as_deref().map(Scenario::load).transpose()?;
+    let profile = match &args.profile {
+        Some(path) => Profile::load(path)?,
+        None => Profile::default(),
+    };
+    if let Some(scenario) = &scenario {
+        if scenario.agents.total() >= args.wallets {
+            bail!(
+                "scenario needs {} wallets besides the exchange",
+                scenario.agents.total()
+            );
+        }
+    }
 
     let custom_assets = parse_custom_assets(&args.custom_assets);
 
@@ -169,113 +482,92 @@ fn run_simulation(args: &Args) -> Result<()> {
         &custom_assets,
         args.custom_asset_amount,
     );
-
-    let mut nonce_book = HashMap::<[u8; 32], u64>::new();
-    for wallet in &wallets {
-        nonce_book.insert(wallet.address, 0);
+    seed_oracle_prices(&mut state, &custom_assets);
+    if let Some(scenario) = &scenario {
+        seed_exchange(
+            &mut state,
+            &wallets[EXCHANGE_WALLET],
+            scenario.market.exchange_lusd,
+        );
     }
 
-    let start = Instant::now();
-    let mut rng = rand::thread_rng();
-
-    let minter = &wallets[0];
-
     // Register custom assets in oracle registry path.
-    for ticker in &custom_assets {
-        let nonce = *nonce_book.get(&minter.address).unwrap_or(&0);
-        let register_tx = build_register_asset_tx(minter, nonce, ticker);
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 1,
-            timestamp: 1_700_000_000,
-        };
-        execute_transaction(&register_tx, &mut ctx)?;
-        nonce_book.insert(minter.address, nonce.saturating_add(1));
-    }
-
-    // Mint once to validate PoR path.
-    let nonce = *nonce_book.get(&minter.address).unwrap_or(&0);
-    let mint_tx = build_mint_tx(minter, nonce, 100_000);
-
-    {
-        let mut ctx = ExecutionContext {
-            state: &mut state,
-            height: 2,
-            timestamp: 1_700_000_001,
-        };
-        execute_transaction(&mint_tx, &mut ctx)?;
-    }
-    nonce_book.insert(minter.address, nonce.saturating_add(1));
-
-    let mut success = 0u64;
-    let mut failed = 0u64;
-
-    for i in 0..args.transfers {
-        let sender_idx = rng.gen_range(0..wallets.len());
-        let mut receiver_idx = rng.gen_range(0..wallets.len());
-        if receiver_idx == sender_idx {
-            receiver_idx = (receiver_idx + 1) % wallets.len();
-        }
-
-        let sender = &wallets[sender_idx];
-        let receiver = wallets[receiver_idx].address;
-        let nonce = *nonce_book.get(&sender.address).unwrap_or(&0);
-
-        let asset = match rng.gen_range(0..(3 + custom_assets.len())) {
-            0 => AssetType::LUSD,
-            1 => AssetType::LJUN,
-            2 => AssetType::Lumina,
-            idx => AssetType::Custom(custom_assets[idx - 3].clone()),
-        };
-
-        let tx = build_transfer_tx(sender, receiver, 1, nonce, asset);
-        let result = {
-            let mut ctx = ExecutionContext {
-                state: &mut state,
-                height: 3 + i as u64,
-                timestamp: 1_700_000_100 + i as u64,
-            };
-            execute_transaction(&tx, &mut ctx)
-        };
-
-        match result {
-            Ok(_) => {
-                success = success.saturating_add(1);
-                nonce_book.insert(sender.address, nonce.saturating_add(1));
-            }
-            Err(_) => {
-                failed = failed.saturating_add(1);
-            }
-        }
+    let registrar = &wallets[0];
+    for (nonce, ticker) in (0u64..).zip(&custom_assets) {
+        let register_tx = build_register_asset_tx(registrar, nonce, ticker);
+        execute_transaction(&register_tx, &mut block_ctx(&mut state, 1))?;
     }
 
+    let workload = Workload {
+        wallets: &wallets,
+        custom_assets: &custom_assets,
+        proofs: Proofs::generate(),
+    };
+    let start = Instant::now();
+    let stats = match &scenario {
+        Some(scenario) => run_agents(&mut state, &workload, scenario, 2),
+        None => run_workload(&mut state, &workload, &profile, args.operations, 2),
+    };
     let elapsed = start.elapsed();
+    let attempted: u64 = stats.ops.values().map(|s| s.attempted()).sum();
+    let succeeded: u64 = stats.ops.values().map(|s| s.succeeded).sum();
     let tps = if elapsed.as_secs_f64() > 0.0 {
-        args.transfers as f64 / elapsed.as_secs_f64()
+        attempted as f64 / elapsed.as_secs_f64()
     } else {
         0.0
     };
 
-    println!("=== Lumina Simulation (separate module, real execution algo) ===");
-    println!("Wallets created: {}", wallets.len());
-    println!(
+    let series_to_stdout = args.output.is_some() && args.output_file.is_none();
+    let mut summary: Box<dyn Write> = if series_to_stdout {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    };
+    writeln!(
+        summary,
+        "=== Lumina Simulation (separate module, real execution algo) ==="
+    )?;
+    match &scenario {
+        Some(scenario) => writeln!(
+            summary,
+            "Scenario: {} ({} blocks)",
+            scenario.name, scenario.blocks
+        )?,
+        None => writeln!(summary, "Profile: {}", profile.name)?,
+    }
+    writeln!(summary, "Wallets created: {}", wallets.len())?;
+    writeln!(
+        summary,
         "Simulation money per wallet: {} LUSD",
         args.simulation_money
-    );
-    println!("Custom assets enabled: {:?}", custom_assets);
-    println!(
+    )?;
+    writeln!(summary, "Custom assets enabled: {:?}", custom_assets)?;
+    writeln!(
+        summary,
         "Custom asset seed amount per wallet: {}",
         args.custom_asset_amount
-    );
-    println!("Transfers attempted: {}", args.transfers);
-    println!("Successful transfers: {}", success);
-    println!("Failed transfers: {}", failed);
-    println!("Elapsed: {:.2?}", elapsed);
-    println!("TPS: {:.2}", tps);
-    println!("Total supply: {}", state.total_lusd_supply);
-    println!("Reserve ratio: {:.4}", state.reserve_ratio);
-    println!("Insurance fund: {}", state.insurance_fund_balance);
-
+    )?;
+    writeln!(summary, "Operations attempted: {}", attempted)?;
+    writeln!(summary, "Successful operations: {}", succeeded)?;
+    writeln!(summary, "Failed operations: {}", attempted - succeeded)?;
+    writeln!(summary, "Elapsed: {:.2?}", elapsed)?;
+    writeln!(summary, "TPS: {:.2}", tps)?;
+    writeln!(summary, "Total supply: {}", state.total_lusd_supply)?;
+    writeln!(summary, "Reserve ratio: {:.4}", state.reserve_ratio)?;
+    writeln!(summary, "Insurance fund: {}", state.insurance_fund_balance)?;
+    writeln!(summary)?;
+    stats.write(&mut summary)?;
+
+    if let Some(format) = args.output {
+        match &args.output_file {
+            Some(path) => {
+                let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                series::write(format, &stats.blocks, &mut file)?;
+                file.flush()?;
+            }
+            None => series::write(format, &stats.blocks, &mut std::io::stdout().lock())?,
+        }
+    }
     Ok(())
 }
 
@@ -308,7 +600,101 @@ mod tests {
         seed_custom_assets(&mut state, &wallets, &assets, 42);
 
         let first = state.accounts.get(&wallets[0].address).unwrap();
-        assert_eq!(first.custom_balances.get("BTC"), Some(&42));
-        assert_eq!(first.custom_balances.get("ETH"), Some(&42));
+        assert_eq!(first.custom_balances.get("BTC"), Some(&42.into()));
+        assert_eq!(first.custom_balances.get("ETH"), Some(&42.into()));
+    }
+
+    #[test]
+    fn mixed_workload_records_every_operation() {
+        let mut state = GlobalState::default();
+        let wallets = build_wallets(4);
+        let assets = vec!["BTC".to_string()];
+        seed_simulation_money(&mut state, &wallets, 100_000);
+        seed_oracle_prices(&mut state, &assets);
+        let profile = Profile::parse(
+            r#"
+            txs_per_block = 5
+            [weights]
+            transfer = 1
+            mint = 1
+            redeem = 1
+            stream = 1
+            wrap_yield = 1
+            unwrap_yield = 1
+            rwa_list = 1
+            rwa_pledge = 1
+            flash_mint = 1
+            oracle_update = 1
+            "#,
+        )
+        .unwrap();
+        let workload = Workload {
+            wallets: &wallets,
+            custom_assets: &assets,
+            proofs: Proofs::generate(),
+        };
+
+        let stats = run_workload(&mut state, &workload, &profile, 60, 2);
+        let attempted: u64 = stats.ops.values().map(|s| s.attempted()).sum();
+        assert_eq!(attempted, 60);
+        // Flash mints are burned within their block, so none are clawed back.
+        if let Some(flash) = stats.ops.get(&Op::FlashMint) {
+            assert_eq!(flash.failed, 0);
+        }
+        assert_eq!(state.pending_flash_mints, 0);
+        assert!(state.accounts.values().all(|a| a.pending_flash_mint == 0));
+
+        // One metrics record per block of five operations.
+        assert_eq!(stats.blocks.len(), 12);
+        assert_eq!(stats.blocks[0].height, 2);
+        assert!(stats.blocks.iter().all(|b| b.operations == 5));
+        let last = stats.blocks.last().unwrap();
+        assert_eq!(last.height, 13);
+        assert_eq!(last.lusd_supply, state.total_lusd_supply);
+    }
+
+    #[test]
+    fn agents_trade_through_a_depeg() {
+        let scenario = Scenario::parse(
+            r#"
+            blocks = 30
+            [agents]
+            arbitrageurs = 2
+            panicked_holders = 3
+            oracle_reporters = 1
+            [market]
+            reversion_bps = 10000
+            noise_bps = 0
+            exchange_lusd = 10000
+            [shock]
+            at_block = 5
+            depth_bps = 500
+            blocks = 10
+            "#,
+        )
+        .unwrap();
+        let mut state = GlobalState::default();
+        let wallets = build_wallets(7);
+        seed_simulation_money(&mut state, &wallets, 10_000);
+        seed_exchange(&mut state, &wallets[EXCHANGE_WALLET], 10_000);
+        seed_oracle_prices(&mut state, &[]);
+        let workload = Workload {
+            wallets: &wallets,
+            custom_assets: &[],
+            proofs: Proofs::generate(),
+        };
+
+        let stats = run_agents(&mut state, &workload, &scenario, 2);
+        assert_eq!(stats.blocks.len(), 30);
+        assert_eq!(stats.ops[&Op::OracleUpdate].succeeded, 30);
+        // Reporters carry the shock to the feed, and the recovery after it
+        let during = &stats.blocks[10];
+        assert!(during.lusd_price < 960_000);
+        let after = stats.blocks.last().unwrap();
+        assert!(after.lusd_price.abs_diff(agents::PEG) <= agents::PEG / 1_000);
+        // Arbitrageurs bought the discount, panicked holders redeemed or sold
+        assert!(stats.ops[&Op::Redeem].succeeded > 0);
+        assert!(stats.ops[&Op::Transfer].succeeded > 0);
+        assert!(after.lusd_supply < 80_000);
     }
 }
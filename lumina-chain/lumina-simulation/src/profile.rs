@@ -0,0 +1,180 @@
+use anyhow::{bail, Context, Result};
+use rand::Rng;
+use serde::Deserialize;
+use std::path::Path;
+
+/// A kind of workload operation. Most submit one instruction; a flash mint
+/// submits `FlashMint` and its `FlashBurn` in the same block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Op {
+    Transfer,
+    Mint,
+    Redeem,
+    Stream,
+    WrapYield,
+    UnwrapYield,
+    RwaList,
+    RwaPledge,
+    FlashMint,
+    OracleUpdate,
+}
+
+impl Op {
+    pub const ALL: [Op; 10] = [
+        Op::Transfer,
+        Op::Mint,
+        Op::Redeem,
+        Op::Stream,
+        Op::WrapYield,
+        Op::UnwrapYield,
+        Op::RwaList,
+        Op::RwaPledge,
+        Op::FlashMint,
+        Op::OracleUpdate,
+    ];
+
+    /// Name used as the weight key in profiles and in the report.
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Transfer => "transfer",
+            Op::Mint => "mint",
+            Op::Redeem => "redeem",
+            Op::Stream => "stream",
+            Op::WrapYield => "wrap_yield",
+            Op::UnwrapYield => "unwrap_yield",
+            Op::RwaList => "rwa_list",
+            Op::RwaPledge => "rwa_pledge",
+            Op::FlashMint => "flash_mint",
+            Op::OracleUpdate => "oracle_update",
+        }
+    }
+}
+
+/// Relative frequency of each operation. Omitted operations never run.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Weights {
+    pub transfer: u32,
+    pub mint: u32,
+    pub redeem: u32,
+    pub stream: u32,
+    pub wrap_yield: u32,
+    pub unwrap_yield: u32,
+    pub rwa_list: u32,
+    pub rwa_pledge: u32,
+    pub flash_mint: u32,
+    pub oracle_update: u32,
+}
+
+impl Weights {
+    pub fn of(&self, op: Op) -> u32 {
+        match op {
+            Op::Transfer => self.transfer,
+            Op::Mint => self.mint,
+            Op::Redeem => self.redeem,
+            Op::Stream => self.stream,
+            Op::WrapYield => self.wrap_yield,
+            Op::UnwrapYield => self.unwrap_yield,
+            Op::RwaList => self.rwa_list,
+            Op::RwaPledge => self.rwa_pledge,
+            Op::FlashMint => self.flash_mint,
+            Op::OracleUpdate => self.oracle_update,
+        }
+    }
+}
+
+/// A weighted workload, loaded from TOML:
+///
+/// ```toml
+/// name = "mixed"
+/// txs_per_block = 100
+///
+/// [weights]
+/// transfer = 60
+/// flash_mint = 5
+/// ```
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    pub name: String,
+    /// Operations executed between `begin_block` and `end_block`
+    pub txs_per_block: usize,
+    pub weights: Weights,
+}
+
+impl Default for Profile {
+    /// Transfers only, the workload the simulator ran before profiles.
+    fn default() -> Self {
+        Self {
+            name: "transfers".to_string(),
+            txs_per_block: 100,
+            weights: Weights {
+                transfer: 1,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl Profile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading profile {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("parsing profile {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let profile: Profile = toml::from_str(text)?;
+        if profile.txs_per_block == 0 {
+            bail!("txs_per_block must be at least 1");
+        }
+        if Op::ALL.iter().all(|op| profile.weights.of(*op) == 0) {
+            bail!("profile weights must not all be zero");
+        }
+        Ok(profile)
+    }
+
+    /// Draw an operation with probability proportional to its weight.
+    pub fn pick(&self, rng: &mut impl Rng) -> Op {
+        let total: u64 = Op::ALL.iter().map(|op| self.weights.of(*op) as u64).sum();
+        let mut roll = rng.gen_range(0..total);
+        for op in Op::ALL {
+            let weight = self.weights.of(op) as u64;
+            if roll < weight {
+                return op;
+            }
+            roll -= weight;
+        }
+        unreachable!("roll is below the total weight")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profiles_and_picks_only_weighted_ops() {
+        let profile = Profile::parse(
+            r#"
+            name = "flash"
+            [weights]
+            flash_mint = 3
+            oracle_update = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(profile.txs_per_block, 100);
+        assert_eq!(profile.weights.of(Op::FlashMint), 3);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let op = profile.pick(&mut rng);
+            assert!(op == Op::FlashMint || op == Op::OracleUpdate);
+        }
+
+        assert!(Profile::parse("[weights]\ntransfer = 0").is_err());
+        assert!(Profile::parse("[weights]\nswap = 1").is_err());
+        assert!(Profile::parse("txs_per_block = 0\n[weights]\nmint = 1").is_err());
+    }
+}
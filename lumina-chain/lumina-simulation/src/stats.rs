@@ -0,0 +1,115 @@
+use crate::profile::Op;
+use crate::series::BlockMetrics;
+use lumina_types::state::GlobalState;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Outcomes and execution latencies of one operation kind.
+#[derive(Default)]
+pub struct OpStats {
+    pub succeeded: u64,
+    pub failed: u64,
+    latencies: Vec<Duration>,
+}
+
+impl OpStats {
+    pub fn attempted(&self) -> u64 {
+        self.succeeded + self.failed
+    }
+
+    pub fn success_rate(&self) -> f64 {
+        match self.attempted() {
+            0 => 0.0,
+            n => self.succeeded as f64 / n as f64,
+        }
+    }
+
+    /// Nearest-rank percentile (`pct` in 0-100) of recorded latencies.
+    pub fn percentile(&self, pct: u32) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let mut sorted = self.latencies.clone();
+        sorted.sort_unstable();
+        let rank = (sorted.len() * pct.min(100) as usize).div_ceil(100);
+        sorted[rank.saturating_sub(1)]
+    }
+}
+
+/// Per-operation results of a workload run, and the chain's metrics after
+/// each block.
+#[derive(Default)]
+pub struct WorkloadStats {
+    pub ops: BTreeMap<Op, OpStats>,
+    pub blocks: Vec<BlockMetrics>,
+    block_operations: u64,
+    block_succeeded: u64,
+}
+
+impl WorkloadStats {
+    pub fn record(&mut self, op: Op, ok: bool, latency: Duration) {
+        let stats = self.ops.entry(op).or_default();
+        if ok {
+            stats.succeeded += 1;
+        } else {
+            stats.failed += 1;
+        }
+        stats.latencies.push(latency);
+        self.block_operations += 1;
+        self.block_succeeded += u64::from(ok);
+    }
+
+    /// Record the metrics of the block the latest operations ran in.
+    pub fn close_block(&mut self, state: &GlobalState, height: u64, timestamp: u64) {
+        self.blocks.push(BlockMetrics::capture(
+            state,
+            height,
+            timestamp,
+            std::mem::take(&mut self.block_operations),
+            std::mem::take(&mut self.block_succeeded),
+        ));
+    }
+
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{:<14} {:>9} {:>9} {:>8} {:>10} {:>10} {:>10}",
+            "operation", "attempted", "succeeded", "success", "p50", "p90", "p99"
+        )?;
+        for (op, stats) in &self.ops {
+            writeln!(
+                out,
+                "{:<14} {:>9} {:>9} {:>7.1}% {:>10.2?} {:>10.2?} {:>10.2?}",
+                op.name(),
+                stats.attempted(),
+                stats.succeeded,
+                stats.success_rate() * 100.0,
+                stats.percentile(50),
+                stats.percentile(90),
+                stats.percentile(99),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_success_rates_and_percentiles() {
+        let mut stats = WorkloadStats::default();
+        for ms in 1..=100 {
+            stats.record(Op::Transfer, ms % 4 != 0, Duration::from_millis(ms));
+        }
+        let transfer = &stats.ops[&Op::Transfer];
+        assert_eq!(transfer.attempted(), 100);
+        assert!((transfer.success_rate() - 0.75).abs() < f64::EPSILON);
+        assert_eq!(transfer.percentile(50), Duration::from_millis(50));
+        assert_eq!(transfer.percentile(99), Duration::from_millis(99));
+        assert_eq!(transfer.percentile(100), Duration::from_millis(100));
+        assert_eq!(OpStats::default().percentile(50), Duration::ZERO);
+    }
+}
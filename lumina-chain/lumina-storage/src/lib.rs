@@ -1,2 +1,4 @@
 pub mod db;
 pub mod merkle;
+pub mod migration;
+pub mod snapshot;
@@ -9,6 +9,7 @@ pub struct MerklePatriciaTrie {
     root: [u8; 32],
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 enum TrieNode {
     Leaf {
@@ -25,6 +26,7 @@ enum TrieNode {
     },
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ProofNode {
     Leaf {
@@ -261,10 +263,7 @@ fn longest_common_extension(entries: &[(Vec<u8>, Vec<u8>)], start: usize) -> Vec
     let mut out = Vec::new();
     let mut idx = start;
 
-    loop {
-        let Some(first) = entries[0].0.get(idx) else {
-            break;
-        };
+    while let Some(first) = entries[0].0.get(idx) {
         if entries.iter().all(|(k, _)| k.get(idx) == Some(first)) {
             out.push(*first);
             idx += 1;
@@ -376,8 +375,10 @@ mod tests {
     #[test]
     fn test_global_state_root_matches_trie() {
         let mut state = GlobalState::default();
-        let mut account = AccountState::default();
-        account.lusd_balance = 42;
+        let account = AccountState {
+            lusd_balance: 42,
+            ..Default::default()
+        };
         state.accounts.insert([9u8; 32], account);
 
         let trie = MerklePatriciaTrie::from_global_state(&state);
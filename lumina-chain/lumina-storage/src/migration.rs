@@ -0,0 +1,1012 @@
+//! Versioned on-disk encoding of `GlobalState`.
+//!
+//! Stored states are `STATE_MAGIC`, a little-endian `u32` encoding version
+//! and the bincode payload. Blobs without the magic predate versioning and
+//! are version 0. Older payloads are upgraded one version at a time through
+//! `MIGRATIONS` when read, and rewritten at startup by
+//! `Storage::migrate_stored_states`.
+
+use anyhow::{anyhow, bail, Result};
+use lumina_types::instruction::AssetType;
+use lumina_types::state::{
+    ConcentrationLimits, CredentialRequirements, GlobalState, HealthComponents, HealthIndexParams,
+    Stabilizer,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+pub const STATE_MAGIC: [u8; 4] = *b"LMST";
+/// Encoding version written by this build.
+pub const STATE_ENCODING_VERSION: u32 = 41;
+
+/// Upgrade of a `from` payload to the `from + 1` encoding.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    pub apply: fn(Vec<u8>) -> Result<Vec<u8>>,
+}
+
+/// One entry per version step, in order.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "append audit log head, audit sequence and state version",
+        apply: v0_to_v1,
+    },
+    Migration {
+        from: 1,
+        description: "append governance proposals and upgrade plans",
+        apply: v1_to_v2,
+    },
+    Migration {
+        from: 2,
+        description: "append deployed contracts",
+        apply: v2_to_v3,
+    },
+    Migration {
+        from: 3,
+        description: "append execution hooks and compliance attestations",
+        apply: v3_to_v4,
+    },
+    Migration {
+        from: 4,
+        description: "append sealed RWA valuations",
+        apply: v4_to_v5,
+    },
+    Migration {
+        from: 5,
+        description: "append view keys and confidential notes",
+        apply: v5_to_v6,
+    },
+    Migration {
+        from: 6,
+        description: "append screening oracle registry",
+        apply: v6_to_v7,
+    },
+    Migration {
+        from: 7,
+        description: "append KYC tiers, compliance officers and account limits",
+        apply: v7_to_v8,
+    },
+    Migration {
+        from: 8,
+        description: "append inheritance designations and claim index",
+        apply: v8_to_v9,
+    },
+    Migration {
+        from: 9,
+        description: "append vesting schedules",
+        apply: v9_to_v10,
+    },
+    Migration {
+        from: 10,
+        description: "append treasury balance, fee share, streams and flows",
+        apply: v10_to_v11,
+    },
+    Migration {
+        from: 11,
+        description: "append validator fee share, fee pool and rewards",
+        apply: v11_to_v12,
+    },
+    Migration {
+        from: 12,
+        description: "append validator liveness windows and jailed validators",
+        apply: v12_to_v13,
+    },
+    Migration {
+        from: 13,
+        description: "append health index parameters, components and history",
+        apply: v13_to_v14,
+    },
+    Migration {
+        from: 14,
+        description: "append stabilization pool composition and valuation",
+        apply: v14_to_v15,
+    },
+    Migration {
+        from: 15,
+        description: "append reserve concentration limits and breaches",
+        apply: v15_to_v16,
+    },
+    Migration {
+        from: 16,
+        description: "append collateral auctions",
+        apply: v16_to_v17,
+    },
+    Migration {
+        from: 17,
+        description: "append keeper bounty heights",
+        apply: v17_to_v18,
+    },
+    Migration {
+        from: 18,
+        description: "append credit histories",
+        apply: v18_to_v19,
+    },
+    Migration {
+        from: 19,
+        description: "append credit lines",
+        apply: v19_to_v20,
+    },
+    Migration {
+        from: 20,
+        description: "append merchant settlement accounts",
+        apply: v20_to_v21,
+    },
+    Migration {
+        from: 21,
+        description: "append the dust policy and reaping index",
+        apply: v21_to_v22,
+    },
+    Migration {
+        from: 22,
+        description: "append the account archive",
+        apply: v22_to_v23,
+    },
+    Migration {
+        from: 23,
+        description: "append the randomness beacon",
+        apply: v23_to_v24,
+    },
+    Migration {
+        from: 24,
+        description: "append the parameter controller",
+        apply: v24_to_v25,
+    },
+    Migration {
+        from: 25,
+        description: "append archived LUSD",
+        apply: v25_to_v26,
+    },
+    Migration {
+        from: 26,
+        description: "append PoR history",
+        apply: v26_to_v27,
+    },
+    Migration {
+        from: 27,
+        description: "append custodian rotation participation",
+        apply: v27_to_v28,
+    },
+    Migration {
+        from: 28,
+        description: "append compliance circuit versions",
+        apply: v28_to_v29,
+    },
+    Migration {
+        from: 29,
+        description: "append jurisdiction policies and account tags",
+        apply: v29_to_v30,
+    },
+    Migration {
+        from: 30,
+        description: "append institution credential registry",
+        apply: v30_to_v31,
+    },
+    Migration {
+        from: 31,
+        description: "give account streams an asset and id, append the next stream id",
+        apply: v31_to_v32,
+    },
+    Migration {
+        from: 32,
+        description: "give account streams accrued amounts and proposed rates",
+        apply: v32_to_v33,
+    },
+    Migration {
+        from: 33,
+        description: "append hash locks, their timeout index and the next lock id",
+        apply: v33_to_v34,
+    },
+    Migration {
+        from: 34,
+        description: "append SPV bridges, light clients, deposit claims and the next claim id",
+        apply: v34_to_v35,
+    },
+    Migration {
+        from: 35,
+        description: "append remote chains, outbound messages, message batches and LUSD returns",
+        apply: v35_to_v36,
+    },
+    Migration {
+        from: 36,
+        description: "append mint caps, this block's capped mints and the capped-block streak",
+        apply: v36_to_v37,
+    },
+    Migration {
+        from: 37,
+        description: "append redemption delay tiers and the delayed redemptions",
+        apply: v37_to_v38,
+    },
+    Migration {
+        from: 38,
+        description: "append the emergency council and its actions",
+        apply: v38_to_v39,
+    },
+    Migration {
+        from: 39,
+        description: "append the governance-paused and paused instruction bitmaps",
+        apply: v39_to_v40,
+    },
+    Migration {
+        from: 40,
+        description: "widen custom and bridged asset balances to u128",
+        apply: v40_to_v41,
+    },
+];
+
+/// The fields added in v1 trail the struct, so the fixed-width bincode
+/// encoding of their zero values can be appended as is.
+fn v0_to_v1(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&[0u8; 32]); // audit_head
+    payload.extend_from_slice(&0u64.to_le_bytes()); // audit_seq
+    payload.extend_from_slice(&0u32.to_le_bytes()); // version
+    Ok(payload)
+}
+
+fn v1_to_v2(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // proposals (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_proposal_id
+    payload.extend_from_slice(&0u64.to_le_bytes()); // upgrade_plans (empty)
+    Ok(payload)
+}
+
+fn v2_to_v3(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // contracts (empty map)
+    Ok(payload)
+}
+
+fn v3_to_v4(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // hooks (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // compliance_attestations (empty map)
+    Ok(payload)
+}
+
+fn v4_to_v5(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // sealed_rwa_valuations (empty map)
+    Ok(payload)
+}
+
+fn v5_to_v6(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // view_keys (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // confidential_notes (empty map)
+    Ok(payload)
+}
+
+fn v6_to_v7(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // screening_oracles (empty map)
+    Ok(payload)
+}
+
+fn v7_to_v8(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // kyc_tiers (empty)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // compliance_officers (empty set)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // kyc_accounts (empty map)
+    Ok(payload)
+}
+
+fn v8_to_v9(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // inheritance (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // inheritance_claims (empty map)
+    Ok(payload)
+}
+
+fn v9_to_v10(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // vesting (empty map)
+    Ok(payload)
+}
+
+fn v10_to_v11(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // treasury_balance
+    payload.extend_from_slice(&0u64.to_le_bytes()); // treasury_fee_share_bps
+    payload.extend_from_slice(&0u64.to_le_bytes()); // treasury_streams (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // treasury_flows (empty map)
+    Ok(payload)
+}
+
+fn v11_to_v12(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // validator_fee_share_bps
+    payload.extend_from_slice(&0u64.to_le_bytes()); // fee_pool
+    payload.extend_from_slice(&0u64.to_le_bytes()); // validator_rewards (empty map)
+    Ok(payload)
+}
+
+fn v12_to_v13(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // validator_liveness (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // jailed_validators (empty map)
+    Ok(payload)
+}
+
+/// Older states keep the weights that were hardcoded before they moved into
+/// state.
+fn v13_to_v14(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend(bincode::serialize(&HealthIndexParams::default())?); // health_params
+    payload.extend(bincode::serialize(&HealthComponents::default())?); // health_components
+    payload.extend_from_slice(&0u64.to_le_bytes()); // health_history (empty map)
+    Ok(payload)
+}
+
+fn v14_to_v15(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // pool_composition (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // pool_valuation (empty map)
+    Ok(payload)
+}
+
+fn v15_to_v16(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend(bincode::serialize(&ConcentrationLimits::default())?); // concentration_limits
+    payload.extend_from_slice(&0u64.to_le_bytes()); // concentration_breaches (empty set)
+    Ok(payload)
+}
+
+fn v16_to_v17(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // collateral_auctions (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_auction_id
+    Ok(payload)
+}
+
+fn v17_to_v18(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // keeper_last_paid (empty map)
+    Ok(payload)
+}
+
+fn v18_to_v19(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // credit_histories (empty map)
+    Ok(payload)
+}
+
+fn v19_to_v20(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // credit_lines (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_credit_line_id
+    Ok(payload)
+}
+
+fn v20_to_v21(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // merchants (empty map)
+    Ok(payload)
+}
+
+fn v21_to_v22(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // dust_policy.existential_deposit
+    payload.extend_from_slice(&0u64.to_le_bytes()); // dust_policy.grace_blocks
+    payload.extend_from_slice(&0u64.to_le_bytes()); // dust_accounts (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // dust_reaps (empty map)
+    Ok(payload)
+}
+
+fn v22_to_v23(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // archive_policy.inactivity_epochs
+    payload.extend_from_slice(&0u64.to_le_bytes()); // archive_policy.enabled_epoch
+    payload.extend_from_slice(&0u64.to_le_bytes()); // archive_activity (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // archive_batches (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_archive_batch
+    payload.extend_from_slice(&0u64.to_le_bytes()); // archived_accounts (empty map)
+    Ok(payload)
+}
+
+fn v23_to_v24(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // beacon_round (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // randomness_beacons (empty map)
+    Ok(payload)
+}
+
+fn v24_to_v25(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend(bincode::serialize(&Stabilizer::default())?); // stabilizer
+    Ok(payload)
+}
+
+fn v25_to_v26(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // archived_lusd
+    Ok(payload)
+}
+
+fn v26_to_v27(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // por_history (empty map)
+    Ok(payload)
+}
+
+fn v27_to_v28(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // custodian_rotations (empty map)
+    Ok(payload)
+}
+
+fn v28_to_v29(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // circuit_versions (empty map)
+    Ok(payload)
+}
+
+fn v29_to_v30(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // jurisdiction_policies (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // account_jurisdictions (empty map)
+    Ok(payload)
+}
+
+fn v30_to_v31(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // credential_attesters (empty set)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // institution_credentials (empty map)
+    payload.extend(bincode::serialize(&CredentialRequirements::default())?); // credential_requirements
+    Ok(payload)
+}
+
+/// `AccountState` as encoded through v31, with its streams' and custom and
+/// bridged balances' encodings as parameters. Frozen here so later changes
+/// to the live type cannot alter how old payloads are read.
+#[derive(Serialize, Deserialize)]
+struct AccountV31<S, B = u64> {
+    nonce: u64,
+    lusd_balance: u64,
+    ljun_balance: u64,
+    lumina_balance: u64,
+    custom_balances: HashMap<String, B>,
+    bridged_balances: HashMap<String, B>,
+    commitment: Option<[u8; 32]>,
+    passkey_device_key: Option<Vec<u8>>,
+    guardians: Vec<[u8; 32]>,
+    pq_pubkey: Option<Vec<u8>>,
+    epoch_tx_volume: u64,
+    last_reward_epoch: u64,
+    credit_score: u16,
+    active_streams: Vec<S>,
+    /// token_id, principal, maturity_height, issued_height
+    yield_positions: Vec<(u64, u64, u64, u64)>,
+    pending_flash_mint: u64,
+    pending_flash_collateral: u64,
+    pending_flash_loan: u64,
+    /// tier, paid_through_epoch, claimed, total_premiums_paid
+    insurance_coverage: Option<(u8, u64, u64, u64)>,
+    collateral_position_ids: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StreamV31 {
+    recipient: [u8; 32],
+    amount_per_sec: u64,
+    start_timestamp: u64,
+    end_timestamp: u64,
+    withdrawn: u64,
+}
+
+/// Encodes as `AssetType::LUSD`, its first variant.
+#[derive(Serialize, Deserialize)]
+enum LusdV32 {
+    Lusd,
+}
+
+/// A v32 stream; the v31 upgrade writes its asset as `LusdV32`.
+#[derive(Serialize, Deserialize)]
+struct StreamV32<A = LusdV32> {
+    recipient: [u8; 32],
+    amount_per_sec: u64,
+    start_timestamp: u64,
+    end_timestamp: u64,
+    withdrawn: u64,
+    asset: A,
+    id: u64,
+}
+
+impl<S, B> AccountV31<S, B> {
+    fn map_streams<T>(self, convert: impl FnMut(S) -> T) -> AccountV31<T, B> {
+        AccountV31 {
+            nonce: self.nonce,
+            lusd_balance: self.lusd_balance,
+            ljun_balance: self.ljun_balance,
+            lumina_balance: self.lumina_balance,
+            custom_balances: self.custom_balances,
+            bridged_balances: self.bridged_balances,
+            commitment: self.commitment,
+            passkey_device_key: self.passkey_device_key,
+            guardians: self.guardians,
+            pq_pubkey: self.pq_pubkey,
+            epoch_tx_volume: self.epoch_tx_volume,
+            last_reward_epoch: self.last_reward_epoch,
+            credit_score: self.credit_score,
+            active_streams: self.active_streams.into_iter().map(convert).collect(),
+            yield_positions: self.yield_positions,
+            pending_flash_mint: self.pending_flash_mint,
+            pending_flash_collateral: self.pending_flash_collateral,
+            pending_flash_loan: self.pending_flash_loan,
+            insurance_coverage: self.insurance_coverage,
+            collateral_position_ids: self.collateral_position_ids,
+        }
+    }
+}
+
+/// Every stream so far escrowed LUSD. `accounts` is the first field, so it
+/// is rewritten in place and the rest of the payload kept; ids are handed
+/// out in address order so every node assigns the same ones.
+fn v31_to_v32(payload: Vec<u8>) -> Result<Vec<u8>> {
+    let mut rest = payload.as_slice();
+    let accounts: BTreeMap<[u8; 32], AccountV31<StreamV31>> = bincode::deserialize_from(&mut rest)?;
+    let mut next_id = 0u64;
+    let accounts: BTreeMap<[u8; 32], AccountV31<StreamV32>> = accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let account = account.map_streams(|s| {
+                next_id += 1;
+                StreamV32 {
+                    recipient: s.recipient,
+                    amount_per_sec: s.amount_per_sec,
+                    start_timestamp: s.start_timestamp,
+                    end_timestamp: s.end_timestamp,
+                    withdrawn: s.withdrawn,
+                    asset: LusdV32::Lusd,
+                    id: next_id - 1,
+                }
+            });
+            (address, account)
+        })
+        .collect();
+    let mut out = bincode::serialize(&accounts)?;
+    out.extend_from_slice(rest);
+    out.extend_from_slice(&next_id.to_le_bytes()); // next_stream_id
+    Ok(out)
+}
+
+#[derive(Serialize, Deserialize)]
+struct StreamV33 {
+    recipient: [u8; 32],
+    amount_per_sec: u64,
+    start_timestamp: u64,
+    end_timestamp: u64,
+    withdrawn: u64,
+    asset: AssetType,
+    id: u64,
+    accrued: u64,
+    proposed_rate: Option<u64>,
+}
+
+/// No stream has been re-rated yet, so each keeps its clock with nothing
+/// accrued before it.
+fn v32_to_v33(payload: Vec<u8>) -> Result<Vec<u8>> {
+    let mut rest = payload.as_slice();
+    let accounts: BTreeMap<[u8; 32], AccountV31<StreamV32<AssetType>>> =
+        bincode::deserialize_from(&mut rest)?;
+    let accounts: BTreeMap<[u8; 32], AccountV31<StreamV33>> = accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let account = account.map_streams(|s| StreamV33 {
+                recipient: s.recipient,
+                amount_per_sec: s.amount_per_sec,
+                start_timestamp: s.start_timestamp,
+                end_timestamp: s.end_timestamp,
+                withdrawn: s.withdrawn,
+                asset: s.asset,
+                id: s.id,
+                accrued: 0,
+                proposed_rate: None,
+            });
+            (address, account)
+        })
+        .collect();
+    let mut out = bincode::serialize(&accounts)?;
+    out.extend_from_slice(rest);
+    Ok(out)
+}
+
+fn v33_to_v34(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // hash_locks (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // hash_lock_timeouts (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_hash_lock_id
+    Ok(payload)
+}
+
+fn v34_to_v35(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // spv_bridges (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // spv_clients (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // spv_deposits (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // spv_claimed (empty set)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_spv_deposit_id
+    Ok(payload)
+}
+
+fn v35_to_v36(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // remote_chains (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // outbound_messages (empty vec)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // message_batches (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // pending_returns (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // completed_returns (empty set)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_message_nonce
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_message_batch_id
+    Ok(payload)
+}
+
+fn v36_to_v37(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&[0u8; 24]); // mint_caps (all caps lifted)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // block_minted
+    payload.extend_from_slice(&0u64.to_le_bytes()); // block_account_mints (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // mint_cap_streak
+    Ok(payload)
+}
+
+fn v37_to_v38(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // redemption_tiers (empty vec)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // delayed_redemptions (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_delayed_redemption_id
+    Ok(payload)
+}
+
+fn v38_to_v39(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&[0u8; 20]); // emergency_council (no members)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // council_actions (empty map)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // next_council_action_id
+    Ok(payload)
+}
+
+fn v39_to_v40(mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    payload.extend_from_slice(&0u64.to_le_bytes()); // governance_paused (no words)
+    payload.extend_from_slice(&0u64.to_le_bytes()); // paused_instructions (no words)
+    Ok(payload)
+}
+
+/// Custom and bridged balances become u128, rewriting `accounts` in place.
+fn v40_to_v41(payload: Vec<u8>) -> Result<Vec<u8>> {
+    let widen = |balances: HashMap<String, u64>| -> HashMap<String, u128> {
+        balances
+            .into_iter()
+            .map(|(asset, amount)| (asset, amount as u128))
+            .collect()
+    };
+    let mut rest = payload.as_slice();
+    let accounts: BTreeMap<[u8; 32], AccountV31<StreamV33>> = bincode::deserialize_from(&mut rest)?;
+    let accounts: BTreeMap<[u8; 32], AccountV31<StreamV33, u128>> = accounts
+        .into_iter()
+        .map(|(address, a)| {
+            let account = AccountV31 {
+                nonce: a.nonce,
+                lusd_balance: a.lusd_balance,
+                ljun_balance: a.ljun_balance,
+                lumina_balance: a.lumina_balance,
+                custom_balances: widen(a.custom_balances),
+                bridged_balances: widen(a.bridged_balances),
+                commitment: a.commitment,
+                passkey_device_key: a.passkey_device_key,
+                guardians: a.guardians,
+                pq_pubkey: a.pq_pubkey,
+                epoch_tx_volume: a.epoch_tx_volume,
+                last_reward_epoch: a.last_reward_epoch,
+                credit_score: a.credit_score,
+                active_streams: a.active_streams,
+                yield_positions: a.yield_positions,
+                pending_flash_mint: a.pending_flash_mint,
+                pending_flash_collateral: a.pending_flash_collateral,
+                pending_flash_loan: a.pending_flash_loan,
+                insurance_coverage: a.insurance_coverage,
+                collateral_position_ids: a.collateral_position_ids,
+            };
+            (address, account)
+        })
+        .collect();
+    let mut out = bincode::serialize(&accounts)?;
+    out.extend_from_slice(rest);
+    Ok(out)
+}
+
+/// Encoding version and payload of a stored state.
+pub fn split_envelope(bytes: &[u8]) -> Result<(u32, &[u8])> {
+    let Some(rest) = bytes.strip_prefix(&STATE_MAGIC) else {
+        return Ok((0, bytes));
+    };
+    if rest.len() < 4 {
+        bail!("Truncated state envelope");
+    }
+    let (version, payload) = rest.split_at(4);
+    let version = u32::from_le_bytes(version.try_into().expect("4-byte version"));
+    Ok((version, payload))
+}
+
+/// Upgrade a `version` payload to `STATE_ENCODING_VERSION`.
+pub fn migrate(version: u32, payload: Vec<u8>) -> Result<Vec<u8>> {
+    if version > STATE_ENCODING_VERSION {
+        bail!(
+            "State encoding v{} is newer than supported v{}",
+            version,
+            STATE_ENCODING_VERSION
+        );
+    }
+    let mut payload = payload;
+    for from in version..STATE_ENCODING_VERSION {
+        let migration = MIGRATIONS
+            .iter()
+            .find(|m| m.from == from)
+            .ok_or_else(|| anyhow!("No state migration from v{}", from))?;
+        payload = (migration.apply)(payload)
+            .map_err(|e| anyhow!("State migration from v{} failed: {}", from, e))?;
+    }
+    Ok(payload)
+}
+
+pub fn encode_state(state: &GlobalState) -> Result<Vec<u8>> {
+    let payload = bincode::serialize(state).map_err(|e| anyhow!("Serialization error: {}", e))?;
+    let mut out = Vec::with_capacity(8 + payload.len());
+    out.extend_from_slice(&STATE_MAGIC);
+    out.extend_from_slice(&STATE_ENCODING_VERSION.to_le_bytes());
+    out.extend_from_slice(&payload);
+    Ok(out)
+}
+
+pub fn decode_state(bytes: &[u8]) -> Result<GlobalState> {
+    let (version, payload) = split_envelope(bytes)?;
+    let decoded = if version == STATE_ENCODING_VERSION {
+        bincode::deserialize(payload)
+    } else {
+        bincode::deserialize(&migrate(version, payload.to_vec())?)
+    };
+    decoded.map_err(|e| anyhow!("Deserialization error: {}", e))
+}
+
+/// Whether a stored state must be rewritten in the current encoding.
+pub fn needs_migration(bytes: &[u8]) -> Result<bool> {
+    Ok(split_envelope(bytes)?.0 != STATE_ENCODING_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::state::{
+        AccountState, ArchivePolicy, DustPolicy, EmergencyCouncil, MintCaps,
+    };
+
+    /// A v0 database state (pre-versioning layout) with two accounts, LUSD
+    /// supply 1_500 and a health index of 9_000.
+    const V0_FIXTURE: &[u8] = include_bytes!("../fixtures/global_state_v0.bin");
+
+    #[test]
+    fn migrations_are_consecutive_and_reach_current_version() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from, i as u32, "{}", migration.description);
+        }
+        assert_eq!(MIGRATIONS.len() as u32, STATE_ENCODING_VERSION);
+    }
+
+    #[test]
+    fn v32_numbers_existing_streams_as_lusd() {
+        let stream = |recipient| StreamV31 {
+            recipient,
+            amount_per_sec: 2,
+            start_timestamp: 10,
+            end_timestamp: 20,
+            withdrawn: 4,
+        };
+        let account = |streams| AccountV31::<_, u64> {
+            nonce: 1,
+            lusd_balance: 100,
+            ljun_balance: 0,
+            lumina_balance: 0,
+            custom_balances: HashMap::new(),
+            bridged_balances: HashMap::new(),
+            commitment: None,
+            passkey_device_key: None,
+            guardians: Vec::new(),
+            pq_pubkey: None,
+            epoch_tx_volume: 0,
+            last_reward_epoch: 0,
+            credit_score: 0,
+            active_streams: streams,
+            yield_positions: vec![(1, 50, 9, 3)],
+            pending_flash_mint: 0,
+            pending_flash_collateral: 0,
+            pending_flash_loan: 0,
+            insurance_coverage: Some((1, 2, 3, 4)),
+            collateral_position_ids: vec![7],
+        };
+        let accounts = BTreeMap::from([
+            ([2u8; 32], account(vec![stream([5u8; 32])])),
+            (
+                [1u8; 32],
+                account(vec![stream([3u8; 32]), stream([4u8; 32])]),
+            ),
+        ]);
+        let mut payload = bincode::serialize(&accounts).unwrap();
+        payload.extend_from_slice(b"tail");
+
+        let out = v31_to_v32(payload).unwrap();
+        let mut rest = out.as_slice();
+        let accounts: BTreeMap<[u8; 32], AccountV31<StreamV32>> =
+            bincode::deserialize_from(&mut rest).unwrap();
+        assert_eq!(rest, [b"tail".as_slice(), &3u64.to_le_bytes()].concat());
+        let ids: Vec<_> = accounts
+            .values()
+            .flat_map(|a| a.active_streams.iter().map(|s| (s.recipient[0], s.id)))
+            .collect();
+        assert_eq!(ids, vec![(3, 0), (4, 1), (5, 2)]);
+        let migrated = &accounts[&[1u8; 32]];
+        assert_eq!(migrated.active_streams[0].withdrawn, 4);
+        assert_eq!(migrated.yield_positions, vec![(1, 50, 9, 3)]);
+        assert_eq!(migrated.insurance_coverage, Some((1, 2, 3, 4)));
+        assert_eq!(
+            bincode::serialize(&LusdV32::Lusd).unwrap(),
+            0u32.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn v33_keeps_stream_ids_and_withdrawals() {
+        let stream = StreamV32 {
+            recipient: [3u8; 32],
+            amount_per_sec: 2,
+            start_timestamp: 10,
+            end_timestamp: 20,
+            withdrawn: 4,
+            asset: AssetType::Custom("GOLD".to_string()),
+            id: 9,
+        };
+        let account = AccountV31::<_, u64> {
+            nonce: 1,
+            lusd_balance: 100,
+            ljun_balance: 0,
+            lumina_balance: 0,
+            custom_balances: HashMap::new(),
+            bridged_balances: HashMap::new(),
+            commitment: None,
+            passkey_device_key: None,
+            guardians: Vec::new(),
+            pq_pubkey: None,
+            epoch_tx_volume: 0,
+            last_reward_epoch: 0,
+            credit_score: 0,
+            active_streams: vec![stream],
+            yield_positions: Vec::new(),
+            pending_flash_mint: 0,
+            pending_flash_collateral: 0,
+            pending_flash_loan: 0,
+            insurance_coverage: None,
+            collateral_position_ids: vec![7],
+        };
+        let mut payload = bincode::serialize(&BTreeMap::from([([1u8; 32], account)])).unwrap();
+        payload.extend_from_slice(b"tail");
+
+        let out = v32_to_v33(payload).unwrap();
+        let mut rest = out.as_slice();
+        let accounts: BTreeMap<[u8; 32], AccountV31<StreamV33>> =
+            bincode::deserialize_from(&mut rest).unwrap();
+        assert_eq!(rest, b"tail");
+        let migrated = &accounts[&[1u8; 32]];
+        let stream = &migrated.active_streams[0];
+        assert_eq!((stream.id, stream.withdrawn, stream.accrued), (9, 4, 0));
+        assert_eq!(stream.asset, AssetType::Custom("GOLD".to_string()));
+        assert_eq!(stream.proposed_rate, None);
+        assert_eq!(migrated.collateral_position_ids, vec![7]);
+    }
+
+    #[test]
+    fn v41_widens_asset_balances_in_place() {
+        let account = AccountV31::<StreamV33> {
+            nonce: 1,
+            lusd_balance: 100,
+            ljun_balance: 0,
+            lumina_balance: 0,
+            custom_balances: HashMap::from([("GOLD".to_string(), u64::MAX)]),
+            bridged_balances: HashMap::from([("ETH".to_string(), 5)]),
+            commitment: None,
+            passkey_device_key: None,
+            guardians: Vec::new(),
+            pq_pubkey: None,
+            epoch_tx_volume: 0,
+            last_reward_epoch: 0,
+            credit_score: 0,
+            active_streams: Vec::new(),
+            yield_positions: Vec::new(),
+            pending_flash_mint: 0,
+            pending_flash_collateral: 0,
+            pending_flash_loan: 0,
+            insurance_coverage: None,
+            collateral_position_ids: vec![7],
+        };
+        let mut payload = bincode::serialize(&BTreeMap::from([([1u8; 32], account)])).unwrap();
+        payload.extend_from_slice(b"tail");
+
+        let out = v40_to_v41(payload).unwrap();
+        let mut rest = out.as_slice();
+        let accounts: BTreeMap<[u8; 32], AccountV31<StreamV33, u128>> =
+            bincode::deserialize_from(&mut rest).unwrap();
+        assert_eq!(rest, b"tail");
+        let migrated = &accounts[&[1u8; 32]];
+        assert_eq!(migrated.custom_balances["GOLD"], u64::MAX as u128);
+        assert_eq!(migrated.bridged_balances["ETH"], 5);
+        assert_eq!(migrated.collateral_position_ids, vec![7]);
+
+        let live: BTreeMap<[u8; 32], AccountState> =
+            bincode::deserialize(&bincode::serialize(&accounts).unwrap()).unwrap();
+        assert_eq!(live[&[1u8; 32]].custom_balances["GOLD"], u64::MAX);
+    }
+
+    #[test]
+    fn v0_fixture_decodes_through_migrations() {
+        assert!(needs_migration(V0_FIXTURE).unwrap());
+        assert!(bincode::deserialize::<GlobalState>(V0_FIXTURE).is_err());
+
+        let state = decode_state(V0_FIXTURE).unwrap();
+        assert_eq!(state.accounts.len(), 2);
+        assert_eq!(state.accounts[&[1u8; 32]].lusd_balance, 1_000);
+        assert_eq!(state.total_lusd_supply, 1_500);
+        assert_eq!(state.health_index, 9_000);
+        assert_eq!(
+            (state.audit_head, state.audit_seq, state.version),
+            ([0u8; 32], 0, 0)
+        );
+        assert!(state.proposals.is_empty() && state.upgrade_plans.is_empty());
+        assert!(state.contracts.is_empty());
+        assert!(state.hooks.is_empty() && state.compliance_attestations.is_empty());
+        assert!(state.sealed_rwa_valuations.is_empty());
+        assert!(state.view_keys.is_empty() && state.confidential_notes.is_empty());
+        assert!(state.screening_oracles.is_empty());
+        assert!(state.kyc_tiers.is_empty() && state.compliance_officers.is_empty());
+        assert!(state.kyc_accounts.is_empty());
+        assert!(state.inheritance.is_empty() && state.inheritance_claims.is_empty());
+        assert!(state.vesting.is_empty());
+        assert_eq!(
+            (state.treasury_balance, state.treasury_fee_share_bps),
+            (0, 0)
+        );
+        assert!(state.treasury_streams.is_empty() && state.treasury_flows.is_empty());
+        assert_eq!((state.validator_fee_share_bps, state.fee_pool), (0, 0));
+        assert!(state.validator_rewards.is_empty());
+        assert!(state.validator_liveness.is_empty() && state.jailed_validators.is_empty());
+        assert_eq!(state.health_params, HealthIndexParams::default());
+        assert!(state.health_history.is_empty());
+        assert!(state.pool_composition.is_empty() && state.pool_valuation.is_empty());
+        assert_eq!(state.concentration_limits, ConcentrationLimits::default());
+        assert!(state.concentration_breaches.is_empty());
+        assert!(state.collateral_auctions.is_empty());
+        assert_eq!(state.next_auction_id, 0);
+        assert!(state.keeper_last_paid.is_empty());
+        assert!(state.credit_histories.is_empty());
+        assert!(state.credit_lines.is_empty() && state.next_credit_line_id == 0);
+        assert!(state.merchants.is_empty());
+        assert_eq!(state.dust_policy, DustPolicy::default());
+        assert!(state.dust_accounts.is_empty() && state.dust_reaps.is_empty());
+        assert_eq!(state.archive_policy, ArchivePolicy::default());
+        assert!(state.archive_activity.is_empty() && state.archive_batches.is_empty());
+        assert!(state.archived_accounts.is_empty() && state.next_archive_batch == 0);
+        assert!(state.beacon_round.is_empty() && state.randomness_beacons.is_empty());
+        assert_eq!(state.stabilizer, Stabilizer::default());
+        assert_eq!(state.archived_lusd, 0);
+        assert!(state.por_history.is_empty());
+        assert!(state.custodian_rotations.is_empty());
+        assert!(state.circuit_versions.is_empty());
+        assert!(state.jurisdiction_policies.is_empty() && state.account_jurisdictions.is_empty());
+        assert!(state.credential_attesters.is_empty() && state.institution_credentials.is_empty());
+        assert_eq!(
+            state.credential_requirements,
+            CredentialRequirements::default()
+        );
+        assert_eq!(state.next_stream_id, 0);
+        assert!(state.hash_locks.is_empty() && state.hash_lock_timeouts.is_empty());
+        assert_eq!(state.next_hash_lock_id, 0);
+        assert!(state.spv_bridges.is_empty() && state.spv_clients.is_empty());
+        assert!(state.spv_deposits.is_empty() && state.spv_claimed.is_empty());
+        assert_eq!(state.next_spv_deposit_id, 0);
+        assert!(state.remote_chains.is_empty() && state.outbound_messages.is_empty());
+        assert!(state.message_batches.is_empty() && state.pending_returns.is_empty());
+        assert!(state.completed_returns.is_empty());
+        assert_eq!(
+            (state.next_message_nonce, state.next_message_batch_id),
+            (0, 0)
+        );
+        assert_eq!(state.mint_caps, MintCaps::default());
+        assert_eq!((state.block_minted, state.mint_cap_streak), (0, 0));
+        assert!(state.block_account_mints.is_empty());
+        assert!(state.redemption_tiers.is_empty() && state.delayed_redemptions.is_empty());
+        assert_eq!(state.next_delayed_redemption_id, 0);
+        assert_eq!(state.emergency_council, EmergencyCouncil::default());
+        assert!(state.council_actions.is_empty());
+        assert_eq!(state.next_council_action_id, 0);
+        assert!(state.governance_paused.is_empty() && state.paused_instructions.is_empty());
+
+        let encoded = encode_state(&state).unwrap();
+        assert!(!needs_migration(&encoded).unwrap());
+        assert_eq!(
+            decode_state(&encoded).unwrap().root_hash(),
+            state.root_hash()
+        );
+    }
+
+    #[test]
+    fn rejects_encodings_from_newer_nodes() {
+        let mut encoded = encode_state(&GlobalState::default()).unwrap();
+        encoded[4..8].copy_from_slice(&(STATE_ENCODING_VERSION + 1).to_le_bytes());
+        assert!(decode_state(&encoded)
+            .unwrap_err()
+            .to_string()
+            .contains("newer than supported"));
+    }
+}
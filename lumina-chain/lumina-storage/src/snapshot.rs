@@ -0,0 +1,134 @@
+//! State snapshots at a checkpoint, served to bootstrapping nodes.
+
+use crate::db::Storage;
+use crate::migration::{decode_state, encode_state};
+use anyhow::{anyhow, bail, Result};
+use lumina_types::block::Block;
+use lumina_types::checkpoint::Checkpoint;
+use lumina_types::state::GlobalState;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub block: Block,
+    /// The state the block committed, in the versioned storage encoding so
+    /// snapshots from older nodes migrate on load
+    pub state: Vec<u8>,
+}
+
+impl StateSnapshot {
+    pub fn new(block: Block, state: &GlobalState) -> Result<Self> {
+        Ok(Self {
+            block,
+            state: encode_state(state)?,
+        })
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        bincode::serialize(self).map_err(|e| anyhow!("Serialization error: {}", e))
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        bincode::deserialize(bytes).map_err(|e| anyhow!("Invalid state snapshot: {}", e))
+    }
+
+    /// The snapshot's state, once its block and state are shown to be the
+    /// ones `checkpoint` names.
+    pub fn verify(&self, checkpoint: &Checkpoint) -> Result<GlobalState> {
+        if Checkpoint::of(&self.block) != *checkpoint {
+            bail!("Snapshot block does not match the checkpoint");
+        }
+        let state = decode_state(&self.state)?;
+        if state.root_hash() != checkpoint.state_root {
+            bail!("Snapshot state does not match the checkpoint state root");
+        }
+        Ok(state)
+    }
+
+    /// Store the checkpoint block and `state` as the canonical tip of an
+    /// empty `storage`, with `genesis` at height 0. Heights in between are
+    /// left out; blocks import from the checkpoint onwards.
+    pub fn install(
+        &self,
+        storage: &Storage,
+        genesis: &GlobalState,
+        state: &GlobalState,
+    ) -> Result<()> {
+        if storage.load_tip()?.is_some_and(|(height, _)| height > 0) {
+            bail!("Bootstrap needs an empty data directory");
+        }
+        let hash = self.block.hash();
+        let height = self.block.header.height;
+        storage.save_state_at_height(0, genesis)?;
+        storage.save_block(&self.block)?;
+        storage.save_block_meta(hash, height, self.block.header.prev_hash)?;
+        storage.save_state_by_hash(hash, state)?;
+        storage.save_canonical_block_at_height(height, hash)?;
+        storage.save_state_at_height(height, state)?;
+        storage.save_state(state)?;
+        storage.save_tip(height, hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lumina_types::block::BlockHeader;
+
+    fn checkpoint_block(state: &GlobalState) -> Block {
+        Block {
+            header: BlockHeader {
+                height: 500,
+                prev_hash: [4u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: state.root_hash(),
+                timestamp: 1,
+                proposer: [0u8; 32],
+                validator_set_hash: state.validator_set_hash(),
+                audit_hash: state.audit_head,
+                signers_hash: [0u8; 32],
+            },
+            transactions: Vec::new(),
+            votes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn snapshots_install_only_the_checkpointed_state() {
+        let mut state = GlobalState::default();
+        state.accounts.entry([1u8; 32]).or_default().lusd_balance = 42;
+        let block = checkpoint_block(&state);
+        let checkpoint = Checkpoint::of(&block);
+        let snapshot = StateSnapshot::decode(
+            &StateSnapshot::new(block.clone(), &state)
+                .unwrap()
+                .encode()
+                .unwrap(),
+        )
+        .unwrap();
+
+        let mut tampered = state.clone();
+        tampered.accounts.entry([1u8; 32]).or_default().lusd_balance = 43;
+        let forged = StateSnapshot::new(block.clone(), &tampered).unwrap();
+        assert!(forged.verify(&checkpoint).is_err());
+        let mut other = checkpoint;
+        other.height += 1;
+        assert!(snapshot.verify(&other).is_err());
+
+        let verified = snapshot.verify(&checkpoint).unwrap();
+        let storage = Storage::new("unused").unwrap();
+        let genesis = GlobalState::default();
+        snapshot.install(&storage, &genesis, &verified).unwrap();
+        assert_eq!(storage.load_tip().unwrap(), Some((500, block.hash())));
+        assert_eq!(
+            storage.load_block_meta(&block.hash()).unwrap(),
+            Some((500, [4u8; 32]))
+        );
+        assert_eq!(
+            storage.load_state().unwrap().root_hash(),
+            checkpoint.state_root
+        );
+        assert!(storage.load_state_by_height(0).unwrap().is_some());
+        assert!(snapshot.install(&storage, &genesis, &verified).is_err());
+    }
+}
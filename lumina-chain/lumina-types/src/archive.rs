@@ -0,0 +1,268 @@
+use crate::balance::Balance;
+use crate::state::{AccountState, InsuranceCoverage, StreamState, YieldPosition};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Most accounts one `ArchiveAccounts` may move out of live state.
+pub const MAX_ARCHIVE_BATCH: usize = 1_000;
+
+/// An archived account: its address and `encode_account` bytes.
+pub type ArchivedLeaf = ([u8; 32], Vec<u8>);
+
+/// An account's archived bytes: its bincode encoding, which `AccountState`'s
+/// ordered balance maps make the same on every node.
+pub fn encode_account(account: &AccountState) -> Vec<u8> {
+    bincode::serialize(account).expect("account serialization")
+}
+
+/// `AccountState` as archived before custom and bridged balances became
+/// u128 (state encoding v41), so those accounts can still be revived.
+#[derive(Deserialize)]
+#[cfg_attr(test, derive(Serialize, Default))]
+struct AccountV40 {
+    nonce: u64,
+    lusd_balance: u64,
+    ljun_balance: u64,
+    lumina_balance: u64,
+    custom_balances: HashMap<String, u64>,
+    bridged_balances: HashMap<String, u64>,
+    commitment: Option<[u8; 32]>,
+    passkey_device_key: Option<Vec<u8>>,
+    guardians: Vec<[u8; 32]>,
+    pq_pubkey: Option<Vec<u8>>,
+    epoch_tx_volume: u64,
+    last_reward_epoch: u64,
+    credit_score: u16,
+    active_streams: Vec<StreamState>,
+    yield_positions: Vec<YieldPosition>,
+    pending_flash_mint: u64,
+    pending_flash_collateral: u64,
+    pending_flash_loan: u64,
+    insurance_coverage: Option<InsuranceCoverage>,
+    collateral_position_ids: Vec<u64>,
+}
+
+impl From<AccountV40> for AccountState {
+    fn from(account: AccountV40) -> Self {
+        let widen = |balances: HashMap<String, u64>| {
+            balances
+                .into_iter()
+                .map(|(asset, amount)| (asset, Balance::from(amount)))
+                .collect()
+        };
+        AccountState {
+            nonce: account.nonce,
+            lusd_balance: account.lusd_balance,
+            ljun_balance: account.ljun_balance,
+            lumina_balance: account.lumina_balance,
+            custom_balances: widen(account.custom_balances),
+            bridged_balances: widen(account.bridged_balances),
+            commitment: account.commitment,
+            passkey_device_key: account.passkey_device_key,
+            guardians: account.guardians,
+            pq_pubkey: account.pq_pubkey,
+            epoch_tx_volume: account.epoch_tx_volume,
+            last_reward_epoch: account.last_reward_epoch,
+            credit_score: account.credit_score,
+            active_streams: account.active_streams,
+            yield_positions: account.yield_positions,
+            pending_flash_mint: account.pending_flash_mint,
+            pending_flash_collateral: account.pending_flash_collateral,
+            pending_flash_loan: account.pending_flash_loan,
+            insurance_coverage: account.insurance_coverage,
+            collateral_position_ids: account.collateral_position_ids,
+        }
+    }
+}
+
+/// Decode archived bytes in the current layout, or else the v40 one. Each
+/// must consume every byte, so one account's bytes cannot pass as both.
+pub fn decode_account(bytes: &[u8]) -> Option<AccountState> {
+    let options = bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes();
+    options.deserialize(bytes).ok().or_else(|| {
+        options
+            .deserialize::<AccountV40>(bytes)
+            .ok()
+            .map(AccountState::from)
+    })
+}
+
+fn leaf_hash(address: &[u8; 32], account: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[0]);
+    hasher.update(address);
+    hasher.update(account);
+    *hasher.finalize().as_bytes()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[1]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+fn bind_root(leaf_count: u64, tree_root: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_ARCHIVE");
+    hasher.update(&leaf_count.to_le_bytes());
+    hasher.update(tree_root);
+    *hasher.finalize().as_bytes()
+}
+
+/// Merkle tree over one batch of archived accounts, in batch order.
+pub struct ArchiveTree {
+    /// Node levels, leaf hashes first, each padded to an even length
+    levels: Vec<Vec<[u8; 32]>>,
+    leaf_count: u64,
+}
+
+/// Inclusion of the account at `index` of a batch of `leaf_count`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArchiveProof {
+    pub index: u64,
+    pub leaf_count: u64,
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl ArchiveTree {
+    pub fn new(leaves: &[ArchivedLeaf]) -> Self {
+        let mut level: Vec<_> = leaves
+            .iter()
+            .map(|(a, bytes)| leaf_hash(a, bytes))
+            .collect();
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                level.push([0u8; 32]);
+            }
+            let next = level.chunks(2).map(|p| node_hash(&p[0], &p[1])).collect();
+            levels.push(std::mem::replace(&mut level, next));
+        }
+        levels.push(level);
+        Self {
+            levels,
+            leaf_count: leaves.len() as u64,
+        }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        let top = self.levels.last().and_then(|l| l.first());
+        bind_root(self.leaf_count, top.unwrap_or(&[0u8; 32]))
+    }
+
+    pub fn prove(&self, index: usize) -> Option<ArchiveProof> {
+        if index as u64 >= self.leaf_count {
+            return None;
+        }
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1])
+            .collect();
+        Some(ArchiveProof {
+            index: index as u64,
+            leaf_count: self.leaf_count,
+            siblings,
+        })
+    }
+}
+
+impl ArchiveProof {
+    /// Whether `account` is the state archived for `address` under `root`.
+    pub fn verify(&self, root: &[u8; 32], address: &[u8; 32], account: &[u8]) -> bool {
+        if self.index >= self.leaf_count {
+            return false;
+        }
+        let depth = (u64::BITS - self.leaf_count.saturating_sub(1).leading_zeros()) as usize;
+        if self.siblings.len() != depth {
+            return false;
+        }
+        let mut hash = leaf_hash(address, account);
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            hash = if (self.index >> level) & 1 == 0 {
+                node_hash(&hash, sibling)
+            } else {
+                node_hash(sibling, &hash)
+            };
+        }
+        bind_root(self.leaf_count, &hash) == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proves_archived_accounts_and_rejects_altered_ones() {
+        let accounts: Vec<AccountState> = (0..5u64)
+            .map(|i| {
+                let mut account = AccountState {
+                    nonce: i,
+                    lusd_balance: 100 * i,
+                    ..Default::default()
+                };
+                for ticker in ["GOLD", "OIL", "WHEAT"] {
+                    account.custom_balances.insert(ticker.to_string(), i.into());
+                }
+                account
+            })
+            .collect();
+        let leaves: Vec<ArchivedLeaf> = accounts
+            .iter()
+            .enumerate()
+            .map(|(i, a)| ([i as u8; 32], encode_account(a)))
+            .collect();
+        let tree = ArchiveTree::new(&leaves);
+        let root = tree.root();
+
+        for (i, (address, bytes)) in leaves.iter().enumerate() {
+            let proof = tree.prove(i).unwrap();
+            assert!(proof.verify(&root, address, bytes));
+            assert!(!proof.verify(&root, &[9u8; 32], bytes));
+            let decoded = decode_account(bytes).unwrap();
+            assert_eq!(decoded.lusd_balance, accounts[i].lusd_balance);
+            assert_eq!(decoded.custom_balances, accounts[i].custom_balances);
+            assert_eq!(encode_account(&decoded), *bytes);
+        }
+        assert!(tree.prove(5).is_none());
+
+        let mut richer = accounts[2].clone();
+        richer.lusd_balance += 1;
+        let proof = tree.prove(2).unwrap();
+        assert!(!proof.verify(&root, &leaves[2].0, &encode_account(&richer)));
+        let short = ArchiveProof {
+            leaf_count: 4,
+            ..proof
+        };
+        assert!(!short.verify(&root, &leaves[2].0, &leaves[2].1));
+    }
+
+    #[test]
+    fn revives_accounts_archived_with_u64_asset_balances() {
+        let legacy = AccountV40 {
+            nonce: 3,
+            lusd_balance: 70,
+            custom_balances: [("GOLD".to_string(), u64::MAX)].into_iter().collect(),
+            bridged_balances: [("ETH".to_string(), 5)].into_iter().collect(),
+            collateral_position_ids: vec![4],
+            ..Default::default()
+        };
+        let bytes = bincode::serialize(&legacy).unwrap();
+        let account = decode_account(&bytes).unwrap();
+        assert_eq!((account.nonce, account.lusd_balance), (3, 70));
+        assert_eq!(account.custom_balances["GOLD"], u64::MAX);
+        assert_eq!(account.bridged_balances["ETH"], 5);
+        assert_eq!(account.collateral_position_ids, vec![4]);
+        assert_ne!(encode_account(&account), bytes);
+
+        let mut trailing = encode_account(&account);
+        trailing.push(0);
+        assert!(decode_account(&trailing).is_none());
+    }
+}
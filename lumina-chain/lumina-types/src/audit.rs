@@ -0,0 +1,167 @@
+use crate::instruction::AssetType;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Role {
+    Validator,
+    Custodian,
+    GreenValidator,
+}
+
+/// A privileged or monetary operation recorded in the audit log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AuditAction {
+    Mint {
+        asset: AssetType,
+        amount: u64,
+    },
+    Burn {
+        asset: AssetType,
+        amount: u64,
+    },
+    InsuranceClaim {
+        amount: u64,
+    },
+    CircuitBreaker {
+        active: bool,
+    },
+    GovernanceVote {
+        proposal_id: u64,
+        approve: bool,
+    },
+    GovernanceExecuted {
+        proposal_id: u64,
+    },
+    RoleChanged {
+        role: Role,
+        granted: bool,
+    },
+    /// An emergency council action took effect (`active`) or was lifted
+    CouncilAction {
+        action_id: u64,
+        active: bool,
+    },
+}
+
+/// One entry of the hash-chained audit log. `hash` commits to `prev_hash`
+/// and every other field, so rewriting any entry breaks all later ones.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub height: u64,
+    /// Index of the transaction in its block, or `None` for end-of-block actions
+    pub tx_index: Option<u32>,
+    /// Transaction sender; zero for protocol-initiated actions
+    pub actor: [u8; 32],
+    pub action: AuditAction,
+    pub prev_hash: [u8; 32],
+    pub hash: [u8; 32],
+}
+
+impl AuditRecord {
+    pub fn new(
+        prev_hash: [u8; 32],
+        seq: u64,
+        height: u64,
+        tx_index: Option<u32>,
+        actor: [u8; 32],
+        action: AuditAction,
+    ) -> Self {
+        let mut record = Self {
+            seq,
+            height,
+            tx_index,
+            actor,
+            action,
+            prev_hash,
+            hash: [0u8; 32],
+        };
+        record.hash = record.compute_hash();
+        record
+    }
+
+    fn compute_hash(&self) -> [u8; 32] {
+        let body = (
+            self.seq,
+            self.height,
+            self.tx_index,
+            self.actor,
+            &self.action,
+        );
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&self.prev_hash);
+        hasher.update(&bincode::serialize(&body).expect("audit record serialization"));
+        *hasher.finalize().as_bytes()
+    }
+
+    /// Check that `records` extend the chain ending at `head` with
+    /// consecutive sequence numbers. Returns the new head, or the sequence
+    /// number of the first entry that does not verify.
+    pub fn verify_chain(head: [u8; 32], records: &[AuditRecord]) -> Result<[u8; 32], u64> {
+        let mut head = head;
+        let mut expected_seq = records.first().map(|r| r.seq);
+        for record in records {
+            if Some(record.seq) != expected_seq
+                || record.prev_hash != head
+                || record.hash != record.compute_hash()
+            {
+                return Err(record.seq);
+            }
+            head = record.hash;
+            expected_seq = record.seq.checked_add(1);
+        }
+        Ok(head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tampering_breaks_the_chain() {
+        let first = AuditRecord::new(
+            [0u8; 32],
+            0,
+            1,
+            Some(0),
+            [1u8; 32],
+            AuditAction::Mint {
+                asset: AssetType::LUSD,
+                amount: 100,
+            },
+        );
+        let second = AuditRecord::new(
+            first.hash,
+            1,
+            1,
+            None,
+            [0u8; 32],
+            AuditAction::CircuitBreaker { active: true },
+        );
+        let mut records = vec![first, second];
+        assert_eq!(
+            AuditRecord::verify_chain([0u8; 32], &records),
+            Ok(records[1].hash)
+        );
+
+        records[0].action = AuditAction::Mint {
+            asset: AssetType::LUSD,
+            amount: 1,
+        };
+        assert_eq!(AuditRecord::verify_chain([0u8; 32], &records), Err(0));
+
+        records[0] = AuditRecord::new(
+            [0u8; 32],
+            0,
+            1,
+            Some(0),
+            [1u8; 32],
+            AuditAction::Mint {
+                asset: AssetType::LUSD,
+                amount: 1,
+            },
+        );
+        assert_eq!(AuditRecord::verify_chain([0u8; 32], &records), Err(1));
+    }
+}
@@ -0,0 +1,142 @@
+//! Account balances of custom and bridged assets. These carry their native
+//! decimals, 18 for most Ethereum tokens, so a large supply outgrows a u64;
+//! holdings are u128 while each instruction still moves a u64 amount.
+
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// A u128 balance. Binary encodings (bincode state, archived accounts)
+/// carry it as a u128; JSON and other human-readable formats as a decimal
+/// string, which JavaScript clients can hold without losing precision.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Balance(u128);
+
+impl Balance {
+    pub const ZERO: Balance = Balance(0);
+    pub const MAX: Balance = Balance(u128::MAX);
+
+    pub const fn new(units: u128) -> Balance {
+        Balance(units)
+    }
+
+    pub const fn get(self) -> u128 {
+        self.0
+    }
+
+    pub const fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, amount: u64) -> Option<Balance> {
+        self.0.checked_add(amount as u128).map(Balance)
+    }
+
+    pub fn checked_sub(self, amount: u64) -> Option<Balance> {
+        self.0.checked_sub(amount as u128).map(Balance)
+    }
+
+    pub fn checked_add_balance(self, other: Balance) -> Option<Balance> {
+        self.0.checked_add(other.0).map(Balance)
+    }
+
+    pub fn saturating_add(self, amount: u64) -> Balance {
+        Balance(self.0.saturating_add(amount as u128))
+    }
+
+    /// The balance as a u64, if it fits.
+    pub fn to_u64(self) -> Option<u64> {
+        u64::try_from(self.0).ok()
+    }
+}
+
+impl From<u64> for Balance {
+    fn from(units: u64) -> Balance {
+        Balance(units as u128)
+    }
+}
+
+impl PartialEq<u64> for Balance {
+    fn eq(&self, other: &u64) -> bool {
+        self.0 == *other as u128
+    }
+}
+
+impl PartialOrd<u64> for Balance {
+    fn partial_cmp(&self, other: &u64) -> Option<std::cmp::Ordering> {
+        Some(self.0.cmp(&(*other as u128)))
+    }
+}
+
+impl fmt::Display for Balance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for Balance {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&self.0)
+        } else {
+            serializer.serialize_u128(self.0)
+        }
+    }
+}
+
+struct BalanceVisitor;
+
+impl Visitor<'_> for BalanceVisitor {
+    type Value = Balance;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a non-negative integer or decimal string")
+    }
+
+    fn visit_u64<E: de::Error>(self, units: u64) -> Result<Balance, E> {
+        Ok(Balance::from(units))
+    }
+
+    fn visit_u128<E: de::Error>(self, units: u128) -> Result<Balance, E> {
+        Ok(Balance(units))
+    }
+
+    fn visit_str<E: de::Error>(self, units: &str) -> Result<Balance, E> {
+        units.parse().map(Balance).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Balance {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Balance, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BalanceVisitor)
+        } else {
+            deserializer.deserialize_u128(BalanceVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn balances_pass_u64_and_encode_as_strings_in_json() {
+        let large = Balance::from(u64::MAX).checked_add(u64::MAX).unwrap();
+        assert_eq!(large.get(), 2 * u64::MAX as u128);
+        assert_eq!(large.to_u64(), None);
+        assert!(large > u64::MAX);
+        assert_eq!(Balance::MAX.checked_add(1), None);
+        assert_eq!(Balance::from(5).checked_sub(6), None);
+
+        let json = serde_json::to_string(&large).unwrap();
+        assert_eq!(json, "\"36893488147419103230\"");
+        assert_eq!(serde_json::from_str::<Balance>(&json).unwrap(), large);
+        assert_eq!(serde_json::from_str::<Balance>("42").unwrap(), 42u64);
+        assert!(serde_json::from_str::<Balance>("\"-1\"").is_err());
+
+        let bytes = bincode::serialize(&large).unwrap();
+        assert_eq!(bytes, large.get().to_le_bytes());
+        assert_eq!(bincode::deserialize::<Balance>(&bytes).unwrap(), large);
+    }
+}
@@ -0,0 +1,37 @@
+//! Hashes of the per-epoch commit-reveal randomness beacon.
+
+/// What a validator commits to for `epoch`: binding the epoch and its key
+/// keeps a commitment from being replayed or copied by another validator.
+pub fn commitment(epoch: u64, validator: &[u8; 32], secret: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_BEACON_COMMIT");
+    hasher.update(&epoch.to_le_bytes());
+    hasher.update(validator);
+    hasher.update(secret);
+    *hasher.finalize().as_bytes()
+}
+
+/// Output of `epoch`'s beacon from the previous output and the revealed
+/// secrets, in validator order.
+pub fn mix(epoch: u64, previous: &[u8; 32], reveals: &[([u8; 32], [u8; 32])]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_BEACON");
+    hasher.update(&epoch.to_le_bytes());
+    hasher.update(previous);
+    for (validator, secret) in reveals {
+        hasher.update(validator);
+        hasher.update(secret);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// Randomness for one use of a beacon output, so different features never
+/// share a value.
+pub fn derive(output: &[u8; 32], domain: &[u8]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_RANDOMNESS");
+    hasher.update(&(domain.len() as u64).to_le_bytes());
+    hasher.update(domain);
+    hasher.update(output);
+    *hasher.finalize().as_bytes()
+}
@@ -1,5 +1,9 @@
 use crate::transaction::Transaction;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Ids of a block's transactions keyed by instruction kind, in block order.
+pub type InstructionIndex = BTreeMap<String, Vec<[u8; 32]>>;
 
 fn hash_concat(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
     let mut hasher = blake3::Hasher::new();
@@ -16,8 +20,17 @@ pub struct BlockHeader {
     pub state_root: [u8; 32],
     pub timestamp: u64,
     pub proposer: [u8; 32],
+    /// Validator set active after this block (see `GlobalState::validator_set_hash`)
+    pub validator_set_hash: [u8; 32],
+    /// Rolling audit log hash after this block (see `GlobalState::audit_head`)
+    pub audit_hash: [u8; 32],
+    /// Validators whose votes for the parent block this block carries (see
+    /// `Block::signers_hash`)
+    pub signers_hash: [u8; 32],
 }
 
+/// A block and the commit votes for its parent: each vote signs the
+/// parent's hash, and the voters are committed in `signers_hash`.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Block {
     pub header: BlockHeader,
@@ -39,7 +52,7 @@ impl Block {
 
         let mut level: Vec<[u8; 32]> = txs.iter().map(|tx| tx.id()).collect();
         while level.len() > 1 {
-            let mut next = Vec::with_capacity((level.len() + 1) / 2);
+            let mut next = Vec::with_capacity(level.len().div_ceil(2));
             let mut i = 0;
             while i < level.len() {
                 let left = level[i];
@@ -56,6 +69,30 @@ impl Block {
         level[0]
     }
 
+    /// Commitment to the validators of `votes`, in order; zero when there
+    /// are none.
+    pub fn signers_hash(votes: &[Vote]) -> [u8; 32] {
+        if votes.is_empty() {
+            return [0u8; 32];
+        }
+        let mut hasher = blake3::Hasher::new();
+        for vote in votes {
+            hasher.update(&vote.validator);
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    pub fn instruction_index(&self) -> InstructionIndex {
+        let mut index = InstructionIndex::new();
+        for tx in &self.transactions {
+            index
+                .entry(tx.instruction.kind().to_string())
+                .or_default()
+                .push(tx.id());
+        }
+        index
+    }
+
     pub fn hash(&self) -> [u8; 32] {
         let mut hasher = blake3::Hasher::new();
         hasher.update(&bincode::serialize(&self.header).expect("block header serialization"));
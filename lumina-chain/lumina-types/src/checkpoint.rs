@@ -0,0 +1,141 @@
+//! Validator-signed checkpoints. A new node can bootstrap from the state
+//! at a checkpoint instead of replaying the chain from genesis.
+
+use crate::block::{Block, Vote};
+use crate::state::ValidatorState;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const CHECKPOINT_DOMAIN: &[u8] = b"LUMINA_CHECKPOINT_V1";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub height: u64,
+    pub block_hash: [u8; 32],
+    /// State root committed by the block
+    pub state_root: [u8; 32],
+}
+
+impl Checkpoint {
+    pub fn of(block: &Block) -> Self {
+        Self {
+            height: block.header.height,
+            block_hash: block.hash(),
+            state_root: block.header.state_root,
+        }
+    }
+
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = CHECKPOINT_DOMAIN.to_vec();
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        bytes.extend_from_slice(&self.block_hash);
+        bytes.extend_from_slice(&self.state_root);
+        bytes
+    }
+}
+
+/// A checkpoint and the validator signatures vouching for it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CheckpointAttestation {
+    pub checkpoint: Checkpoint,
+    pub signatures: Vec<Vote>,
+}
+
+impl CheckpointAttestation {
+    /// Add `key`'s signature, replacing any earlier one from it.
+    pub fn sign(&mut self, key: &SigningKey) {
+        let validator = key.verifying_key().to_bytes();
+        self.signatures.retain(|vote| vote.validator != validator);
+        self.signatures.push(Vote {
+            validator,
+            signature: key
+                .sign(&self.checkpoint.signing_bytes())
+                .to_bytes()
+                .to_vec(),
+        });
+    }
+
+    /// Power of the distinct `trusted` validators that validly signed.
+    pub fn signed_power(&self, trusted: &[ValidatorState]) -> u128 {
+        let message = self.checkpoint.signing_bytes();
+        let mut counted = HashSet::new();
+        let mut power = 0u128;
+        for vote in &self.signatures {
+            let Some(validator) = trusted.iter().find(|v| v.pubkey == vote.validator) else {
+                continue;
+            };
+            let valid = VerifyingKey::from_bytes(&vote.validator)
+                .ok()
+                .zip(Signature::from_slice(&vote.signature).ok())
+                .is_some_and(|(key, sig)| key.verify(&message, &sig).is_ok());
+            if valid && counted.insert(vote.validator) {
+                power += validator.power.max(1) as u128;
+            }
+        }
+        power
+    }
+
+    /// Whether signers hold over two thirds of the power of `trusted`.
+    pub fn has_quorum(&self, trusted: &[ValidatorState]) -> bool {
+        let total: u128 = trusted.iter().map(|v| v.power.max(1) as u128).sum();
+        !trusted.is_empty() && self.signed_power(trusted) > total * 2 / 3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validator(key: &SigningKey, power: u64) -> ValidatorState {
+        ValidatorState {
+            pubkey: key.verifying_key().to_bytes(),
+            stake: 0,
+            power,
+            is_green: false,
+            energy_proof: None,
+            green_verified_at: 0,
+        }
+    }
+
+    #[test]
+    fn quorum_counts_valid_signatures_of_trusted_validators_once() {
+        let keys: Vec<SigningKey> = (1..=4u8)
+            .map(|i| SigningKey::from_bytes(&[i; 32]))
+            .collect();
+        let trusted = vec![
+            validator(&keys[0], 50),
+            validator(&keys[1], 20),
+            validator(&keys[2], 20),
+            validator(&keys[3], 10),
+        ];
+        let mut attestation = CheckpointAttestation {
+            checkpoint: Checkpoint {
+                height: 1_000,
+                block_hash: [7u8; 32],
+                state_root: [8u8; 32],
+            },
+            signatures: Vec::new(),
+        };
+        attestation.sign(&keys[0]);
+        attestation.sign(&keys[0]);
+        attestation.sign(&keys[3]);
+        assert_eq!(attestation.signatures.len(), 2);
+        // 60 of 100 is not over two thirds.
+        assert!(!attestation.has_quorum(&trusted));
+
+        // An untrusted signer and a signature over another checkpoint add nothing.
+        let outsider = SigningKey::from_bytes(&[9u8; 32]);
+        attestation.sign(&outsider);
+        let mut other = attestation.clone();
+        other.checkpoint.height += 1;
+        other.sign(&keys[1]);
+        attestation.signatures.push(other.signatures.pop().unwrap());
+        assert_eq!(attestation.signed_power(&trusted), 60);
+
+        attestation.sign(&keys[2]);
+        assert_eq!(attestation.signed_power(&trusted), 80);
+        assert!(attestation.has_quorum(&trusted));
+        assert!(!attestation.has_quorum(&[]));
+    }
+}
@@ -0,0 +1,137 @@
+//! Cross-chain LUSD messages. LUSD burned on Lumina for another chain is
+//! queued as an outbound message; each block's messages are sealed into a
+//! batch that validators sign, and relayers carry batches with over two
+//! thirds of the signing power to the remote chain, which mints there.
+
+use crate::block::Vote;
+use crate::state::ValidatorState;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const BATCH_DOMAIN: &[u8] = b"LUMINA_MESSAGE_BATCH_V1";
+
+/// Mint `amount` LUSD to `recipient` on `dest_chain`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OutboundMessage {
+    /// Position in the queue across all chains; a remote chain mints each
+    /// nonce once
+    pub nonce: u64,
+    pub dest_chain: u64,
+    pub sender: [u8; 32],
+    /// Address on the remote chain, in its own encoding
+    pub recipient: Vec<u8>,
+    pub amount: u64,
+}
+
+/// The messages sent in one block and the validator signatures over them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MessageBatch {
+    pub batch_id: u64,
+    pub height: u64,
+    pub messages: Vec<OutboundMessage>,
+    /// Signatures over `signing_bytes`, checked when submitted
+    pub signatures: Vec<Vote>,
+}
+
+impl MessageBatch {
+    /// What validators sign, laid out so remote verifiers need no bincode:
+    /// the domain, batch id and height, then per message its nonce, chain,
+    /// sender, recipient length (u32) and bytes, and amount, integers
+    /// little-endian.
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut bytes = BATCH_DOMAIN.to_vec();
+        bytes.extend_from_slice(&self.batch_id.to_le_bytes());
+        bytes.extend_from_slice(&self.height.to_le_bytes());
+        for message in &self.messages {
+            bytes.extend_from_slice(&message.nonce.to_le_bytes());
+            bytes.extend_from_slice(&message.dest_chain.to_le_bytes());
+            bytes.extend_from_slice(&message.sender);
+            bytes.extend_from_slice(&(message.recipient.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&message.recipient);
+            bytes.extend_from_slice(&message.amount.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Power of the distinct `trusted` validators among the signers.
+    pub fn signed_power(&self, trusted: &[ValidatorState]) -> u128 {
+        let mut counted = HashSet::new();
+        self.signatures
+            .iter()
+            .filter(|vote| counted.insert(vote.validator))
+            .filter_map(|vote| trusted.iter().find(|v| v.pubkey == vote.validator))
+            .map(|v| v.power.max(1) as u128)
+            .sum()
+    }
+
+    /// Whether signers hold over two thirds of the power of `trusted`.
+    pub fn has_quorum(&self, trusted: &[ValidatorState]) -> bool {
+        let total: u128 = trusted.iter().map(|v| v.power.max(1) as u128).sum();
+        !trusted.is_empty() && self.signed_power(trusted) > total * 2 / 3
+    }
+}
+
+/// Identifies a burn on `source_chain` returning LUSD to Lumina, so it is
+/// minted back once.
+pub fn return_key(source_chain: u64, burn_id: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_LUSD_RETURN");
+    hasher.update(&source_chain.to_le_bytes());
+    hasher.update(burn_id);
+    *hasher.finalize().as_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signing_bytes_cover_every_message_field() {
+        let message = OutboundMessage {
+            nonce: 7,
+            dest_chain: 1,
+            sender: [1u8; 32],
+            recipient: vec![0xab; 20],
+            amount: 500,
+        };
+        let batch = MessageBatch {
+            batch_id: 3,
+            height: 90,
+            messages: vec![message.clone()],
+            signatures: Vec::new(),
+        };
+        let bytes = batch.signing_bytes();
+        assert_eq!(
+            bytes.len(),
+            BATCH_DOMAIN.len() + 16 + 8 + 8 + 32 + 4 + 20 + 8
+        );
+        assert!(bytes.starts_with(BATCH_DOMAIN));
+
+        let variants = [
+            OutboundMessage {
+                nonce: 8,
+                ..message.clone()
+            },
+            OutboundMessage {
+                dest_chain: 2,
+                ..message.clone()
+            },
+            OutboundMessage {
+                recipient: vec![0xab; 21],
+                ..message.clone()
+            },
+            OutboundMessage {
+                amount: 501,
+                ..message
+            },
+        ];
+        for changed in variants {
+            let other = MessageBatch {
+                messages: vec![changed],
+                ..batch.clone()
+            };
+            assert_ne!(other.signing_bytes(), bytes);
+        }
+        assert_ne!(return_key(1, &[2u8; 32]), return_key(2, &[2u8; 32]));
+    }
+}
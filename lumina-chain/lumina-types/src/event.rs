@@ -0,0 +1,934 @@
+use crate::archive::ArchivedLeaf;
+use crate::instruction::AssetType;
+use crate::state::{
+    ControlledParam, CouncilPower, CredentialKind, KeeperTask, OracleRegime, ProtocolParam,
+    RedemptionTier, SpvChain,
+};
+use serde::{Deserialize, Serialize};
+
+/// Protocol events emitted during block execution.
+/// Collected in `GlobalState::events` and drained by the block pipeline.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ChainEvent {
+    OracleRegimeChanged {
+        height: u64,
+        from: OracleRegime,
+        to: OracleRegime,
+    },
+    FlashLoanDefaulted {
+        height: u64,
+        borrower: [u8; 32],
+        shortfall: u64,
+    },
+    /// An unburned flash mint was clawed back; its collateral is forfeited
+    FlashMintClawedBack {
+        height: u64,
+        account: [u8; 32],
+        burned: u64,
+        unrecovered: u64,
+        collateral_seized: u64,
+    },
+    /// Off-chain reserves custodians must move to meet zone targets.
+    /// Zone 0 stands for unallocated reserves.
+    ReserveMovementRequired {
+        height: u64,
+        from_zone: u32,
+        to_zone: u32,
+        amount: u64,
+    },
+    ReserveRotationFinalized {
+        height: u64,
+        rotation_id: u64,
+        from_custodian: [u8; 32],
+        to_custodian: [u8; 32],
+        amount: u64,
+    },
+    /// A passed governance proposal scheduled a protocol upgrade
+    UpgradeScheduled {
+        height: u64,
+        proposal_id: u64,
+        name: String,
+        upgrade_height: u64,
+    },
+    ContractDeployed {
+        height: u64,
+        contract: [u8; 32],
+        deployer: [u8; 32],
+        code_hash: [u8; 32],
+    },
+    ContractCalled {
+        height: u64,
+        contract: [u8; 32],
+        caller: [u8; 32],
+        gas_used: u64,
+    },
+    /// A passed governance proposal registered or replaced an execution hook
+    HookRegistered {
+        height: u64,
+        proposal_id: u64,
+        name: String,
+        circuit_id: u64,
+    },
+    HookRemoved {
+        height: u64,
+        proposal_id: u64,
+        name: String,
+    },
+    /// A passed governance proposal added or removed a screening oracle
+    ScreeningOracleChanged {
+        height: u64,
+        proposal_id: u64,
+        oracle: [u8; 32],
+        member: bool,
+    },
+    ScreeningRootPublished {
+        height: u64,
+        oracle: [u8; 32],
+        root: [u8; 32],
+    },
+    /// A passed governance proposal added or removed a compliance officer
+    ComplianceOfficerChanged {
+        height: u64,
+        proposal_id: u64,
+        officer: [u8; 32],
+        member: bool,
+    },
+    /// A passed governance proposal replaced the KYC tier table
+    KycTiersUpdated {
+        height: u64,
+        proposal_id: u64,
+        tiers: u64,
+    },
+    KycTierAssigned {
+        height: u64,
+        account: [u8; 32],
+        tier: u8,
+        officer: [u8; 32],
+    },
+    InheritanceClaimStarted {
+        height: u64,
+        owner: [u8; 32],
+        beneficiary: [u8; 32],
+        matures_at: u64,
+    },
+    /// The owner transacted or revoked the designation during the
+    /// challenge window
+    InheritanceClaimCancelled {
+        height: u64,
+        owner: [u8; 32],
+        beneficiary: [u8; 32],
+    },
+    InheritanceSettled {
+        height: u64,
+        owner: [u8; 32],
+        beneficiary: [u8; 32],
+        lusd: u64,
+        ljun_shares: u64,
+        lumina: u64,
+    },
+    VestingCreated {
+        height: u64,
+        funder: [u8; 32],
+        beneficiary: [u8; 32],
+        total: u64,
+        cliff_height: u64,
+        end_height: u64,
+    },
+    /// A passed governance proposal paid out (or escrowed, for a stream
+    /// ending at `stream_end_height`) a treasury spend
+    TreasurySpendExecuted {
+        height: u64,
+        proposal_id: u64,
+        recipient: [u8; 32],
+        amount: u64,
+        stream_end_height: Option<u64>,
+    },
+    TreasuryFeeShareChanged {
+        height: u64,
+        proposal_id: u64,
+        share_bps: u64,
+    },
+    ValidatorFeeShareChanged {
+        height: u64,
+        proposal_id: u64,
+        share_bps: u64,
+    },
+    /// The fee pool was split among validators at an epoch boundary
+    FeePoolDistributed {
+        height: u64,
+        epoch: u64,
+        amount: u64,
+    },
+    HealthParamsChanged {
+        height: u64,
+        proposal_id: u64,
+    },
+    ConcentrationLimitsChanged {
+        height: u64,
+        proposal_id: u64,
+        max_custodian_bps: u64,
+        max_asset_bps: u64,
+    },
+    /// A validator missed too many blocks in its window and was jailed
+    ValidatorJailed {
+        height: u64,
+        validator: [u8; 32],
+        missed: u64,
+        slashed: u64,
+        jailed_until: u64,
+    },
+    ValidatorUnjailed {
+        height: u64,
+        validator: [u8; 32],
+    },
+    /// An undercollateralized position's collateral was put up for auction
+    AuctionStarted {
+        height: u64,
+        auction_id: u64,
+        position_id: u64,
+        owner: [u8; 32],
+        collateral: u64,
+        debt: u64,
+        start_price: u64,
+    },
+    AuctionBid {
+        height: u64,
+        auction_id: u64,
+        bidder: [u8; 32],
+        collateral: u64,
+        price: u64,
+        /// LUSD paid; all but `burned` went to the position's owner
+        paid: u64,
+        burned: u64,
+    },
+    /// An auction closed: unsold collateral went back to the owner, or, if
+    /// it expired with debt outstanding, the position was reopened
+    AuctionSettled {
+        height: u64,
+        auction_id: u64,
+        owner: [u8; 32],
+        collateral_returned: u64,
+        position_reopened: bool,
+        /// Debt left unpaid after all collateral was sold
+        bad_debt: u64,
+    },
+    /// A maintenance call's caller was paid from the insurance fund
+    KeeperRewarded {
+        height: u64,
+        keeper: [u8; 32],
+        task: KeeperTask,
+        amount: u64,
+    },
+    /// A passed governance proposal trusted or distrusted a credit oracle
+    CreditOracleChanged {
+        height: u64,
+        proposal_id: u64,
+        oracle: [u8; 32],
+        member: bool,
+    },
+    CreditLineOpened {
+        height: u64,
+        line_id: u64,
+        borrower: [u8; 32],
+        limit: u64,
+    },
+    /// A matured line's unpaid principal was covered from its guarantors'
+    /// slashed LJUN; `shortfall` is what their guarantee could not cover
+    CreditLineDefaulted {
+        height: u64,
+        line_id: u64,
+        borrower: [u8; 32],
+        slashed: u64,
+        shortfall: u64,
+    },
+    /// One recipient's payment from a `BatchDisburse`
+    Disbursed {
+        height: u64,
+        from: [u8; 32],
+        to: [u8; 32],
+        amount: u64,
+    },
+    MerchantRegistered {
+        height: u64,
+        merchant: [u8; 32],
+        treasury: [u8; 32],
+    },
+    /// A merchant's netted payments were paid to its treasury, at the end
+    /// of `epoch` or on deregistration
+    MerchantSettled {
+        height: u64,
+        epoch: u64,
+        merchant: [u8; 32],
+        treasury: [u8; 32],
+        amount: u64,
+        payments: u64,
+    },
+    /// A `TransferWithMemo` settled the payment request with `memo_hash`
+    MemoPayment {
+        height: u64,
+        from: [u8; 32],
+        to: [u8; 32],
+        amount: u64,
+        asset: AssetType,
+        memo_hash: [u8; 32],
+    },
+    DustPolicyChanged {
+        height: u64,
+        proposal_id: u64,
+        existential_deposit: u64,
+        grace_blocks: u64,
+    },
+    /// A dust account was removed and its LUSD swept to the insurance fund
+    AccountReaped {
+        height: u64,
+        account: [u8; 32],
+        swept: u64,
+    },
+    ArchivePolicyChanged {
+        height: u64,
+        proposal_id: u64,
+        inactivity_epochs: u64,
+    },
+    /// Accounts moved out of live state, with the leaves of their batch's
+    /// `ArchiveTree` so revival proofs can be built from the event log
+    AccountsArchived {
+        height: u64,
+        batch_id: u64,
+        root: [u8; 32],
+        accounts: Vec<ArchivedLeaf>,
+    },
+    AccountRevived {
+        height: u64,
+        account: [u8; 32],
+        batch_id: u64,
+    },
+    /// The beacon for `epoch` was produced; `withheld` committed but never
+    /// revealed
+    RandomnessFinalized {
+        height: u64,
+        epoch: u64,
+        output: [u8; 32],
+        reveals: u64,
+        withheld: Vec<[u8; 32]>,
+    },
+    StabilizerBoundsChanged {
+        height: u64,
+        proposal_id: u64,
+    },
+    /// The parameter controller moved `param` in response to the health
+    /// index and oracle regime
+    ParameterAdjusted {
+        height: u64,
+        param: ControlledParam,
+        from_bps: u64,
+        to_bps: u64,
+        health_index: u64,
+        regime: OracleRegime,
+    },
+    /// LUSD fee charged to `account` by a mint, redemption or flash loan
+    /// repayment
+    FeePaid {
+        height: u64,
+        account: [u8; 32],
+        amount: u64,
+    },
+    /// Yield credited on unwrapping a matured yield token, net of the
+    /// insurance cut
+    YieldPaid {
+        height: u64,
+        account: [u8; 32],
+        token_id: u64,
+        amount: u64,
+    },
+    /// `publisher` uploaded a compliance circuit version, activating at
+    /// `activation_height`
+    CircuitVersionUploaded {
+        height: u64,
+        circuit_id: u64,
+        version: u32,
+        publisher: [u8; 32],
+        activation_height: u64,
+    },
+    CircuitVersionActivated {
+        height: u64,
+        circuit_id: u64,
+        version: u32,
+    },
+    /// A superseded circuit version keeps verifying until `retire_height`
+    CircuitVersionDeprecated {
+        height: u64,
+        circuit_id: u64,
+        version: u32,
+        retire_height: u64,
+    },
+    /// Proofs against the version are rejected and its attestations dropped
+    CircuitVersionRetired {
+        height: u64,
+        circuit_id: u64,
+        version: u32,
+    },
+    /// A passed governance proposal set (`active: true`) or removed a
+    /// jurisdiction's policy
+    JurisdictionPolicyChanged {
+        height: u64,
+        proposal_id: u64,
+        jurisdiction_id: u32,
+        active: bool,
+    },
+    JurisdictionTagged {
+        height: u64,
+        account: [u8; 32],
+        jurisdiction_id: u32,
+    },
+    /// A passed governance proposal trusted or distrusted a credential
+    /// attester
+    CredentialAttesterChanged {
+        height: u64,
+        proposal_id: u64,
+        attester: [u8; 32],
+        member: bool,
+    },
+    CredentialRequirementsChanged {
+        height: u64,
+        proposal_id: u64,
+    },
+    CredentialAnchored {
+        height: u64,
+        institution: [u8; 32],
+        attester: [u8; 32],
+        kind: CredentialKind,
+        expires_height: u64,
+    },
+    CredentialRevoked {
+        height: u64,
+        institution: [u8; 32],
+        attester: [u8; 32],
+        kind: CredentialKind,
+    },
+    InstitutionStatusProven {
+        height: u64,
+        institution: [u8; 32],
+        kind: CredentialKind,
+    },
+    StreamWithdrawn {
+        height: u64,
+        stream_id: u64,
+        payer: [u8; 32],
+        recipient: [u8; 32],
+        asset: AssetType,
+        amount: u64,
+    },
+    /// `paid` went to the recipient and `refunded` back to the payer
+    StreamCancelled {
+        height: u64,
+        stream_id: u64,
+        payer: [u8; 32],
+        recipient: [u8; 32],
+        asset: AssetType,
+        paid: u64,
+        refunded: u64,
+    },
+    /// `amount` more was escrowed, extending the stream to `end_timestamp`
+    StreamToppedUp {
+        height: u64,
+        stream_id: u64,
+        payer: [u8; 32],
+        recipient: [u8; 32],
+        amount: u64,
+        end_timestamp: u64,
+    },
+    /// Payer offered a lower rate, awaiting the recipient's consent
+    StreamRateProposed {
+        height: u64,
+        stream_id: u64,
+        payer: [u8; 32],
+        recipient: [u8; 32],
+        amount_per_sec: u64,
+    },
+    StreamRateChanged {
+        height: u64,
+        stream_id: u64,
+        payer: [u8; 32],
+        recipient: [u8; 32],
+        old_rate: u64,
+        new_rate: u64,
+    },
+    StreamCreated {
+        height: u64,
+        stream_id: u64,
+        payer: [u8; 32],
+        recipient: [u8; 32],
+        asset: AssetType,
+        amount_per_sec: u64,
+        end_timestamp: u64,
+    },
+    HashLocked {
+        height: u64,
+        lock_id: u64,
+        sender: [u8; 32],
+        recipient: [u8; 32],
+        asset: AssetType,
+        amount: u64,
+        hash_lock: [u8; 32],
+        timeout_height: u64,
+    },
+    /// Carries the preimage so the counterparty can claim on the other chain
+    HashLockClaimed {
+        height: u64,
+        lock_id: u64,
+        sender: [u8; 32],
+        recipient: [u8; 32],
+        preimage: [u8; 32],
+    },
+    HashLockExpired {
+        height: u64,
+        lock_id: u64,
+        sender: [u8; 32],
+        recipient: [u8; 32],
+    },
+    HashLockRefunded {
+        height: u64,
+        lock_id: u64,
+        sender: [u8; 32],
+        recipient: [u8; 32],
+    },
+    /// A passed governance proposal configured (`active: true`) or disabled
+    /// a chain's SPV bridge
+    SpvBridgeChanged {
+        height: u64,
+        proposal_id: u64,
+        chain: SpvChain,
+        active: bool,
+    },
+    SpvHeadersAccepted {
+        height: u64,
+        chain: SpvChain,
+        relayer: [u8; 32],
+        count: u64,
+        tip_height: u64,
+    },
+    SpvDepositClaimed {
+        height: u64,
+        deposit_id: u64,
+        chain: SpvChain,
+        claimant: [u8; 32],
+        recipient: [u8; 32],
+        symbol: String,
+        amount: u64,
+        finalize_height: u64,
+    },
+    SpvDepositChallenged {
+        height: u64,
+        deposit_id: u64,
+        challenger: [u8; 32],
+        recipient: [u8; 32],
+    },
+    /// A claim's challenge period ended; `minted` is false when its block
+    /// left the best chain or the bridge was reconfigured meanwhile
+    SpvDepositFinalized {
+        height: u64,
+        deposit_id: u64,
+        recipient: [u8; 32],
+        symbol: String,
+        amount: u64,
+        minted: bool,
+    },
+    /// A passed governance proposal allowed (`active: true`) or stopped
+    /// LUSD sends to a remote chain
+    RemoteChainChanged {
+        height: u64,
+        proposal_id: u64,
+        chain_id: u64,
+        active: bool,
+    },
+    LusdSentCrossChain {
+        height: u64,
+        nonce: u64,
+        dest_chain: u64,
+        sender: [u8; 32],
+        amount: u64,
+    },
+    MessageBatchSealed {
+        height: u64,
+        batch_id: u64,
+        messages: u64,
+    },
+    /// A batch's signers first held over two thirds of validator power;
+    /// relayers can carry it to the remote chains
+    MessageBatchAttested {
+        height: u64,
+        batch_id: u64,
+    },
+    LusdReturnedCrossChain {
+        height: u64,
+        source_chain: u64,
+        burn_id: [u8; 32],
+        recipient: [u8; 32],
+        amount: u64,
+    },
+    MintCapsChanged {
+        height: u64,
+        proposal_id: u64,
+        per_block: u64,
+        per_account: u64,
+        breaker_blocks: u64,
+    },
+    /// `blocks` blocks in a row reached a mint cap and the circuit breaker
+    /// tripped
+    MintBreakerTripped {
+        height: u64,
+        blocks: u64,
+    },
+    /// Governance replaced the redemption delay tiers
+    RedemptionTiersChanged {
+        height: u64,
+        proposal_id: u64,
+        tiers: Vec<RedemptionTier>,
+    },
+    /// A redemption reached a delay tier; its LUSD is escrowed until
+    /// `release_height`
+    RedemptionDelayed {
+        height: u64,
+        redemption_id: u64,
+        owner: [u8; 32],
+        amount: u64,
+        release_height: u64,
+    },
+    /// `by`, the owner or a validator, cancelled a delayed redemption and
+    /// its LUSD went back to the owner
+    DelayedRedemptionCancelled {
+        height: u64,
+        redemption_id: u64,
+        owner: [u8; 32],
+        by: [u8; 32],
+        amount: u64,
+    },
+    /// A delayed redemption's delay ended; it settled, or joined the fair
+    /// redeem queue (`queued`) because the system was under stress
+    DelayedRedemptionReleased {
+        height: u64,
+        redemption_id: u64,
+        owner: [u8; 32],
+        amount: u64,
+        queued: bool,
+    },
+    /// Governance appointed the emergency council
+    EmergencyCouncilChanged {
+        height: u64,
+        proposal_id: u64,
+        members: Vec<[u8; 32]>,
+        threshold: u32,
+        sunset_blocks: u64,
+    },
+    /// A council member proposed using a council power
+    CouncilActionProposed {
+        height: u64,
+        action_id: u64,
+        member: [u8; 32],
+        power: CouncilPower,
+    },
+    /// A council member approved a council action
+    CouncilActionApproved {
+        height: u64,
+        action_id: u64,
+        member: [u8; 32],
+    },
+    /// A council action reached its threshold and took effect until
+    /// `expires_height`
+    CouncilActionActivated {
+        height: u64,
+        action_id: u64,
+        expires_height: u64,
+    },
+    /// A council action reached its expiry unratified; `was_active` if it
+    /// had taken effect
+    CouncilActionLapsed {
+        height: u64,
+        action_id: u64,
+        was_active: bool,
+    },
+    /// Governance ratified a council action or lifted it
+    CouncilActionRuled {
+        height: u64,
+        proposal_id: u64,
+        action_id: u64,
+        ratified: bool,
+    },
+    /// Governance paused or resumed instructions by kind
+    InstructionPauseChanged {
+        height: u64,
+        proposal_id: u64,
+        kinds: Vec<String>,
+        paused: bool,
+    },
+    /// Governance set a protocol parameter
+    ProtocolParamChanged {
+        height: u64,
+        proposal_id: u64,
+        param: ProtocolParam,
+    },
+}
+
+impl ChainEvent {
+    /// Stable event type name used by the event log and its bloom filter.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ChainEvent::OracleRegimeChanged { .. } => "OracleRegimeChanged",
+            ChainEvent::FlashLoanDefaulted { .. } => "FlashLoanDefaulted",
+            ChainEvent::FlashMintClawedBack { .. } => "FlashMintClawedBack",
+            ChainEvent::ReserveMovementRequired { .. } => "ReserveMovementRequired",
+            ChainEvent::ReserveRotationFinalized { .. } => "ReserveRotationFinalized",
+            ChainEvent::UpgradeScheduled { .. } => "UpgradeScheduled",
+            ChainEvent::ContractDeployed { .. } => "ContractDeployed",
+            ChainEvent::ContractCalled { .. } => "ContractCalled",
+            ChainEvent::HookRegistered { .. } => "HookRegistered",
+            ChainEvent::HookRemoved { .. } => "HookRemoved",
+            ChainEvent::ScreeningOracleChanged { .. } => "ScreeningOracleChanged",
+            ChainEvent::ScreeningRootPublished { .. } => "ScreeningRootPublished",
+            ChainEvent::ComplianceOfficerChanged { .. } => "ComplianceOfficerChanged",
+            ChainEvent::KycTiersUpdated { .. } => "KycTiersUpdated",
+            ChainEvent::KycTierAssigned { .. } => "KycTierAssigned",
+            ChainEvent::InheritanceClaimStarted { .. } => "InheritanceClaimStarted",
+            ChainEvent::InheritanceClaimCancelled { .. } => "InheritanceClaimCancelled",
+            ChainEvent::InheritanceSettled { .. } => "InheritanceSettled",
+            ChainEvent::VestingCreated { .. } => "VestingCreated",
+            ChainEvent::TreasurySpendExecuted { .. } => "TreasurySpendExecuted",
+            ChainEvent::TreasuryFeeShareChanged { .. } => "TreasuryFeeShareChanged",
+            ChainEvent::ValidatorFeeShareChanged { .. } => "ValidatorFeeShareChanged",
+            ChainEvent::FeePoolDistributed { .. } => "FeePoolDistributed",
+            ChainEvent::HealthParamsChanged { .. } => "HealthParamsChanged",
+            ChainEvent::ConcentrationLimitsChanged { .. } => "ConcentrationLimitsChanged",
+            ChainEvent::ValidatorJailed { .. } => "ValidatorJailed",
+            ChainEvent::ValidatorUnjailed { .. } => "ValidatorUnjailed",
+            ChainEvent::AuctionStarted { .. } => "AuctionStarted",
+            ChainEvent::AuctionBid { .. } => "AuctionBid",
+            ChainEvent::AuctionSettled { .. } => "AuctionSettled",
+            ChainEvent::KeeperRewarded { .. } => "KeeperRewarded",
+            ChainEvent::CreditOracleChanged { .. } => "CreditOracleChanged",
+            ChainEvent::CreditLineOpened { .. } => "CreditLineOpened",
+            ChainEvent::CreditLineDefaulted { .. } => "CreditLineDefaulted",
+            ChainEvent::Disbursed { .. } => "Disbursed",
+            ChainEvent::MerchantRegistered { .. } => "MerchantRegistered",
+            ChainEvent::MerchantSettled { .. } => "MerchantSettled",
+            ChainEvent::MemoPayment { .. } => "MemoPayment",
+            ChainEvent::DustPolicyChanged { .. } => "DustPolicyChanged",
+            ChainEvent::AccountReaped { .. } => "AccountReaped",
+            ChainEvent::ArchivePolicyChanged { .. } => "ArchivePolicyChanged",
+            ChainEvent::AccountsArchived { .. } => "AccountsArchived",
+            ChainEvent::AccountRevived { .. } => "AccountRevived",
+            ChainEvent::RandomnessFinalized { .. } => "RandomnessFinalized",
+            ChainEvent::StabilizerBoundsChanged { .. } => "StabilizerBoundsChanged",
+            ChainEvent::ParameterAdjusted { .. } => "ParameterAdjusted",
+            ChainEvent::FeePaid { .. } => "FeePaid",
+            ChainEvent::YieldPaid { .. } => "YieldPaid",
+            ChainEvent::CircuitVersionUploaded { .. } => "CircuitVersionUploaded",
+            ChainEvent::CircuitVersionActivated { .. } => "CircuitVersionActivated",
+            ChainEvent::CircuitVersionDeprecated { .. } => "CircuitVersionDeprecated",
+            ChainEvent::CircuitVersionRetired { .. } => "CircuitVersionRetired",
+            ChainEvent::JurisdictionPolicyChanged { .. } => "JurisdictionPolicyChanged",
+            ChainEvent::JurisdictionTagged { .. } => "JurisdictionTagged",
+            ChainEvent::CredentialAttesterChanged { .. } => "CredentialAttesterChanged",
+            ChainEvent::CredentialRequirementsChanged { .. } => "CredentialRequirementsChanged",
+            ChainEvent::CredentialAnchored { .. } => "CredentialAnchored",
+            ChainEvent::CredentialRevoked { .. } => "CredentialRevoked",
+            ChainEvent::InstitutionStatusProven { .. } => "InstitutionStatusProven",
+            ChainEvent::StreamWithdrawn { .. } => "StreamWithdrawn",
+            ChainEvent::StreamCancelled { .. } => "StreamCancelled",
+            ChainEvent::StreamToppedUp { .. } => "StreamToppedUp",
+            ChainEvent::StreamRateProposed { .. } => "StreamRateProposed",
+            ChainEvent::StreamRateChanged { .. } => "StreamRateChanged",
+            ChainEvent::StreamCreated { .. } => "StreamCreated",
+            ChainEvent::HashLocked { .. } => "HashLocked",
+            ChainEvent::HashLockClaimed { .. } => "HashLockClaimed",
+            ChainEvent::HashLockExpired { .. } => "HashLockExpired",
+            ChainEvent::HashLockRefunded { .. } => "HashLockRefunded",
+            ChainEvent::SpvBridgeChanged { .. } => "SpvBridgeChanged",
+            ChainEvent::SpvHeadersAccepted { .. } => "SpvHeadersAccepted",
+            ChainEvent::SpvDepositClaimed { .. } => "SpvDepositClaimed",
+            ChainEvent::SpvDepositChallenged { .. } => "SpvDepositChallenged",
+            ChainEvent::SpvDepositFinalized { .. } => "SpvDepositFinalized",
+            ChainEvent::RemoteChainChanged { .. } => "RemoteChainChanged",
+            ChainEvent::LusdSentCrossChain { .. } => "LusdSentCrossChain",
+            ChainEvent::MessageBatchSealed { .. } => "MessageBatchSealed",
+            ChainEvent::MessageBatchAttested { .. } => "MessageBatchAttested",
+            ChainEvent::LusdReturnedCrossChain { .. } => "LusdReturnedCrossChain",
+            ChainEvent::MintCapsChanged { .. } => "MintCapsChanged",
+            ChainEvent::MintBreakerTripped { .. } => "MintBreakerTripped",
+            ChainEvent::RedemptionTiersChanged { .. } => "RedemptionTiersChanged",
+            ChainEvent::RedemptionDelayed { .. } => "RedemptionDelayed",
+            ChainEvent::DelayedRedemptionCancelled { .. } => "DelayedRedemptionCancelled",
+            ChainEvent::DelayedRedemptionReleased { .. } => "DelayedRedemptionReleased",
+            ChainEvent::EmergencyCouncilChanged { .. } => "EmergencyCouncilChanged",
+            ChainEvent::CouncilActionProposed { .. } => "CouncilActionProposed",
+            ChainEvent::CouncilActionApproved { .. } => "CouncilActionApproved",
+            ChainEvent::CouncilActionActivated { .. } => "CouncilActionActivated",
+            ChainEvent::CouncilActionLapsed { .. } => "CouncilActionLapsed",
+            ChainEvent::CouncilActionRuled { .. } => "CouncilActionRuled",
+            ChainEvent::InstructionPauseChanged { .. } => "InstructionPauseChanged",
+            ChainEvent::ProtocolParamChanged { .. } => "ProtocolParamChanged",
+        }
+    }
+
+    /// Accounts an event concerns, for address-indexed lookups.
+    pub fn addresses(&self) -> Vec<[u8; 32]> {
+        match self {
+            ChainEvent::OracleRegimeChanged { .. }
+            | ChainEvent::ReserveMovementRequired { .. }
+            | ChainEvent::UpgradeScheduled { .. }
+            | ChainEvent::HookRegistered { .. }
+            | ChainEvent::HookRemoved { .. }
+            | ChainEvent::KycTiersUpdated { .. }
+            | ChainEvent::TreasuryFeeShareChanged { .. }
+            | ChainEvent::ValidatorFeeShareChanged { .. }
+            | ChainEvent::FeePoolDistributed { .. }
+            | ChainEvent::HealthParamsChanged { .. }
+            | ChainEvent::ConcentrationLimitsChanged { .. }
+            | ChainEvent::DustPolicyChanged { .. }
+            | ChainEvent::ArchivePolicyChanged { .. }
+            | ChainEvent::StabilizerBoundsChanged { .. }
+            | ChainEvent::ParameterAdjusted { .. }
+            | ChainEvent::CircuitVersionActivated { .. }
+            | ChainEvent::CircuitVersionDeprecated { .. }
+            | ChainEvent::CircuitVersionRetired { .. }
+            | ChainEvent::JurisdictionPolicyChanged { .. }
+            | ChainEvent::CredentialRequirementsChanged { .. }
+            | ChainEvent::SpvBridgeChanged { .. }
+            | ChainEvent::RemoteChainChanged { .. }
+            | ChainEvent::MessageBatchSealed { .. }
+            | ChainEvent::MessageBatchAttested { .. }
+            | ChainEvent::MintCapsChanged { .. }
+            | ChainEvent::MintBreakerTripped { .. }
+            | ChainEvent::RedemptionTiersChanged { .. }
+            | ChainEvent::CouncilActionActivated { .. }
+            | ChainEvent::CouncilActionLapsed { .. }
+            | ChainEvent::CouncilActionRuled { .. }
+            | ChainEvent::InstructionPauseChanged { .. }
+            | ChainEvent::ProtocolParamChanged { .. } => Vec::new(),
+            ChainEvent::FlashLoanDefaulted { borrower, .. } => vec![*borrower],
+            ChainEvent::ValidatorJailed { validator, .. }
+            | ChainEvent::ValidatorUnjailed { validator, .. } => vec![*validator],
+            ChainEvent::AuctionStarted { owner, .. } | ChainEvent::AuctionSettled { owner, .. } => {
+                vec![*owner]
+            }
+            ChainEvent::AuctionBid { bidder, .. } => vec![*bidder],
+            ChainEvent::KeeperRewarded { keeper, .. } => vec![*keeper],
+            ChainEvent::FlashMintClawedBack { account, .. } => vec![*account],
+            ChainEvent::RedemptionDelayed { owner, .. }
+            | ChainEvent::DelayedRedemptionReleased { owner, .. } => vec![*owner],
+            ChainEvent::DelayedRedemptionCancelled { owner, by, .. } => vec![*owner, *by],
+            ChainEvent::EmergencyCouncilChanged { members, .. } => members.clone(),
+            ChainEvent::CouncilActionProposed { member, .. }
+            | ChainEvent::CouncilActionApproved { member, .. } => vec![*member],
+            ChainEvent::ReserveRotationFinalized {
+                from_custodian,
+                to_custodian,
+                ..
+            } => vec![*from_custodian, *to_custodian],
+            ChainEvent::ContractDeployed {
+                contract, deployer, ..
+            } => vec![*contract, *deployer],
+            ChainEvent::ContractCalled {
+                contract, caller, ..
+            } => vec![*contract, *caller],
+            ChainEvent::ScreeningOracleChanged { oracle, .. }
+            | ChainEvent::ScreeningRootPublished { oracle, .. } => vec![*oracle],
+            ChainEvent::ComplianceOfficerChanged { officer, .. } => vec![*officer],
+            ChainEvent::CreditOracleChanged { oracle, .. } => vec![*oracle],
+            ChainEvent::AccountReaped { account, .. }
+            | ChainEvent::AccountRevived { account, .. }
+            | ChainEvent::FeePaid { account, .. }
+            | ChainEvent::YieldPaid { account, .. } => vec![*account],
+            ChainEvent::AccountsArchived { accounts, .. } => {
+                accounts.iter().map(|(address, _)| *address).collect()
+            }
+            ChainEvent::RandomnessFinalized { withheld, .. } => withheld.clone(),
+            ChainEvent::CreditLineOpened { borrower, .. }
+            | ChainEvent::CreditLineDefaulted { borrower, .. } => vec![*borrower],
+            ChainEvent::Disbursed { from, to, .. } | ChainEvent::MemoPayment { from, to, .. } => {
+                vec![*from, *to]
+            }
+            ChainEvent::MerchantRegistered {
+                merchant, treasury, ..
+            }
+            | ChainEvent::MerchantSettled {
+                merchant, treasury, ..
+            } => vec![*merchant, *treasury],
+            ChainEvent::KycTierAssigned {
+                account, officer, ..
+            } => vec![*account, *officer],
+            ChainEvent::InheritanceClaimStarted {
+                owner, beneficiary, ..
+            }
+            | ChainEvent::InheritanceClaimCancelled {
+                owner, beneficiary, ..
+            }
+            | ChainEvent::InheritanceSettled {
+                owner, beneficiary, ..
+            } => vec![*owner, *beneficiary],
+            ChainEvent::VestingCreated {
+                funder,
+                beneficiary,
+                ..
+            } => vec![*funder, *beneficiary],
+            ChainEvent::TreasurySpendExecuted { recipient, .. } => vec![*recipient],
+            ChainEvent::CircuitVersionUploaded { publisher, .. } => vec![*publisher],
+            ChainEvent::JurisdictionTagged { account, .. } => vec![*account],
+            ChainEvent::CredentialAttesterChanged { attester, .. } => vec![*attester],
+            ChainEvent::CredentialAnchored {
+                institution,
+                attester,
+                ..
+            }
+            | ChainEvent::CredentialRevoked {
+                institution,
+                attester,
+                ..
+            } => vec![*institution, *attester],
+            ChainEvent::InstitutionStatusProven { institution, .. } => vec![*institution],
+            ChainEvent::StreamWithdrawn {
+                payer, recipient, ..
+            }
+            | ChainEvent::StreamCancelled {
+                payer, recipient, ..
+            }
+            | ChainEvent::StreamToppedUp {
+                payer, recipient, ..
+            }
+            | ChainEvent::StreamRateProposed {
+                payer, recipient, ..
+            }
+            | ChainEvent::StreamRateChanged {
+                payer, recipient, ..
+            }
+            | ChainEvent::StreamCreated {
+                payer, recipient, ..
+            } => vec![*payer, *recipient],
+            ChainEvent::HashLocked {
+                sender, recipient, ..
+            }
+            | ChainEvent::HashLockClaimed {
+                sender, recipient, ..
+            }
+            | ChainEvent::HashLockExpired {
+                sender, recipient, ..
+            }
+            | ChainEvent::HashLockRefunded {
+                sender, recipient, ..
+            } => vec![*sender, *recipient],
+            ChainEvent::SpvHeadersAccepted { relayer, .. } => vec![*relayer],
+            ChainEvent::SpvDepositClaimed {
+                claimant,
+                recipient,
+                ..
+            } => vec![*claimant, *recipient],
+            ChainEvent::SpvDepositChallenged {
+                challenger,
+                recipient,
+                ..
+            } => vec![*challenger, *recipient],
+            ChainEvent::SpvDepositFinalized { recipient, .. } => vec![*recipient],
+            ChainEvent::LusdSentCrossChain { sender, .. } => vec![*sender],
+            ChainEvent::LusdReturnedCrossChain { recipient, .. } => vec![*recipient],
+        }
+    }
+}
@@ -0,0 +1,54 @@
+/// Work the devnet faucet asks for: `blake3(challenge || address || nonce)`,
+/// with `nonce` little-endian, must start with `difficulty` zero bits.
+pub fn pow_hash(challenge: &[u8; 32], address: &[u8; 32], nonce: u64) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(challenge);
+    hasher.update(address);
+    hasher.update(&nonce.to_le_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+fn leading_zero_bits(hash: &[u8; 32]) -> u32 {
+    let mut bits = 0;
+    for byte in hash {
+        bits += byte.leading_zeros();
+        if *byte != 0 {
+            break;
+        }
+    }
+    bits
+}
+
+pub fn meets_difficulty(
+    challenge: &[u8; 32],
+    address: &[u8; 32],
+    nonce: u64,
+    difficulty: u32,
+) -> bool {
+    leading_zero_bits(&pow_hash(challenge, address, nonce)) >= difficulty
+}
+
+/// Search nonces from zero for one meeting `difficulty`; each extra bit
+/// doubles the expected work.
+pub fn solve(challenge: &[u8; 32], address: &[u8; 32], difficulty: u32) -> u64 {
+    (0..=u64::MAX)
+        .find(|nonce| meets_difficulty(challenge, address, *nonce, difficulty))
+        .expect("a nonce exists for any difficulty up to 256")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solutions_are_bound_to_challenge_and_address() {
+        let (challenge, address) = ([7u8; 32], [9u8; 32]);
+        let nonce = solve(&challenge, &address, 12);
+        assert!(meets_difficulty(&challenge, &address, nonce, 12));
+        assert!(leading_zero_bits(&pow_hash(&challenge, &address, nonce)) >= 12);
+        assert!(!meets_difficulty(&[8u8; 32], &address, nonce, 12));
+        assert!(!meets_difficulty(&challenge, &[1u8; 32], nonce, 12));
+        assert_eq!(leading_zero_bits(&[0u8; 32]), 256);
+        assert!(meets_difficulty(&challenge, &address, 0, 0));
+    }
+}
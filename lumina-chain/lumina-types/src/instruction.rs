@@ -1,7 +1,192 @@
+use crate::archive::ArchiveProof;
+use crate::screening::ScreeningProof;
+use crate::state::{
+    ConcentrationLimits, CouncilPower, CredentialKind, CredentialRequirements, DustPolicy,
+    EmergencyCouncil, ExecutionHook, HealthIndexParams, JurisdictionPolicy, KycTierLimits,
+    MintCaps, ProtocolParam, RedemptionTier, SpvBridge, SpvChain, StabilizerBounds, TreasurySpend,
+};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 pub type ZkProof = Vec<u8>;
 
+/// Most payments one `BatchDisburse` may carry (~200 KB encoded, well under
+/// the gossip message limit).
+pub const MAX_DISBURSE_PAYMENTS: usize = 5_000;
+
+/// Most streams one `CreateStreamBatch` may open.
+pub const MAX_BATCH_STREAMS: usize = 1_000;
+
+/// Most headers one `SubmitSpvHeaders` may carry.
+pub const MAX_SPV_HEADERS: usize = 500;
+
+/// Longest remote-chain recipient address `SendLusdCrossChain` accepts.
+pub const MAX_REMOTE_RECIPIENT_LEN: usize = 64;
+
+/// Most tiers `ProposeRedemptionTiers` may set.
+pub const MAX_REDEMPTION_TIERS: usize = 8;
+
+/// Largest emergency council `ProposeEmergencyCouncil` may appoint.
+pub const MAX_COUNCIL_MEMBERS: usize = 16;
+
+/// Most instruction kinds one pause may name.
+pub const MAX_PAUSED_KINDS: usize = 32;
+
+/// Most tiers `ProtocolParam::InsuranceTiers` may set.
+pub const MAX_INSURANCE_TIERS: usize = 8;
+
+/// Most zones `ProtocolParam::ZoneReserveWeights` may weight.
+pub const MAX_RESERVE_ZONES: usize = 64;
+
+/// `StablecoinInstruction::kind` names in declaration order, so a name's
+/// position is its variant's wire tag and its bit in an
+/// `InstructionBitmap`. New variants are appended here as in the enum.
+pub const INSTRUCTION_KINDS: &[&str] = &[
+    "RegisterAsset",
+    "MintSenior",
+    "RedeemSenior",
+    "MintJunior",
+    "RedeemJunior",
+    "Burn",
+    "Transfer",
+    "RebalanceTranches",
+    "DistributeYield",
+    "TriggerStabilizer",
+    "RunCircuitBreaker",
+    "FairRedeemQueue",
+    "ConfidentialTransfer",
+    "ProveCompliance",
+    "ZkTaxAttest",
+    "MultiJurisdictionalCheck",
+    "UpdateOracle",
+    "SubmitZkPoR",
+    "InstantFiatBridge",
+    "ZeroSlipBatchMatch",
+    "DynamicHedge",
+    "GeoRebalance",
+    "VelocityIncentive",
+    "StreamPayment",
+    "RegisterValidator",
+    "Vote",
+    "CreatePasskeyAccount",
+    "RecoverSocial",
+    "ClaimVelocityReward",
+    "RegisterCustodian",
+    "RotateReserves",
+    "ClaimInsurance",
+    "SwitchToPQSignature",
+    "RegisterGreenValidator",
+    "SubmitGreenProof",
+    "UploadComplianceCircuit",
+    "FlashMint",
+    "FlashBurn",
+    "InstantRedeem",
+    "MintWithCreditScore",
+    "WrapToYieldToken",
+    "UnwrapYieldToken",
+    "ListRWA",
+    "UseRWAAsCollateral",
+    "ComputeHealthIndex",
+    "DeployContract",
+    "CallContract",
+    "ProposeHook",
+    "ProposeHookRemoval",
+    "AttestCompliance",
+    "ListConfidentialRWA",
+    "RegisterViewKey",
+    "SealedConfidentialTransfer",
+    "ProposeScreeningOracle",
+    "PublishScreeningRoot",
+    "TransferWithComplianceProof",
+    "ProposeComplianceOfficer",
+    "ProposeKycTiers",
+    "SetKycTier",
+    "DesignateBeneficiary",
+    "RevokeBeneficiary",
+    "ClaimInheritance",
+    "CreateVestingAccount",
+    "ProposeTreasurySpend",
+    "ProposeTreasuryFeeShare",
+    "ProposeValidatorFeeShare",
+    "SetCommission",
+    "ClaimValidatorRewards",
+    "Unjail",
+    "ProposeHealthParams",
+    "ProposeConcentrationLimits",
+    "StartAuction",
+    "Bid",
+    "SettleAuction",
+    "ProposeCreditOracle",
+    "MintWithNativeCreditScore",
+    "OpenCreditLine",
+    "GuaranteeCreditLine",
+    "ReleaseGuarantee",
+    "DrawCreditLine",
+    "RepayCreditLine",
+    "DeclareCreditDefault",
+    "BatchDisburse",
+    "RegisterMerchant",
+    "DeregisterMerchant",
+    "ProposeDustPolicy",
+    "ProposeArchivePolicy",
+    "ArchiveAccounts",
+    "ReviveAccount",
+    "CommitRandomness",
+    "RevealRandomness",
+    "ProposeStabilizerBounds",
+    "ProposeCredentialAttester",
+    "AnchorCredential",
+    "RevokeCredential",
+    "ProveInstitutionStatus",
+    "ProposeCredentialRequirements",
+    "WithdrawStream",
+    "CancelStream",
+    "TopUpStream",
+    "ModifyStreamRate",
+    "CreateStreamBatch",
+    "LockWithHash",
+    "ClaimWithPreimage",
+    "RefundAfterTimeout",
+    "ProposeSpvBridge",
+    "SubmitSpvHeaders",
+    "ClaimSpvDeposit",
+    "ChallengeSpvDeposit",
+    "ProposeRemoteChain",
+    "SendLusdCrossChain",
+    "AttestMessageBatch",
+    "AttestLusdReturn",
+    "ProposeMintCaps",
+    "ProposeRedemptionTiers",
+    "CancelDelayedRedemption",
+    "ProposeEmergencyCouncil",
+    "CouncilPropose",
+    "CouncilApprove",
+    "ProposeCouncilRuling",
+    "ProposeInstructionPause",
+    "OptInInsurance",
+    "ProposeProtocolParam",
+    "RedeemPosition",
+    "LiquidatePosition",
+    "FlashLoan",
+    "FlashRepay",
+    "SetCustodianZone",
+    "ProposeReserveRotation",
+    "ApproveReserveRotation",
+    "DeregisterValidator",
+    "RenewGreenProof",
+    "ProposeUpgrade",
+    "ProposeJurisdictionPolicy",
+    "UploadComplianceCircuitV2",
+    "MintSeniorWithCollateral",
+    "StreamAsset",
+    "TransferWithMemo",
+];
+
+/// Position of instruction kind `kind` in `INSTRUCTION_KINDS`.
+pub fn kind_index(kind: &str) -> Option<usize> {
+    INSTRUCTION_KINDS.iter().position(|k| *k == kind)
+}
+
 /// All 40+ native StablecoinInstructions for LuminaChain.
 /// Each variant is a first-class on-chain operation with zero VM overhead.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -68,6 +253,8 @@ pub enum StablecoinInstruction {
         period: u64,
         proof: Vec<u8>,
     },
+    /// Prove the sender belongs to `jurisdiction_id`, tagging the account
+    /// with it so the jurisdiction's policy applies
     MultiJurisdictionalCheck {
         jurisdiction_id: u32,
         proof: Vec<u8>,
@@ -108,6 +295,8 @@ pub enum StablecoinInstruction {
     VelocityIncentive {
         multiplier_bps: u64,
     },
+    /// Escrow `amount_per_sec * duration` LUSD and release it to `to`
+    /// linearly over `duration` seconds
     StreamPayment {
         to: [u8; 32],
         amount_per_sec: u64,
@@ -165,6 +354,8 @@ pub enum StablecoinInstruction {
     SubmitGreenProof {
         energy_proof: Vec<u8>,
     },
+    /// Upload the next version of a compliance circuit, replacing the
+    /// active one at the end of the block
     UploadComplianceCircuit {
         circuit_id: u64,
         verifier_key: Vec<u8>,
@@ -212,6 +403,815 @@ pub enum StablecoinInstruction {
         amount_to_pledge: u64,
     },
     ComputeHealthIndex,
+
+    // ══════════════════════════════════════════════════════════════
+    // Smart Contracts (lumina-vm, opt-in)
+    // ══════════════════════════════════════════════════════════════
+    DeployContract {
+        code: Vec<u8>,
+    },
+    CallContract {
+        contract: [u8; 32],
+        input: Vec<u8>,
+        gas_limit: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Execution Hooks
+    // ══════════════════════════════════════════════════════════════
+    ProposeHook {
+        hook: ExecutionHook,
+    },
+    ProposeHookRemoval {
+        name: String,
+    },
+    AttestCompliance {
+        circuit_id: u64,
+        proof: Vec<u8>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Confidential RWA
+    // ══════════════════════════════════════════════════════════════
+    /// List an RWA whose valuation stays behind a Pedersen commitment.
+    /// Collateral capacity is the proven lower bound `min_value`.
+    ListConfidentialRWA {
+        asset_description: String,
+        value_commitment: [u8; 32],
+        min_value: u64,
+        range_proof: Vec<u8>,
+        attestation_proof: Vec<u8>,
+        /// Commitment opening encrypted under the owner's view key
+        sealed_opening: Vec<u8>,
+        maturity_date: Option<u64>,
+        collateral_eligibility: bool,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // View Keys
+    // ══════════════════════════════════════════════════════════════
+    /// Register or rotate the account's view public key
+    RegisterViewKey {
+        view_pubkey: [u8; 32],
+    },
+    /// `ConfidentialTransfer` with the commitment's opening sealed to the
+    /// sender's view key
+    SealedConfidentialTransfer {
+        commitment: [u8; 32],
+        proof: Vec<u8>,
+        sealed_note: Vec<u8>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Sanctions Screening
+    // ══════════════════════════════════════════════════════════════
+    ProposeScreeningOracle {
+        oracle: [u8; 32],
+        member: bool,
+    },
+    /// Publish the root of the sender oracle's current screened-address set
+    PublishScreeningRoot {
+        root: [u8; 32],
+    },
+    /// `Transfer` proving neither party is in any published screening set
+    TransferWithComplianceProof {
+        to: [u8; 32],
+        amount: u64,
+        asset: AssetType,
+        proofs: Vec<ScreeningProof>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // KYC Tiers
+    // ══════════════════════════════════════════════════════════════
+    ProposeComplianceOfficer {
+        officer: [u8; 32],
+        member: bool,
+    },
+    /// Propose replacing the KYC tier table; an empty table lifts all limits
+    ProposeKycTiers {
+        tiers: Vec<KycTierLimits>,
+    },
+    /// Compliance officer assigns `account` the tier its off-chain KYC earned
+    SetKycTier {
+        account: [u8; 32],
+        tier: u8,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Inheritance
+    // ══════════════════════════════════════════════════════════════
+    /// Let `beneficiary` claim the sender's balances after
+    /// `inactivity_blocks` without an outgoing transaction
+    DesignateBeneficiary {
+        beneficiary: [u8; 32],
+        inactivity_blocks: u64,
+    },
+    RevokeBeneficiary,
+    /// Beneficiary opens a claim on an inactive `owner`
+    ClaimInheritance {
+        owner: [u8; 32],
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Vesting
+    // ══════════════════════════════════════════════════════════════
+    /// Fund `total` LUMINA to `beneficiary`, unlocking linearly until
+    /// `end_height` with nothing unlocked before `cliff_height`
+    CreateVestingAccount {
+        beneficiary: [u8; 32],
+        total: u64,
+        cliff_height: u64,
+        end_height: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Treasury
+    // ══════════════════════════════════════════════════════════════
+    ProposeTreasurySpend {
+        spend: TreasurySpend,
+    },
+    /// Propose the share (bps) of each mint fee routed to the treasury
+    /// rather than the insurance fund
+    ProposeTreasuryFeeShare {
+        share_bps: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Validator Rewards
+    // ══════════════════════════════════════════════════════════════
+    /// Propose the share (bps) of each mint fee paid into the validator
+    /// fee pool
+    ProposeValidatorFeeShare {
+        share_bps: u64,
+    },
+    /// Set the sender validator's commission on its fee pool rewards
+    SetCommission {
+        commission_bps: u64,
+    },
+    ClaimValidatorRewards,
+
+    // ══════════════════════════════════════════════════════════════
+    // Validator Liveness
+    // ══════════════════════════════════════════════════════════════
+    /// Return the sender validator to the active set once its jail
+    /// cooldown has passed
+    Unjail,
+
+    // ══════════════════════════════════════════════════════════════
+    // Health Index
+    // ══════════════════════════════════════════════════════════════
+    /// Propose new health index weights and thresholds
+    ProposeHealthParams {
+        params: HealthIndexParams,
+    },
+    /// Propose the largest share of reserves one custodian, and of the
+    /// stabilization pool one asset, may hold
+    ProposeConcentrationLimits {
+        limits: ConcentrationLimits,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Collateral Auctions
+    // ══════════════════════════════════════════════════════════════
+    /// Seize an undercollateralized position and sell its collateral in a
+    /// descending-price auction
+    StartAuction {
+        position_id: u64,
+    },
+    /// Buy up to `max_collateral` units from an auction at its current
+    /// price, never more than repays its remaining debt
+    Bid {
+        auction_id: u64,
+        max_collateral: u64,
+    },
+    /// Close an auction whose debt is repaid, whose collateral is sold out,
+    /// or whose duration has passed
+    SettleAuction {
+        auction_id: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Credit Oracles
+    // ══════════════════════════════════════════════════════════════
+    /// Propose trusting (`member: true`) or distrusting a credit oracle for
+    /// `MintWithCreditScore`
+    ProposeCreditOracle {
+        oracle: [u8; 32],
+        member: bool,
+    },
+    /// `MintWithCreditScore` priced by the sender's on-chain credit history
+    /// instead of an oracle proof
+    MintWithNativeCreditScore {
+        amount: u64,
+        collateral_amount: u64,
+        min_score_threshold: u16,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Credit Lines
+    // ══════════════════════════════════════════════════════════════
+    /// Open a credit line for the sender, to be backed by guarantors
+    OpenCreditLine {
+        limit: u64,
+        /// Yearly interest on drawn principal, paid to the guarantors
+        interest_bps: u64,
+        maturity_height: u64,
+    },
+    /// Lock LJUN shares behind another account's credit line
+    GuaranteeCreditLine {
+        line_id: u64,
+        shares: u64,
+    },
+    /// Unlock guarantee shares the line's debt no longer needs
+    ReleaseGuarantee {
+        line_id: u64,
+        shares: u64,
+    },
+    /// Borrower mints LUSD against the line's guarantee
+    DrawCreditLine {
+        line_id: u64,
+        amount: u64,
+    },
+    /// Borrower pays accrued interest, then principal
+    RepayCreditLine {
+        line_id: u64,
+        amount: u64,
+    },
+    /// Slash the guarantors of a matured line that still owes principal
+    DeclareCreditDefault {
+        line_id: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Payroll
+    // ══════════════════════════════════════════════════════════════
+    /// Pay LUSD to many recipients under one signature, all or nothing
+    BatchDisburse {
+        payments: Vec<([u8; 32], u64)>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Merchant Settlement
+    // ══════════════════════════════════════════════════════════════
+    /// Register the sender as a merchant whose incoming LUSD nets into a
+    /// settlement account paid to `treasury` each epoch; re-registering
+    /// changes the treasury
+    RegisterMerchant {
+        treasury: [u8; 32],
+    },
+    /// Settle what is pending now and stop netting the sender's payments
+    DeregisterMerchant,
+
+    // ══════════════════════════════════════════════════════════════
+    // Dust Policy
+    // ══════════════════════════════════════════════════════════════
+    /// Propose the existential deposit and grace period for reaping dust
+    /// accounts
+    ProposeDustPolicy {
+        policy: DustPolicy,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // State Expiry
+    // ══════════════════════════════════════════════════════════════
+    /// Propose how many epochs an account must be inactive to be archived;
+    /// zero stops archiving
+    ProposeArchivePolicy {
+        inactivity_epochs: u64,
+    },
+    /// Move inactive accounts out of live state into one archive batch
+    ArchiveAccounts {
+        accounts: Vec<[u8; 32]>,
+    },
+    /// Restore an archived account from its `encode_account` bytes and a
+    /// proof against its batch root; anyone may submit it
+    ReviveAccount {
+        address: [u8; 32],
+        account: Vec<u8>,
+        proof: ArchiveProof,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Randomness Beacon
+    // ══════════════════════════════════════════════════════════════
+    /// Validator commits to `beacon::commitment(epoch, sender, secret)`
+    /// during the first part of `epoch`
+    CommitRandomness {
+        epoch: u64,
+        commitment: [u8; 32],
+    },
+    /// Validator reveals its committed secret during the reveal phase
+    /// closing `epoch`
+    RevealRandomness {
+        epoch: u64,
+        secret: [u8; 32],
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Parameter Controller
+    // ══════════════════════════════════════════════════════════════
+    /// Propose the bounds, step and cadence of the health-driven controller
+    /// of the mint fee, redemption fee and savings rate
+    ProposeStabilizerBounds {
+        bounds: StabilizerBounds,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Institution Credentials
+    // ══════════════════════════════════════════════════════════════
+    /// Propose trusting (`member: true`) or distrusting a credential attester
+    ProposeCredentialAttester {
+        attester: [u8; 32],
+        member: bool,
+    },
+    /// Attester anchors a commitment to `institution`'s credential of
+    /// `kind`, replacing any earlier one; `expires_height` 0 never lapses
+    AnchorCredential {
+        institution: [u8; 32],
+        kind: CredentialKind,
+        commitment: [u8; 32],
+        expires_height: u64,
+    },
+    /// Attester withdraws an anchored credential
+    RevokeCredential {
+        institution: [u8; 32],
+        kind: CredentialKind,
+    },
+    /// Institution proves it can open its anchored credential of `kind`
+    ProveInstitutionStatus {
+        kind: CredentialKind,
+        proof: Vec<u8>,
+    },
+    /// Propose which flows require proven credentials
+    ProposeCredentialRequirements {
+        requirements: CredentialRequirements,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Stream Management
+    // ══════════════════════════════════════════════════════════════
+    /// Recipient withdraws what `payer`'s stream has released so far
+    WithdrawStream {
+        payer: [u8; 32],
+        stream_id: u64,
+    },
+    /// Payer ends a stream, paying the recipient what it has released and
+    /// refunding the rest
+    CancelStream {
+        stream_id: u64,
+    },
+    /// Payer extends a stream by `duration` seconds at its current rate,
+    /// escrowing the extra amount
+    TopUpStream {
+        stream_id: u64,
+        duration: u64,
+    },
+    /// Change the rate of `payer`'s stream for the rest of its life. The
+    /// payer may raise it outright; a reduction the payer submits is only
+    /// applied once the recipient submits the same rate
+    ModifyStreamRate {
+        payer: [u8; 32],
+        stream_id: u64,
+        amount_per_sec: u64,
+    },
+    /// Open a stream of `asset` to each `(recipient, amount_per_sec,
+    /// duration)` under one signature, escrowing the total at once; all or
+    /// nothing
+    CreateStreamBatch {
+        asset: AssetType,
+        streams: Vec<([u8; 32], u64, u64)>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Conditional Transfers
+    // ══════════════════════════════════════════════════════════════
+    /// Escrow `amount` of `asset` for `recipient`, who can claim it with the
+    /// SHA-256 preimage of `hash_lock` through `timeout_height`
+    LockWithHash {
+        recipient: [u8; 32],
+        asset: AssetType,
+        amount: u64,
+        hash_lock: [u8; 32],
+        timeout_height: u64,
+    },
+    /// Pay a hash lock to its recipient by revealing its preimage
+    ClaimWithPreimage {
+        lock_id: u64,
+        preimage: [u8; 32],
+    },
+    /// Return a timed-out hash lock to the account that locked it
+    RefundAfterTimeout {
+        lock_id: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // SPV Bridge
+    // ══════════════════════════════════════════════════════════════
+    /// Validator proposes configuring (`Some`) or disabling (`None`) the SPV
+    /// bridge for `chain`; either restarts its light client
+    ProposeSpvBridge {
+        chain: SpvChain,
+        bridge: Option<SpvBridge>,
+    },
+    /// Extend `chain`'s light client with raw headers, parents first. Anyone
+    /// may relay proof-of-work Bitcoin headers; Ethereum headers carry no
+    /// proof of their own and only validators may relay them
+    SubmitSpvHeaders {
+        chain: SpvChain,
+        headers: Vec<Vec<u8>>,
+    },
+    /// Prove a deposit in a confirmed block of `chain`. It is minted to the
+    /// recipient the deposit names once its challenge period passes
+    ClaimSpvDeposit {
+        chain: SpvChain,
+        block_hash: [u8; 32],
+        proof: SpvDepositProof,
+    },
+    /// Validator voids a deposit claim still in its challenge period
+    ChallengeSpvDeposit {
+        deposit_id: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Cross-Chain Messages
+    // ══════════════════════════════════════════════════════════════
+    /// Validator proposes allowing (`active: true`) or stopping LUSD sends
+    /// to remote chain `chain_id`
+    ProposeRemoteChain {
+        chain_id: u64,
+        active: bool,
+    },
+    /// Burn `amount` LUSD to be minted to `recipient` on `dest_chain` once
+    /// validators sign the block's message batch
+    SendLusdCrossChain {
+        dest_chain: u64,
+        recipient: Vec<u8>,
+        amount: u64,
+    },
+    /// Validator signs a sealed message batch's `signing_bytes`
+    AttestMessageBatch {
+        batch_id: u64,
+        signature: Vec<u8>,
+    },
+    /// Validator attests that `amount` LUSD was burned on `source_chain` by
+    /// burn `burn_id` to return to `recipient`; minted on Lumina once the
+    /// attesters hold a quorum
+    AttestLusdReturn {
+        source_chain: u64,
+        burn_id: [u8; 32],
+        recipient: [u8; 32],
+        amount: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Mint Caps
+    // ══════════════════════════════════════════════════════════════
+    /// Propose per-block and per-account mint caps and how many capped
+    /// blocks in a row trip the circuit breaker
+    ProposeMintCaps {
+        caps: MintCaps,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Redemption Delays
+    // ══════════════════════════════════════════════════════════════
+    /// Propose the size tiers above which redemptions wait before settling;
+    /// an empty list removes every delay
+    ProposeRedemptionTiers {
+        tiers: Vec<RedemptionTier>,
+    },
+    /// Cancel a delayed redemption, returning its LUSD to the owner; open
+    /// to the owner and to validators reviewing it
+    CancelDelayedRedemption {
+        redemption_id: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Emergency Council
+    // ══════════════════════════════════════════════════════════════
+    /// Propose the emergency council's members, approval threshold and
+    /// sunset; no members dissolves it
+    ProposeEmergencyCouncil {
+        council: EmergencyCouncil,
+    },
+    /// A council member proposes using a power, approving it themselves
+    CouncilPropose {
+        power: CouncilPower,
+    },
+    /// A council member approves a proposed council action
+    CouncilApprove {
+        action_id: u64,
+    },
+    /// Propose that governance ratify an active council action, making it
+    /// permanent, or lift it
+    ProposeCouncilRuling {
+        action_id: u64,
+        ratify: bool,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Instruction Pauses
+    // ══════════════════════════════════════════════════════════════
+    /// Propose pausing (`paused: true`) or resuming instructions by
+    /// `kind` name, leaving every other instruction running
+    ProposeInstructionPause {
+        kinds: Vec<String>,
+        paused: bool,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Insurance Coverage
+    // ══════════════════════════════════════════════════════════════
+    OptInInsurance {
+        tier: u8,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Protocol Parameters
+    // ══════════════════════════════════════════════════════════════
+    /// Propose a new value for one protocol parameter
+    ProposeProtocolParam {
+        param: ProtocolParam,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Collateral Positions
+    // ══════════════════════════════════════════════════════════════
+    RedeemPosition {
+        position_id: u64,
+        amount: u64,
+    },
+    LiquidatePosition {
+        position_id: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Flash Loans
+    // ══════════════════════════════════════════════════════════════
+    FlashLoan {
+        amount: u64,
+    },
+    FlashRepay {
+        amount: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Custodian Zones
+    // ══════════════════════════════════════════════════════════════
+    /// Place the sender's custodied reserves in geographic zone `zone_id`
+    SetCustodianZone {
+        zone_id: u32,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Reserve Rotation
+    // ══════════════════════════════════════════════════════════════
+    /// Propose moving `amount` of reserves between two custodians, finalized
+    /// once a majority of each one's MPC set approves
+    ProposeReserveRotation {
+        from_custodian: [u8; 32],
+        to_custodian: [u8; 32],
+        amount: u64,
+    },
+    ApproveReserveRotation {
+        rotation_id: u64,
+        mpc_pubkey: [u8; 32],
+        signature: Vec<u8>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Validator Exit
+    // ══════════════════════════════════════════════════════════════
+    DeregisterValidator,
+
+    // ══════════════════════════════════════════════════════════════
+    // Green Validators
+    // ══════════════════════════════════════════════════════════════
+    RenewGreenProof {
+        energy_proof: Vec<u8>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Protocol Upgrades
+    // ══════════════════════════════════════════════════════════════
+    ProposeUpgrade {
+        name: String,
+        height: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Jurisdiction Policies
+    // ══════════════════════════════════════════════════════════════
+    /// Propose the policy for accounts tagged with `jurisdiction_id`;
+    /// `None` removes it
+    ProposeJurisdictionPolicy {
+        jurisdiction_id: u32,
+        policy: Option<JurisdictionPolicy>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Compliance Circuit Versions
+    // ══════════════════════════════════════════════════════════════
+    /// Upload `version` of a compliance circuit. Version 1 activates at
+    /// once; later versions activate at `activation_height`, after which
+    /// the previous version keeps verifying for `deprecation_blocks`.
+    UploadComplianceCircuitV2 {
+        circuit_id: u64,
+        verifier_key: Vec<u8>,
+        version: u32,
+        activation_height: u64,
+        deprecation_blocks: u64,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Multi-Collateral Mints
+    // ══════════════════════════════════════════════════════════════
+    /// `MintSenior` against any priced collateral asset; `MintSenior`
+    /// itself takes LUMINA.
+    MintSeniorWithCollateral {
+        amount: u64,
+        collateral_asset: CollateralAsset,
+        collateral_amount: u64,
+        proof: Vec<u8>,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Asset Streams
+    // ══════════════════════════════════════════════════════════════
+    /// `StreamPayment` in any streamable `asset`
+    StreamAsset {
+        to: [u8; 32],
+        amount_per_sec: u64,
+        duration: u64,
+        asset: AssetType,
+    },
+
+    // ══════════════════════════════════════════════════════════════
+    // Payment Requests
+    // ══════════════════════════════════════════════════════════════
+    /// `Transfer` tagged with the hash of a payment request's memo, so the
+    /// payee can match it to the request
+    TransferWithMemo {
+        to: [u8; 32],
+        amount: u64,
+        asset: AssetType,
+        memo_hash: [u8; 32],
+    },
+}
+
+impl StablecoinInstruction {
+    /// Stable variant name, used to select the execution hooks that apply.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            StablecoinInstruction::RegisterAsset { .. } => "RegisterAsset",
+            StablecoinInstruction::MintSenior { .. } => "MintSenior",
+            StablecoinInstruction::RedeemSenior { .. } => "RedeemSenior",
+            StablecoinInstruction::MintJunior { .. } => "MintJunior",
+            StablecoinInstruction::RedeemJunior { .. } => "RedeemJunior",
+            StablecoinInstruction::Burn { .. } => "Burn",
+            StablecoinInstruction::Transfer { .. } => "Transfer",
+            StablecoinInstruction::RebalanceTranches => "RebalanceTranches",
+            StablecoinInstruction::DistributeYield { .. } => "DistributeYield",
+            StablecoinInstruction::TriggerStabilizer => "TriggerStabilizer",
+            StablecoinInstruction::RunCircuitBreaker { .. } => "RunCircuitBreaker",
+            StablecoinInstruction::FairRedeemQueue { .. } => "FairRedeemQueue",
+            StablecoinInstruction::ConfidentialTransfer { .. } => "ConfidentialTransfer",
+            StablecoinInstruction::ProveCompliance { .. } => "ProveCompliance",
+            StablecoinInstruction::ZkTaxAttest { .. } => "ZkTaxAttest",
+            StablecoinInstruction::MultiJurisdictionalCheck { .. } => "MultiJurisdictionalCheck",
+            StablecoinInstruction::UpdateOracle { .. } => "UpdateOracle",
+            StablecoinInstruction::SubmitZkPoR { .. } => "SubmitZkPoR",
+            StablecoinInstruction::InstantFiatBridge { .. } => "InstantFiatBridge",
+            StablecoinInstruction::ZeroSlipBatchMatch { .. } => "ZeroSlipBatchMatch",
+            StablecoinInstruction::DynamicHedge { .. } => "DynamicHedge",
+            StablecoinInstruction::GeoRebalance { .. } => "GeoRebalance",
+            StablecoinInstruction::VelocityIncentive { .. } => "VelocityIncentive",
+            StablecoinInstruction::StreamPayment { .. } => "StreamPayment",
+            StablecoinInstruction::RegisterValidator { .. } => "RegisterValidator",
+            StablecoinInstruction::Vote { .. } => "Vote",
+            StablecoinInstruction::CreatePasskeyAccount { .. } => "CreatePasskeyAccount",
+            StablecoinInstruction::RecoverSocial { .. } => "RecoverSocial",
+            StablecoinInstruction::ClaimVelocityReward { .. } => "ClaimVelocityReward",
+            StablecoinInstruction::RegisterCustodian { .. } => "RegisterCustodian",
+            StablecoinInstruction::RotateReserves { .. } => "RotateReserves",
+            StablecoinInstruction::ClaimInsurance { .. } => "ClaimInsurance",
+            StablecoinInstruction::SwitchToPQSignature { .. } => "SwitchToPQSignature",
+            StablecoinInstruction::RegisterGreenValidator { .. } => "RegisterGreenValidator",
+            StablecoinInstruction::SubmitGreenProof { .. } => "SubmitGreenProof",
+            StablecoinInstruction::UploadComplianceCircuit { .. } => "UploadComplianceCircuit",
+            StablecoinInstruction::FlashMint { .. } => "FlashMint",
+            StablecoinInstruction::FlashBurn { .. } => "FlashBurn",
+            StablecoinInstruction::InstantRedeem { .. } => "InstantRedeem",
+            StablecoinInstruction::MintWithCreditScore { .. } => "MintWithCreditScore",
+            StablecoinInstruction::WrapToYieldToken { .. } => "WrapToYieldToken",
+            StablecoinInstruction::UnwrapYieldToken { .. } => "UnwrapYieldToken",
+            StablecoinInstruction::ListRWA { .. } => "ListRWA",
+            StablecoinInstruction::UseRWAAsCollateral { .. } => "UseRWAAsCollateral",
+            StablecoinInstruction::ComputeHealthIndex => "ComputeHealthIndex",
+            StablecoinInstruction::DeployContract { .. } => "DeployContract",
+            StablecoinInstruction::CallContract { .. } => "CallContract",
+            StablecoinInstruction::ProposeHook { .. } => "ProposeHook",
+            StablecoinInstruction::ProposeHookRemoval { .. } => "ProposeHookRemoval",
+            StablecoinInstruction::AttestCompliance { .. } => "AttestCompliance",
+            StablecoinInstruction::ListConfidentialRWA { .. } => "ListConfidentialRWA",
+            StablecoinInstruction::RegisterViewKey { .. } => "RegisterViewKey",
+            StablecoinInstruction::SealedConfidentialTransfer { .. } => {
+                "SealedConfidentialTransfer"
+            }
+            StablecoinInstruction::ProposeScreeningOracle { .. } => "ProposeScreeningOracle",
+            StablecoinInstruction::PublishScreeningRoot { .. } => "PublishScreeningRoot",
+            StablecoinInstruction::TransferWithComplianceProof { .. } => {
+                "TransferWithComplianceProof"
+            }
+            StablecoinInstruction::ProposeComplianceOfficer { .. } => "ProposeComplianceOfficer",
+            StablecoinInstruction::ProposeKycTiers { .. } => "ProposeKycTiers",
+            StablecoinInstruction::SetKycTier { .. } => "SetKycTier",
+            StablecoinInstruction::DesignateBeneficiary { .. } => "DesignateBeneficiary",
+            StablecoinInstruction::RevokeBeneficiary => "RevokeBeneficiary",
+            StablecoinInstruction::ClaimInheritance { .. } => "ClaimInheritance",
+            StablecoinInstruction::CreateVestingAccount { .. } => "CreateVestingAccount",
+            StablecoinInstruction::ProposeTreasurySpend { .. } => "ProposeTreasurySpend",
+            StablecoinInstruction::ProposeTreasuryFeeShare { .. } => "ProposeTreasuryFeeShare",
+            StablecoinInstruction::ProposeValidatorFeeShare { .. } => "ProposeValidatorFeeShare",
+            StablecoinInstruction::SetCommission { .. } => "SetCommission",
+            StablecoinInstruction::ClaimValidatorRewards => "ClaimValidatorRewards",
+            StablecoinInstruction::Unjail => "Unjail",
+            StablecoinInstruction::ProposeHealthParams { .. } => "ProposeHealthParams",
+            StablecoinInstruction::ProposeConcentrationLimits { .. } => {
+                "ProposeConcentrationLimits"
+            }
+            StablecoinInstruction::StartAuction { .. } => "StartAuction",
+            StablecoinInstruction::Bid { .. } => "Bid",
+            StablecoinInstruction::SettleAuction { .. } => "SettleAuction",
+            StablecoinInstruction::ProposeCreditOracle { .. } => "ProposeCreditOracle",
+            StablecoinInstruction::MintWithNativeCreditScore { .. } => "MintWithNativeCreditScore",
+            StablecoinInstruction::OpenCreditLine { .. } => "OpenCreditLine",
+            StablecoinInstruction::GuaranteeCreditLine { .. } => "GuaranteeCreditLine",
+            StablecoinInstruction::ReleaseGuarantee { .. } => "ReleaseGuarantee",
+            StablecoinInstruction::DrawCreditLine { .. } => "DrawCreditLine",
+            StablecoinInstruction::RepayCreditLine { .. } => "RepayCreditLine",
+            StablecoinInstruction::DeclareCreditDefault { .. } => "DeclareCreditDefault",
+            StablecoinInstruction::BatchDisburse { .. } => "BatchDisburse",
+            StablecoinInstruction::RegisterMerchant { .. } => "RegisterMerchant",
+            StablecoinInstruction::DeregisterMerchant => "DeregisterMerchant",
+            StablecoinInstruction::ProposeDustPolicy { .. } => "ProposeDustPolicy",
+            StablecoinInstruction::ProposeArchivePolicy { .. } => "ProposeArchivePolicy",
+            StablecoinInstruction::ArchiveAccounts { .. } => "ArchiveAccounts",
+            StablecoinInstruction::ReviveAccount { .. } => "ReviveAccount",
+            StablecoinInstruction::CommitRandomness { .. } => "CommitRandomness",
+            StablecoinInstruction::RevealRandomness { .. } => "RevealRandomness",
+            StablecoinInstruction::ProposeStabilizerBounds { .. } => "ProposeStabilizerBounds",
+            StablecoinInstruction::ProposeCredentialAttester { .. } => "ProposeCredentialAttester",
+            StablecoinInstruction::AnchorCredential { .. } => "AnchorCredential",
+            StablecoinInstruction::RevokeCredential { .. } => "RevokeCredential",
+            StablecoinInstruction::ProveInstitutionStatus { .. } => "ProveInstitutionStatus",
+            StablecoinInstruction::ProposeCredentialRequirements { .. } => {
+                "ProposeCredentialRequirements"
+            }
+            StablecoinInstruction::WithdrawStream { .. } => "WithdrawStream",
+            StablecoinInstruction::CancelStream { .. } => "CancelStream",
+            StablecoinInstruction::TopUpStream { .. } => "TopUpStream",
+            StablecoinInstruction::ModifyStreamRate { .. } => "ModifyStreamRate",
+            StablecoinInstruction::CreateStreamBatch { .. } => "CreateStreamBatch",
+            StablecoinInstruction::LockWithHash { .. } => "LockWithHash",
+            StablecoinInstruction::ClaimWithPreimage { .. } => "ClaimWithPreimage",
+            StablecoinInstruction::RefundAfterTimeout { .. } => "RefundAfterTimeout",
+            StablecoinInstruction::ProposeSpvBridge { .. } => "ProposeSpvBridge",
+            StablecoinInstruction::SubmitSpvHeaders { .. } => "SubmitSpvHeaders",
+            StablecoinInstruction::ClaimSpvDeposit { .. } => "ClaimSpvDeposit",
+            StablecoinInstruction::ChallengeSpvDeposit { .. } => "ChallengeSpvDeposit",
+            StablecoinInstruction::ProposeRemoteChain { .. } => "ProposeRemoteChain",
+            StablecoinInstruction::SendLusdCrossChain { .. } => "SendLusdCrossChain",
+            StablecoinInstruction::AttestMessageBatch { .. } => "AttestMessageBatch",
+            StablecoinInstruction::AttestLusdReturn { .. } => "AttestLusdReturn",
+            StablecoinInstruction::ProposeMintCaps { .. } => "ProposeMintCaps",
+            StablecoinInstruction::ProposeRedemptionTiers { .. } => "ProposeRedemptionTiers",
+            StablecoinInstruction::CancelDelayedRedemption { .. } => "CancelDelayedRedemption",
+            StablecoinInstruction::ProposeEmergencyCouncil { .. } => "ProposeEmergencyCouncil",
+            StablecoinInstruction::CouncilPropose { .. } => "CouncilPropose",
+            StablecoinInstruction::CouncilApprove { .. } => "CouncilApprove",
+            StablecoinInstruction::ProposeCouncilRuling { .. } => "ProposeCouncilRuling",
+            StablecoinInstruction::ProposeInstructionPause { .. } => "ProposeInstructionPause",
+            StablecoinInstruction::OptInInsurance { .. } => "OptInInsurance",
+            StablecoinInstruction::ProposeProtocolParam { .. } => "ProposeProtocolParam",
+            StablecoinInstruction::RedeemPosition { .. } => "RedeemPosition",
+            StablecoinInstruction::LiquidatePosition { .. } => "LiquidatePosition",
+            StablecoinInstruction::FlashLoan { .. } => "FlashLoan",
+            StablecoinInstruction::FlashRepay { .. } => "FlashRepay",
+            StablecoinInstruction::SetCustodianZone { .. } => "SetCustodianZone",
+            StablecoinInstruction::ProposeReserveRotation { .. } => "ProposeReserveRotation",
+            StablecoinInstruction::ApproveReserveRotation { .. } => "ApproveReserveRotation",
+            StablecoinInstruction::DeregisterValidator => "DeregisterValidator",
+            StablecoinInstruction::RenewGreenProof { .. } => "RenewGreenProof",
+            StablecoinInstruction::ProposeUpgrade { .. } => "ProposeUpgrade",
+            StablecoinInstruction::ProposeJurisdictionPolicy { .. } => "ProposeJurisdictionPolicy",
+            StablecoinInstruction::UploadComplianceCircuitV2 { .. } => "UploadComplianceCircuitV2",
+            StablecoinInstruction::MintSeniorWithCollateral { .. } => "MintSeniorWithCollateral",
+            StablecoinInstruction::StreamAsset { .. } => "StreamAsset",
+            StablecoinInstruction::TransferWithMemo { .. } => "TransferWithMemo",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -220,4 +1220,88 @@ pub enum AssetType {
     LJUN,
     Lumina,
     Custom(String),
+    Bridged(String),
+}
+
+impl fmt::Display for AssetType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetType::LUSD => f.write_str("LUSD"),
+            AssetType::LJUN => f.write_str("LJUN"),
+            AssetType::Lumina => f.write_str("Lumina"),
+            AssetType::Custom(symbol) | AssetType::Bridged(symbol) => f.write_str(symbol),
+        }
+    }
+}
+
+/// Inclusion proof of a deposit in a block the light client holds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum SpvDepositProof {
+    /// The raw transaction, its Merkle branch from the leaf up, and its
+    /// position in the block
+    Bitcoin {
+        tx: Vec<u8>,
+        merkle_branch: Vec<[u8; 32]>,
+        index: u32,
+    },
+    /// The receipts-trie proof, root first, of the deposit transaction's
+    /// receipt
+    Ethereum {
+        tx_index: u64,
+        receipt_proof: Vec<Vec<u8>>,
+    },
+}
+
+/// On-chain asset backing a senior mint, debited from the minter.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CollateralAsset {
+    Lumina,
+    Custom(String),
+    Bridged(String),
+    /// Capacity pledged from an RWA listing owned by the minter
+    PledgedRWA(u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn kind_table_positions_are_wire_tags() {
+        let names: HashSet<_> = INSTRUCTION_KINDS.iter().collect();
+        assert_eq!(names.len(), INSTRUCTION_KINDS.len());
+
+        let samples = [
+            StablecoinInstruction::RegisterAsset {
+                ticker: "GOLD".to_string(),
+                decimals: 6,
+            },
+            StablecoinInstruction::Transfer {
+                to: [1u8; 32],
+                amount: 5,
+                asset: AssetType::LUSD,
+            },
+            StablecoinInstruction::RunCircuitBreaker { active: true },
+            StablecoinInstruction::SendLusdCrossChain {
+                dest_chain: 7,
+                recipient: vec![1],
+                amount: 5,
+            },
+            StablecoinInstruction::CouncilApprove { action_id: 0 },
+            StablecoinInstruction::ProposeInstructionPause {
+                kinds: Vec::new(),
+                paused: true,
+            },
+        ];
+        for si in samples {
+            let encoded = bincode::serialize(&si).unwrap();
+            let tag = u32::from_le_bytes(encoded[..4].try_into().unwrap()) as usize;
+            assert_eq!(kind_index(si.kind()), Some(tag), "{}", si.kind());
+        }
+        assert_eq!(
+            kind_index("TransferWithMemo"),
+            Some(INSTRUCTION_KINDS.len() - 1)
+        );
+    }
 }
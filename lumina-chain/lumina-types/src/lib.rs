@@ -1,9 +1,25 @@
+pub mod archive;
+pub mod audit;
+pub mod balance;
+pub mod beacon;
 pub mod block;
+pub mod checkpoint;
+pub mod crosschain;
+pub mod event;
+pub mod faucet;
 pub mod instruction;
+pub mod payment;
+pub mod proof;
+pub mod receipt;
+pub mod screening;
 pub mod state;
+pub mod stats;
+pub mod taxlots;
 pub mod transaction;
+pub mod vectors;
 
 pub use block::Block;
+pub use event::ChainEvent;
 pub use instruction::StablecoinInstruction;
 pub use state::GlobalState;
 pub use transaction::Transaction;
@@ -0,0 +1,267 @@
+use crate::instruction::AssetType;
+use std::fmt;
+
+/// URI scheme of payment requests, e.g.
+/// `lumina:<address hex>?amount=2500&asset=LUSD&memo=Order%2042&expires=1767225600`.
+pub const PAYMENT_URI_SCHEME: &str = "lumina";
+
+/// Longest memo a request may carry, in bytes, so its QR code stays small.
+pub const MAX_MEMO_BYTES: usize = 140;
+
+/// What a point-of-sale asks to be paid, encodable as a URI or QR code.
+/// Only the memo's hash goes on-chain, with `TransferWithMemo`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaymentRequest {
+    pub address: [u8; 32],
+    pub amount: u64,
+    /// LUSD, LJUN or LUMINA
+    pub asset: AssetType,
+    pub memo: Option<String>,
+    /// Unix time after which wallets should refuse to pay
+    pub expires_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaymentUriError {
+    Scheme,
+    Address,
+    MissingAmount,
+    Invalid(&'static str),
+}
+
+impl fmt::Display for PaymentUriError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentUriError::Scheme => write!(f, "not a {}: URI", PAYMENT_URI_SCHEME),
+            PaymentUriError::Address => f.write_str("address must be 32 bytes of hex"),
+            PaymentUriError::MissingAmount => f.write_str("missing amount"),
+            PaymentUriError::Invalid(field) => write!(f, "invalid {}", field),
+        }
+    }
+}
+
+impl std::error::Error for PaymentUriError {}
+
+/// The on-chain reference of a memo: `blake3(memo)`.
+pub fn memo_hash(memo: &str) -> [u8; 32] {
+    *blake3::hash(memo.as_bytes()).as_bytes()
+}
+
+fn asset_code(asset: &AssetType) -> Option<&'static str> {
+    match asset {
+        AssetType::LUSD => Some("LUSD"),
+        AssetType::LJUN => Some("LJUN"),
+        AssetType::Lumina => Some("LUMINA"),
+        _ => None,
+    }
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+                out.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            byte => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+impl PaymentRequest {
+    pub fn memo_hash(&self) -> Option<[u8; 32]> {
+        self.memo.as_deref().map(memo_hash)
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at.is_some_and(|expires| now > expires)
+    }
+
+    /// Encode as a URI; `None` for assets the format cannot name or an
+    /// overlong memo.
+    pub fn to_uri(&self) -> Option<String> {
+        if self.memo.as_ref().is_some_and(|m| m.len() > MAX_MEMO_BYTES) {
+            return None;
+        }
+        let mut uri = format!(
+            "{}:{}?amount={}&asset={}",
+            PAYMENT_URI_SCHEME,
+            hex_encode(&self.address),
+            self.amount,
+            asset_code(&self.asset)?
+        );
+        if let Some(memo) = &self.memo {
+            uri.push_str("&memo=");
+            uri.push_str(&percent_encode(memo));
+        }
+        if let Some(expires) = self.expires_at {
+            uri.push_str(&format!("&expires={}", expires));
+        }
+        Some(uri)
+    }
+
+    /// Parse a payment URI. The asset defaults to LUSD, and unknown
+    /// parameters are ignored so the format can grow.
+    pub fn parse(uri: &str) -> Result<Self, PaymentUriError> {
+        let rest = uri
+            .strip_prefix(PAYMENT_URI_SCHEME)
+            .and_then(|r| r.strip_prefix(':'))
+            .ok_or(PaymentUriError::Scheme)?;
+        let (address, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let address =
+            hex_decode(address.trim_start_matches("0x")).ok_or(PaymentUriError::Address)?;
+        let mut request = PaymentRequest {
+            address,
+            amount: 0,
+            asset: AssetType::LUSD,
+            memo: None,
+            expires_at: None,
+        };
+        let mut amount = None;
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "amount" => {
+                    let parsed = value
+                        .parse()
+                        .map_err(|_| PaymentUriError::Invalid("amount"))?;
+                    amount = Some(parsed);
+                }
+                "asset" => {
+                    request.asset = match value.to_ascii_uppercase().as_str() {
+                        "LUSD" => AssetType::LUSD,
+                        "LJUN" => AssetType::LJUN,
+                        "LUMINA" => AssetType::Lumina,
+                        _ => return Err(PaymentUriError::Invalid("asset")),
+                    }
+                }
+                "memo" => {
+                    let memo = percent_decode(value)
+                        .filter(|m| m.len() <= MAX_MEMO_BYTES)
+                        .ok_or(PaymentUriError::Invalid("memo"))?;
+                    request.memo = Some(memo);
+                }
+                "expires" => {
+                    let expires = value
+                        .parse()
+                        .map_err(|_| PaymentUriError::Invalid("expires"))?;
+                    request.expires_at = Some(expires);
+                }
+                _ => {}
+            }
+        }
+        request.amount = match amount {
+            Some(0) => return Err(PaymentUriError::Invalid("amount")),
+            Some(amount) => amount,
+            None => return Err(PaymentUriError::MissingAmount),
+        };
+        Ok(request)
+    }
+}
+
+fn hex_encode(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hex_decode(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uri_round_trips_and_rejects_malformed_requests() {
+        let request = PaymentRequest {
+            address: [0xab; 32],
+            amount: 2_500,
+            asset: AssetType::LUSD,
+            memo: Some("Order #42 café".to_string()),
+            expires_at: Some(1_767_225_600),
+        };
+        let uri = request.to_uri().unwrap();
+        assert!(uri.starts_with(&format!(
+            "lumina:{}?amount=2500&asset=LUSD",
+            "ab".repeat(32)
+        )));
+        assert!(uri.contains("&memo=Order%20%2342%20caf%C3%A9&expires=1767225600"));
+        assert_eq!(PaymentRequest::parse(&uri).unwrap(), request);
+        assert_eq!(request.memo_hash(), Some(memo_hash("Order #42 café")));
+        assert!(!request.is_expired(1_767_225_600));
+        assert!(request.is_expired(1_767_225_601));
+
+        let bare = format!(
+            "lumina:0x{}?amount=7&asset=ljun&label=shop",
+            "01".repeat(32)
+        );
+        let parsed = PaymentRequest::parse(&bare).unwrap();
+        assert_eq!(
+            (parsed.amount, parsed.asset, parsed.memo),
+            (7, AssetType::LJUN, None)
+        );
+
+        let address = "ab".repeat(32);
+        for (uri, err) in [
+            (
+                format!("bitcoin:{}?amount=1", address),
+                PaymentUriError::Scheme,
+            ),
+            ("lumina:abcd?amount=1".to_string(), PaymentUriError::Address),
+            (
+                format!("lumina:{}", address),
+                PaymentUriError::MissingAmount,
+            ),
+            (
+                format!("lumina:{}?amount=0", address),
+                PaymentUriError::Invalid("amount"),
+            ),
+            (
+                format!("lumina:{}?amount=1&asset=BTC", address),
+                PaymentUriError::Invalid("asset"),
+            ),
+            (
+                format!("lumina:{}?amount=1&memo=%E2%28", address),
+                PaymentUriError::Invalid("memo"),
+            ),
+        ] {
+            assert_eq!(PaymentRequest::parse(&uri), Err(err));
+        }
+        let custom = PaymentRequest {
+            asset: AssetType::Custom("GOLD".to_string()),
+            ..request
+        };
+        assert_eq!(custom.to_uri(), None);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::block::Block;
+use crate::instruction::StablecoinInstruction;
+use serde::{Deserialize, Serialize};
+
+/// What a stored proof attests to, with the public inputs it was verified
+/// against so it can be re-checked later.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProofKind {
+    Reserves { total_reserves: u64, timestamp: u64 },
+    Compliance { tx_hash: [u8; 32] },
+    TaxAttestation { period: u64 },
+    Jurisdiction { jurisdiction_id: u32 },
+}
+
+/// A PoR or compliance proof accepted in a block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BlockProof {
+    pub tx_index: u32,
+    pub tx_id: [u8; 32],
+    pub submitter: [u8; 32],
+    pub kind: ProofKind,
+    pub proof: Vec<u8>,
+}
+
+impl BlockProof {
+    /// Proofs carried by the block's transactions, in block order.
+    pub fn extract(block: &Block) -> Vec<BlockProof> {
+        block
+            .transactions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, tx)| {
+                let (kind, proof) = match &tx.instruction {
+                    StablecoinInstruction::SubmitZkPoR {
+                        proof,
+                        total_reserves,
+                        timestamp,
+                    } => (
+                        ProofKind::Reserves {
+                            total_reserves: *total_reserves,
+                            timestamp: *timestamp,
+                        },
+                        proof,
+                    ),
+                    StablecoinInstruction::ProveCompliance { tx_hash, proof } => {
+                        (ProofKind::Compliance { tx_hash: *tx_hash }, proof)
+                    }
+                    StablecoinInstruction::ZkTaxAttest { period, proof } => {
+                        (ProofKind::TaxAttestation { period: *period }, proof)
+                    }
+                    StablecoinInstruction::MultiJurisdictionalCheck {
+                        jurisdiction_id,
+                        proof,
+                    } => (
+                        ProofKind::Jurisdiction {
+                            jurisdiction_id: *jurisdiction_id,
+                        },
+                        proof,
+                    ),
+                    _ => return None,
+                };
+                Some(BlockProof {
+                    tx_index: i as u32,
+                    tx_id: tx.id(),
+                    submitter: tx.sender,
+                    kind,
+                    proof: proof.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockHeader;
+    use crate::instruction::AssetType;
+    use crate::transaction::Transaction;
+
+    fn tx(instruction: StablecoinInstruction) -> Transaction {
+        Transaction {
+            sender: [1u8; 32],
+            nonce: 0,
+            instruction,
+            signature: Vec::new(),
+            gas_limit: 0,
+            gas_price: 0,
+        }
+    }
+
+    #[test]
+    fn extract_keeps_proof_bearing_txs_in_order() {
+        let block = Block {
+            header: BlockHeader {
+                height: 1,
+                prev_hash: [0u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: [0u8; 32],
+                timestamp: 0,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions: vec![
+                tx(StablecoinInstruction::Transfer {
+                    to: [2u8; 32],
+                    amount: 1,
+                    asset: AssetType::LUSD,
+                }),
+                tx(StablecoinInstruction::SubmitZkPoR {
+                    proof: vec![3u8; 8],
+                    total_reserves: 500,
+                    timestamp: 9,
+                }),
+                tx(StablecoinInstruction::ZkTaxAttest {
+                    period: 4,
+                    proof: vec![5u8; 8],
+                }),
+            ],
+            votes: Vec::new(),
+        };
+
+        let proofs = BlockProof::extract(&block);
+        assert_eq!(proofs.len(), 2);
+        assert_eq!(proofs[0].tx_index, 1);
+        assert_eq!(
+            proofs[0].kind,
+            ProofKind::Reserves {
+                total_reserves: 500,
+                timestamp: 9
+            }
+        );
+        assert_eq!(proofs[1].tx_id, block.transactions[2].id());
+        assert_eq!(proofs[1].proof, vec![5u8; 8]);
+    }
+}
@@ -0,0 +1,126 @@
+use crate::event::ChainEvent;
+use serde::{Deserialize, Serialize};
+
+/// Bloom filter size in bytes (2048 bits, as in Ethereum log blooms)
+pub const BLOOM_BYTES: usize = 256;
+
+/// Per-block bloom over event kinds, addresses and (kind, address) pairs.
+/// False positives are possible; false negatives are not.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogBloom(pub Vec<u8>);
+
+impl Default for LogBloom {
+    fn default() -> Self {
+        Self(vec![0u8; BLOOM_BYTES])
+    }
+}
+
+impl LogBloom {
+    fn bit_positions(item: &[u8]) -> [usize; 3] {
+        let hash = blake3::hash(item);
+        let bytes = hash.as_bytes();
+        let bits = BLOOM_BYTES * 8;
+        let mut out = [0usize; 3];
+        for (i, pos) in out.iter_mut().enumerate() {
+            *pos = (u16::from_le_bytes([bytes[2 * i], bytes[2 * i + 1]]) as usize) % bits;
+        }
+        out
+    }
+
+    fn insert(&mut self, item: &[u8]) {
+        if self.0.len() != BLOOM_BYTES {
+            self.0.resize(BLOOM_BYTES, 0);
+        }
+        for pos in Self::bit_positions(item) {
+            self.0[pos / 8] |= 1 << (pos % 8);
+        }
+    }
+
+    fn contains(&self, item: &[u8]) -> bool {
+        Self::bit_positions(item).iter().all(|pos| {
+            self.0
+                .get(pos / 8)
+                .is_some_and(|b| b & (1 << (pos % 8)) != 0)
+        })
+    }
+
+    pub fn accrue(&mut self, event: &ChainEvent) {
+        let kind = event.kind().as_bytes();
+        self.insert(kind);
+        for address in event.addresses() {
+            self.insert(&address);
+            self.insert(&[kind, &address[..]].concat());
+        }
+    }
+
+    /// Whether the block may contain an event matching both filters
+    /// (`None` matches anything).
+    pub fn may_contain(&self, kind: Option<&str>, address: Option<&[u8; 32]>) -> bool {
+        match (kind, address) {
+            (None, None) => true,
+            (Some(kind), None) => self.contains(kind.as_bytes()),
+            (None, Some(address)) => self.contains(address),
+            (Some(kind), Some(address)) => self.contains(&[kind.as_bytes(), &address[..]].concat()),
+        }
+    }
+}
+
+/// Outcome of one transaction in a block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Receipt {
+    pub tx_id: [u8; 32],
+    pub sender: [u8; 32],
+    pub success: bool,
+    /// Range into `BlockReceipts::events` emitted by this transaction
+    pub first_event: u32,
+    pub event_count: u32,
+}
+
+/// An event together with where in the block it was emitted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LoggedEvent {
+    /// Index of the emitting transaction, or `None` for end-of-block events
+    pub tx_index: Option<u32>,
+    pub event: ChainEvent,
+}
+
+/// Receipts, event log and bloom for a single block.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct BlockReceipts {
+    pub height: u64,
+    pub receipts: Vec<Receipt>,
+    pub events: Vec<LoggedEvent>,
+    pub bloom: LogBloom,
+}
+
+impl BlockReceipts {
+    pub fn log_event(&mut self, tx_index: Option<u32>, event: ChainEvent) {
+        self.bloom.accrue(&event);
+        self.events.push(LoggedEvent { tx_index, event });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_matches_kind_and_address() {
+        let borrower = [7u8; 32];
+        let mut receipts = BlockReceipts::default();
+        receipts.log_event(
+            Some(0),
+            ChainEvent::FlashLoanDefaulted {
+                height: 1,
+                borrower,
+                shortfall: 5,
+            },
+        );
+        let bloom = &receipts.bloom;
+        assert!(bloom.may_contain(Some("FlashLoanDefaulted"), None));
+        assert!(bloom.may_contain(None, Some(&borrower)));
+        assert!(bloom.may_contain(Some("FlashLoanDefaulted"), Some(&borrower)));
+        assert!(!bloom.may_contain(Some("OracleRegimeChanged"), None));
+        assert!(!LogBloom::default().may_contain(None, Some(&borrower)));
+    }
+}
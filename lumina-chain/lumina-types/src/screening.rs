@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+/// Merkle tree over a sorted, de-duplicated set of screened addresses.
+/// Sorting lets an address be proven absent by the two adjacent leaves
+/// that bracket it.
+pub struct ScreeningTree {
+    leaves: Vec<[u8; 32]>,
+    /// Node levels, leaf hashes first, each padded to an even length
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+/// Inclusion of the leaf at `index` of a screening tree.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LeafProof {
+    pub index: u64,
+    pub address: [u8; 32],
+    pub siblings: Vec<[u8; 32]>,
+}
+
+/// Proof that an address is absent from the tree with `leaf_count` leaves:
+/// the neighbours below and above it, either of which may be missing at
+/// the ends of the set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NonMembershipProof {
+    pub leaf_count: u64,
+    pub lower: Option<LeafProof>,
+    pub upper: Option<LeafProof>,
+}
+
+fn leaf_hash(address: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[0]);
+    hasher.update(address);
+    *hasher.finalize().as_bytes()
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[1]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+/// The published root binds the leaf count, so boundary proofs cannot
+/// claim a shorter set.
+fn bind_root(leaf_count: u64, tree_root: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(b"LUMINA_SCREENING");
+    hasher.update(&leaf_count.to_le_bytes());
+    hasher.update(tree_root);
+    *hasher.finalize().as_bytes()
+}
+
+/// Non-membership of both transfer parties in one oracle's published set.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ScreeningProof {
+    pub oracle: [u8; 32],
+    pub sender: NonMembershipProof,
+    pub recipient: NonMembershipProof,
+}
+
+impl ScreeningTree {
+    pub fn new(addresses: impl IntoIterator<Item = [u8; 32]>) -> Self {
+        let mut leaves: Vec<_> = addresses.into_iter().collect();
+        leaves.sort_unstable();
+        leaves.dedup();
+
+        let mut level: Vec<_> = leaves.iter().map(leaf_hash).collect();
+        let mut levels = Vec::new();
+        while level.len() > 1 {
+            if level.len() % 2 == 1 {
+                level.push([0u8; 32]);
+            }
+            let next = level.chunks(2).map(|p| node_hash(&p[0], &p[1])).collect();
+            levels.push(std::mem::replace(&mut level, next));
+        }
+        levels.push(level);
+        Self { leaves, levels }
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        let top = self.levels.last().and_then(|l| l.first());
+        bind_root(self.leaves.len() as u64, top.unwrap_or(&[0u8; 32]))
+    }
+
+    fn leaf_proof(&self, index: usize) -> LeafProof {
+        let siblings = self.levels[..self.levels.len() - 1]
+            .iter()
+            .enumerate()
+            .map(|(depth, level)| level[(index >> depth) ^ 1])
+            .collect();
+        LeafProof {
+            index: index as u64,
+            address: self.leaves[index],
+            siblings,
+        }
+    }
+
+    /// Proof that `address` is not screened, or `None` if it is.
+    pub fn prove_non_membership(&self, address: &[u8; 32]) -> Option<NonMembershipProof> {
+        let upper = match self.leaves.binary_search(address) {
+            Ok(_) => return None,
+            Err(i) => i,
+        };
+        Some(NonMembershipProof {
+            leaf_count: self.leaves.len() as u64,
+            lower: upper.checked_sub(1).map(|i| self.leaf_proof(i)),
+            upper: (upper < self.leaves.len()).then(|| self.leaf_proof(upper)),
+        })
+    }
+}
+
+impl LeafProof {
+    fn verifies(&self, root: &[u8; 32], leaf_count: u64) -> bool {
+        if self.index >= leaf_count {
+            return false;
+        }
+        // Depth of the tree ScreeningTree::new builds for `leaf_count` leaves
+        let depth = (u64::BITS - leaf_count.saturating_sub(1).leading_zeros()) as usize;
+        if self.siblings.len() != depth {
+            return false;
+        }
+        let mut hash = leaf_hash(&self.address);
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            hash = if (self.index >> level) & 1 == 0 {
+                node_hash(&hash, sibling)
+            } else {
+                node_hash(sibling, &hash)
+            };
+        }
+        bind_root(leaf_count, &hash) == *root
+    }
+}
+
+impl NonMembershipProof {
+    /// Whether this proves `address` absent from the set committed to by `root`.
+    pub fn verify(&self, root: &[u8; 32], address: &[u8; 32]) -> bool {
+        let n = self.leaf_count;
+        let lower_ok = match &self.lower {
+            Some(lower) => lower.address < *address && lower.verifies(root, n),
+            None => true,
+        };
+        let upper_ok = match &self.upper {
+            Some(upper) => upper.address > *address && upper.verifies(root, n),
+            None => true,
+        };
+        let adjacent = match (&self.lower, &self.upper) {
+            (Some(lower), Some(upper)) => lower.index.checked_add(1) == Some(upper.index),
+            (None, Some(upper)) => upper.index == 0,
+            (Some(lower), None) => lower.index.checked_add(1) == Some(n),
+            (None, None) => n == 0 && bind_root(0, &[0u8; 32]) == *root,
+        };
+        lower_ok && upper_ok && adjacent
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proves_absence_and_rejects_screened_addresses() {
+        let screened = [[10u8; 32], [20u8; 32], [30u8; 32], [40u8; 32], [50u8; 32]];
+        let tree = ScreeningTree::new(screened);
+        let root = tree.root();
+
+        for address in [[5u8; 32], [25u8; 32], [45u8; 32], [60u8; 32]] {
+            let proof = tree.prove_non_membership(&address).unwrap();
+            assert!(proof.verify(&root, &address));
+            assert!(!proof.verify(&root, &[30u8; 32]));
+        }
+        assert!(tree.prove_non_membership(&[30u8; 32]).is_none());
+
+        // Skipping over a leaf, or claiming a shorter set, does not verify.
+        let mut gap = tree.prove_non_membership(&[25u8; 32]).unwrap();
+        gap.upper = tree.prove_non_membership(&[35u8; 32]).unwrap().upper;
+        assert!(!gap.verify(&root, &[30u8; 32]));
+        let mut short = tree.prove_non_membership(&[60u8; 32]).unwrap();
+        short.leaf_count = 4;
+        assert!(!short.verify(&root, &[60u8; 32]));
+
+        let empty = ScreeningTree::new([]);
+        let proof = empty.prove_non_membership(&[1u8; 32]).unwrap();
+        assert!(proof.verify(&empty.root(), &[1u8; 32]));
+        assert!(!proof.verify(&root, &[1u8; 32]));
+    }
+}
@@ -1,14 +1,24 @@
+use crate::balance::Balance;
+use crate::crosschain::{MessageBatch, OutboundMessage};
+use crate::event::ChainEvent;
+use crate::instruction::{AssetType, CollateralAsset};
 use serde::{Deserialize, Serialize};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Fixed-point scale of the LJUN exchange-rate index (1e6 = par).
+pub const LJUN_RATE_SCALE: u64 = 1_000_000;
 
 /// Per-account state stored in the global state tree.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct AccountState {
     pub nonce: u64,
     pub lusd_balance: u64,
+    /// LJUN tranche shares; value is derived from the global exchange rate
     pub ljun_balance: u64,
     pub lumina_balance: u64,
-    pub custom_balances: HashMap<String, u64>,
+    pub custom_balances: BTreeMap<String, Balance>,
+    /// Bridged asset balances keyed by asset symbol (e.g. "BTC")
+    pub bridged_balances: BTreeMap<String, Balance>,
     pub commitment: Option<[u8; 32]>,
     /// Passkey device key (65 bytes WebAuthn compressed public key)
     pub passkey_device_key: Option<Vec<u8>>,
@@ -29,6 +39,96 @@ pub struct AccountState {
 
     pub pending_flash_mint: u64,
     pub pending_flash_collateral: u64,
+    /// Stabilization pool LUSD borrowed via FlashLoan in the current block
+    pub pending_flash_loan: u64,
+
+    /// Opt-in insurance coverage, if the account has purchased a tier
+    pub insurance_coverage: Option<InsuranceCoverage>,
+    /// Ids of this account's open collateral positions, oldest first
+    pub collateral_position_ids: Vec<u64>,
+}
+
+impl AccountState {
+    /// Balance of `asset`; LJUN is held as tranche shares.
+    pub fn balance(&self, asset: &AssetType) -> Balance {
+        match asset {
+            AssetType::LUSD => Some(self.lusd_balance.into()),
+            AssetType::LJUN => Some(self.ljun_balance.into()),
+            AssetType::Lumina => Some(self.lumina_balance.into()),
+            AssetType::Custom(ticker) => self.custom_balances.get(ticker).copied(),
+            AssetType::Bridged(symbol) => self.bridged_balances.get(symbol).copied(),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Add `amount` of `asset`; `None`, leaving the balance as it was, if it
+    /// would overflow.
+    pub fn credit(&mut self, asset: &AssetType, amount: u64) -> Option<()> {
+        match asset {
+            AssetType::LUSD => self.lusd_balance = self.lusd_balance.checked_add(amount)?,
+            AssetType::LJUN => self.ljun_balance = self.ljun_balance.checked_add(amount)?,
+            AssetType::Lumina => self.lumina_balance = self.lumina_balance.checked_add(amount)?,
+            AssetType::Custom(ticker) => credit_asset(&mut self.custom_balances, ticker, amount)?,
+            AssetType::Bridged(symbol) => credit_asset(&mut self.bridged_balances, symbol, amount)?,
+        }
+        Some(())
+    }
+
+    /// Take `amount` of `asset`; `None`, leaving the balance as it was, if
+    /// it is short.
+    pub fn debit(&mut self, asset: &AssetType, amount: u64) -> Option<()> {
+        match asset {
+            AssetType::LUSD => self.lusd_balance = self.lusd_balance.checked_sub(amount)?,
+            AssetType::LJUN => self.ljun_balance = self.ljun_balance.checked_sub(amount)?,
+            AssetType::Lumina => self.lumina_balance = self.lumina_balance.checked_sub(amount)?,
+            AssetType::Custom(ticker) => debit_asset(&mut self.custom_balances, ticker, amount)?,
+            AssetType::Bridged(symbol) => debit_asset(&mut self.bridged_balances, symbol, amount)?,
+        }
+        Some(())
+    }
+}
+
+/// Add `amount` to the `asset` entry of a custom or bridged balance map.
+pub fn credit_asset(
+    balances: &mut BTreeMap<String, Balance>,
+    asset: &str,
+    amount: u64,
+) -> Option<()> {
+    let balance = balances.get(asset).copied().unwrap_or_default();
+    balances.insert(asset.to_string(), balance.checked_add(amount)?);
+    Some(())
+}
+
+/// Take `amount` from the `asset` entry of a custom or bridged balance map.
+pub fn debit_asset(
+    balances: &mut BTreeMap<String, Balance>,
+    asset: &str,
+    amount: u64,
+) -> Option<()> {
+    let balance = balances.get(asset).copied().unwrap_or_default();
+    balances.insert(asset.to_string(), balance.checked_sub(amount)?);
+    Some(())
+}
+
+/// Collateral held in custody against LUSD minted by an account
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CollateralPosition {
+    pub owner: [u8; 32],
+    pub asset: CollateralAsset,
+    pub collateral_amount: u64,
+    /// LUSD still outstanding against this position
+    pub minted: u64,
+}
+
+/// Per-account opt-in insurance policy
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InsuranceCoverage {
+    pub tier: u8,
+    /// Coverage is active while `current_epoch < paid_through_epoch`
+    pub paid_through_epoch: u64,
+    /// Amount already claimed against the tier cap in the paid period
+    pub claimed: u64,
+    pub total_premiums_paid: u64,
 }
 
 /// Streaming payment state
@@ -39,6 +139,51 @@ pub struct StreamState {
     pub start_timestamp: u64,
     pub end_timestamp: u64,
     pub withdrawn: u64,
+    /// Asset held in escrow and paid out
+    pub asset: AssetType,
+    pub id: u64,
+    /// Released before `start_timestamp`, at the rates the stream paid
+    /// before it was last topped up or re-rated
+    pub accrued: u64,
+    /// Lower rate offered by the payer, pending the recipient's consent
+    pub proposed_rate: Option<u64>,
+}
+
+impl StreamState {
+    /// Amount the stream pays over its whole life.
+    pub fn total(&self) -> u64 {
+        self.vested(self.end_timestamp)
+    }
+
+    /// Amount released to the recipient by `timestamp`.
+    pub fn vested(&self, timestamp: u64) -> u64 {
+        let elapsed = timestamp
+            .min(self.end_timestamp)
+            .saturating_sub(self.start_timestamp);
+        ((self.amount_per_sec as u128) * (elapsed as u128) + self.accrued as u128)
+            .min(u64::MAX as u128) as u64
+    }
+
+    /// Seconds left to pay at `timestamp`.
+    pub fn remaining(&self, timestamp: u64) -> u64 {
+        self.end_timestamp
+            .saturating_sub(timestamp.max(self.start_timestamp))
+    }
+
+    /// Restart the stream's clock at `timestamp`, folding what it has
+    /// released so far into `accrued`, so its rate or end can change
+    /// without altering what was already earned.
+    pub fn rebase(&mut self, timestamp: u64) {
+        let at = timestamp.max(self.start_timestamp);
+        self.accrued = self.vested(at);
+        self.start_timestamp = at;
+        self.end_timestamp = self.end_timestamp.max(at);
+    }
+
+    /// Released but not yet withdrawn at `timestamp`.
+    pub fn withdrawable(&self, timestamp: u64) -> u64 {
+        self.vested(timestamp).saturating_sub(self.withdrawn)
+    }
 }
 
 /// Yield-bearing wrapped token position
@@ -55,13 +200,24 @@ pub struct YieldPosition {
 pub struct GlobalState {
     pub accounts: HashMap<[u8; 32], AccountState>,
     pub total_lusd_supply: u64,
+    /// Aggregate LJUN tranche value (shares × exchange rate)
     pub total_ljun_supply: u64,
+    /// Outstanding LJUN shares across all accounts
+    pub total_ljun_shares: u64,
+    /// LJUN value per share, scaled by `LJUN_RATE_SCALE` (0 = uninitialized, par)
+    pub ljun_exchange_rate: u64,
 
     // Stability & Tranches
     pub stabilization_pool_balance: u64,
     pub reserve_ratio: f64,
     pub oracle_prices: HashMap<String, u64>,
     pub validators: Vec<ValidatorState>,
+    /// Validator set changes staged until the next epoch boundary
+    pub pending_validator_changes: Vec<ValidatorChange>,
+
+    // Oracle regime: as reported by the price feed, and as applied in end_block
+    pub reported_oracle_regime: OracleRegime,
+    pub oracle_regime: OracleRegime,
 
     // Protection
     pub circuit_breaker_active: bool,
@@ -74,10 +230,16 @@ pub struct GlobalState {
     // Custodian marketplace
     pub custodians: Vec<CustodianState>,
     pub last_reserve_rotation_height: u64,
+    pub reserve_rotations: BTreeMap<u64, ReserveRotation>,
+    pub next_rotation_id: u64,
 
     // Compliance circuits registry
     pub compliance_circuits: HashMap<u64, Vec<u8>>,
 
+    // Collateral position ledger (position_id -> position)
+    pub collateral_positions: BTreeMap<u64, CollateralPosition>,
+    pub next_position_id: u64,
+
     // RWA registry
     pub rwa_listings: HashMap<u64, RWAListing>,
     pub next_rwa_id: u64,
@@ -94,6 +256,15 @@ pub struct GlobalState {
 
     // Flash mint tracking (per-block, reset each block)
     pub pending_flash_mints: u64,
+    pub flash_minters: Vec<[u8; 32]>,
+
+    // Flash loans of pool liquidity: outstanding + borrowers (per-block),
+    // cumulative volume/fees, and peak utilization of the current block (bps)
+    pub pending_flash_loans: u64,
+    pub flash_loan_borrowers: Vec<[u8; 32]>,
+    pub flash_loan_volume: u64,
+    pub flash_loan_fees: u64,
+    pub flash_loan_utilization_bps: u64,
 
     // Epoch tracking for velocity rewards
     pub current_epoch: u64,
@@ -105,19 +276,467 @@ pub struct GlobalState {
 
     // Replay protection for zero-slip batches.
     pub executed_batch_matches: Vec<[u8; 32]>,
+
+    // Protocol parameter store
+    pub params: ProtocolParams,
+
+    // Events emitted in the current block
+    pub events: Vec<ChainEvent>,
+
+    // Audit log: hash of the latest entry and the next sequence number
+    pub audit_head: [u8; 32],
+    pub audit_seq: u64,
+
+    // Consensus-visible state version, raised by height-activated upgrades
+    pub version: u32,
+
+    // Governance proposals and the protocol upgrades they have scheduled
+    pub proposals: BTreeMap<u64, GovernanceProposal>,
+    pub next_proposal_id: u64,
+    pub upgrade_plans: Vec<UpgradePlan>,
+
+    // Deployed wasm contracts by address
+    pub contracts: BTreeMap<[u8; 32], ContractState>,
+
+    // Governance-registered execution hooks by name, and the accounts
+    // attested against each compliance circuit
+    pub hooks: BTreeMap<String, ExecutionHook>,
+    pub compliance_attestations: BTreeMap<u64, BTreeSet<[u8; 32]>>,
+
+    // Hidden valuations of confidential RWA listings (rwa_id -> valuation)
+    pub sealed_rwa_valuations: BTreeMap<u64, SealedValuation>,
+
+    // Account view public keys, and the latest sealed opening of each
+    // account's confidential commitment
+    pub view_keys: BTreeMap<[u8; 32], [u8; 32]>,
+    pub confidential_notes: BTreeMap<[u8; 32], ConfidentialNote>,
+
+    // Sanctions screening oracle registry (member -> latest published root)
+    pub screening_oracles: BTreeMap<[u8; 32], ScreeningOracle>,
+
+    // KYC: limits indexed by tier (empty disables enforcement), the
+    // compliance officers who assign tiers, and each account's tier and usage
+    pub kyc_tiers: Vec<KycTierLimits>,
+    pub compliance_officers: BTreeSet<[u8; 32]>,
+    pub kyc_accounts: BTreeMap<[u8; 32], KycAccount>,
+
+    // Inheritance designations by owner, and the owners whose beneficiary
+    // claims mature at each height (settled in end_block)
+    pub inheritance: BTreeMap<[u8; 32], InheritanceDesignation>,
+    pub inheritance_claims: BTreeMap<u64, BTreeSet<[u8; 32]>>,
+
+    // LUMINA vesting schedules by beneficiary
+    pub vesting: BTreeMap<[u8; 32], Vec<VestingSchedule>>,
+
+    // Protocol treasury (LUSD): balance, share of each mint fee routed to it
+    // (bps), streaming payouts by proposal id, and flows per epoch
+    pub treasury_balance: u64,
+    pub treasury_fee_share_bps: u64,
+    pub treasury_streams: BTreeMap<u64, TreasuryStream>,
+    pub treasury_flows: BTreeMap<u64, TreasuryFlows>,
+
+    // Validator rewards (LUSD): share of each mint fee paid into the fee
+    // pool (bps), the pool awaiting the epoch distribution, and each
+    // validator's commission and reward accounting
+    pub validator_fee_share_bps: u64,
+    pub fee_pool: u64,
+    pub validator_rewards: BTreeMap<[u8; 32], ValidatorRewards>,
+
+    // Downtime: each active validator's signed-blocks window, and validators
+    // jailed for missing too many of them
+    pub validator_liveness: BTreeMap<[u8; 32], ValidatorLiveness>,
+    pub jailed_validators: BTreeMap<[u8; 32], JailedValidator>,
+
+    // Health index: governance-set weights and thresholds (kept apart from
+    // `params` so older states can be migrated by appending), the latest
+    // sub-scores, and each epoch's last sub-scores
+    pub health_params: HealthIndexParams,
+    pub health_components: HealthComponents,
+    pub health_history: BTreeMap<u64, HealthComponents>,
+
+    // Stabilization pool backing: units held per asset, and their LUSD value
+    // as of the last ratio recalculation
+    pub pool_composition: BTreeMap<PoolAsset, u64>,
+    pub pool_valuation: BTreeMap<PoolAsset, u64>,
+
+    // Reserve diversification: governance-set concentration limits, and
+    // what exceeded them at the last health index computation
+    pub concentration_limits: ConcentrationLimits,
+    pub concentration_breaches: BTreeSet<ConcentrationBreach>,
+
+    // Dutch auctions of liquidated positions' collateral, by auction id
+    pub collateral_auctions: BTreeMap<u64, CollateralAuction>,
+    pub next_auction_id: u64,
+
+    // Height each rate-limited maintenance task last paid a keeper bounty
+    pub keeper_last_paid: BTreeMap<KeeperTask, u64>,
+
+    // On-chain repayment behaviour behind each account's native credit score
+    pub credit_histories: BTreeMap<[u8; 32], CreditHistory>,
+
+    // Guaranteed credit lines, by line id
+    pub credit_lines: BTreeMap<u64, CreditLine>,
+    pub next_credit_line_id: u64,
+
+    // Registered merchants' settlement accounts, netted once per epoch
+    pub merchants: BTreeMap<[u8; 32], MerchantAccount>,
+
+    // Existential deposit, the height each tracked dust account is reaped
+    // at, and the accounts due at each height (reaped in end_block)
+    pub dust_policy: DustPolicy,
+    pub dust_accounts: BTreeMap<[u8; 32], u64>,
+    pub dust_reaps: BTreeMap<u64, BTreeSet<[u8; 32]>>,
+
+    // State expiry: the archiving policy, the epoch each account was last
+    // active in while it applies, archived batches by id, and the batch
+    // holding each archived account
+    pub archive_policy: ArchivePolicy,
+    pub archive_activity: BTreeMap<[u8; 32], u64>,
+    pub archive_batches: BTreeMap<u64, ArchiveBatch>,
+    pub next_archive_batch: u64,
+    pub archived_accounts: BTreeMap<[u8; 32], u64>,
+
+    // Randomness beacon: validators' commitments and reveals for the
+    // current epoch, and each finished epoch's output
+    pub beacon_round: BTreeMap<[u8; 32], BeaconContribution>,
+    pub randomness_beacons: BTreeMap<u64, RandomnessBeacon>,
+
+    // Health-driven parameter controller: its governance-set bounds and the
+    // savings rate it steers (the mint and redemption fees are in `params`)
+    pub stabilizer: Stabilizer,
+
+    // LUSD of archived accounts, still counted in `total_lusd_supply`
+    pub archived_lusd: u64,
+
+    // Every accepted proof-of-reserves attestation, by its timestamp
+    pub por_history: BTreeMap<u64, PorAttestation>,
+
+    // Finalized reserve rotations each custodian took part in
+    pub custodian_rotations: BTreeMap<[u8; 32], RotationParticipation>,
+
+    // Version history of each compliance circuit; `compliance_circuits`
+    // holds the active version's key and `compliance_attestations` the
+    // accounts attested against any version that still verifies
+    pub circuit_versions: BTreeMap<u64, CircuitVersions>,
+
+    // Governance-set policy of each jurisdiction, and the jurisdiction each
+    // account proved it belongs to
+    pub jurisdiction_policies: BTreeMap<u32, JurisdictionPolicy>,
+    pub account_jurisdictions: BTreeMap<[u8; 32], u32>,
+
+    // Institution credential registry: trusted attesters, the credential
+    // commitments they anchored, and the flows requiring proven credentials
+    pub credential_attesters: BTreeSet<[u8; 32]>,
+    pub institution_credentials: BTreeMap<[u8; 32], BTreeMap<CredentialKind, AnchoredCredential>>,
+    pub credential_requirements: CredentialRequirements,
+
+    // Id given to the next payment stream
+    pub next_stream_id: u64,
+
+    // Hash-time-locked transfers by id, the ids timing out at each height,
+    // and the id given to the next lock
+    pub hash_locks: BTreeMap<u64, HashLock>,
+    pub hash_lock_timeouts: BTreeMap<u64, Vec<u64>>,
+    pub next_hash_lock_id: u64,
+
+    // SPV bridges and their light clients by chain, deposit claims awaiting
+    // their challenge period by id, the keys of deposits already claimed, and
+    // the id given to the next claim
+    pub spv_bridges: BTreeMap<SpvChain, SpvBridge>,
+    pub spv_clients: BTreeMap<SpvChain, SpvLightClient>,
+    pub spv_deposits: BTreeMap<u64, SpvDeposit>,
+    pub spv_claimed: BTreeSet<[u8; 32]>,
+    pub next_spv_deposit_id: u64,
+
+    // Cross-chain LUSD: remote chains by chain id, messages sent this block,
+    // sealed batches by id, returns awaiting validator quorum by return key,
+    // the returns already minted, and the next message nonce and batch id
+    pub remote_chains: BTreeMap<u64, RemoteChain>,
+    pub outbound_messages: Vec<OutboundMessage>,
+    pub message_batches: BTreeMap<u64, MessageBatch>,
+    pub pending_returns: BTreeMap<[u8; 32], PendingReturn>,
+    pub completed_returns: BTreeSet<[u8; 32]>,
+    pub next_message_nonce: u64,
+    pub next_message_batch_id: u64,
+
+    // Governance-set mint caps, the LUSD minted under them this block in
+    // total and by account, and the consecutive blocks that reached a cap
+    pub mint_caps: MintCaps,
+    pub block_minted: u64,
+    pub block_account_mints: BTreeMap<[u8; 32], u64>,
+    pub mint_cap_streak: u64,
+
+    // Governance-set redemption delay tiers, the redemptions waiting out
+    // their delay by id, and the id given to the next one
+    pub redemption_tiers: Vec<RedemptionTier>,
+    pub delayed_redemptions: BTreeMap<u64, DelayedRedemption>,
+    pub next_delayed_redemption_id: u64,
+
+    // Governance-appointed emergency council, every action it has taken or
+    // proposed by id (kept as its on-chain log), and the id given to the
+    // next action
+    pub emergency_council: EmergencyCouncil,
+    pub council_actions: BTreeMap<u64, CouncilAction>,
+    pub next_council_action_id: u64,
+
+    // Instructions refused before they run: those governance paused, and
+    // those plus any paused or frozen by council actions in effect
+    pub governance_paused: InstructionBitmap,
+    pub paused_instructions: InstructionBitmap,
+}
+
+/// Market regime classification used to drive peg defense.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum OracleRegime {
+    #[default]
+    Stable,
+    Volatile,
+    Stress,
+}
+
+/// Protocol parameters that govern economic behaviour.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProtocolParams {
+    /// Premium schedule for opt-in insurance, indexed by tier
+    pub insurance_tiers: Vec<InsuranceTierParams>,
+    /// Maximum single claim for accounts without active coverage
+    pub uninsured_claim_cap: u64,
+    /// Reserve ratio (bps) below which the junior tranche is written down
+    /// once the insurance fund is exhausted
+    pub junior_writedown_floor_bps: u64,
+    /// Base senior mint fee (bps), routed to the insurance fund
+    pub mint_fee_bps: u64,
+    /// Base senior redemption fee (bps), routed to the insurance fund
+    pub redeem_fee_bps: u64,
+    /// Extra mint/redeem fee (bps) applied outside the Stable regime
+    pub volatile_fee_surcharge_bps: u64,
+    /// LUSD-USD peg deviation (bps) at which the regime becomes Volatile
+    pub volatile_peg_deviation_bps: u64,
+    /// LUSD-USD peg deviation (bps) at which the regime becomes Stress
+    pub stress_peg_deviation_bps: u64,
+    /// Collateral value (bps of minted LUSD) below which a position can be liquidated
+    pub liquidation_threshold_bps: u64,
+    /// Flash loan fee (bps of principal), routed to the insurance fund
+    pub flash_loan_fee_bps: u64,
+    /// Seconds a green energy proof stays valid before power decays to stake
+    pub green_proof_validity_secs: u64,
+    /// Target share of reserves per geographic zone (zone_id -> weight).
+    /// Weights are relative; zones without custodians are skipped.
+    pub zone_reserve_weights: BTreeMap<u32, u64>,
+}
+
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        Self {
+            insurance_tiers: vec![
+                InsuranceTierParams {
+                    premium_per_epoch: 10,
+                    coverage_cap: 10_000,
+                },
+                InsuranceTierParams {
+                    premium_per_epoch: 50,
+                    coverage_cap: 100_000,
+                },
+                InsuranceTierParams {
+                    premium_per_epoch: 200,
+                    coverage_cap: 1_000_000,
+                },
+            ],
+            uninsured_claim_cap: u64::MAX,
+            junior_writedown_floor_bps: 10_000,
+            mint_fee_bps: 500,
+            redeem_fee_bps: 0,
+            volatile_fee_surcharge_bps: 100,
+            volatile_peg_deviation_bps: 100,
+            stress_peg_deviation_bps: 300,
+            liquidation_threshold_bps: 11_000,
+            flash_loan_fee_bps: 9,
+            green_proof_validity_secs: 90 * 86_400,
+            zone_reserve_weights: BTreeMap::new(),
+        }
+    }
+}
+
+/// Premium and coverage cap for a single insurance tier
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InsuranceTierParams {
+    pub premium_per_epoch: u64,
+    pub coverage_cap: u64,
+}
+
+/// A `ProtocolParams` value governance sets through `ProposeProtocolParam`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProtocolParam {
+    InsuranceTiers(Vec<InsuranceTierParams>),
+    UninsuredClaimCap(u64),
+    JuniorWritedownFloorBps(u64),
+    MintFeeBps(u64),
+    RedeemFeeBps(u64),
+    VolatileFeeSurchargeBps(u64),
+    VolatilePegDeviationBps(u64),
+    StressPegDeviationBps(u64),
+    LiquidationThresholdBps(u64),
+    FlashLoanFeeBps(u64),
+    ZoneReserveWeights(BTreeMap<u32, u64>),
+    GreenProofValiditySecs(u64),
 }
 
 impl GlobalState {
+    /// Whether `address` may be archived now: inactive for the policy's
+    /// epochs, with no open positions, streams or pending flash balances, and
+    /// not a contract, validator, merchant, inheritance owner or vesting
+    /// beneficiary, whose state other modules read.
+    pub fn archivable(&self, address: &[u8; 32]) -> bool {
+        let policy = &self.archive_policy;
+        if policy.inactivity_epochs == 0 || self.archived_accounts.contains_key(address) {
+            return false;
+        }
+        let Some(account) = self.accounts.get(address) else {
+            return false;
+        };
+        let last_active = self
+            .archive_activity
+            .get(address)
+            .copied()
+            .unwrap_or(policy.enabled_epoch);
+        self.current_epoch >= last_active.saturating_add(policy.inactivity_epochs)
+            && account.active_streams.is_empty()
+            && account.yield_positions.is_empty()
+            && account.pending_flash_mint == 0
+            && account.pending_flash_collateral == 0
+            && account.pending_flash_loan == 0
+            && account.insurance_coverage.is_none()
+            && account.collateral_position_ids.is_empty()
+            && !self.contracts.contains_key(address)
+            && !self.validators.iter().any(|v| v.pubkey == *address)
+            && !self.merchants.contains_key(address)
+            && !self.inheritance.contains_key(address)
+            && !self.vesting.contains_key(address)
+    }
+
+    /// Randomness for an outcome fixed during `epoch` (lottery entries that
+    /// closed then, say), separated by `domain`. It comes from the next
+    /// epoch's beacon, committed to only after `epoch` ended, so nobody
+    /// could know it while the outcome was open. `None` until that beacon
+    /// is finalized, or if no validator revealed into it.
+    pub fn randomness(&self, epoch: u64, domain: &[u8]) -> Option<[u8; 32]> {
+        let beacon = self.randomness_beacons.get(&epoch.checked_add(1)?)?;
+        (beacon.reveals > 0).then(|| crate::beacon::derive(&beacon.output, domain))
+    }
+
+    /// LUMINA of `account` still locked by vesting at `height`.
+    pub fn locked_lumina(&self, account: &[u8; 32], height: u64) -> u64 {
+        self.vesting.get(account).map_or(0, |schedules| {
+            schedules
+                .iter()
+                .map(|s| s.locked_at(height))
+                .fold(0, u64::saturating_add)
+        })
+    }
+
+    /// LUMINA `account` may transfer, burn or pledge at `height`.
+    pub fn spendable_lumina(&self, account: &[u8; 32], height: u64) -> u64 {
+        let balance = self.accounts.get(account).map_or(0, |a| a.lumina_balance);
+        balance.saturating_sub(self.locked_lumina(account, height))
+    }
+
+    /// `payer`'s stream with id `stream_id`, if still open.
+    pub fn stream(&self, payer: &[u8; 32], stream_id: u64) -> Option<&StreamState> {
+        self.accounts
+            .get(payer)?
+            .active_streams
+            .iter()
+            .find(|s| s.id == stream_id)
+    }
+
+    /// KYC tier of `account` and its limits, or `None` while no tiers are
+    /// configured. A tier beyond a since-shortened table gets the top tier.
+    pub fn kyc_limits(&self, account: &[u8; 32]) -> Option<(u8, &KycTierLimits)> {
+        let top = self.kyc_tiers.len().checked_sub(1)?;
+        let tier = self.kyc_accounts.get(account).map_or(0, |a| a.tier);
+        let tier = (tier as usize).min(top);
+        Some((tier as u8, &self.kyc_tiers[tier]))
+    }
+
+    /// Jurisdiction `account` is tagged with and its policy, or `None` if it
+    /// is untagged or its jurisdiction has no policy.
+    pub fn jurisdiction_policy(&self, account: &[u8; 32]) -> Option<(u32, &JurisdictionPolicy)> {
+        let id = *self.account_jurisdictions.get(account)?;
+        self.jurisdiction_policies.get(&id).map(|p| (id, p))
+    }
+
+    /// Whether `account` has proven a live credential of every kind in
+    /// `kinds`: anchored by a still-trusted attester and unexpired at
+    /// `height`.
+    pub fn has_credentials(
+        &self,
+        account: &[u8; 32],
+        kinds: &[CredentialKind],
+        height: u64,
+    ) -> bool {
+        let held = self.institution_credentials.get(account);
+        kinds.iter().all(|kind| {
+            held.and_then(|c| c.get(kind)).is_some_and(|c| {
+                c.proven_height.is_some()
+                    && c.is_live(height)
+                    && self.credential_attesters.contains(&c.attester)
+            })
+        })
+    }
+
+    /// Current LJUN exchange rate, treating an uninitialized index as par.
+    pub fn effective_ljun_rate(&self) -> u64 {
+        if self.ljun_exchange_rate == 0 {
+            LJUN_RATE_SCALE
+        } else {
+            self.ljun_exchange_rate
+        }
+    }
+
+    /// Tranche value of `shares` LJUN shares at the current exchange rate.
+    pub fn ljun_value_of(&self, shares: u64) -> u128 {
+        (shares as u128) * (self.effective_ljun_rate() as u128) / (LJUN_RATE_SCALE as u128)
+    }
+
+    /// Whether a validator's green energy proof is still within its validity window.
+    pub fn green_proof_fresh(&self, validator: &ValidatorState, now: u64) -> bool {
+        validator.is_green
+            && now.saturating_sub(validator.green_verified_at)
+                <= self.params.green_proof_validity_secs
+    }
+
+    /// Commitment to the active validator set (order-sensitive: pubkey, power).
+    pub fn validator_set_hash(&self) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"LUMINA_VALIDATOR_SET");
+        for v in &self.validators {
+            hasher.update(&v.pubkey);
+            hasher.update(&v.power.to_le_bytes());
+        }
+        *hasher.finalize().as_bytes()
+    }
+
     pub fn root_hash(&self) -> [u8; 32] {
         let entries: BTreeMap<[u8; 32], Vec<u8>> = self
             .accounts
             .iter()
             .map(|(k, v)| (*k, bincode::serialize(v).expect("account serialization")))
             .collect();
-        account_trie_root(&entries)
+        let accounts_root = account_trie_root(&entries);
+        // Contracts are folded in only once one exists, leaving the roots
+        // of contract-free chains unchanged.
+        if self.contracts.is_empty() {
+            return accounts_root;
+        }
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"LUMINA_CONTRACTS");
+        hasher.update(&accounts_root);
+        hasher.update(&bincode::serialize(&self.contracts).expect("contract serialization"));
+        *hasher.finalize().as_bytes()
     }
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize, Debug, Clone)]
 enum MptNode {
     Leaf {
@@ -205,10 +824,7 @@ fn longest_common_extension(entries: &[(Vec<u8>, Vec<u8>)], start: usize) -> Vec
     let mut out = Vec::new();
     let mut idx = start;
 
-    loop {
-        let Some(first) = entries[0].0.get(idx) else {
-            break;
-        };
+    while let Some(first) = entries[0].0.get(idx) {
         if entries.iter().all(|(k, _)| k.get(idx) == Some(first)) {
             out.push(*first);
             idx += 1;
@@ -236,6 +852,14 @@ pub struct RedemptionRequest {
     pub timestamp: u64,
 }
 
+/// A validator set mutation, applied in order at the next epoch boundary.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum ValidatorChange {
+    Add(ValidatorState),
+    Remove { pubkey: [u8; 32] },
+    UpdatePower { pubkey: [u8; 32], power: u64 },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ValidatorState {
     pub pubkey: [u8; 32],
@@ -243,6 +867,8 @@ pub struct ValidatorState {
     pub power: u64,
     pub is_green: bool,
     pub energy_proof: Option<Vec<u8>>,
+    /// Block timestamp at which `energy_proof` was last verified
+    pub green_verified_at: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -251,6 +877,1032 @@ pub struct CustodianState {
     pub stake: u64,
     pub mpc_pubkeys: Vec<[u8; 32]>,
     pub registered_height: u64,
+    /// Geographic zone the custodian's reserves are held in (0 = unassigned)
+    pub zone_id: u32,
+    /// Reserves (LUSD-equivalent) allocated to this custodian
+    pub reserve_balance: u64,
+}
+
+/// A deployed wasm contract. Its LUSD balance lives in the account of the
+/// same address; `storage` is the contract's own key-value store.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ContractState {
+    pub deployer: [u8; 32],
+    pub code_hash: [u8; 32],
+    pub code: Vec<u8>,
+    pub storage: BTreeMap<[u8; 32], [u8; 32]>,
+}
+
+/// A named protocol upgrade. Blocks at or above `height` may only be
+/// produced or imported by binaries that declare support for `name`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpgradePlan {
+    pub name: String,
+    pub height: u64,
+}
+
+/// A compliance check bound to an uploaded circuit, run before every
+/// instruction of the listed kinds. The sender and any recipient must hold
+/// an attestation for `circuit_id`; an evaluation costing more than
+/// `gas_budget` fails the instruction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExecutionHook {
+    pub name: String,
+    pub circuit_id: u64,
+    /// `StablecoinInstruction::kind` names this hook guards
+    pub instructions: Vec<String>,
+    pub gas_budget: u64,
+}
+
+/// Uploaded versions of one compliance circuit. Only `publisher`, the
+/// account that uploaded version 1, may upload later versions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CircuitVersions {
+    pub publisher: [u8; 32],
+    pub active: u32,
+    pub versions: BTreeMap<u32, CircuitVersion>,
+}
+
+/// One verifier key of a compliance circuit and the accounts that proved
+/// against it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CircuitVersion {
+    pub verifier_key: Vec<u8>,
+    pub activation_height: u64,
+    /// Blocks the previous version keeps verifying once this one activates
+    pub deprecation_blocks: u64,
+    pub status: CircuitStatus,
+    pub attested: BTreeSet<[u8; 32]>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitStatus {
+    /// Uploaded, activating at `activation_height`; proofs are accepted
+    /// ahead of time but do not count until then
+    Pending,
+    Active,
+    /// Superseded, but still verifying until `retire_height`
+    Deprecated {
+        retire_height: u64,
+    },
+    /// Proofs against this version are rejected
+    Retired,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProposalAction {
+    ScheduleUpgrade(UpgradePlan),
+    RegisterHook(ExecutionHook),
+    RemoveHook(String),
+    SetScreeningOracle { oracle: [u8; 32], member: bool },
+    SetComplianceOfficer { officer: [u8; 32], member: bool },
+    SetKycTiers(Vec<KycTierLimits>),
+    SpendTreasury(TreasurySpend),
+    SetTreasuryFeeShare(u64),
+    SetValidatorFeeShare(u64),
+    SetHealthParams(HealthIndexParams),
+    SetConcentrationLimits(ConcentrationLimits),
+    SetCreditOracle { oracle: [u8; 32], member: bool },
+    SetDustPolicy(DustPolicy),
+    SetArchivePolicy { inactivity_epochs: u64 },
+    SetStabilizerBounds(StabilizerBounds),
+    SetJurisdictionPolicy(u32, Option<JurisdictionPolicy>),
+    SetCredentialAttester { attester: [u8; 32], member: bool },
+    SetCredentialRequirements(CredentialRequirements),
+    SetSpvBridge(SpvChain, Option<SpvBridge>),
+    SetRemoteChain { chain_id: u64, active: bool },
+    SetMintCaps(MintCaps),
+    SetRedemptionTiers(Vec<RedemptionTier>),
+    SetEmergencyCouncil(EmergencyCouncil),
+    RuleCouncilAction { action_id: u64, ratify: bool },
+    SetInstructionPause { kinds: Vec<String>, paused: bool },
+    SetProtocolParam(ProtocolParam),
+}
+
+/// An asset class backing the stabilization pool.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PoolAsset {
+    /// Off-chain reserves attested by the latest proof of reserves
+    FiatAttested,
+    Lumina,
+    /// Pledged RWA capacity, carried at attested value
+    Rwa,
+    Bridged(String),
+    Custom(String),
+}
+
+impl PoolAsset {
+    /// Display name, also used where the asset keys a JSON object.
+    pub fn label(&self) -> String {
+        match self {
+            PoolAsset::FiatAttested => "fiat_attested".to_string(),
+            PoolAsset::Lumina => "lumina".to_string(),
+            PoolAsset::Rwa => "rwa".to_string(),
+            PoolAsset::Bridged(symbol) => format!("bridged:{}", symbol),
+            PoolAsset::Custom(ticker) => format!("custom:{}", ticker),
+        }
+    }
+}
+
+impl From<&CollateralAsset> for PoolAsset {
+    fn from(asset: &CollateralAsset) -> Self {
+        match asset {
+            CollateralAsset::Lumina => PoolAsset::Lumina,
+            CollateralAsset::Custom(ticker) => PoolAsset::Custom(ticker.clone()),
+            CollateralAsset::Bridged(symbol) => PoolAsset::Bridged(symbol.clone()),
+            CollateralAsset::PledgedRWA(_) => PoolAsset::Rwa,
+        }
+    }
+}
+
+/// Largest share (bps) of reserves any one custodian, or of the pool any
+/// one asset, may hold. 10000 disables a limit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConcentrationLimits {
+    pub max_custodian_bps: u64,
+    pub max_asset_bps: u64,
+}
+
+impl Default for ConcentrationLimits {
+    fn default() -> Self {
+        Self {
+            max_custodian_bps: 10_000,
+            max_asset_bps: 10_000,
+        }
+    }
+}
+
+/// A custodian or pool asset holding more than its concentration limit.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConcentrationBreach {
+    Custodian([u8; 32]),
+    Asset(PoolAsset),
+}
+
+/// Weights (points out of 10000) and thresholds of the health index
+/// components.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HealthIndexParams {
+    pub reserve_weight: u64,
+    pub peg_weight: u64,
+    pub breaker_weight: u64,
+    pub insurance_weight: u64,
+    pub green_weight: u64,
+    pub custodian_weight: u64,
+    /// Reserve ratio (bps) that earns the full reserve weight
+    pub full_reserve_ratio_bps: u64,
+    /// LUSD-USD deviation (bps) below which the peg earns its full weight;
+    /// below `peg_loose_bps` it earns 3/5 of it, and 1/5 beyond
+    pub peg_tight_bps: u64,
+    pub peg_loose_bps: u64,
+    /// Insurance fund (bps of LUSD supply) that earns the full insurance weight
+    pub full_insurance_bps: u64,
+    /// Custodian count that earns the full custodian weight
+    pub full_custodian_count: u64,
+}
+
+impl Default for HealthIndexParams {
+    fn default() -> Self {
+        Self {
+            reserve_weight: 3_000,
+            peg_weight: 2_500,
+            breaker_weight: 1_500,
+            insurance_weight: 1_500,
+            green_weight: 1_000,
+            custodian_weight: 500,
+            full_reserve_ratio_bps: 20_000,
+            peg_tight_bps: 500,
+            peg_loose_bps: 1_000,
+            full_insurance_bps: 500,
+            full_custodian_count: 10,
+        }
+    }
+}
+
+impl HealthIndexParams {
+    pub fn total_weight(&self) -> u64 {
+        [
+            self.reserve_weight,
+            self.peg_weight,
+            self.breaker_weight,
+            self.insurance_weight,
+            self.green_weight,
+            self.custodian_weight,
+        ]
+        .iter()
+        .fold(0u64, |sum, w| sum.saturating_add(*w))
+    }
+}
+
+/// Points each component contributed to the health index.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct HealthComponents {
+    pub reserve: u64,
+    pub peg: u64,
+    pub breaker: u64,
+    pub insurance: u64,
+    pub green: u64,
+    pub custodian: u64,
+}
+
+impl HealthComponents {
+    pub fn total(&self) -> u64 {
+        [
+            self.reserve,
+            self.peg,
+            self.breaker,
+            self.insurance,
+            self.green,
+            self.custodian,
+        ]
+        .iter()
+        .fold(0u64, |sum, c| sum.saturating_add(*c))
+    }
+}
+
+/// Fee pool rewards of one validator. Its commission is credited directly;
+/// the rest accrues to its stake through `reward_index` (LUSD per staked
+/// unit, scaled by `REWARD_INDEX_SCALE`), so every stake holder is owed
+/// `stake * (reward_index - claimed_index)`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ValidatorRewards {
+    pub commission_bps: u64,
+    /// Settled rewards awaiting a claim
+    pub accrued: u64,
+    pub reward_index: u128,
+    pub claimed_index: u128,
+}
+
+pub const REWARD_INDEX_SCALE: u128 = 1_000_000_000_000;
+
+impl ValidatorRewards {
+    /// Rewards `stake` has earned through the index since the last claim.
+    pub fn unsettled(&self, stake: u64) -> u64 {
+        let delta = self.reward_index.saturating_sub(self.claimed_index);
+        (stake as u128 * delta / REWARD_INDEX_SCALE).min(u64::MAX as u128) as u64
+    }
+}
+
+/// Sliding window over the last blocks carrying commit votes. Bit
+/// `n % window` of `missed` is set when the `n`th observed block lacked the
+/// validator's vote.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ValidatorLiveness {
+    /// Blocks observed since the validator (re)joined the active set
+    pub observed: u64,
+    pub missed: Vec<u64>,
+    pub missed_count: u64,
+}
+
+/// Permissionless maintenance calls that pay their caller a bounty when
+/// they do real work.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeeperTask {
+    RebalanceTranches,
+    TriggerStabilizer,
+    FairRedeemQueue,
+    Liquidation,
+}
+
+/// Descending-price sale of a liquidated position's collateral. Bids repay
+/// `debt_remaining`; whatever is unsold once it is repaid goes back to the
+/// position's owner.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CollateralAuction {
+    pub position_id: u64,
+    pub owner: [u8; 32],
+    pub asset: CollateralAsset,
+    pub collateral_remaining: u64,
+    pub debt_remaining: u64,
+    /// Asking prices in LUSD per unit of collateral, 1e6 fixed point
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub start_height: u64,
+    pub end_height: u64,
+}
+
+impl CollateralAuction {
+    /// Asking price at `height`, falling linearly from `start_price` at
+    /// `start_height` to `floor_price` at `end_height`.
+    pub fn price_at(&self, height: u64) -> u64 {
+        let duration = self.end_height.saturating_sub(self.start_height);
+        if duration == 0 {
+            return self.floor_price;
+        }
+        let elapsed = height.saturating_sub(self.start_height).min(duration);
+        let drop = self.start_price.saturating_sub(self.floor_price);
+        self.start_price - ((drop as u128) * (elapsed as u128) / (duration as u128)) as u64
+    }
+}
+
+/// A validator removed from the active set for downtime, with its slashed
+/// stake, until it sends `Unjail` at or after `jailed_until`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JailedValidator {
+    pub validator: ValidatorState,
+    pub jailed_height: u64,
+    pub jailed_until: u64,
+}
+
+/// How an approved treasury spend reaches its recipient.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TreasuryPayout {
+    LumpSum,
+    /// Released linearly over `blocks` blocks from execution
+    Stream {
+        blocks: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TreasurySpend {
+    pub recipient: [u8; 32],
+    pub amount: u64,
+    pub payout: TreasuryPayout,
+    pub memo: String,
+}
+
+/// A streaming spend escrowed out of the treasury at execution and paid
+/// out in `end_block` until `end_height`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TreasuryStream {
+    pub recipient: [u8; 32],
+    pub total: u64,
+    pub paid: u64,
+    pub start_height: u64,
+    pub end_height: u64,
+}
+
+/// Treasury LUSD received from fees and paid to recipients in one epoch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TreasuryFlows {
+    pub inflow: u64,
+    pub outflow: u64,
+}
+
+/// The latest screened-address root a registry member has published.
+/// `root` is `None` until its first publication.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ScreeningOracle {
+    pub root: Option<[u8; 32]>,
+    pub published_height: u64,
+}
+
+/// LUSD transfer and redemption limits of one KYC tier. The per-epoch
+/// limits are the daily limits (one epoch is ~1 day).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KycTierLimits {
+    pub max_transfer_per_tx: u64,
+    pub max_transfer_per_epoch: u64,
+    pub max_redeem_per_tx: u64,
+    pub max_redeem_per_epoch: u64,
+}
+
+/// An account's KYC tier and the amounts it has moved during `epoch`.
+/// Accounts without an entry are tier 0.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct KycAccount {
+    pub tier: u8,
+    pub epoch: u64,
+    pub transferred: u64,
+    pub redeemed: u64,
+}
+
+impl KycAccount {
+    /// Transferred and redeemed amounts counted against `epoch`'s limits;
+    /// counters from an earlier epoch have lapsed.
+    pub fn usage(&self, epoch: u64) -> (u64, u64) {
+        if self.epoch == epoch {
+            (self.transferred, self.redeemed)
+        } else {
+            (0, 0)
+        }
+    }
+}
+
+/// Rules for accounts tagged with a jurisdiction. Transfers count against
+/// the same per-epoch usage as the KYC tier limits.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JurisdictionPolicy {
+    pub max_transfer_per_tx: u64,
+    pub max_transfer_per_epoch: u64,
+    /// Compliance circuits a tagged account must be attested against
+    pub required_circuits: Vec<u64>,
+    /// `StablecoinInstruction::kind` names tagged accounts may not send
+    pub blocked_instructions: Vec<String>,
+}
+
+/// Kind of institution credential an attester vouches for.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CredentialKind {
+    /// Legal Entity Identifier
+    Lei,
+    /// Regulatory license, committed by its hash
+    License,
+}
+
+/// Funds escrowed until `recipient` reveals the SHA-256 preimage of
+/// `hash_lock`, or until `timeout_height` passes and they can be refunded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HashLock {
+    pub sender: [u8; 32],
+    pub recipient: [u8; 32],
+    pub asset: AssetType,
+    pub amount: u64,
+    pub hash_lock: [u8; 32],
+    /// Last height at which the lock can be claimed
+    pub timeout_height: u64,
+    /// Set once `timeout_height` has passed; only a refund remains
+    pub expired: bool,
+}
+
+/// A chain whose deposits are proven to Lumina by SPV proofs.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SpvChain {
+    Bitcoin,
+    Ethereum,
+}
+
+/// Governance-set parameters of an SPV bridge.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpvBridge {
+    /// Bridged asset deposits are minted as, one unit per satoshi or token
+    /// base unit
+    pub symbol: String,
+    /// Bitcoin: the script_pubkey deposits pay. Ethereum: the 20-byte
+    /// address of the bridge contract emitting `Deposit` logs
+    pub deposit_target: Vec<u8>,
+    /// Trusted header the light client starts from (80 bytes for Bitcoin,
+    /// RLP for Ethereum) and its height. Bitcoin headers may not have an
+    /// easier target than the checkpoint's
+    pub checkpoint: Vec<u8>,
+    pub checkpoint_height: u64,
+    /// Blocks, the deposit's own included, the best chain must have on top
+    /// of a deposit before it can be claimed
+    pub min_confirmations: u64,
+    /// Lumina blocks a claim stays open to validator challenge before the
+    /// deposit is minted
+    pub challenge_blocks: u64,
+}
+
+/// Header chain a bridge has accepted since its checkpoint.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct SpvLightClient {
+    pub headers: BTreeMap<[u8; 32], SpvHeader>,
+    /// Hash of the header with the most cumulative work
+    pub tip: [u8; 32],
+}
+
+impl SpvLightClient {
+    /// Whether `hash` is on the best chain within `max_depth` headers of
+    /// the tip, and if so how many headers, its own included, sit on it.
+    pub fn confirmations(&self, hash: &[u8; 32], max_depth: u64) -> Option<u64> {
+        let target = self.headers.get(hash)?;
+        let mut cursor = self.tip;
+        for depth in 1..=max_depth {
+            let header = self.headers.get(&cursor)?;
+            if header.height < target.height {
+                return None;
+            }
+            if cursor == *hash {
+                return Some(depth);
+            }
+            cursor = header.parent;
+        }
+        None
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpvHeader {
+    pub parent: [u8; 32],
+    pub height: u64,
+    /// Bitcoin transaction Merkle root, or Ethereum receipts root
+    pub root: [u8; 32],
+    /// Work accumulated since the checkpoint
+    pub work: u128,
+}
+
+/// A chain canonical LUSD can be sent to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct RemoteChain {
+    /// Whether LUSD may be sent; returns are accepted either way
+    pub active: bool,
+    /// LUSD sent to the chain and not yet returned, which bounds returns
+    pub outstanding: u64,
+}
+
+/// LUSD burned on a remote chain to return to `recipient`, attested by
+/// validators until they hold a quorum.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PendingReturn {
+    pub source_chain: u64,
+    pub burn_id: [u8; 32],
+    pub recipient: [u8; 32],
+    pub amount: u64,
+    pub attesters: BTreeSet<[u8; 32]>,
+}
+
+/// A proven deposit awaiting the end of its challenge period.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SpvDeposit {
+    pub chain: SpvChain,
+    pub block_hash: [u8; 32],
+    /// Identifies the deposit across claims: its txid, or block and receipt
+    pub key: [u8; 32],
+    pub recipient: [u8; 32],
+    pub symbol: String,
+    pub amount: u64,
+    pub claimed_height: u64,
+    /// Height at whose end the deposit is minted if still on the best chain
+    pub finalize_height: u64,
+}
+
+/// Commitment to an institution's credential, anchored by a trusted
+/// attester; the credential itself never goes on chain. The institution
+/// proves it can open the commitment with `ProveInstitutionStatus`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AnchoredCredential {
+    pub commitment: [u8; 32],
+    pub attester: [u8; 32],
+    pub anchored_height: u64,
+    /// Height the credential lapses at; 0 never
+    pub expires_height: u64,
+    /// Height the institution last proved the credential since it was
+    /// anchored
+    pub proven_height: Option<u64>,
+}
+
+impl AnchoredCredential {
+    pub fn is_live(&self, height: u64) -> bool {
+        self.expires_height == 0 || height < self.expires_height
+    }
+}
+
+/// Flows governance gates on proven institution credentials.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CredentialRequirements {
+    /// Kinds an account must have proven to `RegisterCustodian`
+    pub custodian: Vec<CredentialKind>,
+    /// KYC tiers from `kyc_min_tier` up may only be assigned to accounts
+    /// that proved every kind in `kyc_kinds`
+    pub kyc_min_tier: u8,
+    pub kyc_kinds: Vec<CredentialKind>,
+}
+
+/// Blocks per year credit line interest rates are quoted over.
+pub const CREDIT_LINE_YEAR_BLOCKS: u64 = 365 * 8_640;
+
+/// LUSD a borrower may draw without collateral, backed by LJUN shares its
+/// guarantors lock in the line. A default is covered from those shares
+/// before the insurance fund or the junior tranche at large.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreditLine {
+    pub borrower: [u8; 32],
+    pub limit: u64,
+    /// Yearly simple interest on drawn principal, paid to the guarantors
+    pub interest_bps: u64,
+    /// Draws stop here, and debt still owed may then be declared in default
+    pub maturity_height: u64,
+    pub principal: u64,
+    pub interest_owed: u64,
+    /// Height interest has been charged through
+    pub accrued_through: u64,
+    /// LJUN shares locked by each guarantor
+    pub guarantors: BTreeMap<[u8; 32], u64>,
+    pub defaulted: bool,
+}
+
+impl CreditLine {
+    pub fn debt(&self) -> u64 {
+        self.principal.saturating_add(self.interest_owed)
+    }
+
+    pub fn guaranteed_shares(&self) -> u64 {
+        self.guarantors
+            .values()
+            .fold(0u64, |acc, s| acc.saturating_add(*s))
+    }
+
+    /// Charge interest on the principal through `height`. Spans too short
+    /// to earn a whole unit are left to accrue, so frequent touches cannot
+    /// round interest away.
+    pub fn accrue(&mut self, height: u64) {
+        let blocks = height.saturating_sub(self.accrued_through);
+        let interest = (self.principal as u128) * (self.interest_bps as u128) * (blocks as u128)
+            / (10_000 * CREDIT_LINE_YEAR_BLOCKS as u128);
+        if interest > 0 || self.principal == 0 {
+            self.interest_owed = self
+                .interest_owed
+                .saturating_add(interest.min(u64::MAX as u128) as u64);
+            self.accrued_through = self.accrued_through.max(height);
+        }
+    }
+}
+
+/// A registered merchant's incoming LUSD, held until the epoch settles it
+/// to `treasury` in one write.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MerchantAccount {
+    pub treasury: [u8; 32],
+    /// Received this epoch and not yet settled
+    pub pending: u64,
+    /// Payments netted into `pending`
+    pub payments: u64,
+}
+
+/// Accounts that never signed a transaction and hold nothing but less than
+/// `existential_deposit` LUSD are reaped `grace_blocks` after they are
+/// seen below it, their LUSD swept to the insurance fund. A zero deposit
+/// disables reaping.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct DustPolicy {
+    pub existential_deposit: u64,
+    pub grace_blocks: u64,
+}
+
+/// Most LUSD the senior mints, the credit-scored mints, `UseRWAAsCollateral`
+/// and `FlashMint` (net of same-block `FlashBurn`s) may mint per block, in
+/// total and per account; zero lifts a cap. Once `breaker_blocks` blocks
+/// in a row reach a cap the circuit breaker trips, pausing those mints;
+/// zero never trips it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MintCaps {
+    pub per_block: u64,
+    pub per_account: u64,
+    pub breaker_blocks: u64,
+}
+
+/// `RedeemSenior` and `InstantRedeem` of at least `min_amount` LUSD wait
+/// `delay_blocks` before settling, however healthy the reserves; the
+/// highest tier an amount reaches applies.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RedemptionTier {
+    pub min_amount: u64,
+    pub delay_blocks: u64,
+}
+
+/// A large redemption whose LUSD is escrowed until `release_height`, when
+/// it settles unless the owner or a validator cancelled it first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DelayedRedemption {
+    pub owner: [u8; 32],
+    pub amount: u64,
+    pub requested_height: u64,
+    pub release_height: u64,
+}
+
+/// Keys that may act in an emergency ahead of a governance vote. An action
+/// takes effect once `threshold` members approve it and lapses
+/// `sunset_blocks` later unless governance ratifies it; an unapproved one
+/// lapses `sunset_blocks` after it was proposed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct EmergencyCouncil {
+    pub members: Vec<[u8; 32]>,
+    pub threshold: u32,
+    pub sunset_blocks: u64,
+}
+
+/// What the emergency council may do.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CouncilPower {
+    /// Trip the circuit breaker
+    TripBreaker,
+    /// Reject instructions of these `StablecoinInstruction::kind` names
+    PauseInstructions(Vec<String>),
+    /// Stop SPV deposits and cross-chain LUSD in both directions
+    FreezeBridge,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CouncilActionStatus {
+    /// Awaiting approvals
+    Proposed,
+    /// In effect until `expires_height`
+    Active,
+    /// Made permanent by governance
+    Ratified,
+    /// Lifted by governance
+    Rejected,
+    /// Reached `expires_height` unratified
+    Lapsed,
+}
+
+/// One use of a council power, kept after it ends as a record.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CouncilAction {
+    pub power: CouncilPower,
+    pub proposer: [u8; 32],
+    pub approvals: BTreeSet<[u8; 32]>,
+    pub proposed_height: u64,
+    pub status: CouncilActionStatus,
+    /// Height the action lapses at unless it activates (while proposed) or
+    /// is ratified (while active)
+    pub expires_height: u64,
+    /// Set when a `TripBreaker` found the breaker already active, so
+    /// lifting it leaves the breaker on
+    pub breaker_was_active: bool,
+}
+
+impl CouncilAction {
+    pub fn in_effect(&self) -> bool {
+        matches!(
+            self.status,
+            CouncilActionStatus::Active | CouncilActionStatus::Ratified
+        )
+    }
+}
+
+/// A set of instruction variants, one bit per position in
+/// `INSTRUCTION_KINDS`. Trailing zero words are trimmed, so equal sets
+/// encode equally.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct InstructionBitmap {
+    words: Vec<u64>,
+}
+
+impl InstructionBitmap {
+    pub fn contains(&self, index: usize) -> bool {
+        self.words
+            .get(index / 64)
+            .is_some_and(|word| word >> (index % 64) & 1 == 1)
+    }
+
+    pub fn insert(&mut self, index: usize) {
+        if self.words.len() <= index / 64 {
+            self.words.resize(index / 64 + 1, 0);
+        }
+        self.words[index / 64] |= 1 << (index % 64);
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if let Some(word) = self.words.get_mut(index / 64) {
+            *word &= !(1 << (index % 64));
+        }
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    pub fn union(&mut self, other: &InstructionBitmap) {
+        for index in other.indices() {
+            self.insert(index);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(at, word)| {
+            (0..64)
+                .filter(move |bit| word >> bit & 1 == 1)
+                .map(move |bit| at * 64 + bit)
+        })
+    }
+}
+
+/// Accounts inactive for `inactivity_epochs` epochs (zero disables) may
+/// be archived; accounts not seen since the policy was adopted in
+/// `enabled_epoch` count from then.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ArchivePolicy {
+    pub inactivity_epochs: u64,
+    pub enabled_epoch: u64,
+}
+
+/// Accounts moved out of live state by one `ArchiveAccounts`, committed to
+/// by an `ArchiveTree` root. The accounts themselves are in the
+/// `AccountsArchived` event logged at `height`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ArchiveBatch {
+    pub root: [u8; 32],
+    pub height: u64,
+    pub accounts: u64,
+}
+
+/// A validator's commitment to this epoch's beacon, and its secret once
+/// revealed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BeaconContribution {
+    pub commitment: [u8; 32],
+    pub secret: Option<[u8; 32]>,
+}
+
+/// An epoch's beacon output, mixed from `reveals` revealed secrets.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RandomnessBeacon {
+    pub output: [u8; 32],
+    pub reveals: u64,
+}
+
+/// An accepted `SubmitZkPoR`, with the LUSD supply it attested against.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PorAttestation {
+    pub height: u64,
+    pub total_reserves: u64,
+    /// blake3 of the proof bytes
+    pub proof_hash: [u8; 32],
+    /// `ZkManager::por_verifying_key_hash` of the key that verified it
+    pub verifying_key_hash: [u8; 32],
+    pub lusd_supply: u64,
+}
+
+/// Inclusive range (bps) a controlled parameter may be moved within.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct BpsRange {
+    pub min: u64,
+    pub max: u64,
+}
+
+impl BpsRange {
+    pub fn clamp(&self, bps: u64) -> u64 {
+        bps.clamp(self.min, self.max)
+    }
+}
+
+/// How far and how often end_block's controller may move the mint fee,
+/// redemption fee, savings rate and redemption cap. Every `interval_blocks`
+/// each moves one `step_bps` toward its maximum while the health index is
+/// below `target_health` or the oracle regime is not Stable, and toward its
+/// minimum otherwise; the redemption cap moves the other way, tightening
+/// under stress. A zero step disables the controller.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct StabilizerBounds {
+    pub mint_fee_bps: BpsRange,
+    pub redeem_fee_bps: BpsRange,
+    pub savings_rate_bps: BpsRange,
+    pub redeem_cap_bps: BpsRange,
+    pub step_bps: u64,
+    pub interval_blocks: u64,
+    pub target_health: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Stabilizer {
+    pub bounds: StabilizerBounds,
+    /// Annual rate (bps) yield tokens accrue at when unwrapped
+    pub savings_rate_bps: u64,
+    /// Share (bps) of the block's opening LUSD supply senior redemptions
+    /// may settle in one block; later ones wait in the fair redeem queue
+    pub redeem_cap_bps: u64,
+    /// LUSD settled by senior redemptions this block
+    pub block_redeemed: u64,
+}
+
+impl Default for Stabilizer {
+    fn default() -> Self {
+        Self {
+            bounds: StabilizerBounds::default(),
+            savings_rate_bps: 500,
+            redeem_cap_bps: 10_000,
+            block_redeemed: 0,
+        }
+    }
+}
+
+/// A parameter the controller adjusts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlledParam {
+    MintFee,
+    RedeemFee,
+    SavingsRate,
+    RedeemCap,
+}
+
+pub const CREDIT_SCORE_FLOOR: u16 = 300;
+/// Points above the floor a native credit score loses per idle epoch.
+pub const CREDIT_DECAY_PER_EPOCH: u64 = 5;
+
+/// An account's repayment record, from which the protocol derives a credit
+/// score without an oracle.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CreditHistory {
+    /// Position redemptions that repaid the account's own debt
+    pub repayments: u64,
+    pub repaid: u64,
+    /// Senior redemptions paid out, at once or from the fair queue
+    pub redemptions_honored: u64,
+    /// Positions seized by liquidation or auction
+    pub liquidations: u64,
+    /// Epoch of the latest entry
+    pub last_epoch: u64,
+}
+
+impl CreditHistory {
+    pub fn record_repayment(&mut self, amount: u64, epoch: u64) {
+        self.repayments = self.repayments.saturating_add(1);
+        self.repaid = self.repaid.saturating_add(amount);
+        self.last_epoch = epoch;
+    }
+
+    pub fn record_redemption(&mut self, epoch: u64) {
+        self.redemptions_honored = self.redemptions_honored.saturating_add(1);
+        self.last_epoch = epoch;
+    }
+
+    pub fn record_liquidation(&mut self, epoch: u64) {
+        self.liquidations = self.liquidations.saturating_add(1);
+        self.last_epoch = epoch;
+    }
+
+    /// Score on the 300..=850 scale as of `epoch`. Repayments earn up to 400
+    /// points and honored redemptions up to 150; each liquidation costs 100,
+    /// and whatever is left above the floor decays while the account is idle.
+    pub fn score(&self, epoch: u64) -> u16 {
+        let earned = self.repayments.min(25) * 16 + self.redemptions_honored.min(25) * 6;
+        let idle = epoch.saturating_sub(self.last_epoch);
+        let earned = earned
+            .saturating_sub(self.liquidations.saturating_mul(100))
+            .saturating_sub(idle.saturating_mul(CREDIT_DECAY_PER_EPOCH));
+        CREDIT_SCORE_FLOOR + earned as u16
+    }
+}
+
+/// Dead-man switch on an account: once it has sent no transaction for
+/// `inactivity_blocks`, `beneficiary` may claim its balances. A claim
+/// settles at `claim_matures_at` unless the owner transacts first.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct InheritanceDesignation {
+    pub beneficiary: [u8; 32],
+    pub inactivity_blocks: u64,
+    pub last_active_height: u64,
+    pub claim_matures_at: Option<u64>,
+}
+
+/// LUMINA granted to an account that unlocks linearly from `start_height`
+/// to `end_height`, with nothing unlocked before `cliff_height`. The grant
+/// sits in the beneficiary's `lumina_balance`; the locked part cannot leave.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VestingSchedule {
+    pub funder: [u8; 32],
+    pub total: u64,
+    pub start_height: u64,
+    pub cliff_height: u64,
+    pub end_height: u64,
+}
+
+impl VestingSchedule {
+    pub fn locked_at(&self, height: u64) -> u64 {
+        if height < self.cliff_height {
+            return self.total;
+        }
+        if height >= self.end_height {
+            return 0;
+        }
+        let elapsed = height.saturating_sub(self.start_height) as u128;
+        let duration = self.end_height.saturating_sub(self.start_height).max(1) as u128;
+        let vested = (self.total as u128) * elapsed / duration;
+        self.total - vested as u64
+    }
+}
+
+/// A validator governance proposal. Executes once approving validators
+/// hold more than two thirds of the voting power.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GovernanceProposal {
+    pub proposer: [u8; 32],
+    pub action: ProposalAction,
+    pub proposed_height: u64,
+    /// Validator -> approve
+    pub votes: BTreeMap<[u8; 32], bool>,
+    pub executed: bool,
+}
+
+/// Pending move of reserves between two custodians. Finalizes once a
+/// majority of both custodians' MPC keys have signed `signing_payload`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ReserveRotation {
+    pub from_custodian: [u8; 32],
+    pub to_custodian: [u8; 32],
+    pub amount: u64,
+    pub proposed_height: u64,
+    pub source_approvals: Vec<[u8; 32]>,
+    pub destination_approvals: Vec<[u8; 32]>,
+}
+
+/// A custodian's share of finalized reserve rotations.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct RotationParticipation {
+    pub rotations_out: u64,
+    pub rotations_in: u64,
+    pub rotated_out: u64,
+    pub rotated_in: u64,
+    pub last_rotation_height: u64,
+}
+
+impl ReserveRotation {
+    /// Message each MPC key signs to approve rotation `rotation_id`
+    pub fn signing_payload(&self, rotation_id: u64) -> [u8; 32] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"LUMINA_RESERVE_ROTATION");
+        hasher.update(&rotation_id.to_le_bytes());
+        hasher.update(&self.from_custodian);
+        hasher.update(&self.to_custodian);
+        hasher.update(&self.amount.to_le_bytes());
+        *hasher.finalize().as_bytes()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -258,9 +1910,57 @@ pub struct RWAListing {
     pub owner: [u8; 32],
     pub asset_description: String,
     pub attestation_proof: Vec<u8>,
+    /// For confidential listings, the proven lower bound of the valuation
     pub attested_value: u64,
     pub maturity_date: Option<u64>,
     pub collateral_eligibility: bool,
     pub is_active: bool,
     pub pledged_amount: u64,
 }
+
+/// Opening of an account's confidential commitment, sealed to the view key
+/// registered when it was made.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ConfidentialNote {
+    pub commitment: [u8; 32],
+    pub view_pubkey: [u8; 32],
+    pub sealed_note: Vec<u8>,
+    pub height: u64,
+}
+
+/// Commitment to a confidential RWA valuation, with the range proof of its
+/// lower bound and the opening sealed for holders of the owner's view key.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SealedValuation {
+    pub commitment: [u8; 32],
+    pub range_proof: Vec<u8>,
+    pub sealed_opening: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn root_hash_ignores_balance_insertion_order() {
+        let symbols = ["BTC", "ETH", "SOL", "ATOM", "DOT", "AVAX", "NEAR", "ADA"];
+        let state_with = |order: Vec<&str>| {
+            let mut account = AccountState::default();
+            for symbol in order {
+                let amount = 1_000 * symbol.len() as u64;
+                credit_asset(&mut account.bridged_balances, symbol, amount).unwrap();
+                credit_asset(&mut account.custom_balances, symbol, amount).unwrap();
+            }
+            let mut state = GlobalState::default();
+            state.accounts.insert([7u8; 32], account);
+            state
+        };
+        let forward = state_with(symbols.to_vec());
+        let reverse = state_with(symbols.iter().rev().copied().collect());
+        assert_eq!(forward.root_hash(), reverse.root_hash());
+
+        let decoded: GlobalState =
+            bincode::deserialize(&bincode::serialize(&reverse).unwrap()).unwrap();
+        assert_eq!(decoded.root_hash(), forward.root_hash());
+    }
+}
@@ -0,0 +1,188 @@
+use crate::block::Block;
+use crate::instruction::StablecoinInstruction;
+use crate::state::GlobalState;
+use serde::{Deserialize, Serialize};
+
+/// Seconds covered by one `SupplyBucket`.
+pub const BUCKET_SECS: u64 = 3_600;
+
+/// Supply and peg figures of one committed block. Flash mints are left out:
+/// they are burned within the block they are minted in.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SupplySample {
+    pub timestamp: u64,
+    /// LUSD minted against collateral
+    pub minted: u64,
+    /// LUSD submitted for senior redemption, paid out or queued
+    pub redeemed: u64,
+    /// Reserve ratio after the block
+    pub reserve_ratio: f64,
+    /// LUSD-USD oracle price after the block (1e6 = $1)
+    pub lusd_price: u64,
+}
+
+impl SupplySample {
+    pub fn from_block(block: &Block, state: &GlobalState) -> Self {
+        let (mut minted, mut redeemed) = (0u64, 0u64);
+        for tx in &block.transactions {
+            match &tx.instruction {
+                StablecoinInstruction::MintSenior { amount, .. }
+                | StablecoinInstruction::MintSeniorWithCollateral { amount, .. }
+                | StablecoinInstruction::MintWithCreditScore { amount, .. }
+                | StablecoinInstruction::MintWithNativeCreditScore { amount, .. }
+                | StablecoinInstruction::DrawCreditLine { amount, .. } => {
+                    minted = minted.saturating_add(*amount)
+                }
+                StablecoinInstruction::RedeemSenior { amount }
+                | StablecoinInstruction::RedeemPosition { amount, .. }
+                | StablecoinInstruction::InstantRedeem { amount, .. }
+                | StablecoinInstruction::RepayCreditLine { amount, .. } => {
+                    redeemed = redeemed.saturating_add(*amount)
+                }
+                _ => {}
+            }
+        }
+        Self {
+            timestamp: block.header.timestamp,
+            minted,
+            redeemed,
+            reserve_ratio: state.reserve_ratio,
+            lusd_price: state
+                .oracle_prices
+                .get("LUSD-USD")
+                .copied()
+                .unwrap_or(1_000_000),
+        }
+    }
+
+    pub fn bucket(&self) -> u64 {
+        self.timestamp / BUCKET_SECS
+    }
+
+    pub fn peg_deviation_bps(&self) -> u64 {
+        self.lusd_price.abs_diff(1_000_000) / 100
+    }
+}
+
+/// Running totals of the canonical blocks whose timestamps fall in one
+/// `BUCKET_SECS` interval, updated as blocks join or leave the canonical
+/// chain.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SupplyBucket {
+    pub blocks: u64,
+    pub minted: u64,
+    pub redeemed: u64,
+    pub reserve_ratio_sum: f64,
+    /// Not lowered when a block is reorged out
+    pub max_peg_deviation_bps: u64,
+}
+
+impl SupplyBucket {
+    pub fn add(&mut self, sample: &SupplySample) {
+        self.blocks += 1;
+        self.minted = self.minted.saturating_add(sample.minted);
+        self.redeemed = self.redeemed.saturating_add(sample.redeemed);
+        self.reserve_ratio_sum += sample.reserve_ratio;
+        self.max_peg_deviation_bps = self.max_peg_deviation_bps.max(sample.peg_deviation_bps());
+    }
+
+    pub fn remove(&mut self, sample: &SupplySample) {
+        self.blocks = self.blocks.saturating_sub(1);
+        self.minted = self.minted.saturating_sub(sample.minted);
+        self.redeemed = self.redeemed.saturating_sub(sample.redeemed);
+        self.reserve_ratio_sum = if self.blocks == 0 {
+            0.0
+        } else {
+            self.reserve_ratio_sum - sample.reserve_ratio
+        };
+    }
+
+    /// Fold `other` (a different interval) into these totals.
+    pub fn merge(&mut self, other: &SupplyBucket) {
+        self.blocks += other.blocks;
+        self.minted = self.minted.saturating_add(other.minted);
+        self.redeemed = self.redeemed.saturating_add(other.redeemed);
+        self.reserve_ratio_sum += other.reserve_ratio_sum;
+        self.max_peg_deviation_bps = self.max_peg_deviation_bps.max(other.max_peg_deviation_bps);
+    }
+
+    pub fn average_reserve_ratio(&self) -> Option<f64> {
+        (self.blocks > 0).then(|| self.reserve_ratio_sum / self.blocks as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockHeader;
+    use crate::transaction::Transaction;
+
+    fn tx(instruction: StablecoinInstruction) -> Transaction {
+        Transaction {
+            sender: [1u8; 32],
+            nonce: 0,
+            instruction,
+            signature: Vec::new(),
+            gas_limit: 0,
+            gas_price: 0,
+        }
+    }
+
+    #[test]
+    fn buckets_add_and_retract_block_samples() {
+        let block = Block {
+            header: BlockHeader {
+                height: 1,
+                prev_hash: [0u8; 32],
+                transactions_root: [0u8; 32],
+                state_root: [0u8; 32],
+                timestamp: 7_300,
+                proposer: [0u8; 32],
+                validator_set_hash: [0u8; 32],
+                audit_hash: [0u8; 32],
+                signers_hash: [0u8; 32],
+            },
+            transactions: vec![
+                tx(StablecoinInstruction::MintSenior {
+                    amount: 1_000,
+                    collateral_amount: 1_200,
+                    proof: Vec::new(),
+                }),
+                tx(StablecoinInstruction::RedeemSenior { amount: 300 }),
+                tx(StablecoinInstruction::FlashBurn { amount: 500 }),
+            ],
+            votes: Vec::new(),
+        };
+        let mut state = GlobalState {
+            reserve_ratio: 1.5,
+            ..Default::default()
+        };
+        state.oracle_prices.insert("LUSD-USD".to_string(), 985_000);
+
+        let sample = SupplySample::from_block(&block, &state);
+        assert_eq!((sample.minted, sample.redeemed), (1_000, 300));
+        assert_eq!(sample.bucket(), 2);
+        assert_eq!(sample.peg_deviation_bps(), 150);
+
+        let mut bucket = SupplyBucket::default();
+        bucket.add(&sample);
+        bucket.add(&SupplySample {
+            reserve_ratio: 1.0,
+            lusd_price: 1_000_000,
+            ..sample.clone()
+        });
+        assert_eq!(bucket.blocks, 2);
+        assert_eq!(bucket.minted, 2_000);
+        assert_eq!(bucket.average_reserve_ratio(), Some(1.25));
+        assert_eq!(bucket.max_peg_deviation_bps, 150);
+
+        bucket.remove(&sample);
+        assert_eq!(
+            (bucket.blocks, bucket.minted, bucket.redeemed),
+            (1, 1_000, 300)
+        );
+        assert_eq!(bucket.average_reserve_ratio(), Some(1.0));
+        bucket.remove(&sample);
+        assert_eq!(bucket.average_reserve_ratio(), None);
+    }
+}
@@ -0,0 +1,304 @@
+//! Cost-basis lots and realized gains per account, kept off the consensus
+//! path by the node's optional tax lot indexer.
+//!
+//! Every increase of a tracked balance opens a lot at the block's market
+//! value and every decrease is a disposal, matched against the open lots
+//! oldest first (FIFO) or newest first (LIFO). Values are LUSD base units,
+//! from the `<SYMBOL>-USD` oracle feeds and the LJUN exchange rate.
+
+use crate::state::{AccountState, GlobalState};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LotMethod {
+    Fifo,
+    Lifo,
+}
+
+impl LotMethod {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "fifo" => Some(LotMethod::Fifo),
+            "lifo" => Some(LotMethod::Lifo),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LotMethod::Fifo => "fifo",
+            LotMethod::Lifo => "lifo",
+        }
+    }
+
+    /// Storage key tag; each method keeps its own ledgers.
+    pub fn tag(self) -> u8 {
+        match self {
+            LotMethod::Fifo => 0,
+            LotMethod::Lifo => 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LotAsset {
+    Ljun,
+    Lumina,
+    Custom(String),
+}
+
+impl fmt::Display for LotAsset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LotAsset::Ljun => f.write_str("LJUN"),
+            LotAsset::Lumina => f.write_str("LUMINA"),
+            LotAsset::Custom(symbol) => f.write_str(symbol),
+        }
+    }
+}
+
+impl LotAsset {
+    /// Market value of `quantity` in `state`; zero without a price feed.
+    pub fn value(&self, state: &GlobalState, quantity: u64) -> u64 {
+        let price = match self {
+            LotAsset::Ljun => return state.ljun_value_of(quantity).min(u64::MAX as u128) as u64,
+            LotAsset::Lumina => state.oracle_prices.get("LUMINA-USD"),
+            LotAsset::Custom(symbol) => state.oracle_prices.get(&format!("{}-USD", symbol)),
+        };
+        let price = price.copied().unwrap_or(0) as u128;
+        ((quantity as u128) * price / 1_000_000).min(u64::MAX as u128) as u64
+    }
+}
+
+/// Tracked balances of an account. Lots hold u64 quantities, so custom
+/// holdings beyond `u64::MAX` units are tracked as that.
+pub fn tracked_balances(account: &AccountState) -> BTreeMap<LotAsset, u64> {
+    let mut balances = BTreeMap::new();
+    balances.insert(LotAsset::Ljun, account.ljun_balance);
+    balances.insert(LotAsset::Lumina, account.lumina_balance);
+    for (symbol, amount) in &account.custom_balances {
+        let quantity = amount.to_u64().unwrap_or(u64::MAX);
+        balances.insert(LotAsset::Custom(symbol.clone()), quantity);
+    }
+    balances
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TaxLot {
+    pub acquired_height: u64,
+    pub acquired_at: u64,
+    pub quantity: u64,
+    /// Cost basis of the remaining quantity
+    pub cost: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Disposal {
+    pub height: u64,
+    pub timestamp: u64,
+    pub asset: LotAsset,
+    pub quantity: u64,
+    pub proceeds: u64,
+    /// Cost basis of the lots consumed; quantity beyond the open lots has
+    /// none
+    pub cost: u64,
+}
+
+impl Disposal {
+    pub fn gain(&self) -> i128 {
+        self.proceeds as i128 - self.cost as i128
+    }
+}
+
+/// Open lots and past disposals of one account under one method.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TaxLedger {
+    pub lots: BTreeMap<LotAsset, Vec<TaxLot>>,
+    pub disposals: Vec<Disposal>,
+}
+
+impl TaxLedger {
+    pub fn acquire(
+        &mut self,
+        asset: LotAsset,
+        quantity: u64,
+        cost: u64,
+        height: u64,
+        timestamp: u64,
+    ) {
+        self.lots.entry(asset).or_default().push(TaxLot {
+            acquired_height: height,
+            acquired_at: timestamp,
+            quantity,
+            cost,
+        });
+    }
+
+    pub fn dispose(
+        &mut self,
+        method: LotMethod,
+        asset: LotAsset,
+        quantity: u64,
+        proceeds: u64,
+        height: u64,
+        timestamp: u64,
+    ) {
+        let lots = self.lots.entry(asset.clone()).or_default();
+        let (mut left, mut cost) = (quantity, 0u64);
+        while left > 0 {
+            let lot = match method {
+                LotMethod::Fifo => lots.first_mut(),
+                LotMethod::Lifo => lots.last_mut(),
+            };
+            let Some(lot) = lot else {
+                break;
+            };
+            let take = left.min(lot.quantity);
+            let share = ((lot.cost as u128) * (take as u128) / (lot.quantity as u128)) as u64;
+            cost = cost.saturating_add(share);
+            lot.cost -= share;
+            lot.quantity -= take;
+            left -= take;
+            if lot.quantity == 0 {
+                match method {
+                    LotMethod::Fifo => lots.remove(0),
+                    LotMethod::Lifo => lots.pop().expect("lot"),
+                };
+            }
+        }
+        if lots.is_empty() {
+            self.lots.remove(&asset);
+        }
+        self.disposals.push(Disposal {
+            height,
+            timestamp,
+            asset,
+            quantity,
+            proceeds,
+            cost,
+        });
+    }
+
+    /// Record the change of one account over a block ending in `state`.
+    /// Returns whether any tracked balance moved.
+    pub fn apply(
+        &mut self,
+        method: LotMethod,
+        before: Option<&AccountState>,
+        after: &AccountState,
+        state: &GlobalState,
+        height: u64,
+        timestamp: u64,
+    ) -> bool {
+        let old = before.map(tracked_balances).unwrap_or_default();
+        let new = tracked_balances(after);
+        let mut assets: Vec<&LotAsset> = old.keys().chain(new.keys()).collect();
+        assets.sort();
+        assets.dedup();
+        let mut moved = false;
+        for asset in assets {
+            let from = old.get(asset).copied().unwrap_or(0);
+            let to = new.get(asset).copied().unwrap_or(0);
+            if to > from {
+                let quantity = to - from;
+                self.acquire(
+                    asset.clone(),
+                    quantity,
+                    asset.value(state, quantity),
+                    height,
+                    timestamp,
+                );
+            } else if from > to {
+                let quantity = from - to;
+                let proceeds = asset.value(state, quantity);
+                self.dispose(method, asset.clone(), quantity, proceeds, height, timestamp);
+            } else {
+                continue;
+            }
+            moved = true;
+        }
+        moved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gold() -> LotAsset {
+        LotAsset::Custom("GOLD".to_string())
+    }
+
+    fn ledger() -> TaxLedger {
+        let mut ledger = TaxLedger::default();
+        ledger.acquire(gold(), 10, 1_000, 1, 100);
+        ledger.acquire(gold(), 10, 3_000, 2, 200);
+        ledger
+    }
+
+    #[test]
+    fn disposals_consume_lots_in_method_order() {
+        let mut fifo = ledger();
+        fifo.dispose(LotMethod::Fifo, gold(), 15, 4_000, 3, 300);
+        assert_eq!(fifo.disposals[0].cost, 1_000 + 1_500);
+        assert_eq!(fifo.disposals[0].gain(), 1_500);
+        assert_eq!(fifo.lots[&gold()].len(), 1);
+        assert_eq!(
+            (fifo.lots[&gold()][0].quantity, fifo.lots[&gold()][0].cost),
+            (5, 1_500)
+        );
+
+        let mut lifo = ledger();
+        lifo.dispose(LotMethod::Lifo, gold(), 15, 4_000, 3, 300);
+        assert_eq!(lifo.disposals[0].cost, 3_000 + 500);
+        assert_eq!(
+            (lifo.lots[&gold()][0].quantity, lifo.lots[&gold()][0].cost),
+            (5, 500)
+        );
+
+        // Beyond the open lots there is no basis, and the asset is closed out.
+        lifo.dispose(LotMethod::Lifo, gold(), 8, 2_000, 4, 400);
+        assert_eq!(lifo.disposals[1].cost, 500);
+        assert!(!lifo.lots.contains_key(&gold()));
+    }
+
+    #[test]
+    fn balance_changes_are_priced_from_state() {
+        let mut state = GlobalState::default();
+        state
+            .oracle_prices
+            .insert("LUMINA-USD".to_string(), 2_000_000);
+        state.oracle_prices.insert("GOLD-USD".to_string(), 500_000);
+        let before = AccountState {
+            lumina_balance: 100,
+            custom_balances: [("GOLD".to_string(), 40.into())].into_iter().collect(),
+            ..Default::default()
+        };
+        let after = AccountState {
+            lumina_balance: 130,
+            custom_balances: [("GOLD".to_string(), 10.into())].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let mut ledger = TaxLedger::default();
+        assert!(ledger.apply(LotMethod::Fifo, None, &before, &state, 0, 0));
+        assert!(!ledger.apply(LotMethod::Fifo, Some(&before), &before, &state, 1, 10));
+        assert!(ledger.apply(LotMethod::Fifo, Some(&before), &after, &state, 2, 20));
+
+        let lumina = &ledger.lots[&LotAsset::Lumina];
+        assert_eq!(lumina.len(), 2);
+        assert_eq!(
+            (lumina[1].quantity, lumina[1].cost, lumina[1].acquired_at),
+            (30, 60, 20)
+        );
+        let sold = &ledger.disposals[0];
+        assert_eq!(
+            (sold.asset.clone(), sold.quantity, sold.proceeds, sold.cost),
+            (gold(), 30, 15, 15)
+        );
+        // LJUN never moved, so it has no lots.
+        assert!(!ledger.lots.contains_key(&LotAsset::Ljun));
+    }
+}
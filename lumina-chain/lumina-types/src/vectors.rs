@@ -0,0 +1,390 @@
+//! Conformance vectors for third-party SDKs: the encodings, signing bytes,
+//! ids and hashes this crate computes for fixed inputs. The published copy
+//! is `lumina-types/vectors/conformance.json`; after a deliberate encoding
+//! change, regenerate it with
+//! `LUMINA_WRITE_VECTORS=1 cargo test -p lumina-types vectors`.
+//!
+//! Byte strings are hex. Transactions are signed with Ed25519 over their
+//! signing bytes, which are deterministic, so the signatures are too.
+
+use crate::block::{Block, BlockHeader};
+use crate::instruction::{AssetType, CollateralAsset, StablecoinInstruction};
+use crate::state::{AccountState, GlobalState};
+use crate::transaction::Transaction;
+use ed25519_dalek::{Signer, SigningKey};
+use serde::Serialize;
+
+#[derive(Serialize, Debug)]
+pub struct ConformanceVectors {
+    pub transactions: Vec<TransactionVector>,
+    pub blocks: Vec<BlockVector>,
+    pub states: Vec<StateVector>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TransactionVector {
+    pub name: &'static str,
+    /// Ed25519 seed of the sender
+    pub secret_key: String,
+    pub transaction: Transaction,
+    /// bincode of the signed transaction, as gossiped and submitted
+    pub encoded: String,
+    pub signing_bytes: String,
+    pub signature: String,
+    /// blake3(signing_bytes || signature)
+    pub id: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BlockVector {
+    pub name: &'static str,
+    pub header: BlockHeader,
+    /// Names of the block's transactions among the transaction vectors
+    pub transactions: Vec<&'static str>,
+    pub header_bytes: String,
+    pub transactions_root: String,
+    /// blake3(header_bytes)
+    pub hash: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct StateVector {
+    pub name: &'static str,
+    pub accounts: Vec<AccountVector>,
+    /// `GlobalState::root_hash` of a default state holding these accounts
+    pub state_root: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AccountVector {
+    pub address: String,
+    pub account: AccountState,
+    /// bincode of the account, the value committed in the account trie
+    pub encoded: String,
+}
+
+fn key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+fn address(seed: u8) -> [u8; 32] {
+    key(seed).verifying_key().to_bytes()
+}
+
+fn sign(
+    name: &'static str,
+    seed: u8,
+    nonce: u64,
+    instruction: StablecoinInstruction,
+) -> (Transaction, TransactionVector) {
+    let mut tx = Transaction {
+        sender: address(seed),
+        nonce,
+        instruction,
+        signature: Vec::new(),
+        gas_limit: 100_000,
+        gas_price: 1,
+    };
+    let signing_bytes = tx.signing_bytes();
+    tx.signature = key(seed).sign(&signing_bytes).to_bytes().to_vec();
+    let vector = TransactionVector {
+        name,
+        secret_key: hex::encode([seed; 32]),
+        encoded: hex::encode(bincode::serialize(&tx).expect("tx serialization")),
+        signing_bytes: hex::encode(&signing_bytes),
+        signature: hex::encode(&tx.signature),
+        id: hex::encode(tx.id()),
+        transaction: tx.clone(),
+    };
+    (tx, vector)
+}
+
+fn state(name: &'static str, accounts: Vec<([u8; 32], AccountState)>) -> StateVector {
+    let mut state = GlobalState::default();
+    state.accounts.extend(accounts.iter().cloned());
+    StateVector {
+        name,
+        accounts: accounts
+            .into_iter()
+            .map(|(address, account)| AccountVector {
+                address: hex::encode(address),
+                encoded: hex::encode(bincode::serialize(&account).expect("account serialization")),
+                account,
+            })
+            .collect(),
+        state_root: hex::encode(state.root_hash()),
+    }
+}
+
+fn block(
+    name: &'static str,
+    mut header: BlockHeader,
+    transactions: &[(Transaction, &'static str)],
+) -> (Block, BlockVector) {
+    let txs: Vec<Transaction> = transactions.iter().map(|(tx, _)| tx.clone()).collect();
+    header.transactions_root = Block::transactions_root(&txs);
+    let block = Block {
+        header,
+        transactions: txs,
+        votes: Vec::new(),
+    };
+    let vector = BlockVector {
+        name,
+        header: block.header.clone(),
+        transactions: transactions.iter().map(|(_, name)| *name).collect(),
+        header_bytes: hex::encode(
+            bincode::serialize(&block.header).expect("block header serialization"),
+        ),
+        transactions_root: hex::encode(block.header.transactions_root),
+        hash: hex::encode(block.hash()),
+    };
+    (block, vector)
+}
+
+/// Every vector, built from fixed keys and inputs.
+pub fn conformance_vectors() -> ConformanceVectors {
+    let signed = [
+        sign(
+            "transfer_lusd",
+            1,
+            0,
+            StablecoinInstruction::Transfer {
+                to: address(2),
+                amount: 1_000_000,
+                asset: AssetType::LUSD,
+            },
+        ),
+        sign(
+            "transfer_custom_with_memo",
+            2,
+            7,
+            StablecoinInstruction::TransferWithMemo {
+                to: address(1),
+                amount: 42,
+                asset: AssetType::Custom("GOLD".to_string()),
+                memo_hash: [0xAB; 32],
+            },
+        ),
+        sign(
+            "mint_senior",
+            1,
+            1,
+            StablecoinInstruction::MintSenior {
+                amount: 500,
+                collateral_amount: 1_000,
+                proof: Vec::new(),
+            },
+        ),
+        sign(
+            "vote",
+            3,
+            4,
+            StablecoinInstruction::Vote {
+                proposal_id: 9,
+                approve: true,
+            },
+        ),
+        sign(
+            "designate_beneficiary",
+            2,
+            8,
+            StablecoinInstruction::DesignateBeneficiary {
+                beneficiary: address(3),
+                inactivity_blocks: 1_000_000,
+            },
+        ),
+        sign(
+            "upload_compliance_circuit",
+            3,
+            5,
+            StablecoinInstruction::UploadComplianceCircuit {
+                circuit_id: 7,
+                verifier_key: vec![1, 2, 3],
+            },
+        ),
+        sign(
+            "mint_senior_with_collateral",
+            2,
+            9,
+            StablecoinInstruction::MintSeniorWithCollateral {
+                amount: 500,
+                collateral_asset: CollateralAsset::Custom("GOLD".to_string()),
+                collateral_amount: 1_000,
+                proof: Vec::new(),
+            },
+        ),
+    ];
+
+    let accounts = vec![
+        (
+            address(1),
+            AccountState {
+                nonce: 2,
+                lusd_balance: 500,
+                lumina_balance: 99_000,
+                ..Default::default()
+            },
+        ),
+        (
+            address(2),
+            AccountState {
+                nonce: 8,
+                lusd_balance: 1_000_000,
+                custom_balances: [("GOLD".to_string(), 58.into())].into_iter().collect(),
+                ..Default::default()
+            },
+        ),
+    ];
+    let states = vec![
+        state("empty", Vec::new()),
+        state("one_account", accounts[..1].to_vec()),
+        state("two_accounts", accounts),
+    ];
+
+    let named = |i: usize| (signed[i].0.clone(), signed[i].1.name);
+    let (first, empty) = block(
+        "empty",
+        BlockHeader {
+            height: 1,
+            prev_hash: [0u8; 32],
+            transactions_root: [0u8; 32],
+            state_root: [0u8; 32],
+            timestamp: 1_767_225_600,
+            proposer: address(3),
+            validator_set_hash: [0x11; 32],
+            audit_hash: [0u8; 32],
+            signers_hash: [0u8; 32],
+        },
+        &[],
+    );
+    let (_, three) = block(
+        "three_transactions",
+        BlockHeader {
+            height: 2,
+            prev_hash: first.hash(),
+            transactions_root: [0u8; 32],
+            state_root: GlobalState::default().root_hash(),
+            timestamp: 1_767_225_605,
+            proposer: address(3),
+            validator_set_hash: [0x11; 32],
+            audit_hash: [0x22; 32],
+            signers_hash: [0u8; 32],
+        },
+        &[named(0), named(2), named(1)],
+    );
+
+    ConformanceVectors {
+        transactions: signed.into_iter().map(|(_, vector)| vector).collect(),
+        blocks: vec![empty, three],
+        states,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    const PUBLISHED: &str = include_str!("../vectors/conformance.json");
+
+    #[test]
+    fn published_vectors_match_this_crate() {
+        let generated = serde_json::to_string_pretty(&conformance_vectors()).unwrap() + "\n";
+        if std::env::var_os("LUMINA_WRITE_VECTORS").is_some() {
+            let path = concat!(env!("CARGO_MANIFEST_DIR"), "/vectors/conformance.json");
+            std::fs::write(path, &generated).unwrap();
+            return;
+        }
+        assert!(
+            generated == PUBLISHED,
+            "encodings changed; regenerate the vectors if that was deliberate"
+        );
+    }
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        hex::decode(hex).unwrap()
+    }
+
+    #[test]
+    fn vectors_check_out_from_their_own_bytes() {
+        let vectors = conformance_vectors();
+        for v in &vectors.transactions {
+            let decoded: Transaction = bincode::deserialize(&bytes(&v.encoded)).unwrap();
+            assert_eq!(decoded, v.transaction, "{}", v.name);
+            assert_eq!(bytes(&v.signing_bytes), decoded.signing_bytes());
+
+            let seed: [u8; 32] = bytes(&v.secret_key).try_into().unwrap();
+            let sender = SigningKey::from_bytes(&seed).verifying_key();
+            assert_eq!(sender.to_bytes(), decoded.sender);
+            let signature = Signature::from_slice(&bytes(&v.signature)).unwrap();
+            VerifyingKey::from_bytes(&decoded.sender)
+                .unwrap()
+                .verify_strict(&bytes(&v.signing_bytes), &signature)
+                .unwrap();
+
+            let mut id = blake3::Hasher::new();
+            id.update(&bytes(&v.signing_bytes));
+            id.update(&bytes(&v.signature));
+            assert_eq!(hex::encode(id.finalize().as_bytes()), v.id);
+        }
+
+        for v in &vectors.blocks {
+            let header_bytes = bytes(&v.header_bytes);
+            let header: BlockHeader = bincode::deserialize(&header_bytes).unwrap();
+            assert_eq!(header, v.header, "{}", v.name);
+            assert_eq!(hex::encode(blake3::hash(&header_bytes).as_bytes()), v.hash);
+            assert_eq!(hex::encode(header.transactions_root), v.transactions_root);
+
+            let ids: Vec<[u8; 32]> = v
+                .transactions
+                .iter()
+                .map(|name| {
+                    let tx = vectors
+                        .transactions
+                        .iter()
+                        .find(|t| t.name == *name)
+                        .unwrap();
+                    bytes(&tx.id).try_into().unwrap()
+                })
+                .collect();
+            // Pairs hash left || right, an odd node out pairing with itself.
+            let mut level = ids;
+            let root = loop {
+                match level.len() {
+                    0 => break [0u8; 32],
+                    1 => break level[0],
+                    _ => {
+                        level = level
+                            .chunks(2)
+                            .map(|pair| {
+                                let right = pair.get(1).unwrap_or(&pair[0]);
+                                *blake3::hash(&[pair[0], *right].concat()).as_bytes()
+                            })
+                            .collect();
+                    }
+                }
+            };
+            assert_eq!(hex::encode(root), v.transactions_root, "{}", v.name);
+        }
+
+        for v in &vectors.states {
+            for a in &v.accounts {
+                let decoded: AccountState = bincode::deserialize(&bytes(&a.encoded)).unwrap();
+                assert_eq!(decoded.nonce, a.account.nonce);
+                assert_eq!(decoded.lusd_balance, a.account.lusd_balance);
+                assert_eq!(decoded.custom_balances, a.account.custom_balances);
+            }
+        }
+        let roots: Vec<&str> = vectors
+            .states
+            .iter()
+            .map(|s| s.state_root.as_str())
+            .collect();
+        assert_eq!(roots.len(), 3);
+        assert!(roots[0] != roots[1] && roots[1] != roots[2]);
+        assert_eq!(
+            vectors.blocks[1].header.prev_hash,
+            bytes(&vectors.blocks[0].hash)[..]
+        );
+    }
+}
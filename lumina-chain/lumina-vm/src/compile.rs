@@ -0,0 +1,500 @@
+use crate::{HostFn, MAX_CODE_SIZE, MAX_FUNCTIONS, MAX_LOCALS, MAX_MEMORY_PAGES, PAGE_SIZE};
+use anyhow::{anyhow, bail, Result};
+use wasmparser::{
+    BlockType, DataKind, ExternalKind, FuncType, Operator, Parser, Payload, TypeRef, ValType,
+    Validator, WasmFeatures,
+};
+
+/// Integer-only WebAssembly 1.0 plus sign extension and multi-value. Floats,
+/// SIMD, threads, reference types and bulk memory are all rejected.
+fn features() -> WasmFeatures {
+    WasmFeatures::GC_TYPES
+        | WasmFeatures::MUTABLE_GLOBAL
+        | WasmFeatures::SIGN_EXTENSION
+        | WasmFeatures::MULTI_VALUE
+}
+
+/// Integer operation, shared by the i32 and i64 forms.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum IntOp {
+    Eqz,
+    Clz,
+    Ctz,
+    Popcnt,
+    Extend8S,
+    Extend16S,
+    Extend32S,
+    Add,
+    Sub,
+    Mul,
+    DivS,
+    DivU,
+    RemS,
+    RemU,
+    And,
+    Or,
+    Xor,
+    Shl,
+    ShrS,
+    ShrU,
+    Rotl,
+    Rotr,
+    Eq,
+    Ne,
+    LtS,
+    LtU,
+    GtS,
+    GtU,
+    LeS,
+    LeU,
+    GeS,
+    GeU,
+}
+
+impl IntOp {
+    pub(crate) fn is_unary(self) -> bool {
+        matches!(
+            self,
+            IntOp::Eqz
+                | IntOp::Clz
+                | IntOp::Ctz
+                | IntOp::Popcnt
+                | IntOp::Extend8S
+                | IntOp::Extend16S
+                | IntOp::Extend32S
+        )
+    }
+}
+
+/// A load or store of `bytes` bytes at `offset` past the address operand.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MemOp {
+    pub offset: u32,
+    pub bytes: u8,
+    pub signed: bool,
+    /// Loads into an i64 rather than an i32
+    pub wide: bool,
+}
+
+/// A validated operator with its branch targets resolved.
+#[derive(Debug, Clone)]
+pub(crate) enum Op {
+    Unreachable,
+    Nop,
+    Block {
+        params: usize,
+        results: usize,
+        end: usize,
+    },
+    Loop {
+        params: usize,
+    },
+    If {
+        params: usize,
+        results: usize,
+        else_at: Option<usize>,
+        end: usize,
+    },
+    Else {
+        end: usize,
+    },
+    End,
+    Br(u32),
+    BrIf(u32),
+    BrTable(Box<[u32]>, u32),
+    Return,
+    Call(u32),
+    Drop,
+    Select,
+    LocalGet(u32),
+    LocalSet(u32),
+    LocalTee(u32),
+    GlobalGet(u32),
+    GlobalSet(u32),
+    Load(MemOp),
+    Store(MemOp),
+    MemorySize,
+    MemoryGrow,
+    Const(u64),
+    I32(IntOp),
+    I64(IntOp),
+    WrapI64,
+    ExtendI32S,
+    ExtendI32U,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Signature {
+    pub params: usize,
+    pub results: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct Function {
+    pub sig: Signature,
+    /// Declared locals beyond the parameters
+    pub locals: usize,
+    pub ops: Vec<Op>,
+}
+
+/// A contract after validation and lowering.
+#[derive(Debug)]
+pub(crate) struct Compiled {
+    pub imports: Vec<HostFn>,
+    pub functions: Vec<Function>,
+    pub globals: Vec<u64>,
+    pub memory_pages: u32,
+    pub memory_max_pages: u32,
+    pub data: Vec<(u32, Vec<u8>)>,
+    /// Index of the exported `call` entry point
+    pub entry: u32,
+}
+
+pub(crate) fn compile(code: &[u8]) -> Result<Compiled> {
+    if code.len() > MAX_CODE_SIZE {
+        bail!("Contract code too large");
+    }
+    Validator::new_with_features(features())
+        .validate_all(code)
+        .map_err(|e| anyhow!("Invalid contract module: {}", e))?;
+
+    let mut types: Vec<FuncType> = Vec::new();
+    let mut imports = Vec::new();
+    let mut declared: Vec<u32> = Vec::new();
+    let mut functions = Vec::new();
+    let mut globals = Vec::new();
+    let mut memory = None;
+    let mut data = Vec::new();
+    let mut entry = None;
+
+    for payload in Parser::new(0).parse_all(code) {
+        match payload? {
+            Payload::TypeSection(reader) => {
+                for ty in reader.into_iter_err_on_gc_types() {
+                    types.push(ty?);
+                }
+            }
+            Payload::ImportSection(reader) => {
+                for import in reader.into_imports() {
+                    let import = import?;
+                    let TypeRef::Func(ty) = import.ty else {
+                        bail!("Contracts may only import host functions");
+                    };
+                    let host = HostFn::lookup(import.module, import.name)
+                        .ok_or_else(|| anyhow!("Unknown host function {}", import.name))?;
+                    let (params, results) = host.signature();
+                    let ty = &types[ty as usize];
+                    if ty.params() != params || ty.results() != results {
+                        bail!("Wrong signature for host function {}", import.name);
+                    }
+                    imports.push(host);
+                }
+            }
+            Payload::FunctionSection(reader) => {
+                for ty in reader {
+                    declared.push(ty?);
+                }
+                if imports.len() + declared.len() > MAX_FUNCTIONS {
+                    bail!("Too many functions");
+                }
+            }
+            Payload::TableSection(_) | Payload::ElementSection(_) => {
+                bail!("Tables are not supported");
+            }
+            Payload::StartSection { .. } => bail!("Start functions are not supported"),
+            Payload::MemorySection(reader) => {
+                for ty in reader {
+                    let ty = ty?;
+                    if ty.initial > MAX_MEMORY_PAGES as u64 {
+                        bail!("Contract memory too large");
+                    }
+                    let max = ty.maximum.unwrap_or(u64::MAX).min(MAX_MEMORY_PAGES as u64);
+                    memory = Some((ty.initial as u32, max as u32));
+                }
+            }
+            Payload::GlobalSection(reader) => {
+                for global in reader {
+                    globals.push(const_value(&global?.init_expr)?);
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    if export.name == "call" && export.kind == ExternalKind::Func {
+                        entry = Some(export.index);
+                    }
+                }
+            }
+            Payload::DataSection(reader) => {
+                for segment in reader {
+                    let segment = segment?;
+                    let DataKind::Active { offset_expr, .. } = segment.kind else {
+                        bail!("Passive data segments are not supported");
+                    };
+                    let offset = const_value(&offset_expr)? as u32;
+                    let pages = memory.map(|(pages, _)| pages).unwrap_or(0);
+                    if offset as usize + segment.data.len() > pages as usize * PAGE_SIZE {
+                        bail!("Data segment out of bounds");
+                    }
+                    data.push((offset, segment.data.to_vec()));
+                }
+            }
+            Payload::CodeSectionEntry(body) => {
+                let ty = &types[declared[functions.len()] as usize];
+                let sig = Signature {
+                    params: ty.params().len(),
+                    results: ty.results().len(),
+                };
+                let mut locals = 0usize;
+                for entry in body.get_locals_reader()? {
+                    locals += entry?.0 as usize;
+                }
+                if sig.params + locals > MAX_LOCALS {
+                    bail!("Too many locals");
+                }
+                let mut reader = body.get_operators_reader()?;
+                let mut ops = Vec::new();
+                while !reader.eof() {
+                    ops.push(lower(reader.read()?, &types)?);
+                }
+                resolve_branches(&mut ops)?;
+                functions.push(Function { sig, locals, ops });
+            }
+            _ => {}
+        }
+    }
+
+    let entry = entry.ok_or_else(|| anyhow!("Contract must export a call function"))?;
+    let sig = entry
+        .checked_sub(imports.len() as u32)
+        .and_then(|i| functions.get(i as usize))
+        .map(|f| &f.sig)
+        .ok_or_else(|| anyhow!("Contract call export must be a defined function"))?;
+    if sig.params != 0 || sig.results != 1 {
+        bail!("Contract call must have type [] -> [i32]");
+    }
+    let (memory_pages, memory_max_pages) = memory.unwrap_or((0, 0));
+    Ok(Compiled {
+        imports,
+        functions,
+        globals,
+        memory_pages,
+        memory_max_pages,
+        data,
+        entry,
+    })
+}
+
+fn const_value(expr: &wasmparser::ConstExpr) -> Result<u64> {
+    let mut reader = expr.get_operators_reader();
+    let value = match reader.read()? {
+        Operator::I32Const { value } => value as u32 as u64,
+        Operator::I64Const { value } => value as u64,
+        _ => bail!("Unsupported constant expression"),
+    };
+    match reader.read()? {
+        Operator::End => Ok(value),
+        _ => bail!("Unsupported constant expression"),
+    }
+}
+
+fn block_arity(ty: BlockType, types: &[FuncType]) -> (usize, usize) {
+    match ty {
+        BlockType::Empty => (0, 0),
+        BlockType::Type(_) => (0, 1),
+        BlockType::FuncType(i) => {
+            let ty = &types[i as usize];
+            (ty.params().len(), ty.results().len())
+        }
+    }
+}
+
+fn load(memarg: wasmparser::MemArg, bytes: u8, signed: bool, wide: bool) -> Op {
+    Op::Load(MemOp {
+        offset: memarg.offset as u32,
+        bytes,
+        signed,
+        wide,
+    })
+}
+
+fn store(memarg: wasmparser::MemArg, bytes: u8) -> Op {
+    Op::Store(MemOp {
+        offset: memarg.offset as u32,
+        bytes,
+        signed: false,
+        wide: false,
+    })
+}
+
+fn lower(op: Operator, types: &[FuncType]) -> Result<Op> {
+    use IntOp::*;
+    use Operator as O;
+    Ok(match op {
+        O::Unreachable => Op::Unreachable,
+        O::Nop => Op::Nop,
+        O::Block { blockty } => {
+            let (params, results) = block_arity(blockty, types);
+            Op::Block {
+                params,
+                results,
+                end: 0,
+            }
+        }
+        O::Loop { blockty } => Op::Loop {
+            params: block_arity(blockty, types).0,
+        },
+        O::If { blockty } => {
+            let (params, results) = block_arity(blockty, types);
+            Op::If {
+                params,
+                results,
+                else_at: None,
+                end: 0,
+            }
+        }
+        O::Else => Op::Else { end: 0 },
+        O::End => Op::End,
+        O::Br { relative_depth } => Op::Br(relative_depth),
+        O::BrIf { relative_depth } => Op::BrIf(relative_depth),
+        O::BrTable { targets } => {
+            let default = targets.default();
+            let targets = targets.targets().collect::<Result<Vec<_>, _>>()?;
+            Op::BrTable(targets.into_boxed_slice(), default)
+        }
+        O::Return => Op::Return,
+        O::Call { function_index } => Op::Call(function_index),
+        O::Drop => Op::Drop,
+        O::Select => Op::Select,
+        O::TypedSelect {
+            ty: ValType::I32 | ValType::I64,
+        } => Op::Select,
+        O::LocalGet { local_index } => Op::LocalGet(local_index),
+        O::LocalSet { local_index } => Op::LocalSet(local_index),
+        O::LocalTee { local_index } => Op::LocalTee(local_index),
+        O::GlobalGet { global_index } => Op::GlobalGet(global_index),
+        O::GlobalSet { global_index } => Op::GlobalSet(global_index),
+        O::I32Load { memarg } => load(memarg, 4, false, false),
+        O::I64Load { memarg } => load(memarg, 8, false, true),
+        O::I32Load8S { memarg } => load(memarg, 1, true, false),
+        O::I32Load8U { memarg } => load(memarg, 1, false, false),
+        O::I32Load16S { memarg } => load(memarg, 2, true, false),
+        O::I32Load16U { memarg } => load(memarg, 2, false, false),
+        O::I64Load8S { memarg } => load(memarg, 1, true, true),
+        O::I64Load8U { memarg } => load(memarg, 1, false, true),
+        O::I64Load16S { memarg } => load(memarg, 2, true, true),
+        O::I64Load16U { memarg } => load(memarg, 2, false, true),
+        O::I64Load32S { memarg } => load(memarg, 4, true, true),
+        O::I64Load32U { memarg } => load(memarg, 4, false, true),
+        O::I32Store { memarg } | O::I64Store32 { memarg } => store(memarg, 4),
+        O::I64Store { memarg } => store(memarg, 8),
+        O::I32Store8 { memarg } | O::I64Store8 { memarg } => store(memarg, 1),
+        O::I32Store16 { memarg } | O::I64Store16 { memarg } => store(memarg, 2),
+        O::MemorySize { .. } => Op::MemorySize,
+        O::MemoryGrow { .. } => Op::MemoryGrow,
+        O::I32Const { value } => Op::Const(value as u32 as u64),
+        O::I64Const { value } => Op::Const(value as u64),
+        O::I32WrapI64 => Op::WrapI64,
+        O::I64ExtendI32S => Op::ExtendI32S,
+        O::I64ExtendI32U => Op::ExtendI32U,
+
+        O::I32Eqz => Op::I32(Eqz),
+        O::I32Clz => Op::I32(Clz),
+        O::I32Ctz => Op::I32(Ctz),
+        O::I32Popcnt => Op::I32(Popcnt),
+        O::I32Extend8S => Op::I32(Extend8S),
+        O::I32Extend16S => Op::I32(Extend16S),
+        O::I32Add => Op::I32(Add),
+        O::I32Sub => Op::I32(Sub),
+        O::I32Mul => Op::I32(Mul),
+        O::I32DivS => Op::I32(DivS),
+        O::I32DivU => Op::I32(DivU),
+        O::I32RemS => Op::I32(RemS),
+        O::I32RemU => Op::I32(RemU),
+        O::I32And => Op::I32(And),
+        O::I32Or => Op::I32(Or),
+        O::I32Xor => Op::I32(Xor),
+        O::I32Shl => Op::I32(Shl),
+        O::I32ShrS => Op::I32(ShrS),
+        O::I32ShrU => Op::I32(ShrU),
+        O::I32Rotl => Op::I32(Rotl),
+        O::I32Rotr => Op::I32(Rotr),
+        O::I32Eq => Op::I32(Eq),
+        O::I32Ne => Op::I32(Ne),
+        O::I32LtS => Op::I32(LtS),
+        O::I32LtU => Op::I32(LtU),
+        O::I32GtS => Op::I32(GtS),
+        O::I32GtU => Op::I32(GtU),
+        O::I32LeS => Op::I32(LeS),
+        O::I32LeU => Op::I32(LeU),
+        O::I32GeS => Op::I32(GeS),
+        O::I32GeU => Op::I32(GeU),
+
+        O::I64Eqz => Op::I64(Eqz),
+        O::I64Clz => Op::I64(Clz),
+        O::I64Ctz => Op::I64(Ctz),
+        O::I64Popcnt => Op::I64(Popcnt),
+        O::I64Extend8S => Op::I64(Extend8S),
+        O::I64Extend16S => Op::I64(Extend16S),
+        O::I64Extend32S => Op::I64(Extend32S),
+        O::I64Add => Op::I64(Add),
+        O::I64Sub => Op::I64(Sub),
+        O::I64Mul => Op::I64(Mul),
+        O::I64DivS => Op::I64(DivS),
+        O::I64DivU => Op::I64(DivU),
+        O::I64RemS => Op::I64(RemS),
+        O::I64RemU => Op::I64(RemU),
+        O::I64And => Op::I64(And),
+        O::I64Or => Op::I64(Or),
+        O::I64Xor => Op::I64(Xor),
+        O::I64Shl => Op::I64(Shl),
+        O::I64ShrS => Op::I64(ShrS),
+        O::I64ShrU => Op::I64(ShrU),
+        O::I64Rotl => Op::I64(Rotl),
+        O::I64Rotr => Op::I64(Rotr),
+        O::I64Eq => Op::I64(Eq),
+        O::I64Ne => Op::I64(Ne),
+        O::I64LtS => Op::I64(LtS),
+        O::I64LtU => Op::I64(LtU),
+        O::I64GtS => Op::I64(GtS),
+        O::I64GtU => Op::I64(GtU),
+        O::I64LeS => Op::I64(LeS),
+        O::I64LeU => Op::I64(LeU),
+        O::I64GeS => Op::I64(GeS),
+        O::I64GeU => Op::I64(GeU),
+
+        other => bail!("Unsupported instruction {:?}", other),
+    })
+}
+
+/// Fill in the `end`/`else` positions of every structured block.
+fn resolve_branches(ops: &mut [Op]) -> Result<()> {
+    let mut open: Vec<usize> = Vec::new();
+    for i in 0..ops.len() {
+        match ops[i] {
+            Op::Block { .. } | Op::Loop { .. } | Op::If { .. } => open.push(i),
+            Op::Else { .. } => {
+                let start = *open.last().ok_or_else(|| anyhow!("Unbalanced else"))?;
+                if let Op::If { else_at, .. } = &mut ops[start] {
+                    *else_at = Some(i);
+                }
+            }
+            Op::End => {
+                // The function body's own `end` closes no block.
+                let Some(start) = open.pop() else { continue };
+                match &mut ops[start] {
+                    Op::Block { end, .. } => *end = i,
+                    Op::If { else_at, end, .. } => {
+                        *end = i;
+                        if let Some(e) = *else_at {
+                            ops[e] = Op::Else { end: i };
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
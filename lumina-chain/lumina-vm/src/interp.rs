@@ -0,0 +1,416 @@
+use crate::compile::{IntOp, MemOp, Op};
+use crate::*;
+use anyhow::{anyhow, bail, Result};
+
+/// A branch target: where the stack is cut back to and how many values
+/// travel with the branch.
+#[derive(Clone, Copy)]
+struct Label {
+    height: usize,
+    arity: usize,
+    target: usize,
+    is_loop: bool,
+}
+
+struct Instance<'a> {
+    module: &'a compile::Compiled,
+    host: &'a mut dyn Host,
+    memory: Vec<u8>,
+    max_pages: u32,
+    globals: Vec<u64>,
+    gas_left: u64,
+    depth: usize,
+}
+
+pub(crate) fn run(module: &Module, host: &mut dyn Host, gas_limit: u64) -> Result<Outcome> {
+    let compiled = &module.compiled;
+    let mut instance = Instance {
+        module: compiled,
+        host,
+        memory: Vec::new(),
+        max_pages: compiled.memory_max_pages,
+        globals: compiled.globals.clone(),
+        gas_left: gas_limit,
+        depth: 0,
+    };
+    instance.charge((module.code_len as u64).saturating_mul(GAS_PER_CODE_BYTE))?;
+    instance.grow(compiled.memory_pages)?;
+    for (offset, bytes) in &compiled.data {
+        let start = *offset as usize;
+        instance.memory[start..start + bytes.len()].copy_from_slice(bytes);
+    }
+    let results = instance.invoke(compiled.entry, Vec::new())?;
+    Ok(Outcome {
+        status: results[0] as u32,
+        gas_used: gas_limit - instance.gas_left,
+    })
+}
+
+impl Instance<'_> {
+    fn charge(&mut self, gas: u64) -> Result<()> {
+        self.gas_left = self
+            .gas_left
+            .checked_sub(gas)
+            .ok_or_else(|| anyhow!("Out of gas"))?;
+        Ok(())
+    }
+
+    fn pages(&self) -> u32 {
+        (self.memory.len() / PAGE_SIZE) as u32
+    }
+
+    fn grow(&mut self, delta: u32) -> Result<()> {
+        self.charge(delta as u64 * GAS_PER_MEMORY_PAGE)?;
+        self.memory
+            .resize(self.memory.len() + delta as usize * PAGE_SIZE, 0);
+        Ok(())
+    }
+
+    fn range(&self, addr: u64, len: u64) -> Result<std::ops::Range<usize>> {
+        let end = addr
+            .checked_add(len)
+            .filter(|e| *e <= self.memory.len() as u64);
+        match end {
+            Some(end) => Ok(addr as usize..end as usize),
+            None => bail!("Out of bounds memory access"),
+        }
+    }
+
+    fn read32(&self, ptr: u64) -> Result<[u8; 32]> {
+        let range = self.range(ptr, 32)?;
+        Ok(self.memory[range].try_into().expect("32-byte range"))
+    }
+
+    fn write(&mut self, ptr: u64, bytes: &[u8]) -> Result<()> {
+        self.charge(bytes.len() as u64 * GAS_PER_BYTE_COPIED)?;
+        let range = self.range(ptr, bytes.len() as u64)?;
+        self.memory[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    fn invoke(&mut self, func: u32, args: Vec<u64>) -> Result<Vec<u64>> {
+        let module = self.module;
+        let imported = module.imports.len();
+        if (func as usize) < imported {
+            return self.host_call(module.imports[func as usize], &args);
+        }
+        if self.depth >= MAX_CALL_DEPTH {
+            bail!("Call stack exhausted");
+        }
+        self.depth += 1;
+        let function = &module.functions[func as usize - imported];
+        let ops = &function.ops;
+
+        let mut locals = args;
+        locals.resize(function.sig.params + function.locals, 0);
+        let mut stack: Vec<u64> = Vec::new();
+        let mut labels = vec![Label {
+            height: 0,
+            arity: function.sig.results,
+            target: ops.len(),
+            is_loop: false,
+        }];
+        let mut pc = 0;
+
+        macro_rules! pop {
+            () => {
+                stack.pop().expect("validated stack")
+            };
+        }
+
+        while pc < ops.len() {
+            self.charge(GAS_PER_OP)?;
+            let mut next = pc + 1;
+            match &ops[pc] {
+                Op::Unreachable => bail!("Unreachable executed"),
+                Op::Nop => {}
+                Op::Block {
+                    params,
+                    results,
+                    end,
+                } => labels.push(Label {
+                    height: stack.len() - params,
+                    arity: *results,
+                    target: end + 1,
+                    is_loop: false,
+                }),
+                Op::Loop { params } => labels.push(Label {
+                    height: stack.len() - params,
+                    arity: *params,
+                    target: pc + 1,
+                    is_loop: true,
+                }),
+                Op::If {
+                    params,
+                    results,
+                    else_at,
+                    end,
+                } => {
+                    let taken = pop!() != 0;
+                    if taken || else_at.is_some() {
+                        labels.push(Label {
+                            height: stack.len() - params,
+                            arity: *results,
+                            target: end + 1,
+                            is_loop: false,
+                        });
+                    }
+                    if !taken {
+                        next = else_at.map_or(end + 1, |e| e + 1);
+                    }
+                }
+                // Falling out of the then-arm skips to the shared `end`.
+                Op::Else { end } => next = *end,
+                Op::End => {
+                    labels.pop();
+                }
+                Op::Br(depth) => next = branch(&mut stack, &mut labels, *depth),
+                Op::BrIf(depth) => {
+                    if pop!() != 0 {
+                        next = branch(&mut stack, &mut labels, *depth);
+                    }
+                }
+                Op::BrTable(targets, default) => {
+                    let i = pop!() as u32 as usize;
+                    let depth = targets.get(i).copied().unwrap_or(*default);
+                    next = branch(&mut stack, &mut labels, depth);
+                }
+                Op::Return => {
+                    let depth = labels.len() as u32 - 1;
+                    next = branch(&mut stack, &mut labels, depth);
+                }
+                Op::Call(callee) => {
+                    let sig = self.signature(*callee);
+                    let args = stack.split_off(stack.len() - sig.0);
+                    let results = self.invoke(*callee, args)?;
+                    stack.extend(results);
+                }
+                Op::Drop => {
+                    pop!();
+                }
+                Op::Select => {
+                    let cond = pop!();
+                    let b = pop!();
+                    let a = pop!();
+                    stack.push(if cond != 0 { a } else { b });
+                }
+                Op::LocalGet(i) => stack.push(locals[*i as usize]),
+                Op::LocalSet(i) => locals[*i as usize] = pop!(),
+                Op::LocalTee(i) => locals[*i as usize] = *stack.last().expect("validated stack"),
+                Op::GlobalGet(i) => stack.push(self.globals[*i as usize]),
+                Op::GlobalSet(i) => self.globals[*i as usize] = pop!(),
+                Op::Load(op) => {
+                    let addr = pop!();
+                    stack.push(self.load(addr, op)?);
+                }
+                Op::Store(op) => {
+                    let value = pop!();
+                    let addr = pop!() as u32 as u64 + op.offset as u64;
+                    let bytes = value.to_le_bytes();
+                    let range = self.range(addr, op.bytes as u64)?;
+                    self.memory[range].copy_from_slice(&bytes[..op.bytes as usize]);
+                }
+                Op::MemorySize => stack.push(self.pages() as u64),
+                Op::MemoryGrow => {
+                    let delta = pop!() as u32;
+                    let old = self.pages();
+                    if old as u64 + delta as u64 > self.max_pages as u64 {
+                        stack.push(u32::MAX as u64);
+                    } else {
+                        self.grow(delta)?;
+                        stack.push(old as u64);
+                    }
+                }
+                Op::Const(v) => stack.push(*v),
+                Op::I32(op) => {
+                    let v = if op.is_unary() {
+                        int32(*op, pop!() as u32, 0)?
+                    } else {
+                        let b = pop!() as u32;
+                        int32(*op, pop!() as u32, b)?
+                    };
+                    stack.push(v as u64);
+                }
+                Op::I64(op) => {
+                    let v = if op.is_unary() {
+                        int64(*op, pop!(), 0)?
+                    } else {
+                        let b = pop!();
+                        int64(*op, pop!(), b)?
+                    };
+                    stack.push(v);
+                }
+                Op::WrapI64 => {
+                    let v = pop!() as u32;
+                    stack.push(v as u64);
+                }
+                Op::ExtendI32S => {
+                    let v = pop!() as u32 as i32;
+                    stack.push(v as i64 as u64);
+                }
+                Op::ExtendI32U => {}
+            }
+            pc = next;
+        }
+
+        self.depth -= 1;
+        Ok(stack.split_off(stack.len() - function.sig.results))
+    }
+
+    fn signature(&self, func: u32) -> (usize, usize) {
+        let imported = self.module.imports.len();
+        match self.module.imports.get(func as usize) {
+            Some(host) => {
+                let (params, results) = host.signature();
+                (params.len(), results.len())
+            }
+            None => {
+                let sig = &self.module.functions[func as usize - imported].sig;
+                (sig.params, sig.results)
+            }
+        }
+    }
+
+    fn load(&self, addr: u64, op: &MemOp) -> Result<u64> {
+        let addr = addr as u32 as u64 + op.offset as u64;
+        let range = self.range(addr, op.bytes as u64)?;
+        let mut bytes = [0u8; 8];
+        bytes[..op.bytes as usize].copy_from_slice(&self.memory[range]);
+        let mut value = u64::from_le_bytes(bytes);
+        if op.signed {
+            let shift = 64 - 8 * op.bytes as u32;
+            value = (((value << shift) as i64) >> shift) as u64;
+        }
+        Ok(if op.wide { value } else { value as u32 as u64 })
+    }
+
+    fn host_call(&mut self, host_fn: HostFn, args: &[u64]) -> Result<Vec<u64>> {
+        self.charge(GAS_HOST_CALL)?;
+        let ptr = |i: usize| args[i] as u32 as u64;
+        Ok(match host_fn {
+            HostFn::Caller => {
+                let caller = self.host.caller();
+                self.write(ptr(0), &caller)?;
+                vec![]
+            }
+            HostFn::Address => {
+                let address = self.host.address();
+                self.write(ptr(0), &address)?;
+                vec![]
+            }
+            HostFn::InputLen => vec![self.host.input().len() as u64],
+            HostFn::InputRead => {
+                let input = self.host.input().to_vec();
+                self.write(ptr(0), &input)?;
+                vec![]
+            }
+            HostFn::Balance => {
+                let account = self.read32(ptr(0))?;
+                vec![self.host.balance(&account)]
+            }
+            HostFn::Transfer => {
+                self.charge(GAS_TRANSFER)?;
+                let to = self.read32(ptr(0))?;
+                let ok = self.host.transfer(to, args[1]);
+                vec![if ok { 0 } else { 1 }]
+            }
+            HostFn::OraclePrice => {
+                let range = self.range(ptr(0), ptr(1))?;
+                let price = std::str::from_utf8(&self.memory[range])
+                    .ok()
+                    .and_then(|asset| self.host.oracle_price(asset));
+                vec![price.unwrap_or(u64::MAX)]
+            }
+            HostFn::StorageRead => {
+                self.charge(GAS_STORAGE_READ)?;
+                let key = self.read32(ptr(0))?;
+                match self.host.storage_read(&key) {
+                    Some(value) => {
+                        self.write(ptr(1), &value)?;
+                        vec![1]
+                    }
+                    None => vec![0],
+                }
+            }
+            HostFn::StorageWrite => {
+                self.charge(GAS_STORAGE_WRITE)?;
+                let key = self.read32(ptr(0))?;
+                let value = self.read32(ptr(1))?;
+                self.host.storage_write(key, value);
+                vec![]
+            }
+        })
+    }
+}
+
+/// Unwind to the label `depth` levels out and return the next pc.
+fn branch(stack: &mut Vec<u64>, labels: &mut Vec<Label>, depth: u32) -> usize {
+    let index = labels.len() - 1 - depth as usize;
+    let label = labels[index];
+    let carried = stack.len() - label.arity;
+    stack.drain(label.height..carried);
+    // A loop label stays in place for the next iteration.
+    labels.truncate(if label.is_loop { index + 1 } else { index });
+    label.target
+}
+
+macro_rules! int_ops {
+    ($name:ident, $u:ty, $s:ty) => {
+        fn $name(op: IntOp, a: $u, b: $u) -> Result<$u> {
+            let (sa, sb) = (a as $s, b as $s);
+            let bits = <$u>::BITS;
+            Ok(match op {
+                IntOp::Eqz => (a == 0) as $u,
+                IntOp::Clz => a.leading_zeros() as $u,
+                IntOp::Ctz => a.trailing_zeros() as $u,
+                IntOp::Popcnt => a.count_ones() as $u,
+                IntOp::Extend8S => a as i8 as $s as $u,
+                IntOp::Extend16S => a as i16 as $s as $u,
+                IntOp::Extend32S => a as i32 as $s as $u,
+                IntOp::Add => a.wrapping_add(b),
+                IntOp::Sub => a.wrapping_sub(b),
+                IntOp::Mul => a.wrapping_mul(b),
+                IntOp::DivS => {
+                    if b == 0 {
+                        bail!("Integer divide by zero");
+                    }
+                    sa.checked_div(sb)
+                        .ok_or_else(|| anyhow!("Integer overflow"))? as $u
+                }
+                IntOp::DivU => a
+                    .checked_div(b)
+                    .ok_or_else(|| anyhow!("Integer divide by zero"))?,
+                IntOp::RemS => {
+                    if b == 0 {
+                        bail!("Integer divide by zero");
+                    }
+                    sa.wrapping_rem(sb) as $u
+                }
+                IntOp::RemU => a
+                    .checked_rem(b)
+                    .ok_or_else(|| anyhow!("Integer divide by zero"))?,
+                IntOp::And => a & b,
+                IntOp::Or => a | b,
+                IntOp::Xor => a ^ b,
+                IntOp::Shl => a.wrapping_shl(b as u32),
+                IntOp::ShrS => sa.wrapping_shr(b as u32) as $u,
+                IntOp::ShrU => a.wrapping_shr(b as u32),
+                IntOp::Rotl => a.rotate_left((b % bits as $u) as u32),
+                IntOp::Rotr => a.rotate_right((b % bits as $u) as u32),
+                IntOp::Eq => (a == b) as $u,
+                IntOp::Ne => (a != b) as $u,
+                IntOp::LtS => (sa < sb) as $u,
+                IntOp::LtU => (a < b) as $u,
+                IntOp::GtS => (sa > sb) as $u,
+                IntOp::GtU => (a > b) as $u,
+                IntOp::LeS => (sa <= sb) as $u,
+                IntOp::LeU => (a <= b) as $u,
+                IntOp::GeS => (sa >= sb) as $u,
+                IntOp::GeU => (a >= b) as $u,
+            })
+        }
+    };
+}
+
+int_ops!(int32, u32, i32);
+int_ops!(int64, u64, i64);
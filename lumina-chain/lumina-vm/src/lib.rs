@@ -0,0 +1,398 @@
+//! Deterministic, metered WebAssembly runtime for issuer contracts.
+//!
+//! Contracts are integer-only wasm modules exporting `call: [] -> [i32]`
+//! (zero commits, anything else reverts). They reach chain state solely
+//! through the `lumina` host functions below; every operator and host call
+//! is charged against the caller's gas limit.
+
+mod compile;
+mod interp;
+
+use anyhow::Result;
+use wasmparser::ValType;
+
+pub const PAGE_SIZE: usize = 65_536;
+pub const MAX_CODE_SIZE: usize = 128 * 1024;
+pub const MAX_MEMORY_PAGES: u32 = 16;
+pub const MAX_FUNCTIONS: usize = 512;
+pub const MAX_LOCALS: usize = 1024;
+pub const MAX_CALL_DEPTH: usize = 64;
+
+// Gas schedule
+pub const GAS_PER_OP: u64 = 1;
+pub const GAS_PER_CODE_BYTE: u64 = 1;
+pub const GAS_PER_MEMORY_PAGE: u64 = 1_000;
+pub const GAS_PER_BYTE_COPIED: u64 = 1;
+pub const GAS_HOST_CALL: u64 = 50;
+pub const GAS_STORAGE_READ: u64 = 200;
+pub const GAS_STORAGE_WRITE: u64 = 5_000;
+pub const GAS_TRANSFER: u64 = 2_000;
+
+/// Chain access for a running contract. `transfer` moves LUSD out of the
+/// contract's own account and reports whether it had the funds.
+pub trait Host {
+    fn caller(&self) -> [u8; 32];
+    fn address(&self) -> [u8; 32];
+    fn input(&self) -> &[u8];
+    fn balance(&self, account: &[u8; 32]) -> u64;
+    fn transfer(&mut self, to: [u8; 32], amount: u64) -> bool;
+    fn oracle_price(&self, asset: &str) -> Option<u64>;
+    fn storage_read(&self, key: &[u8; 32]) -> Option<[u8; 32]>;
+    fn storage_write(&mut self, key: [u8; 32], value: [u8; 32]);
+}
+
+/// Functions a contract may import from the `lumina` module. Pointers are
+/// offsets into the contract's memory; addresses, keys and values are 32 bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostFn {
+    /// `(out)` writes the calling account
+    Caller,
+    /// `(out)` writes the contract's own account
+    Address,
+    /// `() -> len`
+    InputLen,
+    /// `(out)` copies the call input
+    InputRead,
+    /// `(account) -> lusd_balance`
+    Balance,
+    /// `(to, amount) -> 0 ok | 1 insufficient funds`
+    Transfer,
+    /// `(asset_ptr, asset_len) -> price | -1`
+    OraclePrice,
+    /// `(key, out) -> 1 found | 0 absent`
+    StorageRead,
+    /// `(key, value)`
+    StorageWrite,
+}
+
+impl HostFn {
+    pub fn lookup(module: &str, name: &str) -> Option<HostFn> {
+        if module != "lumina" {
+            return None;
+        }
+        Some(match name {
+            "caller" => HostFn::Caller,
+            "address" => HostFn::Address,
+            "input_len" => HostFn::InputLen,
+            "input_read" => HostFn::InputRead,
+            "balance" => HostFn::Balance,
+            "transfer" => HostFn::Transfer,
+            "oracle_price" => HostFn::OraclePrice,
+            "storage_read" => HostFn::StorageRead,
+            "storage_write" => HostFn::StorageWrite,
+            _ => return None,
+        })
+    }
+
+    /// Wasm (params, results).
+    pub fn signature(self) -> (&'static [ValType], &'static [ValType]) {
+        use ValType::{I32, I64};
+        match self {
+            HostFn::Caller | HostFn::Address | HostFn::InputRead => (&[I32], &[]),
+            HostFn::InputLen => (&[], &[I32]),
+            HostFn::Balance => (&[I32], &[I64]),
+            HostFn::Transfer => (&[I32, I64], &[I32]),
+            HostFn::OraclePrice => (&[I32, I32], &[I64]),
+            HostFn::StorageRead => (&[I32, I32], &[I32]),
+            HostFn::StorageWrite => (&[I32, I32], &[]),
+        }
+    }
+}
+
+/// A validated contract, ready to call.
+#[derive(Debug)]
+pub struct Module {
+    compiled: compile::Compiled,
+    code_len: usize,
+}
+
+impl Module {
+    /// Validate `code` and reject anything outside the deterministic subset.
+    pub fn new(code: &[u8]) -> Result<Module> {
+        Ok(Module {
+            compiled: compile::compile(code)?,
+            code_len: code.len(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    /// Value returned by `call`; non-zero means the contract reverted
+    pub status: u32,
+    pub gas_used: u64,
+}
+
+/// Run the contract's `call` entry point with a fresh memory. Traps and gas
+/// exhaustion are errors; the host should discard any effects in that case.
+pub fn call(module: &Module, host: &mut dyn Host, gas_limit: u64) -> Result<Outcome> {
+    interp::run(module, host, gas_limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use wasm_encoder::{
+        CodeSection, ConstExpr, DataSection, EntityType, ExportKind, ExportSection, Function,
+        FunctionSection, ImportSection, Instruction as I, MemArg, MemorySection, MemoryType,
+        TypeSection, ValType as T,
+    };
+
+    #[derive(Default)]
+    struct TestHost {
+        balances: BTreeMap<[u8; 32], u64>,
+        storage: BTreeMap<[u8; 32], [u8; 32]>,
+        input: Vec<u8>,
+    }
+
+    const CONTRACT: [u8; 32] = [7u8; 32];
+
+    impl Host for TestHost {
+        fn caller(&self) -> [u8; 32] {
+            [1u8; 32]
+        }
+        fn address(&self) -> [u8; 32] {
+            CONTRACT
+        }
+        fn input(&self) -> &[u8] {
+            &self.input
+        }
+        fn balance(&self, account: &[u8; 32]) -> u64 {
+            self.balances.get(account).copied().unwrap_or(0)
+        }
+        fn transfer(&mut self, to: [u8; 32], amount: u64) -> bool {
+            let from = self.balance(&CONTRACT);
+            if from < amount {
+                return false;
+            }
+            self.balances.insert(CONTRACT, from - amount);
+            *self.balances.entry(to).or_default() += amount;
+            true
+        }
+        fn oracle_price(&self, asset: &str) -> Option<u64> {
+            (asset == "LUSD").then_some(1_000_000)
+        }
+        fn storage_read(&self, key: &[u8; 32]) -> Option<[u8; 32]> {
+            self.storage.get(key).copied()
+        }
+        fn storage_write(&mut self, key: [u8; 32], value: [u8; 32]) {
+            self.storage.insert(key, value);
+        }
+    }
+
+    fn mem(offset: u64) -> MemArg {
+        MemArg {
+            offset,
+            align: 0,
+            memory_index: 0,
+        }
+    }
+
+    /// A module importing `imports`, with one page of memory holding `data`
+    /// at offset 0, whose `call` runs `body` with `locals` i64 locals.
+    fn module(imports: &[(&str, &[T], &[T])], data: &[u8], locals: u32, body: &[I]) -> Vec<u8> {
+        let mut types = TypeSection::new();
+        let mut import_section = ImportSection::new();
+        for (i, (name, params, results)) in imports.iter().enumerate() {
+            types.ty().function(params.to_vec(), results.to_vec());
+            import_section.import("lumina", name, EntityType::Function(i as u32));
+        }
+        types.ty().function([], [T::I32]);
+        let mut functions = FunctionSection::new();
+        functions.function(imports.len() as u32);
+        let mut memory = MemorySection::new();
+        memory.memory(MemoryType {
+            minimum: 1,
+            maximum: Some(2),
+            memory64: false,
+            shared: false,
+            page_size_log2: None,
+        });
+        let mut exports = ExportSection::new();
+        exports.export("call", ExportKind::Func, imports.len() as u32);
+        let mut code = CodeSection::new();
+        let mut f = Function::new([(locals, T::I64)]);
+        for op in body {
+            f.instruction(op);
+        }
+        f.instruction(&I::End);
+        code.function(&f);
+        let mut data_section = DataSection::new();
+        data_section.active(0, &ConstExpr::i32_const(0), data.iter().copied());
+
+        let mut m = wasm_encoder::Module::new();
+        m.section(&types);
+        if !imports.is_empty() {
+            m.section(&import_section);
+        }
+        m.section(&functions)
+            .section(&memory)
+            .section(&exports)
+            .section(&code)
+            .section(&data_section);
+        m.finish()
+    }
+
+    fn run(code: &[u8], host: &mut TestHost, gas: u64) -> Result<Outcome> {
+        call(&Module::new(code)?, host, gas)
+    }
+
+    #[test]
+    fn loops_and_arithmetic_are_metered() {
+        // local0 = 0; do { local0 += 1 } while local0 < 10; return local0 * 3
+        let body = [
+            I::Loop(wasm_encoder::BlockType::Empty),
+            I::LocalGet(0),
+            I::I64Const(1),
+            I::I64Add,
+            I::LocalTee(0),
+            I::I64Const(10),
+            I::I64LtU,
+            I::BrIf(0),
+            I::End,
+            I::LocalGet(0),
+            I::I32WrapI64,
+            I::I32Const(3),
+            I::I32Mul,
+        ];
+        let code = module(&[], &[], 1, &body);
+        let outcome = run(&code, &mut TestHost::default(), 1_000_000).unwrap();
+        assert_eq!(outcome.status, 30);
+
+        // Same code, same gas: metering is deterministic.
+        let again = run(&code, &mut TestHost::default(), 1_000_000).unwrap();
+        assert_eq!(again, outcome);
+
+        let err = run(&code, &mut TestHost::default(), outcome.gas_used - 1).unwrap_err();
+        assert_eq!(err.to_string(), "Out of gas");
+        assert!(run(&code, &mut TestHost::default(), outcome.gas_used).is_ok());
+    }
+
+    #[test]
+    fn structured_control_flow() {
+        use wasm_encoder::BlockType;
+        // (if (i32.const 0) 10 else 20) + (block (br_if 0 5 (i32.const 1)) drop 99)
+        //   + br_table picking the outer of two blocks = 20 + 5 + 7
+        let body = [
+            I::I32Const(0),
+            I::If(BlockType::Result(T::I32)),
+            I::I32Const(10),
+            I::Else,
+            I::I32Const(20),
+            I::End,
+            I::Block(BlockType::Result(T::I32)),
+            I::I32Const(5),
+            I::I32Const(1),
+            I::BrIf(0),
+            I::Drop,
+            I::I32Const(99),
+            I::End,
+            I::I32Add,
+            I::Block(BlockType::Result(T::I32)),
+            I::Block(BlockType::Result(T::I32)),
+            I::I32Const(7),
+            I::I32Const(1),
+            I::BrTable(vec![0].into(), 1),
+            I::End,
+            I::Drop,
+            I::I32Const(99),
+            I::End,
+            I::I32Add,
+        ];
+        let outcome = run(
+            &module(&[], &[], 0, &body),
+            &mut TestHost::default(),
+            10_000,
+        )
+        .unwrap();
+        assert_eq!(outcome.status, 32);
+    }
+
+    #[test]
+    fn traps_are_errors() {
+        let div = [I::I32Const(1), I::I32Const(0), I::I32DivU];
+        let err = run(&module(&[], &[], 0, &div), &mut TestHost::default(), 10_000).unwrap_err();
+        assert_eq!(err.to_string(), "Integer divide by zero");
+
+        let oob = [I::I32Const(65_535), I::I32Load(mem(0))];
+        let err = run(&module(&[], &[], 0, &oob), &mut TestHost::default(), 10_000).unwrap_err();
+        assert_eq!(err.to_string(), "Out of bounds memory access");
+
+        // Growing past the declared maximum fails softly with -1.
+        let grow = [I::I32Const(5), I::MemoryGrow(0)];
+        let outcome = run(
+            &module(&[], &[], 0, &grow),
+            &mut TestHost::default(),
+            100_000,
+        )
+        .unwrap();
+        assert_eq!(outcome.status, u32::MAX);
+    }
+
+    #[test]
+    fn rejects_nondeterministic_and_unknown_imports() {
+        let float = [I::F32Const(1.5.into()), I::I32TruncF32S];
+        let err = Module::new(&module(&[], &[], 0, &float)).unwrap_err();
+        assert!(err.to_string().contains("Invalid contract module"));
+
+        let imports: &[(&str, &[T], &[T])] = &[("clock", &[], &[T::I64])];
+        let err = Module::new(&module(imports, &[], 0, &[I::I32Const(0)])).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown host function clock");
+
+        let imports: &[(&str, &[T], &[T])] = &[("balance", &[T::I32], &[T::I32])];
+        let err = Module::new(&module(imports, &[], 0, &[I::I32Const(0)])).unwrap_err();
+        assert_eq!(err.to_string(), "Wrong signature for host function balance");
+    }
+
+    #[test]
+    fn host_functions_reach_balances_oracles_and_storage() {
+        // Pay the caller half the contract's balance, remember the LUSD
+        // price under key 0x00.., and return 0.
+        let imports: &[(&str, &[T], &[T])] = &[
+            ("caller", &[T::I32], &[]),
+            ("address", &[T::I32], &[]),
+            ("balance", &[T::I32], &[T::I64]),
+            ("transfer", &[T::I32, T::I64], &[T::I32]),
+            ("oracle_price", &[T::I32, T::I32], &[T::I64]),
+            ("storage_write", &[T::I32, T::I32], &[]),
+        ];
+        let (caller, address, balance, transfer, price, write) = (0, 1, 2, 3, 4, 5);
+        let body = [
+            I::I32Const(32),
+            I::Call(caller),
+            I::I32Const(64),
+            I::Call(address),
+            // transfer(caller, balance(self) / 2)
+            I::I32Const(32),
+            I::I32Const(64),
+            I::Call(balance),
+            I::I64Const(2),
+            I::I64DivU,
+            I::Call(transfer),
+            I::Drop,
+            // mem[128..136] = oracle_price("LUSD")
+            I::I32Const(128),
+            I::I32Const(0),
+            I::I32Const(4),
+            I::Call(price),
+            I::I64Store(mem(0)),
+            // storage[mem[96..128]] = mem[128..160]
+            I::I32Const(96),
+            I::I32Const(128),
+            I::Call(write),
+            I::I32Const(0),
+        ];
+        let code = module(imports, b"LUSD", 0, &body);
+        let mut host = TestHost::default();
+        host.balances.insert(CONTRACT, 500);
+        let outcome = run(&code, &mut host, 100_000).unwrap();
+        assert_eq!(outcome.status, 0);
+        assert_eq!(host.balances[&[1u8; 32]], 250);
+        assert_eq!(host.balances[&CONTRACT], 250);
+        let mut expected = [0u8; 32];
+        expected[..8].copy_from_slice(&1_000_000u64.to_le_bytes());
+        assert_eq!(host.storage[&[0u8; 32]], expected);
+        assert!(outcome.gas_used > GAS_TRANSFER + GAS_STORAGE_WRITE);
+    }
+}